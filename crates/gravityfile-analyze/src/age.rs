@@ -4,26 +4,35 @@
 //! - Stale directories (no recent modifications)
 //! - Age distribution of files
 //! - Old files that may be candidates for cleanup
+//!
+//! Ages are measured from modification time by default; [`TimeBasis`]
+//! selects access or creation time instead, to find what nobody reads.
 
 use std::cmp::Reverse;
 use std::collections::BinaryHeap;
+use std::fmt;
 use std::path::{Path, PathBuf};
+use std::str::FromStr;
 use std::time::{Duration, SystemTime};
 
 use derive_builder::Builder;
 use serde::{Deserialize, Serialize};
 
-use gravityfile_core::{FileNode, FileTree, NodeKind};
+use gravityfile_core::{FileNode, FileTree, NodeKind, Timestamps};
 
 /// Seconds per month, using the astronomically accurate 365.25/12 * 86400 value.
 const SECS_PER_MONTH: u64 = 2_629_800;
 
 /// An age bucket for categorizing files.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// In a profile, buckets are written as
+/// `buckets = [{ name = "Fresh", max_age = "1w" }, { name = "Rest", max_age = "unlimited" }]`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct AgeBucket {
     /// Human-readable name for this bucket.
     pub name: String,
     /// Maximum age for files in this bucket.
+    #[serde(with = "crate::units::duration_spec")]
     pub max_age: Duration,
 }
 
@@ -35,26 +44,151 @@ impl AgeBucket {
             max_age,
         }
     }
+
+    /// The catch-all bucket for files older than every other bucket.
+    pub fn older() -> Self {
+        Self::new("Older", Duration::MAX)
+    }
+
+    /// Parse a comma-separated list of `[NAME=]AGE` buckets such as
+    /// `Fresh=1w,1mo,1y`. Unnamed buckets are called `< AGE`, and an
+    /// `Older` catch-all is appended unless the last age is `unlimited`.
+    pub fn parse_list(spec: &str) -> Result<Vec<Self>, String> {
+        let mut buckets = Vec::new();
+        for item in spec.split(',').map(str::trim).filter(|i| !i.is_empty()) {
+            let (name, age) = match item.split_once('=') {
+                Some((name, age)) => (name.trim().to_string(), age.trim()),
+                None => (format!("< {}", item), item),
+            };
+            buckets.push(Self::new(name, crate::units::parse_duration(age)?));
+        }
+        if buckets.is_empty() {
+            return Err("No age buckets given".to_string());
+        }
+        if buckets.iter().all(|b| b.max_age != Duration::MAX) {
+            buckets.push(Self::older());
+        }
+        Ok(buckets)
+    }
+}
+
+/// The timestamp file ages are measured from.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TimeBasis {
+    /// Last modification time.
+    #[default]
+    #[serde(rename = "mtime", alias = "modified")]
+    Modified,
+    /// Last access time.
+    #[serde(rename = "atime", alias = "accessed")]
+    Accessed,
+    /// Creation time.
+    #[serde(rename = "btime", alias = "created")]
+    Created,
+    /// The most recent of the three.
+    #[serde(rename = "latest")]
+    Latest,
+}
+
+impl TimeBasis {
+    /// All bases, in display order.
+    pub const ALL: [TimeBasis; 4] = [
+        TimeBasis::Modified,
+        TimeBasis::Accessed,
+        TimeBasis::Created,
+        TimeBasis::Latest,
+    ];
+
+    /// Short name used in reports and on the command line.
+    pub fn label(self) -> &'static str {
+        match self {
+            TimeBasis::Modified => "mtime",
+            TimeBasis::Accessed => "atime",
+            TimeBasis::Created => "btime",
+            TimeBasis::Latest => "latest",
+        }
+    }
+
+    /// The basis following this one in [`ALL`](Self::ALL), wrapping around.
+    pub fn next(self) -> Self {
+        let i = Self::ALL.iter().position(|b| *b == self).unwrap_or(0);
+        Self::ALL[(i + 1) % Self::ALL.len()]
+    }
+
+    /// Whether ages on this basis depend on access times.
+    pub fn uses_atime(self) -> bool {
+        matches!(self, TimeBasis::Accessed | TimeBasis::Latest)
+    }
+
+    /// The time of `timestamps` on this basis, or `None` when the platform
+    /// did not record it. Modification time is always available.
+    pub fn time_of(self, timestamps: &Timestamps) -> Option<SystemTime> {
+        match self {
+            TimeBasis::Modified => Some(timestamps.modified),
+            TimeBasis::Accessed => timestamps.accessed,
+            TimeBasis::Created => timestamps.created,
+            TimeBasis::Latest => Some(
+                [timestamps.accessed, timestamps.created]
+                    .into_iter()
+                    .flatten()
+                    .fold(timestamps.modified, SystemTime::max),
+            ),
+        }
+    }
+}
+
+impl fmt::Display for TimeBasis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
+impl FromStr for TimeBasis {
+    type Err = String;
+
+    /// Parse a basis such as `mtime`, `atime`, `created` or `max`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let basis = match s.trim().to_ascii_lowercase().as_str() {
+            "mtime" | "modified" => Self::Modified,
+            "atime" | "accessed" => Self::Accessed,
+            "btime" | "created" | "birth" => Self::Created,
+            "latest" | "max" => Self::Latest,
+            _ => return Err(format!("unknown time basis: {}", s)),
+        };
+        Ok(basis)
+    }
 }
 
 /// Configuration for age-based analysis.
-#[derive(Debug, Clone, Builder)]
+///
+/// Deserializes from the `[age]` table of a profile; durations accept
+/// `"6m"`-style strings and sizes `"1MB"`-style strings.
+#[derive(Debug, Clone, Builder, Serialize, Deserialize)]
 #[builder(setter(into))]
+#[serde(default)]
 pub struct AgeConfig {
     /// Reference time for age calculations (default: now).
     #[builder(default = "SystemTime::now()")]
+    #[serde(skip, default = "SystemTime::now")]
     pub reference_time: SystemTime,
 
     /// Age buckets for categorization.
     #[builder(default = "Self::default_buckets()")]
+    #[serde(default = "AgeConfigBuilder::default_buckets")]
     pub buckets: Vec<AgeBucket>,
 
+    /// Timestamp that ages are measured from.
+    #[builder(default)]
+    pub time_basis: TimeBasis,
+
     /// Minimum age to consider a directory "stale".
     #[builder(default = "Duration::from_secs(365 * 24 * 60 * 60)")] // 1 year
+    #[serde(with = "crate::units::duration_spec")]
     pub stale_threshold: Duration,
 
     /// Minimum size for a stale directory to be reported.
     #[builder(default = "1024 * 1024")] // 1 MB
+    #[serde(with = "crate::units::size_spec")]
     pub min_stale_size: u64,
 
     /// Maximum number of stale directories to report.
@@ -73,7 +207,7 @@ impl AgeConfigBuilder {
             AgeBucket::new("This Week", Duration::from_secs(7 * 24 * 60 * 60)),
             AgeBucket::new("This Month", Duration::from_secs(30 * 24 * 60 * 60)),
             AgeBucket::new("This Year", Duration::from_secs(365 * 24 * 60 * 60)),
-            AgeBucket::new("Older", Duration::MAX),
+            AgeBucket::older(),
         ]
     }
 }
@@ -83,6 +217,7 @@ impl Default for AgeConfig {
         Self {
             reference_time: SystemTime::now(),
             buckets: AgeConfigBuilder::default_buckets(),
+            time_basis: TimeBasis::Modified,
             stale_threshold: Duration::from_secs(365 * 24 * 60 * 60),
             min_stale_size: 1024 * 1024,
             max_stale_dirs: 100,
@@ -109,11 +244,11 @@ pub struct AgeBucketStats {
     pub file_count: u64,
     /// Total size of files in this bucket.
     pub total_size: u64,
-    /// Largest files in this bucket.
+    /// Largest files in this bucket, with their time on the report's basis.
     pub largest_files: Vec<(PathBuf, u64, SystemTime)>,
 }
 
-/// A directory identified as stale (no recent modifications).
+/// A directory identified as stale (no recent activity on the time basis).
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StaleDirectory {
     /// Path to the directory.
@@ -141,6 +276,16 @@ pub struct AgeReport {
     pub average_age: Duration,
     /// Median file age bucket name. None when the tree has no files.
     pub median_age_bucket: Option<String>,
+    /// Timestamp the ages were measured from.
+    #[serde(default)]
+    pub time_basis: TimeBasis,
+    /// Files lacking the basis timestamp, aged by modification time instead.
+    #[serde(default)]
+    pub fallback_files: u64,
+    /// Why access times may be unreliable, such as a `noatime` mount. Set
+    /// by callers that know the mount options; analysis leaves it empty.
+    #[serde(default)]
+    pub atime_warning: Option<String>,
 }
 
 impl AgeReport {
@@ -180,7 +325,8 @@ impl AgeAnalyzer {
 
     /// Create a new analyzer with custom config.
     ///
-    /// Buckets are sorted by max_age ascending so the first bucket that fits is selected.
+    /// Buckets are sorted by max_age ascending so the first bucket that fits is selected,
+    /// and an `Older` catch-all is added when no bucket is unbounded.
     pub fn with_config(mut config: AgeConfig) -> Self {
         // Sort buckets by max_age ascending so the find-first logic in the DFS is correct
         config.buckets.sort_by(|a, b| {
@@ -188,6 +334,13 @@ impl AgeAnalyzer {
                 .partial_cmp(&b.max_age)
                 .unwrap_or(std::cmp::Ordering::Equal)
         });
+        if config
+            .buckets
+            .last()
+            .is_none_or(|b| b.max_age != Duration::MAX)
+        {
+            config.buckets.push(AgeBucket::older());
+        }
         Self { config }
     }
 
@@ -204,6 +357,7 @@ impl AgeAnalyzer {
         let mut total_files: u64 = 0;
         let mut total_size: u64 = 0;
         let mut total_age_secs: u64 = 0;
+        let mut fallback_files: u64 = 0;
 
         // Single DFS pass: collect bucket stats and stale directory candidates together
         self.dfs(
@@ -214,18 +368,17 @@ impl AgeAnalyzer {
             &mut total_files,
             &mut total_size,
             &mut total_age_secs,
+            &mut fallback_files,
         );
 
         // Sort stale directories by size descending and apply limit
-        stale_candidates.sort_by(|a, b| b.size.cmp(&a.size));
+        stale_candidates.sort_by_key(|d| Reverse(d.size));
         stale_candidates.truncate(self.config.max_stale_dirs);
 
         // Calculate average age
-        let average_age = if total_files > 0 {
-            Duration::from_secs(total_age_secs / total_files)
-        } else {
-            Duration::ZERO
-        };
+        let average_age = total_age_secs
+            .checked_div(total_files)
+            .map_or(Duration::ZERO, Duration::from_secs);
 
         // Find median bucket (bucket containing the cumulative 50th-percentile file).
         //
@@ -265,7 +418,7 @@ impl AgeAnalyzer {
                     .into_iter()
                     .map(|(Reverse(size), path, modified)| (path, size, modified))
                     .collect();
-                largest_files.sort_by(|a, b| b.1.cmp(&a.1));
+                largest_files.sort_by_key(|f| Reverse(f.1));
                 AgeBucketStats {
                     name: bucket.name,
                     max_age: bucket.max_age,
@@ -283,6 +436,9 @@ impl AgeAnalyzer {
             total_size,
             average_age,
             median_age_bucket,
+            time_basis: self.config.time_basis,
+            fallback_files,
+            atime_warning: None,
         }
     }
 
@@ -291,7 +447,7 @@ impl AgeAnalyzer {
     /// - Accumulates global totals
     /// - Identifies stale directories
     ///
-    /// Returns the newest modification time found within this subtree (used for stale detection).
+    /// Returns the newest basis time found within this subtree (used for stale detection).
     #[allow(clippy::too_many_arguments)]
     fn dfs(
         &self,
@@ -302,17 +458,24 @@ impl AgeAnalyzer {
         total_files: &mut u64,
         total_size: &mut u64,
         total_age_secs: &mut u64,
+        fallback_files: &mut u64,
     ) -> Option<SystemTime> {
         match &node.kind {
             NodeKind::File { .. } => {
                 // current_path is already the full file path — the Directory branch
                 // pre-joins the child name before recursing.
                 let file_path = current_path.to_path_buf();
-                let modified = node.timestamps.modified;
+                let time = match self.config.time_basis.time_of(&node.timestamps) {
+                    Some(time) => time,
+                    None => {
+                        *fallback_files += 1;
+                        node.timestamps.modified
+                    }
+                };
                 let age = self
                     .config
                     .reference_time
-                    .duration_since(modified)
+                    .duration_since(time)
                     .unwrap_or(Duration::ZERO);
 
                 *total_files += 1;
@@ -323,12 +486,12 @@ impl AgeAnalyzer {
                 // Place into the first bucket whose max_age >= file age
                 for collector in bucket_collectors.iter_mut() {
                     if age <= collector.bucket.max_age {
-                        collector.add_file(file_path, node.size, modified);
+                        collector.add_file(file_path, node.size, time);
                         break;
                     }
                 }
 
-                Some(modified)
+                Some(time)
             }
             NodeKind::Directory { file_count, .. } => {
                 let mut newest_in_subtree: Option<SystemTime> = None;
@@ -343,6 +506,7 @@ impl AgeAnalyzer {
                         total_files,
                         total_size,
                         total_age_secs,
+                        fallback_files,
                     );
                     newest_in_subtree = match (newest_in_subtree, child_newest) {
                         (Some(a), Some(b)) => Some(a.max(b)),
@@ -434,7 +598,7 @@ impl BucketCollector {
             .into_iter()
             .map(|(Reverse(size), path, modified)| (path, size, modified))
             .collect();
-        v.sort_by(|a, b| b.1.cmp(&a.1));
+        v.sort_by_key(|f| Reverse(f.1));
         v
     }
 }
@@ -518,6 +682,60 @@ mod tests {
         assert_eq!(names, vec!["Today", "This Week", "Older"]);
     }
 
+    #[test]
+    fn test_with_config_adds_catch_all() {
+        let config = AgeConfig {
+            buckets: vec![AgeBucket::new("Fresh", Duration::from_secs(86400))],
+            ..AgeConfig::default()
+        };
+        let analyzer = AgeAnalyzer::with_config(config);
+        assert_eq!(
+            analyzer.config.buckets,
+            vec![
+                AgeBucket::new("Fresh", Duration::from_secs(86400)),
+                AgeBucket::older()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_bucket_list() {
+        let buckets = AgeBucket::parse_list("Fresh=1w, 1y").unwrap();
+        assert_eq!(
+            buckets,
+            vec![
+                AgeBucket::new("Fresh", Duration::from_secs(7 * 86400)),
+                AgeBucket::new("< 1y", Duration::from_secs(365 * 86400)),
+                AgeBucket::older(),
+            ]
+        );
+
+        let buckets = AgeBucket::parse_list("Recent=30d,Archive=unlimited").unwrap();
+        assert_eq!(buckets.len(), 2);
+        assert_eq!(buckets[1].max_age, Duration::MAX);
+
+        assert!(AgeBucket::parse_list("").is_err());
+        assert!(AgeBucket::parse_list("Fresh=soon").is_err());
+    }
+
+    #[test]
+    fn test_buckets_from_profile_toml() {
+        let config: AgeConfig = toml::from_str(
+            "buckets = [{ name = \"Fresh\", max_age = \"1w\" }, { name = \"Rest\", max_age = \"unlimited\" }]",
+        )
+        .unwrap();
+        assert_eq!(
+            config.buckets[0],
+            AgeBucket::new("Fresh", Duration::from_secs(7 * 86400))
+        );
+        assert_eq!(config.buckets[1].max_age, Duration::MAX);
+
+        let defaults: AgeConfig = toml::from_str("").unwrap();
+        assert_eq!(defaults.buckets.len(), 5);
+        let round_trip: AgeConfig = toml::from_str(&toml::to_string(&defaults).unwrap()).unwrap();
+        assert_eq!(round_trip.buckets, defaults.buckets);
+    }
+
     // ---------------------------------------------------------------------------
     // Median algorithm correctness
     // ---------------------------------------------------------------------------
@@ -701,6 +919,89 @@ mod tests {
         assert!(sorted.iter().all(|(_, s, _)| *s == 100));
     }
 
+    #[test]
+    fn test_time_basis_parse() {
+        assert_eq!("atime".parse::<TimeBasis>(), Ok(TimeBasis::Accessed));
+        assert_eq!("Modified".parse::<TimeBasis>(), Ok(TimeBasis::Modified));
+        assert_eq!("max".parse::<TimeBasis>(), Ok(TimeBasis::Latest));
+        assert!("ctime".parse::<TimeBasis>().is_err());
+        for basis in TimeBasis::ALL {
+            assert_eq!(basis.label().parse::<TimeBasis>(), Ok(basis));
+        }
+        assert_eq!(TimeBasis::Latest.next(), TimeBasis::Modified);
+    }
+
+    #[test]
+    fn test_analyze_by_access_time() {
+        use gravityfile_core::{NodeId, ScanConfig, TreeStats};
+
+        const DAY: u64 = 86400;
+        let now = SystemTime::now();
+        let ago = |days: u64| now - Duration::from_secs(days * DAY);
+
+        // Modified two years ago but read two days ago.
+        let read = FileNode::new_file(
+            NodeId::new(1),
+            "read.txt",
+            10,
+            1,
+            Timestamps::new(ago(730), Some(ago(2)), None),
+            false,
+        );
+        // No access time recorded: aged by modification time.
+        let unknown = FileNode::new_file(
+            NodeId::new(2),
+            "unknown.txt",
+            20,
+            1,
+            Timestamps::with_modified(ago(3)),
+            false,
+        );
+        let mut root =
+            FileNode::new_directory(NodeId::new(0), "root", Timestamps::with_modified(now));
+        root.size = 30;
+        root.children = vec![read, unknown];
+        root.finalize();
+        let tree = FileTree::new(
+            root,
+            PathBuf::from("/root"),
+            ScanConfig::new("/root"),
+            TreeStats::new(),
+            Duration::ZERO,
+            Vec::new(),
+        );
+
+        let analyze = |time_basis| {
+            AgeAnalyzer::with_config(AgeConfig {
+                reference_time: now,
+                time_basis,
+                ..AgeConfig::default()
+            })
+            .analyze(&tree)
+        };
+        let count = |report: &AgeReport, name: &str| {
+            report
+                .buckets
+                .iter()
+                .find(|b| b.name == name)
+                .map_or(0, |b| b.file_count)
+        };
+
+        let by_mtime = analyze(TimeBasis::Modified);
+        assert_eq!(count(&by_mtime, "Older"), 1);
+        assert_eq!(by_mtime.fallback_files, 0);
+
+        let by_atime = analyze(TimeBasis::Accessed);
+        assert_eq!(by_atime.time_basis, TimeBasis::Accessed);
+        assert_eq!(count(&by_atime, "This Week"), 2);
+        assert_eq!(count(&by_atime, "Older"), 0);
+        assert_eq!(by_atime.fallback_files, 1);
+
+        let latest = analyze(TimeBasis::Latest);
+        assert_eq!(count(&latest, "This Week"), 2);
+        assert_eq!(latest.fallback_files, 0);
+    }
+
     #[test]
     fn test_saturating_add_accumulation() {
         // total_age_secs uses saturating_add; verify it caps at u64::MAX without panic.
@@ -0,0 +1,533 @@
+//! Cleanup candidates: regenerable build artifacts and caches.
+//!
+//! A rule matches directories by name (a glob such as `target` or
+//! `cmake-build-*`). To avoid false positives a rule may require a marker
+//! next to the directory (`Cargo.toml` beside `target/`) or inside it
+//! (`pyvenv.cfg` in a virtualenv). Matched directories are not descended
+//! into, so nested `node_modules` count once.
+//!
+//! The built-in catalogue can be extended and trimmed from the `[cleanup]`
+//! table of a profile:
+//!
+//! ```toml
+//! [cleanup]
+//! disable = ["cache"]
+//!
+//! [[cleanup.rules]]
+//! name = "bazel"
+//! description = "Bazel output"
+//! directory = "bazel-*"
+//! markers = ["WORKSPACE", "MODULE.bazel"]
+//! ```
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use globset::{Glob, GlobMatcher, GlobSet, GlobSetBuilder};
+use serde::{Deserialize, Serialize};
+
+use gravityfile_core::{FileNode, FileTree};
+
+/// A rule describing one kind of regenerable directory.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CleanupRule {
+    /// Short identifier, used to disable built-in rules.
+    pub name: String,
+    /// What the directory holds, shown in reports.
+    #[serde(default)]
+    pub description: String,
+    /// Glob matched against the directory name.
+    pub directory: String,
+    /// Globs of which at least one must match an entry next to the directory.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub markers: Vec<String>,
+    /// Globs of which at least one must match an entry inside the directory.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub contains: Vec<String>,
+}
+
+impl CleanupRule {
+    /// Create a rule matching directories named `directory`.
+    pub fn new(
+        name: impl Into<String>,
+        description: impl Into<String>,
+        directory: impl Into<String>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            description: description.into(),
+            directory: directory.into(),
+            markers: Vec::new(),
+            contains: Vec::new(),
+        }
+    }
+
+    /// Require one of `markers` next to the directory.
+    pub fn markers(mut self, markers: &[&str]) -> Self {
+        self.markers = markers.iter().map(|m| m.to_string()).collect();
+        self
+    }
+
+    /// Require one of `contains` inside the directory.
+    pub fn contains(mut self, contains: &[&str]) -> Self {
+        self.contains = contains.iter().map(|m| m.to_string()).collect();
+        self
+    }
+
+    /// The built-in catalogue.
+    pub fn builtin() -> Vec<CleanupRule> {
+        vec![
+            Self::new("cargo", "Rust build output", "target").markers(&["Cargo.toml"]),
+            Self::new("maven", "Maven build output", "target").markers(&["pom.xml"]),
+            Self::new("node-modules", "npm packages", "node_modules").markers(&["package.json"]),
+            Self::new("next", "Next.js build output", ".next").markers(&["package.json"]),
+            Self::new("pycache", "Python bytecode", "__pycache__"),
+            Self::new("venv", "Python virtualenv", "{.venv,venv}").contains(&["pyvenv.cfg"]),
+            Self::new("pytest", "pytest cache", ".pytest_cache"),
+            Self::new("mypy", "mypy cache", ".mypy_cache"),
+            Self::new("tox", "tox environments", ".tox").markers(&["tox.ini", "pyproject.toml"]),
+            Self::new("gradle", "Gradle cache", ".gradle").markers(&[
+                "build.gradle*",
+                "settings.gradle*",
+                "gradlew",
+            ]),
+            Self::new("gradle-build", "Gradle build output", "build").markers(&["build.gradle*"]),
+            Self::new("zig", "Zig build cache", "{zig-cache,.zig-cache,zig-out}")
+                .markers(&["build.zig"]),
+            Self::new("terraform", "Terraform providers", ".terraform").markers(&["*.tf"]),
+            Self::new(
+                "docker",
+                "Docker build cache",
+                "{.buildx-cache,.docker-cache}",
+            )
+            .markers(&[
+                "Dockerfile*",
+                "*.Dockerfile",
+                "compose.y*ml",
+                "docker-compose.y*ml",
+            ]),
+            // Only project-level caches: a bare `.cache` is also where the
+            // home directory keeps every application's cache.
+            Self::new("cache", "Project cache directory", ".cache").markers(&[
+                "package.json",
+                "pyproject.toml",
+                "setup.py",
+                "Cargo.toml",
+                "go.mod",
+                "CMakeLists.txt",
+            ]),
+        ]
+    }
+}
+
+/// Cleanup settings, from the `[cleanup]` table of a profile.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CleanupConfig {
+    /// Include the built-in catalogue.
+    pub builtin: bool,
+    /// Names of built-in rules to skip.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub disable: Vec<String>,
+    /// Extra rules, checked after the built-in ones.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub rules: Vec<CleanupRule>,
+}
+
+impl Default for CleanupConfig {
+    fn default() -> Self {
+        Self {
+            builtin: true,
+            disable: Vec::new(),
+            rules: Vec::new(),
+        }
+    }
+}
+
+impl CleanupConfig {
+    /// The rules in effect, built-in ones first.
+    pub fn effective_rules(&self) -> Vec<CleanupRule> {
+        let builtin = if self.builtin {
+            CleanupRule::builtin()
+        } else {
+            Vec::new()
+        };
+        builtin
+            .into_iter()
+            .filter(|r| !self.disable.contains(&r.name))
+            .chain(self.rules.iter().cloned())
+            .collect()
+    }
+}
+
+/// A directory that can be deleted and regenerated.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CleanupCandidate {
+    /// Path to the directory.
+    pub path: PathBuf,
+    /// Name of the matching rule.
+    pub rule: String,
+    /// Description of the matching rule.
+    pub description: String,
+    /// Bytes freed by deleting the directory.
+    pub size: u64,
+    /// Number of files inside.
+    pub file_count: u64,
+}
+
+/// Reclaimable bytes per rule.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CleanupRuleSummary {
+    /// Rule name.
+    pub rule: String,
+    /// Rule description.
+    pub description: String,
+    /// Number of matched directories.
+    pub count: usize,
+    /// Total bytes of matched directories.
+    pub size: u64,
+}
+
+/// Results from cleanup analysis.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CleanupReport {
+    /// Matched directories, largest first.
+    pub candidates: Vec<CleanupCandidate>,
+    /// Total bytes of all candidates.
+    pub total_reclaimable: u64,
+}
+
+impl CleanupReport {
+    /// Totals per rule, largest first.
+    pub fn by_rule(&self) -> Vec<CleanupRuleSummary> {
+        let mut summaries: Vec<CleanupRuleSummary> = Vec::new();
+        let mut index: HashMap<&str, usize> = HashMap::new();
+        for candidate in &self.candidates {
+            let i = *index.entry(&candidate.rule).or_insert_with(|| {
+                summaries.push(CleanupRuleSummary {
+                    rule: candidate.rule.clone(),
+                    description: candidate.description.clone(),
+                    count: 0,
+                    size: 0,
+                });
+                summaries.len() - 1
+            });
+            summaries[i].count += 1;
+            summaries[i].size += candidate.size;
+        }
+        summaries.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.rule.cmp(&b.rule)));
+        summaries
+    }
+}
+
+/// A rule with its globs compiled.
+struct CompiledRule {
+    rule: CleanupRule,
+    directory: GlobMatcher,
+    markers: Option<GlobSet>,
+    contains: Option<GlobSet>,
+}
+
+impl CompiledRule {
+    /// Compile `rule`, or `None` (with a warning) when a glob is invalid.
+    fn compile(rule: CleanupRule) -> Option<Self> {
+        let directory = match Glob::new(&rule.directory) {
+            Ok(glob) => glob.compile_matcher(),
+            Err(e) => {
+                tracing::warn!("Invalid cleanup rule {:?}: {}", rule.name, e);
+                return None;
+            }
+        };
+        let markers = compile_set(&rule.name, &rule.markers)?;
+        let contains = compile_set(&rule.name, &rule.contains)?;
+        Some(Self {
+            rule,
+            directory,
+            markers,
+            contains,
+        })
+    }
+
+    fn matches(&self, dir: &FileNode, siblings: &[FileNode]) -> bool {
+        let any = |set: &Option<GlobSet>, nodes: &[FileNode]| match set {
+            None => true,
+            Some(set) => nodes.iter().any(|n| set.is_match(n.name.as_str())),
+        };
+        self.directory.is_match(dir.name.as_str())
+            && any(&self.markers, siblings)
+            && any(&self.contains, &dir.children)
+    }
+}
+
+/// Compile `patterns`; `Some(None)` when there are none, `None` on error.
+fn compile_set(rule: &str, patterns: &[String]) -> Option<Option<GlobSet>> {
+    if patterns.is_empty() {
+        return Some(None);
+    }
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        match Glob::new(pattern) {
+            Ok(glob) => {
+                builder.add(glob);
+            }
+            Err(e) => {
+                tracing::warn!("Invalid cleanup rule {:?}: {}", rule, e);
+                return None;
+            }
+        }
+    }
+    builder.build().ok().map(Some)
+}
+
+/// Finds cleanup candidates in a scanned tree.
+pub struct CleanupFinder {
+    rules: Vec<CompiledRule>,
+}
+
+impl Default for CleanupFinder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CleanupFinder {
+    /// Create a finder with the built-in catalogue.
+    pub fn new() -> Self {
+        Self::with_config(&CleanupConfig::default())
+    }
+
+    /// Create a finder from a configuration. Rules with invalid globs are
+    /// skipped with a warning.
+    pub fn with_config(config: &CleanupConfig) -> Self {
+        let rules = config
+            .effective_rules()
+            .into_iter()
+            .filter_map(CompiledRule::compile)
+            .collect();
+        Self { rules }
+    }
+
+    /// Find candidates in a file tree.
+    pub fn find(&self, tree: &FileTree) -> CleanupReport {
+        let mut report = CleanupReport::default();
+        self.dfs(&tree.root, &tree.root_path, &mut report);
+        report
+            .candidates
+            .sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.path.cmp(&b.path)));
+        report.total_reclaimable = report.candidates.iter().map(|c| c.size).sum();
+        report
+    }
+
+    fn dfs(&self, node: &FileNode, path: &Path, report: &mut CleanupReport) {
+        for child in node.children.iter().filter(|c| c.is_dir()) {
+            let child_path = path.join(&*child.name);
+            if let Some(compiled) = self.rules.iter().find(|r| r.matches(child, &node.children)) {
+                report.candidates.push(CleanupCandidate {
+                    path: child_path,
+                    rule: compiled.rule.name.clone(),
+                    description: compiled.rule.description.clone(),
+                    size: child.size,
+                    file_count: child.file_count(),
+                });
+            } else {
+                self.dfs(child, &child_path, report);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gravityfile_core::{NodeId, ScanConfig, Timestamps, TreeStats};
+    use std::time::{Duration, SystemTime};
+
+    fn file(name: &str, size: u64) -> FileNode {
+        FileNode::new_file(
+            NodeId::new(0),
+            name,
+            size,
+            1,
+            Timestamps::with_modified(SystemTime::now()),
+            false,
+        )
+    }
+
+    fn dir(name: &str, children: Vec<FileNode>) -> FileNode {
+        let mut node = FileNode::new_directory(
+            NodeId::new(0),
+            name,
+            Timestamps::with_modified(SystemTime::now()),
+        );
+        node.size = children.iter().map(|c| c.size).sum();
+        node.children = children;
+        node
+    }
+
+    fn tree(root: FileNode) -> FileTree {
+        let mut tree = FileTree::new(
+            root,
+            PathBuf::from("/work"),
+            ScanConfig::new("/work"),
+            TreeStats::new(),
+            Duration::ZERO,
+            Vec::new(),
+        );
+        tree.root.finalize();
+        tree
+    }
+
+    #[test]
+    fn test_markers_avoid_false_positives() {
+        let tree = tree(dir(
+            "work",
+            vec![
+                dir(
+                    "app",
+                    vec![
+                        file("Cargo.toml", 10),
+                        dir("target", vec![file("app.rlib", 5000)]),
+                    ],
+                ),
+                dir("notes", vec![dir("target", vec![file("goals.md", 100)])]),
+                dir(
+                    "web",
+                    vec![
+                        file("package.json", 10),
+                        dir(
+                            "node_modules",
+                            vec![dir("left-pad", vec![dir("node_modules", vec![])])],
+                        ),
+                    ],
+                ),
+            ],
+        ));
+
+        let report = CleanupFinder::new().find(&tree);
+        let paths: Vec<_> = report.candidates.iter().map(|c| c.path.clone()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                PathBuf::from("/work/app/target"),
+                PathBuf::from("/work/web/node_modules")
+            ]
+        );
+        assert_eq!(report.candidates[0].rule, "cargo");
+        assert_eq!(report.total_reclaimable, 5000);
+    }
+
+    #[test]
+    fn test_contains_and_custom_rules() {
+        let tree = tree(dir(
+            "work",
+            vec![
+                dir(".venv", vec![file("pyvenv.cfg", 1), file("lib.py", 99)]),
+                dir("venv", vec![file("notes.txt", 1)]),
+                dir("bazel-out", vec![file("out.o", 40)]),
+                file("WORKSPACE", 1),
+            ],
+        ));
+
+        let config = CleanupConfig {
+            disable: vec!["venv".to_string()],
+            rules: vec![
+                CleanupRule::new("bazel", "Bazel output", "bazel-*").markers(&["WORKSPACE"]),
+            ],
+            ..Default::default()
+        };
+        let report = CleanupFinder::with_config(&config).find(&tree);
+        assert_eq!(report.candidates.len(), 1);
+        assert_eq!(report.candidates[0].rule, "bazel");
+
+        let report = CleanupFinder::new().find(&tree);
+        assert_eq!(report.candidates.len(), 1);
+        assert_eq!(report.candidates[0].path, PathBuf::from("/work/.venv"));
+        assert_eq!(report.by_rule()[0].size, 100);
+    }
+
+    #[test]
+    fn test_markerless_rules() {
+        let tree = tree(dir(
+            "work",
+            vec![
+                dir(
+                    "lib",
+                    vec![
+                        file("mod.py", 10),
+                        dir("__pycache__", vec![file("mod.cpython-312.pyc", 30)]),
+                    ],
+                ),
+                dir(".pytest_cache", vec![file("lastfailed", 20)]),
+                dir(".mypy_cache", vec![file("cache.db", 40)]),
+            ],
+        ));
+
+        let report = CleanupFinder::new().find(&tree);
+        let mut rules: Vec<_> = report.candidates.iter().map(|c| c.rule.as_str()).collect();
+        rules.sort();
+        assert_eq!(rules, ["mypy", "pycache", "pytest"]);
+        assert_eq!(report.total_reclaimable, 90);
+    }
+
+    #[test]
+    fn test_cache_needs_a_project() {
+        // A home directory's `.cache` holds every application's cache.
+        let home = tree(dir(
+            "home",
+            vec![
+                file(".bashrc", 1),
+                dir(".cache", vec![dir("mozilla", vec![file("places", 500)])]),
+            ],
+        ));
+        assert!(CleanupFinder::new().find(&home).candidates.is_empty());
+
+        let project = tree(dir(
+            "work",
+            vec![
+                file("package.json", 1),
+                dir(".cache", vec![file("babel.json", 50)]),
+            ],
+        ));
+        let report = CleanupFinder::new().find(&project);
+        assert_eq!(report.candidates.len(), 1);
+        assert_eq!(report.candidates[0].rule, "cache");
+    }
+
+    #[test]
+    fn test_profile_overrides() {
+        let config: CleanupConfig = toml::from_str(
+            r#"
+            builtin = false
+
+            [[rules]]
+            name = "scratch"
+            description = "Scratch output"
+            directory = "scratch-*"
+            contains = ["*.tmp"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.effective_rules().len(), 1);
+
+        let tree = tree(dir(
+            "work",
+            vec![
+                dir("__pycache__", vec![file("a.pyc", 5)]),
+                dir("scratch-1", vec![file("a.tmp", 7)]),
+                dir("scratch-2", vec![file("keep.txt", 9)]),
+            ],
+        ));
+        let report = CleanupFinder::with_config(&config).find(&tree);
+        assert_eq!(report.candidates.len(), 1);
+        assert_eq!(report.candidates[0].path, PathBuf::from("/work/scratch-1"));
+
+        let config: CleanupConfig = toml::from_str(r#"disable = ["pycache"]"#).unwrap();
+        assert!(config.builtin);
+        assert!(
+            CleanupFinder::with_config(&config)
+                .find(&tree)
+                .candidates
+                .is_empty()
+        );
+    }
+}
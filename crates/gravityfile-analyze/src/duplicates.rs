@@ -21,15 +21,21 @@ use serde::{Deserialize, Serialize};
 use gravityfile_core::{ContentHash, FileNode, FileTree, NodeKind};
 
 /// Configuration for duplicate detection.
-#[derive(Debug, Clone, Builder)]
+///
+/// Deserializes from the `[duplicates]` table of a profile; sizes accept
+/// `"1MB"`-style strings.
+#[derive(Debug, Clone, Builder, Serialize, Deserialize)]
 #[builder(setter(into))]
+#[serde(default)]
 pub struct DuplicateConfig {
     /// Minimum file size to consider (skip tiny files).
     #[builder(default = "1024")]
+    #[serde(with = "crate::units::size_spec")]
     pub min_size: u64,
 
     /// Maximum file size to consider (skip huge files).
     #[builder(default = "u64::MAX")]
+    #[serde(with = "crate::units::size_spec")]
     pub max_size: u64,
 
     /// Use quick comparison (size + partial hash) before full hash.
@@ -242,7 +248,7 @@ impl DuplicateFinder {
 
         // Sort by wasted space descending
         let mut groups = duplicate_groups;
-        groups.sort_by(|a, b| b.wasted_bytes.cmp(&a.wasted_bytes));
+        groups.sort_by_key(|g| std::cmp::Reverse(g.wasted_bytes));
 
         // Compute all summary stats from the full (untruncated) groups list
         let total_duplicate_size: u64 = groups.iter().map(|g| g.size * g.paths.len() as u64).sum();
@@ -0,0 +1,581 @@
+//! File-type breakdown.
+//!
+//! Classifies files into broad categories (video, archives, source code,
+//! binaries, logs, VM images, ...) by extension, falling back to sniffing
+//! the first bytes of files whose extension is missing or unknown. Bytes and
+//! file counts are aggregated per category and per extension for every
+//! directory in the tree.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use gravityfile_core::{FileNode, FileTree, NodeKind};
+
+/// Number of leading bytes read when sniffing a file.
+const SNIFF_LEN: usize = 512;
+
+/// Broad category of a file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileCategory {
+    Video,
+    Audio,
+    Image,
+    Document,
+    Archive,
+    Source,
+    Binary,
+    Log,
+    Data,
+    VmImage,
+    Other,
+}
+
+impl FileCategory {
+    /// All categories, in display order.
+    pub const ALL: [FileCategory; 11] = [
+        Self::Video,
+        Self::Audio,
+        Self::Image,
+        Self::Document,
+        Self::Archive,
+        Self::Source,
+        Self::Binary,
+        Self::Log,
+        Self::Data,
+        Self::VmImage,
+        Self::Other,
+    ];
+
+    /// Human-readable label.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Video => "Video",
+            Self::Audio => "Audio",
+            Self::Image => "Images",
+            Self::Document => "Documents",
+            Self::Archive => "Archives",
+            Self::Source => "Source code",
+            Self::Binary => "Binaries",
+            Self::Log => "Logs",
+            Self::Data => "Data",
+            Self::VmImage => "VM images",
+            Self::Other => "Other",
+        }
+    }
+
+    /// Category for a lowercase extension (without the dot), if known.
+    pub fn from_extension(ext: &str) -> Option<Self> {
+        let category = match ext {
+            "mp4" | "mkv" | "mov" | "avi" | "webm" | "wmv" | "flv" | "m4v" | "mpg" | "mpeg"
+            | "3gp" | "m2ts" | "mts" | "vob" | "ogv" => Self::Video,
+            "mp3" | "flac" | "wav" | "ogg" | "oga" | "opus" | "m4a" | "aac" | "wma" | "aif"
+            | "aiff" | "alac" | "mid" | "midi" => Self::Audio,
+            "jpg" | "jpeg" | "png" | "gif" | "bmp" | "tif" | "tiff" | "webp" | "heic" | "heif"
+            | "avif" | "svg" | "ico" | "icns" | "cr2" | "nef" | "arw" | "dng" | "psd" | "xcf" => {
+                Self::Image
+            }
+            "pdf" | "doc" | "docx" | "odt" | "rtf" | "txt" | "md" | "rst" | "tex" | "epub"
+            | "mobi" | "xls" | "xlsx" | "ods" | "ppt" | "pptx" | "odp" | "pages" | "numbers"
+            | "key" => Self::Document,
+            "zip" | "tar" | "gz" | "tgz" | "bz2" | "tbz2" | "xz" | "txz" | "zst" | "7z" | "rar"
+            | "lz4" | "lzma" | "z" | "cab" | "jar" | "war" | "deb" | "rpm" | "apk" | "whl"
+            | "crate" | "gem" | "nupkg" => Self::Archive,
+            "rs" | "c" | "h" | "cc" | "cpp" | "cxx" | "hpp" | "hh" | "py" | "pyi" | "js"
+            | "mjs" | "cjs" | "jsx" | "ts" | "tsx" | "go" | "java" | "kt" | "kts" | "scala"
+            | "swift" | "m" | "mm" | "rb" | "php" | "pl" | "pm" | "lua" | "sh" | "bash" | "zsh"
+            | "fish" | "ps1" | "bat" | "cmd" | "cs" | "fs" | "vb" | "dart" | "ex" | "exs"
+            | "erl" | "hrl" | "hs" | "ml" | "mli" | "clj" | "cljs" | "elm" | "r" | "jl" | "zig"
+            | "nim" | "sql" | "html" | "htm" | "css" | "scss" | "sass" | "less" | "vue"
+            | "svelte" | "toml" | "yaml" | "yml" | "ini" | "cfg" | "gradle" | "cmake" | "mk"
+            | "map" => Self::Source,
+            "exe" | "dll" | "so" | "dylib" | "a" | "lib" | "o" | "obj" | "bin" | "class"
+            | "pyc" | "pyo" | "wasm" | "ko" | "node" | "rlib" | "rmeta" | "pdb" => Self::Binary,
+            "log" | "journal" => Self::Log,
+            "json" | "jsonl" | "ndjson" | "csv" | "tsv" | "xml" | "parquet" | "arrow" | "avro"
+            | "orc" | "db" | "sqlite" | "sqlite3" | "mdb" | "h5" | "hdf5" | "npy" | "npz"
+            | "pkl" | "pickle" | "feather" | "dat" | "idx" | "pack" => Self::Data,
+            "iso" | "img" | "qcow" | "qcow2" | "vmdk" | "vdi" | "vhd" | "vhdx" | "ova" | "ovf"
+            | "dmg" => Self::VmImage,
+            _ => return None,
+        };
+        Some(category)
+    }
+
+    /// Category from the leading bytes of a file, if recognised.
+    pub fn from_magic(bytes: &[u8]) -> Option<Self> {
+        const MAGIC: &[(&[u8], FileCategory)] = &[
+            (b"\x7fELF", FileCategory::Binary),
+            (b"MZ", FileCategory::Binary),
+            (b"\xfe\xed\xfa\xce", FileCategory::Binary),
+            (b"\xfe\xed\xfa\xcf", FileCategory::Binary),
+            (b"\xce\xfa\xed\xfe", FileCategory::Binary),
+            (b"\xcf\xfa\xed\xfe", FileCategory::Binary),
+            (b"\xca\xfe\xba\xbe", FileCategory::Binary),
+            (b"\0asm", FileCategory::Binary),
+            (b"PK\x03\x04", FileCategory::Archive),
+            (b"\x1f\x8b", FileCategory::Archive),
+            (b"BZh", FileCategory::Archive),
+            (b"7z\xbc\xaf\x27\x1c", FileCategory::Archive),
+            (b"\xfd7zXZ\0", FileCategory::Archive),
+            (b"\x28\xb5\x2f\xfd", FileCategory::Archive),
+            (b"Rar!", FileCategory::Archive),
+            (b"%PDF", FileCategory::Document),
+            (b"\x89PNG", FileCategory::Image),
+            (b"\xff\xd8\xff", FileCategory::Image),
+            (b"GIF8", FileCategory::Image),
+            (b"ID3", FileCategory::Audio),
+            (b"OggS", FileCategory::Audio),
+            (b"fLaC", FileCategory::Audio),
+            (b"\x1a\x45\xdf\xa3", FileCategory::Video),
+            (b"QFI\xfb", FileCategory::VmImage),
+            (b"KDMV", FileCategory::VmImage),
+            (b"conectix", FileCategory::VmImage),
+            (b"vhdxfile", FileCategory::VmImage),
+            (b"<<< ", FileCategory::VmImage),
+            (b"SQLite format 3\0", FileCategory::Data),
+            (b"#!", FileCategory::Source),
+        ];
+
+        if let Some(&(_, category)) = MAGIC.iter().find(|(magic, _)| bytes.starts_with(magic)) {
+            return Some(category);
+        }
+        if bytes.starts_with(b"RIFF") && bytes.len() >= 12 {
+            return match &bytes[8..12] {
+                b"WAVE" => Some(Self::Audio),
+                b"AVI " => Some(Self::Video),
+                b"WEBP" => Some(Self::Image),
+                _ => None,
+            };
+        }
+        if bytes.get(4..8) == Some(b"ftyp") {
+            return Some(Self::Video);
+        }
+        if bytes.get(257..262) == Some(b"ustar") {
+            return Some(Self::Archive);
+        }
+        None
+    }
+}
+
+impl fmt::Display for FileCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
+impl FromStr for FileCategory {
+    type Err = String;
+
+    /// Parse a category name such as `video`, `vm_image` or `source`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let normalized = s.trim().to_ascii_lowercase().replace(['-', ' '], "_");
+        let category = match normalized.as_str() {
+            "video" | "videos" => Self::Video,
+            "audio" => Self::Audio,
+            "image" | "images" => Self::Image,
+            "document" | "documents" | "docs" => Self::Document,
+            "archive" | "archives" => Self::Archive,
+            "source" | "source_code" | "code" => Self::Source,
+            "binary" | "binaries" => Self::Binary,
+            "log" | "logs" => Self::Log,
+            "data" => Self::Data,
+            "vm_image" | "vm_images" | "vm" => Self::VmImage,
+            "other" => Self::Other,
+            _ => return Err(format!("unknown file category: {}", s)),
+        };
+        Ok(category)
+    }
+}
+
+/// Bytes and file count of one category.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CategoryStats {
+    /// The category.
+    pub category: FileCategory,
+    /// Total bytes of files in the category.
+    pub total_size: u64,
+    /// Number of files in the category.
+    pub file_count: u64,
+}
+
+/// Bytes and file count of one extension.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExtensionStats {
+    /// Lowercase extension without the dot; empty for files without one.
+    pub extension: String,
+    /// Category the files were classified as.
+    pub category: FileCategory,
+    /// Total bytes of files with this extension.
+    pub total_size: u64,
+    /// Number of files with this extension.
+    pub file_count: u64,
+}
+
+impl ExtensionStats {
+    /// Extension for display, `(none)` when the files have no extension.
+    pub fn display_name(&self) -> &str {
+        if self.extension.is_empty() {
+            "(none)"
+        } else {
+            &self.extension
+        }
+    }
+}
+
+/// Per-category and per-extension totals for one directory, including
+/// everything below it.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TypeBreakdown {
+    /// Total bytes of all classified files.
+    pub total_size: u64,
+    /// Number of classified files.
+    pub file_count: u64,
+    /// Non-empty categories, largest first.
+    pub categories: Vec<CategoryStats>,
+    /// Extensions, largest first.
+    pub extensions: Vec<ExtensionStats>,
+}
+
+impl TypeBreakdown {
+    /// Share of the total bytes held by `size`, between 0 and 1.
+    pub fn share(&self, size: u64) -> f64 {
+        if self.total_size == 0 {
+            0.0
+        } else {
+            size as f64 / self.total_size as f64
+        }
+    }
+
+    /// Extensions classified as `category`, largest first.
+    pub fn extensions_in(&self, category: FileCategory) -> impl Iterator<Item = &ExtensionStats> {
+        self.extensions
+            .iter()
+            .filter(move |e| e.category == category)
+    }
+}
+
+/// Results from file-type analysis.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FileTypeReport {
+    /// Breakdown of the whole tree.
+    pub root: TypeBreakdown,
+    /// Number of files classified by sniffing their content.
+    pub sniffed_files: u64,
+    /// Breakdown of every directory, keyed by path.
+    #[serde(skip)]
+    pub directories: HashMap<PathBuf, TypeBreakdown>,
+}
+
+impl FileTypeReport {
+    /// Breakdown of the directory at `path`, if it was part of the scan.
+    pub fn breakdown(&self, path: &Path) -> Option<&TypeBreakdown> {
+        self.directories.get(path)
+    }
+}
+
+/// Running totals keyed by (extension, category).
+type Totals = HashMap<(String, FileCategory), (u64, u64)>;
+
+/// File-type analyzer.
+pub struct FileTypeAnalyzer {
+    sniff: bool,
+}
+
+impl Default for FileTypeAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FileTypeAnalyzer {
+    /// Create an analyzer that sniffs files with unknown extensions.
+    pub fn new() -> Self {
+        Self { sniff: true }
+    }
+
+    /// Enable or disable reading file contents to classify files whose
+    /// extension is missing or unknown.
+    pub fn sniff(mut self, sniff: bool) -> Self {
+        self.sniff = sniff;
+        self
+    }
+
+    /// Classify a single file by name, reading its first bytes from `path`
+    /// when the extension does not decide. Returns the lowercase extension,
+    /// the category, and whether the content was sniffed.
+    pub fn classify(
+        &self,
+        name: &str,
+        path: &Path,
+        executable: bool,
+    ) -> (String, FileCategory, bool) {
+        let extension = extension_of(name);
+        if let Some(category) = FileCategory::from_extension(&extension) {
+            return (extension, category, false);
+        }
+        if is_rotated_log(name) {
+            return (extension, FileCategory::Log, false);
+        }
+        if self.sniff
+            && let Some(category) = sniff_file(path)
+        {
+            return (extension, category, true);
+        }
+        let category = if executable {
+            FileCategory::Binary
+        } else {
+            FileCategory::Other
+        };
+        (extension, category, false)
+    }
+
+    /// Analyze a file tree.
+    pub fn analyze(&self, tree: &FileTree) -> FileTypeReport {
+        let mut report = FileTypeReport::default();
+        let totals = self.dfs(&tree.root, &tree.root_path, &mut report);
+        report.root = build_breakdown(&totals);
+        report
+            .directories
+            .insert(tree.root_path.clone(), report.root.clone());
+        report
+    }
+
+    fn dfs(&self, node: &FileNode, path: &Path, report: &mut FileTypeReport) -> Totals {
+        let mut totals = Totals::new();
+
+        for child in &node.children {
+            match &child.kind {
+                NodeKind::File { executable } => {
+                    let (extension, category, sniffed) =
+                        self.classify(&child.name, &path.join(&*child.name), *executable);
+                    if sniffed {
+                        report.sniffed_files += 1;
+                    }
+                    let entry = totals.entry((extension, category)).or_default();
+                    entry.0 += child.size;
+                    entry.1 += 1;
+                }
+                NodeKind::Directory { .. } => {
+                    let child_path = path.join(&*child.name);
+                    let child_totals = self.dfs(child, &child_path, report);
+                    report
+                        .directories
+                        .insert(child_path, build_breakdown(&child_totals));
+                    for (key, (size, count)) in child_totals {
+                        let entry = totals.entry(key).or_default();
+                        entry.0 += size;
+                        entry.1 += count;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        totals
+    }
+}
+
+fn build_breakdown(totals: &Totals) -> TypeBreakdown {
+    let mut categories: HashMap<FileCategory, (u64, u64)> = HashMap::new();
+    let mut extensions = Vec::with_capacity(totals.len());
+    let mut breakdown = TypeBreakdown::default();
+
+    for ((extension, category), &(size, count)) in totals {
+        breakdown.total_size += size;
+        breakdown.file_count += count;
+        let entry = categories.entry(*category).or_default();
+        entry.0 += size;
+        entry.1 += count;
+        extensions.push(ExtensionStats {
+            extension: extension.clone(),
+            category: *category,
+            total_size: size,
+            file_count: count,
+        });
+    }
+
+    breakdown.categories = categories
+        .into_iter()
+        .map(|(category, (total_size, file_count))| CategoryStats {
+            category,
+            total_size,
+            file_count,
+        })
+        .collect();
+    breakdown.categories.sort_by(|a, b| {
+        b.total_size
+            .cmp(&a.total_size)
+            .then(a.category.cmp(&b.category))
+    });
+
+    extensions.sort_by(|a, b| {
+        b.total_size
+            .cmp(&a.total_size)
+            .then_with(|| a.extension.cmp(&b.extension))
+            .then(a.category.cmp(&b.category))
+    });
+    breakdown.extensions = extensions;
+    breakdown
+}
+
+/// Lowercase extension of a file name; empty for none and for dotfiles
+/// such as `.bashrc`.
+fn extension_of(name: &str) -> String {
+    match name.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => ext.to_ascii_lowercase(),
+        _ => String::new(),
+    }
+}
+
+/// Whether the name looks like a rotated log (`syslog.1`, `app.log.3`).
+fn is_rotated_log(name: &str) -> bool {
+    let Some((stem, suffix)) = name.rsplit_once('.') else {
+        return false;
+    };
+    !suffix.is_empty() && suffix.bytes().all(|b| b.is_ascii_digit()) && stem.ends_with("log")
+}
+
+fn sniff_file(path: &Path) -> Option<FileCategory> {
+    let mut file = std::fs::File::open(path).ok()?;
+    let mut buf = [0u8; SNIFF_LEN];
+    let mut len = 0;
+    while len < buf.len() {
+        match file.read(&mut buf[len..]) {
+            Ok(0) => break,
+            Ok(n) => len += n,
+            Err(_) => return None,
+        }
+    }
+    FileCategory::from_magic(&buf[..len])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gravityfile_core::{NodeId, ScanConfig, Timestamps, TreeStats};
+    use std::time::{Duration, SystemTime};
+
+    fn file(id: u64, name: &str, size: u64) -> FileNode {
+        FileNode::new_file(
+            NodeId::new(id),
+            name,
+            size,
+            1,
+            Timestamps::with_modified(SystemTime::now()),
+            false,
+        )
+    }
+
+    fn dir(id: u64, name: &str, children: Vec<FileNode>) -> FileNode {
+        let mut node = FileNode::new_directory(
+            NodeId::new(id),
+            name,
+            Timestamps::with_modified(SystemTime::now()),
+        );
+        node.size = children.iter().map(|c| c.size).sum();
+        node.children = children;
+        node
+    }
+
+    #[test]
+    fn test_classify_by_extension_and_name() {
+        assert_eq!(
+            FileCategory::from_extension("mkv"),
+            Some(FileCategory::Video)
+        );
+        assert_eq!(
+            FileCategory::from_extension("qcow2"),
+            Some(FileCategory::VmImage)
+        );
+        assert_eq!(FileCategory::from_extension("unknown"), None);
+        assert_eq!(extension_of("Archive.TAR.GZ"), "gz");
+        assert_eq!(extension_of(".bashrc"), "");
+        assert!(is_rotated_log("app.log.3"));
+        assert!(is_rotated_log("syslog.1"));
+        assert!(!is_rotated_log("lib.so.1"));
+        assert_eq!("vm-images".parse(), Ok(FileCategory::VmImage));
+    }
+
+    #[test]
+    fn test_magic_bytes() {
+        assert_eq!(
+            FileCategory::from_magic(b"\x7fELF\x02\x01"),
+            Some(FileCategory::Binary)
+        );
+        assert_eq!(
+            FileCategory::from_magic(b"RIFF\0\0\0\0WAVEfmt "),
+            Some(FileCategory::Audio)
+        );
+        assert_eq!(
+            FileCategory::from_magic(b"\0\0\0\x20ftypisom"),
+            Some(FileCategory::Video)
+        );
+        assert_eq!(FileCategory::from_magic(b"plain text"), None);
+    }
+
+    #[test]
+    fn test_sniff_unknown_extension() {
+        let temp = tempfile::TempDir::new().unwrap();
+        std::fs::write(temp.path().join("blob"), b"\x89PNG\r\n\x1a\n").unwrap();
+        let (ext, category, sniffed) =
+            FileTypeAnalyzer::new().classify("blob", &temp.path().join("blob"), false);
+        assert_eq!(
+            (ext.as_str(), category, sniffed),
+            ("", FileCategory::Image, true)
+        );
+
+        let (_, category, _) =
+            FileTypeAnalyzer::new()
+                .sniff(false)
+                .classify("blob", &temp.path().join("blob"), false);
+        assert_eq!(category, FileCategory::Other);
+    }
+
+    #[test]
+    fn test_breakdown_per_directory() {
+        let root = dir(
+            0,
+            "root",
+            vec![
+                dir(
+                    1,
+                    "videos",
+                    vec![file(2, "a.mp4", 1000), file(3, "b.MKV", 500)],
+                ),
+                dir(
+                    4,
+                    "src",
+                    vec![file(5, "main.rs", 40), file(6, "lib.rs", 60)],
+                ),
+                file(7, "server.log", 200),
+            ],
+        );
+        let tree = FileTree::new(
+            root,
+            PathBuf::from("/root"),
+            ScanConfig::new("/root"),
+            TreeStats::new(),
+            Duration::ZERO,
+            Vec::new(),
+        );
+
+        let report = FileTypeAnalyzer::new().sniff(false).analyze(&tree);
+        assert_eq!(report.root.total_size, 1800);
+        assert_eq!(report.root.file_count, 5);
+        assert_eq!(report.root.categories[0].category, FileCategory::Video);
+        assert_eq!(report.root.categories[0].total_size, 1500);
+        assert_eq!(report.root.extensions[0].extension, "mp4");
+
+        let src = report.breakdown(Path::new("/root/src")).unwrap();
+        assert_eq!(src.categories.len(), 1);
+        assert_eq!(src.extensions[0].extension, "rs");
+        assert_eq!(src.extensions[0].file_count, 2);
+        assert_eq!(report.root.extensions_in(FileCategory::Log).count(), 1);
+    }
+}
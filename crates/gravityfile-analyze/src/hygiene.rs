@@ -0,0 +1,651 @@
+//! Filesystem hygiene: empty directories, zero-byte files, broken symlinks
+//! and files whose hard links reach outside the scan.
+//!
+//! A directory counts as empty when it holds nothing but directories that
+//! are themselves empty; only the outermost one is reported, together with
+//! the number of empty directories inside it. Scan roots are never reported.
+//!
+//! Findings reflect the scan, which may have skipped hidden or ignored
+//! entries or stopped at a depth limit. When the scan recorded disk usage
+//! rather than length, files with no blocks allocated (sparse or not yet
+//! written) are checked on disk before they are called zero-byte.
+//! [`HygieneReport::verify`] re-checks every entry on disk and should be run
+//! before anything is deleted.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use gravityfile_core::{FileNode, FileTree, InodeInfo, NodeKind};
+
+/// The kinds of hygiene issue, in report order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HygieneKind {
+    /// Directories containing nothing but empty directories.
+    EmptyDir,
+    /// Regular files with no content.
+    ZeroByte,
+    /// Symlinks whose target does not exist.
+    BrokenSymlink,
+    /// Files with hard links outside the scan; deleting them frees nothing.
+    DanglingHardlink,
+}
+
+impl HygieneKind {
+    /// All kinds, in report order.
+    pub const ALL: [HygieneKind; 4] = [
+        HygieneKind::EmptyDir,
+        HygieneKind::ZeroByte,
+        HygieneKind::BrokenSymlink,
+        HygieneKind::DanglingHardlink,
+    ];
+
+    /// Short name, as accepted by `FromStr`.
+    pub fn label(self) -> &'static str {
+        match self {
+            HygieneKind::EmptyDir => "empty",
+            HygieneKind::ZeroByte => "zero",
+            HygieneKind::BrokenSymlink => "broken",
+            HygieneKind::DanglingHardlink => "hardlinks",
+        }
+    }
+
+    /// Human-readable description for report headings.
+    pub fn description(self) -> &'static str {
+        match self {
+            HygieneKind::EmptyDir => "Empty directories",
+            HygieneKind::ZeroByte => "Zero-byte files",
+            HygieneKind::BrokenSymlink => "Broken symlinks",
+            HygieneKind::DanglingHardlink => "Hard links outside the scan",
+        }
+    }
+
+    /// Whether entries of this kind are clutter that can be deleted.
+    /// Dangling hard links are reported for information only.
+    pub fn is_deletable(self) -> bool {
+        self != HygieneKind::DanglingHardlink
+    }
+}
+
+impl fmt::Display for HygieneKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
+impl FromStr for HygieneKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "empty" | "empty-dirs" | "dirs" => Ok(HygieneKind::EmptyDir),
+            "zero" | "zero-byte" | "empty-files" => Ok(HygieneKind::ZeroByte),
+            "broken" | "broken-symlinks" | "symlinks" => Ok(HygieneKind::BrokenSymlink),
+            "hardlinks" | "hardlink" | "links" => Ok(HygieneKind::DanglingHardlink),
+            other => Err(format!(
+                "Unknown hygiene kind '{}' (expected empty, zero, broken or hardlinks)",
+                other
+            )),
+        }
+    }
+}
+
+/// What is wrong with an entry.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum HygieneIssue {
+    /// An empty directory and how many empty directories it contains.
+    EmptyDir { nested: u64 },
+    /// A regular file with no content.
+    ZeroByte,
+    /// A symlink pointing at `target`, which does not exist.
+    BrokenSymlink { target: String },
+    /// A file with `links` hard links of which only `found` are in the scan.
+    DanglingHardlink { found: u64, links: u64 },
+}
+
+impl HygieneIssue {
+    /// The kind of issue.
+    pub fn kind(&self) -> HygieneKind {
+        match self {
+            HygieneIssue::EmptyDir { .. } => HygieneKind::EmptyDir,
+            HygieneIssue::ZeroByte => HygieneKind::ZeroByte,
+            HygieneIssue::BrokenSymlink { .. } => HygieneKind::BrokenSymlink,
+            HygieneIssue::DanglingHardlink { .. } => HygieneKind::DanglingHardlink,
+        }
+    }
+
+    /// One-line detail for listings (nested count, target or link count).
+    pub fn detail(&self) -> String {
+        match self {
+            HygieneIssue::EmptyDir { nested: 0 } => String::new(),
+            HygieneIssue::EmptyDir { nested } => format!("{} empty subdirectories", nested),
+            HygieneIssue::ZeroByte => String::new(),
+            HygieneIssue::BrokenSymlink { target } => format!("-> {}", target),
+            HygieneIssue::DanglingHardlink { found, links } => {
+                format!("{} of {} links in scan", found, links)
+            }
+        }
+    }
+}
+
+/// A file, directory or symlink with a hygiene issue.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HygieneEntry {
+    /// Full path of the entry.
+    pub path: PathBuf,
+    /// What is wrong with it.
+    #[serde(flatten)]
+    pub issue: HygieneIssue,
+    /// Bytes the entry holds: the file size for dangling hard links, else 0.
+    pub size: u64,
+}
+
+impl HygieneEntry {
+    /// The kind of issue.
+    pub fn kind(&self) -> HygieneKind {
+        self.issue.kind()
+    }
+
+    /// Whether the entry still has its issue on disk.
+    pub fn still_applies(&self) -> bool {
+        let Ok(metadata) = fs::symlink_metadata(&self.path) else {
+            return false;
+        };
+        match self.issue {
+            HygieneIssue::EmptyDir { .. } => metadata.is_dir() && holds_only_dirs(&self.path),
+            HygieneIssue::ZeroByte => metadata.is_file() && metadata.len() == 0,
+            HygieneIssue::BrokenSymlink { .. } => {
+                metadata.is_symlink() && fs::metadata(&self.path).is_err()
+            }
+            HygieneIssue::DanglingHardlink { .. } => metadata.is_file(),
+        }
+    }
+}
+
+/// Whether `path` contains nothing but directories, recursively.
+fn holds_only_dirs(path: &Path) -> bool {
+    let Ok(entries) = fs::read_dir(path) else {
+        return false;
+    };
+    entries.into_iter().all(|entry| {
+        entry.is_ok_and(|entry| {
+            entry.file_type().is_ok_and(|t| t.is_dir()) && holds_only_dirs(&entry.path())
+        })
+    })
+}
+
+/// Results of a hygiene analysis.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HygieneReport {
+    /// Entries grouped by kind, then sorted by path.
+    pub entries: Vec<HygieneEntry>,
+}
+
+impl HygieneReport {
+    /// Number of entries of `kind`.
+    pub fn count(&self, kind: HygieneKind) -> usize {
+        self.entries.iter().filter(|e| e.kind() == kind).count()
+    }
+
+    /// Bytes held by files whose other hard links keep them alive.
+    pub fn pinned_bytes(&self) -> u64 {
+        self.entries
+            .iter()
+            .filter(|e| e.kind() == HygieneKind::DanglingHardlink)
+            .map(|e| e.size)
+            .sum()
+    }
+
+    /// Entries that can be deleted, in report order.
+    pub fn deletable(&self) -> impl Iterator<Item = &HygieneEntry> {
+        self.entries.iter().filter(|e| e.kind().is_deletable())
+    }
+
+    /// Keep only entries of the given kinds.
+    pub fn retain_kinds(&mut self, kinds: &[HygieneKind]) {
+        self.entries.retain(|e| kinds.contains(&e.kind()));
+    }
+
+    /// Drop entries that no longer have their issue on disk, returning how
+    /// many were dropped.
+    pub fn verify(&mut self) -> usize {
+        let before = self.entries.len();
+        self.entries.retain(HygieneEntry::still_applies);
+        before - self.entries.len()
+    }
+}
+
+/// Finds hygiene issues in a scanned tree.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HygieneAnalyzer {
+    /// Whether node sizes are file lengths rather than disk usage.
+    apparent_size: bool,
+}
+
+/// Names of one hard-linked inode seen during the walk.
+struct LinkedInode {
+    paths: Vec<PathBuf>,
+    size: u64,
+    links: u64,
+}
+
+impl HygieneAnalyzer {
+    /// Create a new analyzer for trees sized by disk usage.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set whether node sizes are file lengths (`ScanConfig::apparent_size`),
+    /// so a size of zero means an empty file without checking the disk.
+    pub fn apparent_size(mut self, apparent_size: bool) -> Self {
+        self.apparent_size = apparent_size;
+        self
+    }
+
+    /// Analyze the whole tree. Each scan root of a multi-root tree is a root.
+    /// Sizes are read the way the tree's scan configuration recorded them.
+    pub fn analyze(&self, tree: &FileTree) -> HygieneReport {
+        let analyzer = self.apparent_size(tree.config.apparent_size);
+        if tree.is_multi_root() {
+            let mut entries = Vec::new();
+            let mut linked = HashMap::new();
+            for child in &tree.root.children {
+                let path = tree.root_path.join(&*child.name);
+                walk_root(
+                    child,
+                    &path,
+                    analyzer.apparent_size,
+                    &mut entries,
+                    &mut linked,
+                );
+            }
+            finish(entries, linked, analyzer.apparent_size)
+        } else {
+            analyzer.analyze_in(&tree.root, &tree.root_path)
+        }
+    }
+
+    /// Analyze the subtree `node`, which lives at `path`.
+    pub fn analyze_in(&self, node: &FileNode, path: &Path) -> HygieneReport {
+        let mut entries = Vec::new();
+        let mut linked = HashMap::new();
+        walk_root(node, path, self.apparent_size, &mut entries, &mut linked);
+        finish(entries, linked, self.apparent_size)
+    }
+}
+
+/// Whether a file the scan sized as `size` is empty. Disk usage is also
+/// zero for sparse files and files with no blocks written yet, so without
+/// apparent sizes the length is checked on disk.
+fn is_empty_file(size: u64, path: &Path, apparent_size: bool) -> bool {
+    size == 0 && (apparent_size || fs::symlink_metadata(path).is_ok_and(|m| m.len() == 0))
+}
+
+fn walk_root(
+    node: &FileNode,
+    path: &Path,
+    apparent_size: bool,
+    entries: &mut Vec<HygieneEntry>,
+    linked: &mut HashMap<InodeInfo, LinkedInode>,
+) {
+    match &node.kind {
+        NodeKind::Directory { .. } => {
+            walk_dir(node, path, true, apparent_size, entries, linked);
+        }
+        _ => visit_leaf(node, path.to_path_buf(), apparent_size, entries, linked),
+    }
+}
+
+/// Walk a directory's children, reporting the outermost empty directories
+/// below it. Returns the number of directories inside `node`, counting
+/// itself, when it is empty and not a root, and `None` otherwise.
+fn walk_dir(
+    node: &FileNode,
+    path: &Path,
+    root: bool,
+    apparent_size: bool,
+    entries: &mut Vec<HygieneEntry>,
+    linked: &mut HashMap<InodeInfo, LinkedInode>,
+) -> Option<u64> {
+    let mut empty_children = Vec::new();
+    let mut all_empty = true;
+    for child in &node.children {
+        let child_path = path.join(&*child.name);
+        if let NodeKind::Directory { .. } = child.kind {
+            match walk_dir(child, &child_path, false, apparent_size, entries, linked) {
+                Some(dirs) => empty_children.push((child_path, dirs)),
+                None => all_empty = false,
+            }
+        } else {
+            all_empty = false;
+            visit_leaf(child, child_path, apparent_size, entries, linked);
+        }
+    }
+
+    if all_empty && !root {
+        return Some(1 + empty_children.iter().map(|(_, dirs)| dirs).sum::<u64>());
+    }
+    for (path, dirs) in empty_children {
+        entries.push(HygieneEntry {
+            path,
+            issue: HygieneIssue::EmptyDir { nested: dirs - 1 },
+            size: 0,
+        });
+    }
+    None
+}
+
+fn visit_leaf(
+    node: &FileNode,
+    path: PathBuf,
+    apparent_size: bool,
+    entries: &mut Vec<HygieneEntry>,
+    linked: &mut HashMap<InodeInfo, LinkedInode>,
+) {
+    match &node.kind {
+        NodeKind::File { .. } => match node.inode.filter(|i| i.links > 1) {
+            // Only the first name of a hard-linked inode carries its size,
+            // so zero-byte checks wait until every name has been seen.
+            Some(inode) => {
+                let entry = linked.entry(inode).or_insert_with(|| LinkedInode {
+                    paths: Vec::new(),
+                    size: 0,
+                    links: inode.links,
+                });
+                entry.paths.push(path);
+                entry.size = entry.size.max(node.size);
+            }
+            None if is_empty_file(node.size, &path, apparent_size) => entries.push(HygieneEntry {
+                path,
+                issue: HygieneIssue::ZeroByte,
+                size: 0,
+            }),
+            None => {}
+        },
+        NodeKind::Symlink {
+            target,
+            broken: true,
+        } => entries.push(HygieneEntry {
+            path,
+            issue: HygieneIssue::BrokenSymlink {
+                target: target.to_string(),
+            },
+            size: 0,
+        }),
+        _ => {}
+    }
+}
+
+fn finish(
+    mut entries: Vec<HygieneEntry>,
+    linked: HashMap<InodeInfo, LinkedInode>,
+    apparent_size: bool,
+) -> HygieneReport {
+    for inode in linked.into_values() {
+        let found = inode.paths.len() as u64;
+        if is_empty_file(inode.size, &inode.paths[0], apparent_size) {
+            entries.extend(inode.paths.into_iter().map(|path| HygieneEntry {
+                path,
+                issue: HygieneIssue::ZeroByte,
+                size: 0,
+            }));
+        } else if found < inode.links {
+            // Report each inode once, under its first name.
+            let path = inode.paths.into_iter().min().unwrap_or_default();
+            entries.push(HygieneEntry {
+                path,
+                issue: HygieneIssue::DanglingHardlink {
+                    found,
+                    links: inode.links,
+                },
+                size: inode.size,
+            });
+        }
+    }
+    entries.sort_by(|a, b| a.kind().cmp(&b.kind()).then_with(|| a.path.cmp(&b.path)));
+    HygieneReport { entries }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gravityfile_core::{NodeId, Timestamps, TreeStats};
+    use std::time::{Duration, SystemTime};
+
+    fn file(name: &str, size: u64) -> FileNode {
+        FileNode::new_file(
+            NodeId::new(0),
+            name,
+            size,
+            1,
+            Timestamps::with_modified(SystemTime::now()),
+            false,
+        )
+    }
+
+    fn linked(name: &str, size: u64, inode: u64, links: u64) -> FileNode {
+        let mut node = file(name, size);
+        node.inode = Some(InodeInfo::new(inode, 1).with_links(links));
+        node
+    }
+
+    fn dir(name: &str, children: Vec<FileNode>) -> FileNode {
+        let mut node = FileNode::new_directory(
+            NodeId::new(0),
+            name,
+            Timestamps::with_modified(SystemTime::now()),
+        );
+        node.children = children;
+        node
+    }
+
+    fn broken(name: &str) -> FileNode {
+        FileNode {
+            kind: NodeKind::Symlink {
+                target: "gone".into(),
+                broken: true,
+            },
+            ..file(name, 0)
+        }
+    }
+
+    fn tree(root: FileNode) -> FileTree {
+        FileTree::new(
+            root,
+            PathBuf::from("/r"),
+            gravityfile_core::ScanConfig::builder()
+                .root("/r")
+                .apparent_size(true)
+                .build()
+                .unwrap(),
+            TreeStats::default(),
+            Duration::ZERO,
+            Vec::new(),
+        )
+    }
+
+    fn paths(report: &HygieneReport, kind: HygieneKind) -> Vec<String> {
+        report
+            .entries
+            .iter()
+            .filter(|e| e.kind() == kind)
+            .map(|e| e.path.display().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_empty_dirs_report_outermost() {
+        let root = dir(
+            "r",
+            vec![
+                dir(
+                    "a",
+                    vec![dir("b", vec![dir("c", vec![])]), dir("d", vec![])],
+                ),
+                dir("keep", vec![file("x", 10), dir("e", vec![])]),
+            ],
+        );
+        let report = HygieneAnalyzer::new().analyze(&tree(root));
+        assert_eq!(paths(&report, HygieneKind::EmptyDir), ["/r/a", "/r/keep/e"]);
+        assert_eq!(
+            report.entries[0].issue,
+            HygieneIssue::EmptyDir { nested: 3 }
+        );
+        assert_eq!(report.entries[0].issue.detail(), "3 empty subdirectories");
+    }
+
+    #[test]
+    fn test_empty_root_is_not_reported() {
+        let root = dir("r", vec![dir("a", vec![])]);
+        let report = HygieneAnalyzer::new().analyze(&tree(root));
+        assert_eq!(paths(&report, HygieneKind::EmptyDir), ["/r/a"]);
+
+        let report = HygieneAnalyzer::new().analyze(&tree(dir("r", vec![])));
+        assert!(report.entries.is_empty());
+    }
+
+    #[test]
+    fn test_zero_byte_and_broken_symlinks() {
+        let root = dir(
+            "r",
+            vec![
+                file("empty.txt", 0),
+                file("full.txt", 5),
+                dir("links", vec![broken("dead")]),
+            ],
+        );
+        let report = HygieneAnalyzer::new().analyze(&tree(root));
+        assert_eq!(paths(&report, HygieneKind::ZeroByte), ["/r/empty.txt"]);
+        assert_eq!(
+            paths(&report, HygieneKind::BrokenSymlink),
+            ["/r/links/dead"]
+        );
+        // A directory holding only a broken symlink is not empty.
+        assert_eq!(report.count(HygieneKind::EmptyDir), 0);
+        assert_eq!(report.deletable().count(), 2);
+    }
+
+    #[test]
+    fn test_sparse_file_is_not_zero_byte() {
+        let temp = tempfile::tempdir().unwrap();
+        fs::File::create(temp.path().join("sparse.img"))
+            .unwrap()
+            .set_len(100 << 20)
+            .unwrap();
+        fs::write(temp.path().join("empty"), "").unwrap();
+
+        // Without apparent sizes both have no blocks allocated.
+        let root = dir("r", vec![file("sparse.img", 0), file("empty", 0)]);
+        let report = HygieneAnalyzer::new().analyze_in(&root, temp.path());
+        assert_eq!(report.entries.len(), 1);
+        assert_eq!(report.entries[0].path, temp.path().join("empty"));
+    }
+
+    #[test]
+    fn test_hardlinks() {
+        let root = dir(
+            "r",
+            vec![
+                // Both names in the scan; the second carries no size.
+                linked("a1", 100, 1, 2),
+                linked("a2", 0, 1, 2),
+                // One of three names in the scan.
+                linked("b", 40, 2, 3),
+                // Empty hard-linked files are zero-byte under every name.
+                linked("c1", 0, 3, 2),
+                linked("c2", 0, 3, 2),
+            ],
+        );
+        let report = HygieneAnalyzer::new().analyze(&tree(root));
+        assert_eq!(paths(&report, HygieneKind::DanglingHardlink), ["/r/b"]);
+        assert_eq!(
+            report.entries.last().unwrap().issue,
+            HygieneIssue::DanglingHardlink { found: 1, links: 3 }
+        );
+        assert_eq!(report.pinned_bytes(), 40);
+        assert_eq!(paths(&report, HygieneKind::ZeroByte), ["/r/c1", "/r/c2"]);
+        assert_eq!(report.deletable().count(), 2);
+    }
+
+    #[test]
+    fn test_retain_kinds() {
+        let root = dir("r", vec![file("z", 0), dir("e", vec![])]);
+        let mut report = HygieneAnalyzer::new().analyze(&tree(root));
+        report.retain_kinds(&[HygieneKind::ZeroByte]);
+        assert_eq!(report.entries.len(), 1);
+        assert_eq!(report.count(HygieneKind::EmptyDir), 0);
+    }
+
+    #[test]
+    fn test_kind_parse() {
+        assert_eq!("empty-dirs".parse(), Ok(HygieneKind::EmptyDir));
+        assert_eq!("Broken".parse(), Ok(HygieneKind::BrokenSymlink));
+        assert!("stale".parse::<HygieneKind>().is_err());
+        assert!(!HygieneKind::DanglingHardlink.is_deletable());
+    }
+
+    #[test]
+    fn test_verify_against_disk() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path();
+        fs::create_dir_all(root.join("empty/nested")).unwrap();
+        fs::create_dir_all(root.join("hidden")).unwrap();
+        fs::write(root.join("hidden/.keep"), "x").unwrap();
+        fs::write(root.join("zero"), "").unwrap();
+        fs::write(root.join("grew"), "data").unwrap();
+
+        let entry = |name: &str, issue| HygieneEntry {
+            path: root.join(name),
+            issue,
+            size: 0,
+        };
+        let mut report = HygieneReport {
+            entries: vec![
+                entry("empty", HygieneIssue::EmptyDir { nested: 1 }),
+                // The scan skipped the hidden file, but the disk has it.
+                entry("hidden", HygieneIssue::EmptyDir { nested: 0 }),
+                entry("zero", HygieneIssue::ZeroByte),
+                entry("grew", HygieneIssue::ZeroByte),
+                entry("missing", HygieneIssue::ZeroByte),
+            ],
+        };
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(root.join("nowhere"), root.join("dead")).unwrap();
+            std::os::unix::fs::symlink(root.join("zero"), root.join("alive")).unwrap();
+            let target = "nowhere".to_string();
+            report.entries.push(entry(
+                "dead",
+                HygieneIssue::BrokenSymlink {
+                    target: target.clone(),
+                },
+            ));
+            report
+                .entries
+                .push(entry("alive", HygieneIssue::BrokenSymlink { target }));
+        }
+
+        let dropped = report.verify();
+        let kept: Vec<_> = report
+            .entries
+            .iter()
+            .map(|e| e.path.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        #[cfg(unix)]
+        {
+            assert_eq!(kept, ["empty", "zero", "dead"]);
+            assert_eq!(dropped, 4);
+        }
+        #[cfg(not(unix))]
+        {
+            assert_eq!(kept, ["empty", "zero"]);
+            assert_eq!(dropped, 3);
+        }
+    }
+}
@@ -4,6 +4,15 @@
 //!
 //! - **Duplicate detection** - Find duplicate files using BLAKE3 hashing
 //! - **Age analysis** - Categorize files by age, find stale directories
+//! - **Cleanup candidates** - Regenerable build artifacts and caches
+//! - **Ownership** - Bytes and file counts per user and group
+//! - **File types** - Bytes per category and extension, by name and magic bytes
+//! - **Queries** - Expressions over node fields for finding and filtering
+//! - **Largest entries** - Globally largest files and leaf directories
+//! - **Hygiene** - Empty directories, zero-byte files, broken symlinks and stray hard links
+//! - **Timeline** - Histogram of files by modification week or month
+//! - **Retention policies** - Declarative rules selecting files to delete
+//! - **Profiles** - Layered `.gravityfile.toml` settings for scans and analyses
 //!
 //! # Duplicate Detection
 //!
@@ -51,14 +60,41 @@
 //! ```
 
 pub mod age;
+mod cleanup;
 mod duplicates;
+mod filetypes;
+mod hygiene;
+mod ownership;
+mod policy;
+mod profile;
+mod query;
+mod timeline;
+mod top;
+mod units;
 
 pub use age::{
-    AgeAnalyzer, AgeBucket, AgeBucketStats, AgeConfig, AgeReport, StaleDirectory, format_age,
+    AgeAnalyzer, AgeBucket, AgeBucketStats, AgeConfig, AgeReport, StaleDirectory, TimeBasis,
+    format_age,
+};
+pub use cleanup::{
+    CleanupCandidate, CleanupConfig, CleanupFinder, CleanupReport, CleanupRule, CleanupRuleSummary,
 };
 pub use duplicates::{
     DuplicateConfig, DuplicateFinder, DuplicateGroup, DuplicateReport, HashProgress,
 };
+pub use filetypes::{
+    CategoryStats, ExtensionStats, FileCategory, FileTypeAnalyzer, FileTypeReport, TypeBreakdown,
+};
+pub use hygiene::{HygieneAnalyzer, HygieneEntry, HygieneIssue, HygieneKind, HygieneReport};
+pub use ownership::{NameResolver, OwnerDirectory, OwnerUsage, OwnershipAnalyzer, OwnershipReport};
+pub use policy::{
+    PolicyError, PolicyMatch, PolicyReport, PolicyRuleSummary, RetentionPolicy, RetentionRule,
+};
+pub use profile::{PROFILE_FILE_NAME, Profile, ProfileError};
+pub use query::{Query, QueryError};
+pub use timeline::{Timeline, TimelineBin, TimelinePeriod};
+pub use top::{TopEntry, TopFinder, TopKind};
+pub use units::{format_duration_spec, parse_duration, parse_size};
 
 // Re-export core types
 pub use gravityfile_core::{ContentHash, FileNode, FileTree};
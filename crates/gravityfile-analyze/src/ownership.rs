@@ -0,0 +1,426 @@
+//! Per-owner and per-group usage accounting.
+//!
+//! Answers "who is filling this disk": bytes and file counts per user and
+//! per group, plus the directories holding most of each user's files.
+//! Numeric IDs are resolved to names through the system user database.
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use gravityfile_core::{FileNode, FileTree, NodeKind};
+
+/// Default number of directories reported per owner.
+const DEFAULT_MAX_DIRECTORIES: usize = 5;
+
+/// Maps numeric user and group IDs to names.
+#[derive(Debug, Clone, Default)]
+pub struct NameResolver {
+    users: HashMap<u32, String>,
+    groups: HashMap<u32, String>,
+    /// Fall back to the system user database for IDs not in the tables.
+    system: bool,
+}
+
+impl NameResolver {
+    /// Resolve through the system user database (see [`gravityfile_core::user_name`]).
+    pub fn system() -> Self {
+        Self {
+            system: true,
+            ..Self::default()
+        }
+    }
+
+    /// Build a resolver from the contents of passwd and group files.
+    ///
+    /// Both use `name:password:id:...` lines; comments and malformed lines
+    /// are skipped, and the first entry for an ID wins. Only these tables
+    /// are consulted.
+    pub fn parse(passwd: &str, group: &str) -> Self {
+        Self {
+            users: parse_id_file(passwd),
+            groups: parse_id_file(group),
+            system: false,
+        }
+    }
+
+    /// Name of user `uid`, or the number itself when unknown.
+    pub fn user(&self, uid: u32) -> String {
+        self.users
+            .get(&uid)
+            .cloned()
+            .or_else(|| {
+                self.system
+                    .then(|| gravityfile_core::user_name(uid))
+                    .flatten()
+            })
+            .unwrap_or_else(|| uid.to_string())
+    }
+
+    /// ID of the user called `name`.
+    pub fn uid(&self, name: &str) -> Option<u32> {
+        find_id(&self.users, name).or_else(|| {
+            self.system
+                .then(|| gravityfile_core::user_id(name))
+                .flatten()
+        })
+    }
+
+    /// ID of the group called `name`.
+    pub fn gid(&self, name: &str) -> Option<u32> {
+        find_id(&self.groups, name).or_else(|| {
+            self.system
+                .then(|| gravityfile_core::group_id(name))
+                .flatten()
+        })
+    }
+
+    /// Name of group `gid`, or the number itself when unknown.
+    pub fn group(&self, gid: u32) -> String {
+        self.groups
+            .get(&gid)
+            .cloned()
+            .or_else(|| {
+                self.system
+                    .then(|| gravityfile_core::group_name(gid))
+                    .flatten()
+            })
+            .unwrap_or_else(|| gid.to_string())
+    }
+}
+
+fn find_id(names: &HashMap<u32, String>, name: &str) -> Option<u32> {
+    names
+        .iter()
+        .find(|(_, n)| n.as_str() == name)
+        .map(|(&id, _)| id)
+}
+
+fn parse_id_file(content: &str) -> HashMap<u32, String> {
+    let mut names = HashMap::new();
+    for line in content.lines() {
+        if line.starts_with('#') {
+            continue;
+        }
+        let mut fields = line.split(':');
+        if let (Some(name), Some(_), Some(id)) = (fields.next(), fields.next(), fields.next())
+            && let Ok(id) = id.parse()
+            && !name.is_empty()
+        {
+            names.entry(id).or_insert_with(|| name.to_string());
+        }
+    }
+    names
+}
+
+/// A directory holding files of one owner.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct OwnerDirectory {
+    /// Bytes of the owner's files directly in this directory.
+    pub size: u64,
+    /// Path to the directory.
+    pub path: PathBuf,
+    /// Number of the owner's files directly in this directory.
+    pub file_count: u64,
+}
+
+/// Usage of one user or group.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OwnerUsage {
+    /// Numeric user or group ID.
+    pub id: u32,
+    /// Resolved name (the ID as text when unknown).
+    pub name: String,
+    /// Total bytes of files owned.
+    pub total_size: u64,
+    /// Number of files owned.
+    pub file_count: u64,
+    /// Directories directly holding the most of this owner's bytes, largest first.
+    pub largest_directories: Vec<OwnerDirectory>,
+}
+
+/// Results from ownership analysis.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OwnershipReport {
+    /// Usage per user, largest first.
+    pub users: Vec<OwnerUsage>,
+    /// Usage per group, largest first.
+    pub groups: Vec<OwnerUsage>,
+    /// Total bytes of files with ownership information.
+    pub total_size: u64,
+    /// Number of files with ownership information.
+    pub total_files: u64,
+    /// Files scanned without ownership information (non-Unix scans).
+    pub unknown_files: u64,
+}
+
+impl OwnershipReport {
+    /// Share of the total bytes owned by `usage`, between 0 and 1.
+    pub fn share(&self, usage: &OwnerUsage) -> f64 {
+        if self.total_size == 0 {
+            0.0
+        } else {
+            usage.total_size as f64 / self.total_size as f64
+        }
+    }
+}
+
+/// Running totals for one owner while walking the tree.
+#[derive(Default)]
+struct Accumulator {
+    total_size: u64,
+    file_count: u64,
+    /// Min-heap bounded to `max_directories` entries.
+    directories: BinaryHeap<Reverse<OwnerDirectory>>,
+}
+
+impl Accumulator {
+    fn push_directory(&mut self, directory: OwnerDirectory, max: usize) {
+        if max == 0 {
+            return;
+        }
+        if self.directories.len() < max {
+            self.directories.push(Reverse(directory));
+        } else if let Some(Reverse(smallest)) = self.directories.peek()
+            && directory.size > smallest.size
+        {
+            self.directories.pop();
+            self.directories.push(Reverse(directory));
+        }
+    }
+
+    fn into_usage(self, id: u32, name: String) -> OwnerUsage {
+        let mut largest_directories: Vec<OwnerDirectory> =
+            self.directories.into_iter().map(|Reverse(d)| d).collect();
+        largest_directories.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.path.cmp(&b.path)));
+        OwnerUsage {
+            id,
+            name,
+            total_size: self.total_size,
+            file_count: self.file_count,
+            largest_directories,
+        }
+    }
+}
+
+/// Ownership analyzer.
+pub struct OwnershipAnalyzer {
+    resolver: NameResolver,
+    max_directories: usize,
+}
+
+impl Default for OwnershipAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OwnershipAnalyzer {
+    /// Create an analyzer resolving names through the system user database.
+    pub fn new() -> Self {
+        Self::with_resolver(NameResolver::system())
+    }
+
+    /// Create an analyzer with a custom name resolver.
+    pub fn with_resolver(resolver: NameResolver) -> Self {
+        Self {
+            resolver,
+            max_directories: DEFAULT_MAX_DIRECTORIES,
+        }
+    }
+
+    /// Set how many directories are reported per owner.
+    pub fn max_directories(mut self, max: usize) -> Self {
+        self.max_directories = max;
+        self
+    }
+
+    /// Analyze a file tree.
+    pub fn analyze(&self, tree: &FileTree) -> OwnershipReport {
+        let mut users: HashMap<u32, Accumulator> = HashMap::new();
+        let mut groups: HashMap<u32, Accumulator> = HashMap::new();
+        let mut report = OwnershipReport::default();
+
+        self.dfs(
+            &tree.root,
+            &tree.root_path,
+            &mut users,
+            &mut groups,
+            &mut report,
+        );
+
+        report.users = Self::finish(users, |id| self.resolver.user(id));
+        report.groups = Self::finish(groups, |id| self.resolver.group(id));
+        report
+    }
+
+    fn finish(
+        accumulators: HashMap<u32, Accumulator>,
+        name: impl Fn(u32) -> String,
+    ) -> Vec<OwnerUsage> {
+        let mut usage: Vec<OwnerUsage> = accumulators
+            .into_iter()
+            .map(|(id, acc)| acc.into_usage(id, name(id)))
+            .collect();
+        usage.sort_by(|a, b| b.total_size.cmp(&a.total_size).then(a.id.cmp(&b.id)));
+        usage
+    }
+
+    fn dfs(
+        &self,
+        node: &FileNode,
+        path: &Path,
+        users: &mut HashMap<u32, Accumulator>,
+        groups: &mut HashMap<u32, Accumulator>,
+        report: &mut OwnershipReport,
+    ) {
+        if !node.is_dir() {
+            return;
+        }
+
+        // (size, count) of files directly in this directory, per owner.
+        let mut direct_users: HashMap<u32, (u64, u64)> = HashMap::new();
+        let mut direct_groups: HashMap<u32, (u64, u64)> = HashMap::new();
+
+        for child in &node.children {
+            match &child.kind {
+                NodeKind::File { .. } => {
+                    let Some(owner) = child.owner else {
+                        report.unknown_files += 1;
+                        continue;
+                    };
+                    report.total_size += child.size;
+                    report.total_files += 1;
+
+                    for (acc, direct, id) in [
+                        (&mut *users, &mut direct_users, owner.uid),
+                        (&mut *groups, &mut direct_groups, owner.gid),
+                    ] {
+                        let acc = acc.entry(id).or_default();
+                        acc.total_size += child.size;
+                        acc.file_count += 1;
+                        let entry = direct.entry(id).or_default();
+                        entry.0 += child.size;
+                        entry.1 += 1;
+                    }
+                }
+                NodeKind::Directory { .. } => {
+                    self.dfs(child, &path.join(&*child.name), users, groups, report);
+                }
+                _ => {}
+            }
+        }
+
+        for (acc, direct) in [(users, direct_users), (groups, direct_groups)] {
+            for (id, (size, file_count)) in direct {
+                acc.entry(id).or_default().push_directory(
+                    OwnerDirectory {
+                        size,
+                        path: path.to_path_buf(),
+                        file_count,
+                    },
+                    self.max_directories,
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gravityfile_core::{NodeId, Ownership, ScanConfig, Timestamps, TreeStats};
+    use std::time::{Duration, SystemTime};
+
+    const PASSWD: &str = "# users\nroot:x:0:0:root:/root:/bin/sh\nalice:x:1000:1000::/home/alice:/bin/sh\nbob:x:1001:100::/home/bob:/bin/sh\n";
+    const GROUP: &str = "root:x:0:\nusers:x:100:bob\nalice:x:1000:\n";
+
+    fn file(id: u64, name: &str, size: u64, uid: u32, gid: u32) -> FileNode {
+        let mut node = FileNode::new_file(
+            NodeId::new(id),
+            name,
+            size,
+            1,
+            Timestamps::with_modified(SystemTime::now()),
+            false,
+        );
+        node.owner = Some(Ownership::new(uid, gid, 0o100644));
+        node
+    }
+
+    fn dir(id: u64, name: &str, children: Vec<FileNode>) -> FileNode {
+        let mut node = FileNode::new_directory(
+            NodeId::new(id),
+            name,
+            Timestamps::with_modified(SystemTime::now()),
+        );
+        node.size = children.iter().map(|c| c.size).sum();
+        node.children = children;
+        node
+    }
+
+    #[test]
+    fn test_name_resolver() {
+        let resolver = NameResolver::parse(PASSWD, GROUP);
+        assert_eq!(resolver.user(1000), "alice");
+        assert_eq!(resolver.user(4242), "4242");
+        assert_eq!(resolver.group(100), "users");
+        assert_eq!(resolver.uid("bob"), Some(1001));
+        assert_eq!(resolver.uid("carol"), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_system_resolver() {
+        let resolver = NameResolver::system();
+        assert_eq!(resolver.user(0), "root");
+        assert_eq!(resolver.uid("root"), Some(0));
+        assert_eq!(resolver.user(4_000_000_000), "4000000000");
+    }
+
+    #[test]
+    fn test_usage_per_owner() {
+        let root = dir(
+            0,
+            "shared",
+            vec![
+                dir(
+                    1,
+                    "data",
+                    vec![file(2, "a", 500, 1000, 1000), file(3, "b", 300, 1001, 100)],
+                ),
+                file(4, "c", 100, 1000, 1000),
+                file(5, "d", 50, 1001, 100),
+            ],
+        );
+        let mut tree = FileTree::new(
+            root,
+            PathBuf::from("/shared"),
+            ScanConfig::new("/shared"),
+            TreeStats::new(),
+            Duration::ZERO,
+            Vec::new(),
+        );
+        tree.root.finalize();
+
+        let report =
+            OwnershipAnalyzer::with_resolver(NameResolver::parse(PASSWD, GROUP)).analyze(&tree);
+        assert_eq!(report.total_size, 950);
+        assert_eq!(report.total_files, 4);
+
+        let alice = &report.users[0];
+        assert_eq!(alice.name, "alice");
+        assert_eq!(alice.total_size, 600);
+        assert_eq!(alice.file_count, 2);
+        assert_eq!(
+            alice.largest_directories[0].path,
+            PathBuf::from("/shared/data")
+        );
+        assert_eq!(alice.largest_directories[1].size, 100);
+
+        let users_group = report.groups.iter().find(|g| g.name == "users").unwrap();
+        assert_eq!(users_group.total_size, 350);
+    }
+}
@@ -0,0 +1,536 @@
+//! Declarative retention policies.
+//!
+//! A policy file lists rules; each rule selects files by glob within a
+//! directory and marks those older or larger than a threshold for deletion,
+//! optionally keeping the newest few per directory:
+//!
+//! ```toml
+//! [[rule]]
+//! name = "app-logs"
+//! path = "var/app"          # relative to the scan root, or absolute
+//! include = ["*.log", "*.log.*"]
+//! exclude = ["audit/**"]
+//! older_than = "30d"
+//! keep_newest = 5
+//! ```
+//!
+//! Globs match the path relative to the rule's directory. A file selected by
+//! several rules is attributed to the first. `keep_newest` counts only the
+//! files a rule would otherwise select: those meeting its age and size limits
+//! and not already claimed by an earlier rule.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use gravityfile_core::{FileNode, FileTree, NodeKind};
+
+/// Errors from loading a policy.
+#[derive(Debug, Error)]
+pub enum PolicyError {
+    /// The policy file could not be read.
+    #[error("Failed to read {path}: {source}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    /// The policy file is not valid TOML or has invalid values.
+    #[error("Invalid policy {path}: {message}")]
+    Parse { path: PathBuf, message: String },
+
+    /// A rule is incomplete or has an invalid glob.
+    #[error("Invalid rule '{rule}': {message}")]
+    Rule { rule: String, message: String },
+}
+
+/// One retention rule.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetentionRule {
+    /// Name reported for files the rule selects.
+    pub name: String,
+    /// Directory the rule applies to; relative paths are resolved against
+    /// the scan root. Defaults to the scan root.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathBuf>,
+    /// Globs a file must match; empty matches every file.
+    #[serde(default)]
+    pub include: Vec<String>,
+    /// Globs that protect a file from the rule.
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    /// Only select files last modified longer ago than this.
+    #[serde(default, with = "crate::units::duration_spec")]
+    pub older_than: Duration,
+    /// Only select files at least this large.
+    #[serde(default, with = "crate::units::size_spec")]
+    pub larger_than: u64,
+    /// Never select the newest this many of the files the rule would
+    /// otherwise select in each directory.
+    #[serde(default)]
+    pub keep_newest: usize,
+}
+
+/// A set of retention rules, read from a policy file.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RetentionPolicy {
+    /// Rules in order of precedence.
+    #[serde(default, rename = "rule")]
+    pub rules: Vec<RetentionRule>,
+}
+
+impl RetentionPolicy {
+    /// Load and validate a policy file.
+    pub fn load(path: &Path) -> Result<Self, PolicyError> {
+        let content = std::fs::read_to_string(path).map_err(|e| PolicyError::Io {
+            path: path.to_path_buf(),
+            source: e,
+        })?;
+        Self::parse(&content).map_err(|e| match e {
+            PolicyError::Parse { message, .. } => PolicyError::Parse {
+                path: path.to_path_buf(),
+                message,
+            },
+            other => other,
+        })
+    }
+
+    /// Parse and validate policy TOML.
+    pub fn parse(content: &str) -> Result<Self, PolicyError> {
+        let policy: Self = toml::from_str(content).map_err(|e| PolicyError::Parse {
+            path: PathBuf::new(),
+            message: e.message().to_string(),
+        })?;
+        for rule in &policy.rules {
+            CompiledRule::compile(rule, Path::new("/"))?;
+        }
+        Ok(policy)
+    }
+
+    /// Files the policy selects in `tree`, with ages measured from now.
+    pub fn evaluate(&self, tree: &FileTree) -> Result<PolicyReport, PolicyError> {
+        self.evaluate_at(tree, SystemTime::now())
+    }
+
+    /// Files the policy selects in `tree`, with ages measured from `now`.
+    pub fn evaluate_at(
+        &self,
+        tree: &FileTree,
+        now: SystemTime,
+    ) -> Result<PolicyReport, PolicyError> {
+        let rules = self
+            .rules
+            .iter()
+            .map(|rule| CompiledRule::compile(rule, &tree.root_path))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut evaluation = Evaluation {
+            rules,
+            now,
+            claimed: HashSet::new(),
+            report: PolicyReport::default(),
+        };
+        evaluation.visit(&tree.root, &tree.root_path);
+
+        let mut report = evaluation.report;
+        report.matches.sort_by(|a, b| a.path.cmp(&b.path));
+        report.total_size = report.matches.iter().map(|m| m.size).sum();
+        Ok(report)
+    }
+}
+
+/// A file selected for deletion.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolicyMatch {
+    /// Path to the file.
+    pub path: PathBuf,
+    /// Name of the rule that selected it.
+    pub rule: String,
+    /// File size.
+    pub size: u64,
+    /// Last modification time.
+    pub modified: SystemTime,
+}
+
+impl PolicyMatch {
+    /// Whether the file on disk is still the one that was evaluated: a
+    /// regular file with the same size and modification time. The size may
+    /// be the apparent or the allocated size, depending on how the tree was
+    /// scanned.
+    pub fn still_applies(&self) -> bool {
+        std::fs::symlink_metadata(&self.path).is_ok_and(|metadata| {
+            metadata.is_file()
+                && (metadata.len() == self.size || allocated_size(&metadata) == self.size)
+                && metadata
+                    .modified()
+                    .is_ok_and(|modified| modified == self.modified)
+        })
+    }
+}
+
+/// Bytes allocated on disk for a file, as the scanner counts them.
+#[cfg(unix)]
+fn allocated_size(metadata: &std::fs::Metadata) -> u64 {
+    use std::os::unix::fs::MetadataExt;
+    metadata.blocks() * 512
+}
+
+#[cfg(not(unix))]
+fn allocated_size(metadata: &std::fs::Metadata) -> u64 {
+    metadata.len()
+}
+
+/// Files and bytes selected by one rule.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolicyRuleSummary {
+    /// Rule name.
+    pub rule: String,
+    /// Number of selected files.
+    pub count: usize,
+    /// Total bytes of selected files.
+    pub size: u64,
+}
+
+/// Result of evaluating a policy.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PolicyReport {
+    /// Selected files, sorted by path.
+    pub matches: Vec<PolicyMatch>,
+    /// Total bytes of selected files.
+    pub total_size: u64,
+    /// Files that met a rule's conditions but were kept by `keep_newest`.
+    pub kept: u64,
+}
+
+impl PolicyReport {
+    /// Totals per rule, in the order rules first selected a file.
+    pub fn by_rule(&self) -> Vec<PolicyRuleSummary> {
+        let mut summaries: Vec<PolicyRuleSummary> = Vec::new();
+        for m in &self.matches {
+            match summaries.iter_mut().find(|s| s.rule == m.rule) {
+                Some(summary) => {
+                    summary.count += 1;
+                    summary.size += m.size;
+                }
+                None => summaries.push(PolicyRuleSummary {
+                    rule: m.rule.clone(),
+                    count: 1,
+                    size: m.size,
+                }),
+            }
+        }
+        summaries
+    }
+
+    /// Drop matches whose file changed or disappeared since the scan,
+    /// returning them.
+    pub fn verify(&mut self) -> Vec<PolicyMatch> {
+        let (current, changed) = std::mem::take(&mut self.matches)
+            .into_iter()
+            .partition(PolicyMatch::still_applies);
+        self.matches = current;
+        self.total_size = self.matches.iter().map(|m| m.size).sum();
+        changed
+    }
+}
+
+/// A rule with its scope resolved and globs compiled.
+struct CompiledRule<'a> {
+    rule: &'a RetentionRule,
+    scope: PathBuf,
+    include: Option<GlobSet>,
+    exclude: Option<GlobSet>,
+}
+
+impl<'a> CompiledRule<'a> {
+    fn compile(rule: &'a RetentionRule, root: &Path) -> Result<Self, PolicyError> {
+        let invalid = |message: String| PolicyError::Rule {
+            rule: rule.name.clone(),
+            message,
+        };
+        if rule.older_than.is_zero() && rule.larger_than == 0 && rule.keep_newest == 0 {
+            return Err(invalid(
+                "needs at least one of older_than, larger_than or keep_newest".to_string(),
+            ));
+        }
+        let scope = match &rule.path {
+            Some(path) => root.join(path),
+            None => root.to_path_buf(),
+        };
+        Ok(Self {
+            rule,
+            scope,
+            include: compile_set(&rule.include).map_err(invalid)?,
+            exclude: compile_set(&rule.exclude).map_err(invalid)?,
+        })
+    }
+
+    /// Whether the rule's globs select `path`.
+    fn selects(&self, path: &Path) -> bool {
+        let Ok(relative) = path.strip_prefix(&self.scope) else {
+            return false;
+        };
+        self.include
+            .as_ref()
+            .is_none_or(|set| set.is_match(relative))
+            && !self
+                .exclude
+                .as_ref()
+                .is_some_and(|set| set.is_match(relative))
+    }
+}
+
+fn compile_set(patterns: &[String]) -> Result<Option<GlobSet>, String> {
+    if patterns.is_empty() {
+        return Ok(None);
+    }
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        builder.add(Glob::new(pattern).map_err(|e| e.to_string())?);
+    }
+    builder.build().map(Some).map_err(|e| e.to_string())
+}
+
+/// State of one policy evaluation.
+struct Evaluation<'a> {
+    rules: Vec<CompiledRule<'a>>,
+    now: SystemTime,
+    /// Paths already selected by an earlier rule.
+    claimed: HashSet<PathBuf>,
+    report: PolicyReport,
+}
+
+impl Evaluation<'_> {
+    fn visit(&mut self, node: &FileNode, path: &Path) {
+        let files: Vec<(PathBuf, &FileNode)> = node
+            .children
+            .iter()
+            .filter(|c| matches!(c.kind, NodeKind::File { .. }))
+            .map(|c| (path.join(&*c.name), c))
+            .collect();
+
+        if !files.is_empty() {
+            for i in 0..self.rules.len() {
+                self.apply_rule(i, &files);
+            }
+        }
+
+        for child in node.children.iter().filter(|c| c.is_dir()) {
+            self.visit(child, &path.join(&*child.name));
+        }
+    }
+
+    /// Apply rule `index` to the files of one directory.
+    fn apply_rule(&mut self, index: usize, files: &[(PathBuf, &FileNode)]) {
+        let rule = &self.rules[index];
+        let mut eligible: Vec<&(PathBuf, &FileNode)> = files
+            .iter()
+            .filter(|(path, node)| {
+                rule.selects(path)
+                    && !self.claimed.contains(path)
+                    && node.size >= rule.rule.larger_than
+                    && (rule.rule.older_than.is_zero()
+                        || self
+                            .now
+                            .duration_since(node.timestamps.modified)
+                            .unwrap_or_default()
+                            > rule.rule.older_than)
+            })
+            .collect();
+        // Newest first, so the protected files come first.
+        eligible.sort_by_key(|(_, node)| std::cmp::Reverse(node.timestamps.modified));
+
+        let keep = rule.rule.keep_newest.min(eligible.len());
+        self.report.kept += keep as u64;
+        for (path, node) in eligible.into_iter().skip(keep) {
+            self.claimed.insert(path.clone());
+            self.report.matches.push(PolicyMatch {
+                path: path.clone(),
+                rule: rule.rule.name.clone(),
+                size: node.size,
+                modified: node.timestamps.modified,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gravityfile_core::{NodeId, ScanConfig, Timestamps, TreeStats};
+
+    const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+    fn file(name: &str, size: u64, days_old: u64, now: SystemTime) -> FileNode {
+        FileNode::new_file(
+            NodeId::new(0),
+            name,
+            size,
+            1,
+            Timestamps::with_modified(now - DAY * days_old as u32),
+            false,
+        )
+    }
+
+    fn dir(name: &str, children: Vec<FileNode>) -> FileNode {
+        let mut node = FileNode::new_directory(
+            NodeId::new(0),
+            name,
+            Timestamps::with_modified(SystemTime::now()),
+        );
+        node.size = children.iter().map(|c| c.size).sum();
+        node.children = children;
+        node
+    }
+
+    fn tree(root: FileNode) -> FileTree {
+        FileTree::new(
+            root,
+            PathBuf::from("/srv"),
+            ScanConfig::new("/srv"),
+            TreeStats::new(),
+            Duration::ZERO,
+            Vec::new(),
+        )
+    }
+
+    #[test]
+    fn test_age_glob_and_keep_newest() {
+        let now = SystemTime::now();
+        let logs: Vec<FileNode> = (0..8)
+            .map(|i| file(&format!("app.log.{}", i), 100, 40 + i, now))
+            .chain([
+                file("app.log", 100, 1, now),
+                file("notes.txt", 100, 90, now),
+            ])
+            .collect();
+        let tree = tree(dir("srv", vec![dir("app", logs)]));
+
+        let policy = RetentionPolicy::parse(
+            "[[rule]]\nname = \"logs\"\npath = \"app\"\ninclude = [\"*.log*\"]\nolder_than = \"30d\"\nkeep_newest = 5\n",
+        )
+        .unwrap();
+        let report = policy.evaluate_at(&tree, now).unwrap();
+
+        // app.log is too recent to count; of the 8 old rotations the newest
+        // 5 are kept.
+        let names: Vec<_> = report
+            .matches
+            .iter()
+            .map(|m| m.path.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(names, vec!["app.log.5", "app.log.6", "app.log.7"]);
+        assert_eq!(report.kept, 5);
+        assert_eq!(report.total_size, 300);
+    }
+
+    #[test]
+    fn test_keep_newest_skips_claimed_files() {
+        let now = SystemTime::now();
+        let logs: Vec<FileNode> = (0..4)
+            .map(|i| {
+                let size = if i == 0 { 5000 } else { 100 };
+                file(&format!("x.log.{}", i), size, 40 + i, now)
+            })
+            .collect();
+        let tree = tree(dir("srv", logs));
+
+        let policy = RetentionPolicy::parse(
+            "[[rule]]\nname = \"large\"\nlarger_than = \"4K\"\n\n[[rule]]\nname = \"logs\"\ninclude = [\"*.log.*\"]\nolder_than = \"30d\"\nkeep_newest = 2\n",
+        )
+        .unwrap();
+        let report = policy.evaluate_at(&tree, now).unwrap();
+
+        // x.log.0 belongs to "large", so "logs" keeps x.log.1 and x.log.2.
+        let rules: Vec<_> = report
+            .matches
+            .iter()
+            .map(|m| (m.path.clone(), m.rule.as_str()))
+            .collect();
+        assert_eq!(
+            rules,
+            vec![
+                (PathBuf::from("/srv/x.log.0"), "large"),
+                (PathBuf::from("/srv/x.log.3"), "logs"),
+            ]
+        );
+        assert_eq!(report.kept, 2);
+    }
+
+    #[test]
+    fn test_verify_drops_changed_files() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let entry = |name: &str| {
+            let path = tmp.path().join(name);
+            std::fs::write(&path, b"old data").unwrap();
+            let metadata = std::fs::metadata(&path).unwrap();
+            PolicyMatch {
+                path,
+                rule: "logs".to_string(),
+                size: metadata.len(),
+                modified: metadata.modified().unwrap(),
+            }
+        };
+        let mut report = PolicyReport {
+            matches: vec![entry("same.log"), entry("grown.log"), entry("gone.log")],
+            total_size: 24,
+            kept: 0,
+        };
+        std::fs::write(tmp.path().join("grown.log"), b"old data and more").unwrap();
+        std::fs::remove_file(tmp.path().join("gone.log")).unwrap();
+
+        let changed = report.verify();
+        assert_eq!(changed.len(), 2);
+        assert_eq!(report.matches.len(), 1);
+        assert!(report.matches[0].path.ends_with("same.log"));
+        assert_eq!(report.total_size, 8);
+    }
+
+    #[test]
+    fn test_first_rule_wins_and_exclude() {
+        let now = SystemTime::now();
+        let tree = tree(dir(
+            "srv",
+            vec![
+                file("big.iso", 5000, 1, now),
+                file("old.iso", 5000, 400, now),
+                dir("keep", vec![file("huge.bin", 9000, 1, now)]),
+            ],
+        ));
+
+        let policy = RetentionPolicy::parse(
+            "[[rule]]\nname = \"stale\"\nolder_than = \"1y\"\n\n[[rule]]\nname = \"large\"\nlarger_than = \"4K\"\nexclude = [\"keep/**\"]\n",
+        )
+        .unwrap();
+        let report = policy.evaluate_at(&tree, now).unwrap();
+        let rules: Vec<_> = report
+            .matches
+            .iter()
+            .map(|m| (m.path.clone(), m.rule.as_str()))
+            .collect();
+        assert_eq!(
+            rules,
+            vec![
+                (PathBuf::from("/srv/big.iso"), "large"),
+                (PathBuf::from("/srv/old.iso"), "stale"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rule_without_condition_is_rejected() {
+        assert!(matches!(
+            RetentionPolicy::parse("[[rule]]\nname = \"all\"\ninclude = [\"*\"]\n"),
+            Err(PolicyError::Rule { .. })
+        ));
+        assert!(matches!(
+            RetentionPolicy::parse("[[rule]]\nname = \"bad\"\nolder_than = \"soon\"\n"),
+            Err(PolicyError::Parse { .. })
+        ));
+    }
+}
@@ -0,0 +1,470 @@
+//! Scan profiles loaded from `.gravityfile.toml` files.
+//!
+//! A profile bundles a [`ScanConfig`], [`DuplicateConfig`], [`AgeConfig`] and
+//! [`CleanupConfig`].
+//! Profile files are layered, lowest precedence first:
+//!
+//! 1. the global file (`~/.config/gravityfile/profile.toml`),
+//! 2. `.gravityfile.toml` in each ancestor of the scan root, outermost first,
+//! 3. `.gravityfile.toml` in the scan root itself.
+//!
+//! Tables merge key by key, so a project file only needs the settings it
+//! changes. A file may also define named variants under `[profiles.<name>]`,
+//! selected with `--profile <name>` and applied on top of everything else:
+//!
+//! ```toml
+//! [scan]
+//! ignore_patterns = ["*.tmp"]
+//! ignore_files = "report"
+//!
+//! [duplicates]
+//! min_size = "64K"
+//!
+//! [age]
+//! stale_threshold = "6m"
+//! time_basis = "atime"
+//! buckets = [{ name = "Fresh", max_age = "1w" }, { name = "Recent", max_age = "3mo" }]
+//!
+//! [cleanup]
+//! disable = ["cache"]
+//!
+//! [profiles.deep.scan]
+//! follow_symlinks = true
+//! cross_filesystems = true
+//! ```
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use toml::{Table, Value};
+
+use gravityfile_core::ScanConfig;
+
+use crate::age::AgeConfig;
+use crate::cleanup::CleanupConfig;
+use crate::duplicates::DuplicateConfig;
+
+/// File name looked up in the scan root and its ancestors.
+pub const PROFILE_FILE_NAME: &str = ".gravityfile.toml";
+
+/// Sections a profile file may contain besides `[profiles]`.
+const SECTIONS: [&str; 4] = ["scan", "duplicates", "age", "cleanup"];
+
+/// Errors from loading or saving profiles.
+#[derive(Debug, Error)]
+pub enum ProfileError {
+    /// A profile file could not be read or written.
+    #[error("Failed to access {path}: {source}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    /// A profile file is not valid TOML or has invalid values.
+    #[error("Invalid profile {path}: {message}")]
+    Parse { path: PathBuf, message: String },
+
+    /// `--profile` named a profile no file defines.
+    #[error("Unknown profile '{name}' (define it under [profiles.{name}])")]
+    UnknownProfile { name: String },
+}
+
+/// Resolved scan, duplicate, age and cleanup settings.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Profile {
+    /// Scanner settings; `root` is ignored and replaced by the scan path.
+    pub scan: ScanConfig,
+    /// Duplicate detection settings.
+    pub duplicates: DuplicateConfig,
+    /// Age analysis settings.
+    pub age: AgeConfig,
+    /// Cleanup candidate rules.
+    pub cleanup: CleanupConfig,
+    /// Files merged into this profile, lowest precedence first.
+    #[serde(skip)]
+    pub sources: Vec<PathBuf>,
+    /// Named variant applied on top, if any.
+    #[serde(skip)]
+    pub name: Option<String>,
+}
+
+impl Profile {
+    /// Path of the global profile file.
+    pub fn global_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|d| d.join("gravityfile").join("profile.toml"))
+    }
+
+    /// Existing profile files that apply to `root`, lowest precedence first.
+    pub fn discover(root: &Path) -> Vec<PathBuf> {
+        let mut files: Vec<PathBuf> = root
+            .ancestors()
+            .map(|dir| dir.join(PROFILE_FILE_NAME))
+            .filter(|file| file.is_file())
+            .collect();
+        files.reverse();
+        if let Some(global) = Self::global_path().filter(|p| p.is_file())
+            && !files.contains(&global)
+        {
+            files.insert(0, global);
+        }
+        files
+    }
+
+    /// Load the profile for scanning `root`.
+    ///
+    /// `selector` is either the name of a `[profiles.<name>]` variant or the
+    /// path of an extra profile file, which then takes precedence over the
+    /// discovered ones.
+    pub fn load(root: &Path, selector: Option<&str>) -> Result<Self, ProfileError> {
+        let mut files = Self::discover(root);
+        let mut name = None;
+        if let Some(selector) = selector {
+            let path = Path::new(selector);
+            if selector.ends_with(".toml") || path.components().count() > 1 {
+                if !path.is_file() {
+                    return Err(ProfileError::Io {
+                        path: path.to_path_buf(),
+                        source: std::io::ErrorKind::NotFound.into(),
+                    });
+                }
+                files.push(path.to_path_buf());
+            } else {
+                name = Some(selector.to_string());
+            }
+        }
+
+        let mut merged = Table::new();
+        for file in &files {
+            merge(&mut merged, read_table(file)?);
+        }
+
+        let mut profiles = merged.remove("profiles");
+        if let Some(name) = &name {
+            let overlay = profiles
+                .as_mut()
+                .and_then(|p| p.as_table_mut())
+                .and_then(|p| p.remove(name))
+                .and_then(|v| match v {
+                    Value::Table(t) => Some(t),
+                    _ => None,
+                })
+                .ok_or_else(|| ProfileError::UnknownProfile { name: name.clone() })?;
+            merge(&mut merged, overlay);
+        }
+
+        let mut profile: Profile =
+            Value::Table(merged)
+                .try_into()
+                .map_err(|e: toml::de::Error| ProfileError::Parse {
+                    path: files.last().cloned().unwrap_or_else(|| root.to_path_buf()),
+                    message: e.message().to_string(),
+                })?;
+        profile.sources = files;
+        profile.name = name;
+        Ok(profile)
+    }
+
+    /// The scan configuration for `root`, with ignore patterns compiled.
+    pub fn scan_config(&self, root: impl Into<PathBuf>) -> ScanConfig {
+        let mut config = self.scan.clone();
+        config.root = root.into();
+        config.compile_patterns();
+        config
+    }
+
+    /// Where edits to this profile are saved: the nearest file it was
+    /// loaded from, or the global file when none exists yet.
+    pub fn save_path(&self) -> Option<PathBuf> {
+        self.sources.last().cloned().or_else(Self::global_path)
+    }
+
+    /// Write the settings this profile changes to `path`.
+    ///
+    /// Only keys that differ from what the other source files (and, for a
+    /// variant, the base sections of `path` itself) already give are
+    /// written, so inherited settings are not frozen into the file. When a
+    /// `[profiles.<name>]` variant is active the changes go into that
+    /// variant instead of the base sections. Other content of an existing
+    /// file is kept.
+    pub fn save_to(&self, path: &Path) -> Result<(), ProfileError> {
+        let mut table = if path.is_file() {
+            read_table(path)?
+        } else {
+            Table::new()
+        };
+
+        let mut inherited = Table::new();
+        for file in self.sources.iter().filter(|f| f.as_path() != path) {
+            merge(&mut inherited, read_table(file)?);
+        }
+        inherited.remove("profiles");
+        if self.name.is_some() {
+            let mut base = table.clone();
+            base.remove("profiles");
+            merge(&mut inherited, base);
+        }
+        let baseline =
+            Profile::deserialize(Value::Table(inherited)).map_err(|e| ProfileError::Parse {
+                path: path.to_path_buf(),
+                message: e.message().to_string(),
+            })?;
+        let mut changes = changed_keys(self.sections(path)?, &baseline.sections(path)?);
+
+        let target = match &self.name {
+            Some(name) => {
+                let profiles = table
+                    .entry("profiles")
+                    .or_insert_with(|| Value::Table(Table::new()));
+                if !profiles.is_table() {
+                    *profiles = Value::Table(Table::new());
+                }
+                let variant = profiles
+                    .as_table_mut()
+                    .expect("checked above")
+                    .entry(name.as_str())
+                    .or_insert_with(|| Value::Table(Table::new()));
+                if !variant.is_table() {
+                    *variant = Value::Table(Table::new());
+                }
+                variant.as_table_mut().expect("checked above")
+            }
+            None => &mut table,
+        };
+        for section in SECTIONS {
+            target.remove(section);
+            if let Some(value) = changes.remove(section) {
+                target.insert(section.to_string(), value);
+            }
+        }
+
+        let content = toml::to_string_pretty(&table).map_err(|e| ProfileError::Parse {
+            path: path.to_path_buf(),
+            message: e.to_string(),
+        })?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| ProfileError::Io {
+                path: parent.to_path_buf(),
+                source: e,
+            })?;
+        }
+        std::fs::write(path, content).map_err(|e| ProfileError::Io {
+            path: path.to_path_buf(),
+            source: e,
+        })
+    }
+}
+
+impl Profile {
+    /// The `[scan]`, `[duplicates]`, `[age]` and `[cleanup]` sections as TOML.
+    fn sections(&self, path: &Path) -> Result<Table, ProfileError> {
+        let Value::Table(mut table) = Value::try_from(self).map_err(|e| ProfileError::Parse {
+            path: path.to_path_buf(),
+            message: e.to_string(),
+        })?
+        else {
+            unreachable!("a struct serializes to a table");
+        };
+        if let Some(Value::Table(scan)) = table.get_mut("scan") {
+            scan.remove("root");
+        }
+        table.retain(|section, _| SECTIONS.contains(&section));
+        Ok(table)
+    }
+}
+
+/// The keys of each section of `current` whose values differ from
+/// `baseline`; sections without changes are left out.
+fn changed_keys(current: Table, baseline: &Table) -> Table {
+    let mut changes = Table::new();
+    for (section, value) in current {
+        let Value::Table(keys) = value else {
+            continue;
+        };
+        let base = baseline.get(&section).and_then(Value::as_table);
+        let changed: Table = keys
+            .into_iter()
+            .filter(|(key, value)| base.and_then(|b| b.get(key)) != Some(value))
+            .collect();
+        if !changed.is_empty() {
+            changes.insert(section, Value::Table(changed));
+        }
+    }
+    changes
+}
+
+/// Read one profile file, checking that it deserializes on its own so errors
+/// point at the file that caused them.
+fn read_table(path: &Path) -> Result<Table, ProfileError> {
+    let content = std::fs::read_to_string(path).map_err(|e| ProfileError::Io {
+        path: path.to_path_buf(),
+        source: e,
+    })?;
+    let parse_error = |message: String| ProfileError::Parse {
+        path: path.to_path_buf(),
+        message,
+    };
+    let table: Table =
+        toml::from_str(&content).map_err(|e| parse_error(e.message().to_string()))?;
+
+    let mut check = table.clone();
+    let profiles = check.remove("profiles");
+    Profile::deserialize(Value::Table(check)).map_err(|e| parse_error(e.message().to_string()))?;
+    if let Some(Value::Table(profiles)) = profiles {
+        for (name, variant) in profiles {
+            Profile::deserialize(variant)
+                .map_err(|e| parse_error(format!("[profiles.{}]: {}", name, e.message())))?;
+        }
+    }
+    Ok(table)
+}
+
+/// Merge `overlay` into `base`, recursing into tables so only the keys
+/// present in `overlay` change.
+fn merge(base: &mut Table, overlay: Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(&key), value) {
+            (Some(Value::Table(base_table)), Value::Table(overlay_table)) => {
+                merge(base_table, overlay_table);
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_nearest_file_wins() {
+        let temp = TempDir::new().unwrap();
+        let project = temp.path().join("project");
+        fs::create_dir_all(&project).unwrap();
+        fs::write(
+            temp.path().join(PROFILE_FILE_NAME),
+            "[scan]\nfollow_symlinks = true\nthreads = 2\n\n[duplicates]\nmin_size = \"1MB\"\n",
+        )
+        .unwrap();
+        fs::write(
+            project.join(PROFILE_FILE_NAME),
+            "[scan]\nthreads = 8\n\n[age]\nstale_threshold = \"6m\"\ntime_basis = \"atime\"\n",
+        )
+        .unwrap();
+
+        let profile = Profile::load(&project, None).unwrap();
+        assert!(profile.scan.follow_symlinks);
+        assert_eq!(profile.scan.threads, 8);
+        assert_eq!(profile.duplicates.min_size, 1024 * 1024);
+        assert_eq!(
+            profile.age.stale_threshold,
+            Duration::from_secs(180 * 24 * 60 * 60)
+        );
+        assert_eq!(profile.age.time_basis, crate::TimeBasis::Accessed);
+        assert_eq!(profile.save_path(), Some(project.join(PROFILE_FILE_NAME)));
+
+        let config = profile.scan_config(&project);
+        assert_eq!(config.root, project);
+    }
+
+    #[test]
+    fn test_named_profile() {
+        let temp = TempDir::new().unwrap();
+        fs::write(
+            temp.path().join(PROFILE_FILE_NAME),
+            "[scan]\nmax_depth = 3\n\n[profiles.deep.scan]\nmax_depth = 50\n",
+        )
+        .unwrap();
+
+        let base = Profile::load(temp.path(), None).unwrap();
+        assert_eq!(base.scan.max_depth, Some(3));
+        let deep = Profile::load(temp.path(), Some("deep")).unwrap();
+        assert_eq!(deep.scan.max_depth, Some(50));
+        assert!(matches!(
+            Profile::load(temp.path(), Some("shallow")),
+            Err(ProfileError::UnknownProfile { .. })
+        ));
+    }
+
+    #[test]
+    fn test_invalid_file_names_path() {
+        let temp = TempDir::new().unwrap();
+        let file = temp.path().join(PROFILE_FILE_NAME);
+        fs::write(&file, "[duplicates]\nmin_size = \"huge\"\n").unwrap();
+
+        match Profile::load(temp.path(), None) {
+            Err(ProfileError::Parse { path, .. }) => assert_eq!(path, file),
+            other => panic!("expected parse error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_save_keeps_named_profiles() {
+        let temp = TempDir::new().unwrap();
+        let file = temp.path().join(PROFILE_FILE_NAME);
+        fs::write(&file, "[profiles.deep.scan]\nmax_depth = 50\n").unwrap();
+
+        let mut profile = Profile::load(temp.path(), None).unwrap();
+        profile.scan.apparent_size = true;
+        profile.save_to(&file).unwrap();
+
+        let reloaded = Profile::load(temp.path(), Some("deep")).unwrap();
+        assert!(reloaded.scan.apparent_size);
+        assert_eq!(reloaded.scan.max_depth, Some(50));
+        assert!(!fs::read_to_string(&file).unwrap().contains("root"));
+    }
+
+    #[test]
+    fn test_save_writes_only_changes() {
+        let temp = TempDir::new().unwrap();
+        let project = temp.path().join("project");
+        fs::create_dir_all(&project).unwrap();
+        fs::write(temp.path().join(PROFILE_FILE_NAME), "[scan]\nthreads = 2\n").unwrap();
+        let file = project.join(PROFILE_FILE_NAME);
+        fs::write(&file, "[scan]\nmax_depth = 3\n").unwrap();
+
+        let mut profile = Profile::load(&project, None).unwrap();
+        profile.scan.apparent_size = true;
+        profile.save_to(&file).unwrap();
+
+        let content = fs::read_to_string(&file).unwrap();
+        assert!(!content.contains("threads"), "{}", content);
+        assert!(!content.contains("[duplicates]"), "{}", content);
+        let reloaded = Profile::load(&project, None).unwrap();
+        assert!(reloaded.scan.apparent_size);
+        assert_eq!(reloaded.scan.max_depth, Some(3));
+        assert_eq!(reloaded.scan.threads, 2);
+    }
+
+    #[test]
+    fn test_save_with_variant_edits_the_variant() {
+        let temp = TempDir::new().unwrap();
+        let file = temp.path().join(PROFILE_FILE_NAME);
+        fs::write(
+            &file,
+            "[scan]\nmax_depth = 3\n\n[profiles.deep.scan]\nmax_depth = 50\nfollow_symlinks = true\n",
+        )
+        .unwrap();
+
+        let mut deep = Profile::load(temp.path(), Some("deep")).unwrap();
+        deep.scan.apparent_size = true;
+        deep.save_to(&deep.save_path().unwrap()).unwrap();
+
+        let base = Profile::load(temp.path(), None).unwrap();
+        assert_eq!(base.scan.max_depth, Some(3));
+        assert!(!base.scan.follow_symlinks);
+        assert!(!base.scan.apparent_size);
+
+        let deep = Profile::load(temp.path(), Some("deep")).unwrap();
+        assert_eq!(deep.scan.max_depth, Some(50));
+        assert!(deep.scan.follow_symlinks);
+        assert!(deep.scan.apparent_size);
+    }
+}
@@ -0,0 +1,1092 @@
+//! A small expression language for selecting nodes.
+//!
+//! ```text
+//! size > 1G and mtime < 6mo and ext in (iso, img) and not path ~ '/backup/'
+//! ```
+//!
+//! Tests compare a field with a value and are combined with `and`, `or`,
+//! `not` and parentheses; `field in (a, b)` is shorthand for
+//! `field = a or field = b`.
+//!
+//! | Field | Operators | Values |
+//! |-------|-----------|--------|
+//! | `name`, `path` | `=` `!=` glob, `~` `!~` regex | `'*.log'`, `'/backup/'` |
+//! | `ext` | `=` `!=` (case-insensitive), `~` `!~` | `iso` |
+//! | `size` | `<` `<=` `>` `>=` `=` `!=` | `1G`, `500K` |
+//! | `mtime`, `atime`, `created` | `<` `<=` `>` `>=` | `6mo`, `30d` (that long ago) or `2024-01-31` (date) |
+//! | `kind` | `=` `!=` | `file` `dir` `symlink` `broken` `exec` `mount` `other` |
+//! | `git` | `=` `!=` | `modified` `staged` `untracked` `ignored` `conflict` `clean` |
+//! | `owner`, `group` | `=` `!=` | name or numeric ID |
+//!
+//! Times compare as timestamps: a duration stands for that long before now,
+//! so `mtime < 6mo` selects files last modified more than six months ago and
+//! `mtime > 1w` those modified within the last week, just as
+//! `mtime < 2024-01-01` selects files last modified before 2024. Values
+//! containing spaces or operator characters can be quoted with `'` or `"`.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use globset::{Glob, GlobMatcher};
+use regex::Regex;
+use thiserror::Error;
+
+use gravityfile_core::{FileNode, FileTree, GitStatus, NodeKind, TreeStats};
+
+use crate::ownership::NameResolver;
+use crate::units::{days_from_civil, parse_duration, parse_size};
+
+const SECS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// A query that could not be parsed.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("{message} (at column {})", .position + 1)]
+pub struct QueryError {
+    /// Byte offset of the offending token.
+    pub position: usize,
+    /// What went wrong.
+    pub message: String,
+}
+
+impl QueryError {
+    fn new(position: usize, message: impl Into<String>) -> Self {
+        Self {
+            position,
+            message: message.into(),
+        }
+    }
+}
+
+/// A compiled query.
+#[derive(Debug, Clone)]
+pub struct Query {
+    source: String,
+    expr: Expr,
+    now: SystemTime,
+}
+
+impl Query {
+    /// Parse `source`. User and group names are resolved through the
+    /// system user database.
+    pub fn parse(source: &str) -> Result<Self, QueryError> {
+        let tokens = tokenize(source)?;
+        let mut parser = Parser {
+            tokens,
+            pos: 0,
+            resolver: None,
+        };
+        let expr = parser.expr()?;
+        match parser.peek() {
+            Tok::End => Ok(Self {
+                source: source.trim().to_string(),
+                expr,
+                now: SystemTime::now(),
+            }),
+            _ => Err(parser.error("Expected 'and', 'or' or end of query")),
+        }
+    }
+
+    /// Measure ages from `now` instead of the time the query was parsed.
+    pub fn with_now(mut self, now: SystemTime) -> Self {
+        self.now = now;
+        self
+    }
+
+    /// The query text.
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// Whether `node`, found at `path`, satisfies the query.
+    pub fn matches(&self, node: &FileNode, path: &Path) -> bool {
+        self.expr.eval(node, path, self.now)
+    }
+
+    /// Every node below the root of `tree` that satisfies the query, in
+    /// tree order.
+    pub fn find<'t>(&self, tree: &'t FileTree) -> Vec<(PathBuf, &'t FileNode)> {
+        let mut found = Vec::new();
+        self.collect(&tree.root, &tree.root_path, &mut found);
+        found
+    }
+
+    fn collect<'t>(
+        &self,
+        node: &'t FileNode,
+        path: &Path,
+        found: &mut Vec<(PathBuf, &'t FileNode)>,
+    ) {
+        for child in &node.children {
+            let child_path = path.join(&*child.name);
+            if self.matches(child, &child_path) {
+                found.push((child_path.clone(), child));
+            }
+            if child.is_dir() {
+                self.collect(child, &child_path, found);
+            }
+        }
+    }
+
+    /// A copy of `tree` holding only the entries that satisfy the query.
+    ///
+    /// Directories are kept when they contain a match, and their sizes,
+    /// counts and the tree statistics are recomputed from what remains, so
+    /// a filtered tree can be browsed like a scanned one. Directories are not
+    /// tested themselves; use [`find`](Self::find) to select directories.
+    pub fn filter_tree(&self, tree: &FileTree) -> FileTree {
+        let mut root = self
+            .prune(&tree.root, &tree.root_path)
+            .unwrap_or_else(|| copy_directory(&tree.root, Vec::new()));
+        root.update_counts();
+
+        let mut stats = TreeStats::new();
+        record_stats(&root, &tree.root_path, 0, &mut stats);
+
+        let mut filtered = FileTree::new(
+            root,
+            tree.root_path.clone(),
+            tree.config.clone(),
+            stats,
+            tree.scan_duration,
+            tree.warnings.clone(),
+        );
+        filtered.roots = tree.roots.clone();
+        filtered.scanned_at = tree.scanned_at;
+        filtered
+    }
+
+    fn prune(&self, node: &FileNode, path: &Path) -> Option<FileNode> {
+        if !node.is_dir() {
+            return self.matches(node, path).then(|| node.clone());
+        }
+        let children: Vec<FileNode> = node
+            .children
+            .iter()
+            .filter_map(|child| self.prune(child, &path.join(&*child.name)))
+            .collect();
+        (!children.is_empty()).then(|| copy_directory(node, children))
+    }
+}
+
+impl fmt::Display for Query {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+/// `dir` with `children` in place of its own, sized by them.
+fn copy_directory(dir: &FileNode, children: Vec<FileNode>) -> FileNode {
+    FileNode {
+        id: dir.id,
+        name: dir.name.clone(),
+        kind: dir.kind.clone(),
+        size: children.iter().map(|c| c.size).sum(),
+        blocks: children.iter().map(|c| c.blocks).sum(),
+        timestamps: dir.timestamps,
+        inode: dir.inode,
+        owner: dir.owner,
+        content_hash: dir.content_hash,
+        git_status: dir.git_status,
+        children,
+    }
+}
+
+fn record_stats(node: &FileNode, path: &Path, depth: u32, stats: &mut TreeStats) {
+    match &node.kind {
+        NodeKind::Directory { .. } => {
+            stats.record_dir(depth);
+            for child in &node.children {
+                record_stats(child, &path.join(&*child.name), depth + 1, stats);
+            }
+        }
+        NodeKind::Symlink { .. } => stats.record_symlink(),
+        NodeKind::MountPoint { .. } => {}
+        NodeKind::File { .. } | NodeKind::Other => {
+            stats.record_file(path, node.size, node.timestamps.modified, depth);
+            if node.git_status == Some(GitStatus::Ignored) {
+                stats.record_ignored(node.size);
+            }
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Evaluation
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone)]
+enum Expr {
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Test(Test),
+}
+
+impl Expr {
+    fn eval(&self, node: &FileNode, path: &Path, now: SystemTime) -> bool {
+        match self {
+            Expr::And(a, b) => a.eval(node, path, now) && b.eval(node, path, now),
+            Expr::Or(a, b) => a.eval(node, path, now) || b.eval(node, path, now),
+            Expr::Not(e) => !e.eval(node, path, now),
+            Expr::Test(test) => test.eval(node, path, now),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Cmp {
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+}
+
+impl Cmp {
+    fn holds<T: PartialOrd>(self, left: T, right: T) -> bool {
+        match self {
+            Cmp::Lt => left < right,
+            Cmp::Le => left <= right,
+            Cmp::Gt => left > right,
+            Cmp::Ge => left >= right,
+            Cmp::Eq => left == right,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TimeField {
+    Modified,
+    Accessed,
+    Created,
+}
+
+impl TimeField {
+    fn of(self, node: &FileNode) -> Option<SystemTime> {
+        match self {
+            TimeField::Modified => Some(node.timestamps.modified),
+            TimeField::Accessed => node.timestamps.accessed,
+            TimeField::Created => node.timestamps.created,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Pattern {
+    Glob(GlobMatcher),
+    Regex(Regex),
+    Exact(String),
+}
+
+impl Pattern {
+    fn is_match(&self, text: &str) -> bool {
+        match self {
+            Pattern::Glob(glob) => glob.is_match(text),
+            Pattern::Regex(regex) => regex.is_match(text),
+            Pattern::Exact(exact) => text == exact,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum KindValue {
+    File,
+    Directory,
+    Symlink,
+    Broken,
+    Executable,
+    Mount,
+    Other,
+}
+
+impl KindValue {
+    fn parse(value: &str) -> Option<Self> {
+        Some(match value.to_lowercase().as_str() {
+            "file" | "f" => KindValue::File,
+            "dir" | "directory" | "d" => KindValue::Directory,
+            "symlink" | "link" | "l" => KindValue::Symlink,
+            "broken" => KindValue::Broken,
+            "exec" | "executable" | "x" => KindValue::Executable,
+            "mount" => KindValue::Mount,
+            "other" => KindValue::Other,
+            _ => return None,
+        })
+    }
+
+    fn matches(self, kind: &NodeKind) -> bool {
+        match (self, kind) {
+            (KindValue::File, NodeKind::File { .. })
+            | (KindValue::Directory, NodeKind::Directory { .. })
+            | (KindValue::Symlink, NodeKind::Symlink { .. })
+            | (KindValue::Mount, NodeKind::MountPoint { .. })
+            | (KindValue::Other, NodeKind::Other) => true,
+            (KindValue::Broken, NodeKind::Symlink { broken, .. }) => *broken,
+            (KindValue::Executable, NodeKind::File { executable }) => *executable,
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Test {
+    Size(Cmp, u64),
+    Ago(TimeField, Cmp, Duration),
+    Time(TimeField, Cmp, SystemTime),
+    Name(Pattern),
+    Path(Pattern),
+    Ext(Pattern),
+    Kind(KindValue),
+    Git(GitStatus),
+    Owner(u32),
+    Group(u32),
+}
+
+impl Test {
+    fn eval(&self, node: &FileNode, path: &Path, now: SystemTime) -> bool {
+        match self {
+            Test::Size(cmp, size) => cmp.holds(node.size, *size),
+            Test::Ago(field, cmp, ago) => {
+                let since = now.checked_sub(*ago).unwrap_or(UNIX_EPOCH);
+                field.of(node).is_some_and(|time| cmp.holds(time, since))
+            }
+            // A date matches the whole day it names.
+            Test::Time(field, cmp, day) => field.of(node).is_some_and(|time| {
+                let end = *day + Duration::from_secs(SECS_PER_DAY);
+                match cmp {
+                    Cmp::Lt => time < *day,
+                    Cmp::Le => time < end,
+                    Cmp::Gt => time >= end,
+                    Cmp::Ge => time >= *day,
+                    Cmp::Eq => time >= *day && time < end,
+                }
+            }),
+            Test::Name(pattern) => pattern.is_match(&node.name),
+            Test::Path(pattern) => pattern.is_match(&path.to_string_lossy()),
+            Test::Ext(pattern) => {
+                !node.is_dir()
+                    && Path::new(node.name.as_str())
+                        .extension()
+                        .is_some_and(|ext| pattern.is_match(&ext.to_string_lossy().to_lowercase()))
+            }
+            Test::Kind(kind) => kind.matches(&node.kind),
+            Test::Git(status) => node.git_status.unwrap_or_default() == *status,
+            Test::Owner(uid) => node.owner.is_some_and(|o| o.uid == *uid),
+            Test::Group(gid) => node.owner.is_some_and(|o| o.gid == *gid),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Parsing
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Cmp(Cmp),
+    Ne,
+    Match,
+    NotMatch,
+}
+
+impl fmt::Display for Op {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Op::Cmp(Cmp::Lt) => "<",
+            Op::Cmp(Cmp::Le) => "<=",
+            Op::Cmp(Cmp::Gt) => ">",
+            Op::Cmp(Cmp::Ge) => ">=",
+            Op::Cmp(Cmp::Eq) => "=",
+            Op::Ne => "!=",
+            Op::Match => "~",
+            Op::NotMatch => "!~",
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Tok {
+    /// Unquoted word: a field, keyword or value.
+    Word(String),
+    /// Quoted value.
+    Str(String),
+    Op(Op),
+    LParen,
+    RParen,
+    Comma,
+    End,
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+    tok: Tok,
+    pos: usize,
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>, QueryError> {
+    let mut tokens = Vec::new();
+    let mut chars = source.char_indices().peekable();
+
+    while let Some(&(pos, c)) = chars.peek() {
+        let tok = match c {
+            c if c.is_whitespace() => {
+                chars.next();
+                continue;
+            }
+            '(' | ')' | ',' => {
+                chars.next();
+                match c {
+                    '(' => Tok::LParen,
+                    ')' => Tok::RParen,
+                    _ => Tok::Comma,
+                }
+            }
+            '\'' | '"' => {
+                chars.next();
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        Some((_, q)) if q == c => break,
+                        Some((_, ch)) => value.push(ch),
+                        None => return Err(QueryError::new(pos, "Unterminated string")),
+                    }
+                }
+                Tok::Str(value)
+            }
+            '=' | '!' | '<' | '>' | '~' => {
+                chars.next();
+                let next = chars.peek().map(|&(_, n)| n);
+                let (op, two) = match (c, next) {
+                    ('=', Some('=')) => (Op::Cmp(Cmp::Eq), true),
+                    ('=', Some('~')) => (Op::Match, true),
+                    ('=', _) => (Op::Cmp(Cmp::Eq), false),
+                    ('!', Some('=')) => (Op::Ne, true),
+                    ('!', Some('~')) => (Op::NotMatch, true),
+                    ('<', Some('=')) => (Op::Cmp(Cmp::Le), true),
+                    ('<', _) => (Op::Cmp(Cmp::Lt), false),
+                    ('>', Some('=')) => (Op::Cmp(Cmp::Ge), true),
+                    ('>', _) => (Op::Cmp(Cmp::Gt), false),
+                    ('~', _) => (Op::Match, false),
+                    _ => return Err(QueryError::new(pos, "Expected '!=' or '!~'")),
+                };
+                if two {
+                    chars.next();
+                }
+                Tok::Op(op)
+            }
+            _ => {
+                let mut word = String::new();
+                while let Some(&(_, ch)) = chars.peek() {
+                    if ch.is_whitespace() || "(),'\"=!<>~".contains(ch) {
+                        break;
+                    }
+                    word.push(ch);
+                    chars.next();
+                }
+                Tok::Word(word)
+            }
+        };
+        tokens.push(Token { tok, pos });
+    }
+
+    tokens.push(Token {
+        tok: Tok::End,
+        pos: source.len(),
+    });
+    Ok(tokens)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Name,
+    Path,
+    Ext,
+    Size,
+    Time(TimeField),
+    Kind,
+    Git,
+    Owner,
+    Group,
+}
+
+impl Field {
+    fn parse(word: &str) -> Option<Self> {
+        Some(match word.to_lowercase().as_str() {
+            "name" => Field::Name,
+            "path" => Field::Path,
+            "ext" | "extension" => Field::Ext,
+            "size" => Field::Size,
+            "mtime" | "modified" => Field::Time(TimeField::Modified),
+            "atime" | "accessed" => Field::Time(TimeField::Accessed),
+            "created" | "btime" => Field::Time(TimeField::Created),
+            "kind" | "type" => Field::Kind,
+            "git" => Field::Git,
+            "owner" | "user" => Field::Owner,
+            "group" => Field::Group,
+            _ => return None,
+        })
+    }
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    /// Loaded on first use by an owner or group name.
+    resolver: Option<NameResolver>,
+}
+
+impl Parser {
+    fn peek(&self) -> &Tok {
+        &self.tokens[self.pos].tok
+    }
+
+    fn advance(&mut self) -> Token {
+        let token = self.tokens[self.pos].clone();
+        if token.tok != Tok::End {
+            self.pos += 1;
+        }
+        token
+    }
+
+    fn error(&self, message: impl Into<String>) -> QueryError {
+        QueryError::new(self.tokens[self.pos].pos, message)
+    }
+
+    fn keyword(&mut self, keyword: &str) -> bool {
+        if let Tok::Word(word) = self.peek()
+            && word.eq_ignore_ascii_case(keyword)
+        {
+            self.pos += 1;
+            return true;
+        }
+        false
+    }
+
+    fn expr(&mut self) -> Result<Expr, QueryError> {
+        let mut left = self.and()?;
+        while self.keyword("or") {
+            left = Expr::Or(Box::new(left), Box::new(self.and()?));
+        }
+        Ok(left)
+    }
+
+    fn and(&mut self) -> Result<Expr, QueryError> {
+        let mut left = self.unary()?;
+        while self.keyword("and") {
+            left = Expr::And(Box::new(left), Box::new(self.unary()?));
+        }
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> Result<Expr, QueryError> {
+        if self.keyword("not") {
+            return Ok(Expr::Not(Box::new(self.unary()?)));
+        }
+        if *self.peek() == Tok::LParen {
+            self.advance();
+            let expr = self.expr()?;
+            if *self.peek() != Tok::RParen {
+                return Err(self.error("Expected ')'"));
+            }
+            self.advance();
+            return Ok(expr);
+        }
+        self.comparison()
+    }
+
+    fn comparison(&mut self) -> Result<Expr, QueryError> {
+        let field = match self.peek() {
+            Tok::Word(word) => match Field::parse(word) {
+                Some(field) => field,
+                None => return Err(self.error(format!("Unknown field '{}'", word))),
+            },
+            _ => return Err(self.error("Expected a field name")),
+        };
+        self.advance();
+
+        if self.keyword("in") {
+            if *self.peek() != Tok::LParen {
+                return Err(self.error("Expected '(' after 'in'"));
+            }
+            self.advance();
+            let mut expr: Option<Expr> = None;
+            loop {
+                let (pos, value) = self.value()?;
+                let test = self.test(field, Op::Cmp(Cmp::Eq), &value, pos)?;
+                expr = Some(match expr {
+                    Some(left) => Expr::Or(Box::new(left), Box::new(test)),
+                    None => test,
+                });
+                match self.peek() {
+                    Tok::Comma => {
+                        self.advance();
+                    }
+                    Tok::RParen => {
+                        self.advance();
+                        break;
+                    }
+                    _ => return Err(self.error("Expected ',' or ')'")),
+                }
+            }
+            return Ok(expr.expect("list has at least one value"));
+        }
+
+        let op = match self.peek() {
+            Tok::Op(op) => *op,
+            _ => return Err(self.error("Expected an operator or 'in'")),
+        };
+        self.advance();
+        let (pos, value) = self.value()?;
+        self.test(field, op, &value, pos)
+    }
+
+    fn value(&mut self) -> Result<(usize, String), QueryError> {
+        match self.peek() {
+            Tok::Word(_) | Tok::Str(_) => {}
+            _ => return Err(self.error("Expected a value")),
+        }
+        let token = self.advance();
+        match token.tok {
+            Tok::Word(value) | Tok::Str(value) => Ok((token.pos, value)),
+            _ => unreachable!("checked above"),
+        }
+    }
+
+    /// Build the test for `field op value`; `!=` and `!~` negate `=` and `~`.
+    fn test(&mut self, field: Field, op: Op, value: &str, pos: usize) -> Result<Expr, QueryError> {
+        let err = |message: String| QueryError::new(pos, message);
+        let unsupported = || err(format!("'{}' is not supported for this field", op));
+
+        let (cmp, negate) = match op {
+            Op::Cmp(cmp) => (Some(cmp), false),
+            Op::Ne => (Some(Cmp::Eq), true),
+            Op::Match => (None, false),
+            Op::NotMatch => (None, true),
+        };
+
+        let test = match field {
+            Field::Size => Test::Size(
+                cmp.ok_or_else(unsupported)?,
+                parse_size(value).map_err(err)?,
+            ),
+            Field::Time(time) => {
+                let cmp = cmp.ok_or_else(unsupported)?;
+                if let Some(day) = parse_date(value) {
+                    Test::Time(time, cmp, day)
+                } else {
+                    if cmp == Cmp::Eq {
+                        return Err(err("Durations can only be compared with < or >".to_string()));
+                    }
+                    Test::Ago(time, cmp, parse_duration(value).map_err(err)?)
+                }
+            }
+            Field::Name | Field::Path | Field::Ext => {
+                let pattern = match cmp {
+                    None => Pattern::Regex(
+                        Regex::new(value).map_err(|e| err(format!("Invalid regex: {}", e)))?,
+                    ),
+                    Some(Cmp::Eq) if field == Field::Ext => {
+                        Pattern::Exact(value.trim_start_matches('.').to_lowercase())
+                    }
+                    Some(Cmp::Eq) => Pattern::Glob(
+                        Glob::new(value)
+                            .map_err(|e| err(format!("Invalid glob: {}", e)))?
+                            .compile_matcher(),
+                    ),
+                    Some(_) => return Err(unsupported()),
+                };
+                match field {
+                    Field::Name => Test::Name(pattern),
+                    Field::Path => Test::Path(pattern),
+                    _ => Test::Ext(pattern),
+                }
+            }
+            Field::Kind | Field::Git | Field::Owner | Field::Group => {
+                if cmp != Some(Cmp::Eq) {
+                    return Err(unsupported());
+                }
+                match field {
+                    Field::Kind => Test::Kind(
+                        KindValue::parse(value)
+                            .ok_or_else(|| err(format!("Unknown kind '{}'", value)))?,
+                    ),
+                    Field::Git => Test::Git(
+                        parse_git_status(value)
+                            .ok_or_else(|| err(format!("Unknown git status '{}'", value)))?,
+                    ),
+                    Field::Owner => Test::Owner(
+                        self.resolve(value, NameResolver::uid)
+                            .ok_or_else(|| err(format!("Unknown user '{}'", value)))?,
+                    ),
+                    _ => Test::Group(
+                        self.resolve(value, NameResolver::gid)
+                            .ok_or_else(|| err(format!("Unknown group '{}'", value)))?,
+                    ),
+                }
+            }
+        };
+
+        let expr = Expr::Test(test);
+        Ok(if negate {
+            Expr::Not(Box::new(expr))
+        } else {
+            expr
+        })
+    }
+
+    /// A numeric ID, or a name looked up with `lookup`.
+    fn resolve(
+        &mut self,
+        value: &str,
+        lookup: fn(&NameResolver, &str) -> Option<u32>,
+    ) -> Option<u32> {
+        value.parse().ok().or_else(|| {
+            let resolver = self.resolver.get_or_insert_with(NameResolver::system);
+            lookup(resolver, value)
+        })
+    }
+}
+
+fn parse_git_status(value: &str) -> Option<GitStatus> {
+    Some(match value.to_lowercase().as_str() {
+        "modified" | "m" => GitStatus::Modified,
+        "staged" | "a" => GitStatus::Staged,
+        "untracked" | "?" => GitStatus::Untracked,
+        "ignored" | "!" => GitStatus::Ignored,
+        "conflict" | "c" => GitStatus::Conflict,
+        "clean" => GitStatus::Clean,
+        _ => return None,
+    })
+}
+
+/// Parse a `YYYY-MM-DD` date as midnight UTC.
+fn parse_date(value: &str) -> Option<SystemTime> {
+    let mut parts = value.splitn(3, '-');
+    let (year, month, day) = (parts.next()?, parts.next()?, parts.next()?);
+    if year.len() != 4 || month.len() != 2 || day.len() != 2 {
+        return None;
+    }
+    let (year, month, day): (i64, u32, u32) =
+        (year.parse().ok()?, month.parse().ok()?, day.parse().ok()?);
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+
+    let days = days_from_civil(year, month, day);
+    let secs = u64::try_from(days).ok()? * SECS_PER_DAY;
+    Some(UNIX_EPOCH + Duration::from_secs(secs))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gravityfile_core::{NodeId, Ownership, ScanConfig, Timestamps};
+
+    const DAY: Duration = Duration::from_secs(SECS_PER_DAY);
+
+    fn file(name: &str, size: u64, days_old: u32, now: SystemTime) -> FileNode {
+        let mut node = FileNode::new_file(
+            NodeId::new(0),
+            name,
+            size,
+            1,
+            Timestamps::with_modified(now - DAY * days_old),
+            false,
+        );
+        node.owner = Some(Ownership::new(1000, 100, 0o644));
+        node
+    }
+
+    fn dir(name: &str, children: Vec<FileNode>) -> FileNode {
+        let mut node = FileNode::new_directory(
+            NodeId::new(0),
+            name,
+            Timestamps::with_modified(SystemTime::now()),
+        );
+        node.size = children.iter().map(|c| c.size).sum();
+        node.children = children;
+        node
+    }
+
+    fn tree(now: SystemTime) -> FileTree {
+        let mut root = dir(
+            "data",
+            vec![
+                dir(
+                    "images",
+                    vec![
+                        file("debian.iso", 4 << 30, 400, now),
+                        file("fresh.ISO", 3 << 30, 2, now),
+                        file("small.img", 1 << 20, 400, now),
+                    ],
+                ),
+                dir("backup", vec![file("old.iso", 2 << 30, 500, now)]),
+                file("notes.txt", 100, 1, now),
+            ],
+        );
+        root.finalize();
+        FileTree::new(
+            root,
+            PathBuf::from("/data"),
+            ScanConfig::new("/data"),
+            TreeStats::new(),
+            Duration::ZERO,
+            Vec::new(),
+        )
+    }
+
+    fn found(query: &str, tree: &FileTree, now: SystemTime) -> Vec<String> {
+        let query = Query::parse(query).unwrap().with_now(now);
+        query
+            .find(tree)
+            .into_iter()
+            .map(|(path, _)| path.strip_prefix("/data").unwrap().display().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_combined_query() {
+        let now = SystemTime::now();
+        let tree = tree(now);
+        assert_eq!(
+            found(
+                "size > 1G and mtime < 6mo and ext in (iso, img) and not path ~ '/backup/'",
+                &tree,
+                now
+            ),
+            vec!["images/debian.iso"]
+        );
+        assert_eq!(
+            found("ext = iso and (mtime > 1w or name = 'old*')", &tree, now),
+            vec!["images/fresh.ISO", "backup/old.iso"]
+        );
+        assert_eq!(
+            found("kind = dir and size >= 2G", &tree, now),
+            vec!["images", "backup"]
+        );
+        assert_eq!(
+            found("owner = 1000 and group != 100", &tree, now),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn test_precedence() {
+        let now = SystemTime::now();
+        let tree = tree(now);
+        // `and` binds tighter than `or`.
+        assert_eq!(
+            found("name = notes.txt or ext = iso and size > 3G", &tree, now),
+            vec!["images/debian.iso", "notes.txt"]
+        );
+        assert_eq!(
+            found("(name = notes.txt or ext = iso) and size > 3G", &tree, now),
+            vec!["images/debian.iso"]
+        );
+        // `not` binds tighter than `and`.
+        assert_eq!(
+            found("not ext = iso and kind = file", &tree, now),
+            vec!["images/small.img", "notes.txt"]
+        );
+        assert_eq!(
+            found("not (ext = iso or kind = dir)", &tree, now),
+            vec!["images/small.img", "notes.txt"]
+        );
+        assert_eq!(
+            found("kind = file and not not ext = img", &tree, now),
+            vec!["images/small.img"]
+        );
+    }
+
+    #[test]
+    fn test_in_lists_and_patterns() {
+        let now = SystemTime::now();
+        let tree = tree(now);
+        assert_eq!(
+            found("ext in (ISO, .img)", &tree, now),
+            vec![
+                "images/debian.iso",
+                "images/fresh.ISO",
+                "images/small.img",
+                "backup/old.iso"
+            ]
+        );
+        assert_eq!(
+            found("name in ('*.img', notes.txt)", &tree, now),
+            vec!["images/small.img", "notes.txt"]
+        );
+        assert_eq!(
+            found(r"name ~ '^[a-z]+\.iso$'", &tree, now),
+            vec!["images/debian.iso", "backup/old.iso"]
+        );
+        assert_eq!(
+            found("kind = file and path !~ 'images'", &tree, now),
+            vec!["backup/old.iso", "notes.txt"]
+        );
+        assert_eq!(
+            found("kind = file and ext != iso and name != 'n*'", &tree, now),
+            vec!["images/small.img"]
+        );
+    }
+
+    #[test]
+    fn test_units() {
+        let now = SystemTime::now();
+        let tree = tree(now);
+        assert_eq!(
+            found("size >= 1M and size < 1.5G", &tree, now),
+            vec!["images/small.img"]
+        );
+        assert_eq!(found("size = 1024K", &tree, now), vec!["images/small.img"]);
+        assert_eq!(found("size <= 100B", &tree, now), vec!["notes.txt"]);
+        assert_eq!(
+            found("kind = file and mtime < 13mo", &tree, now),
+            vec!["images/debian.iso", "images/small.img", "backup/old.iso"]
+        );
+        assert_eq!(
+            found("kind = file and mtime < 1y and mtime >= 70w", &tree, now),
+            vec!["images/debian.iso", "images/small.img"]
+        );
+        assert_eq!(
+            found("kind = file and mtime > 36h", &tree, now),
+            vec!["notes.txt"]
+        );
+        assert_eq!(
+            found("kind = file and mtime < 36h and mtime > 3", &tree, now),
+            vec!["images/fresh.ISO"]
+        );
+        assert!(found("mtime < unlimited", &tree, now).is_empty());
+    }
+
+    #[test]
+    fn test_kind_git_and_owner() {
+        let now = SystemTime::now();
+        let mut exec = file("run.sh", 10, 1, now);
+        exec.kind = NodeKind::File { executable: true };
+        exec.git_status = Some(GitStatus::Modified);
+        exec.owner = Some(Ownership::new(0, 0, 0o755));
+        let mut link = file("dangling", 0, 1, now);
+        link.kind = NodeKind::Symlink {
+            target: "gone".into(),
+            broken: true,
+        };
+        let mut new = file("new.txt", 5, 1, now);
+        new.git_status = Some(GitStatus::Untracked);
+        let mut root = dir("data", vec![exec, link, new, file("plain.txt", 5, 1, now)]);
+        root.finalize();
+        let tree = FileTree::new(
+            root,
+            PathBuf::from("/data"),
+            ScanConfig::new("/data"),
+            TreeStats::new(),
+            Duration::ZERO,
+            Vec::new(),
+        );
+
+        assert_eq!(found("kind = exec", &tree, now), vec!["run.sh"]);
+        assert_eq!(found("kind = broken", &tree, now), vec!["dangling"]);
+        assert_eq!(found("kind = l", &tree, now), vec!["dangling"]);
+        assert!(found("kind = dir", &tree, now).is_empty());
+        assert_eq!(
+            found("kind = file and kind != exec", &tree, now),
+            vec!["new.txt", "plain.txt"]
+        );
+        assert_eq!(found("git = m", &tree, now), vec!["run.sh"]);
+        assert_eq!(
+            found("git in (untracked, modified)", &tree, now),
+            vec!["run.sh", "new.txt"]
+        );
+        assert_eq!(
+            found("kind != dir and git = clean", &tree, now),
+            vec!["plain.txt", "dangling"]
+        );
+        assert_eq!(found("owner = 0", &tree, now), vec!["run.sh"]);
+        assert_eq!(found("owner = 0 and group = 0", &tree, now), vec!["run.sh"]);
+        assert_eq!(
+            found("group = 100", &tree, now),
+            vec!["new.txt", "plain.txt", "dangling"]
+        );
+    }
+
+    #[test]
+    fn test_dates() {
+        assert_eq!(parse_date("1970-01-01"), Some(UNIX_EPOCH));
+        assert_eq!(
+            parse_date("2024-03-01"),
+            Some(UNIX_EPOCH + Duration::from_secs(1_709_251_200))
+        );
+        assert_eq!(parse_date("2024-13-01"), None);
+        assert_eq!(parse_date("30d"), None);
+
+        let now = UNIX_EPOCH + Duration::from_secs(1_709_251_200) + DAY * 10;
+        let tree = tree(now);
+        assert_eq!(
+            found("mtime >= 2024-03-01 and kind = file", &tree, now),
+            vec!["images/fresh.ISO", "notes.txt"]
+        );
+    }
+
+    #[test]
+    fn test_filter_tree_keeps_aggregates() {
+        let now = SystemTime::now();
+        let tree = tree(now);
+        let filtered = Query::parse("ext = iso").unwrap().filter_tree(&tree);
+
+        assert_eq!(filtered.root.size, (4 << 30) + (3 << 30) + (2 << 30));
+        assert_eq!(filtered.root.file_count(), 3);
+        assert_eq!(filtered.stats.total_files, 3);
+        assert_eq!(filtered.stats.total_size, filtered.root.size);
+
+        let images = &filtered.root.children[0];
+        assert_eq!(images.name, "images");
+        assert_eq!(images.size, (4 << 30) + (3 << 30));
+        assert_eq!(images.children.len(), 2);
+        assert!(filtered.root.children.iter().all(|c| c.name != "notes.txt"));
+
+        let empty = Query::parse("size > 100G").unwrap().filter_tree(&tree);
+        assert!(empty.root.children.is_empty());
+        assert_eq!(empty.root.size, 0);
+    }
+
+    #[test]
+    fn test_errors() {
+        let err = |q: &str| Query::parse(q).unwrap_err();
+        assert_eq!(err("colour = red").message, "Unknown field 'colour'");
+        assert_eq!(err("size > 1G and").position, 13);
+        assert_eq!(
+            err("size ~ 1G").message,
+            "'~' is not supported for this field"
+        );
+        assert_eq!(err("name = 'x").message, "Unterminated string");
+        assert_eq!(err("ext in (iso img)").message, "Expected ',' or ')'");
+        assert_eq!(
+            err("mtime = 3d").message,
+            "Durations can only be compared with < or >"
+        );
+        assert!(err("kind = blob").message.starts_with("Unknown kind"));
+
+        // Positions point at the offending token.
+        assert_eq!(err("colour = red").position, 0);
+        assert_eq!(err("size > 1G and colour = red").position, 14);
+        assert_eq!(err("size > 1Q").position, 7);
+        assert_eq!(err("size > 1Q").message, "Invalid size '1Q'");
+        assert_eq!(err("mtime < 3x").position, 8);
+        assert_eq!(err("name ~ '('").position, 7);
+        assert!(err("name ~ '('").message.starts_with("Invalid regex"));
+        assert_eq!(err("(size > 1G").message, "Expected ')'");
+        assert_eq!(err("(size > 1G").position, 10);
+        assert_eq!(err("ext in iso").message, "Expected '(' after 'in'");
+        assert_eq!(err("ext in iso").position, 7);
+        assert_eq!(err("ext in (iso img)").position, 12);
+        assert_eq!(err("git = dirty").position, 6);
+        assert_eq!(
+            err("size > 1G and mtime < 3x").to_string(),
+            "Invalid duration '3x' (at column 23)"
+        );
+        assert!(
+            err("size > 1G size < 2G")
+                .message
+                .starts_with("Expected 'and'")
+        );
+    }
+}
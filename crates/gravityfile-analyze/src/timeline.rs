@@ -0,0 +1,337 @@
+//! Histogram of file modification times.
+//!
+//! Files are binned by the calendar week (Monday to Sunday, UTC) or month of
+//! their mtime. Bins run contiguously from the oldest to the newest file so
+//! quiet periods show up as gaps.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use gravityfile_core::{FileNode, NodeKind};
+
+use crate::units::{civil_from_days, days_from_civil};
+
+const SECS_PER_DAY: u64 = 86_400;
+
+/// Width of one timeline bin.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TimelinePeriod {
+    /// Calendar weeks starting on Monday.
+    Week,
+    /// Calendar months.
+    #[default]
+    Month,
+}
+
+impl TimelinePeriod {
+    /// Lowercase name, as accepted by `FromStr`.
+    pub fn label(self) -> &'static str {
+        match self {
+            TimelinePeriod::Week => "week",
+            TimelinePeriod::Month => "month",
+        }
+    }
+
+    /// The other period.
+    pub fn toggle(self) -> Self {
+        match self {
+            TimelinePeriod::Week => TimelinePeriod::Month,
+            TimelinePeriod::Month => TimelinePeriod::Week,
+        }
+    }
+
+    /// First day of the bin containing `day` (days since the epoch).
+    fn start_of(self, day: i64) -> i64 {
+        match self {
+            // 1970-01-01 was a Thursday, three days after a Monday.
+            TimelinePeriod::Week => day - (day + 3).rem_euclid(7),
+            TimelinePeriod::Month => {
+                let (year, month, _) = civil_from_days(day);
+                days_from_civil(year, month, 1)
+            }
+        }
+    }
+
+    /// First day of the bin after the one starting on `start`.
+    fn next(self, start: i64) -> i64 {
+        match self {
+            TimelinePeriod::Week => start + 7,
+            TimelinePeriod::Month => {
+                let (year, month, _) = civil_from_days(start);
+                if month == 12 {
+                    days_from_civil(year + 1, 1, 1)
+                } else {
+                    days_from_civil(year, month + 1, 1)
+                }
+            }
+        }
+    }
+
+    fn bin_label(self, start: i64) -> String {
+        let (year, month, day) = civil_from_days(start);
+        match self {
+            TimelinePeriod::Week => format!("{:04}-{:02}-{:02}", year, month, day),
+            TimelinePeriod::Month => format!("{:04}-{:02}", year, month),
+        }
+    }
+}
+
+impl fmt::Display for TimelinePeriod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
+impl FromStr for TimelinePeriod {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "week" | "weeks" | "weekly" | "w" => Ok(TimelinePeriod::Week),
+            "month" | "months" | "monthly" | "mo" => Ok(TimelinePeriod::Month),
+            other => Err(format!(
+                "Unknown timeline period '{}' (expected week or month)",
+                other
+            )),
+        }
+    }
+}
+
+/// Files modified within one week or month.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimelineBin {
+    /// Bin label: the month (`2026-03`) or the week's Monday (`2026-03-09`).
+    pub label: String,
+    /// Start of the bin (midnight UTC, inclusive).
+    pub start: SystemTime,
+    /// End of the bin (midnight UTC, exclusive).
+    pub end: SystemTime,
+    /// Number of files modified in the bin.
+    pub file_count: u64,
+    /// Total size of those files in bytes.
+    pub total_size: u64,
+}
+
+impl TimelineBin {
+    /// A query expression selecting the files in this bin.
+    pub fn query(&self) -> String {
+        format!(
+            "kind = file and mtime >= {} and mtime < {}",
+            date(self.start),
+            date(self.end)
+        )
+    }
+}
+
+/// Files under a directory binned by modification time, oldest first.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Timeline {
+    /// Width of each bin.
+    pub period: TimelinePeriod,
+    /// Contiguous bins from the oldest to the newest file, including empty ones.
+    pub bins: Vec<TimelineBin>,
+    /// Number of files counted.
+    pub file_count: u64,
+    /// Total size of the files counted.
+    pub total_size: u64,
+}
+
+impl Timeline {
+    /// Bin every file below `node` by its mtime.
+    pub fn new(node: &FileNode, period: TimelinePeriod) -> Self {
+        let mut counts: BTreeMap<i64, (u64, u64)> = BTreeMap::new();
+        let mut stack = vec![node];
+        while let Some(node) = stack.pop() {
+            match &node.kind {
+                NodeKind::File { .. } => {
+                    let start = period.start_of(day_of(node.timestamps.modified));
+                    let entry = counts.entry(start).or_default();
+                    entry.0 += 1;
+                    entry.1 += node.size;
+                }
+                NodeKind::Directory { .. } => stack.extend(&node.children),
+                _ => {}
+            }
+        }
+
+        let mut timeline = Self {
+            period,
+            ..Self::default()
+        };
+        let (Some(&first), Some(&last)) = (counts.keys().next(), counts.keys().next_back()) else {
+            return timeline;
+        };
+
+        let mut start = first;
+        while start <= last {
+            let end = period.next(start);
+            let (file_count, total_size) = counts.get(&start).copied().unwrap_or_default();
+            timeline.file_count += file_count;
+            timeline.total_size += total_size;
+            timeline.bins.push(TimelineBin {
+                label: period.bin_label(start),
+                start: time_of(start),
+                end: time_of(end),
+                file_count,
+                total_size,
+            });
+            start = end;
+        }
+        timeline
+    }
+
+    /// Size of the fullest bin, for scaling bars.
+    pub fn max_bin_size(&self) -> u64 {
+        self.bins.iter().map(|b| b.total_size).max().unwrap_or(0)
+    }
+
+    /// File count of the busiest bin, for scaling bars.
+    pub fn max_bin_files(&self) -> u64 {
+        self.bins.iter().map(|b| b.file_count).max().unwrap_or(0)
+    }
+}
+
+/// Days since the epoch of `time`, rounding down for times before 1970.
+fn day_of(time: SystemTime) -> i64 {
+    match time.duration_since(UNIX_EPOCH) {
+        Ok(after) => (after.as_secs() / SECS_PER_DAY) as i64,
+        Err(before) => {
+            let nanos_per_day = u128::from(SECS_PER_DAY) * 1_000_000_000;
+            -(before.duration().as_nanos().div_ceil(nanos_per_day) as i64)
+        }
+    }
+}
+
+/// Midnight UTC at the start of `day`.
+fn time_of(day: i64) -> SystemTime {
+    let secs = Duration::from_secs(day.unsigned_abs() * SECS_PER_DAY);
+    if day >= 0 {
+        UNIX_EPOCH + secs
+    } else {
+        UNIX_EPOCH - secs
+    }
+}
+
+fn date(time: SystemTime) -> String {
+    let (year, month, day) = civil_from_days(day_of(time));
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Query;
+    use gravityfile_core::{NodeId, Timestamps};
+
+    fn at(year: i64, month: u32, day: u32) -> SystemTime {
+        time_of(days_from_civil(year, month, day)) + Duration::from_secs(3600)
+    }
+
+    fn file(name: &str, size: u64, modified: SystemTime) -> FileNode {
+        FileNode::new_file(
+            NodeId::new(0),
+            name,
+            size,
+            1,
+            Timestamps::with_modified(modified),
+            false,
+        )
+    }
+
+    fn tree() -> FileNode {
+        let mut root = FileNode::new_directory(
+            NodeId::new(0),
+            "root",
+            Timestamps::with_modified(SystemTime::now()),
+        );
+        let mut sub = FileNode::new_directory(
+            NodeId::new(0),
+            "sub",
+            Timestamps::with_modified(SystemTime::now()),
+        );
+        sub.children = vec![file("c.txt", 30, at(2026, 3, 31))];
+        root.children = vec![
+            file("a.txt", 10, at(2025, 12, 31)),
+            file("b.txt", 20, at(2026, 3, 2)),
+            sub,
+        ];
+        root
+    }
+
+    #[test]
+    fn test_period_parse() {
+        assert_eq!("weekly".parse(), Ok(TimelinePeriod::Week));
+        assert_eq!("Month".parse(), Ok(TimelinePeriod::Month));
+        assert!("day".parse::<TimelinePeriod>().is_err());
+        assert_eq!(TimelinePeriod::Week.toggle(), TimelinePeriod::Month);
+    }
+
+    #[test]
+    fn test_monthly_bins_are_contiguous() {
+        let timeline = Timeline::new(&tree(), TimelinePeriod::Month);
+        let labels: Vec<_> = timeline.bins.iter().map(|b| b.label.as_str()).collect();
+        assert_eq!(labels, ["2025-12", "2026-01", "2026-02", "2026-03"]);
+        assert_eq!(timeline.bins[3].file_count, 2);
+        assert_eq!(timeline.bins[3].total_size, 50);
+        assert_eq!(timeline.bins[1].file_count, 0);
+        assert_eq!(timeline.file_count, 3);
+        assert_eq!(timeline.total_size, 60);
+        assert_eq!(timeline.max_bin_size(), 50);
+    }
+
+    #[test]
+    fn test_weeks_start_on_monday() {
+        let timeline = Timeline::new(&tree(), TimelinePeriod::Week);
+        // 2025-12-31 was a Wednesday; 2026-03-31 a Tuesday.
+        assert_eq!(timeline.bins[0].label, "2025-12-29");
+        assert_eq!(timeline.bins.last().unwrap().label, "2026-03-30");
+        assert_eq!(timeline.bins.len(), 14);
+        assert_eq!(timeline.file_count, 3);
+    }
+
+    #[test]
+    fn test_bin_query_selects_its_files() {
+        let root = tree();
+        let timeline = Timeline::new(&root, TimelinePeriod::Month);
+        let bin = &timeline.bins[3];
+        assert_eq!(
+            bin.query(),
+            "kind = file and mtime >= 2026-03-01 and mtime < 2026-04-01"
+        );
+
+        let query = Query::parse(&bin.query()).unwrap();
+        let mut matched: Vec<_> = root
+            .children
+            .iter()
+            .chain(&root.children[2].children)
+            .filter(|n| query.matches(n, std::path::Path::new(n.name.as_str())))
+            .map(|n| n.name.to_string())
+            .collect();
+        matched.sort();
+        assert_eq!(matched, ["b.txt", "c.txt"]);
+    }
+
+    #[test]
+    fn test_empty_tree() {
+        let root = FileNode::new_directory(
+            NodeId::new(0),
+            "root",
+            Timestamps::with_modified(SystemTime::now()),
+        );
+        let timeline = Timeline::new(&root, TimelinePeriod::Week);
+        assert!(timeline.bins.is_empty());
+        assert_eq!(timeline.max_bin_files(), 0);
+    }
+
+    #[test]
+    fn test_day_before_epoch() {
+        assert_eq!(day_of(UNIX_EPOCH - Duration::from_millis(1)), -1);
+        assert_eq!(day_of(UNIX_EPOCH - Duration::from_secs(SECS_PER_DAY)), -1);
+        assert_eq!(time_of(-1), UNIX_EPOCH - Duration::from_secs(SECS_PER_DAY));
+    }
+}
@@ -0,0 +1,385 @@
+//! Globally largest files and leaf directories.
+//!
+//! Unlike the per-directory listing of a scan summary, this ranks entries
+//! across the whole tree, keeping only the current top N in a min-heap so
+//! memory stays bounded however large the tree is.
+
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use serde::{Deserialize, Serialize};
+
+use gravityfile_core::{FileNode, FileTree, NodeKind};
+
+/// What to rank.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TopKind {
+    /// Regular files.
+    #[default]
+    Files,
+    /// Leaf directories: directories with no subdirectories.
+    Dirs,
+    /// Files and leaf directories together.
+    Both,
+}
+
+impl TopKind {
+    fn files(self) -> bool {
+        matches!(self, TopKind::Files | TopKind::Both)
+    }
+
+    fn dirs(self) -> bool {
+        matches!(self, TopKind::Dirs | TopKind::Both)
+    }
+}
+
+/// A ranked file or directory.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TopEntry {
+    /// Path to the entry.
+    pub path: PathBuf,
+    /// Size in bytes (aggregate for directories).
+    pub size: u64,
+    /// Last modification time.
+    pub modified: SystemTime,
+    /// Whether the entry is a directory.
+    pub is_dir: bool,
+    /// Files in the entry: 1 for a file, the file count for a directory.
+    pub file_count: u64,
+}
+
+/// Heap element ordered by size, then by path in reverse so that among
+/// equal sizes the alphabetically first path ranks highest.
+#[derive(PartialEq, Eq)]
+struct Ranked(TopEntry);
+
+impl Ord for Ranked {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0
+            .size
+            .cmp(&other.0.size)
+            .then_with(|| other.0.path.cmp(&self.0.path))
+    }
+}
+
+impl PartialOrd for Ranked {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Finds the largest entries of a tree, optionally filtered.
+#[derive(Debug, Clone)]
+pub struct TopFinder {
+    count: usize,
+    kind: TopKind,
+    extensions: Vec<String>,
+    older_than: Option<Duration>,
+    newer_than: Option<Duration>,
+    globs: Option<GlobSet>,
+    now: SystemTime,
+}
+
+impl TopFinder {
+    /// Find the `count` largest files.
+    pub fn new(count: usize) -> Self {
+        Self {
+            count,
+            kind: TopKind::default(),
+            extensions: Vec::new(),
+            older_than: None,
+            newer_than: None,
+            globs: None,
+            now: SystemTime::now(),
+        }
+    }
+
+    /// Rank files, leaf directories or both.
+    pub fn kind(mut self, kind: TopKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    /// Only rank files with one of these extensions (case-insensitive,
+    /// with or without the leading dot). Directories are not filtered.
+    pub fn extensions<I, S>(mut self, extensions: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.extensions = extensions
+            .into_iter()
+            .map(|e| e.as_ref().trim_start_matches('.').to_lowercase())
+            .collect();
+        self
+    }
+
+    /// Only rank entries last modified longer ago than `age`.
+    pub fn older_than(mut self, age: Duration) -> Self {
+        self.older_than = Some(age);
+        self
+    }
+
+    /// Only rank entries modified within `age`.
+    pub fn newer_than(mut self, age: Duration) -> Self {
+        self.newer_than = Some(age);
+        self
+    }
+
+    /// Only rank entries whose path relative to the scan root matches one of
+    /// `patterns`.
+    pub fn globs(mut self, patterns: &[String]) -> Result<Self, String> {
+        if patterns.is_empty() {
+            self.globs = None;
+            return Ok(self);
+        }
+        let mut builder = GlobSetBuilder::new();
+        for pattern in patterns {
+            builder.add(Glob::new(pattern).map_err(|e| e.to_string())?);
+        }
+        self.globs = Some(builder.build().map_err(|e| e.to_string())?);
+        Ok(self)
+    }
+
+    /// Measure ages from `now` instead of the current time.
+    pub fn with_now(mut self, now: SystemTime) -> Self {
+        self.now = now;
+        self
+    }
+
+    /// The largest matching entries below the root of `tree`, largest first.
+    pub fn find(&self, tree: &FileTree) -> Vec<TopEntry> {
+        self.find_in(&tree.root, &tree.root_path)
+    }
+
+    /// The largest matching entries below `node`, which lives at `path`.
+    /// Globs are matched against paths relative to `path`.
+    pub fn find_in(&self, node: &FileNode, path: &Path) -> Vec<TopEntry> {
+        let mut heap = BinaryHeap::with_capacity(self.count + 1);
+        if self.count > 0 {
+            for child in &node.children {
+                self.visit(child, path, path, &mut heap);
+            }
+        }
+        heap.into_sorted_vec()
+            .into_iter()
+            .map(|Reverse(Ranked(entry))| entry)
+            .collect()
+    }
+
+    fn visit(
+        &self,
+        node: &FileNode,
+        parent: &Path,
+        root: &Path,
+        heap: &mut BinaryHeap<Reverse<Ranked>>,
+    ) {
+        match &node.kind {
+            NodeKind::File { .. }
+                if self.kind.files()
+                    && self.would_rank(node.size, heap)
+                    && self.extension_matches(node) =>
+            {
+                self.offer(node, parent.join(&*node.name), root, heap);
+            }
+            NodeKind::Directory { .. } => {
+                let path = parent.join(&*node.name);
+                let is_leaf = !node.children.iter().any(|c| c.is_dir());
+                if is_leaf && self.kind.dirs() && self.would_rank(node.size, heap) {
+                    self.offer(node, path.clone(), root, heap);
+                }
+                for child in &node.children {
+                    self.visit(child, &path, root, heap);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Whether an entry of `size` would enter the heap, checked before
+    /// building its path.
+    fn would_rank(&self, size: u64, heap: &BinaryHeap<Reverse<Ranked>>) -> bool {
+        heap.len() < self.count || heap.peek().is_some_and(|Reverse(min)| size >= min.0.size)
+    }
+
+    fn extension_matches(&self, node: &FileNode) -> bool {
+        self.extensions.is_empty()
+            || Path::new(node.name.as_str())
+                .extension()
+                .is_some_and(|ext| {
+                    let ext = ext.to_string_lossy().to_lowercase();
+                    self.extensions.contains(&ext)
+                })
+    }
+
+    fn offer(
+        &self,
+        node: &FileNode,
+        path: PathBuf,
+        root: &Path,
+        heap: &mut BinaryHeap<Reverse<Ranked>>,
+    ) {
+        let age = self
+            .now
+            .duration_since(node.timestamps.modified)
+            .unwrap_or_default();
+        if self.older_than.is_some_and(|min| age <= min)
+            || self.newer_than.is_some_and(|max| age >= max)
+        {
+            return;
+        }
+        if let Some(globs) = &self.globs
+            && !globs.is_match(path.strip_prefix(root).unwrap_or(&path))
+        {
+            return;
+        }
+
+        heap.push(Reverse(Ranked(TopEntry {
+            path,
+            size: node.size,
+            modified: node.timestamps.modified,
+            is_dir: node.is_dir(),
+            file_count: node.file_count(),
+        })));
+        if heap.len() > self.count {
+            heap.pop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gravityfile_core::{NodeId, ScanConfig, Timestamps, TreeStats};
+
+    const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+    fn file(name: &str, size: u64, days_old: u32, now: SystemTime) -> FileNode {
+        FileNode::new_file(
+            NodeId::new(0),
+            name,
+            size,
+            1,
+            Timestamps::with_modified(now - DAY * days_old),
+            false,
+        )
+    }
+
+    fn dir(name: &str, children: Vec<FileNode>) -> FileNode {
+        let mut node = FileNode::new_directory(
+            NodeId::new(0),
+            name,
+            Timestamps::with_modified(SystemTime::now()),
+        );
+        node.size = children.iter().map(|c| c.size).sum();
+        node.children = children;
+        node
+    }
+
+    fn tree(now: SystemTime) -> FileTree {
+        let mut root = dir(
+            "home",
+            vec![
+                dir(
+                    "videos",
+                    vec![
+                        file("a.mkv", 900, 400, now),
+                        file("b.MKV", 800, 10, now),
+                        dir("clips", vec![file("c.mp4", 300, 1, now)]),
+                    ],
+                ),
+                dir(
+                    "src",
+                    vec![file("main.rs", 50, 1, now), file("big.log", 700, 90, now)],
+                ),
+                file("disk.img", 1000, 700, now),
+            ],
+        );
+        root.finalize();
+        FileTree::new(
+            root,
+            PathBuf::from("/home"),
+            ScanConfig::new("/home"),
+            TreeStats::new(),
+            Duration::ZERO,
+            Vec::new(),
+        )
+    }
+
+    fn names(entries: &[TopEntry]) -> Vec<String> {
+        entries
+            .iter()
+            .map(|e| e.path.strip_prefix("/home").unwrap().display().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_largest_files() {
+        let now = SystemTime::now();
+        let tree = tree(now);
+        let top = TopFinder::new(3).with_now(now).find(&tree);
+        assert_eq!(
+            names(&top),
+            vec!["disk.img", "videos/a.mkv", "videos/b.MKV"]
+        );
+        assert_eq!(top[0].size, 1000);
+
+        assert!(TopFinder::new(0).find(&tree).is_empty());
+        assert_eq!(TopFinder::new(100).find(&tree).len(), 6);
+    }
+
+    #[test]
+    fn test_leaf_directories() {
+        let now = SystemTime::now();
+        let tree = tree(now);
+        let top = TopFinder::new(10).kind(TopKind::Dirs).find(&tree);
+        // videos has a subdirectory, so only its leaf clips is listed.
+        assert_eq!(names(&top), vec!["src", "videos/clips"]);
+        assert!(top.iter().all(|e| e.is_dir));
+        assert_eq!(top[0].file_count, 2);
+    }
+
+    #[test]
+    fn test_filters() {
+        let now = SystemTime::now();
+        let tree = tree(now);
+        let find = |finder: TopFinder| names(&finder.with_now(now).find(&tree));
+
+        assert_eq!(
+            find(TopFinder::new(10).extensions([".mkv", "MP4"])),
+            vec!["videos/a.mkv", "videos/b.MKV", "videos/clips/c.mp4"]
+        );
+        assert_eq!(
+            find(
+                TopFinder::new(10)
+                    .older_than(DAY * 30)
+                    .newer_than(DAY * 365)
+            ),
+            vec!["src/big.log"]
+        );
+        assert_eq!(
+            find(
+                TopFinder::new(10)
+                    .globs(&["videos/**".to_string()])
+                    .unwrap()
+            ),
+            vec!["videos/a.mkv", "videos/b.MKV", "videos/clips/c.mp4"]
+        );
+        assert!(TopFinder::new(1).globs(&["[".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_find_in_subdirectory() {
+        let now = SystemTime::now();
+        let tree = tree(now);
+        let videos = &tree.root.children[0];
+        let top = TopFinder::new(10)
+            .globs(&["*.mkv".to_string()])
+            .unwrap()
+            .find_in(videos, Path::new("/home/videos"));
+        assert_eq!(names(&top), vec!["videos/a.mkv"]);
+    }
+}
@@ -0,0 +1,225 @@
+//! Human-friendly size and duration parsing, and calendar arithmetic.
+//!
+//! Shared by the CLI flags and profile files, so `min_size = "1MB"` in a
+//! `.gravityfile.toml` means the same as `--min-size 1MB`.
+
+use std::time::Duration;
+
+const SECS_PER_HOUR: f64 = 60.0 * 60.0;
+const SECS_PER_DAY: f64 = 24.0 * SECS_PER_HOUR;
+const SECS_PER_WEEK: f64 = 7.0 * SECS_PER_DAY;
+const SECS_PER_MONTH: f64 = 30.0 * SECS_PER_DAY;
+const SECS_PER_YEAR: f64 = 365.0 * SECS_PER_DAY;
+
+/// Parse a size such as `"4096"`, `"64K"`, `"1.5MB"` or `"2G"` (binary units).
+///
+/// `"unlimited"` parses to `u64::MAX`.
+pub fn parse_size(s: &str) -> Result<u64, String> {
+    let s = s.trim().to_uppercase();
+    if s == "UNLIMITED" || s == "NONE" {
+        return Ok(u64::MAX);
+    }
+
+    let (num, multiplier) = if let Some(n) = s.strip_suffix("GB").or(s.strip_suffix('G')) {
+        (n, 1024 * 1024 * 1024)
+    } else if let Some(n) = s.strip_suffix("MB").or(s.strip_suffix('M')) {
+        (n, 1024 * 1024)
+    } else if let Some(n) = s.strip_suffix("KB").or(s.strip_suffix('K')) {
+        (n, 1024)
+    } else if let Some(n) = s.strip_suffix('B') {
+        (n, 1)
+    } else {
+        (s.as_str(), 1)
+    };
+
+    let num: f64 = num
+        .trim()
+        .parse()
+        .map_err(|_| format!("Invalid size '{}'", s))?;
+    if num < 0.0 {
+        return Err(format!("Invalid size '{}'", s));
+    }
+    Ok((num * multiplier as f64) as u64)
+}
+
+/// Parse a duration such as `"12h"`, `"30d"`, `"2w"`, `"6m"` (or `"6mo"`) or `"1y"`.
+///
+/// A bare number is taken as days; months are 30 days and years 365.
+/// `"unlimited"` parses to `Duration::MAX`.
+pub fn parse_duration(s: &str) -> Result<Duration, String> {
+    let s = s.trim().to_lowercase();
+    if s == "unlimited" || s == "none" {
+        return Ok(Duration::MAX);
+    }
+
+    let (num, multiplier) = if let Some(n) = s.strip_suffix('y') {
+        (n, SECS_PER_YEAR)
+    } else if let Some(n) = s.strip_suffix("mo").or(s.strip_suffix('m')) {
+        (n, SECS_PER_MONTH)
+    } else if let Some(n) = s.strip_suffix('w') {
+        (n, SECS_PER_WEEK)
+    } else if let Some(n) = s.strip_suffix('d') {
+        (n, SECS_PER_DAY)
+    } else if let Some(n) = s.strip_suffix('h') {
+        (n, SECS_PER_HOUR)
+    } else {
+        (s.as_str(), SECS_PER_DAY) // Default to days
+    };
+
+    let num: f64 = num
+        .trim()
+        .parse()
+        .map_err(|_| format!("Invalid duration '{}'", s))?;
+    if num < 0.0 || !num.is_finite() {
+        return Err(format!("Invalid duration '{}'", s));
+    }
+    Ok(Duration::from_secs_f64(num * multiplier))
+}
+
+/// Format a duration in the largest unit that divides it evenly, in the
+/// syntax accepted by [`parse_duration`].
+pub fn format_duration_spec(duration: Duration) -> String {
+    if duration == Duration::MAX {
+        return "unlimited".to_string();
+    }
+    let secs = duration.as_secs() as f64;
+    for (unit, size) in [
+        ("y", SECS_PER_YEAR),
+        ("m", SECS_PER_MONTH),
+        ("w", SECS_PER_WEEK),
+        ("d", SECS_PER_DAY),
+        ("h", SECS_PER_HOUR),
+    ] {
+        if secs >= size && secs % size == 0.0 {
+            return format!("{}{}", secs / size, unit);
+        }
+    }
+    format!("{}h", secs / SECS_PER_HOUR)
+}
+
+/// Days since the Unix epoch of a date in the proleptic Gregorian calendar.
+pub(crate) fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    // Count years from March so the leap day falls at the end.
+    let (month, day) = (i64::from(month), i64::from(day));
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let year_of_era = y - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// The `(year, month, day)` of a day counted from the Unix epoch; the
+/// inverse of [`days_from_civil`].
+pub(crate) fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_from_march = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_from_march + 2) / 5 + 1) as u32;
+    let month = if month_from_march < 10 {
+        month_from_march + 3
+    } else {
+        month_from_march - 9
+    } as u32;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// Serde helpers for durations written as `"30d"` (or integer seconds).
+///
+/// `Duration::MAX` round-trips as `"unlimited"`.
+pub(crate) mod duration_spec {
+    use std::time::Duration;
+
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Raw {
+        Secs(u64),
+        Spec(String),
+    }
+
+    pub fn serialize<S: Serializer>(duration: &Duration, s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_str(&super::format_duration_spec(*duration))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Duration, D::Error> {
+        match Raw::deserialize(d)? {
+            Raw::Secs(secs) => Ok(Duration::from_secs(secs)),
+            Raw::Spec(spec) => super::parse_duration(&spec).map_err(serde::de::Error::custom),
+        }
+    }
+}
+
+/// Serde helpers for byte sizes written as `"1MB"` (or integer bytes).
+///
+/// `u64::MAX` round-trips as `"unlimited"`, since TOML integers are signed.
+pub(crate) mod size_spec {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Raw {
+        Bytes(u64),
+        Spec(String),
+    }
+
+    pub fn serialize<S: Serializer>(size: &u64, s: S) -> Result<S::Ok, S::Error> {
+        if *size > i64::MAX as u64 {
+            s.serialize_str("unlimited")
+        } else {
+            s.serialize_u64(*size)
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<u64, D::Error> {
+        match Raw::deserialize(d)? {
+            Raw::Bytes(bytes) => Ok(bytes),
+            Raw::Spec(spec) => super::parse_size(&spec).map_err(serde::de::Error::custom),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_size() {
+        assert_eq!(parse_size("4096"), Ok(4096));
+        assert_eq!(parse_size("64K"), Ok(64 * 1024));
+        assert_eq!(parse_size("1.5mb"), Ok(1024 * 1024 * 3 / 2));
+        assert_eq!(parse_size("unlimited"), Ok(u64::MAX));
+        assert!(parse_size("lots").is_err());
+    }
+
+    #[test]
+    fn test_duration_round_trip() {
+        for spec in ["45d", "6m", "1y", "2w", "12h"] {
+            let duration = parse_duration(spec).unwrap();
+            assert_eq!(format_duration_spec(duration), spec);
+        }
+        assert_eq!(parse_duration("7"), Ok(Duration::from_secs(7 * 86400)));
+        assert_eq!(parse_duration("6mo"), parse_duration("6m"));
+        assert!(parse_duration("soon").is_err());
+        assert_eq!(parse_duration("unlimited"), Ok(Duration::MAX));
+        assert_eq!(format_duration_spec(Duration::MAX), "unlimited");
+    }
+
+    #[test]
+    fn test_civil_days_round_trip() {
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(-1), (1969, 12, 31));
+        for days in [19_782, 11_016, 60, 59, -719_468] {
+            let (y, m, d) = civil_from_days(days);
+            assert_eq!(days_from_civil(y, m, d), days);
+        }
+        assert_eq!(civil_from_days(days_from_civil(2024, 2, 29)), (2024, 2, 29));
+    }
+}
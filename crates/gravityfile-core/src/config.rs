@@ -1,18 +1,76 @@
 //! Scan configuration types.
 
+use std::fmt;
 use std::path::PathBuf;
+use std::str::FromStr;
 
 use derive_builder::Builder;
 use globset::{Glob, GlobSet, GlobSetBuilder};
 use serde::{Deserialize, Serialize};
 
+/// How per-directory ignore files (`.gitignore`, `.ignore`, `.gravityignore`)
+/// are applied during a scan.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IgnoreMode {
+    /// Ignore files are not read.
+    #[default]
+    Off,
+    /// Ignored entries are left out of the tree and never descended into.
+    Exclude,
+    /// Everything is scanned; ignored entries are marked and tallied in
+    /// [`TreeStats`](crate::TreeStats) so ignored and tracked space can be compared.
+    Report,
+}
+
+impl IgnoreMode {
+    /// Whether ignore files are read at all.
+    #[inline]
+    pub fn is_enabled(self) -> bool {
+        self != IgnoreMode::Off
+    }
+}
+
+impl fmt::Display for IgnoreMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            IgnoreMode::Off => "off",
+            IgnoreMode::Exclude => "exclude",
+            IgnoreMode::Report => "report",
+        })
+    }
+}
+
+impl FromStr for IgnoreMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "off" | "none" => Ok(IgnoreMode::Off),
+            "exclude" | "skip" | "on" => Ok(IgnoreMode::Exclude),
+            "report" | "mark" => Ok(IgnoreMode::Report),
+            other => Err(format!(
+                "Unknown ignore mode '{}' (expected off, exclude or report)",
+                other
+            )),
+        }
+    }
+}
+
 /// Configuration for scanning operations.
 #[derive(Debug, Clone, Builder, Serialize, Deserialize)]
 #[builder(setter(into), build_fn(validate = "Self::validate"))]
 pub struct ScanConfig {
-    /// Root path to scan.
+    /// Root path to scan. Profile files leave this out; the scan path is
+    /// filled in when the profile is applied.
+    #[serde(default)]
     pub root: PathBuf,
 
+    /// Further roots scanned together with `root` into one combined tree.
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extra_roots: Vec<PathBuf>,
+
     /// Follow symbolic links.
     #[builder(default = "false")]
     #[serde(default)]
@@ -38,6 +96,12 @@ pub struct ScanConfig {
     #[serde(default)]
     pub ignore_patterns: Vec<String>,
 
+    /// Apply hierarchical `.gitignore`/`.ignore`/`.gravityignore` files,
+    /// including anchored patterns and `!` negations.
+    #[builder(default)]
+    #[serde(default)]
+    pub ignore_files: IgnoreMode,
+
     /// Number of threads for scanning (0 = auto-detect).
     #[builder(default = "0")]
     #[serde(default)]
@@ -96,11 +160,13 @@ impl ScanConfig {
     pub fn new(root: impl Into<PathBuf>) -> Self {
         let mut config = Self {
             root: root.into(),
+            extra_roots: Vec::new(),
             follow_symlinks: false,
             cross_filesystems: false,
             apparent_size: false,
             max_depth: None,
             ignore_patterns: Vec::new(),
+            ignore_files: IgnoreMode::Off,
             threads: 0,
             include_hidden: true,
             compute_hashes: false,
@@ -111,6 +177,28 @@ impl ScanConfig {
         config
     }
 
+    /// Create a config scanning several roots into one tree.
+    ///
+    /// The first root becomes `root`, the rest `extra_roots`. Returns `None`
+    /// when `roots` is empty.
+    pub fn with_roots<P: Into<PathBuf>>(roots: impl IntoIterator<Item = P>) -> Option<Self> {
+        let mut roots = roots.into_iter().map(Into::into);
+        let mut config = Self::new(roots.next()?);
+        config.extra_roots = roots.collect();
+        Some(config)
+    }
+
+    /// All roots to scan, `root` first.
+    pub fn roots(&self) -> impl Iterator<Item = &PathBuf> {
+        std::iter::once(&self.root).chain(&self.extra_roots)
+    }
+
+    /// Whether this config scans more than one root.
+    #[inline]
+    pub fn is_multi_root(&self) -> bool {
+        !self.extra_roots.is_empty()
+    }
+
     /// Compile ignore patterns into a `GlobSet` for efficient matching.
     /// Call this after modifying `ignore_patterns`.
     pub fn compile_patterns(&mut self) {
@@ -225,6 +313,24 @@ mod tests {
         assert!(!config.should_ignore("rebuild"));
     }
 
+    #[test]
+    fn test_with_roots() {
+        let config = ScanConfig::with_roots(["/data", "/scratch"]).unwrap();
+        assert_eq!(config.root, PathBuf::from("/data"));
+        assert!(config.is_multi_root());
+        assert_eq!(config.roots().count(), 2);
+        assert!(ScanConfig::with_roots(Vec::<PathBuf>::new()).is_none());
+        assert!(!ScanConfig::new("/data").is_multi_root());
+    }
+
+    #[test]
+    fn test_ignore_mode_parse() {
+        assert_eq!("report".parse::<IgnoreMode>(), Ok(IgnoreMode::Report));
+        assert_eq!("Exclude".parse::<IgnoreMode>(), Ok(IgnoreMode::Exclude));
+        assert!("sometimes".parse::<IgnoreMode>().is_err());
+        assert!(!ScanConfig::new("/test").ignore_files.is_enabled());
+    }
+
     #[test]
     fn test_should_skip_hidden() {
         let mut config = ScanConfig::new("/test");
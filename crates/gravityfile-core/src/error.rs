@@ -93,6 +93,8 @@ pub enum WarningKind {
     MetadataError,
     /// Filesystem boundary crossed (when not allowed).
     CrossFilesystem,
+    /// A scan root was skipped because another root already contains it.
+    OverlappingRoot,
 }
 
 /// Non-fatal warning encountered during scan.
@@ -0,0 +1,51 @@
+//! Filesystem capacity queries.
+
+use std::path::Path;
+
+/// Block and inode usage of one filesystem.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FsUsage {
+    /// Total size in bytes.
+    pub total: u64,
+    /// Bytes in use.
+    pub used: u64,
+    /// Bytes available to unprivileged users.
+    pub available: u64,
+    /// Total number of inodes.
+    pub inodes_total: u64,
+    /// Inodes in use.
+    pub inodes_used: u64,
+}
+
+/// Usage of the filesystem containing `path`, from `statvfs`.
+///
+/// Returns `None` when the filesystem cannot be queried or on non-Unix
+/// platforms.
+#[cfg(unix)]
+pub fn fs_usage(path: &Path) -> Option<FsUsage> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let c_path = CString::new(path.as_os_str().as_bytes()).ok()?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    // SAFETY: c_path is a valid NUL-terminated string and stat is a valid out-pointer.
+    if unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) } != 0 {
+        return None;
+    }
+    #[allow(clippy::unnecessary_cast)]
+    {
+        let fragment = stat.f_frsize as u64;
+        Some(FsUsage {
+            total: stat.f_blocks as u64 * fragment,
+            used: (stat.f_blocks as u64).saturating_sub(stat.f_bfree as u64) * fragment,
+            available: stat.f_bavail as u64 * fragment,
+            inodes_total: stat.f_files as u64,
+            inodes_used: (stat.f_files as u64).saturating_sub(stat.f_ffree as u64),
+        })
+    }
+}
+
+#[cfg(not(unix))]
+pub fn fs_usage(_path: &Path) -> Option<FsUsage> {
+    None
+}
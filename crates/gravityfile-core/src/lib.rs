@@ -12,6 +12,7 @@
 //! - [`ScanConfig`] - Configuration for scanning operations
 //! - [`TreeStats`] - Summary statistics for a scanned tree
 //! - [`ContentHash`] - BLAKE3 content hash for duplicate detection
+//! - [`user_name`], [`user_id`] and friends - user and group name lookups
 //!
 //! # Example
 //!
@@ -40,10 +41,16 @@
 
 mod config;
 mod error;
+mod fs_usage;
 mod node;
 mod tree;
+mod users;
 
-pub use config::{ScanConfig, ScanConfigBuilder};
+pub use config::{IgnoreMode, ScanConfig, ScanConfigBuilder};
 pub use error::{ScanError, ScanWarning, WarningKind};
-pub use node::{ContentHash, FileNode, GitStatus, InodeInfo, NodeId, NodeKind, Timestamps};
+pub use fs_usage::{FsUsage, fs_usage};
+pub use node::{
+    ContentHash, FileNode, GitStatus, InodeInfo, NodeId, NodeKind, Ownership, Timestamps,
+};
 pub use tree::{FileTree, TreeStats};
+pub use users::{group_id, group_name, user_id, user_name};
@@ -120,19 +120,74 @@ impl fmt::Display for ContentHash {
 }
 
 /// Inode information for hardlink detection.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+///
+/// Two values are equal when they name the same inode on the same device;
+/// the link count is not compared.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct InodeInfo {
     /// Inode number.
     pub inode: u64,
     /// Device ID.
     pub device: u64,
+    /// Number of hard links to the inode (`st_nlink`), or 0 if unknown.
+    #[serde(default)]
+    pub links: u64,
 }
 
 impl InodeInfo {
     /// Create new inode info.
     #[inline]
     pub fn new(inode: u64, device: u64) -> Self {
-        Self { inode, device }
+        Self {
+            inode,
+            device,
+            links: 0,
+        }
+    }
+
+    /// Set the hard link count.
+    #[inline]
+    pub fn with_links(mut self, links: u64) -> Self {
+        self.links = links;
+        self
+    }
+}
+
+impl PartialEq for InodeInfo {
+    fn eq(&self, other: &Self) -> bool {
+        self.inode == other.inode && self.device == other.device
+    }
+}
+
+impl Eq for InodeInfo {}
+
+impl std::hash::Hash for InodeInfo {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.inode.hash(state);
+        self.device.hash(state);
+    }
+}
+
+/// Owner, group and permission bits of a file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Ownership {
+    /// Numeric user ID.
+    pub uid: u32,
+    /// Numeric group ID.
+    pub gid: u32,
+    /// Permission bits (`st_mode & 0o7777`).
+    pub mode: u32,
+}
+
+impl Ownership {
+    /// Create new ownership info; file-type bits in `mode` are dropped.
+    #[inline]
+    pub fn new(uid: u32, gid: u32, mode: u32) -> Self {
+        Self {
+            uid,
+            gid,
+            mode: mode & 0o7777,
+        }
     }
 }
 
@@ -197,6 +252,12 @@ pub enum NodeKind {
     },
     /// Other file types (sockets, devices, etc.).
     Other,
+    /// Directory on another filesystem that the scan did not enter
+    /// (`cross_filesystems = false`). Its size is not counted.
+    MountPoint {
+        /// Filesystem type of the mount, if known (e.g. "ext4").
+        fs_type: CompactString,
+    },
 }
 
 impl NodeKind {
@@ -217,6 +278,12 @@ impl NodeKind {
     pub fn is_symlink(&self) -> bool {
         matches!(self, NodeKind::Symlink { .. })
     }
+
+    /// Check if this is an unscanned mount point.
+    #[inline]
+    pub fn is_mount_point(&self) -> bool {
+        matches!(self, NodeKind::MountPoint { .. })
+    }
 }
 
 impl fmt::Display for NodeKind {
@@ -228,6 +295,7 @@ impl fmt::Display for NodeKind {
             NodeKind::Symlink { broken: true, .. } => f.write_str("Broken Symlink"),
             NodeKind::Symlink { .. } => f.write_str("Symlink"),
             NodeKind::Other => f.write_str("Other"),
+            NodeKind::MountPoint { .. } => f.write_str("Other Mount"),
         }
     }
 }
@@ -256,6 +324,10 @@ pub struct FileNode {
     /// Inode info for hardlink detection.
     pub inode: Option<InodeInfo>,
 
+    /// Owner, group and permission bits (Unix scans only).
+    #[serde(default)]
+    pub owner: Option<Ownership>,
+
     /// Content hash (computed on demand). Stored inline — 32 bytes, no heap allocation.
     pub content_hash: Option<ContentHash>,
 
@@ -285,6 +357,7 @@ impl FileNode {
             blocks,
             timestamps,
             inode: None,
+            owner: None,
             content_hash: None,
             git_status: None,
             children: Vec::new(),
@@ -308,6 +381,31 @@ impl FileNode {
             blocks: 0,
             timestamps,
             inode: None,
+            owner: None,
+            content_hash: None,
+            git_status: None,
+            children: Vec::new(),
+        }
+    }
+
+    /// Create a stub for a directory on another filesystem.
+    pub fn new_mount_point(
+        id: NodeId,
+        name: impl Into<CompactString>,
+        fs_type: impl Into<CompactString>,
+        timestamps: Timestamps,
+    ) -> Self {
+        Self {
+            id,
+            name: name.into(),
+            kind: NodeKind::MountPoint {
+                fs_type: fs_type.into(),
+            },
+            size: 0,
+            blocks: 0,
+            timestamps,
+            inode: None,
+            owner: None,
             content_hash: None,
             git_status: None,
             children: Vec::new(),
@@ -333,12 +431,13 @@ impl FileNode {
     }
 
     /// Get file count for directories (includes files, symlinks, and other entries),
-    /// 1 for files/symlinks/other.
+    /// 1 for files/symlinks/other, 0 for unscanned mount points.
     #[inline]
     pub fn file_count(&self) -> u64 {
         match &self.kind {
             NodeKind::Directory { file_count, .. } => *file_count,
             NodeKind::File { .. } | NodeKind::Symlink { .. } | NodeKind::Other => 1,
+            NodeKind::MountPoint { .. } => 0,
         }
     }
 
@@ -368,7 +467,8 @@ impl FileNode {
     /// Recursively update directory file/dir counts based on children (post-order traversal).
     ///
     /// This recurses into all children first, then aggregates counts upward.
-    /// Symlinks and Other entries are counted in the file count.
+    /// Symlinks and Other entries are counted in the file count, mount points
+    /// in the directory count.
     pub fn update_counts(&mut self) {
         if let NodeKind::Directory {
             ref mut file_count,
@@ -395,6 +495,7 @@ impl FileNode {
                         *file_count += fc;
                         *dir_count += dc + 1;
                     }
+                    NodeKind::MountPoint { .. } => *dir_count += 1,
                 }
             }
         }
@@ -522,6 +623,7 @@ mod tests {
             blocks: 0,
             timestamps: Timestamps::with_modified(now),
             inode: None,
+            owner: None,
             content_hash: None,
             git_status: None,
             children: Vec::new(),
@@ -532,6 +634,23 @@ mod tests {
         assert_eq!(root.file_count(), 2); // file + symlink
     }
 
+    #[test]
+    fn test_update_counts_mount_point() {
+        let now = SystemTime::now();
+        let mut root =
+            FileNode::new_directory(NodeId::new(1), "root", Timestamps::with_modified(now));
+        root.children.push(FileNode::new_mount_point(
+            NodeId::new(2),
+            "mnt",
+            "ext4",
+            Timestamps::with_modified(now),
+        ));
+        root.update_counts();
+        assert_eq!(root.file_count(), 0);
+        assert_eq!(root.dir_count(), 1);
+        assert_eq!(root.children[0].kind.to_string(), "Other Mount");
+    }
+
     #[test]
     fn test_sort_deterministic() {
         let now = SystemTime::now();
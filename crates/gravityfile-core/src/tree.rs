@@ -28,6 +28,13 @@ pub struct TreeStats {
     pub oldest_file: Option<(PathBuf, SystemTime)>,
     /// Newest file (path, time).
     pub newest_file: Option<(PathBuf, SystemTime)>,
+    /// Bytes in files matched by ignore files (only tallied in
+    /// [`IgnoreMode::Report`](crate::IgnoreMode::Report) scans).
+    #[serde(default)]
+    pub ignored_size: u64,
+    /// Number of files matched by ignore files.
+    #[serde(default)]
+    pub ignored_files: u64,
 }
 
 impl TreeStats {
@@ -58,6 +65,18 @@ impl TreeStats {
         }
     }
 
+    /// Count an already-recorded file as ignored.
+    pub fn record_ignored(&mut self, size: u64) {
+        self.ignored_files += 1;
+        self.ignored_size += size;
+    }
+
+    /// Bytes in files not matched by any ignore file.
+    #[inline]
+    pub fn tracked_size(&self) -> u64 {
+        self.total_size.saturating_sub(self.ignored_size)
+    }
+
     /// Record a directory.
     pub fn record_dir(&mut self, depth: u32) {
         self.total_dirs += 1;
@@ -78,9 +97,15 @@ pub struct FileTree {
     /// Root node of the tree.
     pub root: FileNode,
 
-    /// Root path that was scanned.
+    /// Root path that was scanned. For multi-root scans this is the deepest
+    /// common ancestor of the roots, and the synthetic top node's children are
+    /// named by their path relative to it.
     pub root_path: PathBuf,
 
+    /// Roots combined under a synthetic top node (empty for single-root scans).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub roots: Vec<PathBuf>,
+
     /// When this scan was performed.
     pub scanned_at: SystemTime,
 
@@ -110,6 +135,7 @@ impl FileTree {
         Self {
             root,
             root_path,
+            roots: Vec::new(),
             scanned_at: SystemTime::now(),
             scan_duration,
             config,
@@ -136,6 +162,12 @@ impl FileTree {
         self.stats.total_dirs
     }
 
+    /// Whether this tree combines several scan roots.
+    #[inline]
+    pub fn is_multi_root(&self) -> bool {
+        !self.roots.is_empty()
+    }
+
     /// Check if there were any warnings during scanning.
     #[inline]
     pub fn has_warnings(&self) -> bool {
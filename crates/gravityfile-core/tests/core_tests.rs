@@ -121,6 +121,15 @@ fn test_inode_info_inequality() {
     assert_ne!(a, c);
 }
 
+#[test]
+fn test_inode_info_links_not_compared() {
+    let a = InodeInfo::new(7, 1).with_links(3);
+    let b = InodeInfo::new(7, 1);
+    assert_eq!(a.links, 3);
+    assert_eq!(b.links, 0);
+    assert_eq!(a, b);
+}
+
 // ---------------------------------------------------------------------------
 // Timestamps
 // ---------------------------------------------------------------------------
@@ -467,6 +476,7 @@ fn test_update_counts_includes_symlinks_and_other() {
         blocks: 0,
         timestamps: Timestamps::with_modified(now),
         inode: None,
+        owner: None,
         content_hash: None,
         git_status: None,
         children: Vec::new(),
@@ -479,6 +489,7 @@ fn test_update_counts_includes_symlinks_and_other() {
         blocks: 0,
         timestamps: Timestamps::with_modified(now),
         inode: None,
+        owner: None,
         content_hash: None,
         git_status: None,
         children: Vec::new(),
@@ -694,6 +705,7 @@ fn test_symlink_node() {
         blocks: 0,
         timestamps: Timestamps::with_modified(now),
         inode: None,
+        owner: None,
         content_hash: None,
         git_status: None,
         children: Vec::new(),
@@ -719,6 +731,7 @@ fn test_symlink_node() {
         blocks: 0,
         timestamps: Timestamps::with_modified(now),
         inode: None,
+        owner: None,
         content_hash: None,
         git_status: None,
         children: Vec::new(),
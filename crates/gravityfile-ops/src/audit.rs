@@ -0,0 +1,141 @@
+//! Append-only audit log of automated deletions.
+//!
+//! Each line of the log is one JSON record, so the file can be appended to
+//! without rewriting it and inspected with ordinary line-oriented tools.
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Default location of the audit log.
+pub fn default_audit_path() -> Option<PathBuf> {
+    dirs::data_dir().map(|d| d.join("gravityfile").join("audit.log"))
+}
+
+/// One audited action on one path.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditRecord {
+    /// When the action was performed.
+    pub time: DateTime<Utc>,
+    /// What was done, e.g. "trash" or "delete".
+    pub action: String,
+    /// Path acted on.
+    pub path: PathBuf,
+    /// Size of the path in bytes.
+    pub size: u64,
+    /// Why the path was acted on, e.g. the name of a policy rule.
+    pub reason: String,
+    /// Error message if the action failed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl AuditRecord {
+    /// A successful action performed now.
+    pub fn new(
+        action: impl Into<String>,
+        path: impl Into<PathBuf>,
+        size: u64,
+        reason: impl Into<String>,
+    ) -> Self {
+        Self {
+            time: Utc::now(),
+            action: action.into(),
+            path: path.into(),
+            size,
+            reason: reason.into(),
+            error: None,
+        }
+    }
+
+    /// Mark the action as failed with `error`.
+    pub fn with_error(mut self, error: impl Into<String>) -> Self {
+        self.error = Some(error.into());
+        self
+    }
+}
+
+/// An audit log file.
+#[derive(Debug, Clone)]
+pub struct AuditLog {
+    path: PathBuf,
+}
+
+impl AuditLog {
+    /// Audit log at `path`. The file is created on first append.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Path of the log file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append records to the log.
+    pub fn append(&self, records: &[AuditRecord]) -> Result<(), String> {
+        if let Some(parent) = self.path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create audit log directory: {}", e))?;
+        }
+
+        let mut lines = Vec::new();
+        for record in records {
+            serde_json::to_writer(&mut lines, record)
+                .map_err(|e| format!("Failed to serialize audit record: {}", e))?;
+            lines.push(b'\n');
+        }
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .map_err(|e| format!("Failed to open audit log: {}", e))?;
+        file.write_all(&lines)
+            .map_err(|e| format!("Failed to write audit log: {}", e))
+    }
+
+    /// Read every record in the log. A missing log has no records.
+    pub fn read(&self) -> Result<Vec<AuditRecord>, String> {
+        let content = match fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(format!("Failed to read audit log: {}", e)),
+        };
+        content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .enumerate()
+            .map(|(i, line)| {
+                serde_json::from_str(line)
+                    .map_err(|e| format!("Invalid audit record on line {}: {}", i + 1, e))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_append_and_read() {
+        let temp = tempfile::tempdir().unwrap();
+        let log = AuditLog::new(temp.path().join("nested").join("audit.log"));
+        assert!(log.read().unwrap().is_empty());
+
+        let first = vec![AuditRecord::new("trash", "/a", 42, "logs")];
+        let second =
+            vec![AuditRecord::new("trash", "/b", 7, "logs").with_error("Permission denied")];
+        log.append(&first).unwrap();
+        log.append(&second).unwrap();
+
+        let records = log.read().unwrap();
+        assert_eq!(records, [first, second].concat());
+    }
+}
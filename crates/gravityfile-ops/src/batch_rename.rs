@@ -0,0 +1,798 @@
+//! Rule-based bulk rename.
+//!
+//! A [`RenameRule`] turns each file name into a new one, either by replacing
+//! regex matches (with `$1` / `${name}` capture references) or by filling a
+//! template. Both the replacement and the template may contain tokens:
+//!
+//! | Token | Value |
+//! |-------|-------|
+//! | `{name}` | Full original name |
+//! | `{stem}` | Name without its extension |
+//! | `{ext}` | Extension without the dot (empty if none) |
+//! | `{parent}` | Name of the containing directory |
+//! | `{size}` | Size in bytes |
+//! | `{mtime}` / `{mtime:%Y%m%d}` | Local modification time, strftime format (default `%Y-%m-%d`) |
+//! | `{n}` / `{n:3}` | Counter, zero-padded to the given width |
+//!
+//! `{{` and `}}` produce literal braces. [`preview_renames`] computes every
+//! new name up front and flags collisions, so a batch can be checked before
+//! [`start_batch_rename`] runs it.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Local};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+
+use crate::progress::{OperationComplete, OperationProgress, OperationType};
+use crate::rename::validate_filename;
+use crate::{OPERATION_CHANNEL_SIZE, OperationError};
+
+/// Default strftime format of the `{mtime}` token.
+const DEFAULT_MTIME_FORMAT: &str = "%Y-%m-%d";
+
+/// Case conversion applied to the new name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CaseTransform {
+    /// Leave the case alone.
+    #[default]
+    Keep,
+    /// all lowercase.
+    Lower,
+    /// ALL UPPERCASE.
+    Upper,
+    /// First Letter Of Every Word Uppercase.
+    Title,
+}
+
+impl CaseTransform {
+    /// Cycle to the next transform.
+    pub fn next(self) -> Self {
+        match self {
+            Self::Keep => Self::Lower,
+            Self::Lower => Self::Upper,
+            Self::Upper => Self::Title,
+            Self::Title => Self::Keep,
+        }
+    }
+
+    /// Apply the transform to `s`.
+    pub fn apply(self, s: &str) -> String {
+        match self {
+            Self::Keep => s.to_string(),
+            Self::Lower => s.to_lowercase(),
+            Self::Upper => s.to_uppercase(),
+            Self::Title => {
+                let mut out = String::with_capacity(s.len());
+                let mut word_start = true;
+                for c in s.chars() {
+                    if c.is_alphanumeric() {
+                        if word_start {
+                            out.extend(c.to_uppercase());
+                        } else {
+                            out.extend(c.to_lowercase());
+                        }
+                        word_start = false;
+                    } else {
+                        out.push(c);
+                        word_start = true;
+                    }
+                }
+                out
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for CaseTransform {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Keep => write!(f, "keep"),
+            Self::Lower => write!(f, "lower"),
+            Self::Upper => write!(f, "upper"),
+            Self::Title => write!(f, "title"),
+        }
+    }
+}
+
+impl std::str::FromStr for CaseTransform {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "keep" | "none" => Ok(Self::Keep),
+            "lower" | "lowercase" => Ok(Self::Lower),
+            "upper" | "uppercase" => Ok(Self::Upper),
+            "title" | "titlecase" => Ok(Self::Title),
+            _ => Err(format!("Unknown case transform: {}", s)),
+        }
+    }
+}
+
+/// How to derive new names for a batch of files.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RenameRule {
+    /// Regex to replace; empty means `replace` is a template for the whole name.
+    pub find: String,
+    /// Replacement (with captures and tokens) or name template (with tokens).
+    pub replace: String,
+    /// Case conversion applied last.
+    pub case: CaseTransform,
+    /// Value of `{n}` for the first file.
+    pub counter_start: u64,
+    /// Increment of `{n}` between files.
+    pub counter_step: u64,
+}
+
+impl Default for RenameRule {
+    fn default() -> Self {
+        Self {
+            find: String::new(),
+            replace: String::new(),
+            case: CaseTransform::Keep,
+            counter_start: 1,
+            counter_step: 1,
+        }
+    }
+}
+
+impl RenameRule {
+    /// Rule that fills `template` for every file.
+    pub fn template(template: impl Into<String>) -> Self {
+        Self {
+            replace: template.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Rule that replaces every match of `find` with `replace`.
+    pub fn regex(find: impl Into<String>, replace: impl Into<String>) -> Self {
+        Self {
+            find: find.into(),
+            replace: replace.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Set the case transform.
+    pub fn with_case(mut self, case: CaseTransform) -> Self {
+        self.case = case;
+        self
+    }
+
+    /// Set the counter start and step.
+    pub fn with_counter(mut self, start: u64, step: u64) -> Self {
+        self.counter_start = start;
+        self.counter_step = step;
+        self
+    }
+
+    /// Check that the regex and template are well-formed.
+    pub fn validate(&self) -> Result<(), String> {
+        self.compile().map(|_| ())
+    }
+
+    fn compile(&self) -> Result<CompiledRule<'_>, String> {
+        let regex = if self.find.is_empty() {
+            None
+        } else {
+            Some(Regex::new(&self.find).map_err(|e| format!("Invalid regex: {}", e))?)
+        };
+        let template = if regex.is_none() && self.replace.is_empty() {
+            vec![Segment::Token(Token::Name)]
+        } else {
+            parse_template(&self.replace)?
+        };
+        Ok(CompiledRule {
+            regex,
+            template,
+            rule: self,
+        })
+    }
+}
+
+/// A template token.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Name,
+    Stem,
+    Ext,
+    Parent,
+    Size,
+    Mtime(String),
+    Counter(usize),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Literal(String),
+    Token(Token),
+}
+
+fn parse_template(template: &str) -> Result<Vec<Segment>, String> {
+    let mut segments = Vec::new();
+    let mut literal = String::new();
+    let mut chars = template.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            // `${1}` / `${name}` are regex capture references, not tokens.
+            '$' if chars.peek() == Some(&'{') => {
+                literal.push('$');
+                for c in chars.by_ref() {
+                    literal.push(c);
+                    if c == '}' {
+                        break;
+                    }
+                }
+            }
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                literal.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                literal.push('}');
+            }
+            '{' => {
+                let mut body = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => body.push(c),
+                        None => return Err(format!("Unclosed token '{{{}'", body)),
+                    }
+                }
+                if !literal.is_empty() {
+                    segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                }
+                segments.push(Segment::Token(parse_token(&body)?));
+            }
+            '}' => return Err("Unmatched '}' (use '}}' for a literal brace)".to_string()),
+            c => literal.push(c),
+        }
+    }
+    if !literal.is_empty() {
+        segments.push(Segment::Literal(literal));
+    }
+    Ok(segments)
+}
+
+fn parse_token(body: &str) -> Result<Token, String> {
+    let (name, arg) = match body.split_once(':') {
+        Some((name, arg)) => (name, Some(arg)),
+        None => (body, None),
+    };
+    let token = match (name, arg) {
+        ("name", None) => Token::Name,
+        ("stem", None) => Token::Stem,
+        ("ext", None) => Token::Ext,
+        ("parent", None) => Token::Parent,
+        ("size", None) => Token::Size,
+        ("mtime", fmt) => {
+            let fmt = fmt.unwrap_or(DEFAULT_MTIME_FORMAT);
+            if chrono::format::StrftimeItems::new(fmt)
+                .any(|item| item == chrono::format::Item::Error)
+            {
+                return Err(format!("Invalid time format '{}'", fmt));
+            }
+            Token::Mtime(fmt.to_string())
+        }
+        ("n", None) => Token::Counter(0),
+        ("n", Some(width)) => Token::Counter(
+            width
+                .parse()
+                .map_err(|_| format!("Invalid counter width '{}'", width))?,
+        ),
+        _ => return Err(format!("Unknown token '{{{}}}'", body)),
+    };
+    Ok(token)
+}
+
+struct CompiledRule<'a> {
+    regex: Option<Regex>,
+    template: Vec<Segment>,
+    rule: &'a RenameRule,
+}
+
+impl CompiledRule<'_> {
+    /// New name of `path`, the `index`-th file of the batch.
+    fn apply(&self, path: &Path, index: usize) -> Result<String, String> {
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .ok_or_else(|| "Path has no file name".to_string())?;
+        let meta = fs::symlink_metadata(path).ok();
+
+        let mut expanded = String::new();
+        for segment in &self.template {
+            match segment {
+                Segment::Literal(text) => expanded.push_str(text),
+                Segment::Token(token) => {
+                    let value = self.token_value(token, path, &name, meta.as_ref(), index)?;
+                    // Token values are literal text inside a regex replacement.
+                    if self.regex.is_some() {
+                        expanded.push_str(&value.replace('$', "$$"));
+                    } else {
+                        expanded.push_str(&value);
+                    }
+                }
+            }
+        }
+
+        let renamed = match &self.regex {
+            Some(regex) => regex.replace_all(&name, expanded.as_str()).into_owned(),
+            None => expanded,
+        };
+        Ok(self.rule.case.apply(&renamed))
+    }
+
+    fn token_value(
+        &self,
+        token: &Token,
+        path: &Path,
+        name: &str,
+        meta: Option<&fs::Metadata>,
+        index: usize,
+    ) -> Result<String, String> {
+        let (stem, ext) = split_extension(name);
+        let value = match token {
+            Token::Name => name.to_string(),
+            Token::Stem => stem.to_string(),
+            Token::Ext => ext.to_string(),
+            Token::Parent => path
+                .parent()
+                .and_then(Path::file_name)
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            Token::Size => meta.map_or(0, |m| m.len()).to_string(),
+            Token::Mtime(fmt) => {
+                let modified = meta
+                    .and_then(|m| m.modified().ok())
+                    .ok_or_else(|| "Modification time unavailable".to_string())?;
+                let mut out = String::new();
+                write!(out, "{}", DateTime::<Local>::from(modified).format(fmt))
+                    .map_err(|_| format!("Invalid time format '{}'", fmt))?;
+                out
+            }
+            Token::Counter(width) => {
+                let step = self.rule.counter_step.saturating_mul(index as u64);
+                let n = self.rule.counter_start.saturating_add(step);
+                format!("{:0width$}", n, width = *width)
+            }
+        };
+        Ok(value)
+    }
+}
+
+/// Split `name` into stem and extension; dotfiles have no extension.
+fn split_extension(name: &str) -> (&str, &str) {
+    match name.rfind('.') {
+        Some(0) | None => (name, ""),
+        Some(i) => (&name[..i], &name[i + 1..]),
+    }
+}
+
+/// Outcome of a rule for one file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", content = "reason", rename_all = "snake_case")]
+pub enum RenameStatus {
+    /// The file will be renamed.
+    Ready,
+    /// The rule leaves the name as it is.
+    Unchanged,
+    /// The new name is not a valid file name.
+    Invalid(String),
+    /// The new name clashes with another file or another new name.
+    Collision(String),
+}
+
+/// Previewed rename of one file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RenamePreview {
+    /// The file being renamed.
+    pub source: PathBuf,
+    /// Its new name (the old name when unchanged or invalid).
+    pub new_name: String,
+    /// Whether the rename can go ahead.
+    #[serde(flatten)]
+    pub status: RenameStatus,
+}
+
+impl RenamePreview {
+    /// Full path after the rename.
+    pub fn target(&self) -> PathBuf {
+        self.source.with_file_name(&self.new_name)
+    }
+
+    /// Whether this entry blocks the batch.
+    pub fn is_problem(&self) -> bool {
+        matches!(
+            self.status,
+            RenameStatus::Invalid(_) | RenameStatus::Collision(_)
+        )
+    }
+}
+
+/// Apply `rule` to `paths` (numbered in the given order) and check the
+/// resulting names for collisions, without touching the filesystem.
+///
+/// A name collides when two files would get the same path, or when the path
+/// is taken by a file that is not itself renamed away in the batch.
+pub fn preview_renames(paths: &[PathBuf], rule: &RenameRule) -> Result<Vec<RenamePreview>, String> {
+    let compiled = rule.compile()?;
+    let mut previews: Vec<RenamePreview> = paths
+        .iter()
+        .enumerate()
+        .map(|(i, path)| {
+            let old_name = path
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            let (new_name, status) = match compiled.apply(path, i) {
+                Ok(new_name) if new_name == old_name => (new_name, RenameStatus::Unchanged),
+                Ok(new_name) => match validate_filename(&new_name) {
+                    Ok(()) => (new_name, RenameStatus::Ready),
+                    Err(e) => (new_name, RenameStatus::Invalid(e)),
+                },
+                Err(e) => (old_name, RenameStatus::Invalid(e)),
+            };
+            RenamePreview {
+                source: path.clone(),
+                new_name,
+                status,
+            }
+        })
+        .collect();
+    mark_collisions(&mut previews);
+    Ok(previews)
+}
+
+/// Check explicit `(path, new_name)` pairs the way [`preview_renames`] does.
+pub fn check_renames(renames: &[(PathBuf, String)]) -> Vec<RenamePreview> {
+    let mut previews: Vec<RenamePreview> = renames
+        .iter()
+        .map(|(source, new_name)| {
+            let status = if source.file_name().is_some_and(|n| n == new_name.as_str()) {
+                RenameStatus::Unchanged
+            } else {
+                match validate_filename(new_name) {
+                    Ok(()) => RenameStatus::Ready,
+                    Err(e) => RenameStatus::Invalid(e),
+                }
+            };
+            RenamePreview {
+                source: source.clone(),
+                new_name: new_name.clone(),
+                status,
+            }
+        })
+        .collect();
+    mark_collisions(&mut previews);
+    previews
+}
+
+/// Flag previews whose targets clash with each other or with existing files.
+fn mark_collisions(previews: &mut [RenamePreview]) {
+    // Paths freed by the batch: every source that is actually renamed.
+    let moving: HashSet<PathBuf> = previews
+        .iter()
+        .filter(|p| p.status == RenameStatus::Ready)
+        .map(|p| p.source.clone())
+        .collect();
+    // Paths still occupied after the batch: sources that stay put.
+    let staying: HashMap<PathBuf, PathBuf> = previews
+        .iter()
+        .filter(|p| p.status != RenameStatus::Ready)
+        .map(|p| (p.source.clone(), p.source.clone()))
+        .collect();
+
+    let mut claimed: HashMap<PathBuf, PathBuf> = HashMap::new();
+    for preview in previews.iter_mut() {
+        if preview.status != RenameStatus::Ready {
+            continue;
+        }
+        let target = preview.target();
+        if let Some(other) = claimed.get(&target).or_else(|| staying.get(&target)) {
+            preview.status = RenameStatus::Collision(format!(
+                "Same name as {}",
+                other.file_name().unwrap_or_default().to_string_lossy()
+            ));
+        } else if !moving.contains(&target)
+            && fs::symlink_metadata(&target).is_ok()
+            && !same_file(&preview.source, &target)
+        {
+            preview.status = RenameStatus::Collision("Target already exists".to_string());
+        } else {
+            claimed.insert(target, preview.source.clone());
+        }
+    }
+}
+
+/// Whether two paths name the same file, as with a case-only rename on a
+/// case-insensitive filesystem.
+#[cfg(unix)]
+fn same_file(a: &Path, b: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    match (fs::symlink_metadata(a), fs::symlink_metadata(b)) {
+        (Ok(a), Ok(b)) => a.dev() == b.dev() && a.ino() == b.ino(),
+        _ => false,
+    }
+}
+
+#[cfg(not(unix))]
+fn same_file(a: &Path, b: &Path) -> bool {
+    a.to_string_lossy().to_lowercase() == b.to_string_lossy().to_lowercase()
+}
+
+/// Result sent through the channel during batch renames.
+#[derive(Debug)]
+pub enum BatchRenameResult {
+    /// Progress update.
+    Progress(OperationProgress),
+    /// The operation completed.
+    Complete(BatchRenameComplete),
+}
+
+/// Completion of a batch rename.
+#[derive(Debug)]
+pub struct BatchRenameComplete {
+    /// Standard operation completion info.
+    pub inner: OperationComplete,
+    /// `(original, renamed)` pairs of every completed rename.
+    pub renamed: Vec<(PathBuf, PathBuf)>,
+}
+
+/// Start an async batch rename of `(path, new_name)` pairs.
+///
+/// Renames whose target is another source of the batch (swaps, shifted
+/// counters, case-only changes) go through a temporary name first, so the
+/// order of the pairs does not matter.
+pub fn start_batch_rename(
+    renames: Vec<(PathBuf, String)>,
+    token: CancellationToken,
+) -> mpsc::Receiver<BatchRenameResult> {
+    let (tx, rx) = mpsc::channel(OPERATION_CHANNEL_SIZE);
+
+    tokio::spawn(async move {
+        let progress_tx = tx.clone();
+        let result = tokio::task::spawn_blocking(move || {
+            batch_rename_impl(renames, &token, |p| {
+                let _ = progress_tx.blocking_send(BatchRenameResult::Progress(p));
+            })
+        })
+        .await;
+        let complete = result.unwrap_or_else(|e| BatchRenameComplete {
+            inner: OperationComplete {
+                operation_type: OperationType::Rename,
+                succeeded: 0,
+                failed: 1,
+                bytes_processed: 0,
+                errors: vec![OperationError::new(
+                    PathBuf::new(),
+                    format!("Task failed: {}", e),
+                )],
+            },
+            renamed: Vec::new(),
+        });
+        let _ = tx.send(BatchRenameResult::Complete(complete)).await;
+    });
+
+    rx
+}
+
+fn batch_rename_impl(
+    renames: Vec<(PathBuf, String)>,
+    token: &CancellationToken,
+    report: impl Fn(OperationProgress),
+) -> BatchRenameComplete {
+    let mut progress = OperationProgress::new(OperationType::Rename, renames.len(), 0);
+    let mut renamed = Vec::new();
+    let mut failed = 0;
+
+    let sources: HashSet<PathBuf> = renames.iter().map(|(p, _)| p.clone()).collect();
+    let mut staged = Vec::new();
+
+    for (i, (source, new_name)) in renames.into_iter().enumerate() {
+        if token.is_cancelled() {
+            break;
+        }
+        progress.set_current_file(Some(source.clone()));
+        report(progress.clone());
+
+        if let Err(e) = validate_filename(&new_name) {
+            progress.add_error(OperationError::new(source, e));
+            failed += 1;
+            continue;
+        }
+        let target = source.with_file_name(&new_name);
+        if target == source {
+            continue;
+        }
+
+        let result = if sources.contains(&target) || same_file(&source, &target) {
+            // Step aside first; the target is freed later in this batch.
+            let temp = source.with_file_name(format!(
+                ".{}.grav-rename-{}-{}",
+                new_name,
+                std::process::id(),
+                i
+            ));
+            fs::rename(&source, &temp).map(|()| staged.push((source.clone(), temp, target)))
+        } else if fs::symlink_metadata(&target).is_ok() {
+            Err(std::io::Error::new(
+                std::io::ErrorKind::AlreadyExists,
+                format!("'{}' already exists", new_name),
+            ))
+        } else {
+            fs::rename(&source, &target).map(|()| {
+                progress.complete_file(0);
+                renamed.push((source.clone(), target));
+            })
+        };
+        if let Err(e) = result {
+            progress.add_error(OperationError::new(
+                source,
+                format!("Failed to rename: {}", e),
+            ));
+            failed += 1;
+        }
+    }
+
+    // Second pass: move staged items into their freed targets.
+    for (source, temp, target) in staged {
+        let result = if fs::symlink_metadata(&target).is_ok() {
+            Err(format!(
+                "'{}' is still taken",
+                target.file_name().unwrap_or_default().to_string_lossy()
+            ))
+        } else {
+            fs::rename(&temp, &target).map_err(|e| format!("Failed to rename: {}", e))
+        };
+        match result {
+            Ok(()) => {
+                progress.complete_file(0);
+                renamed.push((source, target));
+            }
+            Err(e) => {
+                // Put it back under its original name if that is still free.
+                let _ = fs::rename(&temp, &source);
+                progress.add_error(OperationError::new(source, e));
+                failed += 1;
+            }
+        }
+    }
+    report(progress.clone());
+
+    BatchRenameComplete {
+        inner: OperationComplete {
+            operation_type: OperationType::Rename,
+            succeeded: renamed.len(),
+            failed,
+            bytes_processed: 0,
+            errors: progress.errors,
+        },
+        renamed,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn files(dir: &Path, names: &[&str]) -> Vec<PathBuf> {
+        names
+            .iter()
+            .map(|name| {
+                let path = dir.join(name);
+                fs::write(&path, name).unwrap();
+                path
+            })
+            .collect()
+    }
+
+    fn names(previews: &[RenamePreview]) -> Vec<&str> {
+        previews.iter().map(|p| p.new_name.as_str()).collect()
+    }
+
+    async fn run(renames: Vec<(PathBuf, String)>) -> BatchRenameComplete {
+        let mut rx = start_batch_rename(renames, CancellationToken::new());
+        while let Some(result) = rx.recv().await {
+            if let BatchRenameResult::Complete(c) = result {
+                return c;
+            }
+        }
+        panic!("batch rename ended without completing");
+    }
+
+    #[test]
+    fn test_regex_with_captures_and_case() {
+        let temp = TempDir::new().unwrap();
+        let paths = files(temp.path(), &["IMG_0001.JPG", "IMG_0002.JPG"]);
+        let rule =
+            RenameRule::regex(r"IMG_(\d+)\.(\w+)", "photo-${1}.$2").with_case(CaseTransform::Lower);
+        let previews = preview_renames(&paths, &rule).unwrap();
+        assert_eq!(names(&previews), ["photo-0001.jpg", "photo-0002.jpg"]);
+        assert!(previews.iter().all(|p| p.status == RenameStatus::Ready));
+    }
+
+    #[test]
+    fn test_template_tokens_and_counter() {
+        let temp = TempDir::new().unwrap();
+        let paths = files(temp.path(), &["a.txt", "b.md"]);
+        let rule = RenameRule::template("{parent}_{n:3}_{size}.{ext}").with_counter(9, 2);
+        let previews = preview_renames(&paths, &rule).unwrap();
+        let parent = temp.path().file_name().unwrap().to_string_lossy();
+        assert_eq!(previews[0].new_name, format!("{}_009_5.txt", parent));
+        assert_eq!(previews[1].new_name, format!("{}_011_4.md", parent));
+
+        let rule = RenameRule::regex("^", "{mtime:%Y}-");
+        let year = Local::now().format("%Y").to_string();
+        let previews = preview_renames(&paths, &rule).unwrap();
+        assert_eq!(previews[0].new_name, format!("{}-a.txt", year));
+    }
+
+    #[test]
+    fn test_invalid_rules() {
+        assert!(RenameRule::regex("(", "x").validate().is_err());
+        assert!(RenameRule::template("{bogus}").validate().is_err());
+        assert!(RenameRule::template("{n").validate().is_err());
+        assert!(RenameRule::template("{n:x}").validate().is_err());
+        assert!(RenameRule::template("{{literal}}").validate().is_ok());
+    }
+
+    #[test]
+    fn test_collisions() {
+        let temp = TempDir::new().unwrap();
+        let paths = files(temp.path(), &["a1.txt", "a2.txt", "b.txt"]);
+        fs::write(temp.path().join("taken.txt"), "").unwrap();
+
+        // Two sources mapping to one name.
+        let previews = preview_renames(&paths[..2], &RenameRule::regex(r"\d", "")).unwrap();
+        assert_eq!(previews[0].status, RenameStatus::Ready);
+        assert!(matches!(previews[1].status, RenameStatus::Collision(_)));
+
+        // An existing file outside the batch.
+        let previews = preview_renames(&paths[2..], &RenameRule::template("taken.txt")).unwrap();
+        assert!(matches!(previews[0].status, RenameStatus::Collision(_)));
+
+        // A file staying in place keeps its name.
+        let batch = vec![paths[0].clone(), paths[2].clone()];
+        let previews = preview_renames(&batch, &RenameRule::regex("a1", "b")).unwrap();
+        assert!(matches!(previews[0].status, RenameStatus::Collision(_)));
+        assert_eq!(previews[1].status, RenameStatus::Unchanged);
+    }
+
+    #[tokio::test]
+    async fn test_batch_rename_handles_swaps() {
+        let temp = TempDir::new().unwrap();
+        let paths = files(temp.path(), &["1.txt", "2.txt", "3.txt"]);
+        let rule = RenameRule::template("{n}.txt").with_counter(2, 1);
+        let previews = preview_renames(&paths, &rule).unwrap();
+        assert!(previews.iter().all(|p| !p.is_problem()));
+
+        let renames = previews
+            .into_iter()
+            .map(|p| (p.source, p.new_name))
+            .collect();
+        let complete = run(renames).await;
+        assert_eq!((complete.inner.succeeded, complete.inner.failed), (3, 0));
+        assert_eq!(
+            fs::read_to_string(temp.path().join("2.txt")).unwrap(),
+            "1.txt"
+        );
+        assert_eq!(
+            fs::read_to_string(temp.path().join("4.txt")).unwrap(),
+            "3.txt"
+        );
+        assert!(!temp.path().join("1.txt").exists());
+    }
+}
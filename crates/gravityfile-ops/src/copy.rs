@@ -1,14 +1,16 @@
 //! Async copy operation with progress reporting.
 
-use std::collections::HashSet;
 use std::fs;
 use std::path::{Path, PathBuf};
 
+use serde::{Deserialize, Serialize};
 use tokio::sync::mpsc;
 use tokio_util::sync::CancellationToken;
 
 use crate::conflict::{Conflict, ConflictKind, ConflictResolution, auto_rename_path};
+use crate::jobs::PauseToken;
 use crate::progress::{OperationComplete, OperationProgress, OperationType};
+use crate::transfer;
 use crate::{OPERATION_CHANNEL_SIZE, OperationError};
 
 /// Result sent through the channel during copy operations.
@@ -19,16 +21,66 @@ pub enum CopyResult {
     /// A conflict was detected that needs resolution.
     Conflict(Conflict),
     /// The operation completed.
-    Complete(OperationComplete),
+    Complete(CopyComplete),
+}
+
+/// Completion result for copy operations, including the created paths
+/// needed by the undo system.
+#[derive(Debug)]
+pub struct CopyComplete {
+    /// Standard operation completion info.
+    pub inner: OperationComplete,
+    /// Pairs of (source, created_destination) for undo recording.
+    pub copied_pairs: Vec<(PathBuf, PathBuf)>,
 }
 
 /// Options for copy operations.
-#[derive(Debug, Clone, Default)]
+///
+/// The preservation flags are also used by moves that cross filesystems, see
+/// [`MoveOptions::cross_device`](crate::MoveOptions::cross_device).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct CopyOptions {
     /// How to handle conflicts (None means ask for each).
+    #[serde(skip)]
     pub conflict_resolution: Option<ConflictResolution>,
-    /// Whether to preserve timestamps.
+    /// Whether to preserve access and modification times.
     pub preserve_timestamps: bool,
+    /// Whether to preserve permission bits on directories. Regular files get
+    /// their source mode regardless, as with `std::fs::copy`.
+    pub preserve_permissions: bool,
+    /// Whether to preserve owner and group (only applied when running as root).
+    pub preserve_ownership: bool,
+    /// Whether to copy extended attributes (Unix only).
+    pub preserve_xattrs: bool,
+    /// Copy what symlinks point to instead of recreating them as symlinks.
+    pub follow_symlinks: bool,
+    /// Keep holes in sparse files instead of writing them out as zeros.
+    pub sparse: bool,
+    /// Compare every copied file with its source using BLAKE3.
+    pub verify: bool,
+    /// Holds the copy between files (and between chunks of large files)
+    /// while paused.
+    #[serde(skip)]
+    pub pause: Option<PauseToken>,
+}
+
+impl CopyOptions {
+    /// Options that preserve everything `cp -a` would: timestamps, permissions,
+    /// ownership (when privileged), xattrs, symlinks and sparse files.
+    pub fn archive() -> Self {
+        Self {
+            conflict_resolution: None,
+            preserve_timestamps: true,
+            preserve_permissions: true,
+            preserve_ownership: true,
+            preserve_xattrs: true,
+            follow_symlinks: false,
+            sparse: true,
+            verify: false,
+            pause: None,
+        }
+    }
 }
 
 /// Start an async copy operation.
@@ -44,12 +96,15 @@ pub fn start_copy(
 
     if sources.is_empty() {
         // Send immediate completion for empty sources
-        let complete = OperationComplete {
-            operation_type: OperationType::Copy,
-            succeeded: 0,
-            failed: 0,
-            bytes_processed: 0,
-            errors: vec![],
+        let complete = CopyComplete {
+            inner: OperationComplete {
+                operation_type: OperationType::Copy,
+                succeeded: 0,
+                failed: 0,
+                bytes_processed: 0,
+                errors: vec![],
+            },
+            copied_pairs: vec![],
         };
         tokio::spawn(async move {
             let _ = tx.send(CopyResult::Complete(complete)).await;
@@ -79,6 +134,7 @@ async fn copy_impl(
     let global_resolution: Option<ConflictResolution> = options.conflict_resolution;
     let mut succeeded = 0;
     let mut failed = 0;
+    let mut copied_pairs: Vec<(PathBuf, PathBuf)> = Vec::new();
 
     // Ensure destination exists and is a directory
     if !destination.exists()
@@ -89,18 +145,24 @@ async fn copy_impl(
             format!("Failed to create destination: {}", e),
         ));
         let _ = tx
-            .send(CopyResult::Complete(OperationComplete {
-                operation_type: OperationType::Copy,
-                succeeded: 0,
-                failed: sources.len(),
-                bytes_processed: 0,
-                errors: progress.errors.clone(),
+            .send(CopyResult::Complete(CopyComplete {
+                inner: OperationComplete {
+                    operation_type: OperationType::Copy,
+                    succeeded: 0,
+                    failed: sources.len(),
+                    bytes_processed: 0,
+                    errors: progress.errors.clone(),
+                },
+                copied_pairs: vec![],
             }))
             .await;
         return;
     }
 
     for source in sources {
+        if let Some(pause) = &options.pause {
+            pause.wait(&token).await;
+        }
         // HIGH-3: check for cancellation before each item
         if token.is_cancelled() {
             break;
@@ -151,23 +213,29 @@ async fn copy_impl(
                 }
                 ConflictResolution::Abort => {
                     let _ = tx
-                        .send(CopyResult::Complete(OperationComplete {
-                            operation_type: OperationType::Copy,
-                            succeeded,
-                            failed: failed + 1,
-                            bytes_processed: progress.bytes_processed,
-                            errors: progress.errors.clone(),
+                        .send(CopyResult::Complete(CopyComplete {
+                            inner: OperationComplete {
+                                operation_type: OperationType::Copy,
+                                succeeded,
+                                failed: failed + 1,
+                                bytes_processed: progress.bytes_processed,
+                                errors: progress.errors.clone(),
+                            },
+                            copied_pairs,
                         }))
                         .await;
                     return;
                 }
                 ConflictResolution::AutoRename => {
                     let new_dest = auto_rename_path(&dest_path);
-                    if let Err(e) = copy_item(&source, &new_dest, &mut progress, &tx).await {
+                    if let Err(e) =
+                        copy_item(&source, &new_dest, &options, &token, &mut progress, &tx).await
+                    {
                         progress.add_error(OperationError::new(source.clone(), e));
                         failed += 1;
                     } else {
                         succeeded += 1;
+                        copied_pairs.push((source.clone(), new_dest));
                     }
                     continue;
                 }
@@ -198,166 +266,67 @@ async fn copy_impl(
         progress.set_current_file(Some(source.clone()));
         let _ = tx.send(CopyResult::Progress(progress.clone())).await;
 
-        if let Err(e) = copy_item(&source, &dest_path, &mut progress, &tx).await {
+        if let Err(e) = copy_item(&source, &dest_path, &options, &token, &mut progress, &tx).await {
             progress.add_error(OperationError::new(source.clone(), e));
             failed += 1;
         } else {
             succeeded += 1;
+            copied_pairs.push((source, dest_path));
         }
     }
 
-    // Send completion
+    // Send completion with the created paths for undo recording
     let _ = tx
-        .send(CopyResult::Complete(OperationComplete {
-            operation_type: OperationType::Copy,
-            succeeded,
-            failed,
-            bytes_processed: progress.bytes_processed,
-            errors: progress.errors,
+        .send(CopyResult::Complete(CopyComplete {
+            inner: OperationComplete {
+                operation_type: OperationType::Copy,
+                succeeded,
+                failed,
+                bytes_processed: progress.bytes_processed,
+                errors: progress.errors,
+            },
+            copied_pairs,
         }))
         .await;
 }
 
-/// Copy a single item (file, directory, or symlink).
+/// Copy a single item (file, directory, or symlink), then verify it if asked.
+///
+/// Verification mismatches are added to `progress` as individual errors. The
+/// item still counts as copied, since its destination exists and has to be
+/// recorded for undo.
 async fn copy_item(
     source: &Path,
     dest: &Path,
+    options: &CopyOptions,
+    token: &CancellationToken,
     progress: &mut OperationProgress,
     tx: &mpsc::Sender<CopyResult>,
 ) -> Result<(), String> {
     let source = source.to_path_buf();
     let dest = dest.to_path_buf();
+    let options = options.clone();
+    let token = token.clone();
 
-    let result = tokio::task::spawn_blocking(move || {
-        // Use symlink_metadata to avoid following symlinks
-        let metadata =
-            fs::symlink_metadata(&source).map_err(|e| format!("Failed to read metadata: {}", e))?;
-
-        if metadata.is_symlink() {
-            // For symlinks, read the target and recreate at destination
-            let target =
-                fs::read_link(&source).map_err(|e| format!("Failed to read symlink: {}", e))?;
-            #[cfg(unix)]
-            {
-                std::os::unix::fs::symlink(&target, &dest)
-                    .map_err(|e| format!("Failed to create symlink: {}", e))?;
-            }
-            #[cfg(windows)]
-            {
-                if target.is_dir() {
-                    std::os::windows::fs::symlink_dir(&target, &dest)
-                        .map_err(|e| format!("Failed to create symlink: {}", e))?;
-                } else {
-                    std::os::windows::fs::symlink_file(&target, &dest)
-                        .map_err(|e| format!("Failed to create symlink: {}", e))?;
-                }
-            }
-            Ok(0u64) // Symlinks have no real size
-        } else if metadata.is_dir() {
-            copy_dir_recursive(&source, &dest, &mut HashSet::new())
+    let (bytes, mismatches) = tokio::task::spawn_blocking(move || {
+        let bytes = transfer::copy_entry(&source, &dest, &options, &token)?;
+        let mismatches = if options.verify {
+            transfer::verify_entry(&source, &dest, &options)
         } else {
-            copy_file(&source, &dest)
-        }
+            Vec::new()
+        };
+        Ok::<_, String>((bytes, mismatches))
     })
     .await
-    .map_err(|e| format!("Task failed: {}", e))?;
-
-    match result {
-        Ok(bytes) => {
-            progress.complete_file(bytes);
-            let _ = tx.send(CopyResult::Progress(progress.clone())).await;
-            Ok(())
-        }
-        Err(e) => Err(e),
-    }
-}
+    .map_err(|e| format!("Task failed: {}", e))??;
 
-/// Copy a single file.
-///
-/// HIGH-1: use symlink_metadata so we read the link's own size, not the target's.
-fn copy_file(source: &PathBuf, dest: &PathBuf) -> Result<u64, String> {
-    // HIGH-1: use symlink_metadata to avoid following symlinks for size
-    let metadata =
-        fs::symlink_metadata(source).map_err(|e| format!("Failed to read metadata: {}", e))?;
-    let size = metadata.len();
+    progress.complete_file(bytes);
+    let _ = tx.send(CopyResult::Progress(progress.clone())).await;
 
-    fs::copy(source, dest).map_err(|e| format!("Failed to copy: {}", e))?;
-
-    Ok(size)
-}
-
-/// Recursively copy a directory.
-///
-/// CRIT-2: uses `entry.file_type()` (no symlink-follow), handles symlinks as a distinct
-/// branch, and tracks inodes via a visited set to detect hard-link / symlink loops on Unix.
-fn copy_dir_recursive(
-    source: &PathBuf,
-    dest: &PathBuf,
-    visited: &mut HashSet<u64>,
-) -> Result<u64, String> {
-    // Loop detection via inode on Unix
-    #[cfg(unix)]
-    {
-        use std::os::unix::fs::MetadataExt;
-        if let Ok(meta) = fs::symlink_metadata(source) {
-            let inode = meta.ino();
-            if !visited.insert(inode) {
-                // Already visited this inode — skip to break the loop
-                return Ok(0);
-            }
-        }
-    }
-
-    fs::create_dir_all(dest).map_err(|e| format!("Failed to create directory: {}", e))?;
-
-    let mut total_bytes = 0u64;
-
-    let entries = fs::read_dir(source).map_err(|e| format!("Failed to read directory: {}", e))?;
-
-    for entry in entries {
-        let entry = entry.map_err(|e| format!("Failed to read entry: {}", e))?;
-        // CRIT-2: use entry.file_type() — does NOT follow symlinks
-        let file_type = entry
-            .file_type()
-            .map_err(|e| format!("Failed to read file type: {}", e))?;
-        let path = entry.path();
-        let dest_path = dest.join(entry.file_name());
-
-        if file_type.is_symlink() {
-            // Recreate the symlink rather than following it
-            let target =
-                fs::read_link(&path).map_err(|e| format!("Failed to read symlink: {}", e))?;
-            #[cfg(unix)]
-            {
-                std::os::unix::fs::symlink(&target, &dest_path)
-                    .map_err(|e| format!("Failed to create symlink: {}", e))?;
-            }
-            #[cfg(windows)]
-            {
-                // Choose symlink_dir vs symlink_file based on what the
-                // original link pointed at, and log failures.
-                let result = if path.is_dir() {
-                    std::os::windows::fs::symlink_dir(&target, &dest_path)
-                } else {
-                    std::os::windows::fs::symlink_file(&target, &dest_path)
-                };
-                if let Err(e) = result {
-                    tracing::warn!(
-                        "Failed to create symlink {} -> {}: {}",
-                        dest_path.display(),
-                        target.display(),
-                        e
-                    );
-                }
-            }
-        } else if file_type.is_dir() {
-            total_bytes += copy_dir_recursive(&path, &dest_path, visited)?;
-        } else {
-            total_bytes += copy_file(&path, &dest_path)?;
-        }
+    for error in mismatches {
+        progress.add_error(error);
     }
-
-    Ok(total_bytes)
+    Ok(())
 }
 
 /// Calculate total files and bytes for a list of sources.
@@ -12,6 +12,7 @@ use crate::create::{CreateResult, start_create_directory, start_create_file};
 use crate::move_op::{MoveOptions, MoveResult, start_move};
 use crate::progress::{OperationComplete, OperationProgress, OperationType};
 use crate::rename::{RenameResult, start_rename};
+use crate::trash_bin::TrashResult;
 use crate::undo::UndoableOperation;
 use crate::{Conflict, OPERATION_CHANNEL_SIZE};
 
@@ -27,12 +28,28 @@ pub enum OperationResult {
 }
 
 /// Executor for file operations with unified interface.
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct OperationExecutor {
     /// Default conflict resolution.
     pub default_resolution: Option<ConflictResolution>,
     /// Whether to use trash for deletions.
     pub use_trash: bool,
+    /// Metadata and verification options for copies and cross-device moves.
+    /// The conflict resolution inside is replaced by `default_resolution`.
+    pub copy_options: CopyOptions,
+}
+
+impl Default for OperationExecutor {
+    fn default() -> Self {
+        Self {
+            default_resolution: None,
+            use_trash: false,
+            copy_options: CopyOptions {
+                preserve_timestamps: true,
+                ..Default::default()
+            },
+        }
+    }
 }
 
 impl OperationExecutor {
@@ -55,6 +72,12 @@ impl OperationExecutor {
         self
     }
 
+    /// Set the metadata and verification options for copies and moves.
+    pub fn with_copy_options(mut self, options: CopyOptions) -> Self {
+        self.copy_options = options;
+        self
+    }
+
     /// Execute a copy operation (non-cancellable convenience wrapper).
     ///
     /// Creates an internal `CancellationToken` that is never cancelled.
@@ -77,7 +100,7 @@ impl OperationExecutor {
     ) -> mpsc::Receiver<OperationResult> {
         let options = CopyOptions {
             conflict_resolution: self.default_resolution,
-            preserve_timestamps: true,
+            ..self.copy_options.clone()
         };
 
         let copy_rx = start_copy(sources, destination, options, token);
@@ -106,6 +129,7 @@ impl OperationExecutor {
     ) -> mpsc::Receiver<OperationResult> {
         let options = MoveOptions {
             conflict_resolution: self.default_resolution,
+            cross_device: self.copy_options.clone(),
         };
 
         let move_rx = start_move(sources, destination, options, token);
@@ -139,7 +163,7 @@ impl OperationExecutor {
                 let unified = match result {
                     CopyResult::Progress(p) => OperationResult::Progress(p),
                     CopyResult::Conflict(c) => OperationResult::Conflict(c),
-                    CopyResult::Complete(c) => OperationResult::Complete(c),
+                    CopyResult::Complete(cc) => OperationResult::Complete(cc.inner),
                 };
                 if tx.send(unified).await.is_err() {
                     break;
@@ -219,11 +243,40 @@ impl OperationExecutor {
 
 /// Execute an undo operation.
 ///
+/// The entry is verified against the filesystem first (see
+/// [`UndoEntry::verify`](crate::UndoEntry::verify)); if anything changed since
+/// it was recorded, nothing is touched and the reason is reported as an error.
+///
 /// Returns a receiver for the undo operation's progress.
 pub fn execute_undo(entry: crate::UndoEntry) -> mpsc::Receiver<OperationResult> {
+    revert(entry)
+}
+
+/// Execute a redo operation.
+///
+/// Redo entries (see [`UndoLog::record_undone`](crate::UndoLog::record_undone))
+/// store the inverse of the undone operation, so redoing reverts that inverse
+/// with the same verification as [`execute_undo`].
+pub fn execute_redo(entry: crate::UndoEntry) -> mpsc::Receiver<OperationResult> {
+    revert(entry)
+}
+
+/// Verify `entry` and reverse its operation.
+fn revert(entry: crate::UndoEntry) -> mpsc::Receiver<OperationResult> {
     let (tx, rx) = mpsc::channel(OPERATION_CHANNEL_SIZE);
 
     tokio::spawn(async move {
+        let operation_type = undo_operation_type(&entry.operation);
+        let check = entry.clone();
+        let verified = match tokio::task::spawn_blocking(move || check.verify()).await {
+            Ok(result) => result,
+            Err(e) => Err(e.to_string()),
+        };
+        if let Err(e) = verified {
+            send_undo_failure(&tx, operation_type, PathBuf::new(), e).await;
+            return;
+        }
+
         match entry.operation {
             UndoableOperation::FilesMoved { moves } => {
                 // CRIT-1: Reverse each (original→dest) pair back individually.
@@ -303,7 +356,7 @@ pub fn execute_undo(entry: crate::UndoEntry) -> mpsc::Receiver<OperationResult>
                     }))
                     .await;
             }
-            UndoableOperation::FilesCopied { created } => {
+            UndoableOperation::FilesCopied { created, .. } => {
                 // Delete the copied files
                 let mut progress = OperationProgress::new(OperationType::Delete, created.len(), 0);
                 let mut succeeded = 0;
@@ -361,6 +414,47 @@ pub fn execute_undo(entry: crate::UndoEntry) -> mpsc::Receiver<OperationResult>
                     }))
                     .await;
             }
+            UndoableOperation::FilesTrashed { paths } => {
+                let lookup =
+                    tokio::task::spawn_blocking(move || crate::trash_bin::find_trashed(&paths))
+                        .await;
+                let (found, missing) = match lookup {
+                    Ok(Ok(result)) => result,
+                    Ok(Err(e)) => {
+                        send_undo_failure(&tx, OperationType::Restore, PathBuf::new(), e).await;
+                        return;
+                    }
+                    Err(e) => {
+                        send_undo_failure(
+                            &tx,
+                            OperationType::Restore,
+                            PathBuf::new(),
+                            e.to_string(),
+                        )
+                        .await;
+                        return;
+                    }
+                };
+
+                let mut restore_rx = crate::trash_bin::start_restore(found);
+                while let Some(result) = restore_rx.recv().await {
+                    let unified = match result {
+                        TrashResult::Progress(p) => OperationResult::Progress(p),
+                        TrashResult::Complete(mut c) => {
+                            // Items that are no longer in the trash (emptied or
+                            // restored elsewhere) count as failures.
+                            c.failed += missing.len();
+                            c.errors.extend(missing.iter().map(|p| {
+                                crate::OperationError::new(p.clone(), "No longer in the trash")
+                            }));
+                            OperationResult::Complete(c)
+                        }
+                    };
+                    if tx.send(unified).await.is_err() {
+                        break;
+                    }
+                }
+            }
             UndoableOperation::FileRenamed {
                 path: current_path,
                 old_path,
@@ -421,8 +515,250 @@ pub fn execute_undo(entry: crate::UndoEntry) -> mpsc::Receiver<OperationResult>
                     }))
                     .await;
             }
+            UndoableOperation::FilesRestored { paths } => {
+                let items = paths.into_iter().map(|p| (p.clone(), p)).collect();
+                revert_each(&tx, operation_type, items, |path| {
+                    trash::delete(&path).map_err(|e| format!("Failed to move to trash: {}", e))
+                })
+                .await;
+            }
+            UndoableOperation::CopiesRemoved { copies, options } => {
+                let items = copies
+                    .into_iter()
+                    .map(|(from, to)| (to.clone(), (from, to, options.clone())))
+                    .collect();
+                revert_each(&tx, operation_type, items, |(from, to, options)| {
+                    crate::transfer::copy_entry(&from, &to, &options, &CancellationToken::new())
+                        .map(|_| ())
+                })
+                .await;
+            }
+            UndoableOperation::PermissionsChanged { changes } => {
+                let items = changes
+                    .into_iter()
+                    .map(|c| (c.path.clone(), (c.path, c.before)))
+                    .collect();
+                revert_each(&tx, operation_type, items, |(path, mode)| {
+                    crate::permissions::set_mode(&path, mode)
+                })
+                .await;
+            }
+            UndoableOperation::OwnershipChanged { changes } => {
+                let items = changes
+                    .into_iter()
+                    .map(|c| (c.path.clone(), (c.path, c.before)))
+                    .collect();
+                revert_each(&tx, operation_type, items, |(path, ids)| {
+                    crate::permissions::set_owner(&path, ids)
+                })
+                .await;
+            }
+            UndoableOperation::LinksCreated { links } => {
+                let items = links.into_iter().map(|l| (l.path.clone(), l)).collect();
+                revert_each(&tx, operation_type, items, |link| link.remove()).await;
+            }
+            UndoableOperation::LinksRemoved { links } => {
+                let items = links.into_iter().map(|l| (l.path.clone(), l)).collect();
+                revert_each(&tx, operation_type, items, |link| link.create()).await;
+            }
+            UndoableOperation::CreationReverted { path, is_dir } => {
+                let items = vec![(path.clone(), (path, is_dir))];
+                revert_each(&tx, operation_type, items, |(path, is_dir)| {
+                    let result = if is_dir {
+                        fs::create_dir(&path)
+                    } else {
+                        fs::File::create_new(&path).map(|_| ())
+                    };
+                    result.map_err(|e| format!("Failed to recreate: {}", e))
+                })
+                .await;
+            }
         }
     });
 
     rx
 }
+
+/// Run one blocking step per item with progress, then send the completion.
+///
+/// Each item carries the path used for progress and error reporting.
+async fn revert_each<T: Send + 'static>(
+    tx: &mpsc::Sender<OperationResult>,
+    operation_type: OperationType,
+    items: Vec<(PathBuf, T)>,
+    step: fn(T) -> Result<(), String>,
+) {
+    let mut progress = OperationProgress::new(operation_type, items.len(), 0);
+    let mut succeeded = 0;
+    let mut failed = 0;
+
+    for (path, item) in items {
+        progress.set_current_file(Some(path.clone()));
+        let _ = tx.send(OperationResult::Progress(progress.clone())).await;
+
+        match tokio::task::spawn_blocking(move || step(item)).await {
+            Ok(Ok(())) => {
+                progress.complete_file(0);
+                succeeded += 1;
+            }
+            Ok(Err(e)) => {
+                progress.add_error(crate::OperationError::new(path, e));
+                failed += 1;
+            }
+            Err(e) => {
+                progress.add_error(crate::OperationError::new(path, e.to_string()));
+                failed += 1;
+            }
+        }
+    }
+
+    let _ = tx
+        .send(OperationResult::Complete(OperationComplete {
+            operation_type,
+            succeeded,
+            failed,
+            bytes_processed: 0,
+            errors: progress.errors,
+        }))
+        .await;
+}
+
+/// Operation type reported while undoing `operation`.
+fn undo_operation_type(operation: &UndoableOperation) -> OperationType {
+    match operation {
+        UndoableOperation::FilesMoved { .. } => OperationType::Move,
+        UndoableOperation::FilesTrashed { .. } => OperationType::Restore,
+        UndoableOperation::FileRenamed { .. } => OperationType::Rename,
+        UndoableOperation::CopiesRemoved { .. } => OperationType::Copy,
+        UndoableOperation::CreationReverted { is_dir: false, .. } => OperationType::CreateFile,
+        UndoableOperation::CreationReverted { is_dir: true, .. } => OperationType::CreateDirectory,
+        UndoableOperation::PermissionsChanged { .. } => OperationType::Chmod,
+        UndoableOperation::OwnershipChanged { .. } => OperationType::Chown,
+        UndoableOperation::LinksRemoved { .. } => OperationType::Link,
+        UndoableOperation::FilesCopied { .. }
+        | UndoableOperation::LinksCreated { .. }
+        | UndoableOperation::FilesDeleted { .. }
+        | UndoableOperation::FilesRestored { .. }
+        | UndoableOperation::FileCreated { .. }
+        | UndoableOperation::DirectoryCreated { .. } => OperationType::Delete,
+    }
+}
+
+/// Send a single-error completion for an undo that could not start.
+async fn send_undo_failure(
+    tx: &mpsc::Sender<OperationResult>,
+    operation_type: OperationType,
+    path: PathBuf,
+    message: String,
+) {
+    let _ = tx
+        .send(OperationResult::Complete(OperationComplete {
+            operation_type,
+            succeeded: 0,
+            failed: 1,
+            bytes_processed: 0,
+            errors: vec![crate::OperationError::new(path, message)],
+        }))
+        .await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::UndoLog;
+
+    async fn drain(mut rx: mpsc::Receiver<OperationResult>) -> OperationComplete {
+        let mut complete = None;
+        while let Some(result) = rx.recv().await {
+            if let OperationResult::Complete(c) = result {
+                complete = Some(c);
+            }
+        }
+        complete.expect("operation sent no completion")
+    }
+
+    #[tokio::test]
+    async fn test_undo_then_redo_move() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let original = temp.path().join("a.txt");
+        let moved = temp.path().join("b.txt");
+        fs::write(&moved, "data").unwrap();
+
+        let mut log = UndoLog::new(10);
+        log.record_move(vec![(original.clone(), moved.clone())]);
+
+        let entry = log.pop().unwrap();
+        let complete = drain(execute_undo(entry.clone())).await;
+        assert_eq!((complete.succeeded, complete.failed), (1, 0));
+        assert!(original.exists() && !moved.exists());
+        log.record_undone(&entry).unwrap();
+
+        let redo = log.take_redo(log.peek_redo().unwrap().id).unwrap();
+        let complete = drain(execute_redo(redo)).await;
+        assert_eq!((complete.succeeded, complete.failed), (1, 0));
+        assert!(!original.exists() && moved.exists());
+    }
+
+    #[tokio::test]
+    async fn test_redo_recreates_directory() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let dir = temp.path().join("made");
+        fs::create_dir(&dir).unwrap();
+
+        let mut log = UndoLog::new(10);
+        log.record_create_directory(dir.clone());
+        let entry = log.pop().unwrap();
+        drain(execute_undo(entry.clone())).await;
+        assert!(!dir.exists());
+        log.record_undone(&entry).unwrap();
+
+        let redo = log.take_redo(log.peek_redo().unwrap().id).unwrap();
+        let complete = drain(execute_redo(redo)).await;
+        assert_eq!(complete.operation_type, OperationType::CreateDirectory);
+        assert!(dir.is_dir());
+    }
+
+    #[tokio::test]
+    async fn test_redo_copy_keeps_original_options() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let src = temp.path().join("src.txt");
+        let dst = temp.path().join("dst.txt");
+        fs::write(&src, "data").unwrap();
+        let old = filetime::FileTime::from_unix_time(1_000_000_000, 0);
+        filetime::set_file_mtime(&src, old).unwrap();
+        fs::copy(&src, &dst).unwrap();
+        filetime::set_file_mtime(&dst, old).unwrap();
+
+        let mut log = UndoLog::new(10);
+        log.record_operation(UndoableOperation::FilesCopied {
+            created: vec![dst.clone()],
+            sources: vec![src.clone()],
+            options: CopyOptions::archive(),
+        });
+        let entry = log.pop().unwrap();
+        drain(execute_undo(entry.clone())).await;
+        assert!(!dst.exists());
+        log.record_undone(&entry).unwrap();
+
+        let redo = log.take_redo(log.peek_redo().unwrap().id).unwrap();
+        let complete = drain(execute_redo(redo)).await;
+        assert_eq!((complete.succeeded, complete.failed), (1, 0));
+        let meta = fs::metadata(&dst).unwrap();
+        assert_eq!(filetime::FileTime::from_last_modification_time(&meta), old);
+    }
+
+    #[tokio::test]
+    async fn test_undo_refuses_changed_target() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let file = temp.path().join("new.txt");
+        fs::write(&file, "").unwrap();
+
+        let mut log = UndoLog::new(10);
+        log.record_create_file(file.clone());
+        fs::write(&file, "edited").unwrap();
+
+        let complete = drain(execute_undo(log.pop().unwrap())).await;
+        assert_eq!((complete.succeeded, complete.failed), (0, 1));
+        assert!(file.exists());
+    }
+}
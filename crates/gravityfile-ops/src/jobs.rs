@@ -0,0 +1,938 @@
+//! Background job queue for file operations.
+//!
+//! A [`JobManager`] queues [`FileOperation`]s and runs up to a configurable
+//! number of them at once. Each job can be paused, resumed and cancelled on
+//! its own; the manager keeps a snapshot of every job's progress so a UI can
+//! list them while they run, and reports each finished job (with what to
+//! record in the undo log) on a channel.
+//!
+//! Pausing is cooperative: a paused job stops at its next file boundary, or
+//! between chunks of a large file, until it is resumed or cancelled.
+
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+
+use crate::batch_rename::{BatchRenameResult, start_batch_rename};
+use crate::copy::{CopyOptions, CopyResult, start_copy};
+use crate::executor::{OperationExecutor, OperationResult};
+use crate::link::{LinkResult, start_link};
+use crate::move_op::{MoveOptions, MoveResult, start_move};
+use crate::permissions::{AttributeResult, start_chmod, start_chown};
+use crate::plan::tree_size;
+use crate::progress::{OperationComplete, OperationProgress, OperationType};
+use crate::shred::{ShredOptions, ShredResult, start_shred};
+use crate::undo::UndoableOperation;
+use crate::{FileOperation, OperationError};
+
+/// How often a paused operation checks whether it may continue.
+const PAUSE_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Shared flag that holds a running operation at its next pause point.
+#[derive(Debug, Clone, Default)]
+pub struct PauseToken(Arc<AtomicBool>);
+
+impl PauseToken {
+    /// Create a token that is not paused.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Pause operations holding this token.
+    pub fn pause(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    /// Let paused operations continue.
+    pub fn resume(&self) {
+        self.0.store(false, Ordering::SeqCst);
+    }
+
+    /// Whether the token is paused.
+    pub fn is_paused(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+
+    /// Wait while paused. Returns early when `cancel` fires.
+    pub(crate) async fn wait(&self, cancel: &CancellationToken) {
+        while self.is_paused() && !cancel.is_cancelled() {
+            tokio::time::sleep(PAUSE_POLL_INTERVAL).await;
+        }
+    }
+
+    /// Blocking version of [`wait`](Self::wait) for copy threads.
+    pub(crate) fn wait_blocking(&self, cancel: &CancellationToken) {
+        while self.is_paused() && !cancel.is_cancelled() {
+            std::thread::sleep(PAUSE_POLL_INTERVAL);
+        }
+    }
+}
+
+/// Identifier of a job, unique within its manager.
+pub type JobId = u64;
+
+/// Lifecycle state of a job.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobState {
+    /// Waiting for a free slot.
+    Queued,
+    /// Running.
+    Running,
+    /// Paused by the user, either before it started or mid-run.
+    Paused,
+    /// Finished with every item succeeding.
+    Completed,
+    /// Finished with at least one failed item.
+    Failed,
+    /// Cancelled by the user.
+    Cancelled,
+}
+
+impl JobState {
+    /// Whether the job has stopped for good.
+    pub fn is_finished(self) -> bool {
+        matches!(self, Self::Completed | Self::Failed | Self::Cancelled)
+    }
+}
+
+impl std::fmt::Display for JobState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Queued => write!(f, "queued"),
+            Self::Running => write!(f, "running"),
+            Self::Paused => write!(f, "paused"),
+            Self::Completed => write!(f, "done"),
+            Self::Failed => write!(f, "failed"),
+            Self::Cancelled => write!(f, "cancelled"),
+        }
+    }
+}
+
+/// Snapshot of a queued, running or finished job.
+#[derive(Debug, Clone)]
+pub struct Job {
+    /// Job identifier.
+    pub id: JobId,
+    /// The operation this job performs.
+    pub operation: FileOperation,
+    /// Current state.
+    pub state: JobState,
+    /// Latest progress report, if the job has started.
+    pub progress: Option<OperationProgress>,
+    /// Errors and skipped conflicts so far.
+    pub errors: Vec<OperationError>,
+    /// Final result once the job has finished.
+    pub result: Option<OperationComplete>,
+    conflicts: Vec<OperationError>,
+    options: CopyOptions,
+    token: CancellationToken,
+    pause: PauseToken,
+    started: Option<Instant>,
+    finished: Option<Instant>,
+    paused_at: Option<Instant>,
+    paused_for: Duration,
+}
+
+impl Job {
+    /// Short human-readable description, e.g. "Copy 3 items to /dst".
+    pub fn description(&self) -> String {
+        let count = |n: usize| {
+            if n == 1 {
+                "1 item".to_string()
+            } else {
+                format!("{} items", n)
+            }
+        };
+        match &self.operation {
+            FileOperation::Copy {
+                sources,
+                destination,
+            } => format!("Copy {} to {}", count(sources.len()), destination.display()),
+            FileOperation::Move {
+                sources,
+                destination,
+            } => format!("Move {} to {}", count(sources.len()), destination.display()),
+            FileOperation::Delete { targets, use_trash } => format!(
+                "{} {}",
+                if *use_trash { "Trash" } else { "Delete" },
+                count(targets.len())
+            ),
+            FileOperation::Shred { targets, passes } => {
+                format!("Shred {} ({} passes)", count(targets.len()), passes)
+            }
+            FileOperation::Chmod { targets, spec } => {
+                format!("Chmod {} {}", spec, count(targets.len()))
+            }
+            FileOperation::Chown { targets, spec } => {
+                format!("Chown {} {}", spec, count(targets.len()))
+            }
+            FileOperation::Link {
+                sources,
+                destination,
+                kind,
+            } => format!(
+                "Link {} to {} ({})",
+                count(sources.len()),
+                destination.display(),
+                kind
+            ),
+            FileOperation::Rename { source, new_name } => {
+                format!("Rename {} to {}", source.display(), new_name)
+            }
+            FileOperation::BatchRename { renames } => format!("Rename {}", count(renames.len())),
+            FileOperation::CreateFile { path } => format!("Create {}", path.display()),
+            FileOperation::CreateDirectory { path } => format!("Create {}/", path.display()),
+        }
+    }
+
+    /// Bytes processed so far.
+    pub fn bytes_processed(&self) -> u64 {
+        match (&self.result, &self.progress) {
+            (Some(result), _) => result.bytes_processed,
+            (None, Some(progress)) => progress.bytes_processed,
+            (None, None) => 0,
+        }
+    }
+
+    /// Time spent running, excluding pauses.
+    pub fn active_time(&self) -> Duration {
+        let Some(started) = self.started else {
+            return Duration::ZERO;
+        };
+        let end = self
+            .paused_at
+            .or(self.finished)
+            .unwrap_or_else(Instant::now);
+        end.duration_since(started).saturating_sub(self.paused_for)
+    }
+
+    /// Average throughput in bytes per second while running.
+    pub fn throughput(&self) -> Option<f64> {
+        let secs = self.active_time().as_secs_f64();
+        (secs > 0.0).then(|| self.bytes_processed() as f64 / secs)
+    }
+
+    fn set_paused(&mut self, paused: bool) {
+        match (paused, self.paused_at) {
+            (true, None) if self.started.is_some() => self.paused_at = Some(Instant::now()),
+            (false, Some(at)) => {
+                self.paused_for += at.elapsed();
+                self.paused_at = None;
+            }
+            _ => {}
+        }
+    }
+}
+
+/// A finished job's result, sent on the manager's event channel.
+#[derive(Debug)]
+pub struct JobOutcome {
+    /// The finished job.
+    pub id: JobId,
+    /// Its final state.
+    pub state: JobState,
+    /// Completion counts and errors.
+    pub complete: OperationComplete,
+    /// What to record in the undo log, if the job changed anything.
+    pub undo: Option<UndoableOperation>,
+}
+
+#[derive(Debug)]
+struct Shared {
+    jobs: Mutex<Queue>,
+    events: mpsc::UnboundedSender<JobOutcome>,
+}
+
+#[derive(Debug)]
+struct Queue {
+    jobs: Vec<Job>,
+    next_id: JobId,
+    concurrency: usize,
+}
+
+/// Queue that runs file operations in the background.
+///
+/// Cloning the manager gives another handle to the same queue.
+#[derive(Debug, Clone)]
+pub struct JobManager {
+    shared: Arc<Shared>,
+}
+
+impl JobManager {
+    /// Create a manager running at most `concurrency` jobs at once (at least
+    /// one). Finished jobs are reported on the returned receiver.
+    pub fn new(concurrency: usize) -> (Self, mpsc::UnboundedReceiver<JobOutcome>) {
+        let (events, rx) = mpsc::unbounded_channel();
+        let manager = Self {
+            shared: Arc::new(Shared {
+                jobs: Mutex::new(Queue {
+                    jobs: Vec::new(),
+                    next_id: 1,
+                    concurrency: concurrency.max(1),
+                }),
+                events,
+            }),
+        };
+        (manager, rx)
+    }
+
+    fn queue(&self) -> MutexGuard<'_, Queue> {
+        // A panic while holding the lock leaves the queue usable.
+        self.shared.jobs.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Queue an operation. Copies and moves use `options` (including its
+    /// conflict resolution); deletes follow the operation's own trash flag.
+    ///
+    /// Must be called from within a tokio runtime.
+    pub fn submit(&self, operation: FileOperation, options: CopyOptions) -> JobId {
+        let id = {
+            let mut queue = self.queue();
+            let id = queue.next_id;
+            queue.next_id += 1;
+            let pause = PauseToken::new();
+            queue.jobs.push(Job {
+                id,
+                operation,
+                state: JobState::Queued,
+                progress: None,
+                errors: Vec::new(),
+                result: None,
+                conflicts: Vec::new(),
+                options: CopyOptions {
+                    pause: Some(pause.clone()),
+                    ..options
+                },
+                token: CancellationToken::new(),
+                pause,
+                started: None,
+                finished: None,
+                paused_at: None,
+                paused_for: Duration::ZERO,
+            });
+            id
+        };
+        self.schedule();
+        id
+    }
+
+    /// Snapshot of every job, oldest first.
+    pub fn jobs(&self) -> Vec<Job> {
+        self.queue().jobs.clone()
+    }
+
+    /// Snapshot of one job.
+    pub fn job(&self, id: JobId) -> Option<Job> {
+        self.queue().jobs.iter().find(|j| j.id == id).cloned()
+    }
+
+    /// Number of jobs that are queued, running or paused.
+    pub fn active_count(&self) -> usize {
+        self.queue()
+            .jobs
+            .iter()
+            .filter(|j| !j.state.is_finished())
+            .count()
+    }
+
+    /// Maximum number of jobs running at once.
+    pub fn concurrency(&self) -> usize {
+        self.queue().concurrency
+    }
+
+    /// Change how many jobs may run at once. Raising it starts queued jobs;
+    /// lowering it lets running jobs finish.
+    pub fn set_concurrency(&self, concurrency: usize) {
+        self.queue().concurrency = concurrency.max(1);
+        self.schedule();
+    }
+
+    /// Pause a queued or running job. Running jobs whose operation cannot be
+    /// held are left alone. Returns whether anything changed.
+    pub fn pause(&self, id: JobId) -> bool {
+        let mut queue = self.queue();
+        let Some(job) = queue.jobs.iter_mut().find(|j| j.id == id) else {
+            return false;
+        };
+        let pausable = match job.state {
+            JobState::Queued => true,
+            JobState::Running => job.operation.can_pause(),
+            _ => false,
+        };
+        if !pausable {
+            return false;
+        }
+        job.pause.pause();
+        job.set_paused(true);
+        job.state = JobState::Paused;
+        true
+    }
+
+    /// Resume a paused job. Jobs paused before they started go back in the
+    /// queue. Returns whether anything changed.
+    pub fn resume(&self, id: JobId) -> bool {
+        {
+            let mut queue = self.queue();
+            let Some(job) = queue.jobs.iter_mut().find(|j| j.id == id) else {
+                return false;
+            };
+            if job.state != JobState::Paused {
+                return false;
+            }
+            job.pause.resume();
+            job.set_paused(false);
+            job.state = if job.started.is_some() {
+                JobState::Running
+            } else {
+                JobState::Queued
+            };
+        }
+        self.schedule();
+        true
+    }
+
+    /// Cancel a job. Queued jobs never start; running jobs stop at their next
+    /// item and remove partially copied files. Either way an outcome is sent
+    /// once the job has stopped. Returns whether anything changed.
+    pub fn cancel(&self, id: JobId) -> bool {
+        let outcome = {
+            let mut queue = self.queue();
+            let Some(job) = queue.jobs.iter_mut().find(|j| j.id == id) else {
+                return false;
+            };
+            if job.state.is_finished() {
+                return false;
+            }
+            job.token.cancel();
+            if job.started.is_some() {
+                // The running task reports its own outcome.
+                return true;
+            }
+            let complete = OperationComplete {
+                operation_type: operation_type(&job.operation),
+                succeeded: 0,
+                failed: 0,
+                bytes_processed: 0,
+                errors: Vec::new(),
+            };
+            job.set_paused(false);
+            job.state = JobState::Cancelled;
+            job.finished = Some(Instant::now());
+            job.result = Some(complete.clone());
+            JobOutcome {
+                id,
+                state: JobState::Cancelled,
+                complete,
+                undo: None,
+            }
+        };
+        let _ = self.shared.events.send(outcome);
+        true
+    }
+
+    /// Drop finished jobs from the list.
+    pub fn clear_finished(&self) {
+        self.queue().jobs.retain(|j| !j.state.is_finished());
+    }
+
+    /// Start queued jobs while there are free slots.
+    fn schedule(&self) {
+        let mut queue = self.queue();
+        let running = queue
+            .jobs
+            .iter()
+            .filter(|j| j.started.is_some() && !j.state.is_finished())
+            .count();
+        let free = queue.concurrency.saturating_sub(running);
+        for job in queue
+            .jobs
+            .iter_mut()
+            .filter(|j| j.state == JobState::Queued)
+            .take(free)
+        {
+            job.state = JobState::Running;
+            job.started = Some(Instant::now());
+            let manager = self.clone();
+            let (id, operation, options, token) = (
+                job.id,
+                job.operation.clone(),
+                job.options.clone(),
+                job.token.clone(),
+            );
+            tokio::spawn(async move {
+                let (complete, undo) = manager.drive(id, operation, options, &token).await;
+                manager.finish(id, complete, undo, token.is_cancelled());
+            });
+        }
+    }
+
+    fn update(&self, id: JobId, f: impl FnOnce(&mut Job)) {
+        if let Some(job) = self.queue().jobs.iter_mut().find(|j| j.id == id) {
+            f(job);
+        }
+    }
+
+    fn record_progress(&self, id: JobId, progress: OperationProgress) {
+        self.update(id, |job| {
+            job.errors = job.conflicts.clone();
+            job.errors.extend(progress.errors.iter().cloned());
+            job.progress = Some(progress);
+        });
+    }
+
+    fn record_conflict(&self, id: JobId, conflict: crate::Conflict) {
+        self.update(id, |job| {
+            let skipped = OperationError::new(
+                conflict.destination.clone(),
+                format!("Skipped: {}", conflict.kind),
+            );
+            job.conflicts.push(skipped.clone());
+            job.errors.push(skipped);
+        });
+    }
+
+    fn finish(
+        &self,
+        id: JobId,
+        complete: OperationComplete,
+        undo: Option<UndoableOperation>,
+        cancelled: bool,
+    ) {
+        let state = if cancelled {
+            JobState::Cancelled
+        } else if complete.failed == 0 {
+            JobState::Completed
+        } else {
+            JobState::Failed
+        };
+        self.update(id, |job| {
+            job.set_paused(false);
+            job.state = state;
+            job.finished = Some(Instant::now());
+            job.errors = job.conflicts.clone();
+            job.errors.extend(complete.errors.iter().cloned());
+            job.result = Some(complete.clone());
+        });
+        let _ = self.shared.events.send(JobOutcome {
+            id,
+            state,
+            complete,
+            undo,
+        });
+        self.schedule();
+    }
+
+    /// Run one operation to completion, mirroring its progress into the job.
+    async fn drive(
+        &self,
+        id: JobId,
+        operation: FileOperation,
+        options: CopyOptions,
+        token: &CancellationToken,
+    ) -> (OperationComplete, Option<UndoableOperation>) {
+        let operation_type = operation_type(&operation);
+        match operation {
+            FileOperation::Copy {
+                sources,
+                destination,
+            } => {
+                let mut rx = start_copy(sources, destination, options.clone(), token.clone());
+                while let Some(result) = rx.recv().await {
+                    match result {
+                        CopyResult::Progress(p) => self.record_progress(id, p),
+                        CopyResult::Conflict(c) => self.record_conflict(id, c),
+                        CopyResult::Complete(c) => {
+                            let undo = (!c.copied_pairs.is_empty()).then(|| {
+                                let (sources, created) = c.copied_pairs.into_iter().unzip();
+                                UndoableOperation::FilesCopied {
+                                    created,
+                                    sources,
+                                    options,
+                                }
+                            });
+                            return (c.inner, undo);
+                        }
+                    }
+                }
+            }
+            FileOperation::Move {
+                sources,
+                destination,
+            } => {
+                let options = MoveOptions {
+                    conflict_resolution: options.conflict_resolution,
+                    cross_device: options,
+                };
+                let mut rx = start_move(sources, destination, options, token.clone());
+                while let Some(result) = rx.recv().await {
+                    match result {
+                        MoveResult::Progress(p) => self.record_progress(id, p),
+                        MoveResult::Conflict(c) => self.record_conflict(id, c),
+                        MoveResult::Complete(c) => {
+                            let undo = (!c.moved_pairs.is_empty()).then_some(
+                                UndoableOperation::FilesMoved {
+                                    moves: c.moved_pairs,
+                                },
+                            );
+                            return (c.inner, undo);
+                        }
+                    }
+                }
+            }
+            FileOperation::Delete { targets, use_trash } => {
+                let pause = options.pause.unwrap_or_default();
+                return self.delete(id, targets, use_trash, &pause, token).await;
+            }
+            FileOperation::Shred { targets, passes } => {
+                let options = ShredOptions {
+                    pause: options.pause,
+                    ..ShredOptions::with_passes(passes)
+                };
+                let mut rx = start_shred(targets, options, token.clone());
+                while let Some(result) = rx.recv().await {
+                    match result {
+                        ShredResult::Progress(p) => self.record_progress(id, p),
+                        ShredResult::Complete(c) => return (c, None),
+                    }
+                }
+            }
+            FileOperation::Chmod { targets, spec } => {
+                let rx = start_chmod(targets, spec, options.pause, token.clone());
+                return self.drain_attributes(id, rx, operation_type).await;
+            }
+            FileOperation::Chown { targets, spec } => {
+                let rx = start_chown(targets, spec, options.pause, token.clone());
+                return self.drain_attributes(id, rx, operation_type).await;
+            }
+            FileOperation::Link {
+                sources,
+                destination,
+                kind,
+            } => {
+                let mut rx = start_link(
+                    sources,
+                    destination,
+                    kind,
+                    options.conflict_resolution,
+                    options.pause,
+                    token.clone(),
+                );
+                while let Some(result) = rx.recv().await {
+                    match result {
+                        LinkResult::Progress(p) => self.record_progress(id, p),
+                        LinkResult::Complete(c) => {
+                            let undo = (!c.created.is_empty())
+                                .then_some(UndoableOperation::LinksCreated { links: c.created });
+                            return (c.inner, undo);
+                        }
+                    }
+                }
+            }
+            FileOperation::BatchRename { renames } => {
+                let mut rx = start_batch_rename(renames, token.clone());
+                while let Some(result) = rx.recv().await {
+                    match result {
+                        BatchRenameResult::Progress(p) => self.record_progress(id, p),
+                        BatchRenameResult::Complete(c) => {
+                            let undo = (!c.renamed.is_empty())
+                                .then_some(UndoableOperation::FilesMoved { moves: c.renamed });
+                            return (c.inner, undo);
+                        }
+                    }
+                }
+            }
+            FileOperation::Rename { source, new_name } => {
+                let executor = OperationExecutor::new();
+                let undo = UndoableOperation::FileRenamed {
+                    path: source.with_file_name(&new_name),
+                    old_path: source.clone(),
+                    new_name: new_name.clone(),
+                };
+                return self
+                    .drain(id, executor.rename(source, new_name), undo)
+                    .await;
+            }
+            FileOperation::CreateFile { path } => {
+                let executor = OperationExecutor::new();
+                let undo = UndoableOperation::FileCreated { path: path.clone() };
+                return self.drain(id, executor.create_file(path), undo).await;
+            }
+            FileOperation::CreateDirectory { path } => {
+                let executor = OperationExecutor::new();
+                let undo = UndoableOperation::DirectoryCreated { path: path.clone() };
+                return self.drain(id, executor.create_directory(path), undo).await;
+            }
+        }
+        (interrupted(operation_type), None)
+    }
+
+    /// Drain a unified result channel; `undo` is recorded only on success.
+    async fn drain(
+        &self,
+        id: JobId,
+        mut rx: mpsc::Receiver<OperationResult>,
+        undo: UndoableOperation,
+    ) -> (OperationComplete, Option<UndoableOperation>) {
+        let mut operation_type = OperationType::Rename;
+        while let Some(result) = rx.recv().await {
+            match result {
+                OperationResult::Progress(p) => {
+                    operation_type = p.operation_type;
+                    self.record_progress(id, p);
+                }
+                OperationResult::Conflict(c) => self.record_conflict(id, c),
+                OperationResult::Complete(c) => {
+                    let undo = (c.failed == 0 && c.succeeded > 0).then_some(undo);
+                    return (c, undo);
+                }
+            }
+        }
+        (interrupted(operation_type), None)
+    }
+
+    /// Drain a chmod/chown result channel.
+    async fn drain_attributes(
+        &self,
+        id: JobId,
+        mut rx: mpsc::Receiver<AttributeResult>,
+        operation_type: OperationType,
+    ) -> (OperationComplete, Option<UndoableOperation>) {
+        while let Some(result) = rx.recv().await {
+            match result {
+                AttributeResult::Progress(p) => self.record_progress(id, p),
+                AttributeResult::Complete(c) => return (c.inner, c.undo),
+            }
+        }
+        (interrupted(operation_type), None)
+    }
+
+    /// Delete (or trash) each target in turn.
+    async fn delete(
+        &self,
+        id: JobId,
+        targets: Vec<PathBuf>,
+        use_trash: bool,
+        pause: &PauseToken,
+        token: &CancellationToken,
+    ) -> (OperationComplete, Option<UndoableOperation>) {
+        let mut progress = OperationProgress::new(OperationType::Delete, targets.len(), 0);
+        let mut succeeded = 0;
+        let mut failed = 0;
+        let mut trashed = Vec::new();
+
+        for target in targets {
+            pause.wait(token).await;
+            if token.is_cancelled() {
+                break;
+            }
+            progress.set_current_file(Some(target.clone()));
+            self.record_progress(id, progress.clone());
+
+            let path = target.clone();
+            let result = tokio::task::spawn_blocking(move || {
+                let bytes = tree_size(&path);
+                if use_trash {
+                    trash::delete(&path).map_err(|e| format!("Failed to move to trash: {}", e))?;
+                } else {
+                    let meta = fs::symlink_metadata(&path)
+                        .map_err(|e| format!("Failed to read metadata: {}", e))?;
+                    if meta.is_dir() {
+                        fs::remove_dir_all(&path)
+                    } else {
+                        fs::remove_file(&path)
+                    }
+                    .map_err(|e| format!("Failed to delete: {}", e))?;
+                }
+                Ok::<_, String>(bytes)
+            })
+            .await
+            .map_err(|e| format!("Task failed: {}", e));
+
+            match result {
+                Ok(Ok(bytes)) => {
+                    progress.complete_file(bytes);
+                    succeeded += 1;
+                    if use_trash {
+                        trashed.push(target);
+                    }
+                }
+                Ok(Err(e)) | Err(e) => {
+                    progress.add_error(OperationError::new(target, e));
+                    failed += 1;
+                }
+            }
+            self.record_progress(id, progress.clone());
+        }
+
+        let undo =
+            (!trashed.is_empty()).then_some(UndoableOperation::FilesTrashed { paths: trashed });
+        (
+            OperationComplete {
+                operation_type: OperationType::Delete,
+                succeeded,
+                failed,
+                bytes_processed: progress.bytes_processed,
+                errors: progress.errors,
+            },
+            undo,
+        )
+    }
+}
+
+fn operation_type(operation: &FileOperation) -> OperationType {
+    match operation {
+        FileOperation::Copy { .. } => OperationType::Copy,
+        FileOperation::Move { .. } => OperationType::Move,
+        FileOperation::Delete { .. } => OperationType::Delete,
+        FileOperation::Shred { .. } => OperationType::Shred,
+        FileOperation::Chmod { .. } => OperationType::Chmod,
+        FileOperation::Chown { .. } => OperationType::Chown,
+        FileOperation::Link { .. } => OperationType::Link,
+        FileOperation::Rename { .. } | FileOperation::BatchRename { .. } => OperationType::Rename,
+        FileOperation::CreateFile { .. } => OperationType::CreateFile,
+        FileOperation::CreateDirectory { .. } => OperationType::CreateDirectory,
+    }
+}
+
+/// Completion for an operation whose channel closed without reporting one.
+fn interrupted(operation_type: OperationType) -> OperationComplete {
+    OperationComplete {
+        operation_type,
+        succeeded: 0,
+        failed: 1,
+        bytes_processed: 0,
+        errors: vec![OperationError::new(
+            PathBuf::new(),
+            "Operation ended without a result",
+        )],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    async fn next_outcome(rx: &mut mpsc::UnboundedReceiver<JobOutcome>) -> JobOutcome {
+        tokio::time::timeout(Duration::from_secs(10), rx.recv())
+            .await
+            .expect("job did not finish")
+            .expect("manager dropped")
+    }
+
+    fn copy_job(temp: &TempDir, name: &str) -> FileOperation {
+        let source = temp.path().join(name);
+        fs::write(&source, name).unwrap();
+        FileOperation::copy(vec![source], temp.path().join("out"))
+    }
+
+    #[tokio::test]
+    async fn test_concurrency_limit_queues_jobs() {
+        let temp = TempDir::new().unwrap();
+        let (manager, mut rx) = JobManager::new(1);
+        let first = manager.submit(copy_job(&temp, "a"), CopyOptions::default());
+        let second = manager.submit(copy_job(&temp, "b"), CopyOptions::default());
+
+        assert_eq!(manager.job(first).unwrap().state, JobState::Running);
+        assert_eq!(manager.job(second).unwrap().state, JobState::Queued);
+
+        let outcome = next_outcome(&mut rx).await;
+        assert_eq!((outcome.id, outcome.state), (first, JobState::Completed));
+        assert!(matches!(
+            outcome.undo,
+            Some(UndoableOperation::FilesCopied { .. })
+        ));
+        assert_eq!(next_outcome(&mut rx).await.id, second);
+        assert!(temp.path().join("out/b").exists());
+    }
+
+    #[tokio::test]
+    async fn test_pause_and_resume_running_job() {
+        let temp = TempDir::new().unwrap();
+        let (manager, mut rx) = JobManager::new(2);
+        let id = manager.submit(copy_job(&temp, "a"), CopyOptions::default());
+        // The spawned job has not run yet on this single-threaded runtime,
+        // so it stops at its first pause point.
+        assert!(manager.pause(id));
+
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(manager.job(id).unwrap().state, JobState::Paused);
+        assert!(!temp.path().join("out/a").exists());
+
+        assert!(manager.resume(id));
+        assert_eq!(next_outcome(&mut rx).await.state, JobState::Completed);
+        assert!(temp.path().join("out/a").exists());
+    }
+
+    #[tokio::test]
+    async fn test_running_batch_rename_cannot_pause() {
+        let temp = TempDir::new().unwrap();
+        fs::write(temp.path().join("a"), "a").unwrap();
+        fs::write(temp.path().join("c"), "c").unwrap();
+        let (manager, mut rx) = JobManager::new(1);
+        let rename = |from: &str, to: &str| {
+            FileOperation::batch_rename(vec![(temp.path().join(from), to.into())])
+        };
+        // The first job starts at once; the spawned task has not run yet on
+        // this single-threaded runtime.
+        let running = manager.submit(rename("a", "b"), CopyOptions::default());
+        let queued = manager.submit(rename("c", "d"), CopyOptions::default());
+        assert_eq!(manager.job(running).unwrap().state, JobState::Running);
+        assert!(!manager.pause(running));
+
+        // A rename that has not started yet can still be held back.
+        assert!(manager.pause(queued));
+        assert!(manager.resume(queued));
+        assert_eq!(manager.job(queued).unwrap().state, JobState::Queued);
+
+        assert_eq!(next_outcome(&mut rx).await.id, running);
+        assert_eq!(next_outcome(&mut rx).await.id, queued);
+        assert!(temp.path().join("b").exists());
+        assert!(temp.path().join("d").exists());
+    }
+
+    #[tokio::test]
+    async fn test_cancel_queued_and_paused_jobs() {
+        let temp = TempDir::new().unwrap();
+        let (manager, mut rx) = JobManager::new(1);
+        let running = manager.submit(copy_job(&temp, "a"), CopyOptions::default());
+        let queued = manager.submit(copy_job(&temp, "b"), CopyOptions::default());
+        manager.pause(running);
+
+        assert!(manager.cancel(queued));
+        assert_eq!(manager.job(queued).unwrap().state, JobState::Cancelled);
+        let outcome = next_outcome(&mut rx).await;
+        assert_eq!((outcome.id, outcome.state), (queued, JobState::Cancelled));
+        assert!(manager.cancel(running));
+
+        let outcome = next_outcome(&mut rx).await;
+        assert_eq!((outcome.id, outcome.state), (running, JobState::Cancelled));
+        assert_eq!(manager.active_count(), 0);
+        assert!(!temp.path().join("out/b").exists());
+
+        manager.clear_finished();
+        assert!(manager.jobs().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_delete_job() {
+        let temp = TempDir::new().unwrap();
+        let file = temp.path().join("gone");
+        fs::write(&file, "1234").unwrap();
+        let (manager, mut rx) = JobManager::new(1);
+        manager.submit(
+            FileOperation::delete(vec![file.clone()], false),
+            CopyOptions::default(),
+        );
+
+        let outcome = next_outcome(&mut rx).await;
+        assert_eq!(outcome.complete.bytes_processed, 4);
+        assert!(outcome.undo.is_none());
+        assert!(!file.exists());
+    }
+}
@@ -1,31 +1,67 @@
 //! File operations engine for gravityfile.
 //!
-//! This crate provides async file operations (copy, move, rename, create, delete)
+//! This crate provides async file operations (copy, move, rename, create, delete,
+//! rule-based bulk rename, secure shred, chmod/chown, symlinks and hardlinks, sync)
 //! with progress reporting via channels, following the same pattern as the
-//! existing deletion implementation.
+//! existing deletion implementation. Automated deletions can be recorded in an
+//! append-only audit log.
 
 pub mod archive;
+mod audit;
+mod batch_rename;
 mod conflict;
 mod copy;
 mod create;
 mod executor;
+mod jobs;
+mod link;
 mod move_op;
 mod operation;
+mod permissions;
+mod plan;
 mod progress;
 mod rename;
+mod shred;
+mod sync;
+mod transfer;
+mod trash_bin;
 mod undo;
 
 pub use archive::{ArchiveError, ArchiveFormat, ArchiveResult, create_archive, extract_archive};
+pub use audit::{AuditLog, AuditRecord, default_audit_path};
+pub use batch_rename::{
+    BatchRenameComplete, BatchRenameResult, CaseTransform, RenamePreview, RenameRule, RenameStatus,
+    check_renames, preview_renames, start_batch_rename,
+};
 pub use conflict::{Conflict, ConflictKind, ConflictResolution};
-pub use copy::{CopyOptions, CopyResult, start_copy};
+pub use copy::{CopyComplete, CopyOptions, CopyResult, start_copy};
 pub use create::{CreateResult, start_create_directory, start_create_file};
-pub use executor::{OperationExecutor, OperationResult, execute_undo};
+pub use executor::{OperationExecutor, OperationResult, execute_redo, execute_undo};
+pub use jobs::{Job, JobId, JobManager, JobOutcome, JobState, PauseToken};
+pub use link::{LinkComplete, LinkKind, LinkRecord, LinkResult, start_link};
 pub use move_op::{MoveComplete, MoveOptions, MoveResult, start_move};
 pub use operation::{FileOperation, OperationError};
+pub use permissions::{
+    AttributeComplete, AttributeResult, ChmodSpec, ChownSpec, ModeRecord, ModeSpec, OwnerRecord,
+    format_mode, resolve_group, resolve_user, start_chmod, start_chown,
+};
+pub use plan::{OperationPlan, PlanAction, PlanStep, SpaceCheck, plan_operation};
 pub use progress::{OperationComplete, OperationProgress, OperationType};
 pub use rename::{RenameResult, start_rename};
+pub use shred::{
+    DEFAULT_SHRED_PASSES, ShredOptions, ShredResult, copy_on_write_filesystem, shred_warnings,
+    start_shred,
+};
+pub use sync::{
+    SyncChange, SyncDiff, SyncDirection, SyncEntry, SyncMode, SyncOptions, SyncResult, diff_trees,
+    start_sync,
+};
 pub use tokio_util::sync::CancellationToken;
-pub use undo::{UndoEntry, UndoLog, UndoableOperation};
+pub use trash_bin::{
+    TrashEntry, TrashResult, find_trashed, list_trash, start_purge, start_restore,
+    trash_browsing_supported,
+};
+pub use undo::{TargetSnapshot, UndoEntry, UndoLog, UndoableOperation, default_journal_path};
 
 /// Default channel buffer size for operation progress updates.
 pub const OPERATION_CHANNEL_SIZE: usize = 100;
@@ -0,0 +1,676 @@
+//! Symlink and hardlink creation.
+//!
+//! Links are created in a destination directory under the name of each
+//! source, the same way a paste places copies. Symlinks point either at the
+//! absolute path of the source or at a path relative to the link's directory;
+//! hardlinks require the source to be a file on the destination's filesystem.
+//!
+//! A file replaced by a link is renamed to a hidden sibling rather than
+//! deleted, and put back if the link cannot be created. The sibling is kept
+//! in the undo entry so undoing the link restores the file.
+
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+
+use crate::conflict::{Conflict, ConflictKind, ConflictResolution, auto_rename_path};
+use crate::jobs::PauseToken;
+use crate::plan::same_device;
+use crate::progress::{OperationComplete, OperationProgress, OperationType};
+use crate::transfer;
+use crate::{OPERATION_CHANNEL_SIZE, OperationError};
+
+/// Suffix of the hidden sibling a replaced file is kept under.
+const REPLACED_SUFFIX: &str = "gravityfile-replaced";
+
+/// The kind of link to create.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkKind {
+    /// Symlink to the absolute path of the source.
+    Symlink,
+    /// Symlink to the source relative to the link's directory.
+    RelativeSymlink,
+    /// Hardlink to the source file.
+    Hardlink,
+}
+
+impl LinkKind {
+    /// Whether this kind creates a symbolic link.
+    pub fn is_symlink(self) -> bool {
+        !matches!(self, Self::Hardlink)
+    }
+}
+
+impl std::fmt::Display for LinkKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Symlink => write!(f, "symlink"),
+            Self::RelativeSymlink => write!(f, "relative symlink"),
+            Self::Hardlink => write!(f, "hardlink"),
+        }
+    }
+}
+
+/// A link created by a link operation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LinkRecord {
+    /// Path of the link itself.
+    pub path: PathBuf,
+    /// Symlink contents, or the linked file for hardlinks.
+    pub target: PathBuf,
+    /// The kind of link.
+    pub kind: LinkKind,
+    /// Where the file the link replaced is kept, if it replaced one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replaced: Option<PathBuf>,
+}
+
+impl LinkRecord {
+    /// Create the link on disk, first moving the file it replaces aside.
+    /// The file is moved back if the link cannot be created.
+    pub(crate) fn create(&self) -> Result<(), String> {
+        if let Some(kept) = &self.replaced {
+            fs::rename(&self.path, kept)
+                .map_err(|e| format!("Failed to move existing file aside: {}", e))?;
+        }
+        let result = if self.kind.is_symlink() {
+            symlink(&self.target, &self.path)
+        } else {
+            fs::hard_link(&self.target, &self.path)
+        };
+        result.map_err(|e| {
+            if let Some(kept) = &self.replaced {
+                let _ = fs::rename(kept, &self.path);
+            }
+            link_error(self.kind, e)
+        })
+    }
+
+    /// Remove the link, refusing to touch anything that is not a link, and
+    /// put back the file it replaced.
+    pub(crate) fn remove(&self) -> Result<(), String> {
+        let meta = fs::symlink_metadata(&self.path)
+            .map_err(|e| format!("Failed to read metadata: {}", e))?;
+        if meta.is_dir() {
+            return Err("Refusing to remove a directory".to_string());
+        }
+        fs::remove_file(&self.path).map_err(|e| format!("Failed to remove link: {}", e))?;
+        if let Some(kept) = &self.replaced {
+            fs::rename(kept, &self.path)
+                .map_err(|e| format!("Failed to restore replaced file: {}", e))?;
+        }
+        Ok(())
+    }
+
+    /// Check that the link can be created again: its name holds the file it
+    /// replaced (or nothing), and that file's hidden name is free.
+    pub(crate) fn check_removed(&self) -> Result<(), String> {
+        let exists = fs::symlink_metadata(&self.path).is_ok();
+        match &self.replaced {
+            None if exists => Err(format!("'{}' exists again", self.path.display())),
+            None => Ok(()),
+            Some(_) if !exists => Err(format!("'{}' no longer exists", self.path.display())),
+            Some(kept) if fs::symlink_metadata(kept).is_ok() => {
+                Err(format!("'{}' exists again", kept.display()))
+            }
+            Some(_) => Ok(()),
+        }
+    }
+
+    /// Check that the link is still the one that was created, and that the
+    /// file it replaced can be restored.
+    pub(crate) fn check(&self) -> Result<(), String> {
+        if let Some(kept) = &self.replaced
+            && fs::symlink_metadata(kept).is_err()
+        {
+            return Err(format!(
+                "Replaced file '{}' no longer exists",
+                kept.display()
+            ));
+        }
+        let changed = || format!("'{}' is no longer the created link", self.path.display());
+        if self.kind.is_symlink() {
+            match fs::read_link(&self.path) {
+                Ok(target) if target == self.target => Ok(()),
+                Ok(_) => Err(changed()),
+                Err(_) => Err(format!("'{}' no longer exists", self.path.display())),
+            }
+        } else {
+            let meta = fs::symlink_metadata(&self.path)
+                .map_err(|_| format!("'{}' no longer exists", self.path.display()))?;
+            if !meta.is_file() {
+                return Err(changed());
+            }
+            // The original may have been removed since; the link itself is
+            // then the last name of the file, which undo still may remove.
+            match fs::symlink_metadata(&self.target) {
+                Ok(target) if !same_inode(&meta, &target) => Err(changed()),
+                _ => Ok(()),
+            }
+        }
+    }
+}
+
+/// Result sent through the channel during link operations.
+#[derive(Debug)]
+pub enum LinkResult {
+    /// Progress update.
+    Progress(OperationProgress),
+    /// The operation completed.
+    Complete(LinkComplete),
+}
+
+/// Completion of a link operation.
+#[derive(Debug)]
+pub struct LinkComplete {
+    /// Standard operation completion info.
+    pub inner: OperationComplete,
+    /// Links that were created, for the undo log.
+    pub created: Vec<LinkRecord>,
+}
+
+/// Where the link for a source goes once conflicts are resolved.
+pub(crate) enum Placement {
+    /// The name is free.
+    New(PathBuf),
+    /// An existing non-directory entry is replaced.
+    Replace(PathBuf),
+    /// The item is skipped.
+    Skip,
+    /// The name is taken and no resolution applies.
+    Conflict(Conflict),
+    /// The name is taken and the operation stops.
+    Abort(Conflict),
+}
+
+/// Resolve the link path for `source` in `destination`.
+pub(crate) fn place(
+    source: &Path,
+    destination: &Path,
+    resolution: Option<ConflictResolution>,
+) -> Result<Placement, String> {
+    let file_name = source
+        .file_name()
+        .ok_or_else(|| "Source path has no filename component".to_string())?;
+    let path = destination.join(file_name);
+    let Ok(existing) = fs::symlink_metadata(&path) else {
+        return Ok(Placement::New(path));
+    };
+
+    let kind = if path == source {
+        ConflictKind::SameFile
+    } else if existing.is_dir() {
+        ConflictKind::DirectoryExists
+    } else {
+        ConflictKind::FileExists
+    };
+    let conflict = Conflict::new(source.to_path_buf(), path.clone(), kind);
+    let placement = match resolution.map(|r| r.to_single()) {
+        None => Placement::Conflict(conflict),
+        Some(ConflictResolution::Skip) => Placement::Skip,
+        Some(ConflictResolution::Abort) => Placement::Abort(conflict),
+        Some(ConflictResolution::AutoRename) => Placement::New(auto_rename_path(&path)),
+        // Replacing the source with a link to itself, or a directory tree
+        // with a single link, would lose data.
+        Some(_) if kind != ConflictKind::FileExists => Placement::Conflict(conflict),
+        Some(_) => Placement::Replace(path),
+    };
+    Ok(placement)
+}
+
+/// Check that `source` can be linked into `destination` as `kind`.
+pub(crate) fn check_source(
+    source: &Path,
+    destination: &Path,
+    kind: LinkKind,
+) -> Result<(), String> {
+    let meta = fs::symlink_metadata(source).map_err(|_| "Source does not exist".to_string())?;
+    if kind == LinkKind::Hardlink {
+        if meta.is_dir() {
+            return Err("Directories cannot be hardlinked".to_string());
+        }
+        if !same_device(source, destination) {
+            return Err(cross_device_message());
+        }
+    }
+    Ok(())
+}
+
+/// Contents of a `kind` link at `link` pointing to `source`.
+pub(crate) fn link_target(source: &Path, link: &Path, kind: LinkKind) -> Result<PathBuf, String> {
+    let source = absolute(source)?;
+    match kind {
+        LinkKind::Symlink | LinkKind::Hardlink => Ok(source),
+        LinkKind::RelativeSymlink => {
+            let dir = link.parent().unwrap_or(Path::new("."));
+            Ok(relative_path(&source, &absolute_dir(dir)?))
+        }
+    }
+}
+
+/// Start an async link operation.
+///
+/// Conflicting names are handled like a paste: `None` reports them as
+/// failures, otherwise the resolution is applied to each. A `pause` token
+/// holds the operation between sources.
+pub fn start_link(
+    sources: Vec<PathBuf>,
+    destination: PathBuf,
+    kind: LinkKind,
+    resolution: Option<ConflictResolution>,
+    pause: Option<PauseToken>,
+    token: CancellationToken,
+) -> mpsc::Receiver<LinkResult> {
+    let (tx, rx) = mpsc::channel(OPERATION_CHANNEL_SIZE);
+
+    tokio::spawn(async move {
+        let pause = pause.unwrap_or_default();
+        link_impl(sources, destination, kind, resolution, pause, token, tx).await;
+    });
+
+    rx
+}
+
+/// Outcome of linking a single source.
+enum Linked {
+    Created(LinkRecord),
+    Skipped,
+    Aborted(String),
+}
+
+async fn link_impl(
+    sources: Vec<PathBuf>,
+    destination: PathBuf,
+    kind: LinkKind,
+    resolution: Option<ConflictResolution>,
+    pause: PauseToken,
+    token: CancellationToken,
+    tx: mpsc::Sender<LinkResult>,
+) {
+    let mut progress = OperationProgress::new(OperationType::Link, sources.len(), 0);
+    let mut created = Vec::new();
+    let mut succeeded = 0;
+    let mut failed = 0;
+
+    for source in sources {
+        pause.wait(&token).await;
+        if token.is_cancelled() {
+            break;
+        }
+        progress.set_current_file(Some(source.clone()));
+        let _ = tx.send(LinkResult::Progress(progress.clone())).await;
+
+        let src = source.clone();
+        let dest = destination.clone();
+        let result = tokio::task::spawn_blocking(move || link_one(&src, &dest, kind, resolution))
+            .await
+            .map_err(|e| format!("Task failed: {}", e));
+
+        match result {
+            Ok(Ok(Linked::Created(record))) => {
+                progress.complete_file(0);
+                succeeded += 1;
+                created.push(record);
+            }
+            Ok(Ok(Linked::Skipped)) => progress.complete_file(0),
+            Ok(Ok(Linked::Aborted(message))) => {
+                progress.add_error(OperationError::new(source, message));
+                failed += 1;
+                break;
+            }
+            Ok(Err(e)) | Err(e) => {
+                progress.add_error(OperationError::new(source, e));
+                failed += 1;
+            }
+        }
+    }
+
+    let _ = tx
+        .send(LinkResult::Complete(LinkComplete {
+            inner: OperationComplete {
+                operation_type: OperationType::Link,
+                succeeded,
+                failed,
+                bytes_processed: 0,
+                errors: progress.errors,
+            },
+            created,
+        }))
+        .await;
+}
+
+fn link_one(
+    source: &Path,
+    destination: &Path,
+    kind: LinkKind,
+    resolution: Option<ConflictResolution>,
+) -> Result<Linked, String> {
+    if !destination.is_dir() {
+        return Err("Destination is not a directory".to_string());
+    }
+    check_source(source, destination, kind)?;
+
+    let (path, replaced) = match place(source, destination, resolution)? {
+        Placement::New(path) => (path, None),
+        Placement::Replace(path) => {
+            let kept = transfer::temp_sibling(&path, REPLACED_SUFFIX);
+            if fs::symlink_metadata(&kept).is_ok() {
+                return Err(format!("'{}' already exists", kept.display()));
+            }
+            (path, Some(kept))
+        }
+        Placement::Skip => return Ok(Linked::Skipped),
+        Placement::Conflict(c) => {
+            return Err(format!("'{}' already exists", c.destination.display()));
+        }
+        Placement::Abort(c) => {
+            return Ok(Linked::Aborted(format!(
+                "'{}' already exists, operation aborted",
+                c.destination.display()
+            )));
+        }
+    };
+
+    let record = LinkRecord {
+        target: link_target(source, &path, kind)?,
+        path,
+        kind,
+        replaced,
+    };
+    record.create()?;
+    Ok(Linked::Created(record))
+}
+
+/// Absolute path of `path` with its parent directory canonicalized, so the
+/// source itself is never resolved when it is a symlink.
+fn absolute(path: &Path) -> Result<PathBuf, String> {
+    let name = path
+        .file_name()
+        .ok_or_else(|| "Source path has no filename component".to_string())?;
+    let parent = path.parent().unwrap_or(Path::new("."));
+    Ok(absolute_dir(parent)?.join(name))
+}
+
+fn absolute_dir(dir: &Path) -> Result<PathBuf, String> {
+    let dir = if dir.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        dir
+    };
+    fs::canonicalize(dir).map_err(|e| format!("Failed to resolve '{}': {}", dir.display(), e))
+}
+
+/// Path of `target` relative to the directory `base`; both must be absolute.
+fn relative_path(target: &Path, base: &Path) -> PathBuf {
+    let target: Vec<Component> = target.components().collect();
+    let base: Vec<Component> = base.components().collect();
+    let common = target.iter().zip(&base).take_while(|(a, b)| a == b).count();
+
+    let mut relative = PathBuf::new();
+    for _ in common..base.len() {
+        relative.push("..");
+    }
+    for component in &target[common..] {
+        relative.push(component);
+    }
+    if relative.as_os_str().is_empty() {
+        relative.push(".");
+    }
+    relative
+}
+
+fn cross_device_message() -> String {
+    "Cannot hardlink across filesystems; paste as a symlink instead".to_string()
+}
+
+fn link_error(kind: LinkKind, e: std::io::Error) -> String {
+    #[cfg(unix)]
+    if kind == LinkKind::Hardlink && e.raw_os_error() == Some(libc::EXDEV) {
+        return cross_device_message();
+    }
+    format!("Failed to create {}: {}", kind, e)
+}
+
+#[cfg(unix)]
+fn same_inode(a: &fs::Metadata, b: &fs::Metadata) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    a.dev() == b.dev() && a.ino() == b.ino()
+}
+
+#[cfg(not(unix))]
+fn same_inode(_a: &fs::Metadata, _b: &fs::Metadata) -> bool {
+    true
+}
+
+#[cfg(unix)]
+fn symlink(target: &Path, link: &Path) -> std::io::Result<()> {
+    std::os::unix::fs::symlink(target, link)
+}
+
+#[cfg(windows)]
+fn symlink(target: &Path, link: &Path) -> std::io::Result<()> {
+    let resolved = link.parent().unwrap_or(Path::new(".")).join(target);
+    if resolved.is_dir() {
+        std::os::windows::fs::symlink_dir(target, link)
+    } else {
+        std::os::windows::fs::symlink_file(target, link)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{UndoLog, UndoableOperation, execute_redo, execute_undo};
+    use tempfile::TempDir;
+
+    async fn run(
+        sources: Vec<PathBuf>,
+        destination: PathBuf,
+        kind: LinkKind,
+        resolution: Option<ConflictResolution>,
+    ) -> LinkComplete {
+        let mut rx = start_link(
+            sources,
+            destination,
+            kind,
+            resolution,
+            None,
+            CancellationToken::new(),
+        );
+        while let Some(result) = rx.recv().await {
+            if let LinkResult::Complete(c) = result {
+                return c;
+            }
+        }
+        panic!("link operation ended without completing");
+    }
+
+    fn setup() -> (TempDir, PathBuf, PathBuf) {
+        let temp = TempDir::new().unwrap();
+        let src = temp.path().join("src");
+        let dst = temp.path().join("dst");
+        fs::create_dir_all(src.join("dir")).unwrap();
+        fs::create_dir(&dst).unwrap();
+        fs::write(src.join("file.txt"), "content").unwrap();
+        (temp, src, dst)
+    }
+
+    #[test]
+    fn test_relative_path() {
+        let rel = relative_path(Path::new("/a/b/c/file"), Path::new("/a/x/y"));
+        assert_eq!(rel, PathBuf::from("../../b/c/file"));
+        let rel = relative_path(Path::new("/a/file"), Path::new("/a"));
+        assert_eq!(rel, PathBuf::from("file"));
+    }
+
+    #[tokio::test]
+    async fn test_symlinks_absolute_and_relative() {
+        let (_temp, src, dst) = setup();
+        let sources = vec![src.join("file.txt"), src.join("dir")];
+
+        let complete = run(sources.clone(), dst.clone(), LinkKind::Symlink, None).await;
+        assert_eq!(complete.inner.succeeded, 2);
+        let target = fs::read_link(dst.join("file.txt")).unwrap();
+        assert!(target.is_absolute());
+        assert_eq!(fs::read_to_string(dst.join("file.txt")).unwrap(), "content");
+        assert!(dst.join("dir").is_dir());
+
+        let rel = dst.join("rel");
+        fs::create_dir(&rel).unwrap();
+        let complete = run(sources, rel.clone(), LinkKind::RelativeSymlink, None).await;
+        assert_eq!(complete.inner.succeeded, 2);
+        assert_eq!(
+            fs::read_link(rel.join("file.txt")).unwrap(),
+            PathBuf::from("../../src/file.txt")
+        );
+        assert_eq!(fs::read_to_string(rel.join("file.txt")).unwrap(), "content");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_hardlink_shares_inode_and_rejects_directories() {
+        use std::os::unix::fs::MetadataExt;
+
+        let (_temp, src, dst) = setup();
+        let complete = run(
+            vec![src.join("file.txt"), src.join("dir")],
+            dst.clone(),
+            LinkKind::Hardlink,
+            None,
+        )
+        .await;
+        assert_eq!(complete.inner.succeeded, 1);
+        assert_eq!(complete.inner.failed, 1);
+        assert!(complete.inner.errors[0].message.contains("Directories"));
+        let original = fs::metadata(src.join("file.txt")).unwrap();
+        let link = fs::metadata(dst.join("file.txt")).unwrap();
+        assert_eq!(original.ino(), link.ino());
+        assert_eq!(original.nlink(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_conflicts_follow_resolution() {
+        let (_temp, src, dst) = setup();
+        fs::write(dst.join("file.txt"), "existing").unwrap();
+        let sources = vec![src.join("file.txt")];
+
+        let complete = run(sources.clone(), dst.clone(), LinkKind::Symlink, None).await;
+        assert_eq!(complete.inner.failed, 1);
+        assert_eq!(
+            fs::read_to_string(dst.join("file.txt")).unwrap(),
+            "existing"
+        );
+
+        let complete = run(
+            sources,
+            dst.clone(),
+            LinkKind::Symlink,
+            Some(ConflictResolution::AutoRename),
+        )
+        .await;
+        assert_eq!(complete.created[0].path, dst.join("file (1).txt"));
+        assert!(dst.join("file (1).txt").is_symlink());
+    }
+
+    #[tokio::test]
+    async fn test_replace_keeps_file_for_undo() {
+        let (_temp, src, dst) = setup();
+        fs::write(dst.join("file.txt"), "existing").unwrap();
+
+        let complete = run(
+            vec![src.join("file.txt")],
+            dst.clone(),
+            LinkKind::Symlink,
+            Some(ConflictResolution::Overwrite),
+        )
+        .await;
+        assert_eq!(complete.inner.succeeded, 1);
+        assert!(dst.join("file.txt").is_symlink());
+        let kept = complete.created[0].replaced.clone().unwrap();
+        assert_eq!(fs::read_to_string(&kept).unwrap(), "existing");
+
+        let mut log = UndoLog::new(10);
+        log.record(
+            UndoableOperation::LinksCreated {
+                links: complete.created,
+            },
+            "link",
+        );
+        let entry = log.pop().unwrap();
+        entry.verify().unwrap();
+        let mut rx = execute_undo(entry.clone());
+        while rx.recv().await.is_some() {}
+        assert!(!dst.join("file.txt").is_symlink());
+        assert_eq!(
+            fs::read_to_string(dst.join("file.txt")).unwrap(),
+            "existing"
+        );
+        assert!(!kept.exists());
+
+        log.record_undone(&entry).unwrap();
+        let redo = log.take_redo(log.peek_redo().unwrap().id).unwrap();
+        redo.verify().unwrap();
+        let mut rx = execute_redo(redo);
+        while rx.recv().await.is_some() {}
+        assert!(dst.join("file.txt").is_symlink());
+        assert_eq!(fs::read_to_string(&kept).unwrap(), "existing");
+    }
+
+    #[test]
+    fn test_failed_replace_restores_file() {
+        let (_temp, _src, dst) = setup();
+        fs::write(dst.join("file.txt"), "existing").unwrap();
+        let record = LinkRecord {
+            path: dst.join("file.txt"),
+            target: dst.join("missing.txt"),
+            kind: LinkKind::Hardlink,
+            replaced: Some(transfer::temp_sibling(
+                &dst.join("file.txt"),
+                REPLACED_SUFFIX,
+            )),
+        };
+
+        assert!(record.create().is_err());
+        assert_eq!(
+            fs::read_to_string(dst.join("file.txt")).unwrap(),
+            "existing"
+        );
+        assert!(!record.replaced.unwrap().exists());
+    }
+
+    #[tokio::test]
+    async fn test_undo_removes_links_and_redo_recreates_them() {
+        let (_temp, src, dst) = setup();
+        let complete = run(
+            vec![src.join("file.txt")],
+            dst.clone(),
+            LinkKind::Hardlink,
+            None,
+        )
+        .await;
+        let mut log = UndoLog::new(10);
+        log.record(
+            UndoableOperation::LinksCreated {
+                links: complete.created,
+            },
+            "link",
+        );
+        let entry = log.pop().unwrap();
+        entry.verify().unwrap();
+
+        let mut rx = execute_undo(entry.clone());
+        while rx.recv().await.is_some() {}
+        assert!(!dst.join("file.txt").exists());
+        assert!(src.join("file.txt").exists());
+
+        log.record_undone(&entry).unwrap();
+        let redo = log.take_redo(log.peek_redo().unwrap().id).unwrap();
+        let mut rx = execute_redo(redo);
+        while rx.recv().await.is_some() {}
+        assert_eq!(fs::read_to_string(dst.join("file.txt")).unwrap(), "content");
+    }
+}
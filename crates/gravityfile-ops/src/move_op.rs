@@ -2,13 +2,15 @@
 
 use std::collections::HashSet;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use tokio::sync::mpsc;
 use tokio_util::sync::CancellationToken;
 
 use crate::conflict::{Conflict, ConflictKind, ConflictResolution, auto_rename_path};
+use crate::copy::CopyOptions;
 use crate::progress::{OperationComplete, OperationProgress, OperationType};
+use crate::transfer;
 use crate::{OPERATION_CHANNEL_SIZE, OperationError};
 
 /// Result sent through the channel during move operations.
@@ -37,6 +39,9 @@ pub struct MoveComplete {
 pub struct MoveOptions {
     /// How to handle conflicts (None means ask for each).
     pub conflict_resolution: Option<ConflictResolution>,
+    /// How data is copied when a move crosses filesystems and falls back to
+    /// copy + delete. Its `conflict_resolution` is not used.
+    pub cross_device: CopyOptions,
 }
 
 /// Start an async move operation.
@@ -113,6 +118,9 @@ async fn move_impl(
     }
 
     for source in sources {
+        if let Some(pause) = &options.cross_device.pause {
+            pause.wait(&token).await;
+        }
         // HIGH-3: check for cancellation before each item
         if token.is_cancelled() {
             break;
@@ -216,10 +224,14 @@ async fn move_impl(
         // MED-6: only send progress after the move completes, not before
         let source_clone = source.clone();
         let dest_clone = final_dest.clone();
+        let copy_options = options.cross_device.clone();
+        let item_token = token.clone();
 
-        let result = tokio::task::spawn_blocking(move || move_item(&source_clone, &dest_clone))
-            .await
-            .map_err(|e| format!("Task failed: {}", e));
+        let result = tokio::task::spawn_blocking(move || {
+            move_item(&source_clone, &dest_clone, &copy_options, &item_token)
+        })
+        .await
+        .map_err(|e| format!("Task failed: {}", e));
 
         match result {
             Ok(Ok(bytes)) => {
@@ -252,7 +264,15 @@ async fn move_impl(
 }
 
 /// Move a single item (file, directory, or symlink).
-fn move_item(source: &PathBuf, dest: &PathBuf) -> Result<u64, String> {
+///
+/// Falls back to copy + delete across filesystems. The source is only removed
+/// once the copy (and its verification, if enabled) succeeded.
+fn move_item(
+    source: &PathBuf,
+    dest: &PathBuf,
+    options: &CopyOptions,
+    token: &CancellationToken,
+) -> Result<u64, String> {
     // HIGH-2: use symlink_metadata so we don't follow symlinks for size
     let size = get_size(source);
 
@@ -265,44 +285,38 @@ fn move_item(source: &PathBuf, dest: &PathBuf) -> Result<u64, String> {
     let metadata =
         fs::symlink_metadata(source).map_err(|e| format!("Failed to read metadata: {}", e))?;
 
-    if metadata.is_symlink() {
-        // For symlinks, read the target and recreate at destination
-        let target = fs::read_link(source).map_err(|e| format!("Failed to read symlink: {}", e))?;
-        #[cfg(unix)]
-        {
-            std::os::unix::fs::symlink(&target, dest)
-                .map_err(|e| format!("Failed to create symlink: {}", e))?;
-        }
-        #[cfg(windows)]
-        {
-            if target.is_dir() {
-                std::os::windows::fs::symlink_dir(&target, dest)
-                    .map_err(|e| format!("Failed to create symlink: {}", e))?;
-            } else {
-                std::os::windows::fs::symlink_file(&target, dest)
-                    .map_err(|e| format!("Failed to create symlink: {}", e))?;
-            }
-        }
-        fs::remove_file(source).map_err(|e| format!("Failed to remove source symlink: {}", e))?;
-    } else if metadata.is_dir() {
-        if let Err(e) = copy_dir_recursive(source, dest, &mut HashSet::new()) {
-            // Best-effort cleanup of partial destination on copy failure.
-            let _ = fs::remove_dir_all(dest);
-            return Err(e);
-        }
-        if let Err(e) = fs::remove_dir_all(source) {
-            // Copy succeeded but source removal failed — both copies exist.
-            // Clean up the destination to avoid silently doubling disk usage.
-            tracing::warn!(
-                "Source removal failed after cross-fs move; cleaning up destination: {}",
-                e
-            );
-            let _ = fs::remove_dir_all(dest);
-            return Err(format!("Failed to remove source after copy: {}", e));
+    if let Err(e) = transfer::copy_entry(source, dest, options, token) {
+        // Best-effort cleanup of partial destination on copy failure.
+        transfer::remove_partial(dest);
+        return Err(e);
+    }
+
+    if options.verify {
+        let mismatches = transfer::verify_entry(source, dest, options);
+        if let Some(first) = mismatches.first() {
+            transfer::remove_partial(dest);
+            return Err(format!(
+                "{} entries failed verification, source kept ({})",
+                mismatches.len(),
+                first
+            ));
         }
+    }
+
+    let removed = if metadata.is_dir() {
+        fs::remove_dir_all(source)
     } else {
-        fs::copy(source, dest).map_err(|e| format!("Failed to copy: {}", e))?;
-        fs::remove_file(source).map_err(|e| format!("Failed to remove source: {}", e))?;
+        fs::remove_file(source)
+    };
+    if let Err(e) = removed {
+        // Copy succeeded but source removal failed — both copies exist.
+        // Clean up the destination to avoid silently doubling disk usage.
+        tracing::warn!(
+            "Source removal failed after cross-fs move; cleaning up destination: {}",
+            e
+        );
+        transfer::remove_partial(dest);
+        return Err(format!("Failed to remove source after copy: {}", e));
     }
 
     Ok(size)
@@ -343,84 +357,19 @@ fn get_dir_size(dir: &PathBuf) -> u64 {
     size
 }
 
-/// Public wrapper for `copy_dir_recursive` used by the undo path in
-/// `executor.rs` for cross-filesystem move reversal.
+/// Copy a directory tree for the undo path in `executor.rs` when reversing a
+/// cross-filesystem move, preserving metadata.
 pub fn copy_dir_recursive_pub(
-    source: &PathBuf,
-    dest: &PathBuf,
-    visited: &mut HashSet<u64>,
-) -> Result<(), String> {
-    copy_dir_recursive(source, dest, visited)
-}
-
-/// Recursively copy a directory (for cross-filesystem moves).
-///
-/// Uses `entry.file_type()` (no symlink-follow), handles symlinks as a distinct
-/// branch, and tracks inodes via a visited set to detect loops on Unix.
-fn copy_dir_recursive(
-    source: &PathBuf,
-    dest: &PathBuf,
+    source: &Path,
+    dest: &Path,
     visited: &mut HashSet<u64>,
 ) -> Result<(), String> {
-    // Loop detection via inode on Unix
-    #[cfg(unix)]
-    {
-        use std::os::unix::fs::MetadataExt;
-        if let Ok(meta) = fs::symlink_metadata(source) {
-            let inode = meta.ino();
-            if !visited.insert(inode) {
-                return Ok(()); // Already visited — break the loop
-            }
-        }
-    }
-
-    fs::create_dir_all(dest).map_err(|e| format!("Failed to create directory: {}", e))?;
-
-    let entries = fs::read_dir(source).map_err(|e| format!("Failed to read directory: {}", e))?;
-
-    for entry in entries {
-        let entry = entry.map_err(|e| format!("Failed to read entry: {}", e))?;
-        // CRIT-2: use entry.file_type() — does NOT follow symlinks
-        let file_type = entry
-            .file_type()
-            .map_err(|e| format!("Failed to read file type: {}", e))?;
-        let path = entry.path();
-        let dest_path = dest.join(entry.file_name());
-
-        if file_type.is_symlink() {
-            // Recreate the symlink at the destination instead of following it
-            let target =
-                fs::read_link(&path).map_err(|e| format!("Failed to read symlink: {}", e))?;
-            #[cfg(unix)]
-            {
-                std::os::unix::fs::symlink(&target, &dest_path)
-                    .map_err(|e| format!("Failed to create symlink: {}", e))?;
-            }
-            #[cfg(windows)]
-            {
-                // Choose symlink_dir vs symlink_file based on what the
-                // original link pointed at, and log failures rather than
-                // silently discarding them.
-                let result = if path.is_dir() {
-                    std::os::windows::fs::symlink_dir(&target, &dest_path)
-                } else {
-                    std::os::windows::fs::symlink_file(&target, &dest_path)
-                };
-                if let Err(e) = result {
-                    tracing::warn!(
-                        "Failed to create symlink {} -> {}: {}",
-                        dest_path.display(),
-                        target.display(),
-                        e
-                    );
-                }
-            }
-        } else if file_type.is_dir() {
-            copy_dir_recursive(&path, &dest_path, visited)?;
-        } else {
-            fs::copy(&path, &dest_path).map_err(|e| format!("Failed to copy file: {}", e))?;
-        }
-    }
-
-    Ok(())
+    transfer::copy_tree(
+        source,
+        dest,
+        &CopyOptions::archive(),
+        visited,
+        &CancellationToken::new(),
+    )
+    .map(|_| ())
 }
@@ -4,6 +4,9 @@ use std::path::PathBuf;
 
 use serde::{Deserialize, Serialize};
 
+use crate::link::LinkKind;
+use crate::permissions::{ChmodSpec, ChownSpec};
+
 /// A file operation to be executed.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum FileOperation {
@@ -19,11 +22,31 @@ pub enum FileOperation {
     },
     /// Rename a single file or directory.
     Rename { source: PathBuf, new_name: String },
+    /// Rename many files at once, each to a new name in its own directory.
+    BatchRename { renames: Vec<(PathBuf, String)> },
     /// Delete files/directories.
     Delete {
         targets: Vec<PathBuf>,
         use_trash: bool,
     },
+    /// Securely delete files/directories by overwriting them before unlinking.
+    Shred { targets: Vec<PathBuf>, passes: u32 },
+    /// Change permission bits.
+    Chmod {
+        targets: Vec<PathBuf>,
+        spec: ChmodSpec,
+    },
+    /// Change owner and/or group.
+    Chown {
+        targets: Vec<PathBuf>,
+        spec: ChownSpec,
+    },
+    /// Create symlinks or hardlinks to sources in a destination directory.
+    Link {
+        sources: Vec<PathBuf>,
+        destination: PathBuf,
+        kind: LinkKind,
+    },
     /// Create a new empty file.
     CreateFile { path: PathBuf },
     /// Create a new directory.
@@ -55,11 +78,40 @@ impl FileOperation {
         }
     }
 
+    /// Create a batch rename operation from `(path, new_name)` pairs.
+    pub fn batch_rename(renames: Vec<(PathBuf, String)>) -> Self {
+        Self::BatchRename { renames }
+    }
+
     /// Create a delete operation.
     pub fn delete(targets: Vec<PathBuf>, use_trash: bool) -> Self {
         Self::Delete { targets, use_trash }
     }
 
+    /// Create a secure-delete operation with `passes` random overwrites.
+    pub fn shred(targets: Vec<PathBuf>, passes: u32) -> Self {
+        Self::Shred { targets, passes }
+    }
+
+    /// Create a chmod operation.
+    pub fn chmod(targets: Vec<PathBuf>, spec: ChmodSpec) -> Self {
+        Self::Chmod { targets, spec }
+    }
+
+    /// Create a chown/chgrp operation.
+    pub fn chown(targets: Vec<PathBuf>, spec: ChownSpec) -> Self {
+        Self::Chown { targets, spec }
+    }
+
+    /// Create a link operation.
+    pub fn link(sources: Vec<PathBuf>, destination: PathBuf, kind: LinkKind) -> Self {
+        Self::Link {
+            sources,
+            destination,
+            kind,
+        }
+    }
+
     /// Create a file creation operation.
     pub fn create_file(path: PathBuf) -> Self {
         Self::CreateFile { path }
@@ -69,6 +121,19 @@ impl FileOperation {
     pub fn create_directory(path: PathBuf) -> Self {
         Self::CreateDirectory { path }
     }
+
+    /// Whether the operation can be held once it is running. Single renames
+    /// and creations finish in one step, and a batch rename must not stop
+    /// while items sit under temporary names.
+    pub fn can_pause(&self) -> bool {
+        !matches!(
+            self,
+            Self::Rename { .. }
+                | Self::BatchRename { .. }
+                | Self::CreateFile { .. }
+                | Self::CreateDirectory { .. }
+        )
+    }
 }
 
 /// An error that occurred during a file operation.
@@ -0,0 +1,923 @@
+//! Permission and ownership changes (chmod, chown, chgrp).
+//!
+//! Modes are given as octal (`755`) or symbolic (`u+rwx,go-w`, `a=rX`)
+//! specifications and can be applied to files and directories separately.
+//! Every change records the previous mode or owner of each path it touched so
+//! it can be undone.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+
+use crate::jobs::PauseToken;
+use crate::progress::{OperationComplete, OperationProgress, OperationType};
+use crate::undo::UndoableOperation;
+use crate::{OPERATION_CHANNEL_SIZE, OperationError};
+
+/// Mask of the bits chmod manages: permissions plus setuid, setgid and sticky.
+const MODE_MASK: u32 = 0o7777;
+
+/// Who a symbolic clause applies to, as a mask over [`MODE_MASK`].
+const WHO_USER: u32 = 0o4700;
+const WHO_GROUP: u32 = 0o2070;
+const WHO_OTHER: u32 = 0o1007;
+
+/// One `who op perms` step of a symbolic mode, e.g. the `go-w` in `u+x,go-w`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct SymbolicStep {
+    who: u32,
+    op: char,
+    perms: SymbolicPerms,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+struct SymbolicPerms {
+    bits: u32,
+    /// `X`: execute only for directories or files already executable by someone.
+    conditional_exec: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ModeKind {
+    Octal(u32),
+    Symbolic(Vec<SymbolicStep>),
+}
+
+/// A parsed chmod mode specification.
+///
+/// An empty `who` in a symbolic clause means all of user, group and other;
+/// unlike `chmod(1)` the umask is not consulted.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct ModeSpec {
+    text: String,
+    kind: ModeKind,
+}
+
+impl ModeSpec {
+    /// Parse an octal or symbolic mode.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let text = spec.trim();
+        if text.is_empty() {
+            return Err("Mode is empty".to_string());
+        }
+
+        let kind = if text.chars().all(|c| c.is_ascii_digit()) {
+            if text.len() > 4 {
+                return Err(format!("Octal mode '{}' has too many digits", text));
+            }
+            let mode = u32::from_str_radix(text, 8)
+                .map_err(|_| format!("Invalid octal mode '{}'", text))?;
+            ModeKind::Octal(mode)
+        } else {
+            let mut steps = Vec::new();
+            for clause in text.split(',') {
+                parse_clause(clause, &mut steps)?;
+            }
+            ModeKind::Symbolic(steps)
+        };
+
+        Ok(Self {
+            text: text.to_string(),
+            kind,
+        })
+    }
+
+    /// The specification as it was written.
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    /// Compute the new mode of an item currently at `mode`.
+    pub fn apply(&self, mode: u32, is_dir: bool) -> u32 {
+        let mode = mode & MODE_MASK;
+        match &self.kind {
+            ModeKind::Octal(new) => *new,
+            ModeKind::Symbolic(steps) => steps.iter().fold(mode, |mode, step| {
+                let mut bits = step.perms.bits;
+                if step.perms.conditional_exec && (is_dir || mode & 0o111 != 0) {
+                    bits |= 0o111;
+                }
+                let bits = bits & step.who;
+                match step.op {
+                    '+' => mode | bits,
+                    '-' => mode & !bits,
+                    _ => (mode & !step.who) | bits,
+                }
+            }),
+        }
+    }
+}
+
+impl std::fmt::Display for ModeSpec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+impl TryFrom<String> for ModeSpec {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::parse(&value)
+    }
+}
+
+impl From<ModeSpec> for String {
+    fn from(spec: ModeSpec) -> Self {
+        spec.text
+    }
+}
+
+fn parse_clause(clause: &str, steps: &mut Vec<SymbolicStep>) -> Result<(), String> {
+    let invalid = || format!("Invalid symbolic mode '{}'", clause);
+    let mut chars = clause.chars().peekable();
+
+    let mut who = 0;
+    while let Some(&c) = chars.peek() {
+        who |= match c {
+            'u' => WHO_USER,
+            'g' => WHO_GROUP,
+            'o' => WHO_OTHER,
+            'a' => MODE_MASK,
+            _ => break,
+        };
+        chars.next();
+    }
+    if who == 0 {
+        who = MODE_MASK;
+    }
+
+    let mut op = chars.next().ok_or_else(invalid)?;
+    loop {
+        if !matches!(op, '+' | '-' | '=') {
+            return Err(invalid());
+        }
+        let mut perms = SymbolicPerms::default();
+        let mut next_op = None;
+        for c in chars.by_ref() {
+            match c {
+                'r' => perms.bits |= 0o444,
+                'w' => perms.bits |= 0o222,
+                'x' => perms.bits |= 0o111,
+                'X' => perms.conditional_exec = true,
+                's' => perms.bits |= 0o6000,
+                't' => perms.bits |= 0o1000,
+                '+' | '-' | '=' => {
+                    next_op = Some(c);
+                    break;
+                }
+                _ => return Err(invalid()),
+            }
+        }
+        steps.push(SymbolicStep { who, op, perms });
+        match next_op {
+            Some(c) => op = c,
+            None => return Ok(()),
+        }
+    }
+}
+
+/// Render permission bits as `ls -l` does, e.g. `rwxr-sr-t`.
+pub fn format_mode(mode: u32) -> String {
+    let triplet = |shift: u32, special: u32, set: char, unset: char| {
+        let bits = (mode >> shift) & 0o7;
+        let exec = bits & 0o1 != 0;
+        let special = mode & special != 0;
+        [
+            if bits & 0o4 != 0 { 'r' } else { '-' },
+            if bits & 0o2 != 0 { 'w' } else { '-' },
+            match (exec, special) {
+                (true, true) => set,
+                (false, true) => unset,
+                (true, false) => 'x',
+                (false, false) => '-',
+            },
+        ]
+    };
+    triplet(6, 0o4000, 's', 'S')
+        .into_iter()
+        .chain(triplet(3, 0o2000, 's', 'S'))
+        .chain(triplet(0, 0o1000, 't', 'T'))
+        .collect()
+}
+
+/// What a chmod operation changes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChmodSpec {
+    /// Mode applied to regular files (and other non-directories).
+    pub files: Option<ModeSpec>,
+    /// Mode applied to directories.
+    pub dirs: Option<ModeSpec>,
+    /// Descend into directories.
+    pub recursive: bool,
+}
+
+impl ChmodSpec {
+    /// Apply the same mode to files and directories.
+    pub fn all(mode: ModeSpec, recursive: bool) -> Self {
+        Self {
+            files: Some(mode.clone()),
+            dirs: Some(mode),
+            recursive,
+        }
+    }
+
+    /// The new mode of an item, or `None` if the spec leaves it alone.
+    pub fn new_mode(&self, mode: u32, is_dir: bool) -> Option<u32> {
+        let spec = if is_dir { &self.dirs } else { &self.files };
+        let new = spec.as_ref()?.apply(mode, is_dir);
+        (new != mode & MODE_MASK).then_some(new)
+    }
+}
+
+impl std::fmt::Display for ChmodSpec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (&self.files, &self.dirs) {
+            (Some(files), Some(dirs)) if files == dirs => write!(f, "{}", files),
+            (Some(files), Some(dirs)) => write!(f, "files {}, dirs {}", files, dirs),
+            (Some(files), None) => write!(f, "files {}", files),
+            (None, Some(dirs)) => write!(f, "dirs {}", dirs),
+            (None, None) => write!(f, "nothing"),
+        }
+    }
+}
+
+/// What a chown/chgrp operation changes. Owners and groups are names or
+/// numeric IDs.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChownSpec {
+    /// New owner, if it changes.
+    pub owner: Option<String>,
+    /// New group, if it changes.
+    pub group: Option<String>,
+    /// Descend into directories.
+    pub recursive: bool,
+}
+
+impl ChownSpec {
+    /// Parse `owner`, `owner:group`, `owner:` or `:group` as `chown(1)` does.
+    pub fn parse(spec: &str, recursive: bool) -> Result<Self, String> {
+        let non_empty = |s: &str| (!s.is_empty()).then(|| s.to_string());
+        let (owner, group) = match spec.split_once(':') {
+            Some((owner, group)) => (non_empty(owner), non_empty(group)),
+            None => (non_empty(spec), None),
+        };
+        if owner.is_none() && group.is_none() {
+            return Err("Owner and group are both empty".to_string());
+        }
+        Ok(Self {
+            owner,
+            group,
+            recursive,
+        })
+    }
+}
+
+impl std::fmt::Display for ChownSpec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (&self.owner, &self.group) {
+            (Some(owner), Some(group)) => write!(f, "{}:{}", owner, group),
+            (Some(owner), None) => write!(f, "{}", owner),
+            (None, Some(group)) => write!(f, ":{}", group),
+            (None, None) => Ok(()),
+        }
+    }
+}
+
+/// Previous and new mode of a path changed by chmod.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModeRecord {
+    /// Path whose mode changed.
+    pub path: PathBuf,
+    /// Mode before the change.
+    pub before: u32,
+    /// Mode after the change.
+    pub after: u32,
+}
+
+/// Previous and new `(uid, gid)` of a path changed by chown.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OwnerRecord {
+    /// Path whose owner changed.
+    pub path: PathBuf,
+    /// Owner and group before the change.
+    pub before: (u32, u32),
+    /// Owner and group after the change.
+    pub after: (u32, u32),
+}
+
+/// Result sent through the channel during chmod and chown operations.
+#[derive(Debug)]
+pub enum AttributeResult {
+    /// Progress update.
+    Progress(OperationProgress),
+    /// The operation completed.
+    Complete(AttributeComplete),
+}
+
+/// Completion of a chmod or chown operation, with the undo record of every
+/// path that actually changed.
+#[derive(Debug)]
+pub struct AttributeComplete {
+    /// Standard operation completion info.
+    pub inner: OperationComplete,
+    /// Undo record, `None` if nothing changed.
+    pub undo: Option<UndoableOperation>,
+}
+
+/// Start an async chmod operation. A `pause` token holds it between paths.
+pub fn start_chmod(
+    targets: Vec<PathBuf>,
+    spec: ChmodSpec,
+    pause: Option<PauseToken>,
+    token: CancellationToken,
+) -> mpsc::Receiver<AttributeResult> {
+    let (tx, rx) = mpsc::channel(OPERATION_CHANNEL_SIZE);
+    tokio::spawn(async move {
+        let recursive = spec.recursive;
+        let change = move |path: &Path, meta: &fs::Metadata| chmod_path(path, meta, &spec);
+        let (inner, records) = attribute_impl(
+            OperationType::Chmod,
+            targets,
+            recursive,
+            pause.unwrap_or_default(),
+            &token,
+            &tx,
+            change,
+        )
+        .await;
+        let undo = (!records.is_empty())
+            .then_some(UndoableOperation::PermissionsChanged { changes: records });
+        let _ = tx
+            .send(AttributeResult::Complete(AttributeComplete { inner, undo }))
+            .await;
+    });
+    rx
+}
+
+/// Start an async chown/chgrp operation. Symlinks themselves are changed,
+/// not their targets. A `pause` token holds it between paths.
+pub fn start_chown(
+    targets: Vec<PathBuf>,
+    spec: ChownSpec,
+    pause: Option<PauseToken>,
+    token: CancellationToken,
+) -> mpsc::Receiver<AttributeResult> {
+    let (tx, rx) = mpsc::channel(OPERATION_CHANNEL_SIZE);
+    tokio::spawn(async move {
+        let ids = resolve_chown(&spec);
+        let (inner, records) = match ids {
+            Ok(ids) => {
+                let change = move |path: &Path, meta: &fs::Metadata| chown_path(path, meta, ids);
+                attribute_impl(
+                    OperationType::Chown,
+                    targets,
+                    spec.recursive,
+                    pause.unwrap_or_default(),
+                    &token,
+                    &tx,
+                    change,
+                )
+                .await
+            }
+            Err(e) => {
+                let errors: Vec<_> = targets
+                    .into_iter()
+                    .map(|t| OperationError::new(t, e.clone()))
+                    .collect();
+                (
+                    OperationComplete {
+                        operation_type: OperationType::Chown,
+                        succeeded: 0,
+                        failed: errors.len(),
+                        bytes_processed: 0,
+                        errors,
+                    },
+                    Vec::new(),
+                )
+            }
+        };
+        let undo = (!records.is_empty())
+            .then_some(UndoableOperation::OwnershipChanged { changes: records });
+        let _ = tx
+            .send(AttributeResult::Complete(AttributeComplete { inner, undo }))
+            .await;
+    });
+    rx
+}
+
+/// Walk each target (recursively if asked) on a blocking thread and apply
+/// `change` to every path, collecting the records of what changed.
+async fn attribute_impl<R, F>(
+    operation_type: OperationType,
+    targets: Vec<PathBuf>,
+    recursive: bool,
+    pause: PauseToken,
+    token: &CancellationToken,
+    tx: &mpsc::Sender<AttributeResult>,
+    change: F,
+) -> (OperationComplete, Vec<R>)
+where
+    R: Send + 'static,
+    F: Fn(&Path, &fs::Metadata) -> Result<Option<R>, String> + Clone + Send + 'static,
+{
+    let mut progress = OperationProgress::new(operation_type, targets.len(), 0);
+    let mut records = Vec::new();
+    let mut succeeded = 0;
+    let mut failed = 0;
+
+    for target in targets {
+        pause.wait(token).await;
+        if token.is_cancelled() {
+            break;
+        }
+        progress.set_current_file(Some(target.clone()));
+        let _ = tx.send(AttributeResult::Progress(progress.clone())).await;
+
+        let change = change.clone();
+        let path = target.clone();
+        let walk_pause = pause.clone();
+        let walk_token = token.clone();
+        let result = tokio::task::spawn_blocking(move || {
+            let mut records = Vec::new();
+            let mut errors = Vec::new();
+            walk(
+                &path,
+                recursive,
+                &walk_pause,
+                &walk_token,
+                &change,
+                &mut records,
+                &mut errors,
+            );
+            (records, errors)
+        })
+        .await;
+
+        match result {
+            Ok((changed, errors)) => {
+                records.extend(changed);
+                if errors.is_empty() {
+                    progress.complete_file(0);
+                    succeeded += 1;
+                } else {
+                    progress.errors.extend(errors);
+                    failed += 1;
+                }
+            }
+            Err(e) => {
+                progress.add_error(OperationError::new(target, format!("Task failed: {}", e)));
+                failed += 1;
+            }
+        }
+    }
+
+    (
+        OperationComplete {
+            operation_type,
+            succeeded,
+            failed,
+            bytes_processed: 0,
+            errors: progress.errors,
+        },
+        records,
+    )
+}
+
+fn walk<R, F>(
+    path: &Path,
+    recursive: bool,
+    pause: &PauseToken,
+    token: &CancellationToken,
+    change: &F,
+    records: &mut Vec<R>,
+    errors: &mut Vec<OperationError>,
+) where
+    F: Fn(&Path, &fs::Metadata) -> Result<Option<R>, String>,
+{
+    let meta = match fs::symlink_metadata(path) {
+        Ok(meta) => meta,
+        Err(e) => {
+            errors.push(OperationError::new(
+                path.to_path_buf(),
+                format!("Cannot access path: {}", e),
+            ));
+            return;
+        }
+    };
+    match change(path, &meta) {
+        Ok(Some(record)) => records.push(record),
+        Ok(None) => {}
+        Err(e) => errors.push(OperationError::new(path.to_path_buf(), e)),
+    }
+
+    // Never descend through symlinks.
+    if !recursive || !meta.is_dir() {
+        return;
+    }
+    let entries = match fs::read_dir(path) {
+        Ok(entries) => entries,
+        Err(e) => {
+            errors.push(OperationError::new(
+                path.to_path_buf(),
+                format!("Failed to read directory: {}", e),
+            ));
+            return;
+        }
+    };
+    for entry in entries.flatten() {
+        pause.wait_blocking(token);
+        if token.is_cancelled() {
+            return;
+        }
+        walk(
+            &entry.path(),
+            recursive,
+            pause,
+            token,
+            change,
+            records,
+            errors,
+        );
+    }
+}
+
+/// Paths under `targets` that a chmod with `spec` would change, without
+/// changing anything.
+pub(crate) fn preview_chmod(
+    targets: &[PathBuf],
+    spec: &ChmodSpec,
+) -> (Vec<PathBuf>, Vec<OperationError>) {
+    preview(targets, spec.recursive, |path, meta| {
+        Ok(mode_of(meta)
+            .and_then(|mode| spec.new_mode(mode, meta.is_dir()))
+            .map(|_| path.to_path_buf()))
+    })
+}
+
+/// Paths under `targets` that a chown with `spec` would change, without
+/// changing anything.
+pub(crate) fn preview_chown(
+    targets: &[PathBuf],
+    spec: &ChownSpec,
+) -> (Vec<PathBuf>, Vec<OperationError>) {
+    let (uid, gid) = match resolve_chown(spec) {
+        Ok(ids) => ids,
+        Err(e) => {
+            let errors = targets
+                .iter()
+                .map(|t| OperationError::new(t.clone(), e.clone()))
+                .collect();
+            return (Vec::new(), errors);
+        }
+    };
+    preview(targets, spec.recursive, |path, meta| {
+        Ok(owner_of(meta)
+            .filter(|&(u, g)| uid.is_some_and(|uid| uid != u) || gid.is_some_and(|gid| gid != g))
+            .map(|_| path.to_path_buf()))
+    })
+}
+
+fn preview(
+    targets: &[PathBuf],
+    recursive: bool,
+    check: impl Fn(&Path, &fs::Metadata) -> Result<Option<PathBuf>, String>,
+) -> (Vec<PathBuf>, Vec<OperationError>) {
+    let pause = PauseToken::new();
+    let token = CancellationToken::new();
+    let mut paths = Vec::new();
+    let mut errors = Vec::new();
+    for target in targets {
+        walk(
+            target,
+            recursive,
+            &pause,
+            &token,
+            &check,
+            &mut paths,
+            &mut errors,
+        );
+    }
+    (paths, errors)
+}
+
+#[cfg(unix)]
+fn mode_of(meta: &fs::Metadata) -> Option<u32> {
+    use std::os::unix::fs::PermissionsExt;
+    (!meta.is_symlink()).then(|| meta.permissions().mode() & MODE_MASK)
+}
+
+#[cfg(not(unix))]
+fn mode_of(_meta: &fs::Metadata) -> Option<u32> {
+    None
+}
+
+#[cfg(unix)]
+fn owner_of(meta: &fs::Metadata) -> Option<(u32, u32)> {
+    use std::os::unix::fs::MetadataExt;
+    Some((meta.uid(), meta.gid()))
+}
+
+#[cfg(not(unix))]
+fn owner_of(_meta: &fs::Metadata) -> Option<(u32, u32)> {
+    None
+}
+
+/// Chmod one path. Symlinks are skipped since their mode is meaningless and
+/// changing it would change the target instead.
+#[cfg(unix)]
+fn chmod_path(
+    path: &Path,
+    meta: &fs::Metadata,
+    spec: &ChmodSpec,
+) -> Result<Option<ModeRecord>, String> {
+    use std::os::unix::fs::PermissionsExt;
+
+    if meta.is_symlink() {
+        return Ok(None);
+    }
+    let before = meta.permissions().mode() & MODE_MASK;
+    let Some(after) = spec.new_mode(before, meta.is_dir()) else {
+        return Ok(None);
+    };
+    fs::set_permissions(path, fs::Permissions::from_mode(after))
+        .map_err(|e| format!("Failed to change mode: {}", e))?;
+    Ok(Some(ModeRecord {
+        path: path.to_path_buf(),
+        before,
+        after,
+    }))
+}
+
+#[cfg(not(unix))]
+fn chmod_path(
+    _path: &Path,
+    _meta: &fs::Metadata,
+    _spec: &ChmodSpec,
+) -> Result<Option<ModeRecord>, String> {
+    Err("Changing permissions is not supported on this platform".to_string())
+}
+
+/// Chown one path without following symlinks.
+#[cfg(unix)]
+fn chown_path(
+    path: &Path,
+    meta: &fs::Metadata,
+    (uid, gid): (Option<u32>, Option<u32>),
+) -> Result<Option<OwnerRecord>, String> {
+    use std::os::unix::fs::MetadataExt;
+
+    let before = (meta.uid(), meta.gid());
+    let after = (uid.unwrap_or(before.0), gid.unwrap_or(before.1));
+    if after == before {
+        return Ok(None);
+    }
+    set_owner(path, after)?;
+    Ok(Some(OwnerRecord {
+        path: path.to_path_buf(),
+        before,
+        after,
+    }))
+}
+
+#[cfg(not(unix))]
+fn chown_path(
+    _path: &Path,
+    _meta: &fs::Metadata,
+    _ids: (Option<u32>, Option<u32>),
+) -> Result<Option<OwnerRecord>, String> {
+    Err("Changing ownership is not supported on this platform".to_string())
+}
+
+/// Restore a recorded mode.
+#[cfg(unix)]
+pub(crate) fn set_mode(path: &Path, mode: u32) -> Result<(), String> {
+    use std::os::unix::fs::PermissionsExt;
+
+    fs::set_permissions(path, fs::Permissions::from_mode(mode))
+        .map_err(|e| format!("Failed to change mode: {}", e))
+}
+
+#[cfg(not(unix))]
+pub(crate) fn set_mode(_path: &Path, _mode: u32) -> Result<(), String> {
+    Err("Changing permissions is not supported on this platform".to_string())
+}
+
+/// Set owner and group without following symlinks.
+#[cfg(unix)]
+pub(crate) fn set_owner(path: &Path, (uid, gid): (u32, u32)) -> Result<(), String> {
+    std::os::unix::fs::lchown(path, Some(uid), Some(gid))
+        .map_err(|e| format!("Failed to change owner: {}", e))
+}
+
+#[cfg(not(unix))]
+pub(crate) fn set_owner(_path: &Path, _ids: (u32, u32)) -> Result<(), String> {
+    Err("Changing ownership is not supported on this platform".to_string())
+}
+
+/// Current mode of `path` (without following symlinks), for undo checks.
+#[cfg(unix)]
+pub(crate) fn current_mode(path: &Path) -> Option<u32> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::symlink_metadata(path)
+        .ok()
+        .map(|m| m.permissions().mode() & MODE_MASK)
+}
+
+#[cfg(not(unix))]
+pub(crate) fn current_mode(_path: &Path) -> Option<u32> {
+    None
+}
+
+/// Current `(uid, gid)` of `path` (without following symlinks), for undo checks.
+#[cfg(unix)]
+pub(crate) fn current_owner(path: &Path) -> Option<(u32, u32)> {
+    use std::os::unix::fs::MetadataExt;
+    fs::symlink_metadata(path).ok().map(|m| (m.uid(), m.gid()))
+}
+
+#[cfg(not(unix))]
+pub(crate) fn current_owner(_path: &Path) -> Option<(u32, u32)> {
+    None
+}
+
+/// Resolve the owner and group names of a chown spec to IDs.
+fn resolve_chown(spec: &ChownSpec) -> Result<(Option<u32>, Option<u32>), String> {
+    let uid = spec.owner.as_deref().map(resolve_user).transpose()?;
+    let gid = spec.group.as_deref().map(resolve_group).transpose()?;
+    Ok((uid, gid))
+}
+
+/// Resolve a user name or numeric ID.
+pub fn resolve_user(name: &str) -> Result<u32, String> {
+    if let Ok(id) = name.parse() {
+        return Ok(id);
+    }
+    gravityfile_core::user_id(name).ok_or_else(|| format!("Unknown user '{}'", name))
+}
+
+/// Resolve a group name or numeric ID.
+pub fn resolve_group(name: &str) -> Result<u32, String> {
+    if let Ok(id) = name.parse() {
+        return Ok(id);
+    }
+    gravityfile_core::group_id(name).ok_or_else(|| format!("Unknown group '{}'", name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_octal() {
+        let spec = ModeSpec::parse("755").unwrap();
+        assert_eq!(spec.apply(0o600, false), 0o755);
+        assert_eq!(ModeSpec::parse("4755").unwrap().apply(0, false), 0o4755);
+        assert!(ModeSpec::parse("75555").is_err());
+        assert!(ModeSpec::parse("789").is_err());
+        assert!(ModeSpec::parse("").is_err());
+    }
+
+    #[test]
+    fn test_parse_symbolic() {
+        let apply = |spec: &str, mode: u32, is_dir: bool| {
+            ModeSpec::parse(spec).unwrap().apply(mode, is_dir)
+        };
+        assert_eq!(apply("u+x", 0o644, false), 0o744);
+        assert_eq!(apply("go-w", 0o666, false), 0o644);
+        assert_eq!(apply("a=r", 0o777, false), 0o444);
+        assert_eq!(apply("+x", 0o644, false), 0o755);
+        assert_eq!(apply("u=rw,go=r", 0o777, false), 0o644);
+        assert_eq!(apply("u+rw-x", 0o500, false), 0o600);
+        assert_eq!(apply("g+s", 0o755, true), 0o2755);
+        assert_eq!(apply("o+t", 0o777, true), 0o1777);
+        assert!(ModeSpec::parse("u*x").is_err());
+        assert!(ModeSpec::parse("u+q").is_err());
+        assert!(ModeSpec::parse("ug").is_err());
+    }
+
+    #[test]
+    fn test_conditional_execute() {
+        let spec = ModeSpec::parse("a+rX").unwrap();
+        assert_eq!(spec.apply(0o600, false), 0o644);
+        assert_eq!(spec.apply(0o700, false), 0o755);
+        assert_eq!(spec.apply(0o700, true), 0o755);
+        assert_eq!(spec.apply(0o600, true), 0o755);
+    }
+
+    #[test]
+    fn test_format_mode() {
+        assert_eq!(format_mode(0o755), "rwxr-xr-x");
+        assert_eq!(format_mode(0o640), "rw-r-----");
+        assert_eq!(format_mode(0o4755), "rwsr-xr-x");
+        assert_eq!(format_mode(0o2745), "rwxr-Sr-x");
+        assert_eq!(format_mode(0o1777), "rwxrwxrwt");
+    }
+
+    #[test]
+    fn test_chown_spec_parse() {
+        let spec = ChownSpec::parse("alice:staff", false).unwrap();
+        assert_eq!(spec.owner.as_deref(), Some("alice"));
+        assert_eq!(spec.group.as_deref(), Some("staff"));
+        let spec = ChownSpec::parse(":staff", true).unwrap();
+        assert_eq!(spec.owner, None);
+        assert_eq!(spec.to_string(), ":staff");
+        assert!(ChownSpec::parse(":", false).is_err());
+    }
+
+    #[test]
+    fn test_mode_spec_serde_roundtrip() {
+        let spec = ChmodSpec::all(ModeSpec::parse("u+x,go-w").unwrap(), true);
+        let json = serde_json::to_string(&spec).unwrap();
+        assert!(json.contains("\"u+x,go-w\""));
+        let back: ChmodSpec = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, spec);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_chmod_recursive_with_file_dir_split() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp = tempfile::TempDir::new().unwrap();
+        let root = temp.path().join("share");
+        fs::create_dir_all(root.join("sub")).unwrap();
+        fs::write(root.join("sub/a.txt"), b"a").unwrap();
+        fs::set_permissions(root.join("sub/a.txt"), fs::Permissions::from_mode(0o600)).unwrap();
+        fs::set_permissions(root.join("sub"), fs::Permissions::from_mode(0o700)).unwrap();
+        fs::set_permissions(&root, fs::Permissions::from_mode(0o700)).unwrap();
+
+        let spec = ChmodSpec {
+            files: Some(ModeSpec::parse("644").unwrap()),
+            dirs: Some(ModeSpec::parse("755").unwrap()),
+            recursive: true,
+        };
+        let mut rx = start_chmod(vec![root.clone()], spec, None, CancellationToken::new());
+        let mut complete = None;
+        while let Some(result) = rx.recv().await {
+            if let AttributeResult::Complete(c) = result {
+                complete = Some(c);
+            }
+        }
+        let complete = complete.unwrap();
+        assert_eq!(complete.inner.succeeded, 1);
+
+        let mode = |p: &Path| fs::metadata(p).unwrap().permissions().mode() & 0o7777;
+        assert_eq!(mode(&root), 0o755);
+        assert_eq!(mode(&root.join("sub")), 0o755);
+        assert_eq!(mode(&root.join("sub/a.txt")), 0o644);
+
+        let Some(UndoableOperation::PermissionsChanged { changes }) = complete.undo else {
+            panic!("expected a permissions undo record");
+        };
+        assert_eq!(changes.len(), 3);
+        let file = changes.iter().find(|r| r.path.ends_with("a.txt")).unwrap();
+        assert_eq!((file.before, file.after), (0o600, 0o644));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_chmod_undo_restores_modes() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp = tempfile::TempDir::new().unwrap();
+        let file = temp.path().join("key.pem");
+        fs::write(&file, b"k").unwrap();
+        fs::set_permissions(&file, fs::Permissions::from_mode(0o600)).unwrap();
+
+        let spec = ChmodSpec::all(ModeSpec::parse("go+r").unwrap(), false);
+        let mut rx = start_chmod(vec![file.clone()], spec, None, CancellationToken::new());
+        let mut undo = None;
+        while let Some(result) = rx.recv().await {
+            if let AttributeResult::Complete(c) = result {
+                undo = c.undo;
+            }
+        }
+        let mode = || fs::metadata(&file).unwrap().permissions().mode() & 0o7777;
+        assert_eq!(mode(), 0o644);
+
+        let mut log = crate::UndoLog::new(10);
+        log.record_operation(undo.unwrap());
+        let entry = log.pop().unwrap();
+        let mut rx = crate::execute_undo(entry);
+        while let Some(result) = rx.recv().await {
+            if let crate::OperationResult::Complete(c) = result {
+                assert_eq!(c.succeeded, 1, "{:?}", c.errors);
+            }
+        }
+        assert_eq!(mode(), 0o600);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_resolve_numeric_and_root() {
+        assert_eq!(resolve_user("1234").unwrap(), 1234);
+        assert_eq!(resolve_group("42").unwrap(), 42);
+        assert_eq!(resolve_user("root").unwrap(), 0);
+        assert!(resolve_user("no-such-user-gravityfile").is_err());
+    }
+}
@@ -0,0 +1,739 @@
+//! Dry-run planning for file operations.
+//!
+//! [`plan_operation`] expands a [`FileOperation`] into the concrete steps it
+//! would perform, without modifying the filesystem. The plan predicts the same
+//! conflicts the copy and move engines would report, tells same-filesystem
+//! renames apart from cross-device moves, and checks the bytes to be written
+//! against the free space of the destination filesystem.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::batch_rename::{RenameStatus, check_renames};
+use crate::conflict::{Conflict, ConflictKind, ConflictResolution, auto_rename_path};
+use crate::link::{LinkKind, Placement, check_source, place};
+use crate::permissions::{preview_chmod, preview_chown};
+use crate::progress::OperationType;
+use crate::rename::validate_filename;
+use crate::{FileOperation, OperationError};
+
+/// A single concrete action within an [`OperationPlan`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlanAction {
+    /// A new regular file will be written.
+    CreateFile,
+    /// An existing file will be replaced.
+    OverwriteFile,
+    /// A new directory will be created.
+    CreateDirectory,
+    /// A symlink will be created at the destination.
+    CreateSymlink,
+    /// A hardlink will be created at the destination.
+    CreateHardlink,
+    /// An existing destination will be removed before writing.
+    RemoveExisting,
+    /// The item will be renamed in place (same filesystem).
+    Rename,
+    /// The item will be copied to another filesystem and the source removed.
+    CrossDeviceMove,
+    /// The item will be permanently deleted.
+    Delete,
+    /// The item will be moved to the trash.
+    Trash,
+    /// The item will be overwritten and then deleted.
+    Shred,
+    /// The item's permission bits will change.
+    SetPermissions,
+    /// The item's owner or group will change.
+    SetOwner,
+}
+
+impl std::fmt::Display for PlanAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::CreateFile => write!(f, "create"),
+            Self::OverwriteFile => write!(f, "overwrite"),
+            Self::CreateDirectory => write!(f, "mkdir"),
+            Self::CreateSymlink => write!(f, "symlink"),
+            Self::CreateHardlink => write!(f, "hardlink"),
+            Self::RemoveExisting => write!(f, "remove"),
+            Self::Rename => write!(f, "rename"),
+            Self::CrossDeviceMove => write!(f, "cross-device move"),
+            Self::Delete => write!(f, "delete"),
+            Self::Trash => write!(f, "trash"),
+            Self::Shred => write!(f, "shred"),
+            Self::SetPermissions => write!(f, "chmod"),
+            Self::SetOwner => write!(f, "chown"),
+        }
+    }
+}
+
+/// One step of a planned operation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlanStep {
+    /// What will happen.
+    pub action: PlanAction,
+    /// The item being read, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<PathBuf>,
+    /// The path that will be written or removed.
+    pub path: PathBuf,
+    /// Bytes involved in this step.
+    pub bytes: u64,
+}
+
+impl PlanStep {
+    fn new(action: PlanAction, source: Option<&Path>, path: PathBuf, bytes: u64) -> Self {
+        Self {
+            action,
+            source: source.map(Path::to_path_buf),
+            path,
+            bytes,
+        }
+    }
+}
+
+/// Free-space check against the destination filesystem.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpaceCheck {
+    /// The path whose filesystem was queried.
+    pub path: PathBuf,
+    /// Bytes that will be written to that filesystem.
+    pub required: u64,
+    /// Bytes available to unprivileged users.
+    pub available: u64,
+}
+
+impl SpaceCheck {
+    /// Whether the destination has room for the operation.
+    pub fn sufficient(&self) -> bool {
+        self.required <= self.available
+    }
+}
+
+/// The expanded, side-effect-free preview of a [`FileOperation`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OperationPlan {
+    /// The kind of operation planned.
+    pub operation_type: OperationType,
+    /// Concrete steps in execution order.
+    pub steps: Vec<PlanStep>,
+    /// Conflicts the operation will run into.
+    pub conflicts: Vec<Conflict>,
+    /// Total bytes read, written or removed.
+    pub total_bytes: u64,
+    /// Free-space check for operations that write data.
+    pub space: Option<SpaceCheck>,
+    /// Problems that will make individual items fail.
+    pub errors: Vec<OperationError>,
+}
+
+impl OperationPlan {
+    fn new(operation_type: OperationType) -> Self {
+        Self {
+            operation_type,
+            steps: Vec::new(),
+            conflicts: Vec::new(),
+            total_bytes: 0,
+            space: None,
+            errors: Vec::new(),
+        }
+    }
+
+    /// Number of steps with the given action.
+    pub fn count(&self, action: PlanAction) -> usize {
+        self.steps.iter().filter(|s| s.action == action).count()
+    }
+
+    /// Whether the destination filesystem is too small for the operation.
+    pub fn insufficient_space(&self) -> bool {
+        self.space.as_ref().is_some_and(|s| !s.sufficient())
+    }
+
+    /// Whether the plan has conflicts, errors or a space shortfall.
+    pub fn has_problems(&self) -> bool {
+        !self.conflicts.is_empty() || !self.errors.is_empty() || self.insufficient_space()
+    }
+
+    fn push(&mut self, step: PlanStep) {
+        self.steps.push(step);
+    }
+}
+
+/// Expand a file operation into a plan without performing it.
+///
+/// `resolution` is the conflict resolution the operation will run with; `None`
+/// means conflicting items are reported and skipped, as the engines do.
+pub fn plan_operation(
+    operation: &FileOperation,
+    resolution: Option<ConflictResolution>,
+) -> OperationPlan {
+    match operation {
+        FileOperation::Copy {
+            sources,
+            destination,
+        } => plan_transfer(OperationType::Copy, sources, destination, resolution),
+        FileOperation::Move {
+            sources,
+            destination,
+        } => plan_transfer(OperationType::Move, sources, destination, resolution),
+        FileOperation::Rename { source, new_name } => plan_rename(source, new_name),
+        FileOperation::BatchRename { renames } => plan_batch_rename(renames),
+        FileOperation::Delete { targets, use_trash } => {
+            let action = if *use_trash {
+                PlanAction::Trash
+            } else {
+                PlanAction::Delete
+            };
+            plan_delete(OperationType::Delete, targets, action)
+        }
+        FileOperation::Shred { targets, .. } => {
+            plan_delete(OperationType::Shred, targets, PlanAction::Shred)
+        }
+        FileOperation::Chmod { targets, spec } => plan_attributes(
+            OperationType::Chmod,
+            PlanAction::SetPermissions,
+            preview_chmod(targets, spec),
+        ),
+        FileOperation::Chown { targets, spec } => plan_attributes(
+            OperationType::Chown,
+            PlanAction::SetOwner,
+            preview_chown(targets, spec),
+        ),
+        FileOperation::Link {
+            sources,
+            destination,
+            kind,
+        } => plan_link(sources, destination, *kind, resolution),
+        FileOperation::CreateFile { path } => plan_create(OperationType::CreateFile, path),
+        FileOperation::CreateDirectory { path } => {
+            plan_create(OperationType::CreateDirectory, path)
+        }
+    }
+}
+
+fn plan_transfer(
+    operation_type: OperationType,
+    sources: &[PathBuf],
+    destination: &Path,
+    resolution: Option<ConflictResolution>,
+) -> OperationPlan {
+    let mut plan = OperationPlan::new(operation_type);
+    let is_move = operation_type == OperationType::Move;
+
+    match fs::metadata(destination) {
+        Ok(m) if m.is_dir() => {}
+        Ok(_) => {
+            plan.errors.push(OperationError::new(
+                destination.to_path_buf(),
+                "Destination is not a directory",
+            ));
+            return plan;
+        }
+        Err(e) => {
+            plan.errors.push(OperationError::new(
+                destination.to_path_buf(),
+                format!("Destination is not accessible: {}", e),
+            ));
+            return plan;
+        }
+    }
+
+    // Bytes that will actually land on the destination filesystem.
+    let mut written = 0u64;
+
+    for source in sources {
+        let Ok(meta) = fs::symlink_metadata(source) else {
+            plan.errors
+                .push(OperationError::new(source.clone(), "Source does not exist"));
+            continue;
+        };
+        let Some(file_name) = source.file_name() else {
+            plan.errors.push(OperationError::new(
+                source.clone(),
+                "Source path has no filename component",
+            ));
+            continue;
+        };
+        let mut dest_path = destination.join(file_name);
+
+        if meta.is_dir() && dest_path.starts_with(source) {
+            plan.conflicts
+                .push(Conflict::source_is_ancestor(source.clone(), dest_path));
+            continue;
+        }
+
+        // Conflicts mirror the engines: symlink_metadata, resolved per item.
+        let mut replace_existing = None;
+        if let Ok(existing) = fs::symlink_metadata(&dest_path) {
+            let kind = if &dest_path == source {
+                ConflictKind::SameFile
+            } else if existing.is_dir() {
+                ConflictKind::DirectoryExists
+            } else {
+                ConflictKind::FileExists
+            };
+            let Some(res) = resolution else {
+                plan.conflicts
+                    .push(Conflict::new(source.clone(), dest_path, kind));
+                continue;
+            };
+            match res.to_single() {
+                ConflictResolution::Skip => continue,
+                ConflictResolution::Abort => {
+                    plan.conflicts
+                        .push(Conflict::new(source.clone(), dest_path, kind));
+                    break;
+                }
+                ConflictResolution::AutoRename => dest_path = auto_rename_path(&dest_path),
+                _ => {
+                    if kind == ConflictKind::SameFile {
+                        plan.conflicts
+                            .push(Conflict::new(source.clone(), dest_path, kind));
+                        continue;
+                    }
+                    replace_existing = Some(existing);
+                }
+            }
+        }
+
+        // A plain file replacing a plain file is an overwrite; anything else
+        // removes the existing entry first.
+        let overwrite_in_place = replace_existing
+            .as_ref()
+            .is_some_and(|e| e.is_file() && meta.is_file() && !is_move);
+        if let Some(existing) = &replace_existing
+            && !overwrite_in_place
+        {
+            let bytes = if existing.is_dir() {
+                tree_size(&dest_path)
+            } else {
+                existing.len()
+            };
+            plan.push(PlanStep::new(
+                PlanAction::RemoveExisting,
+                None,
+                dest_path.clone(),
+                bytes,
+            ));
+        }
+
+        if is_move {
+            let bytes = if meta.is_dir() {
+                tree_size(source)
+            } else if meta.is_symlink() {
+                0
+            } else {
+                meta.len()
+            };
+            let action = if same_device(source, destination) {
+                PlanAction::Rename
+            } else {
+                written += bytes;
+                PlanAction::CrossDeviceMove
+            };
+            plan.total_bytes += bytes;
+            plan.push(PlanStep::new(action, Some(source), dest_path, bytes));
+        } else if overwrite_in_place {
+            written += meta.len();
+            plan.total_bytes += meta.len();
+            plan.push(PlanStep::new(
+                PlanAction::OverwriteFile,
+                Some(source),
+                dest_path,
+                meta.len(),
+            ));
+        } else {
+            let before = plan.total_bytes;
+            expand_copy(source, &meta, &dest_path, &mut plan);
+            written += plan.total_bytes - before;
+        }
+    }
+
+    if written > 0 {
+        plan.space = gravityfile_core::fs_usage(destination).map(|usage| SpaceCheck {
+            path: destination.to_path_buf(),
+            required: written,
+            available: usage.available,
+        });
+    }
+
+    plan
+}
+
+/// Expand a copy of `source` into per-entry steps, without following symlinks.
+fn expand_copy(source: &Path, meta: &fs::Metadata, dest: &Path, plan: &mut OperationPlan) {
+    if meta.is_symlink() {
+        plan.push(PlanStep::new(
+            PlanAction::CreateSymlink,
+            Some(source),
+            dest.to_path_buf(),
+            0,
+        ));
+    } else if meta.is_dir() {
+        plan.push(PlanStep::new(
+            PlanAction::CreateDirectory,
+            Some(source),
+            dest.to_path_buf(),
+            0,
+        ));
+        let entries = match fs::read_dir(source) {
+            Ok(entries) => entries,
+            Err(e) => {
+                plan.errors.push(OperationError::new(
+                    source.to_path_buf(),
+                    format!("Failed to read directory: {}", e),
+                ));
+                return;
+            }
+        };
+        let mut children: Vec<_> = entries.filter_map(Result::ok).collect();
+        children.sort_by_key(|e| e.file_name());
+        for entry in children {
+            let path = entry.path();
+            match fs::symlink_metadata(&path) {
+                Ok(child) => expand_copy(&path, &child, &dest.join(entry.file_name()), plan),
+                Err(e) => plan.errors.push(OperationError::new(
+                    path,
+                    format!("Failed to read metadata: {}", e),
+                )),
+            }
+        }
+    } else {
+        plan.total_bytes += meta.len();
+        plan.push(PlanStep::new(
+            PlanAction::CreateFile,
+            Some(source),
+            dest.to_path_buf(),
+            meta.len(),
+        ));
+    }
+}
+
+fn plan_rename(source: &Path, new_name: &str) -> OperationPlan {
+    let mut plan = OperationPlan::new(OperationType::Rename);
+    let Ok(meta) = fs::symlink_metadata(source) else {
+        plan.errors.push(OperationError::new(
+            source.to_path_buf(),
+            "Source does not exist",
+        ));
+        return plan;
+    };
+    if let Err(e) = validate_filename(new_name) {
+        plan.errors
+            .push(OperationError::new(source.to_path_buf(), e));
+        return plan;
+    }
+
+    let new_path = source.parent().unwrap_or(Path::new("")).join(new_name);
+    if new_path != source
+        && let Ok(existing) = fs::symlink_metadata(&new_path)
+    {
+        let kind = if existing.is_dir() {
+            ConflictKind::DirectoryExists
+        } else {
+            ConflictKind::FileExists
+        };
+        plan.conflicts
+            .push(Conflict::new(source.to_path_buf(), new_path, kind));
+        return plan;
+    }
+
+    let bytes = if meta.is_dir() {
+        tree_size(source)
+    } else {
+        meta.len()
+    };
+    plan.total_bytes = bytes;
+    plan.push(PlanStep::new(
+        PlanAction::Rename,
+        Some(source),
+        new_path,
+        bytes,
+    ));
+    plan
+}
+
+fn plan_batch_rename(renames: &[(PathBuf, String)]) -> OperationPlan {
+    let mut plan = OperationPlan::new(OperationType::Rename);
+    for preview in check_renames(renames) {
+        let target = preview.target();
+        match preview.status {
+            RenameStatus::Ready => {
+                plan.push(PlanStep::new(
+                    PlanAction::Rename,
+                    Some(&preview.source),
+                    target,
+                    0,
+                ));
+            }
+            RenameStatus::Unchanged => {}
+            RenameStatus::Invalid(e) => plan.errors.push(OperationError::new(preview.source, e)),
+            RenameStatus::Collision(_) => plan.conflicts.push(Conflict::new(
+                preview.source,
+                target,
+                ConflictKind::FileExists,
+            )),
+        }
+    }
+    plan
+}
+
+fn plan_delete(
+    operation_type: OperationType,
+    targets: &[PathBuf],
+    action: PlanAction,
+) -> OperationPlan {
+    let mut plan = OperationPlan::new(operation_type);
+    for target in targets {
+        match fs::symlink_metadata(target) {
+            Ok(meta) => {
+                let bytes = if meta.is_dir() {
+                    tree_size(target)
+                } else if meta.is_symlink() {
+                    0
+                } else {
+                    meta.len()
+                };
+                plan.total_bytes += bytes;
+                plan.push(PlanStep::new(action, None, target.clone(), bytes));
+            }
+            Err(_) => plan
+                .errors
+                .push(OperationError::new(target.clone(), "Target does not exist")),
+        }
+    }
+    plan
+}
+
+fn plan_attributes(
+    operation_type: OperationType,
+    action: PlanAction,
+    (paths, errors): (Vec<PathBuf>, Vec<OperationError>),
+) -> OperationPlan {
+    let mut plan = OperationPlan::new(operation_type);
+    for path in paths {
+        plan.push(PlanStep::new(action, None, path, 0));
+    }
+    plan.errors = errors;
+    plan
+}
+
+fn plan_link(
+    sources: &[PathBuf],
+    destination: &Path,
+    kind: LinkKind,
+    resolution: Option<ConflictResolution>,
+) -> OperationPlan {
+    let mut plan = OperationPlan::new(OperationType::Link);
+    if !destination.is_dir() {
+        plan.errors.push(OperationError::new(
+            destination.to_path_buf(),
+            "Destination is not a directory",
+        ));
+        return plan;
+    }
+    let action = if kind.is_symlink() {
+        PlanAction::CreateSymlink
+    } else {
+        PlanAction::CreateHardlink
+    };
+
+    for source in sources {
+        if let Err(e) = check_source(source, destination, kind) {
+            plan.errors.push(OperationError::new(source.clone(), e));
+            continue;
+        }
+        let path = match place(source, destination, resolution) {
+            Ok(Placement::New(path)) => path,
+            Ok(Placement::Replace(path)) => {
+                let bytes = fs::symlink_metadata(&path).map_or(0, |m| m.len());
+                plan.push(PlanStep::new(
+                    PlanAction::RemoveExisting,
+                    None,
+                    path.clone(),
+                    bytes,
+                ));
+                path
+            }
+            Ok(Placement::Skip) => continue,
+            Ok(Placement::Conflict(conflict)) => {
+                plan.conflicts.push(conflict);
+                continue;
+            }
+            Ok(Placement::Abort(conflict)) => {
+                plan.conflicts.push(conflict);
+                break;
+            }
+            Err(e) => {
+                plan.errors.push(OperationError::new(source.clone(), e));
+                continue;
+            }
+        };
+        plan.push(PlanStep::new(action, Some(source), path, 0));
+    }
+    plan
+}
+
+fn plan_create(operation_type: OperationType, path: &Path) -> OperationPlan {
+    let mut plan = OperationPlan::new(operation_type);
+    if let Ok(existing) = fs::symlink_metadata(path) {
+        let kind = if existing.is_dir() {
+            ConflictKind::DirectoryExists
+        } else {
+            ConflictKind::FileExists
+        };
+        plan.conflicts
+            .push(Conflict::new(path.to_path_buf(), path.to_path_buf(), kind));
+        return plan;
+    }
+    let action = if operation_type == OperationType::CreateDirectory {
+        PlanAction::CreateDirectory
+    } else {
+        PlanAction::CreateFile
+    };
+    plan.push(PlanStep::new(action, None, path.to_path_buf(), 0));
+    plan
+}
+
+/// Total size of regular files under `path`, not following symlinks.
+pub(crate) fn tree_size(path: &Path) -> u64 {
+    let Ok(meta) = fs::symlink_metadata(path) else {
+        return 0;
+    };
+    if meta.is_symlink() {
+        return 0;
+    }
+    if !meta.is_dir() {
+        return meta.len();
+    }
+    fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .filter_map(Result::ok)
+                .map(|e| tree_size(&e.path()))
+                .sum()
+        })
+        .unwrap_or(0)
+}
+
+/// Whether `source` and the directory `destination` share a filesystem, so a
+/// move is a plain `rename(2)`.
+#[cfg(unix)]
+pub(crate) fn same_device(source: &Path, destination: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    match (fs::symlink_metadata(source), fs::metadata(destination)) {
+        (Ok(s), Ok(d)) => s.dev() == d.dev(),
+        _ => true,
+    }
+}
+
+#[cfg(not(unix))]
+pub(crate) fn same_device(_source: &Path, _destination: &Path) -> bool {
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn setup() -> (TempDir, PathBuf, PathBuf) {
+        let tmp = TempDir::new().unwrap();
+        let src = tmp.path().join("src");
+        let dst = tmp.path().join("dst");
+        fs::create_dir_all(src.join("sub")).unwrap();
+        fs::create_dir(&dst).unwrap();
+        fs::write(src.join("a.txt"), b"hello").unwrap();
+        fs::write(src.join("sub/b.bin"), vec![0u8; 100]).unwrap();
+        (tmp, src, dst)
+    }
+
+    #[test]
+    fn test_copy_plan_expands_tree() {
+        let (_tmp, src, dst) = setup();
+        let plan = plan_operation(&FileOperation::copy(vec![src.clone()], dst.clone()), None);
+
+        assert_eq!(plan.count(PlanAction::CreateDirectory), 2);
+        assert_eq!(plan.count(PlanAction::CreateFile), 2);
+        assert_eq!(plan.total_bytes, 105);
+        assert!(plan.conflicts.is_empty());
+        assert!(
+            plan.steps
+                .iter()
+                .any(|s| s.path == dst.join("src/sub/b.bin"))
+        );
+        let space = plan.space.expect("space check");
+        assert_eq!(space.required, 105);
+        assert!(space.sufficient());
+    }
+
+    #[test]
+    fn test_copy_plan_predicts_conflicts() {
+        let (_tmp, src, dst) = setup();
+        fs::write(dst.join("a.txt"), b"old").unwrap();
+        let op = FileOperation::copy(vec![src.join("a.txt")], dst.clone());
+
+        let plan = plan_operation(&op, None);
+        assert_eq!(plan.conflicts.len(), 1);
+        assert_eq!(plan.conflicts[0].kind, ConflictKind::FileExists);
+        assert!(plan.steps.is_empty());
+
+        let plan = plan_operation(&op, Some(ConflictResolution::OverwriteAll));
+        assert!(plan.conflicts.is_empty());
+        assert_eq!(plan.count(PlanAction::OverwriteFile), 1);
+
+        let plan = plan_operation(&op, Some(ConflictResolution::AutoRename));
+        assert_eq!(plan.steps[0].path, dst.join("a (1).txt"));
+    }
+
+    #[test]
+    fn test_move_plan_same_device_is_rename() {
+        let (_tmp, src, dst) = setup();
+        let plan = plan_operation(
+            &FileOperation::move_to(vec![src.clone()], dst.clone()),
+            None,
+        );
+
+        assert_eq!(plan.steps.len(), 1);
+        assert_eq!(plan.steps[0].action, PlanAction::Rename);
+        assert_eq!(plan.steps[0].bytes, 105);
+        assert!(plan.space.is_none());
+    }
+
+    #[test]
+    fn test_move_into_itself_is_conflict() {
+        let (_tmp, src, _dst) = setup();
+        let plan = plan_operation(
+            &FileOperation::move_to(vec![src.clone()], src.join("sub")),
+            None,
+        );
+        assert_eq!(plan.conflicts[0].kind, ConflictKind::SourceIsAncestor);
+        assert!(plan.steps.is_empty());
+    }
+
+    #[test]
+    fn test_delete_and_missing_sources() {
+        let (_tmp, src, _dst) = setup();
+        let plan = plan_operation(
+            &FileOperation::delete(vec![src.clone(), src.join("missing")], true),
+            None,
+        );
+        assert_eq!(plan.count(PlanAction::Trash), 1);
+        assert_eq!(plan.total_bytes, 105);
+        assert_eq!(plan.errors.len(), 1);
+        assert!(plan.has_problems());
+    }
+
+    #[test]
+    fn test_plan_serializes() {
+        let (_tmp, src, dst) = setup();
+        let plan = plan_operation(&FileOperation::copy(vec![src], dst), None);
+        let json = serde_json::to_value(&plan).unwrap();
+        assert_eq!(json["operation_type"], "Copy");
+        assert_eq!(json["steps"][0]["action"], "create_directory");
+    }
+}
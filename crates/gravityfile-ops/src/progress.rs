@@ -15,6 +15,12 @@ pub enum OperationType {
     Rename,
     CreateFile,
     CreateDirectory,
+    Restore,
+    Sync,
+    Shred,
+    Chmod,
+    Chown,
+    Link,
 }
 
 impl std::fmt::Display for OperationType {
@@ -26,6 +32,12 @@ impl std::fmt::Display for OperationType {
             Self::Rename => write!(f, "Rename"),
             Self::CreateFile => write!(f, "Create file"),
             Self::CreateDirectory => write!(f, "Create directory"),
+            Self::Restore => write!(f, "Restore"),
+            Self::Sync => write!(f, "Sync"),
+            Self::Shred => write!(f, "Shred"),
+            Self::Chmod => write!(f, "Change permissions"),
+            Self::Chown => write!(f, "Change owner"),
+            Self::Link => write!(f, "Link"),
         }
     }
 }
@@ -131,6 +143,12 @@ impl OperationComplete {
             OperationType::Rename => "Renamed",
             OperationType::CreateFile => "Created",
             OperationType::CreateDirectory => "Created",
+            OperationType::Restore => "Restored",
+            OperationType::Sync => "Synced",
+            OperationType::Shred => "Shredded",
+            OperationType::Chmod => "Changed permissions of",
+            OperationType::Chown => "Changed owner of",
+            OperationType::Link => "Linked",
         };
 
         if self.failed == 0 {
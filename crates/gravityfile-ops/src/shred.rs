@@ -0,0 +1,578 @@
+//! Secure deletion: overwrite file contents, sync, scrub names, then unlink.
+//!
+//! Each regular file is overwritten in place with random data for the
+//! configured number of passes (optionally followed by a pass of zeros),
+//! flushed to disk after every pass, truncated, renamed to a random name of
+//! the same length and finally unlinked. Directories are shredded depth-first
+//! and removed the same way once empty. Symlinks and special files are only
+//! renamed and unlinked; their targets are never touched. Files with other
+//! hard links are only unlinked too, since overwriting them would destroy the
+//! data still reachable through those links.
+//!
+//! Overwriting only destroys data when the filesystem writes in place. On
+//! copy-on-write or log-structured filesystems the old blocks survive until
+//! they are reused; [`copy_on_write_filesystem`] detects the common ones so
+//! callers can warn before starting.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+
+use crate::jobs::PauseToken;
+use crate::plan::tree_size;
+use crate::progress::{OperationComplete, OperationProgress, OperationType};
+use crate::{OPERATION_CHANNEL_SIZE, OperationError};
+
+/// Number of random overwrite passes used when none is given.
+pub const DEFAULT_SHRED_PASSES: u32 = 3;
+
+/// Size of each overwrite write.
+const CHUNK_SIZE: usize = 1024 * 1024;
+
+/// Characters used for scrubbed names.
+const NAME_ALPHABET: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+
+/// Result sent through the channel during shred operations.
+#[derive(Debug)]
+pub enum ShredResult {
+    /// Progress update, sent after every overwrite pass.
+    Progress(OperationProgress),
+    /// The operation completed.
+    Complete(OperationComplete),
+}
+
+/// Options for shred operations.
+#[derive(Debug, Clone)]
+pub struct ShredOptions {
+    /// Number of random-data overwrite passes.
+    pub passes: u32,
+    /// Finish with a pass of zeros so the file does not look shredded.
+    pub zero_pass: bool,
+    /// Holds the shred between chunks while paused.
+    pub pause: Option<PauseToken>,
+}
+
+impl Default for ShredOptions {
+    fn default() -> Self {
+        Self {
+            passes: DEFAULT_SHRED_PASSES,
+            zero_pass: true,
+            pause: None,
+        }
+    }
+}
+
+impl ShredOptions {
+    /// Options with `passes` random passes and a final zero pass.
+    pub fn with_passes(passes: u32) -> Self {
+        Self {
+            passes,
+            ..Self::default()
+        }
+    }
+
+    /// Total number of times every byte is written.
+    pub fn total_passes(&self) -> u32 {
+        self.passes + u32::from(self.zero_pass)
+    }
+}
+
+/// Name of the copy-on-write or log-structured filesystem containing `path`,
+/// if it is one. Overwriting in place does not reach the original blocks on
+/// these filesystems.
+#[cfg(target_os = "linux")]
+pub fn copy_on_write_filesystem(path: &Path) -> Option<&'static str> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    const BTRFS_SUPER_MAGIC: i64 = 0x9123_683e;
+    const ZFS_SUPER_MAGIC: i64 = 0x2fc1_2fc1;
+    const BCACHEFS_SUPER_MAGIC: i64 = 0xca45_1a4e;
+    const NILFS_SUPER_MAGIC: i64 = 0x3434;
+    const F2FS_SUPER_MAGIC: i64 = 0xf2f5_2010;
+
+    let c_path = CString::new(path.as_os_str().as_bytes()).ok()?;
+    let mut stat: libc::statfs = unsafe { std::mem::zeroed() };
+    // SAFETY: c_path is a valid NUL-terminated string and stat is a valid out-pointer.
+    if unsafe { libc::statfs(c_path.as_ptr(), &mut stat) } != 0 {
+        return None;
+    }
+    #[allow(clippy::unnecessary_cast)]
+    match stat.f_type as i64 {
+        BTRFS_SUPER_MAGIC => Some("btrfs"),
+        ZFS_SUPER_MAGIC => Some("zfs"),
+        BCACHEFS_SUPER_MAGIC => Some("bcachefs"),
+        NILFS_SUPER_MAGIC => Some("nilfs2"),
+        F2FS_SUPER_MAGIC => Some("f2fs"),
+        _ => None,
+    }
+}
+
+/// Name of the copy-on-write filesystem containing `path`, if it is one.
+#[cfg(target_os = "macos")]
+pub fn copy_on_write_filesystem(path: &Path) -> Option<&'static str> {
+    use std::ffi::{CStr, CString};
+    use std::os::unix::ffi::OsStrExt;
+
+    let c_path = CString::new(path.as_os_str().as_bytes()).ok()?;
+    let mut stat: libc::statfs = unsafe { std::mem::zeroed() };
+    // SAFETY: c_path is a valid NUL-terminated string and stat is a valid out-pointer.
+    if unsafe { libc::statfs(c_path.as_ptr(), &mut stat) } != 0 {
+        return None;
+    }
+    // SAFETY: f_fstypename is a NUL-terminated buffer filled in by statfs.
+    let name = unsafe { CStr::from_ptr(stat.f_fstypename.as_ptr()) };
+    match name.to_bytes() {
+        b"apfs" => Some("apfs"),
+        b"zfs" => Some("zfs"),
+        _ => None,
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+pub fn copy_on_write_filesystem(_path: &Path) -> Option<&'static str> {
+    None
+}
+
+/// Warnings for targets whose overwrite will not be effective: one per
+/// distinct copy-on-write filesystem, and one for files with other hard links.
+pub fn shred_warnings(targets: &[PathBuf]) -> Vec<String> {
+    let mut seen: Vec<&'static str> = Vec::new();
+    let mut linked = 0;
+    for target in targets {
+        let probe = target.parent().unwrap_or(target);
+        if let Some(fs_name) = copy_on_write_filesystem(probe)
+            && !seen.contains(&fs_name)
+        {
+            seen.push(fs_name);
+        }
+        linked += hard_linked_files(target);
+    }
+    let mut warnings: Vec<String> = seen
+        .into_iter()
+        .map(|fs_name| {
+            format!(
+                "{} is copy-on-write: overwriting may leave the original data on disk",
+                fs_name
+            )
+        })
+        .collect();
+    if linked > 0 {
+        warnings.push(format!(
+            "{} file{} with other hard links will only be unlinked, not overwritten",
+            linked,
+            if linked == 1 { "" } else { "s" }
+        ));
+    }
+    warnings
+}
+
+/// Number of regular files under `path` that have other hard links.
+fn hard_linked_files(path: &Path) -> usize {
+    match fs::symlink_metadata(path) {
+        Ok(meta) if meta.is_dir() => fs::read_dir(path)
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .map(|e| hard_linked_files(&e.path()))
+                    .sum()
+            })
+            .unwrap_or(0),
+        Ok(meta) => usize::from(meta.is_file() && link_count(&meta) > 1),
+        Err(_) => 0,
+    }
+}
+
+/// Number of hard links to the file.
+#[cfg(unix)]
+fn link_count(meta: &fs::Metadata) -> u64 {
+    use std::os::unix::fs::MetadataExt;
+    meta.nlink()
+}
+
+#[cfg(not(unix))]
+fn link_count(_meta: &fs::Metadata) -> u64 {
+    1
+}
+
+/// Start an async shred operation.
+///
+/// Returns a receiver for progress updates and the final result.
+pub fn start_shred(
+    targets: Vec<PathBuf>,
+    options: ShredOptions,
+    token: CancellationToken,
+) -> mpsc::Receiver<ShredResult> {
+    let (tx, rx) = mpsc::channel(OPERATION_CHANNEL_SIZE);
+
+    tokio::spawn(async move {
+        shred_impl(targets, options, token, tx).await;
+    });
+
+    rx
+}
+
+async fn shred_impl(
+    targets: Vec<PathBuf>,
+    options: ShredOptions,
+    token: CancellationToken,
+    tx: mpsc::Sender<ShredResult>,
+) {
+    let bytes_total: u64 =
+        targets.iter().map(|t| tree_size(t)).sum::<u64>() * u64::from(options.total_passes());
+    let mut progress = OperationProgress::new(OperationType::Shred, targets.len(), bytes_total);
+    let pause = options.pause.clone().unwrap_or_default();
+    let mut succeeded = 0;
+    let mut failed = 0;
+
+    for target in targets {
+        pause.wait(&token).await;
+        if token.is_cancelled() {
+            break;
+        }
+        progress.set_current_file(Some(target.clone()));
+        let _ = tx.send(ShredResult::Progress(progress.clone())).await;
+
+        let mut shredder = Shredder {
+            options: options.clone(),
+            pause: pause.clone(),
+            token: token.clone(),
+            tx: tx.clone(),
+            progress: progress.clone(),
+        };
+        let path = target.clone();
+        let joined = tokio::task::spawn_blocking(move || {
+            let result = shredder.shred_path(&path);
+            (shredder.progress, result)
+        })
+        .await;
+
+        match joined {
+            Ok((p, result)) => {
+                progress = p;
+                match result {
+                    Ok(()) => {
+                        progress.files_completed += 1;
+                        succeeded += 1;
+                    }
+                    Err(e) => {
+                        progress.add_error(OperationError::new(target, e));
+                        failed += 1;
+                    }
+                }
+            }
+            Err(e) => {
+                progress.add_error(OperationError::new(target, format!("Task failed: {}", e)));
+                failed += 1;
+            }
+        }
+        let _ = tx.send(ShredResult::Progress(progress.clone())).await;
+    }
+
+    let _ = tx
+        .send(ShredResult::Complete(OperationComplete {
+            operation_type: OperationType::Shred,
+            succeeded,
+            failed,
+            bytes_processed: progress.bytes_processed,
+            errors: progress.errors,
+        }))
+        .await;
+}
+
+/// Blocking worker that shreds one target tree.
+struct Shredder {
+    options: ShredOptions,
+    pause: PauseToken,
+    token: CancellationToken,
+    tx: mpsc::Sender<ShredResult>,
+    progress: OperationProgress,
+}
+
+impl Shredder {
+    fn shred_path(&mut self, path: &Path) -> Result<(), String> {
+        let meta = fs::symlink_metadata(path).map_err(|e| format!("Cannot access path: {}", e))?;
+
+        if meta.is_dir() {
+            let entries = fs::read_dir(path)
+                .map_err(|e| format!("Failed to read directory {}: {}", path.display(), e))?;
+            for entry in entries {
+                let entry = entry.map_err(|e| format!("Failed to read directory: {}", e))?;
+                self.shred_path(&entry.path())?;
+            }
+            let scrubbed = scrub_name(path)?;
+            fs::remove_dir(&scrubbed)
+                .map_err(|e| format!("Failed to remove directory {}: {}", path.display(), e))
+        } else {
+            // IMPORTANT: only regular files are overwritten. Symlinks would
+            // redirect the writes to their target and device nodes must never
+            // be written to; both are just unlinked. So are files with other
+            // hard links, whose data lives on through those links.
+            if meta.is_file() && link_count(&meta) > 1 {
+                tracing::warn!(
+                    "{} has other hard links; unlinking without overwriting",
+                    path.display()
+                );
+            } else if meta.is_file() {
+                self.overwrite(path, meta.len())?;
+            }
+            let scrubbed = scrub_name(path)?;
+            fs::remove_file(&scrubbed)
+                .map_err(|e| format!("Failed to remove {}: {}", path.display(), e))
+        }
+    }
+
+    /// Overwrite the file in place for every pass, syncing after each one,
+    /// then truncate it.
+    fn overwrite(&mut self, path: &Path, len: u64) -> Result<(), String> {
+        let mut file = open_for_overwrite(path)?;
+        let mut buf = vec![0u8; CHUNK_SIZE];
+        let total_passes = self.options.total_passes();
+
+        for pass in 0..total_passes {
+            let random = pass < self.options.passes;
+            let mut stream = if random {
+                Some(blake3::Hasher::new_keyed(&random_key()?).finalize_xof())
+            } else {
+                None
+            };
+            if !random {
+                buf.fill(0);
+            }
+
+            file.seek(SeekFrom::Start(0))
+                .map_err(|e| format!("Failed to seek {}: {}", path.display(), e))?;
+            let mut remaining = len;
+            while remaining > 0 {
+                self.pause.wait_blocking(&self.token);
+                if self.token.is_cancelled() {
+                    return Err("Cancelled while overwriting".to_string());
+                }
+                let n = remaining.min(CHUNK_SIZE as u64) as usize;
+                if let Some(stream) = stream.as_mut() {
+                    stream.fill(&mut buf[..n]);
+                }
+                file.write_all(&buf[..n])
+                    .map_err(|e| format!("Failed to overwrite {}: {}", path.display(), e))?;
+                remaining -= n as u64;
+            }
+            file.sync_all()
+                .map_err(|e| format!("Failed to sync {}: {}", path.display(), e))?;
+
+            self.progress.bytes_processed += len;
+            let _ = self
+                .tx
+                .blocking_send(ShredResult::Progress(self.progress.clone()));
+        }
+
+        file.set_len(0)
+            .and_then(|()| file.sync_all())
+            .map_err(|e| format!("Failed to truncate {}: {}", path.display(), e))
+    }
+}
+
+/// Open a regular file for in-place writing, making it owner-writable first if
+/// needed (as `shred --force` does).
+fn open_for_overwrite(path: &Path) -> Result<File, String> {
+    let open = || OpenOptions::new().write(true).open(path);
+    match open() {
+        Ok(file) => Ok(file),
+        Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied => {
+            let mut perms = fs::metadata(path)
+                .map_err(|e| format!("Cannot access path: {}", e))?
+                .permissions();
+            #[allow(clippy::permissions_set_readonly_false)]
+            perms.set_readonly(false);
+            fs::set_permissions(path, perms)
+                .and_then(|()| open())
+                .map_err(|_| format!("Cannot open {} for writing: {}", path.display(), e))
+        }
+        Err(e) => Err(format!("Cannot open {} for writing: {}", path.display(), e)),
+    }
+}
+
+/// Rename `path` to a random name of the same length in the same directory so
+/// the original name does not linger in the directory entry, and return the
+/// new path.
+fn scrub_name(path: &Path) -> Result<PathBuf, String> {
+    let (Some(parent), Some(name)) = (path.parent(), path.file_name()) else {
+        return Err(format!("Cannot shred {}", path.display()));
+    };
+    let len = name.len().max(1);
+
+    // Retry a few times in the unlikely case the random name is taken.
+    for _ in 0..8 {
+        let mut bytes = vec![0u8; len];
+        blake3::Hasher::new_keyed(&random_key()?)
+            .finalize_xof()
+            .fill(&mut bytes);
+        let scrubbed: String = bytes
+            .into_iter()
+            .map(|b| NAME_ALPHABET[b as usize % NAME_ALPHABET.len()] as char)
+            .collect();
+        let candidate = parent.join(scrubbed);
+        if fs::symlink_metadata(&candidate).is_ok() {
+            continue;
+        }
+        fs::rename(path, &candidate)
+            .map_err(|e| format!("Failed to scrub name of {}: {}", path.display(), e))?;
+        sync_dir(parent);
+        return Ok(candidate);
+    }
+    Err(format!(
+        "Failed to find a free name next to {}",
+        path.display()
+    ))
+}
+
+/// Flush a directory so a rename is persisted before the unlink.
+fn sync_dir(dir: &Path) {
+    #[cfg(unix)]
+    if let Ok(handle) = File::open(dir) {
+        let _ = handle.sync_all();
+    }
+    #[cfg(not(unix))]
+    let _ = dir;
+}
+
+/// A fresh 256-bit key from the operating system's CSPRNG, used to seed the
+/// BLAKE3 output stream of one overwrite pass or scrubbed name.
+fn random_key() -> Result<[u8; 32], String> {
+    let mut key = [0u8; 32];
+    getrandom::fill(&mut key).map_err(|e| format!("Failed to get random data: {}", e))?;
+    Ok(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    async fn run(targets: Vec<PathBuf>, options: ShredOptions) -> OperationComplete {
+        let mut rx = start_shred(targets, options, CancellationToken::new());
+        while let Some(result) = rx.recv().await {
+            if let ShredResult::Complete(c) = result {
+                return c;
+            }
+        }
+        panic!("no completion");
+    }
+
+    #[tokio::test]
+    async fn test_shred_file_and_tree() {
+        let temp = TempDir::new().unwrap();
+        let file = temp.path().join("secret.txt");
+        fs::write(&file, b"customer data").unwrap();
+        let dir = temp.path().join("export");
+        fs::create_dir_all(dir.join("nested")).unwrap();
+        fs::write(dir.join("nested/a.csv"), vec![7u8; 3 * CHUNK_SIZE / 2]).unwrap();
+
+        let complete = run(
+            vec![file.clone(), dir.clone()],
+            ShredOptions::with_passes(2),
+        )
+        .await;
+
+        assert_eq!(complete.succeeded, 2);
+        assert_eq!(complete.failed, 0);
+        assert_eq!(
+            complete.bytes_processed,
+            (13 + 3 * CHUNK_SIZE as u64 / 2) * 3
+        );
+        assert!(!file.exists());
+        assert!(!dir.exists());
+        // Nothing, not even a scrubbed name, is left behind.
+        assert_eq!(fs::read_dir(temp.path()).unwrap().count(), 0);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_shred_symlink_leaves_target_intact() {
+        let temp = TempDir::new().unwrap();
+        let target = temp.path().join("keep.txt");
+        fs::write(&target, b"keep me").unwrap();
+        let link = temp.path().join("link");
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+
+        let complete = run(vec![link.clone()], ShredOptions::default()).await;
+
+        assert_eq!(complete.succeeded, 1);
+        assert!(link.symlink_metadata().is_err());
+        assert_eq!(fs::read(&target).unwrap(), b"keep me");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_hard_linked_file_is_only_unlinked() {
+        let temp = TempDir::new().unwrap();
+        let file = temp.path().join("shared.txt");
+        fs::write(&file, b"still needed").unwrap();
+        let other = temp.path().join("other.txt");
+        fs::hard_link(&file, &other).unwrap();
+
+        let warnings = shred_warnings(std::slice::from_ref(&file));
+        assert!(warnings.iter().any(|w| w.contains("hard links")));
+
+        let complete = run(vec![file.clone()], ShredOptions::default()).await;
+        assert_eq!(complete.succeeded, 1);
+        assert!(!file.exists());
+        assert_eq!(fs::read(&other).unwrap(), b"still needed");
+        assert!(shred_warnings(&[other]).is_empty());
+    }
+
+    #[test]
+    fn test_overwrite_syncs_and_truncates() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("data.bin");
+        fs::write(&path, vec![0xAAu8; 4096]).unwrap();
+
+        let (tx, _rx) = mpsc::channel(OPERATION_CHANNEL_SIZE);
+        let mut shredder = Shredder {
+            options: ShredOptions {
+                passes: 1,
+                zero_pass: false,
+                pause: None,
+            },
+            pause: PauseToken::new(),
+            token: CancellationToken::new(),
+            tx,
+            progress: OperationProgress::new(OperationType::Shred, 1, 4096),
+        };
+        shredder.overwrite(&path, 4096).unwrap();
+        assert_eq!(fs::metadata(&path).unwrap().len(), 0);
+        assert_eq!(shredder.progress.bytes_processed, 4096);
+    }
+
+    #[test]
+    fn test_scrub_name_keeps_length() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("passwords.kdbx");
+        fs::write(&path, b"x").unwrap();
+
+        let scrubbed = scrub_name(&path).unwrap();
+        assert!(!path.exists());
+        assert!(scrubbed.exists());
+        assert_eq!(
+            scrubbed.file_name().unwrap().len(),
+            path.file_name().unwrap().len()
+        );
+        assert_ne!(scrubbed.file_name(), path.file_name());
+    }
+
+    #[test]
+    fn test_total_passes() {
+        assert_eq!(
+            ShredOptions::default().total_passes(),
+            DEFAULT_SHRED_PASSES + 1
+        );
+        let options = ShredOptions {
+            passes: 2,
+            zero_pass: false,
+            pause: None,
+        };
+        assert_eq!(options.total_passes(), 2);
+    }
+}
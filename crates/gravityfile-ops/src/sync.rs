@@ -0,0 +1,880 @@
+//! Directory synchronisation between two trees.
+//!
+//! [`diff_trees`] compares a source and a destination directory by size and
+//! modification time (and optionally by BLAKE3 hash) and reports what is new,
+//! changed, or only present on the destination side. [`start_sync`] applies
+//! such a diff, either as a one-way mirror or as a two-way update where the
+//! newer side of each changed file wins.
+//!
+//! Files are copied through the shared transfer engine, so replaced files are
+//! swapped in atomically and a cancelled sync leaves no partial files behind.
+//! Entries that change type are copied into a hidden sibling first and only
+//! then swapped with the old entry.
+
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+
+use crate::conflict::{Conflict, ConflictKind, ConflictResolution, auto_rename_path};
+use crate::copy::CopyOptions;
+use crate::plan::tree_size;
+use crate::progress::{OperationComplete, OperationProgress, OperationType};
+use crate::transfer;
+use crate::{OPERATION_CHANNEL_SIZE, OperationError};
+
+/// Modification times closer than this are considered equal. FAT and some
+/// network filesystems only store times with two-second granularity.
+const MTIME_TOLERANCE: Duration = Duration::from_secs(2);
+
+/// Suffix of the sibling a replacement entry is copied into before its swap.
+const STAGED_SUFFIX: &str = "gravityfile-sync";
+
+/// Suffix the replaced entry is renamed to until the swap has succeeded.
+const REPLACED_SUFFIX: &str = "gravityfile-old";
+
+/// How a sync brings the two trees in line.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncMode {
+    /// Make the destination an exact copy of the source.
+    #[default]
+    Mirror,
+    /// Copy missing entries both ways and let the newer side of each changed
+    /// file win. Nothing is deleted, since there is no record of what was
+    /// removed on either side, and an entry that is a file on one side and a
+    /// directory on the other is reported as a conflict rather than replaced.
+    TwoWay,
+}
+
+impl std::fmt::Display for SyncMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Mirror => write!(f, "mirror"),
+            Self::TwoWay => write!(f, "two-way"),
+        }
+    }
+}
+
+/// Options for comparing and synchronising two trees.
+#[derive(Debug, Clone)]
+pub struct SyncOptions {
+    /// Mirror or two-way update.
+    pub mode: SyncMode,
+    /// Compare file contents with BLAKE3 instead of trusting modification
+    /// times when sizes match.
+    pub compare_hash: bool,
+    /// In mirror mode, remove destination entries that are not in the source.
+    pub delete_extras: bool,
+    /// Move removed extras to the trash instead of deleting them.
+    pub use_trash: bool,
+    /// How files are copied. Timestamps are always preserved, otherwise the
+    /// next comparison would see every copied file as changed. The conflict
+    /// resolution decides ambiguous two-way changes.
+    pub copy: CopyOptions,
+}
+
+impl Default for SyncOptions {
+    fn default() -> Self {
+        Self {
+            mode: SyncMode::Mirror,
+            compare_hash: false,
+            delete_extras: false,
+            use_trash: true,
+            copy: CopyOptions::archive(),
+        }
+    }
+}
+
+/// How an entry differs between the two trees.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncChange {
+    /// Present on one side only and will be copied to the other.
+    New,
+    /// Present on both sides with different contents or type.
+    Changed,
+    /// Present only in a mirror's destination.
+    Extra,
+}
+
+impl std::fmt::Display for SyncChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::New => write!(f, "new"),
+            Self::Changed => write!(f, "changed"),
+            Self::Extra => write!(f, "extra"),
+        }
+    }
+}
+
+/// Which side of the sync an entry is written to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncDirection {
+    /// Copy from the source into the destination (or, for extras, remove
+    /// from the destination).
+    ToDestination,
+    /// Copy from the destination back into the source (two-way only).
+    ToSource,
+}
+
+/// A single difference between the two trees.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncEntry {
+    /// Path relative to both roots.
+    pub path: PathBuf,
+    /// What kind of difference this is.
+    pub change: SyncChange,
+    /// Which side gets written.
+    pub direction: SyncDirection,
+    /// Whether the entry being copied (or removed) is a directory.
+    pub is_dir: bool,
+    /// Bytes that will be copied (or removed, for extras).
+    pub bytes: u64,
+    /// Two-way change where neither side is clearly newer.
+    pub conflict: bool,
+}
+
+/// Result of comparing two trees.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncDiff {
+    /// Root the sync copies from.
+    pub source: PathBuf,
+    /// Root the sync copies to.
+    pub destination: PathBuf,
+    /// Mode the comparison was made for.
+    pub mode: SyncMode,
+    /// Differences in tree order.
+    pub entries: Vec<SyncEntry>,
+    /// Number of files that are already in sync.
+    pub unchanged: usize,
+    /// Entries that could not be compared.
+    pub errors: Vec<OperationError>,
+}
+
+impl SyncDiff {
+    /// Number of entries with the given change.
+    pub fn count(&self, change: SyncChange) -> usize {
+        self.entries.iter().filter(|e| e.change == change).count()
+    }
+
+    /// Number of two-way changes that need a conflict resolution.
+    pub fn conflicts(&self) -> usize {
+        self.entries.iter().filter(|e| e.conflict).count()
+    }
+
+    /// Entries a sync with `options` would act on. Extras are only removed
+    /// by a mirror with `delete_extras` set.
+    pub fn pending<'a>(&'a self, options: &SyncOptions) -> impl Iterator<Item = &'a SyncEntry> {
+        let delete = options.delete_extras && self.mode == SyncMode::Mirror;
+        self.entries
+            .iter()
+            .filter(move |e| e.change != SyncChange::Extra || delete)
+    }
+
+    /// Bytes a sync with `options` would copy.
+    pub fn bytes_to_copy(&self, options: &SyncOptions) -> u64 {
+        self.pending(options)
+            .filter(|e| e.change != SyncChange::Extra)
+            .map(|e| e.bytes)
+            .sum()
+    }
+
+    /// Whether the trees are already in sync.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// Compare `source` against `destination`.
+///
+/// Symlinks are compared by target and never followed. A missing destination
+/// is treated as empty. Roots nested inside each other are refused, since the
+/// sync would copy (or delete) its own output. This walks both trees (and
+/// hashes files when asked), so call it from a blocking context.
+pub fn diff_trees(source: &Path, destination: &Path, options: &SyncOptions) -> SyncDiff {
+    let mut diff = SyncDiff {
+        source: source.to_path_buf(),
+        destination: destination.to_path_buf(),
+        mode: options.mode,
+        entries: Vec::new(),
+        unchanged: 0,
+        errors: Vec::new(),
+    };
+
+    match fs::metadata(source) {
+        Ok(m) if m.is_dir() => {
+            if let Some(error) = nested_roots(source, destination) {
+                diff.errors.push(error);
+            } else {
+                diff_dirs(Path::new(""), options, &mut diff);
+            }
+        }
+        Ok(_) => diff.errors.push(OperationError::new(
+            source.to_path_buf(),
+            "Sync source is not a directory",
+        )),
+        Err(e) => diff.errors.push(OperationError::new(
+            source.to_path_buf(),
+            format!("Cannot read sync source: {}", e),
+        )),
+    }
+    diff
+}
+
+/// An error if either root lies inside the other.
+fn nested_roots(source: &Path, destination: &Path) -> Option<OperationError> {
+    let source = resolve(source)?;
+    let destination = resolve(destination)?;
+    if destination.starts_with(&source) {
+        Some(OperationError::new(
+            destination,
+            "Sync destination is inside the source",
+        ))
+    } else if source.starts_with(&destination) {
+        Some(OperationError::new(
+            source,
+            "Sync source is inside the destination",
+        ))
+    } else {
+        None
+    }
+}
+
+/// Canonical form of `path`, resolving its nearest existing ancestor when the
+/// path itself does not exist yet.
+fn resolve(path: &Path) -> Option<PathBuf> {
+    let mut existing = path;
+    let mut rest = Vec::new();
+    loop {
+        if let Ok(canonical) = existing.canonicalize() {
+            return Some(rest.iter().rev().fold(canonical, |p, name| p.join(name)));
+        }
+        rest.push(existing.file_name()?);
+        existing = existing.parent()?;
+        if existing.as_os_str().is_empty() {
+            existing = Path::new(".");
+        }
+    }
+}
+
+/// Entries of `dir` keyed (and so sorted) by name. A missing directory is empty.
+fn read_entries(dir: &Path) -> Result<BTreeMap<OsString, fs::Metadata>, String> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(BTreeMap::new()),
+        Err(e) => return Err(format!("Failed to read directory: {}", e)),
+    };
+    let mut map = BTreeMap::new();
+    for entry in entries.flatten() {
+        if let Ok(meta) = fs::symlink_metadata(entry.path()) {
+            map.insert(entry.file_name(), meta);
+        }
+    }
+    Ok(map)
+}
+
+fn diff_dirs(relative: &Path, options: &SyncOptions, diff: &mut SyncDiff) {
+    let source_dir = diff.source.join(relative);
+    let dest_dir = diff.destination.join(relative);
+
+    let (source_entries, dest_entries) = match (read_entries(&source_dir), read_entries(&dest_dir))
+    {
+        (Ok(s), Ok(d)) => (s, d),
+        (Err(e), _) => {
+            diff.errors.push(OperationError::new(source_dir, e));
+            return;
+        }
+        (_, Err(e)) => {
+            diff.errors.push(OperationError::new(dest_dir, e));
+            return;
+        }
+    };
+
+    for (name, src) in &source_entries {
+        let path = relative.join(name);
+        let Some(dst) = dest_entries.get(name) else {
+            diff.entries.push(new_entry(
+                path.clone(),
+                SyncDirection::ToDestination,
+                src,
+                &diff.source.join(&path),
+            ));
+            continue;
+        };
+
+        if src.is_dir() && dst.is_dir() {
+            diff_dirs(&path, options, diff);
+            continue;
+        }
+
+        let source_path = diff.source.join(&path);
+        let dest_path = diff.destination.join(&path);
+        let differs = if src.file_type() != dst.file_type() {
+            true
+        } else if src.is_symlink() {
+            fs::read_link(&source_path).ok() != fs::read_link(&dest_path).ok()
+        } else {
+            match files_differ(&source_path, src, &dest_path, dst, options.compare_hash) {
+                Ok(differs) => differs,
+                Err(e) => {
+                    diff.errors.push(OperationError::new(source_path, e));
+                    continue;
+                }
+            }
+        };
+
+        if !differs {
+            diff.unchanged += 1;
+            continue;
+        }
+
+        let (direction, conflict) = match options.mode {
+            SyncMode::Mirror => (SyncDirection::ToDestination, false),
+            // Replacing a directory with a file (or the reverse) would delete
+            // one side, which two-way mode never does.
+            SyncMode::TwoWay if src.file_type() != dst.file_type() => (
+                newer_side(src, dst).unwrap_or(SyncDirection::ToDestination),
+                true,
+            ),
+            SyncMode::TwoWay => match newer_side(src, dst) {
+                Some(direction) => (direction, false),
+                None => (SyncDirection::ToDestination, true),
+            },
+        };
+        let (from_meta, from_path) = match direction {
+            SyncDirection::ToDestination => (src, &source_path),
+            SyncDirection::ToSource => (dst, &dest_path),
+        };
+        diff.entries.push(SyncEntry {
+            path,
+            change: SyncChange::Changed,
+            direction,
+            is_dir: from_meta.is_dir(),
+            bytes: tree_size(from_path),
+            conflict,
+        });
+    }
+
+    for (name, dst) in &dest_entries {
+        if source_entries.contains_key(name) {
+            continue;
+        }
+        let path = relative.join(name);
+        let dest_path = diff.destination.join(&path);
+        let mut entry = new_entry(path, SyncDirection::ToSource, dst, &dest_path);
+        if options.mode == SyncMode::Mirror {
+            entry.change = SyncChange::Extra;
+            entry.direction = SyncDirection::ToDestination;
+        }
+        diff.entries.push(entry);
+    }
+}
+
+fn new_entry(
+    path: PathBuf,
+    direction: SyncDirection,
+    meta: &fs::Metadata,
+    full: &Path,
+) -> SyncEntry {
+    SyncEntry {
+        path,
+        change: SyncChange::New,
+        direction,
+        is_dir: meta.is_dir(),
+        bytes: tree_size(full),
+        conflict: false,
+    }
+}
+
+/// Whether two regular files differ by size, then by hash or modification time.
+fn files_differ(
+    source: &Path,
+    source_meta: &fs::Metadata,
+    dest: &Path,
+    dest_meta: &fs::Metadata,
+    compare_hash: bool,
+) -> Result<bool, String> {
+    if source_meta.len() != dest_meta.len() {
+        return Ok(true);
+    }
+    if compare_hash {
+        let hash = |path: &Path| {
+            transfer::file_hash(path)
+                .map_err(|e| format!("Failed to hash {}: {}", path.display(), e))
+        };
+        return Ok(hash(source)? != hash(dest)?);
+    }
+    Ok(!mtimes_match(
+        source_meta.modified().ok(),
+        dest_meta.modified().ok(),
+    ))
+}
+
+fn mtimes_match(a: Option<SystemTime>, b: Option<SystemTime>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => {
+            let delta = a.duration_since(b).or_else(|_| b.duration_since(a));
+            delta.is_ok_and(|d| d < MTIME_TOLERANCE)
+        }
+        _ => false,
+    }
+}
+
+/// The side whose entry was modified more recently, or `None` when the
+/// modification times are too close (or unknown) to tell.
+fn newer_side(source: &fs::Metadata, dest: &fs::Metadata) -> Option<SyncDirection> {
+    let (a, b) = (source.modified().ok()?, dest.modified().ok()?);
+    if mtimes_match(Some(a), Some(b)) {
+        None
+    } else if a > b {
+        Some(SyncDirection::ToDestination)
+    } else {
+        Some(SyncDirection::ToSource)
+    }
+}
+
+/// Result sent through the channel during a sync.
+#[derive(Debug)]
+pub enum SyncResult {
+    /// Progress update.
+    Progress(OperationProgress),
+    /// A conflict was detected that needs resolution.
+    Conflict(Conflict),
+    /// The operation completed.
+    Complete(OperationComplete),
+}
+
+/// Start applying a diff produced by [`diff_trees`].
+///
+/// Returns a receiver for progress updates and results.
+pub fn start_sync(
+    diff: SyncDiff,
+    options: SyncOptions,
+    token: CancellationToken,
+) -> mpsc::Receiver<SyncResult> {
+    let (tx, rx) = mpsc::channel(OPERATION_CHANNEL_SIZE);
+
+    tokio::spawn(async move {
+        sync_impl(diff, options, token, tx).await;
+    });
+
+    rx
+}
+
+async fn sync_impl(
+    diff: SyncDiff,
+    options: SyncOptions,
+    token: CancellationToken,
+    tx: mpsc::Sender<SyncResult>,
+) {
+    let entries: Vec<SyncEntry> = diff.pending(&options).cloned().collect();
+    let mut progress = OperationProgress::new(
+        OperationType::Sync,
+        entries.len(),
+        diff.bytes_to_copy(&options),
+    );
+    let mut copy = options.copy.clone();
+    copy.preserve_timestamps = true;
+    let global_resolution = options.copy.conflict_resolution;
+    let mut succeeded = 0;
+    let mut failed = 0;
+
+    if let Err(e) = fs::create_dir_all(&diff.destination) {
+        progress.add_error(OperationError::new(
+            diff.destination.clone(),
+            format!("Failed to create destination: {}", e),
+        ));
+        failed = entries.len();
+    } else {
+        for entry in entries {
+            if let Some(pause) = &copy.pause {
+                pause.wait(&token).await;
+            }
+            if token.is_cancelled() {
+                break;
+            }
+
+            let (from, mut to) = match entry.direction {
+                SyncDirection::ToDestination => (
+                    diff.source.join(&entry.path),
+                    diff.destination.join(&entry.path),
+                ),
+                SyncDirection::ToSource => (
+                    diff.destination.join(&entry.path),
+                    diff.source.join(&entry.path),
+                ),
+            };
+            progress.set_current_file(Some(entry.path.clone()));
+            let _ = tx.send(SyncResult::Progress(progress.clone())).await;
+
+            if entry.change == SyncChange::Extra {
+                match remove_extra(to.clone(), options.use_trash).await {
+                    Ok(()) => {
+                        succeeded += 1;
+                        progress.complete_file(0);
+                    }
+                    Err(e) => {
+                        progress.add_error(OperationError::new(to, e));
+                        failed += 1;
+                    }
+                }
+                continue;
+            }
+
+            // A new entry that appeared since the diff was made is a conflict
+            // just like an ambiguous two-way change.
+            let existing = fs::symlink_metadata(&to).ok();
+            let appeared = entry.change == SyncChange::New && existing.is_some();
+            let type_changed = existing.is_some_and(|to_meta| {
+                fs::symlink_metadata(&from).is_ok_and(|m| m.file_type() != to_meta.file_type())
+            });
+            if diff.mode == SyncMode::TwoWay && type_changed {
+                let _ = tx
+                    .send(SyncResult::Conflict(Conflict::new(
+                        from.clone(),
+                        to.clone(),
+                        if to.is_dir() {
+                            ConflictKind::DirectoryExists
+                        } else {
+                            ConflictKind::FileExists
+                        },
+                    )))
+                    .await;
+                progress.add_error(OperationError::new(
+                    entry.path.clone(),
+                    "File on one side and directory on the other; resolve it by hand",
+                ));
+                failed += 1;
+                continue;
+            }
+            if entry.conflict || appeared {
+                let kind = if to.is_dir() {
+                    ConflictKind::DirectoryExists
+                } else {
+                    ConflictKind::FileExists
+                };
+                let resolution = if let Some(res) = global_resolution {
+                    res.to_single()
+                } else {
+                    let _ = tx
+                        .send(SyncResult::Conflict(Conflict::new(
+                            from.clone(),
+                            to.clone(),
+                            kind,
+                        )))
+                        .await;
+                    ConflictResolution::Skip
+                };
+                match resolution {
+                    ConflictResolution::Skip | ConflictResolution::SkipAll => {
+                        failed += 1;
+                        continue;
+                    }
+                    ConflictResolution::Abort => {
+                        failed += 1;
+                        break;
+                    }
+                    ConflictResolution::AutoRename => to = auto_rename_path(&to),
+                    ConflictResolution::Overwrite | ConflictResolution::OverwriteAll => {}
+                }
+            }
+
+            match sync_entry(from.clone(), to, copy.clone(), token.clone()).await {
+                Ok((bytes, mismatches)) => {
+                    progress.complete_file(bytes);
+                    if mismatches.is_empty() {
+                        succeeded += 1;
+                    } else {
+                        for error in mismatches {
+                            progress.add_error(error);
+                        }
+                        failed += 1;
+                    }
+                }
+                Err(e) => {
+                    progress.add_error(OperationError::new(from, e));
+                    failed += 1;
+                }
+            }
+            let _ = tx.send(SyncResult::Progress(progress.clone())).await;
+        }
+    }
+
+    let _ = tx
+        .send(SyncResult::Complete(OperationComplete {
+            operation_type: OperationType::Sync,
+            succeeded,
+            failed,
+            bytes_processed: progress.bytes_processed,
+            errors: progress.errors,
+        }))
+        .await;
+}
+
+/// Copy `from` over `to`, replacing whatever is there, then verify if asked.
+async fn sync_entry(
+    from: PathBuf,
+    to: PathBuf,
+    options: CopyOptions,
+    token: CancellationToken,
+) -> Result<(u64, Vec<OperationError>), String> {
+    tokio::task::spawn_blocking(move || {
+        if let Some(parent) = to.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("Failed to create directory: {}", e))?;
+        }
+
+        // Regular files are renamed over their old version by the transfer
+        // engine. Anything else is copied next to the old entry and swapped
+        // in, so a failed or cancelled copy leaves the old entry untouched.
+        let replace_in_place = fs::symlink_metadata(&from).is_ok_and(|m| m.is_file())
+            && fs::symlink_metadata(&to).is_ok_and(|m| m.is_file());
+        let bytes = if replace_in_place {
+            transfer::copy_entry(&from, &to, &options, &token)?
+        } else {
+            let staged = transfer::temp_sibling(&to, STAGED_SUFFIX);
+            transfer::remove_partial(&staged);
+            let bytes =
+                transfer::copy_entry(&from, &staged, &options, &token).inspect_err(|_| {
+                    transfer::remove_partial(&staged);
+                })?;
+            swap_in(&staged, &to).inspect_err(|_| transfer::remove_partial(&staged))?;
+            bytes
+        };
+        let mismatches = if options.verify {
+            transfer::verify_entry(&from, &to, &options)
+        } else {
+            Vec::new()
+        };
+        Ok((bytes, mismatches))
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+/// Move `staged` to `to`, setting aside and then removing whatever was there.
+/// The old entry is put back if the final rename fails.
+fn swap_in(staged: &Path, to: &Path) -> Result<(), String> {
+    if fs::symlink_metadata(to).is_err() {
+        return fs::rename(staged, to)
+            .map_err(|e| format!("Failed to move copy into place: {}", e));
+    }
+    let old = transfer::temp_sibling(to, REPLACED_SUFFIX);
+    transfer::remove_partial(&old);
+    fs::rename(to, &old).map_err(|e| format!("Failed to move {} aside: {}", to.display(), e))?;
+    if let Err(e) = fs::rename(staged, to) {
+        let _ = fs::rename(&old, to);
+        return Err(format!("Failed to move copy into place: {}", e));
+    }
+    transfer::remove_partial(&old);
+    Ok(())
+}
+
+async fn remove_extra(path: PathBuf, use_trash: bool) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || {
+        if use_trash {
+            return trash::delete(&path).map_err(|e| format!("Failed to move to trash: {}", e));
+        }
+        let result = match fs::symlink_metadata(&path) {
+            Ok(m) if m.is_dir() => fs::remove_dir_all(&path),
+            Ok(_) => fs::remove_file(&path),
+            Err(e) => Err(e),
+        };
+        result.map_err(|e| format!("Failed to delete: {}", e))
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use filetime::FileTime;
+    use tempfile::TempDir;
+
+    fn set_mtime(path: &Path, secs: i64) {
+        filetime::set_file_mtime(path, FileTime::from_unix_time(secs, 0)).unwrap();
+    }
+
+    fn options(mode: SyncMode) -> SyncOptions {
+        SyncOptions {
+            mode,
+            use_trash: false,
+            ..SyncOptions::default()
+        }
+    }
+
+    async fn run(diff: SyncDiff, options: SyncOptions) -> OperationComplete {
+        let mut rx = start_sync(diff, options, CancellationToken::new());
+        let mut complete = None;
+        while let Some(result) = rx.recv().await {
+            if let SyncResult::Complete(c) = result {
+                complete = Some(c);
+            }
+        }
+        complete.expect("sync sent no completion")
+    }
+
+    /// Source with `same`, `changed` and `sub/new`; destination with `same`,
+    /// an older `changed` and `extra`.
+    fn fixture() -> (TempDir, PathBuf, PathBuf) {
+        let temp = TempDir::new().unwrap();
+        let src = temp.path().join("src");
+        let dst = temp.path().join("dst");
+        fs::create_dir_all(src.join("sub")).unwrap();
+        fs::create_dir_all(&dst).unwrap();
+        for (root, changed) in [(&src, "new contents"), (&dst, "old")] {
+            fs::write(root.join("same"), "same").unwrap();
+            set_mtime(&root.join("same"), 1_000_000);
+            fs::write(root.join("changed"), changed).unwrap();
+        }
+        set_mtime(&src.join("changed"), 2_000_000);
+        set_mtime(&dst.join("changed"), 1_000_000);
+        fs::write(src.join("sub/new"), "fresh").unwrap();
+        fs::write(dst.join("extra"), "stale").unwrap();
+        (temp, src, dst)
+    }
+
+    #[test]
+    fn test_diff_mirror() {
+        let (_temp, src, dst) = fixture();
+        let diff = diff_trees(&src, &dst, &options(SyncMode::Mirror));
+
+        let summary: Vec<_> = diff
+            .entries
+            .iter()
+            .map(|e| (e.path.to_string_lossy().into_owned(), e.change))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("changed".to_string(), SyncChange::Changed),
+                ("sub".to_string(), SyncChange::New),
+                ("extra".to_string(), SyncChange::Extra),
+            ]
+        );
+        assert_eq!(diff.unchanged, 1);
+        assert_eq!(diff.bytes_to_copy(&options(SyncMode::Mirror)), 17);
+    }
+
+    #[tokio::test]
+    async fn test_mirror_with_delete_matches_source() {
+        let (_temp, src, dst) = fixture();
+        let mut opts = options(SyncMode::Mirror);
+        opts.delete_extras = true;
+
+        let complete = run(diff_trees(&src, &dst, &opts), opts.clone()).await;
+        assert_eq!((complete.succeeded, complete.failed), (3, 0));
+        assert!(!dst.join("extra").exists());
+        assert_eq!(
+            fs::read_to_string(dst.join("changed")).unwrap(),
+            "new contents"
+        );
+        assert!(diff_trees(&src, &dst, &opts).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_two_way_newer_side_wins() {
+        let (_temp, src, dst) = fixture();
+        fs::write(dst.join("same"), "edited").unwrap();
+        set_mtime(&dst.join("same"), 3_000_000);
+        let opts = options(SyncMode::TwoWay);
+
+        let diff = diff_trees(&src, &dst, &opts);
+        assert_eq!(diff.count(SyncChange::Extra), 0);
+        let complete = run(diff, opts.clone()).await;
+        assert_eq!(complete.failed, 0);
+
+        assert_eq!(fs::read_to_string(src.join("same")).unwrap(), "edited");
+        assert_eq!(fs::read_to_string(src.join("extra")).unwrap(), "stale");
+        assert_eq!(
+            fs::read_to_string(dst.join("changed")).unwrap(),
+            "new contents"
+        );
+        assert!(dst.join("sub/new").exists());
+        assert!(diff_trees(&src, &dst, &opts).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_hash_compare_and_conflict() {
+        let (_temp, src, dst) = fixture();
+        // Same size and mtime, different contents: only a hash sees it.
+        fs::write(dst.join("same"), "SAME").unwrap();
+        set_mtime(&dst.join("same"), 1_000_000);
+
+        let mut opts = options(SyncMode::TwoWay);
+        assert!(
+            !diff_trees(&src, &dst, &opts)
+                .entries
+                .iter()
+                .any(|e| e.path == Path::new("same"))
+        );
+
+        opts.compare_hash = true;
+        let diff = diff_trees(&src, &dst, &opts);
+        assert_eq!(diff.conflicts(), 1);
+
+        // Unresolved conflicts are skipped, leaving both sides alone.
+        let complete = run(diff, opts).await;
+        assert_eq!(complete.failed, 1);
+        assert_eq!(fs::read_to_string(dst.join("same")).unwrap(), "SAME");
+    }
+
+    #[tokio::test]
+    async fn test_two_way_type_change_is_conflict() {
+        let (_temp, src, dst) = fixture();
+        fs::remove_file(dst.join("same")).unwrap();
+        fs::create_dir(dst.join("same")).unwrap();
+        fs::write(dst.join("same/inner"), "keep").unwrap();
+        let mut opts = options(SyncMode::TwoWay);
+        opts.copy.conflict_resolution = Some(ConflictResolution::OverwriteAll);
+
+        let diff = diff_trees(&src, &dst, &opts);
+        assert_eq!(diff.conflicts(), 1);
+        let complete = run(diff, opts).await;
+        assert_eq!(complete.failed, 1);
+        assert_eq!(fs::read_to_string(dst.join("same/inner")).unwrap(), "keep");
+        assert_eq!(fs::read_to_string(src.join("same")).unwrap(), "same");
+    }
+
+    #[tokio::test]
+    async fn test_mirror_swaps_type_changes() {
+        let (_temp, src, dst) = fixture();
+        fs::remove_file(dst.join("same")).unwrap();
+        fs::create_dir(dst.join("same")).unwrap();
+        fs::write(dst.join("same/inner"), "old").unwrap();
+        let opts = options(SyncMode::Mirror);
+
+        let complete = run(diff_trees(&src, &dst, &opts), opts.clone()).await;
+        assert_eq!(complete.failed, 0);
+        assert_eq!(fs::read_to_string(dst.join("same")).unwrap(), "same");
+        let leftovers: Vec<_> = fs::read_dir(&dst)
+            .unwrap()
+            .flatten()
+            .filter(|e| e.file_name().to_string_lossy().starts_with('.'))
+            .collect();
+        assert!(leftovers.is_empty());
+    }
+
+    #[test]
+    fn test_nested_roots_refused() {
+        let (_temp, src, dst) = fixture();
+        let opts = options(SyncMode::Mirror);
+
+        let inside = diff_trees(&src, &src.join("backup"), &opts);
+        assert!(inside.is_empty());
+        assert_eq!(inside.errors.len(), 1);
+        assert!(!src.join("backup").exists());
+
+        fs::create_dir(dst.join("sub")).unwrap();
+        let around = diff_trees(&dst.join("sub"), &dst, &opts);
+        assert_eq!(around.errors.len(), 1);
+        assert!(around.is_empty());
+    }
+}
@@ -0,0 +1,907 @@
+//! Shared copy engine for copy operations and cross-device moves.
+//!
+//! Copies files, directories and symlinks according to [`CopyOptions`]:
+//! metadata preservation (permissions, ownership, timestamps, xattrs), sparse
+//! files, and an optional BLAKE3 verification pass over the result.
+//!
+//! Files are written to a hidden `.<name>.gravityfile-part` sibling and renamed
+//! into place once complete, so the destination never holds a truncated file.
+//! Large files also checkpoint their progress next to the partial file; a
+//! retried copy re-verifies the written prefix against the checkpoint's BLAKE3
+//! hash and resumes from there. Cancelling removes the partial file.
+
+use std::collections::HashSet;
+use std::ffi::OsString;
+use std::fs;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+use tokio_util::sync::CancellationToken;
+
+use crate::OperationError;
+use crate::copy::CopyOptions;
+use crate::jobs::PauseToken;
+
+/// Suffix of the temporary sibling a file is written to before its rename.
+const PARTIAL_SUFFIX: &str = "gravityfile-part";
+
+/// Longest file name most filesystems accept, in bytes.
+const NAME_MAX: usize = 255;
+
+/// Room left in temporary names for the checkpoint's `.json` extension.
+const CHECKPOINT_EXT_LEN: usize = ".json".len();
+
+/// Files at least this large are copied resumably with checkpoints.
+#[cfg(not(test))]
+const RESUMABLE_MIN_SIZE: u64 = 64 * 1024 * 1024;
+#[cfg(test)]
+const RESUMABLE_MIN_SIZE: u64 = 64 * 1024;
+
+/// Bytes written between checkpoints of a resumable copy.
+#[cfg(not(test))]
+const CHECKPOINT_INTERVAL: u64 = 64 * 1024 * 1024;
+#[cfg(test)]
+const CHECKPOINT_INTERVAL: u64 = 16 * 1024;
+
+/// Read buffer size for resumable copies.
+#[cfg(not(test))]
+const COPY_BUFFER_SIZE: usize = 1024 * 1024;
+#[cfg(test)]
+const COPY_BUFFER_SIZE: usize = 4 * 1024;
+
+/// Error message used when a copy stops because its token was cancelled.
+pub(crate) const CANCELLED: &str = "Operation cancelled";
+
+/// Copy a file, symlink or directory tree from `source` to `dest`.
+///
+/// Returns the number of file bytes copied.
+pub(crate) fn copy_entry(
+    source: &Path,
+    dest: &Path,
+    options: &CopyOptions,
+    cancel: &CancellationToken,
+) -> Result<u64, String> {
+    let metadata = entry_metadata(source, options)?;
+
+    if metadata.is_symlink() {
+        copy_symlink(source, dest)?;
+        apply_metadata(source, &metadata, dest, options)?;
+        Ok(0) // Symlinks have no real size
+    } else if metadata.is_dir() {
+        copy_tree(source, dest, options, &mut HashSet::new(), cancel)
+    } else {
+        copy_file(source, dest, &metadata, options, cancel)
+    }
+}
+
+/// Recursively copy a directory.
+///
+/// Uses `entry.file_type()` (no symlink-follow) unless the options ask for
+/// symlinks to be dereferenced, and tracks inodes via a visited set to detect
+/// hard-link / symlink loops on Unix.
+pub(crate) fn copy_tree(
+    source: &Path,
+    dest: &Path,
+    options: &CopyOptions,
+    visited: &mut HashSet<u64>,
+    cancel: &CancellationToken,
+) -> Result<u64, String> {
+    let metadata = entry_metadata(source, options)?;
+
+    // Loop detection via inode on Unix
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        if !visited.insert(metadata.ino()) {
+            // Already visited this inode — skip to break the loop
+            return Ok(0);
+        }
+    }
+
+    fs::create_dir_all(dest).map_err(|e| format!("Failed to create directory: {}", e))?;
+
+    let mut total_bytes = 0u64;
+
+    let entries = fs::read_dir(source).map_err(|e| format!("Failed to read directory: {}", e))?;
+
+    for entry in entries {
+        if let Some(pause) = &options.pause {
+            pause.wait_blocking(cancel);
+        }
+        if cancel.is_cancelled() {
+            return Err(CANCELLED.to_string());
+        }
+        let entry = entry.map_err(|e| format!("Failed to read entry: {}", e))?;
+        let path = entry.path();
+        let dest_path = dest.join(entry.file_name());
+        let child = entry_metadata(&path, options)?;
+
+        if child.is_symlink() {
+            copy_symlink(&path, &dest_path)?;
+            apply_metadata(&path, &child, &dest_path, options)?;
+        } else if child.is_dir() {
+            total_bytes += copy_tree(&path, &dest_path, options, visited, cancel)?;
+        } else {
+            total_bytes += copy_file(&path, &dest_path, &child, options, cancel)?;
+        }
+    }
+
+    // Applied last so copying children doesn't bump the mtime or trip over
+    // read-only directory modes.
+    apply_metadata(source, &metadata, dest, options)?;
+
+    Ok(total_bytes)
+}
+
+/// Metadata for `path`, following symlinks only when the options ask for it.
+///
+/// Broken symlinks are always returned as links so they can be recreated.
+fn entry_metadata(path: &Path, options: &CopyOptions) -> Result<fs::Metadata, String> {
+    let link = fs::symlink_metadata(path).map_err(|e| format!("Failed to read metadata: {}", e))?;
+    if options.follow_symlinks && link.is_symlink() {
+        Ok(fs::metadata(path).unwrap_or(link))
+    } else {
+        Ok(link)
+    }
+}
+
+/// Recreate the symlink at `source` as `dest` rather than following it.
+fn copy_symlink(source: &Path, dest: &Path) -> Result<(), String> {
+    let target = fs::read_link(source).map_err(|e| format!("Failed to read symlink: {}", e))?;
+    #[cfg(unix)]
+    {
+        std::os::unix::fs::symlink(&target, dest)
+            .map_err(|e| format!("Failed to create symlink: {}", e))?;
+    }
+    #[cfg(windows)]
+    {
+        // Choose symlink_dir vs symlink_file based on what the original link
+        // pointed at.
+        let result = if source.is_dir() {
+            std::os::windows::fs::symlink_dir(&target, dest)
+        } else {
+            std::os::windows::fs::symlink_file(&target, dest)
+        };
+        result.map_err(|e| format!("Failed to create symlink: {}", e))?;
+    }
+    Ok(())
+}
+
+/// Copy a single regular file via its partial sibling.
+///
+/// Failed resumable copies that got as far as a checkpoint keep their partial
+/// file for the next attempt and name it in the error; anything else
+/// (including cancellation) removes the partial file and checkpoint.
+fn copy_file(
+    source: &Path,
+    dest: &Path,
+    metadata: &fs::Metadata,
+    options: &CopyOptions,
+    cancel: &CancellationToken,
+) -> Result<u64, String> {
+    let partial = partial_path(dest);
+    let resumable =
+        !(options.sparse && is_sparse(metadata)) && metadata.len() >= RESUMABLE_MIN_SIZE;
+
+    let result = if options.sparse && is_sparse(metadata) {
+        copy_sparse(source, &partial).map_err(|e| format!("Failed to copy: {}", e))
+    } else if resumable {
+        copy_resumable(source, &partial, metadata, options.pause.as_ref(), cancel)
+    } else {
+        fs::copy(source, &partial)
+            .map(|_| ())
+            .map_err(|e| format!("Failed to copy: {}", e))
+    }
+    .and_then(|()| apply_metadata(source, metadata, &partial, options))
+    .and_then(|()| {
+        fs::rename(&partial, dest).map_err(|e| format!("Failed to move copy into place: {}", e))
+    });
+
+    match result {
+        Ok(()) => {
+            let _ = fs::remove_file(checkpoint_path(&partial));
+            Ok(metadata.len())
+        }
+        Err(e) => {
+            if resumable && !cancel.is_cancelled() && checkpoint_path(&partial).exists() {
+                return Err(format!(
+                    "{} (partial copy kept at {} to resume)",
+                    e,
+                    partial.display()
+                ));
+            }
+            discard_partial(dest);
+            Err(e)
+        }
+    }
+}
+
+/// Path of the temporary sibling `dest` is written to.
+pub(crate) fn partial_path(dest: &Path) -> PathBuf {
+    temp_sibling(dest, PARTIAL_SUFFIX)
+}
+
+/// Hidden sibling `.<name>.<suffix>` of `dest`.
+///
+/// Names that would not fit in `NAME_MAX` (with room for a checkpoint
+/// extension) use a hash of the original name instead, so the same `dest`
+/// always maps to the same sibling.
+pub(crate) fn temp_sibling(dest: &Path, suffix: &str) -> PathBuf {
+    let file_name = dest.file_name().unwrap_or_default();
+    let mut name = OsString::from(".");
+    if file_name.len() + suffix.len() + 2 + CHECKPOINT_EXT_LEN <= NAME_MAX {
+        name.push(file_name);
+    } else {
+        let hash = blake3::hash(file_name.as_encoded_bytes());
+        name.push(&hash.to_hex()[..32]);
+    }
+    name.push(".");
+    name.push(suffix);
+    dest.with_file_name(name)
+}
+
+/// Path of the checkpoint stored next to a partial file.
+fn checkpoint_path(partial: &Path) -> PathBuf {
+    let mut path = partial.as_os_str().to_owned();
+    path.push(".json");
+    PathBuf::from(path)
+}
+
+/// Remove the partial file and checkpoint for `dest`, if any.
+pub(crate) fn discard_partial(dest: &Path) {
+    let partial = partial_path(dest);
+    let _ = fs::remove_file(checkpoint_path(&partial));
+    let _ = fs::remove_file(&partial);
+}
+
+/// Progress of a resumable copy, persisted next to the partial file.
+#[derive(Debug, Serialize, Deserialize)]
+struct Checkpoint {
+    /// Source length when the copy started.
+    source_len: u64,
+    /// Source modification time when the copy started.
+    source_modified: Option<SystemTime>,
+    /// Bytes of the partial file known to be written and synced.
+    offset: u64,
+    /// BLAKE3 hash (hex) of the first `offset` bytes.
+    prefix_hash: String,
+}
+
+impl Checkpoint {
+    fn load(path: &Path) -> Option<Self> {
+        serde_json::from_slice(&fs::read(path).ok()?).ok()
+    }
+
+    /// Whether the source is unchanged since the checkpoint was written.
+    fn matches(&self, metadata: &fs::Metadata) -> bool {
+        self.source_len == metadata.len() && self.source_modified == metadata.modified().ok()
+    }
+}
+
+/// Hash the first `offset` bytes of `partial` into `hasher` and check them
+/// against the checkpoint.
+fn verify_prefix(partial: &Path, checkpoint: &Checkpoint, hasher: &mut blake3::Hasher) -> bool {
+    let Ok(file) = fs::File::open(partial) else {
+        return false;
+    };
+    if file.metadata().map(|m| m.len()).unwrap_or(0) < checkpoint.offset {
+        return false;
+    }
+    hasher.update_reader(file.take(checkpoint.offset)).is_ok()
+        && hasher.finalize().to_hex().as_str() == checkpoint.prefix_hash
+}
+
+/// Copy `source` to `partial` in chunks, checkpointing as it goes and resuming
+/// from a valid earlier checkpoint.
+fn copy_resumable(
+    source: &Path,
+    partial: &Path,
+    metadata: &fs::Metadata,
+    pause: Option<&PauseToken>,
+    cancel: &CancellationToken,
+) -> Result<(), String> {
+    let checkpoint_file = checkpoint_path(partial);
+    let mut hasher = blake3::Hasher::new();
+    let mut offset = 0u64;
+
+    if let Some(checkpoint) = Checkpoint::load(&checkpoint_file)
+        && checkpoint.matches(metadata)
+    {
+        if verify_prefix(partial, &checkpoint, &mut hasher) {
+            tracing::info!(
+                "Resuming copy of {} at byte {}",
+                source.display(),
+                checkpoint.offset
+            );
+            offset = checkpoint.offset;
+        } else {
+            hasher = blake3::Hasher::new();
+        }
+    }
+
+    let io_err = |e: std::io::Error| format!("Failed to copy: {}", e);
+    let mut input = fs::File::open(source).map_err(io_err)?;
+    let mut output = fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(partial)
+        .map_err(io_err)?;
+    output.set_len(offset).map_err(io_err)?;
+    input.seek(SeekFrom::Start(offset)).map_err(io_err)?;
+    output.seek(SeekFrom::Start(offset)).map_err(io_err)?;
+
+    let mut buffer = vec![0u8; COPY_BUFFER_SIZE];
+    let mut since_checkpoint = 0u64;
+    loop {
+        if let Some(pause) = pause {
+            pause.wait_blocking(cancel);
+        }
+        if cancel.is_cancelled() {
+            return Err(CANCELLED.to_string());
+        }
+        let n = input.read(&mut buffer).map_err(io_err)?;
+        if n == 0 {
+            break;
+        }
+        output.write_all(&buffer[..n]).map_err(io_err)?;
+        hasher.update(&buffer[..n]);
+        offset += n as u64;
+        since_checkpoint += n as u64;
+
+        if since_checkpoint >= CHECKPOINT_INTERVAL {
+            // Only checkpoint data that is durably on disk.
+            output.sync_data().map_err(io_err)?;
+            let checkpoint = Checkpoint {
+                source_len: metadata.len(),
+                source_modified: metadata.modified().ok(),
+                offset,
+                prefix_hash: hasher.finalize().to_hex().to_string(),
+            };
+            let json = serde_json::to_vec(&checkpoint)
+                .map_err(|e| format!("Failed to write checkpoint: {}", e))?;
+            fs::write(&checkpoint_file, json)
+                .map_err(|e| format!("Failed to write checkpoint: {}", e))?;
+            since_checkpoint = 0;
+        }
+    }
+
+    output.sync_all().map_err(io_err)?;
+    // Match `fs::copy`, which gives the copy its source's mode.
+    output
+        .set_permissions(metadata.permissions())
+        .map_err(io_err)?;
+    Ok(())
+}
+
+/// Whether the file occupies fewer blocks than its length implies.
+#[cfg(unix)]
+fn is_sparse(metadata: &fs::Metadata) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    metadata.blocks().saturating_mul(512) < metadata.len()
+}
+
+#[cfg(not(unix))]
+fn is_sparse(_metadata: &fs::Metadata) -> bool {
+    false
+}
+
+/// Copy only the data regions of a sparse file, leaving holes unallocated.
+#[cfg(target_os = "linux")]
+fn copy_sparse(source: &Path, dest: &Path) -> std::io::Result<()> {
+    use std::os::unix::io::AsRawFd;
+
+    let mut input = fs::File::open(source)?;
+    let len = input.metadata()?.len();
+    let mut output = fs::File::create(dest)?;
+    let fd = input.as_raw_fd();
+
+    let mut offset: libc::off_t = 0;
+    while (offset as u64) < len {
+        // SAFETY: fd is a valid open descriptor owned by `input`.
+        let data = unsafe { libc::lseek(fd, offset, libc::SEEK_DATA) };
+        if data < 0 {
+            let err = std::io::Error::last_os_error();
+            match err.raw_os_error() {
+                // No more data past `offset`: the rest is a hole.
+                Some(libc::ENXIO) => break,
+                // SEEK_DATA unsupported here: fall back to a dense copy.
+                Some(libc::EINVAL) => {
+                    drop(output);
+                    return fs::copy(source, dest).map(|_| ());
+                }
+                _ => return Err(err),
+            }
+        }
+        // SAFETY: as above.
+        let hole = unsafe { libc::lseek(fd, data, libc::SEEK_HOLE) };
+        if hole < 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+
+        input.seek(SeekFrom::Start(data as u64))?;
+        output.seek(SeekFrom::Start(data as u64))?;
+        std::io::copy(&mut (&mut input).take((hole - data) as u64), &mut output)?;
+        offset = hole;
+    }
+
+    // Extend to the full length so a trailing hole is preserved.
+    output.set_len(len)?;
+    output.set_permissions(input.metadata()?.permissions())?;
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn copy_sparse(source: &Path, dest: &Path) -> std::io::Result<()> {
+    fs::copy(source, dest).map(|_| ())
+}
+
+/// Apply the metadata of the original entry to its copy.
+///
+/// Ownership comes first because `chown` clears setuid/setgid bits, and
+/// timestamps come last so nothing else touches them afterwards.
+fn apply_metadata(
+    source: &Path,
+    metadata: &fs::Metadata,
+    dest: &Path,
+    options: &CopyOptions,
+) -> Result<(), String> {
+    let is_symlink = metadata.is_symlink();
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{MetadataExt, PermissionsExt};
+
+        if options.preserve_ownership && is_privileged() {
+            std::os::unix::fs::lchown(dest, Some(metadata.uid()), Some(metadata.gid()))
+                .map_err(|e| format!("Failed to set ownership: {}", e))?;
+        }
+
+        if options.preserve_xattrs && !is_symlink {
+            // Setting xattrs needs write access, and `fs::copy` has already
+            // given copies of read-only files their read-only mode.
+            let current = fs::metadata(dest)
+                .map_err(|e| format!("Failed to read metadata: {}", e))?
+                .permissions();
+            let read_only = current.mode() & 0o200 == 0;
+            if read_only {
+                fs::set_permissions(dest, fs::Permissions::from_mode(current.mode() | 0o200))
+                    .map_err(|e| format!("Failed to set permissions: {}", e))?;
+            }
+            let result = copy_xattrs(source, dest);
+            if read_only && !options.preserve_permissions {
+                fs::set_permissions(dest, current)
+                    .map_err(|e| format!("Failed to set permissions: {}", e))?;
+            }
+            result?;
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = source;
+
+    if !is_symlink && options.preserve_permissions {
+        fs::set_permissions(dest, metadata.permissions())
+            .map_err(|e| format!("Failed to set permissions: {}", e))?;
+    }
+
+    if options.preserve_timestamps {
+        let atime = filetime::FileTime::from_last_access_time(metadata);
+        let mtime = filetime::FileTime::from_last_modification_time(metadata);
+        filetime::set_symlink_file_times(dest, atime, mtime)
+            .map_err(|e| format!("Failed to set timestamps: {}", e))?;
+    }
+
+    Ok(())
+}
+
+/// Copy extended attributes from `source` to `dest`.
+///
+/// Attributes the destination refuses (unsupported filesystem, or privileged
+/// namespaces such as `security.*` without the rights to set them) are skipped.
+#[cfg(unix)]
+fn copy_xattrs(source: &Path, dest: &Path) -> Result<(), String> {
+    if !xattr::SUPPORTED_PLATFORM {
+        return Ok(());
+    }
+
+    let names = match xattr::list(source) {
+        Ok(names) => names,
+        Err(e) if is_xattr_refusal(&e) => return Ok(()),
+        Err(e) => return Err(format!("Failed to list extended attributes: {}", e)),
+    };
+
+    for name in names {
+        let Ok(Some(value)) = xattr::get(source, &name) else {
+            continue;
+        };
+        match xattr::set(dest, &name, &value) {
+            Ok(()) => {}
+            Err(e) if is_xattr_refusal(&e) => {
+                tracing::debug!(
+                    "Skipping extended attribute {:?} on {}: {}",
+                    name,
+                    dest.display(),
+                    e
+                );
+            }
+            Err(e) => {
+                return Err(format!(
+                    "Failed to set extended attribute {:?}: {}",
+                    name, e
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(unix)]
+fn is_xattr_refusal(e: &std::io::Error) -> bool {
+    matches!(
+        e.raw_os_error(),
+        Some(libc::ENOTSUP) | Some(libc::EPERM) | Some(libc::EACCES)
+    ) || e.kind() == std::io::ErrorKind::Unsupported
+}
+
+/// Whether the process may hand files to other users.
+#[cfg(unix)]
+fn is_privileged() -> bool {
+    // SAFETY: geteuid has no preconditions and cannot fail.
+    unsafe { libc::geteuid() == 0 }
+}
+
+/// Compare a copied entry against its source with BLAKE3.
+///
+/// Returns one error per file whose contents differ (or that is missing),
+/// and per symlink whose target differs.
+pub(crate) fn verify_entry(
+    source: &Path,
+    dest: &Path,
+    options: &CopyOptions,
+) -> Vec<OperationError> {
+    let mut errors = Vec::new();
+    verify_into(source, dest, options, &mut HashSet::new(), &mut errors);
+    errors
+}
+
+fn verify_into(
+    source: &Path,
+    dest: &Path,
+    options: &CopyOptions,
+    visited: &mut HashSet<u64>,
+    errors: &mut Vec<OperationError>,
+) {
+    let metadata = match entry_metadata(source, options) {
+        Ok(m) => m,
+        Err(e) => {
+            errors.push(OperationError::new(source.to_path_buf(), e));
+            return;
+        }
+    };
+
+    if metadata.is_symlink() {
+        if fs::read_link(source).ok() != fs::read_link(dest).ok() {
+            errors.push(OperationError::new(
+                dest.to_path_buf(),
+                "Verification failed: symlink target differs from source",
+            ));
+        }
+    } else if metadata.is_dir() {
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::MetadataExt;
+            if !visited.insert(metadata.ino()) {
+                return;
+            }
+        }
+        let Ok(entries) = fs::read_dir(source) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let name = entry.file_name();
+            verify_into(&entry.path(), &dest.join(&name), options, visited, errors);
+        }
+    } else {
+        match (file_hash(source), file_hash(dest)) {
+            (Ok(a), Ok(b)) if a == b => {}
+            (Ok(a), Ok(b)) => errors.push(OperationError::new(
+                dest.to_path_buf(),
+                format!(
+                    "Verification failed: checksum mismatch (source {}, copy {})",
+                    &a.to_hex()[..16],
+                    &b.to_hex()[..16]
+                ),
+            )),
+            (Err(e), _) => errors.push(OperationError::new(
+                source.to_path_buf(),
+                format!("Verification failed: cannot read source: {}", e),
+            )),
+            (_, Err(e)) => errors.push(OperationError::new(
+                dest.to_path_buf(),
+                format!("Verification failed: cannot read copy: {}", e),
+            )),
+        }
+    }
+}
+
+/// BLAKE3 hash of a file's contents.
+pub(crate) fn file_hash(path: &Path) -> std::io::Result<blake3::Hash> {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update_reader(fs::File::open(path)?)?;
+    Ok(hasher.finalize())
+}
+
+/// Remove a partially written destination, whatever its type.
+pub(crate) fn remove_partial(dest: &Path) {
+    let result = match fs::symlink_metadata(dest) {
+        Ok(m) if m.is_dir() => fs::remove_dir_all(dest),
+        Ok(_) => fs::remove_file(dest),
+        Err(_) => return,
+    };
+    if let Err(e) = result {
+        tracing::warn!("Failed to clean up {}: {}", dest.display(), e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn archive() -> CopyOptions {
+        CopyOptions {
+            verify: true,
+            ..CopyOptions::archive()
+        }
+    }
+
+    #[test]
+    fn test_copy_tree_preserves_symlinks_and_verifies() {
+        let tmp = TempDir::new().unwrap();
+        let src = tmp.path().join("src");
+        fs::create_dir_all(src.join("sub")).unwrap();
+        fs::write(src.join("sub/data.bin"), vec![7u8; 4096]).unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink("sub/data.bin", src.join("link")).unwrap();
+
+        let dest = tmp.path().join("dest");
+        let bytes = copy_entry(&src, &dest, &archive(), &CancellationToken::new()).unwrap();
+        assert_eq!(bytes, 4096);
+        #[cfg(unix)]
+        assert!(
+            fs::symlink_metadata(dest.join("link"))
+                .unwrap()
+                .file_type()
+                .is_symlink()
+        );
+        assert!(verify_entry(&src, &dest, &archive()).is_empty());
+
+        fs::write(dest.join("sub/data.bin"), vec![8u8; 4096]).unwrap();
+        let errors = verify_entry(&src, &dest, &archive());
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].message.contains("checksum mismatch"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_copy_preserves_permissions_and_mtime() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let tmp = TempDir::new().unwrap();
+        let src = tmp.path().join("dir");
+        fs::create_dir(&src).unwrap();
+        let file = src.join("script.sh");
+        fs::write(&file, b"#!/bin/sh\n").unwrap();
+        fs::set_permissions(&file, fs::Permissions::from_mode(0o750)).unwrap();
+        fs::set_permissions(&src, fs::Permissions::from_mode(0o710)).unwrap();
+        let old = filetime::FileTime::from_unix_time(1_000_000_000, 0);
+        filetime::set_file_mtime(&file, old).unwrap();
+        filetime::set_file_mtime(&src, old).unwrap();
+
+        let dest = tmp.path().join("copy");
+        copy_entry(&src, &dest, &archive(), &CancellationToken::new()).unwrap();
+
+        let meta = fs::metadata(dest.join("script.sh")).unwrap();
+        assert_eq!(meta.permissions().mode() & 0o777, 0o750);
+        assert_eq!(filetime::FileTime::from_last_modification_time(&meta), old);
+        let dir_meta = fs::metadata(&dest).unwrap();
+        assert_eq!(dir_meta.permissions().mode() & 0o777, 0o710);
+        assert_eq!(
+            filetime::FileTime::from_last_modification_time(&dir_meta),
+            old
+        );
+
+        fs::set_permissions(&src, fs::Permissions::from_mode(0o755)).unwrap();
+        fs::set_permissions(&dest, fs::Permissions::from_mode(0o755)).unwrap();
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_sparse_copy_keeps_holes() {
+        use std::io::{Seek, SeekFrom, Write};
+        use std::os::unix::fs::MetadataExt;
+
+        let tmp = TempDir::new().unwrap();
+        let src = tmp.path().join("sparse.img");
+        let mut f = fs::File::create(&src).unwrap();
+        f.seek(SeekFrom::Start(8 * 1024 * 1024)).unwrap();
+        f.write_all(b"tail").unwrap();
+        drop(f);
+        if !is_sparse(&fs::metadata(&src).unwrap()) {
+            return; // Filesystem without sparse file support.
+        }
+
+        let dest = tmp.path().join("copy.img");
+        copy_entry(&src, &dest, &archive(), &CancellationToken::new()).unwrap();
+
+        let meta = fs::metadata(&dest).unwrap();
+        assert_eq!(meta.len(), 8 * 1024 * 1024 + 4);
+        assert!(meta.blocks() * 512 < meta.len());
+        assert!(verify_entry(&src, &dest, &archive()).is_empty());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_copy_preserves_xattrs() {
+        let tmp = TempDir::new().unwrap();
+        let src = tmp.path().join("tagged.txt");
+        fs::write(&src, b"data").unwrap();
+        if xattr::set(&src, "user.gravityfile.test", b"kept").is_err() {
+            return; // Filesystem without user xattr support.
+        }
+
+        let dest = tmp.path().join("copy.txt");
+        copy_entry(&src, &dest, &archive(), &CancellationToken::new()).unwrap();
+        assert_eq!(
+            xattr::get(&dest, "user.gravityfile.test").unwrap(),
+            Some(b"kept".to_vec())
+        );
+    }
+
+    fn pattern(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i % 251) as u8).collect()
+    }
+
+    #[test]
+    fn test_copy_leaves_no_partial_files() {
+        let tmp = TempDir::new().unwrap();
+        let src = tmp.path().join("big.bin");
+        fs::write(&src, pattern(200 * 1024)).unwrap();
+
+        let dest = tmp.path().join("copy.bin");
+        copy_entry(&src, &dest, &archive(), &CancellationToken::new()).unwrap();
+
+        assert_eq!(fs::read(&dest).unwrap(), fs::read(&src).unwrap());
+        assert!(!partial_path(&dest).exists());
+        assert!(!checkpoint_path(&partial_path(&dest)).exists());
+    }
+
+    #[test]
+    fn test_long_file_name_fits_partial() {
+        let tmp = TempDir::new().unwrap();
+        let src = tmp.path().join("big.bin");
+        fs::write(&src, pattern(200 * 1024)).unwrap();
+
+        let dest = tmp.path().join("x".repeat(250));
+        let partial = partial_path(&dest);
+        assert!(checkpoint_path(&partial).file_name().unwrap().len() <= NAME_MAX);
+        assert_eq!(partial, partial_path(&dest));
+
+        copy_entry(&src, &dest, &archive(), &CancellationToken::new()).unwrap();
+        assert_eq!(fs::read(&dest).unwrap(), fs::read(&src).unwrap());
+        assert!(!partial.exists());
+    }
+
+    #[test]
+    fn test_resume_from_verified_checkpoint() {
+        let tmp = TempDir::new().unwrap();
+        let src = tmp.path().join("big.bin");
+        let data = pattern(200 * 1024);
+        fs::write(&src, &data).unwrap();
+        let dest = tmp.path().join("copy.bin");
+        let partial = partial_path(&dest);
+
+        // Simulate a crash after 100 KiB whose prefix was written from a
+        // different (but checkpointed) source: resuming keeps it, proving the
+        // copy picked up at the offset instead of starting over.
+        let mut prefix = vec![0xAAu8; 100 * 1024];
+        fs::write(&partial, &prefix).unwrap();
+        let meta = fs::metadata(&src).unwrap();
+        let checkpoint = Checkpoint {
+            source_len: meta.len(),
+            source_modified: meta.modified().ok(),
+            offset: prefix.len() as u64,
+            prefix_hash: blake3::hash(&prefix).to_hex().to_string(),
+        };
+        fs::write(
+            checkpoint_path(&partial),
+            serde_json::to_vec(&checkpoint).unwrap(),
+        )
+        .unwrap();
+
+        copy_entry(
+            &src,
+            &dest,
+            &CopyOptions::default(),
+            &CancellationToken::new(),
+        )
+        .unwrap();
+        prefix.extend_from_slice(&data[100 * 1024..]);
+        assert_eq!(fs::read(&dest).unwrap(), prefix);
+        assert!(!checkpoint_path(&partial).exists());
+    }
+
+    #[test]
+    fn test_corrupt_partial_restarts_copy() {
+        let tmp = TempDir::new().unwrap();
+        let src = tmp.path().join("big.bin");
+        let data = pattern(200 * 1024);
+        fs::write(&src, &data).unwrap();
+        let dest = tmp.path().join("copy.bin");
+        let partial = partial_path(&dest);
+
+        fs::write(&partial, vec![0xAAu8; 100 * 1024]).unwrap();
+        let meta = fs::metadata(&src).unwrap();
+        let checkpoint = Checkpoint {
+            source_len: meta.len(),
+            source_modified: meta.modified().ok(),
+            offset: 100 * 1024,
+            prefix_hash: blake3::hash(b"something else").to_hex().to_string(),
+        };
+        fs::write(
+            checkpoint_path(&partial),
+            serde_json::to_vec(&checkpoint).unwrap(),
+        )
+        .unwrap();
+
+        copy_entry(
+            &src,
+            &dest,
+            &CopyOptions::default(),
+            &CancellationToken::new(),
+        )
+        .unwrap();
+        assert_eq!(fs::read(&dest).unwrap(), data);
+    }
+
+    #[test]
+    fn test_failed_copy_reports_kept_partial() {
+        let tmp = TempDir::new().unwrap();
+        let src = tmp.path().join("big.bin");
+        fs::write(&src, pattern(200 * 1024)).unwrap();
+        let dest = tmp.path().join("copy.bin");
+        fs::create_dir(&dest).unwrap();
+        fs::write(dest.join("blocker"), "").unwrap();
+
+        let options = CopyOptions::default();
+        let err = copy_entry(&src, &dest, &options, &CancellationToken::new()).unwrap_err();
+        let partial = partial_path(&dest);
+        assert!(err.contains(&partial.display().to_string()));
+        assert!(partial.exists());
+
+        fs::remove_dir_all(&dest).unwrap();
+        copy_entry(&src, &dest, &options, &CancellationToken::new()).unwrap();
+        assert_eq!(fs::read(&dest).unwrap(), fs::read(&src).unwrap());
+        assert!(!partial.exists());
+        assert!(!checkpoint_path(&partial).exists());
+    }
+
+    #[test]
+    fn test_cancel_removes_partial() {
+        let tmp = TempDir::new().unwrap();
+        let src = tmp.path().join("big.bin");
+        fs::write(&src, pattern(200 * 1024)).unwrap();
+        let dest = tmp.path().join("copy.bin");
+
+        let token = CancellationToken::new();
+        token.cancel();
+        let err = copy_entry(&src, &dest, &CopyOptions::default(), &token).unwrap_err();
+        assert_eq!(err, CANCELLED);
+        assert!(!dest.exists());
+        assert!(!partial_path(&dest).exists());
+        assert!(!checkpoint_path(&partial_path(&dest)).exists());
+    }
+}
@@ -0,0 +1,347 @@
+//! Browsing, restoring and purging the system trash.
+//!
+//! Listing the trash is only possible on platforms that follow the
+//! freedesktop.org trash specification (Linux and other non-Apple unixes).
+//! On other platforms every entry point returns an error so callers can keep
+//! a single code path.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use tokio::sync::mpsc;
+
+use crate::progress::{OperationComplete, OperationProgress, OperationType};
+use crate::{OPERATION_CHANNEL_SIZE, OperationError};
+
+/// A single item currently sitting in the trash.
+#[derive(Debug, Clone)]
+pub struct TrashEntry {
+    /// Name the item had before it was trashed.
+    pub name: String,
+    /// Full path the item had before it was trashed.
+    pub original_path: PathBuf,
+    /// When the item was trashed (`None` if the trash info is missing a date).
+    pub deleted_at: Option<SystemTime>,
+    /// Total size in bytes (recursive for directories).
+    pub size: u64,
+    /// Whether the trashed item is a directory.
+    pub is_dir: bool,
+    /// Where the item's contents live inside the trash.
+    pub trashed_path: PathBuf,
+    /// Underlying platform handle used for restore/purge.
+    item: trash::TrashItem,
+}
+
+/// Result sent through the channel during restore and purge operations.
+#[derive(Debug)]
+pub enum TrashResult {
+    /// Progress update.
+    Progress(OperationProgress),
+    /// The operation completed.
+    Complete(OperationComplete),
+}
+
+/// Whether trash browsing is available on this platform.
+pub const fn trash_browsing_supported() -> bool {
+    platform::SUPPORTED
+}
+
+/// List every item currently in the trash, newest first.
+pub fn list_trash() -> Result<Vec<TrashEntry>, String> {
+    let mut entries: Vec<TrashEntry> = platform::list()?
+        .into_iter()
+        .map(TrashEntry::from_item)
+        .collect();
+    entries.sort_by_key(|e| std::cmp::Reverse(e.deleted_at));
+    Ok(entries)
+}
+
+/// Find the trash entries for items that were trashed from `paths`.
+///
+/// When the same original path was trashed several times, the most recently
+/// trashed copy wins. Paths with no matching entry are returned separately.
+pub fn find_trashed(paths: &[PathBuf]) -> Result<(Vec<TrashEntry>, Vec<PathBuf>), String> {
+    let mut newest: HashMap<&Path, TrashEntry> = HashMap::new();
+    for entry in list_trash()? {
+        let Some(path) = paths.iter().find(|p| **p == entry.original_path) else {
+            continue;
+        };
+        // list_trash is sorted newest first, so keep the first hit.
+        newest.entry(path.as_path()).or_insert(entry);
+    }
+
+    let mut found = Vec::with_capacity(newest.len());
+    let mut missing = Vec::new();
+    for path in paths {
+        match newest.remove(path.as_path()) {
+            Some(entry) => found.push(entry),
+            None => missing.push(path.clone()),
+        }
+    }
+    Ok((found, missing))
+}
+
+/// Start restoring trash entries to their original locations.
+///
+/// Entries whose original path is occupied again are reported as errors and
+/// left in the trash.
+pub fn start_restore(entries: Vec<TrashEntry>) -> mpsc::Receiver<TrashResult> {
+    start_trash_op(entries, OperationType::Restore, |entry| {
+        platform::restore(entry.item)
+    })
+}
+
+/// Start permanently deleting trash entries.
+pub fn start_purge(entries: Vec<TrashEntry>) -> mpsc::Receiver<TrashResult> {
+    start_trash_op(entries, OperationType::Delete, |entry| {
+        platform::purge(entry.item)
+    })
+}
+
+/// Shared driver for restore and purge: one blocking call per entry with
+/// progress after each.
+fn start_trash_op(
+    entries: Vec<TrashEntry>,
+    operation_type: OperationType,
+    op: fn(TrashEntry) -> Result<(), String>,
+) -> mpsc::Receiver<TrashResult> {
+    let (tx, rx) = mpsc::channel(OPERATION_CHANNEL_SIZE);
+
+    tokio::spawn(async move {
+        let bytes_total = entries.iter().map(|e| e.size).sum();
+        let mut progress = OperationProgress::new(operation_type, entries.len(), bytes_total);
+        let mut succeeded = 0;
+        let mut failed = 0;
+
+        for entry in entries {
+            progress.set_current_file(Some(entry.original_path.clone()));
+            let _ = tx.send(TrashResult::Progress(progress.clone())).await;
+
+            let path = entry.original_path.clone();
+            let size = entry.size;
+            // The trash crate asserts on malformed trash directories, so a
+            // panic in the blocking task is treated like any other failure.
+            match tokio::task::spawn_blocking(move || op(entry)).await {
+                Ok(Ok(())) => {
+                    progress.complete_file(size);
+                    succeeded += 1;
+                }
+                Ok(Err(e)) => {
+                    progress.add_error(OperationError::new(path, e));
+                    failed += 1;
+                }
+                Err(e) => {
+                    progress.add_error(OperationError::new(path, e.to_string()));
+                    failed += 1;
+                }
+            }
+        }
+
+        let _ = tx
+            .send(TrashResult::Complete(OperationComplete {
+                operation_type,
+                succeeded,
+                failed,
+                bytes_processed: progress.bytes_processed,
+                errors: progress.errors,
+            }))
+            .await;
+    });
+
+    rx
+}
+
+impl TrashEntry {
+    fn from_item(item: trash::TrashItem) -> Self {
+        let trashed_path = platform::trashed_path(&item);
+        let is_dir = trashed_path
+            .symlink_metadata()
+            .map(|m| m.is_dir())
+            .unwrap_or(false);
+        let size = if is_dir {
+            dir_size(&trashed_path)
+        } else {
+            trashed_path
+                .symlink_metadata()
+                .map(|m| m.len())
+                .unwrap_or(0)
+        };
+        // The trash crate reports -1 when the DeletionDate is missing.
+        let deleted_at = u64::try_from(item.time_deleted)
+            .ok()
+            .map(|secs| UNIX_EPOCH + Duration::from_secs(secs));
+
+        Self {
+            name: item.name.to_string_lossy().into_owned(),
+            original_path: item.original_path(),
+            deleted_at,
+            size,
+            is_dir,
+            trashed_path,
+            item,
+        }
+    }
+}
+
+/// Recursive size of a directory without following symlinks.
+fn dir_size(path: &Path) -> u64 {
+    let Ok(read_dir) = fs::read_dir(path) else {
+        return 0;
+    };
+    read_dir
+        .flatten()
+        .map(|entry| match entry.path().symlink_metadata() {
+            Ok(meta) if meta.is_dir() => dir_size(&entry.path()),
+            Ok(meta) => meta.len(),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+#[cfg(all(
+    unix,
+    not(target_os = "macos"),
+    not(target_os = "ios"),
+    not(target_os = "android")
+))]
+mod platform {
+    use std::path::{Path, PathBuf};
+
+    use trash::TrashItem;
+    use trash::os_limited;
+
+    pub const SUPPORTED: bool = true;
+
+    pub fn list() -> Result<Vec<TrashItem>, String> {
+        os_limited::list().map_err(|e| format!("Failed to read trash: {}", e))
+    }
+
+    pub fn restore(item: TrashItem) -> Result<(), String> {
+        os_limited::restore_all([item]).map_err(|e| match e {
+            trash::Error::RestoreCollision { path, .. } => {
+                format!("'{}' already exists", path.display())
+            }
+            e => format!("Failed to restore: {}", e),
+        })
+    }
+
+    pub fn purge(item: TrashItem) -> Result<(), String> {
+        os_limited::purge_all([item]).map_err(|e| format!("Failed to purge: {}", e))
+    }
+
+    /// On freedesktop systems `id` is `<trash>/info/<name>.trashinfo`; the
+    /// contents live at `<trash>/files/<name>`.
+    pub fn trashed_path(item: &TrashItem) -> PathBuf {
+        let info = Path::new(&item.id);
+        match (info.parent().and_then(Path::parent), info.file_stem()) {
+            (Some(root), Some(stem)) => root.join("files").join(stem),
+            _ => PathBuf::from(&item.id),
+        }
+    }
+}
+
+#[cfg(not(all(
+    unix,
+    not(target_os = "macos"),
+    not(target_os = "ios"),
+    not(target_os = "android")
+)))]
+mod platform {
+    use std::path::PathBuf;
+
+    use trash::TrashItem;
+
+    pub const SUPPORTED: bool = false;
+
+    const UNSUPPORTED: &str = "Browsing the trash is not supported on this platform";
+
+    pub fn list() -> Result<Vec<TrashItem>, String> {
+        Err(UNSUPPORTED.to_string())
+    }
+
+    pub fn restore(_item: TrashItem) -> Result<(), String> {
+        Err(UNSUPPORTED.to_string())
+    }
+
+    pub fn purge(_item: TrashItem) -> Result<(), String> {
+        Err(UNSUPPORTED.to_string())
+    }
+
+    pub fn trashed_path(item: &TrashItem) -> PathBuf {
+        PathBuf::from(&item.id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::OsString;
+
+    fn fake_item(id: &str, name: &str, parent: &str, time_deleted: i64) -> trash::TrashItem {
+        trash::TrashItem {
+            id: OsString::from(id),
+            name: OsString::from(name),
+            original_parent: PathBuf::from(parent),
+            time_deleted,
+        }
+    }
+
+    #[test]
+    fn test_entry_from_item_missing_date() {
+        let entry = TrashEntry::from_item(fake_item(
+            "/nonexistent/info/a.txt.trashinfo",
+            "a.txt",
+            "/home/user",
+            -1,
+        ));
+        assert_eq!(entry.original_path, PathBuf::from("/home/user/a.txt"));
+        assert!(entry.deleted_at.is_none());
+        assert_eq!(entry.size, 0);
+        assert!(!entry.is_dir);
+    }
+
+    #[test]
+    fn test_entry_from_item_with_date() {
+        let entry = TrashEntry::from_item(fake_item(
+            "/nonexistent/info/b.trashinfo",
+            "b",
+            "/tmp",
+            1_700_000_000,
+        ));
+        assert_eq!(
+            entry.deleted_at,
+            Some(UNIX_EPOCH + Duration::from_secs(1_700_000_000))
+        );
+    }
+
+    #[cfg(all(
+        unix,
+        not(target_os = "macos"),
+        not(target_os = "ios"),
+        not(target_os = "android")
+    ))]
+    #[test]
+    fn test_trashed_path_freedesktop_layout() {
+        let item = fake_item(
+            "/home/user/.local/share/Trash/info/report.pdf.trashinfo",
+            "report.pdf",
+            "/home/user/docs",
+            0,
+        );
+        assert_eq!(
+            platform::trashed_path(&item),
+            PathBuf::from("/home/user/.local/share/Trash/files/report.pdf")
+        );
+    }
+
+    #[test]
+    fn test_dir_size_recursive() {
+        let temp = tempfile::TempDir::new().unwrap();
+        fs::write(temp.path().join("a"), vec![0u8; 10]).unwrap();
+        fs::create_dir(temp.path().join("sub")).unwrap();
+        fs::write(temp.path().join("sub/b"), vec![0u8; 5]).unwrap();
+        assert_eq!(dir_size(temp.path()), 15);
+    }
+}
@@ -1,11 +1,91 @@
 //! Undo log for file operations.
+//!
+//! The log can be backed by an on-disk journal so that operations performed
+//! in one session can still be undone in a later one. Because the filesystem
+//! may have changed in between, each entry snapshots the paths it touched and
+//! is verified against them before being undone.
+//!
+//! Undoing an entry moves its inverse onto a redo stack; redoing applies that
+//! inverse and moves the original back. Recording a new operation clears the
+//! redo stack, as in any editor.
+//!
+//! Several instances may share one journal. Saving takes a lock file, re-reads
+//! the journal and merges this instance's additions and removals into it, so
+//! entries recorded elsewhere in the meantime are kept.
 
-use std::collections::VecDeque;
-use std::path::PathBuf;
-use std::time::SystemTime;
+use std::collections::{HashSet, VecDeque};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
 
 use serde::{Deserialize, Serialize};
 
+use crate::copy::CopyOptions;
+use crate::link::LinkRecord;
+use crate::permissions::{ModeRecord, OwnerRecord, current_mode, current_owner};
+
+/// Version of the on-disk journal format.
+const JOURNAL_VERSION: u32 = 1;
+
+/// How long a save waits for another instance to release the journal lock.
+const LOCK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Lock files older than this are left over from a crashed instance.
+const STALE_LOCK_AGE: Duration = Duration::from_secs(30);
+
+/// Default location of the persistent undo journal.
+pub fn default_journal_path() -> Option<PathBuf> {
+    dirs::data_dir().map(|d| d.join("gravityfile").join("undo-journal.json"))
+}
+
+/// Recorded state of a path produced by an operation.
+///
+/// Used to detect whether the path was changed by something else before the
+/// operation is undone.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TargetSnapshot {
+    /// Path that was checked.
+    pub path: PathBuf,
+    /// Whether the path was a directory.
+    pub is_dir: bool,
+    /// File size in bytes (0 for directories).
+    pub size: u64,
+    /// Modification time of files (`None` for directories).
+    pub modified: Option<SystemTime>,
+}
+
+impl TargetSnapshot {
+    /// Capture the current state of `path` without following symlinks.
+    pub fn capture(path: &Path) -> Option<Self> {
+        let meta = fs::symlink_metadata(path).ok()?;
+        let is_dir = meta.is_dir();
+        Some(Self {
+            path: path.to_path_buf(),
+            is_dir,
+            // Directory sizes and mtimes change whenever their contents do.
+            size: if is_dir { 0 } else { meta.len() },
+            modified: if is_dir { None } else { meta.modified().ok() },
+        })
+    }
+
+    /// Check that the path is still in the recorded state.
+    pub fn check(&self) -> Result<(), String> {
+        let Some(current) = Self::capture(&self.path) else {
+            return Err(format!("'{}' no longer exists", self.path.display()));
+        };
+        if current.is_dir != self.is_dir {
+            return Err(format!("'{}' changed type", self.path.display()));
+        }
+        if current.size != self.size || current.modified != self.modified {
+            return Err(format!(
+                "'{}' was modified after the operation",
+                self.path.display()
+            ));
+        }
+        Ok(())
+    }
+}
+
 /// An entry in the undo log.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UndoEntry {
@@ -17,18 +97,98 @@ pub struct UndoEntry {
     pub operation: UndoableOperation,
     /// Human-readable description.
     pub description: String,
+    /// State of the operation's results when it was recorded.
+    #[serde(default)]
+    pub targets: Vec<TargetSnapshot>,
 }
 
 impl UndoEntry {
-    /// Create a new undo entry.
+    /// Create a new undo entry, snapshotting the paths it produced.
     pub fn new(id: u64, operation: UndoableOperation, description: impl Into<String>) -> Self {
+        let targets = operation
+            .targets()
+            .into_iter()
+            .filter_map(TargetSnapshot::capture)
+            .collect();
         Self {
             id,
             timestamp: SystemTime::now(),
             operation,
             description: description.into(),
+            targets,
         }
     }
+
+    /// Check that undoing this entry is still safe.
+    ///
+    /// Fails if the entry cannot be undone at all, if a path the undo would
+    /// write to is occupied again, or if a produced path was changed since
+    /// the operation was recorded.
+    pub fn verify(&self) -> Result<(), String> {
+        let occupied = |path: &PathBuf| -> Result<(), String> {
+            if fs::symlink_metadata(path).is_ok() {
+                Err(format!("'{}' exists again", path.display()))
+            } else {
+                Ok(())
+            }
+        };
+
+        match &self.operation {
+            UndoableOperation::FilesDeleted { .. } => {
+                return Err(self.operation.undo_description());
+            }
+            UndoableOperation::FilesMoved { moves } => {
+                moves
+                    .iter()
+                    .try_for_each(|(original, _)| occupied(original))?;
+            }
+            UndoableOperation::FilesTrashed { paths } => {
+                paths.iter().try_for_each(occupied)?;
+            }
+            UndoableOperation::CopiesRemoved { copies, .. } => {
+                copies.iter().try_for_each(|(_, copy)| occupied(copy))?;
+            }
+            UndoableOperation::CreationReverted { path, .. } => occupied(path)?,
+            UndoableOperation::FileRenamed { old_path, .. } => occupied(old_path)?,
+            UndoableOperation::DirectoryCreated { path } => {
+                // Undo removes the directory, so refuse once it has contents.
+                if fs::read_dir(path).is_ok_and(|mut d| d.next().is_some()) {
+                    return Err(format!("'{}' is no longer empty", path.display()));
+                }
+            }
+            UndoableOperation::PermissionsChanged { changes } => {
+                for change in changes {
+                    if current_mode(&change.path) != Some(change.after) {
+                        return Err(format!(
+                            "Mode of '{}' changed since the operation",
+                            change.path.display()
+                        ));
+                    }
+                }
+            }
+            UndoableOperation::OwnershipChanged { changes } => {
+                for change in changes {
+                    if current_owner(&change.path) != Some(change.after) {
+                        return Err(format!(
+                            "Owner of '{}' changed since the operation",
+                            change.path.display()
+                        ));
+                    }
+                }
+            }
+            UndoableOperation::LinksCreated { links } => {
+                links.iter().try_for_each(LinkRecord::check)?;
+            }
+            UndoableOperation::LinksRemoved { links } => {
+                links.iter().try_for_each(LinkRecord::check_removed)?;
+            }
+            UndoableOperation::FilesCopied { .. }
+            | UndoableOperation::FilesRestored { .. }
+            | UndoableOperation::FileCreated { .. } => {}
+        }
+
+        self.targets.iter().try_for_each(TargetSnapshot::check)
+    }
 }
 
 /// An operation that can be undone.
@@ -43,12 +203,38 @@ pub enum UndoableOperation {
     FilesCopied {
         /// List of created files/directories.
         created: Vec<PathBuf>,
+        /// Source of each created item, parallel to `created`.
+        ///
+        /// Needed to redo the copy after it was undone; empty if unknown.
+        #[serde(default)]
+        sources: Vec<PathBuf>,
+        /// Options the copy ran with, reused when redoing it.
+        #[serde(default)]
+        options: CopyOptions,
+    },
+    /// Copies were removed by undoing a copy (redo copies them again).
+    CopiesRemoved {
+        /// List of (source, removed_copy) pairs.
+        copies: Vec<(PathBuf, PathBuf)>,
+        /// Options of the original copy.
+        #[serde(default)]
+        options: CopyOptions,
     },
-    /// Files were moved to trash or permanently deleted.
+    /// Files were permanently deleted.
     FilesDeleted {
         /// List of paths that were deleted.
         paths: Vec<PathBuf>,
     },
+    /// Files were moved to the system trash.
+    FilesTrashed {
+        /// Original paths of the trashed items.
+        paths: Vec<PathBuf>,
+    },
+    /// Files were restored from the trash (redo trashes them again).
+    FilesRestored {
+        /// Paths the items were restored to.
+        paths: Vec<PathBuf>,
+    },
     /// A file or directory was renamed.
     FileRenamed {
         /// The full path with the new name (after rename).
@@ -69,32 +255,102 @@ pub enum UndoableOperation {
         /// Path to the created directory.
         path: PathBuf,
     },
+    /// A created file or directory was removed by undo (redo recreates it).
+    CreationReverted {
+        /// Path of the removed item.
+        path: PathBuf,
+        /// Whether the item was a directory.
+        is_dir: bool,
+    },
+    /// Permission bits were changed.
+    PermissionsChanged {
+        /// Previous and new mode of every changed path.
+        changes: Vec<ModeRecord>,
+    },
+    /// Owner or group was changed.
+    OwnershipChanged {
+        /// Previous and new owner of every changed path.
+        changes: Vec<OwnerRecord>,
+    },
+    /// Symlinks or hardlinks were created.
+    LinksCreated {
+        /// The created links.
+        links: Vec<LinkRecord>,
+    },
+    /// Created links were removed by undo (redo creates them again).
+    LinksRemoved {
+        /// The removed links.
+        links: Vec<LinkRecord>,
+    },
+}
+
+/// File name of `path` for use in descriptions.
+fn display_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default()
 }
 
 impl UndoableOperation {
+    /// Get a description of what this operation did.
+    pub fn summary(&self) -> String {
+        match self {
+            Self::FilesMoved { moves } => format!("Moved {} items", moves.len()),
+            Self::FilesCopied { created, .. } => format!("Copied {} items", created.len()),
+            Self::CopiesRemoved { copies, .. } => format!("Removed {} copies", copies.len()),
+            Self::FilesDeleted { paths } => format!("Deleted {} items", paths.len()),
+            Self::FilesTrashed { paths } => format!("Trashed {} items", paths.len()),
+            Self::FilesRestored { paths } => format!("Restored {} items", paths.len()),
+            Self::FileRenamed {
+                old_path, new_name, ..
+            } => format!("Renamed '{}' to '{}'", display_name(old_path), new_name),
+            Self::FileCreated { path } => format!("Created file '{}'", display_name(path)),
+            Self::DirectoryCreated { path } => {
+                format!("Created directory '{}'", display_name(path))
+            }
+            Self::CreationReverted { path, .. } => format!("Removed '{}'", display_name(path)),
+            Self::PermissionsChanged { changes } => {
+                format!("Changed permissions of {} items", changes.len())
+            }
+            Self::OwnershipChanged { changes } => {
+                format!("Changed owner of {} items", changes.len())
+            }
+            Self::LinksCreated { links } => format!("Created {} links", links.len()),
+            Self::LinksRemoved { links } => format!("Removed {} links", links.len()),
+        }
+    }
+
     /// Get a description of how to undo this operation.
     pub fn undo_description(&self) -> String {
         match self {
             Self::FilesMoved { moves } => {
                 format!("Move {} items back to original location", moves.len())
             }
-            Self::FilesCopied { created } => {
+            Self::FilesCopied { created, .. } => {
                 format!("Delete {} copied items", created.len())
             }
+            Self::CopiesRemoved { copies, .. } => format!("Copy {} items again", copies.len()),
             Self::FilesDeleted { paths } => {
-                format!("Deleted {} items (recover via OS trash)", paths.len())
+                format!("Deleted {} items (cannot be recovered)", paths.len())
+            }
+            Self::FilesTrashed { paths } => {
+                format!("Restore {} items from trash", paths.len())
             }
+            Self::FilesRestored { paths } => format!("Move {} items back to trash", paths.len()),
             Self::FileRenamed { old_path, .. } => {
-                format!(
-                    "Rename back to '{}'",
-                    old_path
-                        .file_name()
-                        .map(|n| n.to_string_lossy().into_owned())
-                        .unwrap_or_default()
-                )
+                format!("Rename back to '{}'", display_name(old_path))
             }
             Self::FileCreated { .. } => "Delete the created file".to_string(),
             Self::DirectoryCreated { .. } => "Delete the created directory".to_string(),
+            Self::CreationReverted { path, .. } => format!("Recreate '{}'", display_name(path)),
+            Self::PermissionsChanged { changes } => {
+                format!("Restore previous permissions of {} items", changes.len())
+            }
+            Self::OwnershipChanged { changes } => {
+                format!("Restore previous owner of {} items", changes.len())
+            }
+            Self::LinksCreated { links } => format!("Remove {} created links", links.len()),
+            Self::LinksRemoved { links } => format!("Create {} links again", links.len()),
         }
     }
 
@@ -102,14 +358,215 @@ impl UndoableOperation {
     pub fn can_undo(&self) -> bool {
         !matches!(self, Self::FilesDeleted { .. })
     }
+
+    /// Paths that undo will move, remove or read from.
+    pub fn targets(&self) -> Vec<&Path> {
+        match self {
+            Self::FilesMoved { moves } => moves.iter().map(|(_, to)| to.as_path()).collect(),
+            Self::FilesCopied { created, .. } => created.iter().map(PathBuf::as_path).collect(),
+            Self::CopiesRemoved { copies, .. } => {
+                copies.iter().map(|(from, _)| from.as_path()).collect()
+            }
+            Self::FilesRestored { paths } => paths.iter().map(PathBuf::as_path).collect(),
+            Self::FileRenamed { path, .. }
+            | Self::FileCreated { path }
+            | Self::DirectoryCreated { path } => vec![path.as_path()],
+            // Attribute changes are verified against their recorded modes
+            // and owners instead, which also keeps large recursive changes
+            // out of the snapshot list. Links are checked against their
+            // targets, since a hardlink's size and mtime follow the original.
+            Self::FilesDeleted { .. }
+            | Self::FilesTrashed { .. }
+            | Self::CreationReverted { .. }
+            | Self::PermissionsChanged { .. }
+            | Self::OwnershipChanged { .. }
+            | Self::LinksCreated { .. }
+            | Self::LinksRemoved { .. } => Vec::new(),
+        }
+    }
+
+    /// The operation that reverses this one once it has been undone.
+    ///
+    /// Returns `None` when undoing cannot be reversed, e.g. for copies
+    /// recorded without their sources.
+    pub fn inverse(&self) -> Option<Self> {
+        let inverse = match self {
+            Self::FilesMoved { moves } => Self::FilesMoved {
+                moves: moves.iter().map(|(a, b)| (b.clone(), a.clone())).collect(),
+            },
+            Self::FilesCopied {
+                created,
+                sources,
+                options,
+            } => {
+                if sources.len() != created.len() {
+                    return None;
+                }
+                Self::CopiesRemoved {
+                    copies: sources
+                        .iter()
+                        .cloned()
+                        .zip(created.iter().cloned())
+                        .collect(),
+                    options: options.clone(),
+                }
+            }
+            Self::CopiesRemoved { copies, options } => Self::FilesCopied {
+                created: copies.iter().map(|(_, to)| to.clone()).collect(),
+                sources: copies.iter().map(|(from, _)| from.clone()).collect(),
+                options: options.clone(),
+            },
+            Self::FilesDeleted { .. } => return None,
+            Self::FilesTrashed { paths } => Self::FilesRestored {
+                paths: paths.clone(),
+            },
+            Self::FilesRestored { paths } => Self::FilesTrashed {
+                paths: paths.clone(),
+            },
+            Self::FileRenamed { path, old_path, .. } => Self::FileRenamed {
+                path: old_path.clone(),
+                old_path: path.clone(),
+                new_name: display_name(old_path),
+            },
+            Self::FileCreated { path } => Self::CreationReverted {
+                path: path.clone(),
+                is_dir: false,
+            },
+            Self::DirectoryCreated { path } => Self::CreationReverted {
+                path: path.clone(),
+                is_dir: true,
+            },
+            Self::CreationReverted {
+                path,
+                is_dir: false,
+            } => Self::FileCreated { path: path.clone() },
+            Self::CreationReverted { path, is_dir: true } => {
+                Self::DirectoryCreated { path: path.clone() }
+            }
+            Self::PermissionsChanged { changes } => Self::PermissionsChanged {
+                changes: changes
+                    .iter()
+                    .map(|c| ModeRecord {
+                        path: c.path.clone(),
+                        before: c.after,
+                        after: c.before,
+                    })
+                    .collect(),
+            },
+            Self::OwnershipChanged { changes } => Self::OwnershipChanged {
+                changes: changes
+                    .iter()
+                    .map(|c| OwnerRecord {
+                        path: c.path.clone(),
+                        before: c.after,
+                        after: c.before,
+                    })
+                    .collect(),
+            },
+            Self::LinksCreated { links } => Self::LinksRemoved {
+                links: links.clone(),
+            },
+            Self::LinksRemoved { links } => Self::LinksCreated {
+                links: links.clone(),
+            },
+        };
+        Some(inverse)
+    }
+}
+
+/// On-disk representation of the undo journal.
+#[derive(Serialize, Deserialize)]
+struct Journal {
+    version: u32,
+    next_id: u64,
+    entries: Vec<UndoEntry>,
+    #[serde(default)]
+    redo: Vec<UndoEntry>,
+}
+
+impl Journal {
+    /// Read the journal at `path`, or `None` if there is none yet.
+    ///
+    /// A journal that cannot be read is reported as an error rather than
+    /// treated as empty, since saving would overwrite it.
+    fn read(path: &Path) -> Result<Option<Self>, String> {
+        let bytes = match fs::read(path) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(format!("Failed to read undo journal: {}", e)),
+        };
+        let journal: Journal =
+            serde_json::from_slice(&bytes).map_err(|e| format!("Invalid undo journal: {}", e))?;
+        if journal.version > JOURNAL_VERSION {
+            return Err(format!(
+                "Undo journal version {} is newer than supported",
+                journal.version
+            ));
+        }
+        Ok(Some(journal))
+    }
+}
+
+/// Exclusive lock on a journal, held by creating a `.lock` sibling.
+struct JournalLock(PathBuf);
+
+impl JournalLock {
+    /// Take the lock, waiting for other instances to release it. A lock left
+    /// behind by a crashed instance is broken once it is stale.
+    fn acquire(journal: &Path) -> Result<Self, String> {
+        let path = journal.with_extension("json.lock");
+        let started = SystemTime::now();
+        loop {
+            match fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&path)
+            {
+                Ok(_) => return Ok(Self(path)),
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {}
+                Err(e) => return Err(format!("Failed to lock undo journal: {}", e)),
+            }
+
+            let age = fs::metadata(&path)
+                .and_then(|m| m.modified())
+                .ok()
+                .and_then(|t| t.elapsed().ok());
+            if age.is_some_and(|age| age > STALE_LOCK_AGE) {
+                let _ = fs::remove_file(&path);
+                continue;
+            }
+            if started.elapsed().unwrap_or_default() > LOCK_TIMEOUT {
+                return Err(format!(
+                    "Undo journal is locked by another instance ({})",
+                    path.display()
+                ));
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        }
+    }
+}
+
+impl Drop for JournalLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
 }
 
 /// Undo log with configurable maximum depth.
 #[derive(Debug)]
 pub struct UndoLog {
     entries: VecDeque<UndoEntry>,
+    /// Inverses of undone entries, most recent last.
+    redo: VecDeque<UndoEntry>,
     max_entries: usize,
     next_id: u64,
+    /// Journal file the log is persisted to, if any.
+    journal: Option<PathBuf>,
+    /// `next_id` when the journal was last read or written. Entries with
+    /// lower IDs are shared with the journal; higher ones were added here.
+    synced_id: u64,
+    /// IDs of shared entries removed here since the journal was last synced.
+    removed: HashSet<u64>,
 }
 
 impl Default for UndoLog {
@@ -123,15 +580,133 @@ impl UndoLog {
     pub fn new(max_entries: usize) -> Self {
         Self {
             entries: VecDeque::with_capacity(max_entries.min(1000)),
+            redo: VecDeque::new(),
             max_entries,
             next_id: 0,
+            journal: None,
+            synced_id: 0,
+            removed: HashSet::new(),
         }
     }
 
+    /// Open a log backed by the journal at `path`.
+    ///
+    /// A missing journal yields an empty log. A journal that cannot be read
+    /// is reported as an error rather than silently discarded, since saving
+    /// would overwrite it.
+    pub fn open(path: impl Into<PathBuf>, max_entries: usize) -> Result<Self, String> {
+        let path = path.into();
+        let mut log = Self::new(max_entries);
+
+        if let Some(journal) = Journal::read(&path)? {
+            let skip = journal.entries.len().saturating_sub(max_entries);
+            log.entries.extend(journal.entries.into_iter().skip(skip));
+            let skip = journal.redo.len().saturating_sub(max_entries);
+            log.redo.extend(journal.redo.into_iter().skip(skip));
+            log.next_id = journal.next_id;
+            log.synced_id = journal.next_id;
+        }
+
+        log.journal = Some(path);
+        Ok(log)
+    }
+
+    /// Path of the backing journal, if any.
+    pub fn journal_path(&self) -> Option<&Path> {
+        self.journal.as_deref()
+    }
+
+    /// Write the log to its journal. Does nothing for in-memory logs.
+    ///
+    /// Under the journal's lock file, the journal is re-read and merged with
+    /// this log: entries another instance recorded since the last sync are
+    /// kept (this log's new entries are renumbered after them) and entries
+    /// removed here are dropped. The log is updated to the merged state.
+    ///
+    /// The journal is written to a temporary sibling and renamed into place
+    /// so a crash never leaves a truncated file behind.
+    pub fn save(&mut self) -> Result<(), String> {
+        let Some(path) = self.journal.clone() else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create journal directory: {}", e))?;
+        }
+
+        let _lock = JournalLock::acquire(&path)?;
+        if let Some(disk) = Journal::read(&path)? {
+            self.merge(disk);
+        }
+
+        let journal = Journal {
+            version: JOURNAL_VERSION,
+            next_id: self.next_id,
+            entries: self.entries.iter().cloned().collect(),
+            redo: self.redo.iter().cloned().collect(),
+        };
+        let json = serde_json::to_vec_pretty(&journal)
+            .map_err(|e| format!("Failed to serialize undo journal: {}", e))?;
+
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, json).map_err(|e| format!("Failed to write undo journal: {}", e))?;
+        fs::rename(&tmp, &path).map_err(|e| format!("Failed to write undo journal: {}", e))?;
+
+        self.synced_id = self.next_id;
+        self.removed.clear();
+        Ok(())
+    }
+
+    /// Merge the journal as currently on disk into this log.
+    fn merge(&mut self, disk: Journal) {
+        // IDs handed out elsewhere since the last sync; ours move up past them.
+        let offset = disk.next_id.saturating_sub(self.synced_id);
+        let synced_id = self.synced_id;
+        // Only shared IDs can have been removed here; higher ones in the
+        // journal belong to entries recorded elsewhere.
+        let removed: HashSet<u64> = std::mem::take(&mut self.removed)
+            .into_iter()
+            .filter(|&id| id < synced_id)
+            .collect();
+        let merge_stack = |local: &mut VecDeque<UndoEntry>, shared: Vec<UndoEntry>| {
+            // Shared entries missing from the journal were undone or redone
+            // by another instance.
+            let on_disk: HashSet<u64> = shared.iter().map(|e| e.id).collect();
+            local.retain(|e| e.id >= synced_id || on_disk.contains(&e.id));
+            for entry in local.iter_mut().filter(|e| e.id >= synced_id) {
+                entry.id += offset;
+            }
+            let known: HashSet<u64> = local.iter().map(|e| e.id).collect();
+            local.extend(
+                shared
+                    .into_iter()
+                    .filter(|e| !removed.contains(&e.id) && !known.contains(&e.id)),
+            );
+            local.make_contiguous().sort_by_key(|e| e.id);
+        };
+        merge_stack(&mut self.entries, disk.entries);
+        merge_stack(&mut self.redo, disk.redo);
+
+        for stack in [&mut self.entries, &mut self.redo] {
+            while stack.len() > self.max_entries {
+                stack.pop_front();
+            }
+        }
+        self.next_id += offset;
+    }
+
     /// Record an operation in the undo log.
     ///
+    /// A new operation invalidates everything on the redo stack.
     /// Returns the ID assigned to this entry.
     pub fn record(&mut self, operation: UndoableOperation, description: impl Into<String>) -> u64 {
+        let cleared: Vec<u64> = self.redo.drain(..).map(|e| e.id).collect();
+        self.removed.extend(cleared);
+        self.push_undo(operation, description.into())
+    }
+
+    /// Push an entry onto the undo stack without touching the redo stack.
+    fn push_undo(&mut self, operation: UndoableOperation, description: String) -> u64 {
         let id = self.next_id;
         self.next_id += 1;
 
@@ -146,31 +721,64 @@ impl UndoLog {
         id
     }
 
+    /// Record that `entry` was undone, making its inverse available to redo.
+    ///
+    /// Call this once the undo has completed so the redo entry snapshots the
+    /// restored state. Returns the new redo entry's ID, or `None` if the
+    /// undo cannot be reversed.
+    pub fn record_undone(&mut self, entry: &UndoEntry) -> Option<u64> {
+        let inverse = entry.operation.inverse()?;
+        let id = self.next_id;
+        self.next_id += 1;
+
+        if self.redo.len() >= self.max_entries {
+            self.redo.pop_front();
+        }
+        self.redo
+            .push_back(UndoEntry::new(id, inverse, entry.description.clone()));
+        Some(id)
+    }
+
+    /// Record that the redo `entry` was applied, making it undoable again.
+    ///
+    /// Unlike [`record`](Self::record) this keeps the rest of the redo stack.
+    pub fn record_redone(&mut self, entry: &UndoEntry) -> Option<u64> {
+        let inverse = entry.operation.inverse()?;
+        Some(self.push_undo(inverse, entry.description.clone()))
+    }
+
+    /// Record an operation with its standard description.
+    pub fn record_operation(&mut self, operation: UndoableOperation) -> u64 {
+        let description = operation.summary();
+        self.record(operation, description)
+    }
+
     /// Record a move operation.
     pub fn record_move(&mut self, moves: Vec<(PathBuf, PathBuf)>) -> u64 {
-        let count = moves.len();
-        self.record(
-            UndoableOperation::FilesMoved { moves },
-            format!("Moved {} items", count),
-        )
+        self.record_operation(UndoableOperation::FilesMoved { moves })
     }
 
     /// Record a copy operation.
+    ///
+    /// Copies recorded this way cannot be redone after an undo since their
+    /// sources are unknown; record a [`UndoableOperation::FilesCopied`] with
+    /// `sources` through [`record_operation`](Self::record_operation) instead.
     pub fn record_copy(&mut self, created: Vec<PathBuf>) -> u64 {
-        let count = created.len();
-        self.record(
-            UndoableOperation::FilesCopied { created },
-            format!("Copied {} items", count),
-        )
+        self.record_operation(UndoableOperation::FilesCopied {
+            created,
+            sources: Vec::new(),
+            options: CopyOptions::default(),
+        })
     }
 
     /// Record a delete operation.
     pub fn record_delete(&mut self, paths: Vec<PathBuf>) -> u64 {
-        let count = paths.len();
-        self.record(
-            UndoableOperation::FilesDeleted { paths },
-            format!("Deleted {} items", count),
-        )
+        self.record_operation(UndoableOperation::FilesDeleted { paths })
+    }
+
+    /// Record a move-to-trash operation.
+    pub fn record_trash(&mut self, paths: Vec<PathBuf>) -> u64 {
+        self.record_operation(UndoableOperation::FilesTrashed { paths })
     }
 
     /// Record a rename operation.
@@ -180,43 +788,21 @@ impl UndoLog {
     pub fn record_rename(&mut self, source: PathBuf, new_name: String) -> u64 {
         let parent = source.parent().unwrap_or(std::path::Path::new(""));
         let new_path = parent.join(&new_name);
-        let old_name = source
-            .file_name()
-            .map(|n| n.to_string_lossy().into_owned())
-            .unwrap_or_default();
-        let description = format!("Renamed '{}' to '{}'", old_name, new_name);
-        self.record(
-            UndoableOperation::FileRenamed {
-                path: new_path,
-                old_path: source,
-                new_name,
-            },
-            description,
-        )
+        self.record_operation(UndoableOperation::FileRenamed {
+            path: new_path,
+            old_path: source,
+            new_name,
+        })
     }
 
     /// Record a file creation.
     pub fn record_create_file(&mut self, path: PathBuf) -> u64 {
-        let name = path
-            .file_name()
-            .map(|n| n.to_string_lossy().to_string())
-            .unwrap_or_default();
-        self.record(
-            UndoableOperation::FileCreated { path },
-            format!("Created file '{}'", name),
-        )
+        self.record_operation(UndoableOperation::FileCreated { path })
     }
 
     /// Record a directory creation.
     pub fn record_create_directory(&mut self, path: PathBuf) -> u64 {
-        let name = path
-            .file_name()
-            .map(|n| n.to_string_lossy().to_string())
-            .unwrap_or_default();
-        self.record(
-            UndoableOperation::DirectoryCreated { path },
-            format!("Created directory '{}'", name),
-        )
+        self.record_operation(UndoableOperation::DirectoryCreated { path })
     }
 
     /// Pop the most recent undoable entry.
@@ -226,12 +812,54 @@ impl UndoLog {
     pub fn pop(&mut self) -> Option<UndoEntry> {
         // HIGH-4: only look at the back entry; don't consume non-undoable ones
         if self.entries.back()?.operation.can_undo() {
-            self.entries.pop_back()
+            let entry = self.entries.pop_back()?;
+            self.removed.insert(entry.id);
+            Some(entry)
+        } else {
+            None
+        }
+    }
+
+    /// Remove the entry with the given ID, wherever it sits in the log.
+    ///
+    /// Returns `None` if there is no such entry or it cannot be undone.
+    pub fn take(&mut self, id: u64) -> Option<UndoEntry> {
+        let index = self.entries.iter().position(|e| e.id == id)?;
+        if self.entries[index].operation.can_undo() {
+            self.removed.insert(id);
+            self.entries.remove(index)
         } else {
             None
         }
     }
 
+    /// Get the entry with the given ID.
+    pub fn get(&self, id: u64) -> Option<&UndoEntry> {
+        self.entries.iter().find(|e| e.id == id)
+    }
+
+    /// Peek at the most recent redo entry without removing it.
+    pub fn peek_redo(&self) -> Option<&UndoEntry> {
+        self.redo.back()
+    }
+
+    /// Get the redo entry with the given ID.
+    pub fn get_redo(&self, id: u64) -> Option<&UndoEntry> {
+        self.redo.iter().find(|e| e.id == id)
+    }
+
+    /// Remove the redo entry with the given ID.
+    pub fn take_redo(&mut self, id: u64) -> Option<UndoEntry> {
+        let index = self.redo.iter().position(|e| e.id == id)?;
+        self.removed.insert(id);
+        self.redo.remove(index)
+    }
+
+    /// Get an iterator over the redo stack (oldest first).
+    pub fn redo_iter(&self) -> impl DoubleEndedIterator<Item = &UndoEntry> {
+        self.redo.iter()
+    }
+
     /// Peek at the most recent entry without removing it.
     pub fn peek(&self) -> Option<&UndoEntry> {
         self.entries.back()
@@ -247,13 +875,19 @@ impl UndoLog {
         self.entries.is_empty()
     }
 
-    /// Clear all entries from the log.
+    /// Clear all entries from the log, including the redo stack.
     pub fn clear(&mut self) {
-        self.entries.clear();
+        let ids: Vec<u64> = self
+            .entries
+            .drain(..)
+            .chain(self.redo.drain(..))
+            .map(|e| e.id)
+            .collect();
+        self.removed.extend(ids);
     }
 
     /// Get an iterator over all entries (oldest first).
-    pub fn iter(&self) -> impl Iterator<Item = &UndoEntry> {
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &UndoEntry> {
         self.entries.iter()
     }
 }
@@ -326,4 +960,227 @@ mod tests {
         // The undoable create entry must still be present
         assert_eq!(log.len(), 2);
     }
+
+    #[test]
+    fn test_trash_is_undoable() {
+        let mut log = UndoLog::new(10);
+        log.record_trash(vec![PathBuf::from("/test/a.txt")]);
+
+        let entry = log.pop().unwrap();
+        assert!(matches!(
+            entry.operation,
+            UndoableOperation::FilesTrashed { .. }
+        ));
+    }
+
+    #[test]
+    fn test_take_by_id() {
+        let mut log = UndoLog::new(10);
+        let first = log.record_create_file(PathBuf::from("/test/a.txt"));
+        log.record_create_file(PathBuf::from("/test/b.txt"));
+
+        let entry = log.take(first).unwrap();
+        assert_eq!(entry.id, first);
+        assert_eq!(log.len(), 1);
+        assert!(log.take(first).is_none());
+    }
+
+    #[test]
+    fn test_journal_roundtrip() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let journal = temp.path().join("state/undo.json");
+
+        let mut log = UndoLog::open(&journal, 10).unwrap();
+        assert!(log.is_empty());
+        log.record_move(vec![(PathBuf::from("/a"), PathBuf::from("/b"))]);
+        log.record_create_directory(PathBuf::from("/c"));
+        log.save().unwrap();
+
+        let mut reopened = UndoLog::open(&journal, 10).unwrap();
+        assert_eq!(reopened.len(), 2);
+        assert_eq!(reopened.record_create_file(PathBuf::from("/d")), 2);
+        assert_eq!(reopened.iter().next().unwrap().description, "Moved 1 items");
+    }
+
+    #[test]
+    fn test_journal_rejects_garbage() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let journal = temp.path().join("undo.json");
+        fs::write(&journal, "not json").unwrap();
+        assert!(UndoLog::open(&journal, 10).is_err());
+    }
+
+    #[test]
+    fn test_verify_detects_modification() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let file = temp.path().join("new.txt");
+        fs::write(&file, "hello").unwrap();
+
+        let mut log = UndoLog::new(10);
+        log.record_create_file(file.clone());
+        assert!(log.peek().unwrap().verify().is_ok());
+
+        fs::write(&file, "hello, world").unwrap();
+        assert!(log.peek().unwrap().verify().is_err());
+    }
+
+    #[test]
+    fn test_verify_move_destination_occupied() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let original = temp.path().join("a.txt");
+        let moved = temp.path().join("b.txt");
+        fs::write(&moved, "data").unwrap();
+
+        let mut log = UndoLog::new(10);
+        log.record_move(vec![(original.clone(), moved)]);
+        assert!(log.peek().unwrap().verify().is_ok());
+
+        fs::write(&original, "new").unwrap();
+        let err = log.peek().unwrap().verify().unwrap_err();
+        assert!(err.contains("exists again"));
+    }
+
+    #[test]
+    fn test_verify_created_directory_not_empty() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let dir = temp.path().join("dir");
+        fs::create_dir(&dir).unwrap();
+
+        let mut log = UndoLog::new(10);
+        log.record_create_directory(dir.clone());
+        assert!(log.peek().unwrap().verify().is_ok());
+
+        fs::write(dir.join("file"), "x").unwrap();
+        assert!(log.peek().unwrap().verify().is_err());
+    }
+
+    #[test]
+    fn test_redo_stack_lifecycle() {
+        let mut log = UndoLog::new(10);
+        log.record_move(vec![(PathBuf::from("/a"), PathBuf::from("/b"))]);
+
+        let entry = log.pop().unwrap();
+        log.record_undone(&entry).unwrap();
+        let redo = log.peek_redo().unwrap();
+        assert_eq!(redo.description, "Moved 1 items");
+        assert!(matches!(
+            &redo.operation,
+            UndoableOperation::FilesMoved { moves } if moves[0] == (PathBuf::from("/b"), PathBuf::from("/a"))
+        ));
+
+        // Redoing puts the original operation back without clearing the stack.
+        let redo = log.take_redo(redo.id).unwrap();
+        log.record_undone(&entry).unwrap();
+        log.record_redone(&redo).unwrap();
+        assert_eq!(log.len(), 1);
+        assert!(log.peek_redo().is_some());
+
+        // A new operation invalidates the redo stack.
+        log.record_create_file(PathBuf::from("/c"));
+        assert!(log.peek_redo().is_none());
+    }
+
+    #[test]
+    fn test_inverse_roundtrip() {
+        let ops = vec![
+            UndoableOperation::FilesCopied {
+                created: vec![PathBuf::from("/dst/a")],
+                sources: vec![PathBuf::from("/src/a")],
+                options: CopyOptions::archive(),
+            },
+            UndoableOperation::FilesTrashed {
+                paths: vec![PathBuf::from("/t")],
+            },
+            UndoableOperation::FileCreated {
+                path: PathBuf::from("/f"),
+            },
+            UndoableOperation::DirectoryCreated {
+                path: PathBuf::from("/d"),
+            },
+        ];
+        for op in ops {
+            let back = op.inverse().unwrap().inverse().unwrap();
+            assert_eq!(
+                serde_json::to_string(&back).unwrap(),
+                serde_json::to_string(&op).unwrap()
+            );
+        }
+
+        let renamed = UndoableOperation::FileRenamed {
+            path: PathBuf::from("/dir/new.txt"),
+            old_path: PathBuf::from("/dir/old.txt"),
+            new_name: "new.txt".to_string(),
+        };
+        match renamed.inverse().unwrap() {
+            UndoableOperation::FileRenamed { path, new_name, .. } => {
+                assert_eq!(path, PathBuf::from("/dir/old.txt"));
+                assert_eq!(new_name, "old.txt");
+            }
+            other => panic!("unexpected inverse: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_no_inverse_without_sources() {
+        let mut log = UndoLog::new(10);
+        log.record_copy(vec![PathBuf::from("/dst/a")]);
+        let entry = log.pop().unwrap();
+        assert!(log.record_undone(&entry).is_none());
+        assert!(
+            UndoableOperation::FilesDeleted { paths: vec![] }
+                .inverse()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_journal_persists_redo() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let journal = temp.path().join("undo.json");
+
+        let mut log = UndoLog::open(&journal, 10).unwrap();
+        log.record_create_directory(PathBuf::from("/x"));
+        let entry = log.pop().unwrap();
+        log.record_undone(&entry);
+        log.save().unwrap();
+
+        let reopened = UndoLog::open(&journal, 10).unwrap();
+        assert!(reopened.is_empty());
+        assert_eq!(reopened.redo_iter().count(), 1);
+    }
+
+    #[test]
+    fn test_concurrent_saves_merge() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let journal = temp.path().join("undo.json");
+        let mut seed = UndoLog::open(&journal, 10).unwrap();
+        seed.record_create_file(PathBuf::from("/shared"));
+        seed.save().unwrap();
+
+        let mut a = UndoLog::open(&journal, 10).unwrap();
+        let mut b = UndoLog::open(&journal, 10).unwrap();
+        a.record_create_file(PathBuf::from("/a"));
+        a.save().unwrap();
+        b.record_create_file(PathBuf::from("/b"));
+        let shared = b.take(0).unwrap();
+        assert_eq!(shared.description, "Created file 'shared'");
+        b.save().unwrap();
+
+        // B's save kept A's entry, dropped the one B undid and renumbered
+        // B's own entry after A's.
+        let descriptions: Vec<_> = b.iter().map(|e| (e.id, e.description.as_str())).collect();
+        assert_eq!(
+            descriptions,
+            [(1, "Created file 'a'"), (2, "Created file 'b'")]
+        );
+        let reopened = UndoLog::open(&journal, 10).unwrap();
+        assert_eq!(reopened.len(), 2);
+        assert!(!journal.with_extension("json.lock").exists());
+
+        // A saving again neither resurrects the removed entry nor loses B's.
+        a.save().unwrap();
+        assert_eq!(a.len(), 2);
+        assert!(a.get(0).is_none());
+        assert_eq!(a.record_create_file(PathBuf::from("/c")), 3);
+    }
 }
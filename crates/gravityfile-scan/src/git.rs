@@ -170,8 +170,11 @@ fn apply_status_recursive(
     current_path: &Path,
     cache: &GitStatusCache,
 ) {
-    // Apply status to this node
-    node.git_status = cache.get_status(current_path);
+    // Apply status to this node. Entries the scanner marked as ignored via
+    // `.ignore`/`.gravityignore` keep that mark when git has no opinion.
+    node.git_status = cache.get_status(current_path).or(node
+        .git_status
+        .filter(|s| *s == gravityfile_core::GitStatus::Ignored));
 
     // Recursively apply to children
     for child in &mut node.children {
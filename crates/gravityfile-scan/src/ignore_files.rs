@@ -0,0 +1,164 @@
+//! Hierarchical ignore-file matching.
+//!
+//! Each directory may carry `.gitignore`, `.ignore` and `.gravityignore`
+//! files. Their rules apply to the directory and everything below it, with
+//! deeper files taking precedence and later files in the same directory
+//! overriding earlier ones — the same layering git uses. Patterns keep full
+//! gitignore semantics (anchoring, `dir/` suffixes, `**`, `!` negation), and
+//! an entry inside an ignored directory stays ignored, since git cannot
+//! re-include files whose parent is excluded.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use ignore::Match;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+
+/// Ignore files read from every directory, lowest precedence first.
+pub const IGNORE_FILE_NAMES: &[&str] = &[".gitignore", ".ignore", ".gravityignore"];
+
+/// Matchers in effect for one directory, carried through jwalk as read-dir state.
+///
+/// Cloning is cheap: each level's matcher is shared.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct IgnoreStack {
+    /// Matchers from the outermost directory down to the current one.
+    matchers: Vec<Arc<Gitignore>>,
+    /// The directory being read is itself ignored (or inside an ignored one).
+    parent_ignored: bool,
+    /// Subdirectories of the directory being read that were found ignored.
+    ignored_dirs: Arc<Vec<PathBuf>>,
+}
+
+impl IgnoreStack {
+    /// Build the initial stack for a scan root.
+    ///
+    /// Ignore files in ancestors of `root` are included up to the enclosing
+    /// repository (the nearest directory holding `.git`), so scanning a
+    /// subdirectory of a checkout honours the checkout's top-level rules.
+    pub(crate) fn for_root(root: &Path) -> Self {
+        let mut ancestors = Vec::new();
+        for dir in root.ancestors().skip(1) {
+            ancestors.push(dir);
+            if dir.join(".git").exists() {
+                break;
+            }
+        }
+        // No repository above the root: only the scan tree's own files count.
+        if !ancestors.last().is_some_and(|d| d.join(".git").exists()) {
+            ancestors.clear();
+        }
+
+        let mut stack = Self::default();
+        for dir in ancestors.into_iter().rev() {
+            stack.push_dir(dir);
+        }
+        stack
+    }
+
+    /// Add the ignore files found in `dir`, if any.
+    pub(crate) fn push_dir(&mut self, dir: &Path) {
+        let mut builder = GitignoreBuilder::new(dir);
+        let mut found = false;
+        for name in IGNORE_FILE_NAMES {
+            let file = dir.join(name);
+            if file.is_file() {
+                // A partially invalid file still contributes its valid lines.
+                let _ = builder.add(file);
+                found = true;
+            }
+        }
+        if !found {
+            return;
+        }
+        if let Ok(gitignore) = builder.build()
+            && !gitignore.is_empty()
+        {
+            self.matchers.push(Arc::new(gitignore));
+        }
+    }
+
+    /// Whether `path` is ignored by the rules in effect.
+    ///
+    /// The deepest matcher with an opinion wins; within a matcher the last
+    /// matching pattern wins, so a `!` negation can re-include a path.
+    pub(crate) fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        for matcher in self.matchers.iter().rev() {
+            match matcher.matched(path, is_dir) {
+                Match::Ignore(_) => return true,
+                Match::Whitelist(_) => return false,
+                Match::None => {}
+            }
+        }
+        false
+    }
+
+    /// Enter the directory `dir`, loading its ignore files.
+    ///
+    /// Returns whether `dir` itself is ignored. Ignore files inside an
+    /// ignored directory are not read, matching git.
+    pub(crate) fn enter(&mut self, dir: &Path) -> bool {
+        let ignored = self.parent_ignored || self.ignored_dirs.iter().any(|d| d == dir);
+        if !ignored {
+            self.push_dir(dir);
+        }
+        self.parent_ignored = ignored;
+        ignored
+    }
+
+    /// Remember which children of the current directory are ignored
+    /// directories, so their own reads start out ignored.
+    pub(crate) fn set_ignored_dirs(&mut self, dirs: Vec<PathBuf>) {
+        self.ignored_dirs = Arc::new(dirs);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_nested_rules_and_negation() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        fs::create_dir_all(root.join("build/out")).unwrap();
+        fs::create_dir_all(root.join("src/gen")).unwrap();
+        fs::write(root.join(".gitignore"), "*.log\n!keep.log\n/build/out/\n").unwrap();
+        fs::write(root.join("src/.ignore"), "gen/\n").unwrap();
+
+        let mut stack = IgnoreStack::default();
+        assert!(!stack.enter(root));
+        assert!(stack.is_ignored(&root.join("debug.log"), false));
+        assert!(!stack.is_ignored(&root.join("keep.log"), false));
+        // Anchored to the directory holding the .gitignore.
+        assert!(stack.is_ignored(&root.join("build/out"), true));
+        assert!(!stack.is_ignored(&root.join("src/build/out"), true));
+
+        let mut src = stack.clone();
+        assert!(!src.enter(&root.join("src")));
+        assert!(src.is_ignored(&root.join("src/gen"), true));
+        assert!(src.is_ignored(&root.join("src/trace.log"), false));
+        // The nested file does not leak back up to the parent.
+        assert!(!stack.is_ignored(&root.join("gen"), true));
+    }
+
+    #[test]
+    fn test_ignored_directory_propagates() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        fs::create_dir_all(root.join("target/debug")).unwrap();
+        fs::write(root.join(".gitignore"), "target/\n").unwrap();
+        fs::write(root.join("target/.gitignore"), "!*\n").unwrap();
+
+        let mut stack = IgnoreStack::default();
+        stack.enter(root);
+        stack.set_ignored_dirs(vec![root.join("target")]);
+
+        let mut target = stack.clone();
+        assert!(target.enter(&root.join("target")));
+        let mut debug = target.clone();
+        assert!(debug.enter(&root.join("target/debug")));
+    }
+}
@@ -11,6 +11,9 @@
 //! - **Parallel traversal** via jwalk/rayon
 //! - **Progress updates** via broadcast channels
 //! - **Hardlink detection** to avoid double-counting
+//! - **Multi-root scans** combined under one synthetic top node
+//! - **Mount table** with free space and inode usage per filesystem
+//! - **Ignore files** with hierarchical `.gitignore`/`.ignore`/`.gravityignore` semantics
 //! - **Configurable** depth limits, ignore patterns, etc.
 //!
 //! # Example
@@ -45,17 +48,21 @@
 //! ```
 
 mod git;
+mod ignore_files;
 mod inode;
+mod mounts;
 mod progress;
 mod scanner;
 
 pub use git::{GitStatusCache, apply_git_status};
+pub use ignore_files::IGNORE_FILE_NAMES;
 pub use inode::InodeTracker;
+pub use mounts::{Mount, MountTable};
 pub use progress::ScanProgress;
-pub use scanner::{JwalkScanner, quick_list};
+pub use scanner::{JwalkScanner, common_ancestor, quick_list};
 
 // Re-export core types for convenience
 pub use gravityfile_core::{
-    FileNode, FileTree, NodeId, NodeKind, ScanConfig, ScanError, ScanWarning, Timestamps,
-    TreeStats, WarningKind,
+    FileNode, FileTree, IgnoreMode, NodeId, NodeKind, ScanConfig, ScanError, ScanWarning,
+    Timestamps, TreeStats, WarningKind,
 };
@@ -0,0 +1,320 @@
+//! Mounted filesystems and their free space.
+//!
+//! On Linux the mount table is read from `/proc/self/mountinfo` and each
+//! mount is queried with `statvfs` for block and inode usage. Other platforms
+//! report an empty table.
+
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use gravityfile_core::FileTree;
+
+/// Filesystem types that do not store files on a device (proc, cgroups,
+/// tracing, ...). They are hidden from the default mount listing.
+const PSEUDO_FILESYSTEMS: &[&str] = &[
+    "autofs",
+    "binfmt_misc",
+    "bpf",
+    "cgroup",
+    "cgroup2",
+    "configfs",
+    "debugfs",
+    "devpts",
+    "efivarfs",
+    "fusectl",
+    "hugetlbfs",
+    "mqueue",
+    "nsfs",
+    "proc",
+    "pstore",
+    "securityfs",
+    "selinuxfs",
+    "sysfs",
+    "tracefs",
+];
+
+/// One mounted filesystem.
+#[derive(Debug, Clone, Serialize)]
+pub struct Mount {
+    /// Where the filesystem is mounted.
+    pub mount_point: PathBuf,
+    /// Mount source, usually a device path (e.g. `/dev/sda1`).
+    pub source: String,
+    /// Filesystem type (e.g. `ext4`).
+    pub fs_type: String,
+    /// Per-mount options (e.g. `rw`, `noatime`).
+    pub options: Vec<String>,
+    /// Device number shared by files on this filesystem (`st_dev`).
+    pub device: u64,
+    /// Total size in bytes.
+    pub total: u64,
+    /// Bytes in use.
+    pub used: u64,
+    /// Bytes available to unprivileged users.
+    pub available: u64,
+    /// Total number of inodes.
+    pub inodes_total: u64,
+    /// Inodes in use.
+    pub inodes_used: u64,
+}
+
+impl Mount {
+    /// Whether the filesystem is mounted read-only.
+    pub fn is_read_only(&self) -> bool {
+        self.has_option("ro")
+    }
+
+    /// Whether `option` is among the mount options.
+    pub fn has_option(&self, option: &str) -> bool {
+        self.options.iter().any(|o| o == option)
+    }
+
+    /// Why access times on this filesystem may not reflect actual reads:
+    /// `noatime` never updates them and `relatime` does so at most once a
+    /// day unless the file changed. `None` when every read is recorded.
+    pub fn atime_caveat(&self) -> Option<&'static str> {
+        if self.has_option("noatime") {
+            Some("noatime: access times are never updated")
+        } else if self.has_option("relatime") {
+            Some("relatime: access times are updated at most once a day")
+        } else {
+            None
+        }
+    }
+
+    /// Whether this is a pseudo filesystem without real storage.
+    pub fn is_pseudo(&self) -> bool {
+        PSEUDO_FILESYSTEMS.contains(&self.fs_type.as_str())
+    }
+
+    /// Fraction of the space in use, between 0 and 1.
+    pub fn usage_ratio(&self) -> f64 {
+        let capacity = self.used + self.available;
+        if capacity == 0 {
+            0.0
+        } else {
+            self.used as f64 / capacity as f64
+        }
+    }
+
+    /// Fraction of the inodes in use, between 0 and 1.
+    pub fn inode_ratio(&self) -> f64 {
+        if self.inodes_total == 0 {
+            0.0
+        } else {
+            self.inodes_used as f64 / self.inodes_total as f64
+        }
+    }
+}
+
+/// The mount table, in mount order.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MountTable {
+    /// All mounts, including pseudo filesystems.
+    pub mounts: Vec<Mount>,
+}
+
+impl MountTable {
+    /// Read the current mount table, including free space for each mount.
+    pub fn load() -> std::io::Result<Self> {
+        let mut table = Self::load_without_usage()?;
+        for mount in &mut table.mounts {
+            fill_usage(mount);
+        }
+        Ok(table)
+    }
+
+    /// Read the current mount table without querying free space, which can
+    /// block on unresponsive network filesystems.
+    #[cfg(target_os = "linux")]
+    pub fn load_without_usage() -> std::io::Result<Self> {
+        let content = std::fs::read_to_string("/proc/self/mountinfo")?;
+        Ok(Self::parse(&content))
+    }
+
+    /// Read the current mount table without querying free space.
+    #[cfg(not(target_os = "linux"))]
+    pub fn load_without_usage() -> std::io::Result<Self> {
+        Ok(Self::default())
+    }
+
+    /// Parse the contents of a `mountinfo` file. Malformed lines are skipped.
+    pub fn parse(content: &str) -> Self {
+        let mounts = content.lines().filter_map(parse_mountinfo_line).collect();
+        Self { mounts }
+    }
+
+    /// Mounts backed by real storage (pseudo filesystems and empty mounts
+    /// left out), each mount point listed once.
+    pub fn real(&self) -> Vec<&Mount> {
+        let mut seen = std::collections::HashSet::new();
+        self.mounts
+            .iter()
+            .rev()
+            .filter(|m| !m.is_pseudo() && seen.insert(&m.mount_point))
+            .filter(|m| m.total > 0 || m.inodes_total > 0)
+            .collect::<Vec<_>>()
+            .into_iter()
+            .rev()
+            .collect()
+    }
+
+    /// The mount containing `path`: the one with the longest mount point
+    /// that is a prefix of it. Later mounts shadow earlier ones.
+    pub fn containing(&self, path: &Path) -> Option<&Mount> {
+        self.mounts
+            .iter()
+            .rev()
+            .filter(|m| path.starts_with(&m.mount_point))
+            .max_by_key(|m| m.mount_point.components().count())
+    }
+
+    /// Why access times in `tree` may be unreliable, naming the first scan
+    /// root whose mount limits atime updates.
+    pub fn atime_caveat(&self, tree: &FileTree) -> Option<String> {
+        let roots = if tree.is_multi_root() {
+            tree.roots.as_slice()
+        } else {
+            std::slice::from_ref(&tree.root_path)
+        };
+        roots.iter().find_map(|root| {
+            let mount = self.containing(root)?;
+            let caveat = mount.atime_caveat()?;
+            Some(format!(
+                "{} is mounted {}",
+                mount.mount_point.display(),
+                caveat
+            ))
+        })
+    }
+}
+
+/// Parse one `mountinfo` line:
+///
+/// `36 35 98:0 /mnt1 /mnt2 rw,noatime master:1 - ext3 /dev/root rw,errors=continue`
+fn parse_mountinfo_line(line: &str) -> Option<Mount> {
+    let (before, after) = line.split_once(" - ")?;
+    let mut fields = before.split(' ');
+    let _mount_id = fields.next()?;
+    let _parent_id = fields.next()?;
+    let (major, minor) = fields.next()?.split_once(':')?;
+    let _root = fields.next()?;
+    let mount_point = unescape(fields.next()?);
+    let options = fields.next()?.split(',').map(str::to_string).collect();
+
+    let mut fields = after.split(' ');
+    let fs_type = fields.next()?.to_string();
+    let source = unescape(fields.next().unwrap_or_default());
+
+    Some(Mount {
+        mount_point: PathBuf::from(mount_point),
+        source,
+        fs_type,
+        options,
+        device: makedev(major.parse().ok()?, minor.parse().ok()?),
+        total: 0,
+        used: 0,
+        available: 0,
+        inodes_total: 0,
+        inodes_used: 0,
+    })
+}
+
+/// Decode the octal escapes (`\040` for a space) used in `mountinfo` paths.
+fn unescape(field: &str) -> String {
+    let bytes = field.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'\\'
+            && let Some(digits) = field.get(i + 1..i + 4)
+            && let Ok(byte) = u8::from_str_radix(digits, 8)
+        {
+            out.push(byte);
+            i += 4;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Combine major and minor numbers the way glibc's `makedev` does.
+fn makedev(major: u64, minor: u64) -> u64 {
+    ((major & 0xffff_f000) << 32)
+        | ((major & 0x0000_0fff) << 8)
+        | ((minor & 0xffff_ff00) << 12)
+        | (minor & 0x0000_00ff)
+}
+
+/// Fill in block and inode usage. Mounts that cannot be queried keep zero
+/// usage.
+fn fill_usage(mount: &mut Mount) {
+    let Some(usage) = gravityfile_core::fs_usage(&mount.mount_point) else {
+        return;
+    };
+    mount.total = usage.total;
+    mount.used = usage.used;
+    mount.available = usage.available;
+    mount.inodes_total = usage.inodes_total;
+    mount.inodes_used = usage.inodes_used;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MOUNTINFO: &str = "\
+22 1 8:1 / / rw,relatime shared:1 - ext4 /dev/sda1 rw
+23 22 0:21 / /proc rw,nosuid,nodev,noexec,relatime shared:12 - proc proc rw
+24 22 8:17 / /mnt/my\\040disk rw,noatime shared:2 - xfs /dev/sdb1 rw
+garbage line
+";
+
+    #[test]
+    fn test_parse_mountinfo() {
+        let table = MountTable::parse(MOUNTINFO);
+        assert_eq!(table.mounts.len(), 3);
+
+        let disk = &table.mounts[2];
+        assert_eq!(disk.mount_point, PathBuf::from("/mnt/my disk"));
+        assert_eq!(disk.fs_type, "xfs");
+        assert_eq!(disk.source, "/dev/sdb1");
+        assert_eq!(disk.device, makedev(8, 17));
+        assert!(disk.has_option("noatime"));
+        assert!(!disk.is_read_only());
+        assert!(table.mounts[1].is_pseudo());
+    }
+
+    #[test]
+    fn test_containing_prefers_longest_mount_point() {
+        let table = MountTable::parse(MOUNTINFO);
+        let mount = table.containing(Path::new("/mnt/my disk/photos")).unwrap();
+        assert_eq!(mount.fs_type, "xfs");
+        let mount = table.containing(Path::new("/mnt/other")).unwrap();
+        assert_eq!(mount.fs_type, "ext4");
+    }
+
+    #[test]
+    fn test_atime_caveat() {
+        let table = MountTable::parse(MOUNTINFO);
+        assert!(
+            table.mounts[0]
+                .atime_caveat()
+                .unwrap()
+                .starts_with("relatime")
+        );
+        assert!(
+            table.mounts[2]
+                .atime_caveat()
+                .unwrap()
+                .starts_with("noatime")
+        );
+
+        let mut strict = table.mounts[0].clone();
+        strict.options = vec!["rw".to_string()];
+        assert!(strict.atime_caveat().is_none());
+    }
+}
@@ -14,11 +14,13 @@ use jwalk::{DirEntry, Parallelism, WalkDirGeneric};
 use tokio::sync::broadcast;
 
 use gravityfile_core::{
-    FileNode, FileTree, InodeInfo, NodeId, NodeKind, ScanConfig, ScanError, ScanWarning,
-    Timestamps, TreeStats, WarningKind,
+    FileNode, FileTree, GitStatus, IgnoreMode, InodeInfo, NodeId, NodeKind, Ownership, ScanConfig,
+    ScanError, ScanWarning, Timestamps, TreeStats, WarningKind,
 };
 
+use crate::ignore_files::IgnoreStack;
 use crate::inode::InodeTracker;
+use crate::mounts::MountTable;
 use crate::progress::ScanProgress;
 
 /// High-performance scanner using jwalk for parallel traversal.
@@ -39,52 +41,111 @@ impl JwalkScanner {
     }
 
     /// Perform a scan of the given path.
+    ///
+    /// When the config has `extra_roots`, every root is scanned into one tree
+    /// whose synthetic top node has the roots as children (see
+    /// [`FileTree::roots`]).
     pub fn scan(&self, config: &ScanConfig) -> Result<FileTree, ScanError> {
         let start = Instant::now();
-        let root_path = config
-            .root
-            .canonicalize()
-            .map_err(|e| ScanError::io(&config.root, e))?;
+        let mut warnings = Vec::new();
+        let roots = canonical_roots(config, &mut warnings)?;
 
-        // Verify root is a directory
-        if !root_path.is_dir() {
-            return Err(ScanError::NotADirectory { path: root_path });
+        // Set up tracking. One inode tracker spans all roots so a hardlink
+        // reachable from two roots is only counted once.
+        let mut inode_tracker = InodeTracker::new();
+        let node_id_counter = AtomicU64::new(0);
+        let mut stats = TreeStats::new();
+
+        if let [root_path] = roots.as_slice() {
+            let root_node = self.scan_root(
+                config,
+                root_path,
+                &mut inode_tracker,
+                &node_id_counter,
+                &mut stats,
+                &mut warnings,
+            )?;
+
+            return Ok(FileTree::new(
+                root_node,
+                root_path.clone(),
+                config.clone(),
+                stats,
+                start.elapsed(),
+                warnings,
+            ));
         }
 
+        // Multi-root: the top node stands for the roots' common ancestor and
+        // each root is named by its path relative to it, so joining names onto
+        // `root_path` still yields real paths everywhere.
+        let base = common_ancestor(&roots);
+        let top_id = NodeId::new(node_id_counter.fetch_add(1, Ordering::Relaxed));
+        let mut top = FileNode::new_directory(top_id, node_name(&base), dir_timestamps(&base));
+
+        let mut total_size: u64 = 0;
+        let mut file_count: u64 = 0;
+        let mut dir_count: u64 = 0;
+        for root_path in &roots {
+            let mut node = self.scan_root(
+                config,
+                root_path,
+                &mut inode_tracker,
+                &node_id_counter,
+                &mut stats,
+                &mut warnings,
+            )?;
+            node.name = CompactString::new(
+                root_path
+                    .strip_prefix(&base)
+                    .unwrap_or(root_path)
+                    .to_string_lossy(),
+            );
+            total_size += node.size;
+            file_count += node.file_count();
+            dir_count += node.dir_count() + 1;
+            top.children.push(node);
+        }
+
+        top.size = total_size;
+        top.kind = NodeKind::Directory {
+            file_count,
+            dir_count,
+        };
+        top.children.sort_by_key(|c| std::cmp::Reverse(c.size));
+
+        let mut tree = FileTree::new(top, base, config.clone(), stats, start.elapsed(), warnings);
+        tree.roots = roots;
+        Ok(tree)
+    }
+
+    /// Scan a single root directory into a node.
+    fn scan_root(
+        &self,
+        config: &ScanConfig,
+        root_path: &Path,
+        inode_tracker: &mut InodeTracker,
+        node_id_counter: &AtomicU64,
+        stats: &mut TreeStats,
+        warnings: &mut Vec<ScanWarning>,
+    ) -> Result<FileNode, ScanError> {
         // Get root device for cross-filesystem detection
         let root_metadata =
-            std::fs::metadata(&root_path).map_err(|e| ScanError::io(&root_path, e))?;
+            std::fs::metadata(root_path).map_err(|e| ScanError::io(root_path, e))?;
         let root_device = get_dev(&root_metadata);
 
-        // Set up tracking
-        let mut inode_tracker = InodeTracker::new();
-        let node_id_counter = AtomicU64::new(0);
-        let mut stats = TreeStats::new();
-        let mut warnings = Vec::new();
-
         // Collect all entries first
         let entries = self.collect_entries(
             config,
-            &root_path,
+            root_path,
             root_device,
-            &mut inode_tracker,
-            &mut stats,
-            &mut warnings,
+            inode_tracker,
+            stats,
+            warnings,
         )?;
 
         // Build tree from collected entries
-        let root_node = self.build_tree(&root_path, entries, &node_id_counter, &mut stats);
-
-        let scan_duration = start.elapsed();
-
-        Ok(FileTree::new(
-            root_node,
-            root_path,
-            config.clone(),
-            stats,
-            scan_duration,
-            warnings,
-        ))
+        Ok(self.build_tree(root_path, entries, node_id_counter, stats))
     }
 
     /// Collect all entries using jwalk.
@@ -117,6 +178,12 @@ impl JwalkScanner {
         // Capture config fields needed in the closure.
         let cross_filesystems = config.cross_filesystems;
         let include_hidden = config.include_hidden;
+        let ignore_mode = config.ignore_files;
+        let root_ignore_stack = if ignore_mode.is_enabled() {
+            IgnoreStack::for_root(root_path)
+        } else {
+            IgnoreStack::default()
+        };
 
         // Re-use the GlobSet already compiled by ScanConfig when available.
         // Fall back to compiling on-the-fly if patterns exist but weren't compiled
@@ -138,13 +205,23 @@ impl JwalkScanner {
                 builder.build().ok().map(Arc::new)
             });
 
-        let walker = WalkDirGeneric::<((), ())>::new(root_path)
+        // Read-dir state carries the ignore-file stack; entry state marks
+        // entries matched by it (only set in `IgnoreMode::Report`).
+        let walker = WalkDirGeneric::<(IgnoreStack, bool)>::new(root_path)
             .parallelism(parallelism)
             .skip_hidden(!include_hidden)
             .follow_links(config.follow_symlinks)
             .min_depth(0)
             .max_depth(config.max_depth.map(|d| d as usize).unwrap_or(usize::MAX))
-            .process_read_dir(move |_depth, _dir_path, _state, children| {
+            .root_read_dir_state(root_ignore_stack)
+            .process_read_dir(move |depth, dir_path, ignore_stack, children| {
+                // Load this directory's ignore files before filtering its
+                // children. `depth` is None only for the root entry itself,
+                // which is never ignored.
+                let apply_ignore_files = ignore_mode.is_enabled() && depth.is_some();
+                let dir_ignored = apply_ignore_files && ignore_stack.enter(dir_path);
+                let mut ignored_dirs = Vec::new();
+
                 // Prune and filter early — before jwalk recurses.
                 children.retain_mut(|entry_result| {
                     let entry = match entry_result {
@@ -161,7 +238,22 @@ impl JwalkScanner {
                         return false;
                     }
 
-                    // Prune cross-filesystem subtrees for directories.
+                    // Apply hierarchical ignore files.
+                    if apply_ignore_files {
+                        let is_dir = entry.file_type.is_dir();
+                        if dir_ignored || ignore_stack.is_ignored(&entry.path(), is_dir) {
+                            if ignore_mode == IgnoreMode::Exclude {
+                                return false;
+                            }
+                            entry.client_state = true;
+                            if is_dir {
+                                ignored_dirs.push(entry.path());
+                            }
+                        }
+                    }
+
+                    // Prune cross-filesystem subtrees for directories. The
+                    // entry itself is kept and becomes an "other mount" stub.
                     if !cross_filesystems
                         && entry.file_type.is_dir()
                         && let Ok(meta) = entry.metadata()
@@ -170,19 +262,30 @@ impl JwalkScanner {
                         // Setting read_children_path to None stops jwalk
                         // from descending into this directory.
                         entry.read_children_path = None;
-                        return false; // drop the dir entry itself too
                     }
 
                     true
                 });
+
+                if apply_ignore_files {
+                    ignore_stack.set_ignored_dirs(ignored_dirs);
+                }
             });
 
+        // Filesystem types for "other mount" stubs. Free space is not needed,
+        // so no mount is queried.
+        let mount_table = if cross_filesystems {
+            MountTable::default()
+        } else {
+            MountTable::load_without_usage().unwrap_or_default()
+        };
+
         // Map from parent path to children
         let mut entries_by_parent: HashMap<PathBuf, Vec<EntryInfo>> = HashMap::new();
         let mut progress_counter: u64 = 0;
 
         for entry_result in walker {
-            let entry: DirEntry<((), ())> = match entry_result {
+            let entry: DirEntry<(IgnoreStack, bool)> = match entry_result {
                 Ok(e) => e,
                 Err(err) => {
                     let path = err.path().map(|p| p.to_path_buf()).unwrap_or_default();
@@ -215,28 +318,30 @@ impl JwalkScanner {
             // Handle different file types
             let file_type = entry.file_type();
             let depth = entry.depth() as u32;
+            let ignored = entry.client_state;
 
-            if file_type.is_dir() {
+            if file_type.is_dir() && !cross_filesystems && get_dev(&metadata) != root_device {
+                // Directory on another filesystem, left unscanned.
+                if let Some(parent) = path.parent() {
+                    let fs_type = mount_table
+                        .containing(&path)
+                        .map(|m| CompactString::new(&m.fs_type))
+                        .unwrap_or_default();
+                    let mut entry_info = EntryInfo::directory(file_name, path.clone(), &metadata);
+                    entry_info.mount_fs_type = Some(fs_type);
+                    entries_by_parent
+                        .entry(parent.to_path_buf())
+                        .or_default()
+                        .push(entry_info);
+                }
+            } else if file_type.is_dir() {
                 stats.record_dir(depth);
 
                 // For directories, track them but size will be aggregated later
                 if let Some(parent) = path.parent() {
                     let entry_info = EntryInfo {
-                        name: file_name,
-                        path: path.clone(),
-                        size: 0,
-                        blocks: 0,
-                        is_dir: true,
-                        is_symlink: false,
-                        symlink_target: None,
-                        symlink_broken: false,
-                        executable: false,
-                        timestamps: Timestamps::new(
-                            metadata.modified().unwrap_or(std::time::UNIX_EPOCH),
-                            metadata.accessed().ok(),
-                            metadata.created().ok(),
-                        ),
-                        inode: Some(InodeInfo::new(get_ino(&metadata), get_dev(&metadata))),
+                        ignored,
+                        ..EntryInfo::directory(file_name, path.clone(), &metadata)
                     };
 
                     entries_by_parent
@@ -253,7 +358,8 @@ impl JwalkScanner {
                 }
 
                 let nlink = get_nlink(&metadata);
-                let inode_info = InodeInfo::new(get_ino(&metadata), get_dev(&metadata));
+                let inode_info =
+                    InodeInfo::new(get_ino(&metadata), get_dev(&metadata)).with_links(nlink);
 
                 let size = if config.apparent_size {
                     metadata.len()
@@ -275,6 +381,9 @@ impl JwalkScanner {
                     metadata.modified().unwrap_or(std::time::UNIX_EPOCH),
                     depth,
                 );
+                if ignored {
+                    stats.record_ignored(size);
+                }
 
                 if let Some(parent) = path.parent() {
                     let executable = is_executable(&metadata);
@@ -294,6 +403,9 @@ impl JwalkScanner {
                             metadata.created().ok(),
                         ),
                         inode: Some(inode_info),
+                        ignored,
+                        mount_fs_type: None,
+                        owner: get_owner(&metadata),
                     };
 
                     entries_by_parent
@@ -355,6 +467,9 @@ impl JwalkScanner {
                             metadata.created().ok(),
                         ),
                         inode: None,
+                        ignored,
+                        mount_fs_type: None,
+                        owner: get_owner(&metadata),
                     };
 
                     entries_by_parent
@@ -376,35 +491,31 @@ impl JwalkScanner {
         node_id_counter: &AtomicU64,
         _stats: &mut TreeStats,
     ) -> FileNode {
-        self.build_node(root_path, &mut entries_by_parent, node_id_counter)
+        let owner = std::fs::metadata(root_path)
+            .ok()
+            .and_then(|m| get_owner(&m));
+        self.build_node(
+            root_path,
+            false,
+            owner,
+            &mut entries_by_parent,
+            node_id_counter,
+        )
     }
 
     /// Recursively build a node and its children.
     fn build_node(
         &self,
         path: &Path,
+        ignored: bool,
+        owner: Option<Ownership>,
         entries_by_parent: &mut HashMap<PathBuf, Vec<EntryInfo>>,
         node_id_counter: &AtomicU64,
     ) -> FileNode {
         let id = NodeId::new(node_id_counter.fetch_add(1, Ordering::Relaxed));
-        let name = path
-            .file_name()
-            .map(|n| n.to_string_lossy().to_string())
-            .unwrap_or_else(|| path.to_string_lossy().to_string());
-
-        let metadata = std::fs::metadata(path).ok();
-        let timestamps = metadata
-            .as_ref()
-            .map(|m| {
-                Timestamps::new(
-                    m.modified().unwrap_or(std::time::UNIX_EPOCH),
-                    m.accessed().ok(),
-                    m.created().ok(),
-                )
-            })
-            .unwrap_or_else(|| Timestamps::with_modified(std::time::UNIX_EPOCH));
-
-        let mut node = FileNode::new_directory(id, name, timestamps);
+        let mut node = FileNode::new_directory(id, node_name(path), dir_timestamps(path));
+        node.git_status = ignored_status(ignored);
+        node.owner = owner;
 
         // Get children for this path
         let children_entries = entries_by_parent.remove(path).unwrap_or_default();
@@ -414,9 +525,23 @@ impl JwalkScanner {
         let mut dir_count: u64 = 0;
 
         for entry in children_entries {
-            if entry.is_dir {
+            if let Some(fs_type) = entry.mount_fs_type {
+                let child_id = NodeId::new(node_id_counter.fetch_add(1, Ordering::Relaxed));
+                let mut child_node =
+                    FileNode::new_mount_point(child_id, entry.name, fs_type, entry.timestamps);
+                child_node.inode = entry.inode;
+                child_node.owner = entry.owner;
+                dir_count += 1;
+                node.children.push(child_node);
+            } else if entry.is_dir {
                 // Recursively build directory
-                let child_node = self.build_node(&entry.path, entries_by_parent, node_id_counter);
+                let child_node = self.build_node(
+                    &entry.path,
+                    entry.ignored,
+                    entry.owner,
+                    entries_by_parent,
+                    node_id_counter,
+                );
                 total_size += child_node.size;
                 file_count += child_node.file_count();
                 dir_count += child_node.dir_count() + 1;
@@ -436,8 +561,9 @@ impl JwalkScanner {
                     blocks: 0,
                     timestamps: entry.timestamps,
                     inode: None,
+                    owner: entry.owner,
                     content_hash: None,
-                    git_status: None,
+                    git_status: ignored_status(entry.ignored),
                     children: Vec::new(),
                 };
                 node.children.push(child_node);
@@ -453,6 +579,8 @@ impl JwalkScanner {
                     entry.executable,
                 );
                 child_node.inode = entry.inode;
+                child_node.owner = entry.owner;
+                child_node.git_status = ignored_status(entry.ignored);
 
                 total_size += entry.size;
                 file_count += 1;
@@ -468,7 +596,7 @@ impl JwalkScanner {
         };
 
         // Sort children by size (descending)
-        node.children.sort_by(|a, b| b.size.cmp(&a.size));
+        node.children.sort_by_key(|c| std::cmp::Reverse(c.size));
 
         node
     }
@@ -480,6 +608,77 @@ impl Default for JwalkScanner {
     }
 }
 
+/// Display name of a directory node: its file name, or the whole path for `/`.
+fn node_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.to_string_lossy().to_string())
+}
+
+/// Timestamps of a directory, or the epoch if its metadata is unreadable.
+fn dir_timestamps(path: &Path) -> Timestamps {
+    std::fs::metadata(path)
+        .map(|m| {
+            Timestamps::new(
+                m.modified().unwrap_or(std::time::UNIX_EPOCH),
+                m.accessed().ok(),
+                m.created().ok(),
+            )
+        })
+        .unwrap_or_else(|_| Timestamps::with_modified(std::time::UNIX_EPOCH))
+}
+
+/// Canonicalize the configured roots, dropping duplicates and roots nested
+/// inside another root (their files would otherwise be counted twice).
+fn canonical_roots(
+    config: &ScanConfig,
+    warnings: &mut Vec<ScanWarning>,
+) -> Result<Vec<PathBuf>, ScanError> {
+    let mut roots = Vec::new();
+    for root in config.roots() {
+        let path = root.canonicalize().map_err(|e| ScanError::io(root, e))?;
+
+        // Verify root is a directory
+        if !path.is_dir() {
+            return Err(ScanError::NotADirectory { path });
+        }
+        roots.push(path);
+    }
+
+    // Sorting puts every ancestor before its descendants.
+    roots.sort();
+    roots.dedup();
+    let mut kept: Vec<PathBuf> = Vec::with_capacity(roots.len());
+    for path in roots {
+        if let Some(outer) = kept.iter().find(|outer| path.starts_with(outer)) {
+            let message = format!("Skipped root already inside {}", outer.display());
+            warnings.push(ScanWarning::new(
+                &path,
+                WarningKind::OverlappingRoot,
+                message,
+            ));
+        } else {
+            kept.push(path);
+        }
+    }
+    Ok(kept)
+}
+
+/// Deepest directory containing every path in `paths`.
+pub fn common_ancestor(paths: &[PathBuf]) -> PathBuf {
+    let Some(first) = paths.first() else {
+        return PathBuf::new();
+    };
+    let mut base = first.as_path();
+    while !paths.iter().all(|p| p.starts_with(base)) {
+        match base.parent() {
+            Some(parent) => base = parent,
+            None => break,
+        }
+    }
+    base.to_path_buf()
+}
+
 /// Create a quick, non-recursive directory listing for immediate display.
 /// This function reads only the immediate children of a directory without
 /// recursing into subdirectories. Directory sizes will be 0 (unknown).
@@ -531,6 +730,7 @@ pub fn quick_list(path: &Path, config: Option<&ScanConfig>) -> Result<FileTree,
 
     let root_id = NodeId::new(node_id_counter.fetch_add(1, Ordering::Relaxed));
     let mut root_node = FileNode::new_directory(root_id, root_name, root_timestamps);
+    root_node.owner = get_owner(&root_metadata);
 
     // Read immediate children
     let read_dir = match std::fs::read_dir(&root_path) {
@@ -590,8 +790,9 @@ pub fn quick_list(path: &Path, config: Option<&ScanConfig>) -> Result<FileTree,
 
         if metadata.is_dir() {
             // Directory - size is unknown (0) until full scan
-            let child_node =
+            let mut child_node =
                 FileNode::new_directory(child_id, CompactString::new(&entry_name), timestamps);
+            child_node.owner = get_owner(&metadata);
             root_node.children.push(child_node);
             dir_count += 1;
             stats.record_dir(1);
@@ -614,8 +815,10 @@ pub fn quick_list(path: &Path, config: Option<&ScanConfig>) -> Result<FileTree,
             );
 
             // Set inode info for potential hardlink detection
-            let inode = InodeInfo::new(get_ino(&metadata), get_dev(&metadata));
+            let inode = InodeInfo::new(get_ino(&metadata), get_dev(&metadata))
+                .with_links(get_nlink(&metadata));
             child_node.inode = Some(inode);
+            child_node.owner = get_owner(&metadata);
 
             total_size += size;
             file_count += 1;
@@ -643,6 +846,7 @@ pub fn quick_list(path: &Path, config: Option<&ScanConfig>) -> Result<FileTree,
                 blocks: 0,
                 timestamps,
                 inode: None,
+                owner: get_owner(&metadata),
                 content_hash: None,
                 git_status: None,
                 children: Vec::new(),
@@ -692,6 +896,43 @@ struct EntryInfo {
     executable: bool,
     timestamps: Timestamps,
     inode: Option<InodeInfo>,
+    /// Matched by an ignore file (`IgnoreMode::Report` scans only).
+    ignored: bool,
+    /// Filesystem type of an unscanned directory on another filesystem.
+    mount_fs_type: Option<CompactString>,
+    owner: Option<Ownership>,
+}
+
+impl EntryInfo {
+    /// Entry for a directory; its size is aggregated from its children later.
+    fn directory(name: CompactString, path: PathBuf, metadata: &std::fs::Metadata) -> Self {
+        Self {
+            name,
+            path,
+            size: 0,
+            blocks: 0,
+            is_dir: true,
+            is_symlink: false,
+            symlink_target: None,
+            symlink_broken: false,
+            executable: false,
+            timestamps: Timestamps::new(
+                metadata.modified().unwrap_or(std::time::UNIX_EPOCH),
+                metadata.accessed().ok(),
+                metadata.created().ok(),
+            ),
+            inode: Some(InodeInfo::new(get_ino(metadata), get_dev(metadata))),
+            ignored: false,
+            mount_fs_type: None,
+            owner: get_owner(metadata),
+        }
+    }
+}
+
+/// Status to tag ignore-file matches with, so they render like git-ignored entries.
+#[inline]
+fn ignored_status(ignored: bool) -> Option<GitStatus> {
+    ignored.then_some(GitStatus::Ignored)
 }
 
 /// Check if a file is executable (Unix).
@@ -724,6 +965,21 @@ fn get_dev(_metadata: &std::fs::Metadata) -> u64 {
     0
 }
 
+/// Get the owner, group and permission bits from metadata.
+#[cfg(unix)]
+fn get_owner(metadata: &std::fs::Metadata) -> Option<Ownership> {
+    Some(Ownership::new(
+        metadata.uid(),
+        metadata.gid(),
+        metadata.mode(),
+    ))
+}
+
+#[cfg(not(unix))]
+fn get_owner(_metadata: &std::fs::Metadata) -> Option<Ownership> {
+    None
+}
+
 /// Get the inode number from metadata.
 #[cfg(unix)]
 fn get_ino(metadata: &std::fs::Metadata) -> u64 {
@@ -856,6 +1112,188 @@ mod tests {
         assert!(!tree.root.children.iter().any(|c| c.name.as_str() == "dir2"));
     }
 
+    fn create_ignore_tree() -> TempDir {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        fs::create_dir_all(root.join("build/out")).unwrap();
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(root.join(".gitignore"), "build/out/\n*.log\n!keep.log\n").unwrap();
+        fs::write(root.join("src/.gravityignore"), "scratch.rs\n").unwrap();
+        fs::write(root.join("build/out/app.bin"), vec![0u8; 8192]).unwrap();
+        fs::write(root.join("build/notes.txt"), "notes").unwrap();
+        fs::write(root.join("debug.log"), "log").unwrap();
+        fs::write(root.join("keep.log"), "keep").unwrap();
+        fs::write(root.join("src/main.rs"), "fn main() {}").unwrap();
+        fs::write(root.join("src/scratch.rs"), "// wip").unwrap();
+        temp
+    }
+
+    #[test]
+    fn test_ignore_files_exclude() {
+        let temp = create_ignore_tree();
+        let config = ScanConfig::builder()
+            .root(temp.path())
+            .ignore_files(IgnoreMode::Exclude)
+            .build()
+            .unwrap();
+
+        let tree = JwalkScanner::new().scan(&config).unwrap();
+        let names = |node: &FileNode| -> Vec<String> {
+            node.children.iter().map(|c| c.name.to_string()).collect()
+        };
+
+        let root = names(&tree.root);
+        assert!(root.contains(&"keep.log".to_string()));
+        assert!(!root.contains(&"debug.log".to_string()));
+        let build = tree
+            .root
+            .children
+            .iter()
+            .find(|c| &*c.name == "build")
+            .unwrap();
+        assert_eq!(names(build), vec!["notes.txt".to_string()]);
+        let src = tree
+            .root
+            .children
+            .iter()
+            .find(|c| &*c.name == "src")
+            .unwrap();
+        assert!(names(src).contains(&"main.rs".to_string()));
+        assert!(!names(src).contains(&"scratch.rs".to_string()));
+    }
+
+    #[test]
+    fn test_ignore_files_report() {
+        let temp = create_ignore_tree();
+        let config = ScanConfig::builder()
+            .root(temp.path())
+            .apparent_size(true)
+            .ignore_files(IgnoreMode::Report)
+            .build()
+            .unwrap();
+
+        let tree = JwalkScanner::new().scan(&config).unwrap();
+
+        // app.bin (inside the ignored dir), debug.log and scratch.rs.
+        assert_eq!(tree.stats.ignored_files, 3);
+        assert_eq!(tree.stats.ignored_size, 8192 + 3 + 6);
+        assert_eq!(
+            tree.stats.tracked_size(),
+            tree.stats.total_size - tree.stats.ignored_size
+        );
+
+        let build = tree
+            .root
+            .children
+            .iter()
+            .find(|c| &*c.name == "build")
+            .unwrap();
+        let out = build.children.iter().find(|c| &*c.name == "out").unwrap();
+        assert_eq!(out.git_status, Some(GitStatus::Ignored));
+        assert_eq!(out.children[0].git_status, Some(GitStatus::Ignored));
+        assert_eq!(build.git_status, None);
+    }
+
+    #[test]
+    fn test_multi_root_scan() {
+        let temp = TempDir::new().unwrap();
+        let base = temp.path().canonicalize().unwrap();
+        fs::create_dir_all(base.join("data")).unwrap();
+        fs::create_dir_all(base.join("home/team")).unwrap();
+        fs::write(base.join("data/shared.bin"), vec![1u8; 8192]).unwrap();
+        fs::hard_link(
+            base.join("data/shared.bin"),
+            base.join("home/team/link.bin"),
+        )
+        .unwrap();
+        fs::write(base.join("home/team/notes.txt"), "notes").unwrap();
+
+        let config = ScanConfig::with_roots([
+            base.join("home/team"),
+            base.join("data"),
+            // Listed twice: deduplicated.
+            base.join("home/team"),
+        ])
+        .unwrap();
+        let tree = JwalkScanner::new().scan(&config).unwrap();
+
+        assert!(tree.is_multi_root());
+        assert_eq!(tree.root_path, base);
+        let mut names: Vec<_> = tree
+            .root
+            .children
+            .iter()
+            .map(|c| c.name.to_string())
+            .collect();
+        names.sort();
+        assert_eq!(names, vec!["data".to_string(), "home/team".to_string()]);
+        assert_eq!(tree.root.file_count(), 3);
+
+        // The hardlinked file counts once across both roots.
+        let shared = tree.root.children.iter().map(|c| c.size).sum::<u64>();
+        assert_eq!(tree.root.size, shared);
+        assert!(shared < 2 * 8192);
+
+        // Names joined onto root_path resolve to the real roots.
+        for child in &tree.root.children {
+            assert!(tree.root_path.join(&*child.name).is_dir());
+        }
+    }
+
+    #[test]
+    fn test_nested_roots_are_skipped() {
+        let temp = create_test_tree();
+        let config =
+            ScanConfig::with_roots([temp.path().to_path_buf(), temp.path().join("dir1")]).unwrap();
+        let tree = JwalkScanner::new().scan(&config).unwrap();
+
+        assert!(!tree.is_multi_root());
+        assert_eq!(tree.stats.total_files, 4);
+        assert!(
+            tree.warnings
+                .iter()
+                .any(|w| w.kind == WarningKind::OverlappingRoot)
+        );
+    }
+
+    #[test]
+    fn test_common_ancestor() {
+        let paths = [
+            PathBuf::from("/data/a"),
+            PathBuf::from("/data/b/c"),
+            PathBuf::from("/data/b"),
+        ];
+        assert_eq!(common_ancestor(&paths), PathBuf::from("/data"));
+        assert_eq!(
+            common_ancestor(&[PathBuf::from("/data"), PathBuf::from("/scratch")]),
+            PathBuf::from("/")
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_other_mounts_become_stubs() {
+        // /proc is always a separate filesystem from /.
+        let config = ScanConfig::builder()
+            .root("/")
+            .max_depth(Some(1))
+            .build()
+            .unwrap();
+        let tree = JwalkScanner::new().scan(&config).unwrap();
+
+        let proc = tree
+            .root
+            .children
+            .iter()
+            .find(|c| c.name == "proc")
+            .unwrap();
+        match &proc.kind {
+            NodeKind::MountPoint { fs_type } => assert_eq!(fs_type, "proc"),
+            other => panic!("expected mount point, got {:?}", other),
+        }
+        assert_eq!(proc.size, 0);
+    }
+
     #[test]
     fn test_quick_list_respects_hidden() {
         let temp = TempDir::new().unwrap();
@@ -2,6 +2,9 @@
 
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 
+use gravityfile_analyze::{HygieneKind, TimeBasis, TimelinePeriod};
+use gravityfile_ops::{LinkKind, SyncMode};
+
 use super::state::View;
 
 /// Command input state.
@@ -133,6 +136,16 @@ pub enum CommandAction {
     SwitchView(View),
     /// Clear deletion marks.
     ClearMarks,
+    /// Filter the tree by a query, or clear the filter.
+    Filter(Option<String>),
+    /// Show the Age view measuring ages from the given timestamp.
+    AgeBasis(TimeBasis),
+    /// Show the Largest view, replacing its filters when words are given.
+    Largest(Vec<String>),
+    /// Show the Timeline view, switching to the given bin width.
+    Timeline(Option<TimelinePeriod>),
+    /// Show the Hygiene view, limited to one kind of issue when given.
+    Hygiene(Option<HygieneKind>),
     /// Toggle details panel.
     ToggleDetails,
     /// Set theme.
@@ -159,6 +172,8 @@ pub enum CommandAction {
     Cut,
     /// Paste clipboard contents.
     Paste,
+    /// Paste clipboard items as links.
+    PasteLink(LinkKind),
     /// Delete marked items.
     Delete,
     /// Rename current item.
@@ -171,8 +186,25 @@ pub enum CommandAction {
     Take(Option<String>),
     /// Undo last operation.
     Undo,
+    /// Redo last undone operation.
+    Redo,
+    /// Show the undo history.
+    History,
     /// Bulk rename marked items.
     BulkRename,
+    /// Sync the current directory into the next tab's directory.
+    Sync(SyncMode),
+    /// Securely shred marked items with an optional number of passes.
+    Shred(Option<u32>),
+    /// Open the permissions modal, optionally prefilled with a mode.
+    Chmod {
+        mode: Option<String>,
+        recursive: bool,
+    },
+    /// Change owner and/or group (`owner`, `owner:group` or `:group`).
+    Chown { spec: String, recursive: bool },
+    /// Open the rename pattern modal, optionally prefilled.
+    RenamePattern { find: String, replace: String },
 
     // Archive operations
     /// Extract archive to destination.
@@ -212,6 +244,17 @@ pub enum SortCommand {
     Reverse,
 }
 
+/// Split a `-R` flag off command arguments.
+fn split_recursive<'a>(args: &[&'a str]) -> (bool, Vec<&'a str>) {
+    let recursive = args.iter().any(|a| *a == "-R" || *a == "-r");
+    let rest = args
+        .iter()
+        .copied()
+        .filter(|a| *a != "-R" && *a != "-r")
+        .collect();
+    (recursive, rest)
+}
+
 /// Parse and execute a command string.
 pub fn parse_command(cmd: &str) -> CommandAction {
     let parts: Vec<&str> = cmd.split_whitespace().collect();
@@ -247,12 +290,41 @@ pub fn parse_command(cmd: &str) -> CommandAction {
         // View switching
         "explorer" | "e" | "tree" => CommandAction::SwitchView(View::Explorer),
         "duplicates" | "dups" | "d" => CommandAction::SwitchView(View::Duplicates),
-        "age" | "a" => CommandAction::SwitchView(View::Age),
+        "age" | "a" => match parts.get(1).map(|b| b.parse::<TimeBasis>()) {
+            None => CommandAction::SwitchView(View::Age),
+            Some(Ok(basis)) => CommandAction::AgeBasis(basis),
+            Some(Err(_)) => CommandAction::None,
+        },
         "errors" | "err" => CommandAction::SwitchView(View::Errors),
+        "trash" | "bin" => CommandAction::SwitchView(View::Trash),
+        "jobs" => CommandAction::SwitchView(View::Jobs),
+        "mounts" | "df" => CommandAction::SwitchView(View::Mounts),
+        "owners" | "users" => CommandAction::SwitchView(View::Owners),
+        "types" | "filetypes" => CommandAction::SwitchView(View::Types),
+        "cleanup" | "clean" => CommandAction::SwitchView(View::Cleanup),
+        "largest" | "biggest" => {
+            CommandAction::Largest(parts[1..].iter().map(|s| s.to_string()).collect())
+        }
+        "timeline" | "histogram" => match parts.get(1).map(|p| p.parse::<TimelinePeriod>()) {
+            None => CommandAction::Timeline(None),
+            Some(Ok(period)) => CommandAction::Timeline(Some(period)),
+            Some(Err(_)) => CommandAction::None,
+        },
+        "hygiene" => match parts.get(1).map(|k| k.parse::<HygieneKind>()) {
+            None => CommandAction::Hygiene(None),
+            Some(Ok(kind)) => CommandAction::Hygiene(Some(kind)),
+            Some(Err(_)) => CommandAction::None,
+        },
 
         // Clear marks
         "clear" | "unmark" => CommandAction::ClearMarks,
 
+        // Query filter; the rest of the line is the query, spacing intact
+        "filter" | "where" => {
+            let query = cmd.trim_start()[parts[0].len()..].trim();
+            CommandAction::Filter((!query.is_empty()).then(|| query.to_string()))
+        }
+
         // Toggle details
         "details" | "info" | "i" => CommandAction::ToggleDetails,
 
@@ -352,6 +424,15 @@ pub fn parse_command(cmd: &str) -> CommandAction {
         "yank" | "y" | "copy" | "cp" => CommandAction::Yank,
         "cut" | "x" => CommandAction::Cut,
         "paste" | "p" => CommandAction::Paste,
+        "symlink" | "link" => {
+            if parts[1..].iter().any(|a| matches!(*a, "-r" | "--relative")) {
+                CommandAction::PasteLink(LinkKind::RelativeSymlink)
+            } else {
+                CommandAction::PasteLink(LinkKind::Symlink)
+            }
+        }
+        "relink" => CommandAction::PasteLink(LinkKind::RelativeSymlink),
+        "hardlink" | "hlink" => CommandAction::PasteLink(LinkKind::Hardlink),
         "delete" | "del" | "rm" => CommandAction::Delete,
 
         // Rename
@@ -392,9 +473,55 @@ pub fn parse_command(cmd: &str) -> CommandAction {
 
         // Undo
         "undo" | "u" => CommandAction::Undo,
+        "redo" | "red" => CommandAction::Redo,
+        "history" | "hist" => CommandAction::History,
 
         // Bulk rename
         "rename-bulk" | "bulk-rename" | "bulkrename" | "brn" => CommandAction::BulkRename,
+        "rename-pattern" | "rename-regex" | "rnp" => CommandAction::RenamePattern {
+            find: parts.get(1).map(|s| s.to_string()).unwrap_or_default(),
+            replace: parts.get(2..).map(|r| r.join(" ")).unwrap_or_default(),
+        },
+
+        // Sync with the next tab
+        "sync" | "mirror" => match parts.get(1).copied() {
+            Some("two-way" | "twoway" | "2way" | "update") => CommandAction::Sync(SyncMode::TwoWay),
+            Some("mirror") | None => CommandAction::Sync(SyncMode::Mirror),
+            Some(_) => CommandAction::None,
+        },
+
+        // Permissions and ownership; `-R` recurses into directories
+        "chmod" | "perms" | "permissions" => {
+            let (recursive, args) = split_recursive(&parts[1..]);
+            CommandAction::Chmod {
+                mode: (!args.is_empty()).then(|| args.join(",")),
+                recursive,
+            }
+        }
+        "chown" | "chgrp" => {
+            let (recursive, args) = split_recursive(&parts[1..]);
+            match args.first() {
+                Some(spec) if args.len() == 1 => CommandAction::Chown {
+                    spec: if parts[0] == "chgrp" {
+                        format!(":{}", spec)
+                    } else {
+                        spec.to_string()
+                    },
+                    recursive,
+                },
+                _ => CommandAction::None,
+            }
+        }
+
+        // Secure delete
+        "shred" | "wipe" => match parts.get(1) {
+            Some(n) => n
+                .parse()
+                .ok()
+                .filter(|&n| n > 0)
+                .map_or(CommandAction::None, |n| CommandAction::Shred(Some(n))),
+            None => CommandAction::Shred(None),
+        },
 
         // Archive operations
         "extract" | "unzip" | "untar" | "decompress" => {
@@ -439,6 +566,164 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_undo_history() {
+        assert!(matches!(parse_command("u"), CommandAction::Undo));
+        assert!(matches!(parse_command("redo"), CommandAction::Redo));
+        assert!(matches!(parse_command("history"), CommandAction::History));
+        assert!(matches!(parse_command("hist"), CommandAction::History));
+    }
+
+    #[test]
+    fn test_parse_sync() {
+        assert!(matches!(
+            parse_command("sync"),
+            CommandAction::Sync(SyncMode::Mirror)
+        ));
+        assert!(matches!(
+            parse_command("sync two-way"),
+            CommandAction::Sync(SyncMode::TwoWay)
+        ));
+        assert!(matches!(
+            parse_command("sync sideways"),
+            CommandAction::None
+        ));
+    }
+
+    #[test]
+    fn test_parse_rename_pattern() {
+        let CommandAction::RenamePattern { find, replace } =
+            parse_command(r"rnp IMG_(\d+) photo ${1}")
+        else {
+            panic!("expected rename pattern");
+        };
+        assert_eq!(find, r"IMG_(\d+)");
+        assert_eq!(replace, "photo ${1}");
+        assert!(matches!(
+            parse_command("rename-pattern"),
+            CommandAction::RenamePattern { find, replace } if find.is_empty() && replace.is_empty()
+        ));
+    }
+
+    #[test]
+    fn test_parse_paste_link() {
+        assert!(matches!(
+            parse_command("symlink"),
+            CommandAction::PasteLink(LinkKind::Symlink)
+        ));
+        assert!(matches!(
+            parse_command("symlink -r"),
+            CommandAction::PasteLink(LinkKind::RelativeSymlink)
+        ));
+        assert!(matches!(
+            parse_command("relink"),
+            CommandAction::PasteLink(LinkKind::RelativeSymlink)
+        ));
+        assert!(matches!(
+            parse_command("hardlink"),
+            CommandAction::PasteLink(LinkKind::Hardlink)
+        ));
+    }
+
+    #[test]
+    fn test_parse_chmod_chown() {
+        assert!(matches!(
+            parse_command("chmod"),
+            CommandAction::Chmod {
+                mode: None,
+                recursive: false
+            }
+        ));
+        let CommandAction::Chmod { mode, recursive } = parse_command("chmod -R u+rwX") else {
+            panic!("expected chmod");
+        };
+        assert_eq!(mode.as_deref(), Some("u+rwX"));
+        assert!(recursive);
+
+        let CommandAction::Chown { spec, recursive } = parse_command("chgrp staff") else {
+            panic!("expected chgrp");
+        };
+        assert_eq!(spec, ":staff");
+        assert!(!recursive);
+        assert!(matches!(parse_command("chown"), CommandAction::None));
+    }
+
+    #[test]
+    fn test_parse_shred() {
+        assert!(matches!(parse_command("shred"), CommandAction::Shred(None)));
+        assert!(matches!(
+            parse_command("shred 7"),
+            CommandAction::Shred(Some(7))
+        ));
+        assert!(matches!(parse_command("shred 0"), CommandAction::None));
+        assert!(matches!(parse_command("shred lots"), CommandAction::None));
+    }
+
+    #[test]
+    fn test_parse_age_basis() {
+        assert!(matches!(
+            parse_command("age atime"),
+            CommandAction::AgeBasis(TimeBasis::Accessed)
+        ));
+        assert!(matches!(
+            parse_command("age"),
+            CommandAction::SwitchView(View::Age)
+        ));
+        assert!(matches!(parse_command("age ctime"), CommandAction::None));
+    }
+
+    #[test]
+    fn test_parse_timeline() {
+        assert!(matches!(
+            parse_command("timeline week"),
+            CommandAction::Timeline(Some(TimelinePeriod::Week))
+        ));
+        assert!(matches!(
+            parse_command("histogram"),
+            CommandAction::Timeline(None)
+        ));
+        assert!(matches!(parse_command("timeline day"), CommandAction::None));
+    }
+
+    #[test]
+    fn test_parse_hygiene() {
+        assert!(matches!(
+            parse_command("hygiene broken"),
+            CommandAction::Hygiene(Some(HygieneKind::BrokenSymlink))
+        ));
+        assert!(matches!(
+            parse_command("hygiene"),
+            CommandAction::Hygiene(None)
+        ));
+        assert!(matches!(parse_command("hygiene junk"), CommandAction::None));
+    }
+
+    #[test]
+    fn test_parse_largest() {
+        match parse_command("largest dirs ext:iso,img") {
+            CommandAction::Largest(words) => assert_eq!(words, vec!["dirs", "ext:iso,img"]),
+            other => panic!("unexpected {:?}", other),
+        }
+        assert!(matches!(
+            parse_command("biggest"),
+            CommandAction::Largest(words) if words.is_empty()
+        ));
+    }
+
+    #[test]
+    fn test_parse_filter() {
+        match parse_command("filter  size > 1G and path ~ '/a  b/'") {
+            CommandAction::Filter(Some(query)) => {
+                assert_eq!(query, "size > 1G and path ~ '/a  b/'")
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+        assert!(matches!(
+            parse_command("filter"),
+            CommandAction::Filter(None)
+        ));
+    }
+
     #[test]
     fn test_parse_view() {
         assert!(matches!(
@@ -449,5 +734,29 @@ mod tests {
             parse_command("d"),
             CommandAction::SwitchView(View::Duplicates)
         ));
+        assert!(matches!(
+            parse_command("trash"),
+            CommandAction::SwitchView(View::Trash)
+        ));
+        assert!(matches!(
+            parse_command("jobs"),
+            CommandAction::SwitchView(View::Jobs)
+        ));
+        assert!(matches!(
+            parse_command("df"),
+            CommandAction::SwitchView(View::Mounts)
+        ));
+        assert!(matches!(
+            parse_command("owners"),
+            CommandAction::SwitchView(View::Owners)
+        ));
+        assert!(matches!(
+            parse_command("types"),
+            CommandAction::SwitchView(View::Types)
+        ));
+        assert!(matches!(
+            parse_command("cleanup"),
+            CommandAction::SwitchView(View::Cleanup)
+        ));
     }
 }
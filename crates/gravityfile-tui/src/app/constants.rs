@@ -6,14 +6,23 @@ pub const PAGE_SIZE: usize = 10;
 /// Maximum number of duplicate groups to analyze.
 pub const MAX_DUPLICATE_GROUPS: usize = 100;
 
-/// Minimum file size for duplicate detection (1KB).
-pub const MIN_DUPLICATE_SIZE: u64 = 1024;
-
 /// Channel buffer size for scan results.
 pub const SCAN_CHANNEL_SIZE: usize = 100;
 
 /// Channel buffer size for analysis results.
 pub const ANALYSIS_CHANNEL_SIZE: usize = 10;
 
+/// Maximum number of entries kept in the undo journal.
+pub const UNDO_LOG_DEPTH: usize = 100;
+
+/// Pastes writing at least this many bytes are confirmed before running (100 MiB).
+pub const PLAN_REVIEW_BYTES: u64 = 100 * 1024 * 1024;
+
+/// Pastes expanding to at least this many steps are confirmed before running.
+pub const PLAN_REVIEW_STEPS: usize = 100;
+
+/// Number of entries ranked by the Largest view.
+pub const TOP_COUNT: usize = 100;
+
 /// Event loop tick interval in milliseconds.
 pub const TICK_INTERVAL_MS: u64 = 50;
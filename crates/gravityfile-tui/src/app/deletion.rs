@@ -44,6 +44,7 @@ pub fn start_deletion_with_mode(
         let mut deleted = 0;
         let mut failed = 0;
         let mut bytes_freed: u64 = 0;
+        let mut trashed = Vec::new();
 
         for (i, (path, size)) in items.iter().enumerate() {
             // Send progress update
@@ -65,6 +66,9 @@ pub fn start_deletion_with_mode(
                 Ok(Ok(())) => {
                     deleted += 1;
                     bytes_freed += size;
+                    if mode == DeletionMode::Trash {
+                        trashed.push(path.clone());
+                    }
                 }
                 Ok(Err(e)) => {
                     tracing::warn!("Failed to delete {:?}: {}", path, e);
@@ -96,6 +100,7 @@ pub fn start_deletion_with_mode(
                 deleted,
                 failed,
                 bytes_freed,
+                trashed,
             })
             .await;
     });
@@ -22,10 +22,15 @@ use ratatui::widgets::Widget;
 use ratatui::{DefaultTerminal, Frame};
 use tokio::sync::{RwLock, mpsc};
 
-use gravityfile_analyze::{AgeReport, DuplicateReport};
+use gravityfile_analyze::{
+    AgeReport, CleanupReport, DuplicateReport, FileTypeReport, OwnershipReport, Profile, Query,
+    TimeBasis, TypeBreakdown,
+};
 use gravityfile_core::{FileNode, FileTree};
 use gravityfile_ops::{
-    Conflict, CopyOptions, CopyResult, MoveOptions, MoveResult, OperationProgress, UndoLog,
+    Conflict, CopyOptions, FileOperation, JobManager, JobOutcome, JobState, LinkKind,
+    OperationProgress, OperationResult, OperationType, SyncMode, SyncOptions, SyncResult,
+    TrashResult, UndoLog, UndoableOperation,
 };
 use gravityfile_scan::ScanProgress;
 
@@ -34,19 +39,23 @@ use crate::event::{KeyAction, MouseAction};
 use crate::preview::PreviewState;
 use crate::search::SearchState;
 use crate::theme::Theme;
-use crate::ui::{TreeState, TreeView};
+use crate::ui::{TreeState, TreeView, format_size};
 
 use self::commands::{
     CommandAction, CommandInput, CommandKeyResult, LayoutCommand, SortCommand, ThemeCommand,
     parse_command,
 };
-use self::constants::{PAGE_SIZE, TICK_INTERVAL_MS};
+use self::constants::{PAGE_SIZE, TICK_INTERVAL_MS, UNDO_LOG_DEPTH};
 use self::input::{InputResult, InputState};
 use self::render::{RenderContext, render_app};
 use self::state::{
     AppMode, BookmarkListState, ClipboardMode, ClipboardState, DeletionProgress,
-    DuplicatesViewState, LayoutMode, PendingOperation, ScanResult, SelectedInfo, SettingsState,
-    SortMode, TabManager, UserSettings, View,
+    DuplicatesViewState, HistoryState, HygieneViewState, JobsViewState, LargestViewState,
+    LayoutMode, MountsViewState, OwnersViewState, PendingOperation, PendingReplay, PermissionEntry,
+    PermissionsState, PlanReviewState, RenameField, RenamePatternState, SHRED_CONFIRM_WORD,
+    ScanResult, SelectedInfo, SettingsState, ShredConfirmState, SortMode, SyncReviewState,
+    TabManager, TimelineViewState, TrashViewState, TreeFilter, TypeRow, TypesViewState,
+    UserSettings, View,
 };
 
 /// Application result type.
@@ -80,8 +89,10 @@ pub struct App {
     view: View,
     /// Color theme.
     theme: Theme,
-    /// Scanned file tree.
+    /// Scanned file tree, or its filtered copy while `filter` is set.
     tree: Option<FileTree>,
+    /// Query filter hiding non-matching entries of the tree.
+    filter: Option<TreeFilter>,
     /// Tree view state.
     tree_state: TreeState,
     /// Cached count of visible tree items (to avoid flatten on every nav).
@@ -90,10 +101,38 @@ pub struct App {
     duplicates: Option<DuplicateReport>,
     /// Age analysis report.
     age_report: Option<AgeReport>,
+    /// Ownership analysis report.
+    ownership: Option<OwnershipReport>,
+    /// Owners view state.
+    owners_state: OwnersViewState,
+    /// File-type analysis report.
+    file_types: Option<FileTypeReport>,
+    /// Types view state.
+    types_state: TypesViewState,
+    /// Cleanup candidates report.
+    cleanup: Option<CleanupReport>,
+    /// Selected cleanup candidate index.
+    selected_cleanup: usize,
+    /// Largest view state.
+    largest_state: LargestViewState,
+    /// Timeline view state.
+    timeline_state: TimelineViewState,
+    /// Hygiene view state.
+    hygiene_state: HygieneViewState,
     /// Duplicates view state (expanded groups, selected files).
     duplicates_state: DuplicatesViewState,
     /// Selected stale directory index.
     selected_stale_dir: usize,
+    /// Trash view state.
+    trash_state: TrashViewState,
+    /// Background job queue for pastes.
+    jobs: JobManager,
+    /// Finished background jobs.
+    job_rx: mpsc::UnboundedReceiver<JobOutcome>,
+    /// Jobs view state.
+    jobs_state: JobsViewState,
+    /// Mounts view state.
+    mounts_state: MountsViewState,
     /// Show details panel.
     show_details: bool,
     /// Error message to display.
@@ -103,8 +142,9 @@ pub struct App {
     /// Clipboard state for yank/cut/paste.
     clipboard: ClipboardState,
     /// Undo log for reversible operations.
-    #[allow(dead_code)]
     undo_log: UndoLog,
+    /// Undo or redo in flight, recorded in the journal once it completes.
+    pending_replay: Option<PendingReplay>,
     /// Layout mode (tree vs miller).
     layout_mode: LayoutMode,
     /// Miller columns state.
@@ -115,6 +155,8 @@ pub struct App {
     input_state: Option<InputState>,
     /// Current file operation progress (copy/move).
     operation_progress: Option<OperationProgress>,
+    /// Cancels the running paste; dropped when it completes.
+    operation_token: Option<gravityfile_ops::CancellationToken>,
     /// Pending conflict requiring resolution.
     pending_conflict: Option<Conflict>,
     /// Pending operation that needs conflict resolution before proceeding.
@@ -153,10 +195,28 @@ pub struct App {
     scan_on_startup: bool,
     /// User settings (persistent configuration).
     user_settings: UserSettings,
+    /// Profile selector from `--profile`, re-resolved for each scan root.
+    profile_selector: Option<String>,
+    /// Scan profile resolved for the current scan root.
+    profile: Profile,
+    /// Roots of a multi-root session; `path` is then their common ancestor.
+    scan_roots: Vec<PathBuf>,
     /// Settings modal state.
     settings_state: Option<SettingsState>,
     /// Bookmark list modal state.
     bookmark_list_state: Option<BookmarkListState>,
+    /// Undo history modal state.
+    history_state: Option<HistoryState>,
+    /// Paste waiting on (or under review in) the plan confirmation modal.
+    plan_review: Option<PlanReviewState>,
+    /// Sync waiting on (or under review in) the sync preview modal.
+    sync_review: Option<SyncReviewState>,
+    /// Pending shred awaiting typed confirmation.
+    shred_confirm: Option<ShredConfirmState>,
+    /// Permissions modal state.
+    permissions_state: Option<PermissionsState>,
+    /// Rename pattern modal state.
+    rename_pattern: Option<RenamePatternState>,
     /// Cached parent tree for Miller columns when at tree root.
     /// This allows showing the parent column even when navigated beyond the original scan root.
     cached_parent_tree: Option<FileTree>,
@@ -191,12 +251,50 @@ impl App {
     /// Create a new application with custom config.
     /// Immediately loads a quick directory listing for instant display,
     /// and optionally starts a full recursive scan based on config.
-    pub fn with_config(path: PathBuf, config: TuiConfig) -> Self {
+    pub fn with_config(path: PathBuf, mut config: TuiConfig) -> Self {
         // Load user settings from disk
         let user_settings = UserSettings::load();
 
+        // Open the persistent undo journal. An unreadable journal is left
+        // untouched on disk and this session falls back to an in-memory log.
+        let (undo_log, undo_error) = match gravityfile_ops::default_journal_path()
+            .map(|path| UndoLog::open(path, UNDO_LOG_DEPTH))
+        {
+            Some(Ok(log)) => (log, None),
+            Some(Err(e)) => (UndoLog::new(UNDO_LOG_DEPTH), Some(e)),
+            None => (UndoLog::new(UNDO_LOG_DEPTH), None),
+        };
+
+        // Resolve the scan profile. A broken profile file is reported and
+        // scans fall back to the defaults.
+        let (profile, profile_error) = match Profile::load(&path, config.profile.as_deref()) {
+            Ok(profile) => (profile, None),
+            Err(e) => (Profile::default(), Some(e.to_string())),
+        };
+
+        // Several roots are browsed from their common ancestor. A quick
+        // listing of that ancestor would show unrelated siblings, so the
+        // combined tree is scanned right away instead.
+        let scan_roots = if config.extra_roots.is_empty() {
+            Vec::new()
+        } else {
+            let mut roots = vec![path.clone()];
+            roots.append(&mut config.extra_roots);
+            roots
+        };
+        let path = if scan_roots.is_empty() {
+            path
+        } else {
+            config.scan_on_startup = Some(true);
+            gravityfile_scan::common_ancestor(&scan_roots)
+        };
+
         // Load quick tree immediately for instant display
-        let quick_tree = gravityfile_scan::quick_list(&path, None).ok();
+        let quick_tree = if scan_roots.is_empty() {
+            gravityfile_scan::quick_list(&path, None).ok()
+        } else {
+            None
+        };
 
         // Determine scan_on_startup: CLI flag (Some) overrides user settings; None defers to settings.
         let scan_on_startup = config
@@ -216,6 +314,7 @@ impl App {
         }
 
         let plugin_manager = Arc::new(RwLock::new(pm_inner));
+        let (jobs, job_rx) = JobManager::new(user_settings.job_concurrency);
 
         let mut app = Self {
             path: path.clone(),
@@ -230,18 +329,35 @@ impl App {
             cached_tree_len: 0,
             duplicates: None,
             age_report: None,
+            ownership: None,
+            owners_state: OwnersViewState::default(),
+            file_types: None,
+            types_state: TypesViewState::default(),
+            cleanup: None,
+            selected_cleanup: 0,
+            largest_state: LargestViewState::default(),
+            timeline_state: TimelineViewState::default(),
+            hygiene_state: HygieneViewState::default(),
+            filter: None,
             duplicates_state: DuplicatesViewState::new(),
             selected_stale_dir: 0,
+            trash_state: TrashViewState::default(),
+            jobs,
+            job_rx,
+            jobs_state: JobsViewState::default(),
+            mounts_state: MountsViewState::default(),
             show_details: true,
-            error: None,
+            error: undo_error.or(profile_error),
             marked: HashSet::new(),
             clipboard: ClipboardState::default(),
-            undo_log: UndoLog::new(100),
+            undo_log,
+            pending_replay: None,
             layout_mode: LayoutMode::default(),
             miller_state: crate::ui::MillerState::new(),
             cached_miller_len: 0,
             input_state: None,
             operation_progress: None,
+            operation_token: None,
             pending_conflict: None,
             pending_operation: None,
             operation_message: None,
@@ -261,8 +377,17 @@ impl App {
             preview_state: PreviewState::new(),
             scan_on_startup,
             user_settings,
+            profile_selector: config.profile,
+            profile,
+            scan_roots,
             settings_state: None,
             bookmark_list_state: None,
+            history_state: None,
+            plan_review: None,
+            sync_review: None,
+            shred_confirm: None,
+            permissions_state: None,
+            rename_pattern: None,
             cached_parent_tree: None,
             pending_suspend_command: None,
             scanned_cache: HashMap::new(),
@@ -368,12 +493,10 @@ impl App {
                         Event::Key(key_event) if key_event.kind == crossterm::event::KeyEventKind::Press => {
                             self.dispatch_key_event(key_event);
                         }
-                        Event::Mouse(mouse_event) => {
+                        Event::Mouse(mouse_event) if self.mode == AppMode::Normal => {
                             // Only handle mouse events in Normal mode for now
-                            if self.mode == AppMode::Normal {
-                                let action = MouseAction::from_mouse_event(mouse_event);
-                                self.handle_mouse_action(action);
-                            }
+                            let action = MouseAction::from_mouse_event(mouse_event);
+                            self.handle_mouse_action(action);
                         }
                         _ => {}
                     }
@@ -387,11 +510,9 @@ impl App {
                                     break;
                                 }
                             }
-                            Ok(Event::Mouse(mouse_event)) => {
-                                if self.mode == AppMode::Normal {
-                                    let action = MouseAction::from_mouse_event(mouse_event);
-                                    self.handle_mouse_action(action);
-                                }
+                            Ok(Event::Mouse(mouse_event)) if self.mode == AppMode::Normal => {
+                                let action = MouseAction::from_mouse_event(mouse_event);
+                                self.handle_mouse_action(action);
                             }
                             _ => {}
                         }
@@ -410,7 +531,12 @@ impl App {
                     self.needs_redraw = true;
                 }
 
-                _ = interval.tick(), if self.scan_progress.is_some() || self.deletion_progress.is_some() => {
+                Some(outcome) = self.job_rx.recv() => {
+                    self.handle_job_outcome(outcome);
+                    self.needs_redraw = true;
+                }
+
+                _ = interval.tick(), if self.scan_progress.is_some() || self.deletion_progress.is_some() || self.jobs.active_count() > 0 => {
                     // Periodic tick only when background work is active (avoids busy-waking CPU)
                     self.needs_redraw = true;
                 }
@@ -482,12 +608,10 @@ impl App {
                         Event::Key(key_event) if key_event.kind == crossterm::event::KeyEventKind::Press => {
                             self.dispatch_key_event(key_event);
                         }
-                        Event::Mouse(mouse_event) => {
+                        Event::Mouse(mouse_event) if self.mode == AppMode::Normal => {
                             // Only handle mouse events in Normal mode for now
-                            if self.mode == AppMode::Normal {
-                                let action = MouseAction::from_mouse_event(mouse_event);
-                                self.handle_mouse_action(action);
-                            }
+                            let action = MouseAction::from_mouse_event(mouse_event);
+                            self.handle_mouse_action(action);
                         }
                         _ => {}
                     }
@@ -501,11 +625,9 @@ impl App {
                                     break;
                                 }
                             }
-                            Ok(Event::Mouse(mouse_event)) => {
-                                if self.mode == AppMode::Normal {
-                                    let action = MouseAction::from_mouse_event(mouse_event);
-                                    self.handle_mouse_action(action);
-                                }
+                            Ok(Event::Mouse(mouse_event)) if self.mode == AppMode::Normal => {
+                                let action = MouseAction::from_mouse_event(mouse_event);
+                                self.handle_mouse_action(action);
                             }
                             _ => {}
                         }
@@ -524,7 +646,12 @@ impl App {
                     self.needs_redraw = true;
                 }
 
-                _ = interval.tick(), if self.scan_progress.is_some() || self.deletion_progress.is_some() => {
+                Some(outcome) = self.job_rx.recv() => {
+                    self.handle_job_outcome(outcome);
+                    self.needs_redraw = true;
+                }
+
+                _ = interval.tick(), if self.scan_progress.is_some() || self.deletion_progress.is_some() || self.jobs.active_count() > 0 => {
                     // Periodic tick only when background work is active (avoids busy-waking CPU)
                     self.needs_redraw = true;
                 }
@@ -576,12 +703,38 @@ impl App {
         // self.tree = None;
         self.duplicates = None;
         self.age_report = None;
+        self.ownership = None;
+        self.file_types = None;
+        self.cleanup = None;
         self.warnings.clear();
         self.selected_warning = 0;
         // Don't reset cached lengths - keep them for navigation
         // self.cached_tree_len = 0;
 
-        self.scan_rx = Some(scanning::start_scan(self.view_root.clone()));
+        // A multi-root session rescans all of its roots from their common
+        // ancestor; anywhere below it only the current directory is scanned.
+        let roots = match self.scan_roots.split_first() {
+            Some((first, rest))
+                if self.view_root == gravityfile_scan::common_ancestor(&self.scan_roots) =>
+            {
+                (first.clone(), rest.to_vec())
+            }
+            _ => (self.view_root.clone(), Vec::new()),
+        };
+
+        // Profiles are discovered from the scan root, so re-resolve them for
+        // every scan.
+        match Profile::load(&roots.0, self.profile_selector.as_deref()) {
+            Ok(profile) => self.profile = profile,
+            Err(e) => {
+                self.error = Some(e.to_string());
+                self.profile = Profile::default();
+            }
+        }
+
+        let mut config = self.profile.scan_config(&roots.0);
+        config.extra_roots = roots.1;
+        self.scan_rx = Some(scanning::start_scan(config));
     }
 
     /// Handle a scan result from the background task.
@@ -597,7 +750,7 @@ impl App {
                 // Update tree with partial results during scanning
                 // This allows the UI to show progressive updates as directories are scanned
                 let root_path = tree.root_path.clone();
-                self.tree = Some(tree);
+                self.tree = Some(self.filtered(tree));
 
                 // Preserve selection position if possible
                 if self.tree_state.expanded.is_empty() {
@@ -627,7 +780,7 @@ impl App {
                 // Cache the scanned tree for later restoration
                 self.scanned_cache.insert(root_path.clone(), tree.clone());
 
-                self.tree = Some(tree);
+                self.tree = Some(self.filtered(tree));
 
                 // Preserve tree state (selection, expanded) across rescans
                 // Only reset if this is a different root path
@@ -648,6 +801,13 @@ impl App {
 
                 // Update cached parent tree for Miller columns display
                 self.update_cached_parent();
+                if self.view == View::Largest {
+                    self.reload_largest();
+                } else if self.view == View::Timeline {
+                    self.reload_timeline();
+                } else if self.view == View::Hygiene {
+                    self.reload_hygiene();
+                }
 
                 // Clamp selection to valid range
                 if self.tree_state.selected >= self.cached_tree_len && self.cached_tree_len > 0 {
@@ -660,7 +820,12 @@ impl App {
 
                 // Start background analysis
                 self.analyzing = true;
-                self.scan_rx = Some(scanning::start_analysis(tree_for_analysis.clone()));
+                self.scan_rx = Some(scanning::start_analysis(
+                    tree_for_analysis.clone(),
+                    self.profile.duplicates.clone(),
+                    self.profile.age.clone(),
+                    self.profile.cleanup.clone(),
+                ));
 
                 // Dispatch scan complete hook (extract only the stats we need)
                 let pm = self.plugin_manager.clone();
@@ -693,9 +858,15 @@ impl App {
             ScanResult::AnalysisComplete {
                 duplicates,
                 age_report,
+                ownership,
+                file_types,
+                cleanup,
             } => {
                 self.duplicates = Some(duplicates);
                 self.age_report = Some(age_report);
+                self.ownership = Some(ownership);
+                self.file_types = Some(file_types);
+                self.cleanup = Some(cleanup);
                 self.analyzing = false;
                 self.scan_rx = None;
             }
@@ -706,10 +877,16 @@ impl App {
                 deleted,
                 failed,
                 bytes_freed,
+                trashed,
             } => {
                 self.deletion_progress = None;
                 self.scan_rx = None;
 
+                if !trashed.is_empty() {
+                    self.undo_log.record_trash(trashed);
+                    self.save_undo_log();
+                }
+
                 let (success, msg) = deletion::format_deletion_result(deleted, failed, bytes_freed);
                 self.deletion_message = Some((success, msg));
                 self.mode = AppMode::Normal;
@@ -720,6 +897,26 @@ impl App {
             ScanResult::OperationProgress(progress) => {
                 self.operation_progress = Some(progress);
             }
+            ScanResult::PlanReady(plan) => {
+                self.scan_rx = None;
+                let Some(mut review) = self.plan_review.take() else {
+                    // The review was cancelled while planning.
+                    return;
+                };
+                if PlanReviewState::requires_confirmation(&plan) {
+                    review.plan = Some(*plan);
+                    self.plan_review = Some(review);
+                } else {
+                    self.start_paste(review.operation, review.resolution);
+                }
+            }
+            ScanResult::SyncDiffReady(diff) => {
+                self.scan_rx = None;
+                if let Some(review) = &mut self.sync_review {
+                    review.diff = Some(*diff);
+                    review.scroll = 0;
+                }
+            }
             ScanResult::OperationConflict(conflict) => {
                 self.pending_conflict = Some(conflict);
                 self.mode = AppMode::ConflictResolution;
@@ -729,30 +926,36 @@ impl App {
                 succeeded,
                 failed,
                 bytes_processed: _,
+                undo,
             } => {
                 self.operation_progress = None;
+                self.operation_token = None;
                 self.scan_rx = None;
 
-                let action = match operation_type {
-                    gravityfile_ops::OperationType::Copy => "Copied",
-                    gravityfile_ops::OperationType::Move => "Moved",
-                    gravityfile_ops::OperationType::Delete => "Deleted",
-                    gravityfile_ops::OperationType::Rename => "Renamed",
-                    gravityfile_ops::OperationType::CreateFile => "Created file",
-                    gravityfile_ops::OperationType::CreateDirectory => "Created directory",
-                };
+                if let Some(operation) = undo {
+                    self.undo_log.record_operation(operation);
+                    self.save_undo_log();
+                }
+                // Only a fully successful undo/redo can be reversed again.
+                if let Some(replay) = self.pending_replay.take()
+                    && failed == 0
+                    && succeeded > 0
+                {
+                    match replay {
+                        PendingReplay::Undo(entry) => self.undo_log.record_undone(&entry),
+                        PendingReplay::Redo(entry) => self.undo_log.record_redone(&entry),
+                    };
+                    self.save_undo_log();
+                }
 
-                let success = failed == 0;
-                let msg = if success {
-                    format!("{} {} items", action, succeeded)
-                } else {
-                    format!("{} {} items, {} failed", action, succeeded, failed)
-                };
-                self.operation_message = Some((success, msg));
+                self.operation_message = Some(operation_summary(operation_type, succeeded, failed));
                 self.mode = AppMode::Normal;
 
                 // Refresh scan after operation
                 self.start_scan();
+                if self.view == View::Trash {
+                    self.reload_trash();
+                }
             }
         }
     }
@@ -793,7 +996,7 @@ impl App {
 
         // Sort children by size descending (same order as treemap layout)
         let mut children: Vec<&FileNode> = node.children.iter().collect();
-        children.sort_by(|a, b| b.size.cmp(&a.size));
+        children.sort_by_key(|c| std::cmp::Reverse(c.size));
 
         let child = children.get(self.treemap_state.selected)?;
         let path = root_path.join(&*child.name);
@@ -1112,6 +1315,18 @@ impl App {
             self.handle_bookmark_key(key_event);
         } else if self.mode == AppMode::BookmarkList {
             self.handle_bookmark_list_input(key_event);
+        } else if self.mode == AppMode::History {
+            self.handle_history_input(key_event);
+        } else if self.mode == AppMode::ConfirmPlan {
+            self.handle_plan_input(key_event);
+        } else if self.mode == AppMode::ConfirmSync {
+            self.handle_sync_input(key_event);
+        } else if self.mode == AppMode::ConfirmShred {
+            self.handle_shred_input(key_event);
+        } else if self.mode == AppMode::Permissions {
+            self.handle_permissions_input(key_event);
+        } else if self.mode == AppMode::RenamePattern {
+            self.handle_rename_pattern_input(key_event);
         } else if self.mode == AppMode::ConfirmBulkRename {
             self.handle_bulk_rename_input(key_event);
         } else if self.mode == AppMode::Visual {
@@ -1158,6 +1373,18 @@ impl App {
                 }
                 return;
             }
+            AppMode::ConfirmPurge => {
+                match action {
+                    KeyAction::Confirm | KeyAction::DrillDown | KeyAction::Yank => {
+                        self.purge_trash_targets();
+                    }
+                    KeyAction::Quit | KeyAction::Cancel => {
+                        self.mode = AppMode::Normal;
+                    }
+                    _ => {}
+                }
+                return;
+            }
             AppMode::Deleting => {
                 return;
             }
@@ -1175,13 +1402,52 @@ impl App {
                 // Handled in run loop via handle_conflict_key
                 return;
             }
-            AppMode::Copying | AppMode::Moving => {
-                // Operations in progress - ignore most input
+            AppMode::Syncing => {
+                // Operations in progress - only cancellation is accepted.
+                // Cancelled copies remove their partial files.
+                if matches!(action, KeyAction::Cancel | KeyAction::Quit)
+                    && let Some(token) = &self.operation_token
+                {
+                    token.cancel();
+                    self.operation_message = Some((false, "Cancelling...".to_string()));
+                }
                 return;
             }
             _ => {}
         }
 
+        if self.view == View::Trash && self.handle_trash_action(action) {
+            return;
+        }
+        if self.view == View::Jobs && self.handle_jobs_action(action) {
+            return;
+        }
+        if self.view == View::Mounts && self.handle_mounts_action(action) {
+            return;
+        }
+        if self.view == View::Owners && self.handle_owners_action(action) {
+            return;
+        }
+        if self.view == View::Types && self.handle_types_action(action) {
+            return;
+        }
+        if self.view == View::Cleanup && self.handle_cleanup_action(action) {
+            return;
+        }
+        if self.view == View::Largest && self.handle_largest_action(action) {
+            return;
+        }
+        if self.view == View::Timeline && self.handle_timeline_action(action) {
+            return;
+        }
+        if self.view == View::Hygiene && self.handle_hygiene_action(action) {
+            return;
+        }
+        if self.view == View::Age && action == KeyAction::ToggleLayout {
+            self.set_age_basis(self.profile.age.time_basis.next());
+            return;
+        }
+
         match action {
             KeyAction::Quit => {
                 self.mode = AppMode::Quit;
@@ -1204,6 +1470,16 @@ impl App {
                 if self.view == View::Treemap {
                     self.update_cached_treemap_len();
                     self.treemap_state.reset();
+                } else if self.view == View::Trash {
+                    self.reload_trash();
+                } else if self.view == View::Mounts {
+                    self.reload_mounts();
+                } else if self.view == View::Largest {
+                    self.reload_largest();
+                } else if self.view == View::Timeline {
+                    self.reload_timeline();
+                } else if self.view == View::Hygiene {
+                    self.reload_hygiene();
                 }
             }
             KeyAction::PrevView => {
@@ -1211,6 +1487,16 @@ impl App {
                 if self.view == View::Treemap {
                     self.update_cached_treemap_len();
                     self.treemap_state.reset();
+                } else if self.view == View::Trash {
+                    self.reload_trash();
+                } else if self.view == View::Mounts {
+                    self.reload_mounts();
+                } else if self.view == View::Largest {
+                    self.reload_largest();
+                } else if self.view == View::Timeline {
+                    self.reload_timeline();
+                } else if self.view == View::Hygiene {
+                    self.reload_hygiene();
                 }
             }
 
@@ -1222,26 +1508,20 @@ impl App {
             KeyAction::CloseDirTab => {
                 self.tab_manager.close_active_tab();
             }
-            KeyAction::NextDirTab => {
+            KeyAction::NextDirTab if self.tab_manager.len() > 1 => {
                 // Only switch tabs if there are multiple
-                if self.tab_manager.len() > 1 {
-                    self.tab_manager.next_tab();
-                    self.sync_from_active_tab();
-                }
+                self.tab_manager.next_tab();
+                self.sync_from_active_tab();
             }
-            KeyAction::PrevDirTab => {
+            KeyAction::PrevDirTab if self.tab_manager.len() > 1 => {
                 // Only switch tabs if there are multiple
-                if self.tab_manager.len() > 1 {
-                    self.tab_manager.prev_tab();
-                    self.sync_from_active_tab();
-                }
+                self.tab_manager.prev_tab();
+                self.sync_from_active_tab();
             }
-            KeyAction::DirTab(n) => {
+            KeyAction::DirTab(n) if self.tab_manager.len() > 1 => {
                 // Only switch tabs if there are multiple
-                if self.tab_manager.len() > 1 {
-                    self.tab_manager.switch_to_number(n as usize);
-                    self.sync_from_active_tab();
-                }
+                self.tab_manager.switch_to_number(n as usize);
+                self.sync_from_active_tab();
             }
 
             KeyAction::MoveUp => self.move_up(),
@@ -1304,17 +1584,15 @@ impl App {
             KeyAction::ClearMarks => {
                 self.marked.clear();
             }
-            KeyAction::EnterVisual => {
+            KeyAction::EnterVisual if self.view == View::Explorer => {
                 // Enter visual selection mode
                 // Only supported in Explorer view for now
-                if self.view == View::Explorer {
-                    let start_index = match self.layout_mode {
-                        LayoutMode::Tree => self.tree_state.selected,
-                        LayoutMode::Miller => self.miller_state.selected,
-                    };
-                    self.visual_state = Some(state::VisualState::new(start_index));
-                    self.mode = AppMode::Visual;
-                }
+                let start_index = match self.layout_mode {
+                    LayoutMode::Tree => self.tree_state.selected,
+                    LayoutMode::Miller => self.miller_state.selected,
+                };
+                self.visual_state = Some(state::VisualState::new(start_index));
+                self.mode = AppMode::Visual;
             }
 
             // Clipboard operations
@@ -1327,6 +1605,9 @@ impl App {
             KeyAction::Paste => {
                 self.paste_clipboard();
             }
+            KeyAction::PasteSymlink => {
+                self.paste_as_link(LinkKind::Symlink);
+            }
 
             // File operations
             KeyAction::Delete => {
@@ -1365,6 +1646,26 @@ impl App {
                         for path in paths_to_mark {
                             self.marked.insert(path);
                         }
+                    } else if self.view == View::Cleanup {
+                        if let Some(candidate) = self
+                            .cleanup
+                            .as_ref()
+                            .and_then(|r| r.candidates.get(self.selected_cleanup))
+                        {
+                            self.marked.insert(candidate.path.clone());
+                        }
+                    } else if self.view == View::Largest {
+                        if let Some(entry) = self.largest_state.selected_entry() {
+                            self.marked.insert(entry.path.clone());
+                        }
+                    } else if self.view == View::Hygiene {
+                        if let Some(entry) = self
+                            .hygiene_state
+                            .selected_entry()
+                            .filter(|e| e.kind().is_deletable())
+                        {
+                            self.marked.insert(entry.path.clone());
+                        }
                     } else if self.view == View::Errors {
                         // In Errors view, get the selected warning's path
                         if let Some(warning) = self.warnings.get(self.selected_warning) {
@@ -1399,6 +1700,9 @@ impl App {
             KeyAction::Undo => {
                 self.execute_undo();
             }
+            KeyAction::Redo => {
+                self.execute_redo();
+            }
 
             KeyAction::Confirm => {
                 if !self.marked.is_empty() {
@@ -1408,24 +1712,20 @@ impl App {
                 }
             }
 
-            KeyAction::DrillDown | KeyAction::OpenFile => {
-                if self.view == View::Explorer {
-                    // Check if selected item is a file or directory
-                    let is_file = self.is_selected_file();
-                    if is_file {
-                        self.open_selected_file();
-                    } else {
-                        match self.layout_mode {
-                            LayoutMode::Tree => self.drill_into_selected(),
-                            LayoutMode::Miller => self.drill_into_miller_selected(),
-                        }
+            KeyAction::DrillDown | KeyAction::OpenFile if self.view == View::Explorer => {
+                // Check if selected item is a file or directory
+                let is_file = self.is_selected_file();
+                if is_file {
+                    self.open_selected_file();
+                } else {
+                    match self.layout_mode {
+                        LayoutMode::Tree => self.drill_into_selected(),
+                        LayoutMode::Miller => self.drill_into_miller_selected(),
                     }
                 }
             }
-            KeyAction::NavigateBack => {
-                if self.view == View::Explorer {
-                    self.navigate_back();
-                }
+            KeyAction::NavigateBack if self.view == View::Explorer => {
+                self.navigate_back();
             }
 
             KeyAction::CommandMode => {
@@ -1449,7 +1749,10 @@ impl App {
                 self.preview_state.cycle_mode();
             }
             KeyAction::OpenSettings => {
-                self.settings_state = Some(SettingsState::new(self.user_settings.clone()));
+                self.settings_state = Some(SettingsState::new(
+                    self.user_settings.clone(),
+                    self.profile.clone(),
+                ));
                 self.mode = AppMode::Settings;
             }
             KeyAction::Refresh => {
@@ -1570,6 +1873,17 @@ impl App {
                     None
                 }
             }
+            View::Cleanup => self
+                .cleanup
+                .as_ref()
+                .and_then(|r| r.candidates.get(self.selected_cleanup))
+                .map(|c| c.path.clone()),
+            View::Largest => self.largest_state.selected_entry().map(|e| e.path.clone()),
+            View::Hygiene => self
+                .hygiene_state
+                .selected_entry()
+                .filter(|e| e.kind().is_deletable())
+                .map(|e| e.path.clone()),
             View::Errors => {
                 // Only allow marking broken symlinks for deletion
                 if let Some(warning) = self.warnings.get(self.selected_warning) {
@@ -1582,6 +1896,11 @@ impl App {
                     None
                 }
             }
+            View::Trash => {
+                self.trash_state.toggle_mark();
+                return;
+            }
+            View::Owners | View::Types | View::Timeline | View::Jobs | View::Mounts => return,
             View::Treemap => {
                 // For treemap, mark is based on what's currently under cursor
                 // This requires the treemap state which we don't have yet
@@ -1689,11 +2008,26 @@ impl App {
     /// - If cursor is on a directory → paste INTO that directory
     /// - If cursor is on a file → paste into the same parent directory
     fn paste_clipboard(&mut self) {
-        self.paste_with_resolution(None);
+        self.paste_with_resolution(None, None);
+    }
+
+    /// Create links to the clipboard items at the paste destination.
+    ///
+    /// Works for yanked and cut items alike; the clipboard is kept either way.
+    fn paste_as_link(&mut self, kind: LinkKind) {
+        if self.clipboard.is_empty() {
+            self.operation_message = Some((false, "Clipboard is empty".to_string()));
+            return;
+        }
+        self.paste_with_resolution(None, Some(kind));
     }
 
     /// Paste with a specific conflict resolution (or None to check for conflicts).
-    fn paste_with_resolution(&mut self, resolution: Option<gravityfile_ops::ConflictResolution>) {
+    fn paste_with_resolution(
+        &mut self,
+        resolution: Option<gravityfile_ops::ConflictResolution>,
+        link: Option<LinkKind>,
+    ) {
         if self.clipboard.is_empty() {
             return;
         }
@@ -1713,138 +2047,422 @@ impl App {
                 sources: sources.clone(),
                 destination: destination.clone(),
                 mode,
+                link,
             });
             self.pending_conflict = Some(conflict);
             self.mode = AppMode::ConflictResolution;
             return;
         }
 
-        match mode {
-            ClipboardMode::Copy => {
-                self.mode = AppMode::Copying;
-                let options = CopyOptions {
-                    conflict_resolution: resolution,
-                    preserve_timestamps: false,
-                };
-                let rx = gravityfile_ops::start_copy(
-                    sources,
-                    destination,
-                    options,
-                    gravityfile_ops::CancellationToken::new(),
-                );
-                self.scan_rx = Some(Self::adapt_copy_rx(rx));
-            }
+        self.plan_paste(
+            PendingOperation::Paste {
+                sources,
+                destination,
+                mode,
+                link,
+            },
+            resolution,
+        );
+    }
+
+    /// Compute the dry-run plan for a paste in the background.
+    ///
+    /// The plan confirmation modal shows "planning" until the result arrives;
+    /// small, clean pastes then start without asking.
+    fn plan_paste(
+        &mut self,
+        operation: PendingOperation,
+        resolution: Option<gravityfile_ops::ConflictResolution>,
+    ) {
+        let PendingOperation::Paste {
+            sources,
+            destination,
+            mode,
+            link,
+        } = &operation;
+        let file_op = match mode {
+            _ if let Some(kind) = link => {
+                FileOperation::link(sources.clone(), destination.clone(), *kind)
+            }
+            ClipboardMode::Copy => FileOperation::copy(sources.clone(), destination.clone()),
+            ClipboardMode::Cut => FileOperation::move_to(sources.clone(), destination.clone()),
+            ClipboardMode::Empty => return,
+        };
+
+        let (tx, rx) = mpsc::channel(1);
+        tokio::task::spawn_blocking(move || {
+            let plan = gravityfile_ops::plan_operation(&file_op, resolution);
+            let _ = tx.blocking_send(ScanResult::PlanReady(Box::new(plan)));
+        });
+        self.scan_rx = Some(rx);
+        self.plan_review = Some(PlanReviewState::new(operation, resolution));
+        self.mode = AppMode::ConfirmPlan;
+    }
+
+    /// Start a paste without further checks.
+    fn start_paste(
+        &mut self,
+        operation: PendingOperation,
+        resolution: Option<gravityfile_ops::ConflictResolution>,
+    ) {
+        let PendingOperation::Paste {
+            sources,
+            destination,
+            mode,
+            link,
+        } = operation;
+        let (operation, verb) = match mode {
+            _ if let Some(kind) = link => {
+                (FileOperation::link(sources, destination, kind), "Linking")
+            }
+            ClipboardMode::Copy => (FileOperation::copy(sources, destination), "Copying"),
             ClipboardMode::Cut => {
-                self.mode = AppMode::Moving;
-                let options = MoveOptions {
-                    conflict_resolution: resolution,
-                };
-                let rx = gravityfile_ops::start_move(
-                    sources,
-                    destination,
-                    options,
-                    gravityfile_ops::CancellationToken::new(),
-                );
-                self.scan_rx = Some(Self::adapt_move_rx(rx));
                 self.clipboard.clear();
+                (FileOperation::move_to(sources, destination), "Moving")
             }
-            ClipboardMode::Empty => {}
-        }
+            ClipboardMode::Empty => return,
+        };
+        let options = CopyOptions {
+            conflict_resolution: resolution,
+            ..self.user_settings.copy_options()
+        };
+        self.mode = AppMode::Normal;
+        let id = self.jobs.submit(operation, options);
+        let queued = self
+            .jobs
+            .job(id)
+            .is_some_and(|job| job.state == JobState::Queued);
+        let msg = if queued {
+            format!("Queued job #{} (see Jobs view)", id)
+        } else {
+            format!("{} in background as job #{} (see Jobs view)", verb, id)
+        };
+        self.operation_message = Some((true, msg));
     }
 
-    /// Check if pasting would cause a conflict with an existing file.
-    fn check_paste_conflict(&self, sources: &[PathBuf], destination: &Path) -> Option<Conflict> {
-        use gravityfile_ops::ConflictKind;
-
-        for source in sources {
-            if let Some(filename) = source.file_name() {
-                let dest_path = destination.join(filename);
+    /// Ask for typed confirmation before shredding the marked (or selected) items.
+    fn start_shred_confirm(&mut self, passes: u32) {
+        let paths: Vec<PathBuf> = if self.marked.is_empty() {
+            self.get_selected_path().into_iter().collect()
+        } else {
+            self.marked.iter().cloned().collect()
+        };
+        if paths.is_empty() {
+            self.operation_message = Some((false, "Nothing selected to shred".to_string()));
+            return;
+        }
 
-                // Check if destination already exists
-                if dest_path.exists() {
-                    // Determine conflict kind
-                    let kind = if source == &dest_path {
-                        // Same file - source and destination are identical
-                        ConflictKind::SameFile
-                    } else if dest_path.is_dir() {
-                        ConflictKind::DirectoryExists
-                    } else {
-                        ConflictKind::FileExists
-                    };
+        let warnings = gravityfile_ops::shred_warnings(&paths);
+        let targets = paths
+            .into_iter()
+            .map(|p| {
+                let size = self.get_path_size(&p).unwrap_or(0);
+                (p, size)
+            })
+            .collect();
+        self.shred_confirm = Some(ShredConfirmState {
+            targets,
+            passes,
+            warnings,
+            input: InputState::new(),
+        });
+        self.mode = AppMode::ConfirmShred;
+    }
 
-                    return Some(Conflict::new(source.clone(), dest_path, kind));
+    /// Handle typing in the shred confirmation modal.
+    fn handle_shred_input(&mut self, key: crossterm::event::KeyEvent) {
+        let Some(state) = &mut self.shred_confirm else {
+            self.mode = AppMode::Normal;
+            return;
+        };
+        match state.input.handle_key(key) {
+            InputResult::Continue => {}
+            InputResult::Cancel => {
+                self.shred_confirm = None;
+                self.mode = AppMode::Normal;
+            }
+            InputResult::Submit(value) => {
+                if value.trim() != SHRED_CONFIRM_WORD {
+                    state.input.set_error(format!(
+                        "Type \"{}\" exactly to confirm",
+                        SHRED_CONFIRM_WORD
+                    ));
+                    return;
                 }
+                let Some(state) = self.shred_confirm.take() else {
+                    return;
+                };
+                let targets: Vec<PathBuf> = state.targets.into_iter().map(|(p, _)| p).collect();
+                self.marked.clear();
+                self.mode = AppMode::Normal;
+                let id = self.jobs.submit(
+                    FileOperation::shred(targets, state.passes),
+                    CopyOptions::default(),
+                );
+                self.operation_message = Some((
+                    true,
+                    format!("Shredding in background as job #{} (see Jobs view)", id),
+                ));
             }
         }
-        None
     }
 
-    /// Get the appropriate paste destination based on current selection.
-    /// - If selected item is a directory → return that directory
-    /// - If selected item is a file → return the parent directory
-    /// - If nothing selected → return view_root
-    fn get_paste_destination(&self) -> PathBuf {
-        // Try to get the currently selected item
-        if let Some(selected_path) = self.get_selected_path() {
-            // Check if the selected item is a directory
-            if selected_path.is_dir() {
-                return selected_path;
-            } else {
-                // It's a file, use its parent directory
-                if let Some(parent) = selected_path.parent() {
-                    return parent.to_path_buf();
-                }
-            }
+    /// Marked items, or the selected one when nothing is marked.
+    fn selection_targets(&self) -> Vec<PathBuf> {
+        if self.marked.is_empty() {
+            self.get_selected_path().into_iter().collect()
+        } else {
+            let mut paths: Vec<PathBuf> = self.marked.iter().cloned().collect();
+            paths.sort();
+            paths
         }
-        // Default to view_root if nothing selected
-        self.view_root.clone()
     }
 
-    /// Adapt copy result channel to ScanResult channel.
-    fn adapt_copy_rx(mut rx: mpsc::Receiver<CopyResult>) -> mpsc::Receiver<ScanResult> {
-        let (tx, result_rx) = mpsc::channel(100);
-        tokio::spawn(async move {
-            while let Some(result) = rx.recv().await {
-                let scan_result = match result {
-                    CopyResult::Progress(p) => ScanResult::OperationProgress(p),
-                    CopyResult::Conflict(c) => ScanResult::OperationConflict(c),
-                    CopyResult::Complete(c) => ScanResult::OperationComplete {
-                        operation_type: c.operation_type,
-                        succeeded: c.succeeded,
-                        failed: c.failed,
-                        bytes_processed: c.bytes_processed,
-                    },
-                };
-                if tx.send(scan_result).await.is_err() {
-                    break;
-                }
-            }
+    /// Open the permissions modal for the selection.
+    fn start_permissions(&mut self, mode: Option<String>, recursive: bool) {
+        let entries: Vec<PermissionEntry> = self
+            .selection_targets()
+            .into_iter()
+            .filter_map(permission_entry)
+            .collect();
+        if entries.is_empty() {
+            self.operation_message = Some((false, "Nothing selected".to_string()));
+            return;
+        }
+        let input = match mode {
+            Some(mode) => InputState::with_initial(&mode),
+            None => InputState::new(),
+        };
+        self.permissions_state = Some(PermissionsState {
+            entries,
+            input,
+            scope: Default::default(),
+            recursive,
         });
-        result_rx
+        self.mode = AppMode::Permissions;
     }
 
-    /// Adapt move result channel to ScanResult channel.
-    fn adapt_move_rx(mut rx: mpsc::Receiver<MoveResult>) -> mpsc::Receiver<ScanResult> {
-        let (tx, result_rx) = mpsc::channel(100);
-        tokio::spawn(async move {
-            while let Some(result) = rx.recv().await {
-                let scan_result = match result {
-                    MoveResult::Progress(p) => ScanResult::OperationProgress(p),
-                    MoveResult::Conflict(c) => ScanResult::OperationConflict(c),
-                    MoveResult::Complete(c) => ScanResult::OperationComplete {
-                        operation_type: c.inner.operation_type,
-                        succeeded: c.inner.succeeded,
-                        failed: c.inner.failed,
-                        bytes_processed: c.inner.bytes_processed,
-                    },
-                };
-                if tx.send(scan_result).await.is_err() {
-                    break;
-                }
-            }
-        });
-        result_rx
-    }
+    /// Handle keys in the permissions modal.
+    fn handle_permissions_input(&mut self, key: crossterm::event::KeyEvent) {
+        use crossterm::event::{KeyCode, KeyModifiers};
+
+        let Some(state) = &mut self.permissions_state else {
+            self.mode = AppMode::Normal;
+            return;
+        };
+        match (key.code, key.modifiers) {
+            (KeyCode::Tab, _) => {
+                state.scope = state.scope.next();
+                return;
+            }
+            (KeyCode::Char('r'), KeyModifiers::CONTROL) => {
+                state.recursive = !state.recursive;
+                return;
+            }
+            _ => {}
+        }
+        match state.input.handle_key(key) {
+            InputResult::Continue => {}
+            InputResult::Cancel => {
+                self.permissions_state = None;
+                self.mode = AppMode::Normal;
+            }
+            InputResult::Submit(_) => {
+                let spec = match state.spec() {
+                    Ok(spec) => spec,
+                    Err(e) => {
+                        state.input.set_error(e);
+                        return;
+                    }
+                };
+                let targets = state.entries.iter().map(|e| e.path.clone()).collect();
+                self.permissions_state = None;
+                self.marked.clear();
+                self.mode = AppMode::Normal;
+                let id = self
+                    .jobs
+                    .submit(FileOperation::chmod(targets, spec), CopyOptions::default());
+                self.operation_message =
+                    Some((true, format!("Changing permissions (job #{})", id)));
+            }
+        }
+    }
+
+    /// Open the rename pattern modal for the selection.
+    fn start_rename_pattern(&mut self, find: &str, replace: &str) {
+        let paths = self.selection_targets();
+        if paths.is_empty() {
+            self.operation_message = Some((false, "Nothing selected".to_string()));
+            return;
+        }
+        self.rename_pattern = Some(RenamePatternState::new(paths, find, replace));
+        self.mode = AppMode::RenamePattern;
+    }
+
+    /// Handle keys in the rename pattern modal.
+    fn handle_rename_pattern_input(&mut self, key: crossterm::event::KeyEvent) {
+        use crossterm::event::{KeyCode, KeyModifiers};
+
+        let Some(state) = &mut self.rename_pattern else {
+            self.mode = AppMode::Normal;
+            return;
+        };
+        match (key.code, key.modifiers) {
+            (KeyCode::Tab, _) | (KeyCode::BackTab, _) => {
+                state.focus = match state.focus {
+                    RenameField::Find => RenameField::Replace,
+                    RenameField::Replace => RenameField::Find,
+                };
+                return;
+            }
+            (KeyCode::Char('t'), KeyModifiers::CONTROL) => {
+                state.case = state.case.next();
+                state.refresh();
+                return;
+            }
+            (KeyCode::Up, _) => {
+                state.offset = state.offset.saturating_sub(1);
+                return;
+            }
+            (KeyCode::Down, _) => {
+                if state.offset + 1 < state.paths.len() {
+                    state.offset += 1;
+                }
+                return;
+            }
+            _ => {}
+        }
+        match state.input_mut().handle_key(key) {
+            InputResult::Continue => state.refresh(),
+            InputResult::Cancel => {
+                self.rename_pattern = None;
+                self.mode = AppMode::Normal;
+            }
+            InputResult::Submit(_) => {
+                if let Err(e) = &state.preview {
+                    let e = e.clone();
+                    state.input_mut().set_error(e);
+                    return;
+                }
+                let problems = state.problems();
+                if problems > 0 {
+                    state
+                        .input_mut()
+                        .set_error(format!("{} names collide or are invalid", problems));
+                    return;
+                }
+                let renames = state.ready();
+                if renames.is_empty() {
+                    state.input_mut().set_error("No names change");
+                    return;
+                }
+                let count = renames.len();
+                self.rename_pattern = None;
+                self.marked.clear();
+                self.mode = AppMode::Normal;
+                let id = self
+                    .jobs
+                    .submit(FileOperation::batch_rename(renames), CopyOptions::default());
+                self.operation_message =
+                    Some((true, format!("Renaming {} items (job #{})", count, id)));
+            }
+        }
+    }
+
+    /// Change owner and/or group of the selection in the background.
+    fn start_chown(&mut self, spec: &str, recursive: bool) {
+        let spec = match gravityfile_ops::ChownSpec::parse(spec, recursive) {
+            Ok(spec) => spec,
+            Err(e) => {
+                self.operation_message = Some((false, e));
+                return;
+            }
+        };
+        let targets = self.selection_targets();
+        if targets.is_empty() {
+            self.operation_message = Some((false, "Nothing selected".to_string()));
+            return;
+        }
+        self.marked.clear();
+        let id = self
+            .jobs
+            .submit(FileOperation::chown(targets, spec), CopyOptions::default());
+        self.operation_message = Some((true, format!("Changing owner (job #{})", id)));
+    }
+
+    /// Record a finished background job and refresh what it touched.
+    fn handle_job_outcome(&mut self, outcome: JobOutcome) {
+        if let Some(operation) = outcome.undo {
+            self.undo_log.record_operation(operation);
+            self.save_undo_log();
+        }
+
+        let complete = &outcome.complete;
+        let (success, msg) = if outcome.state == JobState::Cancelled {
+            (false, format!("Job #{} cancelled", outcome.id))
+        } else {
+            let (success, msg) =
+                operation_summary(complete.operation_type, complete.succeeded, complete.failed);
+            (success, format!("Job #{}: {}", outcome.id, msg))
+        };
+        self.operation_message = Some((success, msg));
+
+        // Only refresh when nothing else is using the result channel, so a
+        // finishing job never interrupts a modal or running operation. A job
+        // cancelled before doing anything changed nothing.
+        let changed = outcome.state != JobState::Cancelled || complete.succeeded > 0;
+        if changed && self.mode == AppMode::Normal && self.scan_rx.is_none() {
+            self.start_scan();
+        }
+    }
+
+    /// Check if pasting would cause a conflict with an existing file.
+    fn check_paste_conflict(&self, sources: &[PathBuf], destination: &Path) -> Option<Conflict> {
+        use gravityfile_ops::ConflictKind;
+
+        for source in sources {
+            if let Some(filename) = source.file_name() {
+                let dest_path = destination.join(filename);
+
+                // Check if destination already exists
+                if dest_path.exists() {
+                    // Determine conflict kind
+                    let kind = if source == &dest_path {
+                        // Same file - source and destination are identical
+                        ConflictKind::SameFile
+                    } else if dest_path.is_dir() {
+                        ConflictKind::DirectoryExists
+                    } else {
+                        ConflictKind::FileExists
+                    };
+
+                    return Some(Conflict::new(source.clone(), dest_path, kind));
+                }
+            }
+        }
+        None
+    }
+
+    /// Get the appropriate paste destination based on current selection.
+    /// - If selected item is a directory → return that directory
+    /// - If selected item is a file → return the parent directory
+    /// - If nothing selected → return view_root
+    fn get_paste_destination(&self) -> PathBuf {
+        // Try to get the currently selected item
+        if let Some(selected_path) = self.get_selected_path() {
+            // Check if the selected item is a directory
+            if selected_path.is_dir() {
+                return selected_path;
+            } else {
+                // It's a file, use its parent directory
+                if let Some(parent) = selected_path.parent() {
+                    return parent.to_path_buf();
+                }
+            }
+        }
+        // Default to view_root if nothing selected
+        self.view_root.clone()
+    }
 
     /// Handle key press during conflict resolution mode.
     fn handle_conflict_key(&mut self, event: crossterm::event::KeyEvent) {
@@ -1918,6 +2536,7 @@ impl App {
                         sources,
                         destination: _,
                         mode,
+                        link,
                     } => {
                         // Temporarily set clipboard to resume the paste
                         let old_clipboard = self.clipboard.clone();
@@ -1925,11 +2544,11 @@ impl App {
                         self.clipboard.mode = mode;
 
                         // Execute paste with the chosen resolution
-                        self.paste_with_resolution(Some(res));
+                        self.paste_with_resolution(Some(res), link);
 
-                        // For Copy mode, restore clipboard after paste starts
+                        // For Copy mode and links, restore clipboard after paste starts
                         // For Cut mode, the paste_with_resolution already clears it
-                        if mode == ClipboardMode::Copy {
+                        if mode == ClipboardMode::Copy || link.is_some() {
                             self.clipboard = old_clipboard;
                         }
                     }
@@ -2031,56 +2650,750 @@ impl App {
                 self.update_cached_miller_len();
                 self.preview_state.content = crate::preview::PreviewContent::Empty;
 
-                // Update the active tab view_root but keep the tree
-                if let Some(tab) = self.tab_manager.active_tab_mut() {
-                    tab.view_root = self.view_root.clone();
+                // Update the active tab view_root but keep the tree
+                if let Some(tab) = self.tab_manager.active_tab_mut() {
+                    tab.view_root = self.view_root.clone();
+                }
+            } else {
+                // Going to a new location - use quick_list for immediate display
+                // Don't auto-scan; user can press R to scan if needed
+                self.path = path.clone();
+                self.view_root = path.clone();
+                self.view_history.clear();
+                self.forward_history.clear();
+                self.tree_state = TreeState::new(self.path.clone());
+                self.miller_state.reset();
+                self.marked.clear();
+                self.clipboard.clear();
+                self.duplicates = None;
+                self.age_report = None;
+                self.ownership = None;
+                self.file_types = None;
+                self.cleanup = None;
+                self.filter = None;
+                self.warnings.clear();
+                self.preview_state.content = crate::preview::PreviewContent::Empty;
+                self.scan_progress = None;
+
+                // Load quick tree for immediate display
+                self.tree = gravityfile_scan::quick_list(&path, None).ok();
+                self.update_cached_tree_len();
+                self.update_cached_miller_len();
+
+                // Update the active tab
+                if let Some(tab) = self.tab_manager.active_tab_mut() {
+                    tab.path = self.path.clone();
+                    tab.view_root = self.view_root.clone();
+                    tab.label = self
+                        .path
+                        .file_name()
+                        .map(|n| n.to_string_lossy().to_string())
+                        .unwrap_or_else(|| self.path.to_string_lossy().to_string());
+                    tab.history.clear();
+                }
+
+                // Don't auto-start scan - user can press R when ready
+            }
+        } else {
+            self.error = Some(format!("Not a directory: {}", path.display()));
+        }
+    }
+
+    /// Execute undo of the last operation.
+    fn execute_undo(&mut self) {
+        match self.undo_log.peek() {
+            Some(entry) => self.undo_entry(entry.id),
+            None => self.operation_message = Some((false, "Nothing to undo".to_string())),
+        }
+    }
+
+    /// Undo the journal entry with the given ID.
+    ///
+    /// The entry stays in the journal if the filesystem no longer matches
+    /// what was recorded.
+    fn undo_entry(&mut self, id: u64) {
+        if let Some(Err(reason)) = self.undo_log.get(id).map(|e| e.verify()) {
+            self.operation_message = Some((false, format!("Cannot undo: {}", reason)));
+            return;
+        }
+        let Some(entry) = self.undo_log.take(id) else {
+            return;
+        };
+        self.save_undo_log();
+
+        self.operation_message = Some((true, format!("Undoing: {}", entry.description)));
+        self.pending_replay = Some(PendingReplay::Undo(entry.clone()));
+        self.scan_rx = Some(Self::adapt_operation_rx(gravityfile_ops::execute_undo(
+            entry,
+        )));
+    }
+
+    /// Redo the most recently undone operation.
+    fn execute_redo(&mut self) {
+        match self.undo_log.peek_redo() {
+            Some(entry) => self.redo_entry(entry.id),
+            None => self.operation_message = Some((false, "Nothing to redo".to_string())),
+        }
+    }
+
+    /// Redo the redo-stack entry with the given ID.
+    fn redo_entry(&mut self, id: u64) {
+        if let Some(Err(reason)) = self.undo_log.get_redo(id).map(|e| e.verify()) {
+            self.operation_message = Some((false, format!("Cannot redo: {}", reason)));
+            return;
+        }
+        let Some(entry) = self.undo_log.take_redo(id) else {
+            return;
+        };
+        self.save_undo_log();
+
+        self.operation_message = Some((true, format!("Redoing: {}", entry.description)));
+        self.pending_replay = Some(PendingReplay::Redo(entry.clone()));
+        self.scan_rx = Some(Self::adapt_operation_rx(gravityfile_ops::execute_redo(
+            entry,
+        )));
+    }
+
+    /// Write the undo log to its journal, reporting failures in the status bar.
+    fn save_undo_log(&mut self) {
+        if let Err(e) = self.undo_log.save() {
+            self.operation_message = Some((false, e));
+        }
+    }
+
+    /// Open the undo history modal.
+    fn show_history(&mut self) {
+        self.history_state = Some(HistoryState::new(
+            self.undo_log.iter(),
+            self.undo_log.redo_iter(),
+        ));
+        self.mode = AppMode::History;
+    }
+
+    /// Handle input in the undo history modal.
+    fn handle_history_input(&mut self, key: crossterm::event::KeyEvent) {
+        use crossterm::event::KeyCode;
+
+        let Some(state) = &mut self.history_state else {
+            self.mode = AppMode::Normal;
+            return;
+        };
+
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => {
+                self.history_state = None;
+                self.mode = AppMode::Normal;
+            }
+            KeyCode::Char('j') | KeyCode::Down => state.move_down(),
+            KeyCode::Char('k') | KeyCode::Up => state.move_up(),
+            KeyCode::Char('u') | KeyCode::Enter => {
+                let Some(row) = state.selected_row() else {
+                    return;
+                };
+                let action = if row.redo { "redo" } else { "undo" };
+                if let Err(reason) = &row.status {
+                    self.operation_message =
+                        Some((false, format!("Cannot {}: {}", action, reason)));
+                    return;
+                }
+                let (id, redo) = (row.id, row.redo);
+                self.history_state = None;
+                self.mode = AppMode::Normal;
+                if redo {
+                    self.redo_entry(id);
+                } else {
+                    self.undo_entry(id);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Handle input in the plan confirmation modal.
+    fn handle_plan_input(&mut self, key: crossterm::event::KeyEvent) {
+        use crossterm::event::KeyCode;
+
+        let Some(review) = &mut self.plan_review else {
+            self.mode = AppMode::Normal;
+            return;
+        };
+
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('n') => {
+                // Dropping the receiver discards a plan that is still computing.
+                self.plan_review = None;
+                self.scan_rx = None;
+                self.mode = AppMode::Normal;
+                self.operation_message = Some((true, "Paste cancelled".to_string()));
+            }
+            KeyCode::Char('j') | KeyCode::Down => review.scroll_down(),
+            KeyCode::Char('k') | KeyCode::Up => review.scroll_up(),
+            KeyCode::Char('y') | KeyCode::Enter if review.plan.is_some() => {
+                if let Some(review) = self.plan_review.take() {
+                    self.start_paste(review.operation, review.resolution);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Open the sync preview for the current directory and the next tab's.
+    fn start_sync_review(&mut self, mode: SyncMode) {
+        let tabs = self.tab_manager.tabs();
+        if tabs.len() < 2 {
+            self.operation_message = Some((false, "Open a second tab to sync with".to_string()));
+            return;
+        }
+        let next = (self.tab_manager.active_index() + 1) % tabs.len();
+        let destination = tabs[next].view_root.clone();
+        if destination.starts_with(&self.view_root) || self.view_root.starts_with(&destination) {
+            self.operation_message = Some((
+                false,
+                "Cannot sync a directory with its own parent or child".to_string(),
+            ));
+            return;
+        }
+
+        let options = SyncOptions {
+            mode,
+            copy: self.user_settings.copy_options(),
+            ..SyncOptions::default()
+        };
+        self.sync_review = Some(SyncReviewState::new(
+            self.view_root.clone(),
+            destination,
+            options,
+        ));
+        self.compute_sync_diff();
+        self.mode = AppMode::ConfirmSync;
+    }
+
+    /// Compare the two trees of the pending sync in the background.
+    fn compute_sync_diff(&mut self) {
+        let Some(review) = &mut self.sync_review else {
+            return;
+        };
+        review.diff = None;
+        let source = review.source.clone();
+        let destination = review.destination.clone();
+        let options = review.options.clone();
+
+        let (tx, rx) = mpsc::channel(1);
+        tokio::task::spawn_blocking(move || {
+            let diff = gravityfile_ops::diff_trees(&source, &destination, &options);
+            let _ = tx.blocking_send(ScanResult::SyncDiffReady(Box::new(diff)));
+        });
+        self.scan_rx = Some(rx);
+    }
+
+    /// Handle input in the sync preview modal.
+    fn handle_sync_input(&mut self, key: crossterm::event::KeyEvent) {
+        use crossterm::event::KeyCode;
+
+        let Some(review) = &mut self.sync_review else {
+            self.mode = AppMode::Normal;
+            return;
+        };
+
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('n') => {
+                // Dropping the receiver discards a diff that is still computing.
+                self.sync_review = None;
+                self.scan_rx = None;
+                self.mode = AppMode::Normal;
+                self.operation_message = Some((true, "Sync cancelled".to_string()));
+            }
+            KeyCode::Char('j') | KeyCode::Down => review.scroll_down(),
+            KeyCode::Char('k') | KeyCode::Up => review.scroll_up(),
+            KeyCode::Char('m') => {
+                review.options.mode = match review.options.mode {
+                    SyncMode::Mirror => SyncMode::TwoWay,
+                    SyncMode::TwoWay => SyncMode::Mirror,
+                };
+                self.compute_sync_diff();
+            }
+            KeyCode::Char('h') => {
+                review.options.compare_hash = !review.options.compare_hash;
+                self.compute_sync_diff();
+            }
+            KeyCode::Char('d') => review.options.delete_extras = !review.options.delete_extras,
+            KeyCode::Char('y') | KeyCode::Enter if review.diff.is_some() => {
+                if review.pending() == 0 {
+                    self.sync_review = None;
+                    self.mode = AppMode::Normal;
+                    self.operation_message = Some((true, "Already in sync".to_string()));
+                } else if let Some(review) = self.sync_review.take()
+                    && let Some(diff) = review.diff
+                {
+                    let token = gravityfile_ops::CancellationToken::new();
+                    let rx = gravityfile_ops::start_sync(diff, review.options, token.clone());
+                    self.scan_rx = Some(Self::adapt_sync_rx(rx));
+                    self.operation_token = Some(token);
+                    self.mode = AppMode::Syncing;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Adapt sync result channel to ScanResult channel.
+    ///
+    /// Syncs overwrite and delete in place, so they are not recorded for undo.
+    fn adapt_sync_rx(mut rx: mpsc::Receiver<SyncResult>) -> mpsc::Receiver<ScanResult> {
+        let (tx, result_rx) = mpsc::channel(100);
+        tokio::spawn(async move {
+            while let Some(result) = rx.recv().await {
+                let scan_result = match result {
+                    SyncResult::Progress(p) => ScanResult::OperationProgress(p),
+                    SyncResult::Conflict(c) => ScanResult::OperationConflict(c),
+                    SyncResult::Complete(c) => ScanResult::OperationComplete {
+                        operation_type: c.operation_type,
+                        succeeded: c.succeeded,
+                        failed: c.failed,
+                        bytes_processed: c.bytes_processed,
+                        undo: None,
+                    },
+                };
+                if tx.send(scan_result).await.is_err() {
+                    break;
+                }
+            }
+        });
+        result_rx
+    }
+
+    /// Adapt unified operation results (e.g. from undo) to ScanResult.
+    fn adapt_operation_rx(mut rx: mpsc::Receiver<OperationResult>) -> mpsc::Receiver<ScanResult> {
+        let (tx, adapted_rx) = mpsc::channel(100);
+        tokio::spawn(async move {
+            while let Some(result) = rx.recv().await {
+                let scan_result = match result {
+                    OperationResult::Progress(p) => ScanResult::OperationProgress(p),
+                    OperationResult::Conflict(c) => ScanResult::OperationConflict(c),
+                    OperationResult::Complete(c) => ScanResult::OperationComplete {
+                        operation_type: c.operation_type,
+                        succeeded: c.succeeded,
+                        failed: c.failed,
+                        bytes_processed: c.bytes_processed,
+                        undo: None,
+                    },
+                };
+                if tx.send(scan_result).await.is_err() {
+                    break;
+                }
+            }
+        });
+        adapted_rx
+    }
+
+    /// Reload the trash listing for the Trash view.
+    fn reload_trash(&mut self) {
+        match gravityfile_ops::list_trash() {
+            Ok(entries) => self.trash_state.set_entries(entries),
+            Err(e) => {
+                self.trash_state.entries = None;
+                self.trash_state.error = Some(e);
+            }
+        }
+    }
+
+    /// Handle keys that behave differently in the Trash view.
+    ///
+    /// Returns `true` if the action was consumed.
+    fn handle_trash_action(&mut self, action: KeyAction) -> bool {
+        match action {
+            KeyAction::Rename | KeyAction::DrillDown | KeyAction::OpenFile => {
+                self.restore_trash_targets();
+            }
+            KeyAction::Delete => {
+                if !self.trash_state.targets().is_empty() {
+                    self.mode = AppMode::ConfirmPurge;
+                }
+            }
+            KeyAction::Refresh => self.reload_trash(),
+            KeyAction::Cancel | KeyAction::ClearMarks => self.trash_state.marked.clear(),
+            _ => return false,
+        }
+        true
+    }
+
+    /// Handle Jobs-view specific actions. Returns whether the action was consumed.
+    fn handle_jobs_action(&mut self, action: KeyAction) -> bool {
+        let jobs = self.jobs.jobs();
+        let selected = jobs.get(self.jobs_state.selected);
+        match action {
+            // p pauses or resumes the selected job
+            KeyAction::Paste => {
+                if let Some(job) = selected {
+                    if job.state == JobState::Paused {
+                        self.jobs.resume(job.id);
+                    } else if !self.jobs.pause(job.id) && job.state == JobState::Running {
+                        self.operation_message =
+                            Some((false, format!("Job #{} cannot be paused", job.id)));
+                    }
+                }
+            }
+            // d / x cancel the selected job
+            KeyAction::Delete | KeyAction::Cut => {
+                if let Some(job) = selected
+                    && self.jobs.cancel(job.id)
+                {
+                    self.operation_message =
+                        Some((false, format!("Cancelling job #{}...", job.id)));
+                }
+            }
+            KeyAction::ClearMarks | KeyAction::Cancel => {
+                self.jobs.clear_finished();
+                self.jobs_state.selected = 0;
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    /// Re-read the mount table and free space of each mount.
+    fn reload_mounts(&mut self) {
+        match gravityfile_scan::MountTable::load() {
+            Ok(table) => self
+                .mounts_state
+                .set_mounts(table.real().into_iter().cloned().collect()),
+            Err(e) => {
+                self.mounts_state.mounts = None;
+                self.mounts_state.error = Some(format!("Failed to read mount table: {}", e));
+            }
+        }
+    }
+
+    /// Handle Mounts-view specific actions. Returns whether the action was consumed.
+    fn handle_mounts_action(&mut self, action: KeyAction) -> bool {
+        match action {
+            KeyAction::DrillDown | KeyAction::OpenFile => {
+                if let Some(mount) = self.mounts_state.selected_mount() {
+                    self.scan_mount(mount.mount_point.clone());
+                }
+            }
+            KeyAction::Refresh => self.reload_mounts(),
+            KeyAction::ToggleMark | KeyAction::Delete => {}
+            _ => return false,
+        }
+        true
+    }
+
+    /// Number of owners listed in the Owners view.
+    fn owners_len(&self) -> usize {
+        self.ownership
+            .as_ref()
+            .map_or(0, |r| self.owners_state.entries(r).len())
+    }
+
+    /// Handle Owners-view specific actions. Returns whether the action was consumed.
+    fn handle_owners_action(&mut self, action: KeyAction) -> bool {
+        match action {
+            KeyAction::ToggleLayout => self.owners_state.toggle_groups(),
+            KeyAction::DrillDown | KeyAction::OpenFile => {
+                let target = self.ownership.as_ref().and_then(|r| {
+                    let owner = self
+                        .owners_state
+                        .entries(r)
+                        .get(self.owners_state.selected)?;
+                    owner.largest_directories.first().map(|d| d.path.clone())
+                });
+                if let Some(path) = target {
+                    self.view = View::Explorer;
+                    self.navigate_to_search_result(&path);
+                }
+            }
+            KeyAction::ToggleMark | KeyAction::Delete => {}
+            _ => return false,
+        }
+        true
+    }
+
+    /// File-type breakdown of the current view root, or of the whole scan
+    /// when the view root has none.
+    fn type_breakdown(&self) -> Option<&TypeBreakdown> {
+        let report = self.file_types.as_ref()?;
+        Some(report.breakdown(&self.view_root).unwrap_or(&report.root))
+    }
+
+    /// Number of rows listed in the Types view.
+    fn types_len(&self) -> usize {
+        self.type_breakdown()
+            .map_or(0, |b| self.types_state.rows(b).len())
+    }
+
+    /// Handle Types-view specific actions. Returns whether the action was consumed.
+    fn handle_types_action(&mut self, action: KeyAction) -> bool {
+        match action {
+            KeyAction::DrillDown | KeyAction::OpenFile => {
+                let category = self.type_breakdown().and_then(|b| {
+                    match self.types_state.rows(b).get(self.types_state.selected) {
+                        Some(TypeRow::Category(c)) => Some(c.category),
+                        _ => None,
+                    }
+                });
+                if let Some(category) = category {
+                    self.types_state.enter(category);
+                }
+            }
+            KeyAction::NavigateBack | KeyAction::Cancel => {
+                let Some(report) = self.file_types.as_ref() else {
+                    return false;
+                };
+                let breakdown = report.breakdown(&self.view_root).unwrap_or(&report.root);
+                return self.types_state.leave(breakdown);
+            }
+            KeyAction::ToggleMark | KeyAction::Delete => {}
+            _ => return false,
+        }
+        true
+    }
+
+    /// Number of cleanup candidates.
+    fn cleanup_len(&self) -> usize {
+        self.cleanup.as_ref().map_or(0, |r| r.candidates.len())
+    }
+
+    /// Handle Cleanup-view specific actions. Returns whether the action was consumed.
+    fn handle_cleanup_action(&mut self, action: KeyAction) -> bool {
+        let Some(report) = self.cleanup.as_ref() else {
+            return false;
+        };
+        match action {
+            // a marks every candidate
+            KeyAction::CreateFile => {
+                let before = self.marked.len();
+                self.marked
+                    .extend(report.candidates.iter().map(|c| c.path.clone()));
+                let added = self.marked.len() - before;
+                self.operation_message = Some((
+                    true,
+                    format!(
+                        "Marked {} candidates ({} reclaimable)",
+                        added,
+                        format_size(report.total_reclaimable)
+                    ),
+                ));
+            }
+            KeyAction::DrillDown | KeyAction::OpenFile => {
+                if let Some(candidate) = report.candidates.get(self.selected_cleanup) {
+                    let path = candidate.path.clone();
+                    self.view = View::Explorer;
+                    self.navigate_to_search_result(&path);
+                }
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    /// Measure ages from `basis` for the rest of the session and re-run the
+    /// age analysis when a full scan is available.
+    fn set_age_basis(&mut self, basis: TimeBasis) {
+        self.profile.age.time_basis = basis;
+        let tree = match &self.filter {
+            Some(filter) => Some(&filter.full_tree),
+            None => self.tree.as_ref().filter(|_| self.has_full_scan),
+        };
+        if let Some(tree) = tree {
+            self.age_report = Some(scanning::analyze_age(tree, self.profile.age.clone()));
+            self.selected_stale_dir = 0;
+        }
+    }
+
+    /// Rank the largest entries below the current view root.
+    fn reload_largest(&mut self) {
+        let Some(tree) = self.tree.as_ref() else {
+            self.largest_state.entries.clear();
+            return;
+        };
+        let root = Self::find_node_at_path(&tree.root, &self.view_root, &tree.root_path)
+            .map(|node| (node, self.view_root.clone()))
+            .unwrap_or((&tree.root, tree.root_path.clone()));
+        self.largest_state.reload(root.0, &root.1);
+    }
+
+    /// Handle Largest-view specific actions. Returns whether the action was consumed.
+    fn handle_largest_action(&mut self, action: KeyAction) -> bool {
+        match action {
+            KeyAction::ToggleLayout => {
+                self.largest_state.cycle_kind();
+                self.reload_largest();
+            }
+            KeyAction::DrillDown | KeyAction::OpenFile => {
+                if let Some(entry) = self.largest_state.selected_entry() {
+                    let path = entry.path.clone();
+                    self.view = View::Explorer;
+                    self.navigate_to_search_result(&path);
                 }
-            } else {
-                // Going to a new location - use quick_list for immediate display
-                // Don't auto-scan; user can press R to scan if needed
-                self.path = path.clone();
-                self.view_root = path.clone();
-                self.view_history.clear();
-                self.forward_history.clear();
-                self.tree_state = TreeState::new(self.path.clone());
-                self.miller_state.reset();
-                self.marked.clear();
-                self.clipboard.clear();
-                self.duplicates = None;
-                self.age_report = None;
-                self.warnings.clear();
-                self.preview_state.content = crate::preview::PreviewContent::Empty;
-                self.scan_progress = None;
+            }
+            _ => return false,
+        }
+        true
+    }
 
-                // Load quick tree for immediate display
-                self.tree = gravityfile_scan::quick_list(&path, None).ok();
-                self.update_cached_tree_len();
-                self.update_cached_miller_len();
+    /// Bin the files below the current view root by modification time,
+    /// ignoring any active filter.
+    fn reload_timeline(&mut self) {
+        let tree = match &self.filter {
+            Some(filter) => &filter.full_tree,
+            None => match self.tree.as_ref() {
+                Some(tree) => tree,
+                None => return,
+            },
+        };
+        let root = Self::find_node_at_path(&tree.root, &self.view_root, &tree.root_path)
+            .map(|node| (node, self.view_root.clone()))
+            .unwrap_or((&tree.root, tree.root_path.clone()));
+        self.timeline_state.reload(root.0, &root.1);
+    }
 
-                // Update the active tab
-                if let Some(tab) = self.tab_manager.active_tab_mut() {
-                    tab.path = self.path.clone();
-                    tab.view_root = self.view_root.clone();
-                    tab.label = self
-                        .path
-                        .file_name()
-                        .map(|n| n.to_string_lossy().to_string())
-                        .unwrap_or_else(|| self.path.to_string_lossy().to_string());
-                    tab.history.clear();
+    /// Handle Timeline-view specific actions. Returns whether the action was consumed.
+    fn handle_timeline_action(&mut self, action: KeyAction) -> bool {
+        match action {
+            KeyAction::ToggleLayout => {
+                self.timeline_state.toggle_period();
+                self.reload_timeline();
+            }
+            KeyAction::DrillDown | KeyAction::OpenFile => {
+                if let Some(bin) = self.timeline_state.selected_bin() {
+                    let query = bin.query();
+                    self.apply_filter(&query);
                 }
+            }
+            // A bar is not a path; filter to its files and mark them there.
+            KeyAction::Delete if self.marked.is_empty() => {}
+            _ => return false,
+        }
+        true
+    }
 
-                // Don't auto-start scan - user can press R when ready
+    /// Check the current view root for hygiene issues, ignoring any active
+    /// filter. Needs a full scan: a quick listing leaves directories unread.
+    fn reload_hygiene(&mut self) {
+        if !self.has_full_scan {
+            self.hygiene_state.report = None;
+            return;
+        }
+        let tree = match &self.filter {
+            Some(filter) => &filter.full_tree,
+            None => match self.tree.as_ref() {
+                Some(tree) => tree,
+                None => return,
+            },
+        };
+        let root = Self::find_node_at_path(&tree.root, &self.view_root, &tree.root_path)
+            .map(|node| (node, self.view_root.clone()))
+            .unwrap_or((&tree.root, tree.root_path.clone()));
+        self.hygiene_state
+            .reload(root.0, &root.1, tree.config.apparent_size);
+    }
+
+    /// Handle Hygiene-view specific actions. Returns whether the action was consumed.
+    fn handle_hygiene_action(&mut self, action: KeyAction) -> bool {
+        match action {
+            KeyAction::ToggleLayout => {
+                self.hygiene_state.cycle_kind();
             }
-        } else {
-            self.error = Some(format!("Not a directory: {}", path.display()));
+            // a marks every deletable entry shown
+            KeyAction::CreateFile => {
+                let before = self.marked.len();
+                let paths: Vec<PathBuf> = self
+                    .hygiene_state
+                    .entries()
+                    .into_iter()
+                    .filter(|e| e.kind().is_deletable())
+                    .map(|e| e.path.clone())
+                    .collect();
+                self.marked.extend(paths);
+                let added = self.marked.len() - before;
+                self.operation_message = Some((true, format!("Marked {} entries", added)));
+            }
+            KeyAction::DrillDown | KeyAction::OpenFile => {
+                if let Some(entry) = self.hygiene_state.selected_entry() {
+                    let path = entry.path.clone();
+                    self.view = View::Explorer;
+                    self.navigate_to_search_result(&path);
+                }
+            }
+            _ => return false,
         }
+        true
     }
 
-    /// Execute undo of the last operation.
-    fn execute_undo(&mut self) {
-        // TODO: Implement undo
-        // For now, just show a message that undo is not yet implemented
-        self.operation_message = Some((false, "Undo not yet implemented".to_string()));
+    /// Make `mount_point` the scan root, switch to the Explorer and scan it.
+    fn scan_mount(&mut self, mount_point: PathBuf) {
+        let saved_expanded = self.tree_state.expanded.clone();
+        let saved_selected = self.tree_state.selected;
+        self.view_history
+            .push((self.view_root.clone(), saved_selected, saved_expanded));
+        self.forward_history.clear();
+
+        self.scan_roots.clear();
+        self.path = mount_point.clone();
+        self.view_root = mount_point;
+        self.tree = gravityfile_scan::quick_list(&self.path, None).ok();
+        self.tree_state = TreeState::new(self.path.clone());
+        self.tree_state.expand(&self.path);
+        self.miller_state.reset();
+        self.duplicates = None;
+        self.age_report = None;
+        self.ownership = None;
+        self.file_types = None;
+        self.cleanup = None;
+        self.filter = None;
+        self.has_full_scan = false;
+        self.view = View::Explorer;
+        self.update_cached_tree_len();
+        self.update_cached_miller_len();
+        self.sync_to_active_tab();
+        self.start_scan();
+    }
+
+    /// Restore the marked (or selected) trash entries to their original paths.
+    fn restore_trash_targets(&mut self) {
+        let targets = self.trash_state.targets();
+        if targets.is_empty() {
+            return;
+        }
+        self.trash_state.marked.clear();
+        self.scan_rx = Some(Self::adapt_trash_rx(gravityfile_ops::start_restore(
+            targets,
+        )));
+    }
+
+    /// Permanently delete the marked (or selected) trash entries.
+    fn purge_trash_targets(&mut self) {
+        let targets = self.trash_state.targets();
+        self.mode = AppMode::Normal;
+        if targets.is_empty() {
+            return;
+        }
+        self.trash_state.marked.clear();
+        self.scan_rx = Some(Self::adapt_trash_rx(gravityfile_ops::start_purge(targets)));
+    }
+
+    /// Adapt trash restore/purge results to ScanResult.
+    fn adapt_trash_rx(mut rx: mpsc::Receiver<TrashResult>) -> mpsc::Receiver<ScanResult> {
+        let (tx, adapted_rx) = mpsc::channel(100);
+        tokio::spawn(async move {
+            while let Some(result) = rx.recv().await {
+                let scan_result = match result {
+                    TrashResult::Progress(p) => ScanResult::OperationProgress(p),
+                    TrashResult::Complete(c) => ScanResult::OperationComplete {
+                        operation_type: c.operation_type,
+                        succeeded: c.succeeded,
+                        failed: c.failed,
+                        bytes_processed: c.bytes_processed,
+                        undo: None,
+                    },
+                };
+                if tx.send(scan_result).await.is_err() {
+                    break;
+                }
+            }
+        });
+        adapted_rx
     }
 
     /// Handle key events in input modes (Renaming, CreatingFile, CreatingDirectory).
@@ -2145,9 +3458,14 @@ impl App {
         }
 
         let rx = start_rename(source.clone(), new_name.clone());
+        let undo = UndoableOperation::FileRenamed {
+            path: source.with_file_name(&new_name),
+            old_path: source,
+            new_name: new_name.clone(),
+        };
 
         // Adapt the rename result receiver
-        let adapted_rx = Self::adapt_rename_rx(rx);
+        let adapted_rx = Self::adapt_rename_rx(rx, Some(undo));
         self.scan_rx = Some(adapted_rx);
         self.mode = AppMode::Normal;
 
@@ -2156,8 +3474,11 @@ impl App {
     }
 
     /// Adapt rename receiver to ScanResult.
+    ///
+    /// `undo` is recorded if the rename succeeds.
     fn adapt_rename_rx(
         mut rx: mpsc::Receiver<gravityfile_ops::RenameResult>,
+        mut undo: Option<UndoableOperation>,
     ) -> mpsc::Receiver<ScanResult> {
         let (tx, adapted_rx) = mpsc::channel(100);
 
@@ -2173,6 +3494,7 @@ impl App {
                             succeeded: complete.succeeded,
                             failed: complete.failed,
                             bytes_processed: complete.bytes_processed,
+                            undo: undo.take().filter(|_| complete.succeeded > 0),
                         }
                     }
                 };
@@ -2198,7 +3520,7 @@ impl App {
         let path = parent.join(&name);
         let rx = start_create_file(path.clone());
 
-        let adapted_rx = Self::adapt_create_rx(rx);
+        let adapted_rx = Self::adapt_create_rx(rx, Some(UndoableOperation::FileCreated { path }));
         self.scan_rx = Some(adapted_rx);
 
         self.operation_message = Some((true, format!("Creating file {}...", name)));
@@ -2217,7 +3539,8 @@ impl App {
         let path = parent.join(&name);
         let rx = start_create_directory(path.clone());
 
-        let adapted_rx = Self::adapt_create_rx(rx);
+        let adapted_rx =
+            Self::adapt_create_rx(rx, Some(UndoableOperation::DirectoryCreated { path }));
         self.scan_rx = Some(adapted_rx);
 
         self.operation_message = Some((true, format!("Creating directory {}...", name)));
@@ -2254,6 +3577,8 @@ impl App {
                     Some((false, format!("Failed to create directory: {}", e)));
                 return;
             }
+            self.undo_log.record_create_directory(path.clone());
+            self.save_undo_log();
             self.operation_message = Some((true, format!("Created and entered {}", name)));
         }
 
@@ -2289,8 +3614,11 @@ impl App {
     }
 
     /// Adapt create result receiver to ScanResult.
+    ///
+    /// `undo` is recorded if the creation succeeds.
     fn adapt_create_rx(
         mut rx: mpsc::Receiver<gravityfile_ops::CreateResult>,
+        mut undo: Option<UndoableOperation>,
     ) -> mpsc::Receiver<ScanResult> {
         let (tx, adapted_rx) = mpsc::channel(100);
 
@@ -2306,6 +3634,7 @@ impl App {
                             succeeded: complete.succeeded,
                             failed: complete.failed,
                             bytes_processed: complete.bytes_processed,
+                            undo: undo.take().filter(|_| complete.succeeded > 0),
                         }
                     }
                 };
@@ -2354,6 +3683,33 @@ impl App {
                 // For now, use explorer behavior
                 self.miller_state.move_up(1);
             }
+            View::Trash => {
+                self.trash_state.selected = self.trash_state.selected.saturating_sub(1);
+            }
+            View::Jobs => {
+                self.jobs_state.selected = self.jobs_state.selected.saturating_sub(1);
+            }
+            View::Mounts => {
+                self.mounts_state.selected = self.mounts_state.selected.saturating_sub(1);
+            }
+            View::Owners => {
+                self.owners_state.selected = self.owners_state.selected.saturating_sub(1);
+            }
+            View::Types => {
+                self.types_state.selected = self.types_state.selected.saturating_sub(1);
+            }
+            View::Cleanup => {
+                self.selected_cleanup = self.selected_cleanup.saturating_sub(1);
+            }
+            View::Largest => {
+                self.largest_state.selected = self.largest_state.selected.saturating_sub(1);
+            }
+            View::Timeline => {
+                self.timeline_state.selected = self.timeline_state.selected.saturating_sub(1);
+            }
+            View::Hygiene => {
+                self.hygiene_state.selected = self.hygiene_state.selected.saturating_sub(1);
+            }
         }
     }
 
@@ -2396,6 +3752,42 @@ impl App {
             View::Treemap => {
                 self.miller_state.move_down(1, self.cached_miller_len);
             }
+            View::Trash => {
+                let max = self.trash_state.len().saturating_sub(1);
+                self.trash_state.selected = (self.trash_state.selected + 1).min(max);
+            }
+            View::Jobs => {
+                let max = self.jobs.jobs().len().saturating_sub(1);
+                self.jobs_state.selected = (self.jobs_state.selected + 1).min(max);
+            }
+            View::Mounts => {
+                let max = self.mounts_state.len().saturating_sub(1);
+                self.mounts_state.selected = (self.mounts_state.selected + 1).min(max);
+            }
+            View::Owners => {
+                let max = self.owners_len().saturating_sub(1);
+                self.owners_state.selected = (self.owners_state.selected + 1).min(max);
+            }
+            View::Types => {
+                let max = self.types_len().saturating_sub(1);
+                self.types_state.selected = (self.types_state.selected + 1).min(max);
+            }
+            View::Cleanup => {
+                let max = self.cleanup_len().saturating_sub(1);
+                self.selected_cleanup = (self.selected_cleanup + 1).min(max);
+            }
+            View::Largest => {
+                let max = self.largest_state.entries.len().saturating_sub(1);
+                self.largest_state.selected = (self.largest_state.selected + 1).min(max);
+            }
+            View::Timeline => {
+                let max = self.timeline_state.timeline.bins.len().saturating_sub(1);
+                self.timeline_state.selected = (self.timeline_state.selected + 1).min(max);
+            }
+            View::Hygiene => {
+                let max = self.hygiene_state.entries().len().saturating_sub(1);
+                self.hygiene_state.selected = (self.hygiene_state.selected + 1).min(max);
+            }
         }
     }
 
@@ -2427,6 +3819,34 @@ impl App {
                     self.treemap_state.move_prev();
                 }
             }
+            View::Trash => {
+                self.trash_state.selected = self.trash_state.selected.saturating_sub(PAGE_SIZE);
+            }
+            View::Jobs => {
+                self.jobs_state.selected = self.jobs_state.selected.saturating_sub(PAGE_SIZE);
+            }
+            View::Mounts => {
+                self.mounts_state.selected = self.mounts_state.selected.saturating_sub(PAGE_SIZE);
+            }
+            View::Owners => {
+                self.owners_state.selected = self.owners_state.selected.saturating_sub(PAGE_SIZE);
+            }
+            View::Types => {
+                self.types_state.selected = self.types_state.selected.saturating_sub(PAGE_SIZE);
+            }
+            View::Cleanup => {
+                self.selected_cleanup = self.selected_cleanup.saturating_sub(PAGE_SIZE);
+            }
+            View::Largest => {
+                self.largest_state.selected = self.largest_state.selected.saturating_sub(PAGE_SIZE);
+            }
+            View::Timeline => {
+                self.timeline_state.selected =
+                    self.timeline_state.selected.saturating_sub(PAGE_SIZE);
+            }
+            View::Hygiene => {
+                self.hygiene_state.selected = self.hygiene_state.selected.saturating_sub(PAGE_SIZE);
+            }
         }
     }
 
@@ -2466,6 +3886,42 @@ impl App {
                     self.treemap_state.move_next(self.cached_treemap_len);
                 }
             }
+            View::Trash => {
+                let max = self.trash_state.len().saturating_sub(1);
+                self.trash_state.selected = (self.trash_state.selected + PAGE_SIZE).min(max);
+            }
+            View::Jobs => {
+                let max = self.jobs.jobs().len().saturating_sub(1);
+                self.jobs_state.selected = (self.jobs_state.selected + PAGE_SIZE).min(max);
+            }
+            View::Mounts => {
+                let max = self.mounts_state.len().saturating_sub(1);
+                self.mounts_state.selected = (self.mounts_state.selected + PAGE_SIZE).min(max);
+            }
+            View::Owners => {
+                let max = self.owners_len().saturating_sub(1);
+                self.owners_state.selected = (self.owners_state.selected + PAGE_SIZE).min(max);
+            }
+            View::Types => {
+                let max = self.types_len().saturating_sub(1);
+                self.types_state.selected = (self.types_state.selected + PAGE_SIZE).min(max);
+            }
+            View::Cleanup => {
+                let max = self.cleanup_len().saturating_sub(1);
+                self.selected_cleanup = (self.selected_cleanup + PAGE_SIZE).min(max);
+            }
+            View::Largest => {
+                let max = self.largest_state.entries.len().saturating_sub(1);
+                self.largest_state.selected = (self.largest_state.selected + PAGE_SIZE).min(max);
+            }
+            View::Timeline => {
+                let max = self.timeline_state.timeline.bins.len().saturating_sub(1);
+                self.timeline_state.selected = (self.timeline_state.selected + PAGE_SIZE).min(max);
+            }
+            View::Hygiene => {
+                let max = self.hygiene_state.entries().len().saturating_sub(1);
+                self.hygiene_state.selected = (self.hygiene_state.selected + PAGE_SIZE).min(max);
+            }
         }
     }
 
@@ -2482,6 +3938,15 @@ impl App {
             View::Age => self.selected_stale_dir = 0,
             View::Errors => self.selected_warning = 0,
             View::Treemap => self.treemap_state.reset(),
+            View::Trash => self.trash_state.selected = 0,
+            View::Jobs => self.jobs_state.selected = 0,
+            View::Mounts => self.mounts_state.selected = 0,
+            View::Owners => self.owners_state.selected = 0,
+            View::Types => self.types_state.selected = 0,
+            View::Cleanup => self.selected_cleanup = 0,
+            View::Largest => self.largest_state.selected = 0,
+            View::Timeline => self.timeline_state.selected = 0,
+            View::Hygiene => self.hygiene_state.selected = 0,
         }
     }
 
@@ -2513,6 +3978,34 @@ impl App {
                 // Jump to last item in treemap
                 self.treemap_state.selected = self.cached_treemap_len.saturating_sub(1);
             }
+            View::Trash => {
+                self.trash_state.selected = self.trash_state.len().saturating_sub(1);
+            }
+            View::Jobs => {
+                self.jobs_state.selected = self.jobs.jobs().len().saturating_sub(1);
+            }
+            View::Mounts => {
+                self.mounts_state.selected = self.mounts_state.len().saturating_sub(1);
+            }
+            View::Owners => {
+                self.owners_state.selected = self.owners_len().saturating_sub(1);
+            }
+            View::Types => {
+                self.types_state.selected = self.types_len().saturating_sub(1);
+            }
+            View::Cleanup => {
+                self.selected_cleanup = self.cleanup_len().saturating_sub(1);
+            }
+            View::Largest => {
+                self.largest_state.selected = self.largest_state.entries.len().saturating_sub(1);
+            }
+            View::Timeline => {
+                self.timeline_state.selected =
+                    self.timeline_state.timeline.bins.len().saturating_sub(1);
+            }
+            View::Hygiene => {
+                self.hygiene_state.selected = self.hygiene_state.entries().len().saturating_sub(1);
+            }
         }
     }
 
@@ -2740,6 +4233,13 @@ impl App {
             return;
         };
 
+        // Other mounts are left out of the scan; entering one scans it on its own.
+        if item.node.kind == crate::ui::VisibleNodeKind::MountPoint {
+            let mount_point = item.path.clone();
+            self.scan_mount(mount_point);
+            return;
+        }
+
         if !matches!(item.node.kind, crate::ui::VisibleNodeKind::Directory { .. }) {
             return;
         }
@@ -3004,6 +4504,7 @@ impl App {
         // Create a new tree rooted at the parent
         self.path = parent.to_path_buf();
         self.tree = Some(parent_tree);
+        self.filter = None;
         self.view_root = parent.to_path_buf();
         self.view_history.clear();
         // Note: don't clear forward_history here - we want to allow navigating back
@@ -3176,6 +4677,58 @@ impl App {
         }
     }
 
+    /// Show only the tree entries matching `expression`.
+    fn apply_filter(&mut self, expression: &str) {
+        let query = match Query::parse(expression) {
+            Ok(query) => query,
+            Err(e) => {
+                self.error = Some(format!("Invalid query: {}", e));
+                return;
+            }
+        };
+        let full_tree = match self.filter.take() {
+            Some(filter) => filter.full_tree,
+            None => match self.tree.take() {
+                Some(tree) => tree,
+                None => return,
+            },
+        };
+        self.tree = Some(query.filter_tree(&full_tree));
+        self.filter = Some(TreeFilter { query, full_tree });
+        self.view = View::Explorer;
+        self.reset_tree_selection();
+    }
+
+    /// Restore the unfiltered tree.
+    fn clear_filter(&mut self) {
+        if let Some(filter) = self.filter.take() {
+            self.tree = Some(filter.full_tree);
+            self.reset_tree_selection();
+        }
+    }
+
+    /// Keep `tree` as the unfiltered tree and return its filtered copy
+    /// while a filter is active.
+    fn filtered(&mut self, tree: FileTree) -> FileTree {
+        match &mut self.filter {
+            Some(filter) => {
+                let filtered = filter.query.filter_tree(&tree);
+                filter.full_tree = tree;
+                filtered
+            }
+            None => tree,
+        }
+    }
+
+    /// Move the selection to the top after the tree's entries changed.
+    fn reset_tree_selection(&mut self) {
+        self.tree_state.selected = 0;
+        self.tree_state.offset = 0;
+        self.miller_state.reset();
+        self.update_cached_tree_len();
+        self.update_cached_miller_len();
+    }
+
     /// Execute a command from the command palette.
     fn execute_command(&mut self, cmd: &str) {
         match parse_command(cmd) {
@@ -3201,10 +4754,54 @@ impl App {
             }
             CommandAction::SwitchView(view) => {
                 self.view = view;
+                if view == View::Trash {
+                    self.reload_trash();
+                } else if view == View::Mounts {
+                    self.reload_mounts();
+                } else if view == View::Largest {
+                    self.reload_largest();
+                } else if view == View::Timeline {
+                    self.reload_timeline();
+                } else if view == View::Hygiene {
+                    self.reload_hygiene();
+                }
             }
             CommandAction::ClearMarks => {
                 self.marked.clear();
             }
+            CommandAction::Filter(Some(expression)) => {
+                self.apply_filter(&expression);
+            }
+            CommandAction::Filter(None) => {
+                self.clear_filter();
+            }
+            CommandAction::AgeBasis(basis) => {
+                self.view = View::Age;
+                self.set_age_basis(basis);
+            }
+            CommandAction::Largest(words) => {
+                if !words.is_empty()
+                    && let Err(e) = self.largest_state.configure(&words)
+                {
+                    self.error = Some(format!("Invalid :largest filter: {}", e));
+                    return;
+                }
+                self.view = View::Largest;
+                self.reload_largest();
+            }
+            CommandAction::Timeline(period) => {
+                if let Some(period) = period {
+                    self.timeline_state.period = period;
+                }
+                self.view = View::Timeline;
+                self.reload_timeline();
+            }
+            CommandAction::Hygiene(kind) => {
+                self.hygiene_state.kind = kind;
+                self.hygiene_state.selected = 0;
+                self.view = View::Hygiene;
+                self.reload_hygiene();
+            }
             CommandAction::ToggleDetails => {
                 self.show_details = !self.show_details;
             }
@@ -3256,6 +4853,9 @@ impl App {
             CommandAction::Paste => {
                 self.paste_clipboard();
             }
+            CommandAction::PasteLink(kind) => {
+                self.paste_as_link(kind);
+            }
             CommandAction::Delete => {
                 if !self.marked.is_empty() {
                     self.mode = AppMode::ConfirmDelete;
@@ -3302,6 +4902,27 @@ impl App {
             CommandAction::Undo => {
                 self.execute_undo();
             }
+            CommandAction::Redo => {
+                self.execute_redo();
+            }
+            CommandAction::History => {
+                self.show_history();
+            }
+            CommandAction::Sync(mode) => {
+                self.start_sync_review(mode);
+            }
+            CommandAction::Chmod { mode, recursive } => {
+                self.start_permissions(mode, recursive);
+            }
+            CommandAction::Chown { spec, recursive } => {
+                self.start_chown(&spec, recursive);
+            }
+            CommandAction::RenamePattern { find, replace } => {
+                self.start_rename_pattern(&find, &replace);
+            }
+            CommandAction::Shred(passes) => {
+                self.start_shred_confirm(passes.unwrap_or(gravityfile_ops::DEFAULT_SHRED_PASSES));
+            }
             CommandAction::ShowBookmarks => {
                 self.bookmark_list_state =
                     Some(BookmarkListState::new(&self.user_settings.bookmarks));
@@ -3660,6 +5281,7 @@ impl App {
 
         let mut success_count = 0;
         let mut error_messages: Vec<String> = Vec::new();
+        let mut renamed: Vec<(PathBuf, PathBuf)> = Vec::new();
 
         for entry in &bulk_state.entries {
             let new_path = entry.original.parent().map(|p| p.join(&entry.new_name));
@@ -3667,6 +5289,7 @@ impl App {
                 match std::fs::rename(&entry.original, &new_path) {
                     Ok(_) => {
                         success_count += 1;
+                        renamed.push((entry.original.clone(), new_path.clone()));
                         // Update marks if this path was marked
                         if self.marked.remove(&entry.original) {
                             self.marked.insert(new_path);
@@ -3687,6 +5310,11 @@ impl App {
             }
         }
 
+        if !renamed.is_empty() {
+            self.undo_log.record_move(renamed);
+            self.save_undo_log();
+        }
+
         // Show result message
         if error_messages.is_empty() {
             self.operation_message = Some((true, format!("Renamed {} items", success_count)));
@@ -3829,6 +5457,10 @@ impl App {
                 self.tree_state.expand(&self.path);
                 self.duplicates = None;
                 self.age_report = None;
+                self.ownership = None;
+                self.file_types = None;
+                self.cleanup = None;
+                self.filter = None;
                 self.has_full_scan = false;
                 // Start a background scan of the new location
                 if self.scan_on_startup {
@@ -3892,12 +5524,13 @@ impl App {
                 }
             }
             // Allow jumping directly by pressing the bookmark key
-            KeyCode::Char(c) if state::Bookmarks::is_valid_key(c) => {
-                if self.user_settings.bookmarks.get(c).is_some() {
-                    self.bookmark_list_state = None;
-                    self.mode = AppMode::Normal;
-                    self.jump_to_bookmark(c);
-                }
+            KeyCode::Char(c)
+                if state::Bookmarks::is_valid_key(c)
+                    && self.user_settings.bookmarks.get(c).is_some() =>
+            {
+                self.bookmark_list_state = None;
+                self.mode = AppMode::Normal;
+                self.jump_to_bookmark(c);
             }
             _ => {}
         }
@@ -4185,6 +5818,19 @@ impl App {
                             self.treemap_state.move_prev();
                         }
                     }
+                    View::Owners
+                    | View::Types
+                    | View::Cleanup
+                    | View::Largest
+                    | View::Timeline
+                    | View::Hygiene
+                    | View::Trash
+                    | View::Jobs
+                    | View::Mounts => {
+                        for _ in 0..3 {
+                            self.move_up();
+                        }
+                    }
                 }
             }
             MouseAction::ScrollDown { .. } => {
@@ -4232,91 +5878,98 @@ impl App {
                             self.treemap_state.move_next(self.cached_treemap_len);
                         }
                     }
+                    View::Owners
+                    | View::Types
+                    | View::Cleanup
+                    | View::Largest
+                    | View::Timeline
+                    | View::Hygiene
+                    | View::Trash
+                    | View::Jobs
+                    | View::Mounts => {
+                        for _ in 0..3 {
+                            self.move_down();
+                        }
+                    }
                 }
             }
-            MouseAction::Click { y, .. } => {
+            MouseAction::Click { y, .. } if self.view == View::Explorer => {
                 // For now, simple click-to-select functionality
                 // Calculate which item was clicked based on the Y position
                 // This is a simplified implementation - a more complete version would
                 // track rendered areas from the last frame
 
-                if self.view == View::Explorer {
-                    // Estimate header height (path bar + tabs)
-                    let header_height: u16 = if self.tab_manager.len() > 1 { 3 } else { 2 };
-                    let _footer_height: u16 = 1; // Reserved for future use in click calculation
-
-                    if y > header_height {
-                        let relative_y = (y - header_height) as usize;
-
-                        match self.layout_mode {
-                            LayoutMode::Tree => {
-                                let new_selected = self.tree_state.offset + relative_y;
-                                if new_selected < self.cached_tree_len {
-                                    self.tree_state.selected = new_selected;
-                                }
+                // Estimate header height (path bar + tabs)
+                let header_height: u16 = if self.tab_manager.len() > 1 { 3 } else { 2 };
+                let _footer_height: u16 = 1; // Reserved for future use in click calculation
+
+                if y > header_height {
+                    let relative_y = (y - header_height) as usize;
+
+                    match self.layout_mode {
+                        LayoutMode::Tree => {
+                            let new_selected = self.tree_state.offset + relative_y;
+                            if new_selected < self.cached_tree_len {
+                                self.tree_state.selected = new_selected;
                             }
-                            LayoutMode::Miller => {
-                                // In Miller mode, the middle column starts after the left column
-                                // This is a simplified version - we just select by row
-                                let new_selected = self.miller_state.offset + relative_y;
-                                if new_selected < self.cached_miller_len {
-                                    self.miller_state.selected = new_selected;
-                                    self.update_preview();
-                                }
+                        }
+                        LayoutMode::Miller => {
+                            // In Miller mode, the middle column starts after the left column
+                            // This is a simplified version - we just select by row
+                            let new_selected = self.miller_state.offset + relative_y;
+                            if new_selected < self.cached_miller_len {
+                                self.miller_state.selected = new_selected;
+                                self.update_preview();
                             }
                         }
                     }
                 }
             }
-            MouseAction::DoubleClick { y, .. } => {
+            MouseAction::DoubleClick { y, .. } if self.view == View::Explorer => {
                 // Double-click to enter directory
-                if self.view == View::Explorer {
-                    let header_height: u16 = if self.tab_manager.len() > 1 { 3 } else { 2 };
+                let header_height: u16 = if self.tab_manager.len() > 1 { 3 } else { 2 };
 
-                    if y > header_height {
-                        let relative_y = (y - header_height) as usize;
+                if y > header_height {
+                    let relative_y = (y - header_height) as usize;
 
-                        match self.layout_mode {
-                            LayoutMode::Tree => {
-                                let new_selected = self.tree_state.offset + relative_y;
-                                if new_selected < self.cached_tree_len {
-                                    self.tree_state.selected = new_selected;
-                                    self.drill_into_selected();
-                                }
+                    match self.layout_mode {
+                        LayoutMode::Tree => {
+                            let new_selected = self.tree_state.offset + relative_y;
+                            if new_selected < self.cached_tree_len {
+                                self.tree_state.selected = new_selected;
+                                self.drill_into_selected();
                             }
-                            LayoutMode::Miller => {
-                                let new_selected = self.miller_state.offset + relative_y;
-                                if new_selected < self.cached_miller_len {
-                                    self.miller_state.selected = new_selected;
-                                    self.drill_into_miller_selected();
-                                }
+                        }
+                        LayoutMode::Miller => {
+                            let new_selected = self.miller_state.offset + relative_y;
+                            if new_selected < self.cached_miller_len {
+                                self.miller_state.selected = new_selected;
+                                self.drill_into_miller_selected();
                             }
                         }
                     }
                 }
             }
-            MouseAction::RightClick { y, .. } => {
+            MouseAction::RightClick { y, .. } if self.view == View::Explorer => {
                 // Right-click to toggle mark
-                if self.view == View::Explorer {
-                    let header_height: u16 = if self.tab_manager.len() > 1 { 3 } else { 2 };
+                let header_height: u16 = if self.tab_manager.len() > 1 { 3 } else { 2 };
 
-                    if y > header_height {
-                        let relative_y = (y - header_height) as usize;
+                if y > header_height {
+                    let relative_y = (y - header_height) as usize;
 
-                        match self.layout_mode {
-                            LayoutMode::Tree => {
-                                let new_selected = self.tree_state.offset + relative_y;
-                                if new_selected < self.cached_tree_len {
-                                    self.tree_state.selected = new_selected;
-                                    self.toggle_mark();
-                                }
+                    match self.layout_mode {
+                        LayoutMode::Tree => {
+                            let new_selected = self.tree_state.offset + relative_y;
+                            if new_selected < self.cached_tree_len {
+                                self.tree_state.selected = new_selected;
+                                self.toggle_mark();
                             }
-                            LayoutMode::Miller => {
-                                let new_selected = self.miller_state.offset + relative_y;
-                                if new_selected < self.cached_miller_len {
-                                    self.miller_state.selected = new_selected;
-                                    self.toggle_mark();
-                                }
+                        }
+                        LayoutMode::Miller => {
+                            let new_selected = self.miller_state.offset + relative_y;
+                            if new_selected < self.cached_miller_len {
+                                self.miller_state.selected = new_selected;
+                                self.toggle_mark();
                             }
                         }
                     }
@@ -4595,6 +6248,36 @@ impl App {
             }
             KeyAction::Sort => {
                 // 's' saves settings
+                if let Some(ref state) = self.settings_state
+                    && state.profile_dirty
+                {
+                    // Profile edits go to the nearest profile file (or the
+                    // global one), into the active variant if any, and
+                    // apply from the next scan.
+                    self.profile = state.profile.clone();
+                    let saved = self
+                        .profile
+                        .save_path()
+                        .ok_or_else(|| "No config directory".to_string())
+                        .and_then(|path| {
+                            self.profile
+                                .save_to(&path)
+                                .map(|()| path)
+                                .map_err(|e| e.to_string())
+                        });
+                    match saved {
+                        Ok(path) => {
+                            if !self.profile.sources.contains(&path) {
+                                self.profile.sources.push(path.clone());
+                            }
+                            self.operation_message = Some((
+                                true,
+                                format!("Profile saved to {}; rescan to apply", path.display()),
+                            ));
+                        }
+                        Err(e) => self.error = Some(format!("Failed to save profile: {}", e)),
+                    }
+                }
                 if let Some(ref state) = self.settings_state
                     && state.dirty
                 {
@@ -4605,6 +6288,8 @@ impl App {
                     } else {
                         // Apply settings immediately where applicable
                         self.scan_on_startup = self.user_settings.scan_on_startup;
+                        self.jobs
+                            .set_concurrency(self.user_settings.job_concurrency);
                     }
                 }
                 self.settings_state = None;
@@ -4735,6 +6420,7 @@ impl Widget for &App {
             command_cursor: self.command_input.cursor(),
             input_state: self.input_state.as_ref(),
             operation_progress: self.operation_progress.as_ref(),
+            operation_cancellable: self.operation_token.is_some(),
             pending_conflict: self.pending_conflict.as_ref(),
             clipboard: &self.clipboard,
             get_path_size: Box::new(|p| self.get_path_size(p)),
@@ -4752,12 +6438,82 @@ impl Widget for &App {
             has_full_scan: self.has_full_scan,
             settings_state: self.settings_state.as_ref(),
             bookmark_list_state: self.bookmark_list_state.as_ref(),
+            history_state: self.history_state.as_ref(),
+            plan_review: self.plan_review.as_ref(),
+            sync_review: self.sync_review.as_ref(),
             bookmarks: &self.user_settings.bookmarks,
             bulk_rename_state: self.bulk_rename_state.as_ref(),
             treemap_selected: self.treemap_state.selected,
             visual_state: self.visual_state.as_ref(),
+            trash_state: &self.trash_state,
+            jobs: self.jobs.jobs(),
+            jobs_state: &self.jobs_state,
+            mounts_state: &self.mounts_state,
+            ownership: self.ownership.as_ref(),
+            owners_state: &self.owners_state,
+            file_types: self.file_types.as_ref(),
+            types_state: &self.types_state,
+            cleanup: self.cleanup.as_ref(),
+            selected_cleanup: self.selected_cleanup,
+            largest_state: &self.largest_state,
+            timeline_state: &self.timeline_state,
+            hygiene_state: &self.hygiene_state,
+            filter: self.filter.as_ref().map(|f| f.query.as_str()),
+            shred_confirm: self.shred_confirm.as_ref(),
+            permissions_state: self.permissions_state.as_ref(),
+            rename_pattern: self.rename_pattern.as_ref(),
         };
 
         render_app(&ctx, area, buf);
     }
 }
+
+/// Status line message for a finished operation.
+fn operation_summary(
+    operation_type: OperationType,
+    succeeded: usize,
+    failed: usize,
+) -> (bool, String) {
+    let action = match operation_type {
+        OperationType::Copy => "Copied",
+        OperationType::Move => "Moved",
+        OperationType::Delete => "Deleted",
+        OperationType::Rename => "Renamed",
+        OperationType::CreateFile => "Created file",
+        OperationType::CreateDirectory => "Created directory",
+        OperationType::Restore => "Restored",
+        OperationType::Sync => "Synced",
+        OperationType::Shred => "Shredded",
+        OperationType::Chmod => "Changed permissions of",
+        OperationType::Chown => "Changed owner of",
+        OperationType::Link => "Linked",
+    };
+
+    let success = failed == 0;
+    let msg = if success {
+        format!("{} {} items", action, succeeded)
+    } else {
+        format!("{} {} items, {} failed", action, succeeded, failed)
+    };
+    (success, msg)
+}
+
+/// Read the current permissions and owner of `path` for the permissions modal.
+#[cfg(unix)]
+fn permission_entry(path: PathBuf) -> Option<PermissionEntry> {
+    use std::os::unix::fs::{MetadataExt, PermissionsExt};
+
+    let meta = std::fs::symlink_metadata(&path).ok()?;
+    Some(PermissionEntry {
+        is_dir: meta.is_dir(),
+        mode: meta.permissions().mode() & 0o7777,
+        owner: gravityfile_core::user_name(meta.uid()).unwrap_or_else(|| meta.uid().to_string()),
+        group: gravityfile_core::group_name(meta.gid()).unwrap_or_else(|| meta.gid().to_string()),
+        path,
+    })
+}
+
+#[cfg(not(unix))]
+fn permission_entry(_path: PathBuf) -> Option<PermissionEntry> {
+    None
+}
@@ -1,6 +1,7 @@
 //! Application rendering.
 
 use std::path::Path;
+use std::time::SystemTime;
 
 use ratatui::buffer::Buffer;
 use ratatui::layout::{Constraint, Layout, Rect};
@@ -9,24 +10,29 @@ use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Paragraph, Tabs, Widget};
 use strum::IntoEnumIterator;
 
-use gravityfile_analyze::format_age;
+use gravityfile_analyze::{HygieneKind, TopKind, format_age};
 use gravityfile_ops::{Conflict, OperationProgress};
 
 use crate::preview::PreviewContent;
 use crate::theme::Theme;
 use crate::ui::modals::{
     BookmarkListModal, BookmarkPrompt, BulkRenameConfirmModal, CommandPalette, ConflictModal,
-    DeleteConfirmModal, DeletionProgressModal, InputModal, OperationProgressModal, SettingsModal,
+    DeleteConfirmModal, DeletionProgressModal, HistoryModal, InputModal, OperationProgressModal,
+    PermissionsModal, PlanModal, PurgeConfirmModal, RenamePatternModal, SettingsModal,
+    ShredConfirmModal, SyncModal,
 };
 use crate::ui::{
-    AppLayout, HelpOverlay, MillerColumns, MillerState, TreeState, TreeView, TreemapView,
-    format_relative_time, format_size,
+    AppLayout, CompactSizeBar, HelpOverlay, MillerColumns, MillerState, TreeState, TreeView,
+    TreemapView, format_relative_time, format_size, truncate_to_width,
 };
 
 use super::input::InputState;
 use super::state::{
     AppMode, BookmarkListState, Bookmarks, ClipboardMode, ClipboardState, DeletionProgress,
-    LayoutMode, SelectedInfo, SettingsState, SortMode, View,
+    HistoryState, HygieneViewState, JobsViewState, LargestViewState, LayoutMode, MountsViewState,
+    OwnersViewState, PermissionsState, PlanReviewState, RenamePatternState, SelectedInfo,
+    SettingsState, ShredConfirmState, SortMode, SyncReviewState, TimelineViewState, TrashViewState,
+    TypeRow, TypesViewState, View,
 };
 
 /// Item in the duplicates list (either a group header or a file within a group).
@@ -78,6 +84,7 @@ pub struct RenderContext<'a> {
     pub command_cursor: usize,
     pub input_state: Option<&'a InputState>,
     pub operation_progress: Option<&'a OperationProgress>,
+    pub operation_cancellable: bool,
     pub pending_conflict: Option<&'a Conflict>,
     pub clipboard: &'a ClipboardState,
     #[allow(clippy::type_complexity)]
@@ -100,6 +107,9 @@ pub struct RenderContext<'a> {
     pub settings_state: Option<&'a SettingsState>,
     /// Bookmark list modal state.
     pub bookmark_list_state: Option<&'a BookmarkListState>,
+    pub history_state: Option<&'a HistoryState>,
+    pub plan_review: Option<&'a PlanReviewState>,
+    pub sync_review: Option<&'a SyncReviewState>,
     /// User bookmarks.
     pub bookmarks: &'a Bookmarks,
     /// Bulk rename state for confirmation modal.
@@ -108,6 +118,39 @@ pub struct RenderContext<'a> {
     pub treemap_selected: usize,
     /// Visual selection state.
     pub visual_state: Option<&'a super::state::VisualState>,
+    /// Trash view state.
+    pub trash_state: &'a TrashViewState,
+    pub mounts_state: &'a MountsViewState,
+    /// Ownership analysis report.
+    pub ownership: Option<&'a gravityfile_analyze::OwnershipReport>,
+    /// Owners view state.
+    pub owners_state: &'a OwnersViewState,
+    /// File-type analysis report.
+    pub file_types: Option<&'a gravityfile_analyze::FileTypeReport>,
+    /// Types view state.
+    pub types_state: &'a TypesViewState,
+    /// Cleanup candidates report.
+    pub cleanup: Option<&'a gravityfile_analyze::CleanupReport>,
+    /// Selected cleanup candidate index.
+    pub selected_cleanup: usize,
+    /// Largest view state.
+    pub largest_state: &'a LargestViewState,
+    /// Timeline view state.
+    pub timeline_state: &'a TimelineViewState,
+    /// Hygiene view state.
+    pub hygiene_state: &'a HygieneViewState,
+    /// Active query filter on the tree.
+    pub filter: Option<&'a str>,
+    /// Snapshot of background jobs.
+    pub jobs: Vec<gravityfile_ops::Job>,
+    /// Jobs view state.
+    pub jobs_state: &'a JobsViewState,
+    /// Pending shred confirmation.
+    pub shred_confirm: Option<&'a ShredConfirmState>,
+    /// Permissions modal state.
+    pub permissions_state: Option<&'a PermissionsState>,
+    /// Rename pattern modal state.
+    pub rename_pattern: Option<&'a RenamePatternState>,
 }
 
 /// Main render function for the application.
@@ -162,6 +205,15 @@ pub fn render_app(ctx: &RenderContext, area: Rect, buf: &mut Buffer) {
         View::Age => render_age(ctx, content, buf),
         View::Errors => render_errors(ctx, content, buf),
         View::Treemap => render_treemap(ctx, content, buf),
+        View::Trash => render_trash(ctx, content, buf),
+        View::Jobs => render_jobs(ctx, content, buf),
+        View::Mounts => render_mounts(ctx, content, buf),
+        View::Owners => render_owners(ctx, content, buf),
+        View::Types => render_types(ctx, content, buf),
+        View::Cleanup => render_cleanup(ctx, content, buf),
+        View::Largest => render_largest(ctx, content, buf),
+        View::Timeline => render_timeline(ctx, content, buf),
+        View::Hygiene => render_hygiene(ctx, content, buf),
     }
 
     // Render footer
@@ -222,9 +274,11 @@ pub fn render_app(ctx: &RenderContext, area: Rect, buf: &mut Buffer) {
                     .render(area, buf);
             }
         }
-        AppMode::Copying | AppMode::Moving => {
+        AppMode::Syncing => {
             if let Some(progress) = ctx.operation_progress {
-                OperationProgressModal::new(ctx.theme, progress).render(area, buf);
+                OperationProgressModal::new(ctx.theme, progress)
+                    .cancellable(ctx.operation_cancellable)
+                    .render(area, buf);
             }
         }
         AppMode::ConflictResolution => {
@@ -245,6 +299,36 @@ pub fn render_app(ctx: &RenderContext, area: Rect, buf: &mut Buffer) {
                 BookmarkListModal::new(ctx.theme, ctx.bookmarks, state).render(area, buf);
             }
         }
+        AppMode::History => {
+            if let Some(state) = ctx.history_state {
+                HistoryModal::new(ctx.theme, state).render(area, buf);
+            }
+        }
+        AppMode::ConfirmPlan => {
+            if let Some(review) = ctx.plan_review {
+                PlanModal::new(ctx.theme, review).render(area, buf);
+            }
+        }
+        AppMode::RenamePattern => {
+            if let Some(state) = ctx.rename_pattern {
+                RenamePatternModal::new(ctx.theme, state).render(area, buf);
+            }
+        }
+        AppMode::Permissions => {
+            if let Some(state) = ctx.permissions_state {
+                PermissionsModal::new(ctx.theme, state).render(area, buf);
+            }
+        }
+        AppMode::ConfirmShred => {
+            if let Some(state) = ctx.shred_confirm {
+                ShredConfirmModal::new(ctx.theme, state).render(area, buf);
+            }
+        }
+        AppMode::ConfirmSync => {
+            if let Some(review) = ctx.sync_review {
+                SyncModal::new(ctx.theme, review).render(area, buf);
+            }
+        }
         AppMode::SettingBookmark => {
             // Render prompt in footer area
             let footer_area = Rect::new(area.x, area.bottom().saturating_sub(1), area.width, 1);
@@ -255,6 +339,10 @@ pub fn render_app(ctx: &RenderContext, area: Rect, buf: &mut Buffer) {
             let footer_area = Rect::new(area.x, area.bottom().saturating_sub(1), area.width, 1);
             BookmarkPrompt::new(ctx.theme, "Jump to bookmark:").render(footer_area, buf);
         }
+        AppMode::ConfirmPurge => {
+            let targets = ctx.trash_state.targets();
+            PurgeConfirmModal::new(ctx.theme, &targets).render(area, buf);
+        }
         AppMode::ConfirmBulkRename => {
             if let Some(state) = ctx.bulk_rename_state {
                 BulkRenameConfirmModal::new(ctx.theme, state).render(area, buf);
@@ -498,11 +586,17 @@ fn render_header(ctx: &RenderContext, area: Rect, buf: &mut Buffer) {
     };
 
     let stats = if let Some(tree) = &ctx.tree {
+        let ignored = if tree.stats.ignored_files > 0 {
+            format!(", {} ignored", format_size(tree.stats.ignored_size))
+        } else {
+            String::new()
+        };
         format!(
-            " {} in {} files, {} dirs ",
+            " {} in {} files, {} dirs{} ",
             format_size(tree.stats.total_size),
             tree.stats.total_files,
-            tree.stats.total_dirs
+            tree.stats.total_dirs,
+            ignored
         )
     } else {
         String::new()
@@ -510,6 +604,16 @@ fn render_header(ctx: &RenderContext, area: Rect, buf: &mut Buffer) {
 
     let stats_span = Span::styled(stats, ctx.theme.header);
 
+    let filter_span = match ctx.filter {
+        Some(query) => Span::styled(
+            format!(" filter: {} ", query),
+            Style::default()
+                .fg(ctx.theme.background)
+                .bg(ctx.theme.warning),
+        ),
+        None => Span::raw(""),
+    };
+
     // Show operation message, deletion message, marked items, or clipboard status
     let status = if let Some((success, msg)) = ctx.operation_message {
         let color = if *success {
@@ -569,6 +673,7 @@ fn render_header(ctx: &RenderContext, area: Rect, buf: &mut Buffer) {
         scanning_indicator,
         Span::raw(" "),
         stats_span,
+        filter_span,
         status,
         clipboard_status,
     ]);
@@ -582,11 +687,14 @@ fn render_tabs(ctx: &RenderContext, area: Rect, buf: &mut Buffer) {
     // Always show the standard View tabs - user can switch views during scanning
     let is_scanning = ctx.scan_progress.is_some();
     let error_count = ctx.warnings.len();
+    let active_jobs = ctx.jobs.iter().filter(|j| !j.state.is_finished()).count();
 
     let titles: Vec<String> = View::iter()
         .map(|v| {
             if v == View::Errors && error_count > 0 {
                 format!(" {} ({}) ", v, error_count)
+            } else if v == View::Jobs && active_jobs > 0 {
+                format!(" {} ({}) ", v, active_jobs)
             } else if v == View::Explorer && is_scanning {
                 " Explorer ⟳ ".to_string()
             } else {
@@ -1039,15 +1147,19 @@ fn render_duplicates(ctx: &RenderContext, area: Rect, buf: &mut Buffer) {
 }
 
 fn render_age(ctx: &RenderContext, area: Rect, buf: &mut Buffer) {
+    let basis = ctx
+        .age_report
+        .map(|a| format!(" by {}", a.time_basis))
+        .unwrap_or_default();
     let title = if ctx.view_root != ctx.path {
         let relative = ctx
             .view_root
             .strip_prefix(ctx.path)
             .map(|p| p.display().to_string())
             .unwrap_or_else(|_| ctx.view_root.display().to_string());
-        format!(" Age Analysis - {} ", relative)
+        format!(" Age Analysis{} - {} ", basis, relative)
     } else {
-        " Age Analysis ".to_string()
+        format!(" Age Analysis{} ", basis)
     };
 
     let block = Block::default()
@@ -1063,17 +1175,24 @@ fn render_age(ctx: &RenderContext, area: Rect, buf: &mut Buffer) {
         let max_size = age.buckets.iter().map(|b| b.total_size).max().unwrap_or(1);
         let chart_height = age.buckets.len().min(inner.height as usize / 2);
 
-        let bucket_start_y = if ctx.view_root != ctx.path {
+        let mut bucket_start_y = inner.y;
+        if let Some(warning) = &age.atime_warning {
+            let note = Line::styled(
+                format!(" {}; recent reads may not show.", warning),
+                Style::default().fg(ctx.theme.warning),
+            );
+            Paragraph::new(note).render(Rect::new(inner.x, bucket_start_y, inner.width, 1), buf);
+            bucket_start_y += 1;
+        }
+        if ctx.view_root != ctx.path {
             let note = Line::styled(
                 " Distribution (full scan):",
                 Style::default().fg(ctx.theme.muted),
             );
-            let note_area = Rect::new(inner.x, inner.y, inner.width, 1);
+            let note_area = Rect::new(inner.x, bucket_start_y, inner.width, 1);
             Paragraph::new(note).render(note_area, buf);
-            inner.y + 1
-        } else {
-            inner.y
-        };
+            bucket_start_y += 1;
+        }
 
         for (i, bucket) in age.buckets.iter().enumerate().take(chart_height) {
             let y = bucket_start_y + i as u16;
@@ -1239,6 +1358,9 @@ fn render_errors(ctx: &RenderContext, area: Rect, buf: &mut Buffer) {
             gravityfile_core::WarningKind::CrossFilesystem => {
                 ("\u{1F4BE}", "Cross Filesystem", false)
             }
+            gravityfile_core::WarningKind::OverlappingRoot => {
+                ("\u{1F5C2}", "Overlapping Root", false)
+            }
         };
 
         // Check if this warning's path is marked for deletion
@@ -1302,6 +1424,897 @@ fn render_errors(ctx: &RenderContext, area: Rect, buf: &mut Buffer) {
     }
 }
 
+fn render_trash(ctx: &RenderContext, area: Rect, buf: &mut Buffer) {
+    let state = ctx.trash_state;
+    let title = match &state.entries {
+        Some(entries) => {
+            let total: u64 = entries.iter().map(|e| e.size).sum();
+            format!(" Trash ({} items, {}) ", entries.len(), format_size(total))
+        }
+        None => " Trash ".to_string(),
+    };
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(ctx.theme.border)
+        .title(title)
+        .title_style(ctx.theme.title);
+
+    let inner = block.inner(area);
+    block.render(area, buf);
+
+    let entries = match (&state.entries, &state.error) {
+        (_, Some(error)) => {
+            Paragraph::new(Line::styled(
+                format!(" {}", error),
+                Style::default().fg(ctx.theme.error),
+            ))
+            .render(inner, buf);
+            return;
+        }
+        (Some(entries), None) if !entries.is_empty() => entries,
+        _ => {
+            Paragraph::new(Line::styled(
+                " Trash is empty.",
+                Style::default().fg(ctx.theme.muted),
+            ))
+            .render(inner, buf);
+            return;
+        }
+    };
+
+    let visible = inner.height as usize;
+    let offset = if state.selected >= visible {
+        state.selected - visible + 1
+    } else {
+        0
+    };
+
+    // Fixed columns: mark(4) + size(10) + deleted(10) + spacing
+    let path_width = (inner.width as usize).saturating_sub(28);
+
+    for (i, entry) in entries.iter().enumerate().skip(offset).take(visible) {
+        let y = inner.y + (i - offset) as u16;
+        let is_selected = i == state.selected;
+        let is_marked = state.marked.contains(&entry.trashed_path);
+
+        let mark = if is_marked { "[x] " } else { "    " };
+        let deleted = entry
+            .deleted_at
+            .map(format_relative_time)
+            .unwrap_or_else(|| "unknown".to_string());
+        let mut path = entry.original_path.display().to_string();
+        if entry.is_dir {
+            path.push('/');
+        }
+        let path = if path.len() > path_width && path_width > 3 {
+            format!("...{}", &path[path.len() - (path_width - 3)..])
+        } else {
+            path
+        };
+
+        let style = if is_selected {
+            ctx.theme.selected
+        } else if is_marked {
+            Style::default().fg(ctx.theme.warning)
+        } else if entry.is_dir {
+            ctx.theme.directory
+        } else {
+            Style::default()
+        };
+
+        let line = Line::from(vec![
+            Span::styled(mark, style),
+            Span::styled(format!("{:<width$}", path, width = path_width), style),
+            Span::styled(format!(" {:>10}", format_size(entry.size)), style),
+            Span::styled(
+                format!(" {:>10}", deleted),
+                if is_selected {
+                    style
+                } else {
+                    Style::default().fg(ctx.theme.muted)
+                },
+            ),
+        ]);
+        Paragraph::new(line).render(Rect::new(inner.x, y, inner.width, 1), buf);
+    }
+}
+
+fn render_mounts(ctx: &RenderContext, area: Rect, buf: &mut Buffer) {
+    let state = ctx.mounts_state;
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(ctx.theme.border)
+        .title(format!(" Mounts ({}) ", state.len()))
+        .title_style(ctx.theme.title);
+
+    let inner = block.inner(area);
+    block.render(area, buf);
+
+    let mounts = match (&state.mounts, &state.error) {
+        (_, Some(error)) => {
+            Paragraph::new(Line::styled(
+                format!(" {}", error),
+                Style::default().fg(ctx.theme.error),
+            ))
+            .render(inner, buf);
+            return;
+        }
+        (Some(mounts), None) if !mounts.is_empty() => mounts,
+        _ => {
+            Paragraph::new(Line::styled(
+                " No mounted filesystems found.",
+                Style::default().fg(ctx.theme.muted),
+            ))
+            .render(inner, buf);
+            return;
+        }
+    };
+
+    // Header row, then one row per mount.
+    let header = format!(
+        " {:<8} {:>10} {:>10} {:>10} {:>5} {:>5}  {:<12} Mounted on",
+        "Type", "Size", "Used", "Avail", "Use%", "IUse%", ""
+    );
+    Paragraph::new(Line::styled(header, ctx.theme.title))
+        .render(Rect::new(inner.x, inner.y, inner.width, 1), buf);
+
+    let visible = inner.height.saturating_sub(1) as usize;
+    let offset = if state.selected >= visible {
+        state.selected - visible + 1
+    } else {
+        0
+    };
+
+    for (i, mount) in mounts.iter().enumerate().skip(offset).take(visible) {
+        let y = inner.y + 1 + (i - offset) as u16;
+        let is_selected = i == state.selected;
+        let ratio = mount.usage_ratio();
+
+        let style = if is_selected {
+            ctx.theme.selected
+        } else {
+            Style::default()
+        };
+        let bar_color = if ratio >= 0.9 {
+            ctx.theme.error
+        } else if ratio >= 0.75 {
+            ctx.theme.warning
+        } else {
+            ctx.theme.success
+        };
+        let inode_use = if mount.inodes_total == 0 {
+            "-".to_string()
+        } else {
+            format!("{:.0}%", mount.inode_ratio() * 100.0)
+        };
+        let mut target = mount.mount_point.display().to_string();
+        if mount.is_read_only() {
+            target.push_str(" (ro)");
+        }
+
+        let stats = format!(
+            " {:<8} {:>10} {:>10} {:>10} {:>4.0}% {:>5}  ",
+            mount.fs_type,
+            format_size(mount.total),
+            format_size(mount.used),
+            format_size(mount.available),
+            ratio * 100.0,
+            inode_use,
+        );
+        let stats_width = stats.chars().count() as u16;
+        let line = Line::from(vec![
+            Span::styled(stats, style),
+            Span::raw(" ".repeat(13)),
+            Span::styled(target, style.patch(ctx.theme.directory)),
+        ]);
+        Paragraph::new(line).render(Rect::new(inner.x, y, inner.width, 1), buf);
+
+        let bar_area = Rect::new(inner.x + stats_width, y, 12, 1).intersection(inner);
+        CompactSizeBar::new(ratio)
+            .style(Style::default().fg(bar_color))
+            .render(bar_area, buf);
+    }
+}
+
+fn render_owners(ctx: &RenderContext, area: Rect, buf: &mut Buffer) {
+    let state = ctx.owners_state;
+    let kind = if state.show_groups { "Groups" } else { "Users" };
+
+    let Some(report) = ctx.ownership else {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(ctx.theme.border)
+            .title(format!(" Owners - {} ", kind))
+            .title_style(ctx.theme.title);
+        let inner = block.inner(area);
+        block.render(area, buf);
+        Paragraph::new(Line::styled(
+            " Ownership is available after a full scan completes.",
+            Style::default().fg(ctx.theme.muted),
+        ))
+        .render(inner, buf);
+        return;
+    };
+
+    let entries = state.entries(report);
+    let selected = entries.get(state.selected);
+    let detail_rows = selected.map_or(0, |o| o.largest_directories.len()) as u16;
+    let [list_area, detail_area] = Layout::vertical([
+        Constraint::Min(5),
+        Constraint::Length(detail_rows.max(1) + 2),
+    ])
+    .areas(area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(ctx.theme.border)
+        .title(format!(
+            " Owners - {} ({}, {} in {} files) ",
+            kind,
+            entries.len(),
+            format_size(report.total_size),
+            report.total_files
+        ))
+        .title_style(ctx.theme.title);
+    let inner = block.inner(list_area);
+    block.render(list_area, buf);
+
+    if entries.is_empty() {
+        Paragraph::new(Line::styled(
+            " No ownership information recorded for this scan.",
+            Style::default().fg(ctx.theme.muted),
+        ))
+        .render(inner, buf);
+        return;
+    }
+
+    let header = format!(
+        " {:<16} {:>10} {:>6} {:>10}  {:<12}",
+        "Name", "Size", "Share", "Files", ""
+    );
+    Paragraph::new(Line::styled(header, ctx.theme.title))
+        .render(Rect::new(inner.x, inner.y, inner.width, 1), buf);
+
+    let visible = inner.height.saturating_sub(1) as usize;
+    let offset = if state.selected >= visible {
+        state.selected - visible + 1
+    } else {
+        0
+    };
+
+    for (i, owner) in entries.iter().enumerate().skip(offset).take(visible) {
+        let y = inner.y + 1 + (i - offset) as u16;
+        let style = if i == state.selected {
+            ctx.theme.selected
+        } else {
+            Style::default()
+        };
+        let share = report.share(owner);
+        let stats = format!(
+            " {:<16} {:>10} {:>5.1}% {:>10}  ",
+            truncate_to_width(&owner.name, 16),
+            format_size(owner.total_size),
+            share * 100.0,
+            owner.file_count,
+        );
+        let stats_width = stats.chars().count() as u16;
+        Paragraph::new(Line::styled(stats, style))
+            .render(Rect::new(inner.x, y, inner.width, 1), buf);
+
+        let bar_area = Rect::new(inner.x + stats_width, y, 12, 1).intersection(inner);
+        CompactSizeBar::new(share)
+            .style(Style::default().fg(ctx.theme.info))
+            .render(bar_area, buf);
+    }
+
+    let Some(owner) = selected else {
+        return;
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(ctx.theme.border)
+        .title(format!(" Largest directories of {} ", owner.name))
+        .title_style(ctx.theme.title);
+    let inner = block.inner(detail_area);
+    block.render(detail_area, buf);
+
+    for (i, dir) in owner.largest_directories.iter().enumerate() {
+        let y = inner.y + i as u16;
+        if y >= inner.y + inner.height {
+            break;
+        }
+        let relative = dir
+            .path
+            .strip_prefix(ctx.path)
+            .ok()
+            .filter(|p| !p.as_os_str().is_empty())
+            .map(|p| p.display().to_string())
+            .unwrap_or_else(|| dir.path.display().to_string());
+        let line = Line::from(vec![
+            Span::raw(format!(
+                " {:>10} {:>8} files  ",
+                format_size(dir.size),
+                dir.file_count
+            )),
+            Span::styled(relative, ctx.theme.directory),
+        ]);
+        Paragraph::new(line).render(Rect::new(inner.x, y, inner.width, 1), buf);
+    }
+}
+
+fn render_types(ctx: &RenderContext, area: Rect, buf: &mut Buffer) {
+    let state = ctx.types_state;
+    let relative = ctx
+        .view_root
+        .strip_prefix(ctx.path)
+        .ok()
+        .filter(|p| !p.as_os_str().is_empty())
+        .map(|p| format!(" - {}", p.display()))
+        .unwrap_or_default();
+    let scope = match state.category {
+        Some(category) => format!("{} by extension", category),
+        None => "Categories".to_string(),
+    };
+
+    let Some(report) = ctx.file_types else {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(ctx.theme.border)
+            .title(format!(" File Types{} ", relative))
+            .title_style(ctx.theme.title);
+        let inner = block.inner(area);
+        block.render(area, buf);
+        Paragraph::new(Line::styled(
+            " File types are available after a full scan completes.",
+            Style::default().fg(ctx.theme.muted),
+        ))
+        .render(inner, buf);
+        return;
+    };
+
+    let breakdown = report.breakdown(ctx.view_root).unwrap_or(&report.root);
+    let rows = state.rows(breakdown);
+    let selected = state.selected.min(rows.len().saturating_sub(1));
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(ctx.theme.border)
+        .title(format!(
+            " File Types{} - {} ({} in {} files) ",
+            relative,
+            scope,
+            format_size(breakdown.total_size),
+            breakdown.file_count
+        ))
+        .title_style(ctx.theme.title);
+    let inner = block.inner(area);
+    block.render(area, buf);
+
+    if rows.is_empty() {
+        Paragraph::new(Line::styled(
+            " No files here.",
+            Style::default().fg(ctx.theme.muted),
+        ))
+        .render(inner, buf);
+        return;
+    }
+
+    let label = if state.category.is_some() {
+        "Extension"
+    } else {
+        "Category"
+    };
+    let header = format!(
+        " {:<14} {:>10} {:>6} {:>10}  ",
+        label, "Size", "Share", "Files"
+    );
+    Paragraph::new(Line::styled(header, ctx.theme.title))
+        .render(Rect::new(inner.x, inner.y, inner.width, 1), buf);
+
+    let visible = inner.height.saturating_sub(1) as usize;
+    let offset = if selected >= visible {
+        selected - visible + 1
+    } else {
+        0
+    };
+
+    for (i, row) in rows.iter().enumerate().skip(offset).take(visible) {
+        let y = inner.y + 1 + (i - offset) as u16;
+        let style = if i == selected {
+            ctx.theme.selected
+        } else {
+            Style::default()
+        };
+        let (name, size, files) = match row {
+            TypeRow::Category(c) => (c.category.label(), c.total_size, c.file_count),
+            TypeRow::Extension(e) => (e.display_name(), e.total_size, e.file_count),
+        };
+        let share = breakdown.share(size);
+        let stats = format!(
+            " {:<14} {:>10} {:>5.1}% {:>10}  ",
+            truncate_to_width(name, 14),
+            format_size(size),
+            share * 100.0,
+            files,
+        );
+        let stats_width = stats.chars().count() as u16;
+        Paragraph::new(Line::styled(stats, style))
+            .render(Rect::new(inner.x, y, inner.width, 1), buf);
+
+        // The bar fills the rest of the row, scaled to the share.
+        let bar_width = inner.width.saturating_sub(stats_width + 1);
+        let bar_area = Rect::new(inner.x + stats_width, y, bar_width, 1).intersection(inner);
+        CompactSizeBar::new(share)
+            .style(Style::default().fg(ctx.theme.info))
+            .render(bar_area, buf);
+    }
+}
+
+fn render_cleanup(ctx: &RenderContext, area: Rect, buf: &mut Buffer) {
+    let Some(report) = ctx.cleanup else {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(ctx.theme.border)
+            .title(" Cleanup ")
+            .title_style(ctx.theme.title);
+        let inner = block.inner(area);
+        block.render(area, buf);
+        Paragraph::new(Line::styled(
+            " Cleanup candidates are available after a full scan completes.",
+            Style::default().fg(ctx.theme.muted),
+        ))
+        .render(inner, buf);
+        return;
+    };
+
+    let marked_size: u64 = report
+        .candidates
+        .iter()
+        .filter(|c| ctx.marked.contains(&c.path))
+        .map(|c| c.size)
+        .sum();
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(ctx.theme.border)
+        .title(format!(
+            " Cleanup - {} reclaimable in {} directories ({} marked) ",
+            format_size(report.total_reclaimable),
+            report.candidates.len(),
+            format_size(marked_size)
+        ))
+        .title_style(ctx.theme.title);
+    let inner = block.inner(area);
+    block.render(area, buf);
+
+    if report.candidates.is_empty() {
+        Paragraph::new(Line::styled(
+            " No regenerable build artifacts or caches found.",
+            Style::default().fg(ctx.theme.success),
+        ))
+        .render(inner, buf);
+        return;
+    }
+
+    let visible = inner.height as usize;
+    let selected = ctx
+        .selected_cleanup
+        .min(report.candidates.len().saturating_sub(1));
+    let offset = if selected >= visible {
+        selected - visible + 1
+    } else {
+        0
+    };
+
+    for (i, candidate) in report
+        .candidates
+        .iter()
+        .enumerate()
+        .skip(offset)
+        .take(visible)
+    {
+        let y = inner.y + (i - offset) as u16;
+        let is_marked = ctx.marked.contains(&candidate.path);
+        let style = if i == selected {
+            ctx.theme.selected
+        } else {
+            Style::default()
+        };
+        let relative = candidate
+            .path
+            .strip_prefix(ctx.path)
+            .map(|p| p.display().to_string())
+            .unwrap_or_else(|_| candidate.path.display().to_string());
+
+        let line = Line::from(vec![
+            Span::styled(
+                if is_marked { " ● " } else { "   " },
+                style.patch(ctx.theme.marked),
+            ),
+            Span::styled(format!("{:>10}  ", format_size(candidate.size)), style),
+            Span::styled(
+                format!("{:<18} ", truncate_to_width(&candidate.description, 18)),
+                style.fg(ctx.theme.muted),
+            ),
+            Span::styled(relative, style.patch(ctx.theme.directory)),
+        ]);
+        Paragraph::new(line).render(Rect::new(inner.x, y, inner.width, 1), buf);
+    }
+}
+
+fn render_largest(ctx: &RenderContext, area: Rect, buf: &mut Buffer) {
+    let state = ctx.largest_state;
+    let kind = match state.kind {
+        TopKind::Files => "Files",
+        TopKind::Dirs => "Leaf Directories",
+        TopKind::Both => "Files and Leaf Directories",
+    };
+    let relative = state
+        .root
+        .strip_prefix(ctx.path)
+        .ok()
+        .filter(|p| !p.as_os_str().is_empty())
+        .map(|p| format!(" in {}", p.display()))
+        .unwrap_or_default();
+    let filters = if state.filters.is_empty() {
+        String::new()
+    } else {
+        format!(" [{}]", state.filters.join(" "))
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(ctx.theme.border)
+        .title(format!(" Largest {}{}{} ", kind, relative, filters))
+        .title_style(ctx.theme.title);
+    let inner = block.inner(area);
+    block.render(area, buf);
+
+    if state.entries.is_empty() {
+        Paragraph::new(Line::styled(
+            " Nothing matches here.",
+            Style::default().fg(ctx.theme.muted),
+        ))
+        .render(inner, buf);
+        return;
+    }
+
+    let visible = inner.height as usize;
+    let selected = state.selected.min(state.entries.len().saturating_sub(1));
+    let offset = if selected >= visible {
+        selected - visible + 1
+    } else {
+        0
+    };
+    let now = SystemTime::now();
+
+    for (i, entry) in state.entries.iter().enumerate().skip(offset).take(visible) {
+        let y = inner.y + (i - offset) as u16;
+        let is_marked = ctx.marked.contains(&entry.path);
+        let style = if i == selected {
+            ctx.theme.selected
+        } else {
+            Style::default()
+        };
+        let relative = entry
+            .path
+            .strip_prefix(&state.root)
+            .map(|p| p.display().to_string())
+            .unwrap_or_else(|_| entry.path.display().to_string());
+        let (name, name_style) = if entry.is_dir {
+            (format!("{}/", relative), style.patch(ctx.theme.directory))
+        } else {
+            (relative, style)
+        };
+        let age = now.duration_since(entry.modified).unwrap_or_default();
+
+        let line = Line::from(vec![
+            Span::styled(
+                if is_marked { " ● " } else { "   " },
+                style.patch(ctx.theme.marked),
+            ),
+            Span::styled(format!("{:>4}. ", i + 1), style.fg(ctx.theme.muted)),
+            Span::styled(format!("{:>10}  ", format_size(entry.size)), style),
+            Span::styled(
+                format!("{:>10}  ", format_age(age)),
+                style.fg(ctx.theme.muted),
+            ),
+            Span::styled(name, name_style),
+        ]);
+        Paragraph::new(line).render(Rect::new(inner.x, y, inner.width, 1), buf);
+    }
+}
+
+fn render_timeline(ctx: &RenderContext, area: Rect, buf: &mut Buffer) {
+    let state = ctx.timeline_state;
+    let timeline = &state.timeline;
+    let relative = state
+        .root
+        .strip_prefix(ctx.path)
+        .ok()
+        .filter(|p| !p.as_os_str().is_empty())
+        .map(|p| format!(" - {}", p.display()))
+        .unwrap_or_default();
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(ctx.theme.border)
+        .title(format!(
+            " Timeline by {}{} ({} files, {}) ",
+            timeline.period,
+            relative,
+            timeline.file_count,
+            format_size(timeline.total_size)
+        ))
+        .title_style(ctx.theme.title);
+    let inner = block.inner(area);
+    block.render(area, buf);
+
+    if timeline.bins.is_empty() {
+        Paragraph::new(Line::styled(
+            " No files here.",
+            Style::default().fg(ctx.theme.muted),
+        ))
+        .render(inner, buf);
+        return;
+    }
+
+    // Label, size and count columns take 34 cells; the rest is split
+    // between the bytes bar and the files bar.
+    let bar_width = (inner.width.saturating_sub(34) / 2) as usize;
+    let max_size = timeline.max_bin_size().max(1);
+    let max_files = timeline.max_bin_files().max(1);
+    let scale = |value: u64, max: u64| -> usize {
+        if value == 0 {
+            0
+        } else {
+            ((value as f64 / max as f64) * bar_width as f64).ceil() as usize
+        }
+    };
+
+    let visible = inner.height as usize;
+    let selected = state.selected.min(timeline.bins.len().saturating_sub(1));
+    let offset = if selected >= visible {
+        selected - visible + 1
+    } else {
+        0
+    };
+
+    for (i, bin) in timeline.bins.iter().enumerate().skip(offset).take(visible) {
+        let y = inner.y + (i - offset) as u16;
+        let style = if i == selected {
+            ctx.theme.selected
+        } else {
+            Style::default()
+        };
+        let size_bar = "\u{2588}".repeat(scale(bin.total_size, max_size));
+        let files_bar = "\u{2588}".repeat(scale(bin.file_count, max_files));
+        let line = Line::from(vec![
+            Span::styled(format!(" {:<10} ", bin.label), style),
+            Span::styled(format!("{:>10} ", format_size(bin.total_size)), style),
+            Span::styled(
+                format!("{:>8} files ", bin.file_count),
+                style.fg(ctx.theme.muted),
+            ),
+            Span::styled(format!("{:<width$} ", size_bar, width = bar_width), style),
+            Span::styled(files_bar, style.fg(ctx.theme.muted)),
+        ]);
+        Paragraph::new(line).render(Rect::new(inner.x, y, inner.width, 1), buf);
+    }
+}
+
+fn render_hygiene(ctx: &RenderContext, area: Rect, buf: &mut Buffer) {
+    let state = ctx.hygiene_state;
+    let Some(report) = &state.report else {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(ctx.theme.border)
+            .title(" Hygiene ")
+            .title_style(ctx.theme.title);
+        let inner = block.inner(area);
+        block.render(area, buf);
+        Paragraph::new(Line::styled(
+            " Hygiene checks are available after a full scan completes.",
+            Style::default().fg(ctx.theme.muted),
+        ))
+        .render(inner, buf);
+        return;
+    };
+
+    let relative = state
+        .root
+        .strip_prefix(ctx.path)
+        .ok()
+        .filter(|p| !p.as_os_str().is_empty())
+        .map(|p| format!(" - {}", p.display()))
+        .unwrap_or_default();
+    let shown = match state.kind {
+        Some(kind) => kind.description().to_string(),
+        None => HygieneKind::ALL
+            .iter()
+            .map(|&kind| format!("{} {}", report.count(kind), kind))
+            .collect::<Vec<_>>()
+            .join(", "),
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(ctx.theme.border)
+        .title(format!(" Hygiene{} ({}) ", relative, shown))
+        .title_style(ctx.theme.title);
+    let inner = block.inner(area);
+    block.render(area, buf);
+
+    let entries = state.entries();
+    if entries.is_empty() {
+        Paragraph::new(Line::styled(
+            " Nothing to tidy up here.",
+            Style::default().fg(ctx.theme.success),
+        ))
+        .render(inner, buf);
+        return;
+    }
+
+    let visible = inner.height as usize;
+    let selected = state.selected.min(entries.len().saturating_sub(1));
+    let offset = if selected >= visible {
+        selected - visible + 1
+    } else {
+        0
+    };
+
+    for (i, entry) in entries.iter().enumerate().skip(offset).take(visible) {
+        let y = inner.y + (i - offset) as u16;
+        let is_marked = ctx.marked.contains(&entry.path);
+        let style = if i == selected {
+            ctx.theme.selected
+        } else {
+            Style::default()
+        };
+        let kind = entry.kind();
+        let kind_style = if kind.is_deletable() {
+            style.fg(ctx.theme.muted)
+        } else {
+            style.fg(ctx.theme.warning)
+        };
+        let relative = entry
+            .path
+            .strip_prefix(&state.root)
+            .map(|p| p.display().to_string())
+            .unwrap_or_else(|_| entry.path.display().to_string());
+        let (name, name_style) = if kind == HygieneKind::EmptyDir {
+            (format!("{}/", relative), style.patch(ctx.theme.directory))
+        } else {
+            (relative, style)
+        };
+
+        let line = Line::from(vec![
+            Span::styled(
+                if is_marked { " ● " } else { "   " },
+                style.patch(ctx.theme.marked),
+            ),
+            Span::styled(format!("{:<10} ", kind.label()), kind_style),
+            Span::styled(name, name_style),
+            Span::styled(
+                format!("  {}", entry.issue.detail()),
+                style.fg(ctx.theme.muted),
+            ),
+        ]);
+        Paragraph::new(line).render(Rect::new(inner.x, y, inner.width, 1), buf);
+    }
+}
+
+fn render_jobs(ctx: &RenderContext, area: Rect, buf: &mut Buffer) {
+    let active = ctx.jobs.iter().filter(|j| !j.state.is_finished()).count();
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(ctx.theme.border)
+        .title(format!(
+            " Jobs ({} active, {} total) ",
+            active,
+            ctx.jobs.len()
+        ))
+        .title_style(ctx.theme.title);
+
+    let inner = block.inner(area);
+    block.render(area, buf);
+
+    if ctx.jobs.is_empty() {
+        Paragraph::new(Line::styled(
+            " No background jobs. Copy and move operations run here.",
+            Style::default().fg(ctx.theme.muted),
+        ))
+        .render(inner, buf);
+        return;
+    }
+
+    let lines_per_item = 2;
+    let visible = (inner.height as usize / lines_per_item).max(1);
+    let selected = ctx.jobs_state.selected.min(ctx.jobs.len() - 1);
+    let offset = if selected >= visible {
+        selected - visible + 1
+    } else {
+        0
+    };
+
+    // Fixed columns: id(6) + state(11) + percent(6) + rate(12) + spacing
+    let desc_width = (inner.width as usize).saturating_sub(38);
+
+    for (i, job) in ctx.jobs.iter().enumerate().skip(offset).take(visible) {
+        let y = inner.y + ((i - offset) * lines_per_item) as u16;
+        let is_selected = i == selected;
+
+        let percent = match (&job.progress, job.state) {
+            (_, gravityfile_ops::JobState::Completed) => "100%".to_string(),
+            (Some(progress), _) => format!("{:.0}%", progress.percentage()),
+            (None, _) => "-".to_string(),
+        };
+        let rate = job
+            .throughput()
+            .map(|r| format!("{}/s", format_size(r as u64)))
+            .unwrap_or_default();
+        let desc = job.description();
+        let desc = if desc.chars().count() > desc_width && desc_width > 3 {
+            let tail: String = desc
+                .chars()
+                .skip(desc.chars().count() - (desc_width - 3))
+                .collect();
+            format!("...{}", tail)
+        } else {
+            desc
+        };
+
+        let state_style = match job.state {
+            gravityfile_ops::JobState::Running => Style::default().fg(ctx.theme.info),
+            gravityfile_ops::JobState::Paused => Style::default().fg(ctx.theme.warning),
+            gravityfile_ops::JobState::Failed | gravityfile_ops::JobState::Cancelled => {
+                Style::default().fg(ctx.theme.error)
+            }
+            gravityfile_ops::JobState::Completed => Style::default().fg(ctx.theme.success),
+            gravityfile_ops::JobState::Queued => Style::default().fg(ctx.theme.muted),
+        };
+        let style = if is_selected {
+            ctx.theme.selected
+        } else {
+            Style::default()
+        };
+
+        let line = Line::from(vec![
+            Span::styled(format!(" #{:<4}", job.id), style),
+            Span::styled(format!("{:<width$}", desc, width = desc_width), style),
+            Span::styled(
+                format!(" {:<10}", job.state.to_string()),
+                if is_selected { style } else { state_style },
+            ),
+            Span::styled(format!(" {:>5}", percent), style),
+            Span::styled(format!(" {:>12}", rate), style),
+        ]);
+        Paragraph::new(line).render(Rect::new(inner.x, y, inner.width, 1), buf);
+
+        if y + 1 < inner.y + inner.height {
+            let detail = match (job.errors.last(), &job.progress) {
+                (Some(last), _) => format!(
+                    "      {} error(s), last: {}",
+                    job.errors.len(),
+                    last.message
+                ),
+                (None, Some(progress)) => format!(
+                    "      {}/{} files, {} of {}",
+                    progress.files_completed,
+                    progress.files_total,
+                    format_size(progress.bytes_processed),
+                    format_size(progress.bytes_total)
+                ),
+                (None, None) => String::new(),
+            };
+            let detail_style = if job.errors.is_empty() {
+                Style::default().fg(ctx.theme.muted)
+            } else {
+                Style::default().fg(ctx.theme.warning)
+            };
+            Paragraph::new(Line::styled(detail, detail_style))
+                .render(Rect::new(inner.x, y + 1, inner.width, 1), buf);
+        }
+    }
+}
+
 fn render_treemap(ctx: &RenderContext, area: Rect, buf: &mut Buffer) {
     let Some(tree) = ctx.tree else {
         let block = Block::default()
@@ -1424,6 +2437,7 @@ fn render_footer(ctx: &RenderContext, area: Rect, buf: &mut Buffer) {
                 // Paste if clipboard has content
                 if !ctx.clipboard.is_empty() {
                     v.push(("p", "Paste"));
+                    v.push(("L", "Link"));
                 }
 
                 // Esc clears clipboard (if any) then marks (if any)
@@ -1479,7 +2493,7 @@ fn render_footer(ctx: &RenderContext, area: Rect, buf: &mut Buffer) {
                 v
             }
             View::Age => {
-                let mut v = vec![("j/k", "Nav"), ("y", "Copy"), ("d", "Del")];
+                let mut v = vec![("j/k", "Nav"), ("v", "Basis"), ("y", "Copy"), ("d", "Del")];
                 if !ctx.clipboard.is_empty() {
                     v.push(("Esc", "Unclip"));
                 } else if !ctx.marked.is_empty() {
@@ -1499,6 +2513,64 @@ fn render_footer(ctx: &RenderContext, area: Rect, buf: &mut Buffer) {
                 }
                 v
             }
+            View::Trash => {
+                let mut v = vec![
+                    ("j/k", "Nav"),
+                    ("Spc", "+Sel"),
+                    ("r/Enter", "Restore"),
+                    ("d", "Purge"),
+                ];
+                if !ctx.trash_state.marked.is_empty() {
+                    v.push(("Esc", "Clear"));
+                }
+                v
+            }
+            View::Jobs => vec![
+                ("j/k", "Nav"),
+                ("p", "Pause/Resume"),
+                ("d", "Cancel"),
+                ("Esc", "Clear done"),
+            ],
+            View::Mounts => vec![("j/k", "Nav"), ("Enter", "Scan mount")],
+            View::Types => {
+                if ctx.types_state.category.is_some() {
+                    vec![("j/k", "Nav"), ("Esc", "Categories"), ("Bksp", "Up dir")]
+                } else {
+                    vec![("j/k", "Nav"), ("Enter", "Extensions"), ("Bksp", "Up dir")]
+                }
+            }
+            View::Cleanup => vec![
+                ("j/k", "Nav"),
+                ("Spc", "Mark"),
+                ("a", "Mark all"),
+                ("d", "Delete"),
+                ("Enter", "Show"),
+            ],
+            View::Largest => vec![
+                ("j/k", "Nav"),
+                ("Spc", "Mark"),
+                ("d", "Delete"),
+                ("Enter", "Show"),
+                ("v", "Files/Dirs"),
+            ],
+            View::Timeline => vec![
+                ("j/k", "Nav"),
+                ("Enter", "Filter explorer"),
+                ("v", "Week/Month"),
+            ],
+            View::Hygiene => vec![
+                ("j/k", "Nav"),
+                ("Spc", "Mark"),
+                ("a", "Mark all"),
+                ("d", "Delete"),
+                ("Enter", "Show"),
+                ("v", "Kind"),
+            ],
+            View::Owners => vec![
+                ("j/k", "Nav"),
+                ("Enter", "Largest dir"),
+                ("v", "Users/Groups"),
+            ],
             View::Treemap => {
                 let mut v = vec![
                     ("Enter", "Drill"),
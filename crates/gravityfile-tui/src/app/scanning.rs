@@ -1,26 +1,24 @@
 //! Background scanning and analysis.
 
-use std::path::PathBuf;
-
 use tokio::sync::mpsc;
 
-use gravityfile_analyze::{AgeAnalyzer, AgeConfig, DuplicateConfig, DuplicateFinder};
+use gravityfile_analyze::{
+    AgeAnalyzer, AgeConfig, AgeReport, CleanupConfig, CleanupFinder, DuplicateConfig,
+    DuplicateFinder, FileTypeAnalyzer, OwnershipAnalyzer,
+};
 use gravityfile_core::FileTree;
-use gravityfile_scan::{JwalkScanner, ScanConfig};
+use gravityfile_scan::{JwalkScanner, MountTable, ScanConfig};
 
-use super::constants::{
-    ANALYSIS_CHANNEL_SIZE, MAX_DUPLICATE_GROUPS, MIN_DUPLICATE_SIZE, SCAN_CHANNEL_SIZE,
-};
+use super::constants::{ANALYSIS_CHANNEL_SIZE, MAX_DUPLICATE_GROUPS, SCAN_CHANNEL_SIZE};
 use super::state::ScanResult;
 
 /// Start a background filesystem scan.
 ///
 /// Returns a receiver that will receive scan progress updates and the final result.
-pub fn start_scan(path: PathBuf) -> mpsc::Receiver<ScanResult> {
+pub fn start_scan(config: ScanConfig) -> mpsc::Receiver<ScanResult> {
     let (tx, rx) = mpsc::channel(SCAN_CHANNEL_SIZE);
 
     tokio::spawn(async move {
-        let config = ScanConfig::new(&path);
         let scanner = JwalkScanner::new();
         let mut progress_rx = scanner.subscribe();
 
@@ -55,35 +53,57 @@ pub fn start_scan(path: PathBuf) -> mpsc::Receiver<ScanResult> {
     rx
 }
 
+/// Analyze file ages, flagging mounts that limit access-time updates when
+/// ages are measured from access times.
+pub fn analyze_age(tree: &FileTree, config: AgeConfig) -> AgeReport {
+    let mut report = AgeAnalyzer::with_config(config).analyze(tree);
+    if report.time_basis.uses_atime() {
+        report.atime_warning = MountTable::load_without_usage()
+            .ok()
+            .and_then(|table| table.atime_caveat(tree));
+    }
+    report
+}
+
 /// Start background analysis of a scanned tree.
 ///
 /// Returns a receiver that will receive the analysis results.
-pub fn start_analysis(tree: FileTree) -> mpsc::Receiver<ScanResult> {
+pub fn start_analysis(
+    tree: FileTree,
+    mut dup_config: DuplicateConfig,
+    age_config: AgeConfig,
+    cleanup_config: CleanupConfig,
+) -> mpsc::Receiver<ScanResult> {
     let (tx, rx) = mpsc::channel(ANALYSIS_CHANNEL_SIZE);
 
+    // An unlimited profile setting still gets the view's cap.
+    if dup_config.max_groups == 0 {
+        dup_config.max_groups = MAX_DUPLICATE_GROUPS;
+    }
+
     tokio::spawn(async move {
         let result = tokio::task::spawn_blocking(move || {
-            let dup_config = DuplicateConfig::builder()
-                .min_size(MIN_DUPLICATE_SIZE)
-                .max_groups(MAX_DUPLICATE_GROUPS)
-                .build()
-                .unwrap();
             let finder = DuplicateFinder::with_config(dup_config);
             let duplicates = finder.find_duplicates(&tree);
 
-            let age_config = AgeConfig::default();
-            let analyzer = AgeAnalyzer::with_config(age_config);
-            let age_report = analyzer.analyze(&tree);
+            let age_report = analyze_age(&tree, age_config);
+
+            let ownership = OwnershipAnalyzer::new().analyze(&tree);
+            let file_types = FileTypeAnalyzer::new().analyze(&tree);
+            let cleanup = CleanupFinder::with_config(&cleanup_config).find(&tree);
 
-            (duplicates, age_report)
+            (duplicates, age_report, ownership, file_types, cleanup)
         })
         .await;
 
-        if let Ok((duplicates, age_report)) = result {
+        if let Ok((duplicates, age_report, ownership, file_types, cleanup)) = result {
             let _ = tx
                 .send(ScanResult::AnalysisComplete {
                     duplicates,
                     age_report,
+                    ownership,
+                    file_types,
+                    cleanup,
                 })
                 .await;
         }
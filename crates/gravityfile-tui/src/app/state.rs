@@ -1,15 +1,28 @@
 //! Application state types and enums.
 
-use std::collections::HashMap;
-use std::path::PathBuf;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
 
 use serde::{Deserialize, Serialize};
 use strum::{Display, EnumIter, FromRepr, IntoEnumIterator};
 
-use gravityfile_analyze::{AgeReport, DuplicateReport};
-use gravityfile_core::FileTree;
-use gravityfile_ops::{Conflict, OperationProgress, OperationType};
-use gravityfile_scan::ScanProgress;
+use gravityfile_analyze::{
+    AgeReport, CategoryStats, CleanupReport, DuplicateReport, ExtensionStats, FileCategory,
+    FileTypeReport, HygieneAnalyzer, HygieneEntry, HygieneKind, HygieneReport, OwnerUsage,
+    OwnershipReport, Profile, Query, Timeline, TimelineBin, TimelinePeriod, TopEntry, TopFinder,
+    TopKind, TypeBreakdown, format_duration_spec, parse_duration,
+};
+use gravityfile_core::{FileNode, FileTree};
+use gravityfile_ops::{
+    CaseTransform, ChmodSpec, Conflict, ConflictResolution, CopyOptions, LinkKind, ModeSpec,
+    OperationPlan, OperationProgress, OperationType, PlanAction, RenamePreview, RenameRule,
+    RenameStatus, SyncDiff, SyncOptions, TrashEntry, UndoEntry, UndoableOperation, preview_renames,
+};
+use gravityfile_scan::{IgnoreMode, Mount, ScanProgress};
+
+use super::constants::{PLAN_REVIEW_BYTES, PLAN_REVIEW_STEPS, TOP_COUNT};
+use super::input::InputState;
 
 /// Application mode representing the current UI state.
 /// Note: Scanning is NOT a mode - scanning happens in the background
@@ -25,10 +38,6 @@ pub enum AppMode {
     ConfirmDelete,
     /// Deletion in progress.
     Deleting,
-    /// Copy operation in progress.
-    Copying,
-    /// Move operation in progress.
-    Moving,
     /// Renaming a file or directory (text input mode).
     Renaming,
     /// Creating a new file (text input mode).
@@ -55,6 +64,22 @@ pub enum AppMode {
     ConfirmBulkRename,
     /// Visual selection mode (vim-style range selection).
     Visual,
+    /// Confirming permanent removal of items from the trash.
+    ConfirmPurge,
+    /// Browsing the undo history modal.
+    History,
+    /// Reviewing the dry-run plan of a paste before it runs.
+    ConfirmPlan,
+    /// Reviewing the diff between two tabs before syncing them.
+    ConfirmSync,
+    /// Sync operation in progress.
+    Syncing,
+    /// Confirming a secure shred by typing the confirmation word.
+    ConfirmShred,
+    /// Editing permissions of the selection.
+    Permissions,
+    /// Renaming the selection with a regex or template rule.
+    RenamePattern,
     Quit,
 }
 
@@ -285,8 +310,17 @@ pub enum View {
     Explorer,
     Duplicates,
     Age,
+    Owners,
+    Types,
+    Cleanup,
+    Largest,
+    Timeline,
+    Hygiene,
     Errors,
     Treemap,
+    Trash,
+    Jobs,
+    Mounts,
 }
 
 impl View {
@@ -354,6 +388,9 @@ pub enum ScanResult {
     AnalysisComplete {
         duplicates: DuplicateReport,
         age_report: AgeReport,
+        ownership: OwnershipReport,
+        file_types: FileTypeReport,
+        cleanup: CleanupReport,
     },
     /// Progress update during deletion.
     DeletionProgress(DeletionProgress),
@@ -362,6 +399,8 @@ pub enum ScanResult {
         deleted: usize,
         failed: usize,
         bytes_freed: u64,
+        /// Original paths of items that were moved to the trash (for undo).
+        trashed: Vec<PathBuf>,
     },
     /// Progress update during file operations (copy/move/etc).
     OperationProgress(OperationProgress),
@@ -373,7 +412,13 @@ pub enum ScanResult {
         succeeded: usize,
         failed: usize,
         bytes_processed: u64,
+        /// What to record in the undo log, if the operation changed anything.
+        undo: Option<UndoableOperation>,
     },
+    /// Dry-run plan for a pending paste is ready.
+    PlanReady(Box<OperationPlan>),
+    /// Comparison of the two trees of a pending sync is ready.
+    SyncDiffReady(Box<SyncDiff>),
 }
 
 /// Information about the currently selected item.
@@ -391,14 +436,314 @@ pub struct SelectedInfo {
 /// A pending file operation waiting for conflict resolution.
 #[derive(Debug, Clone)]
 pub enum PendingOperation {
-    /// Paste operation (copy or move).
+    /// Paste operation (copy or move, or links to the clipboard items).
     Paste {
         sources: Vec<PathBuf>,
         destination: PathBuf,
         mode: ClipboardMode,
+        /// Create links of this kind instead of copying or moving.
+        link: Option<LinkKind>,
     },
 }
 
+/// State for the plan confirmation modal.
+#[derive(Debug, Clone)]
+pub struct PlanReviewState {
+    /// The paste waiting on the plan.
+    pub operation: PendingOperation,
+    /// Conflict resolution the paste will run with.
+    pub resolution: Option<ConflictResolution>,
+    /// The computed plan, or `None` while planning is still running.
+    pub plan: Option<OperationPlan>,
+    /// Scroll offset into the step list.
+    pub scroll: usize,
+}
+
+impl PlanReviewState {
+    /// Create a review state for an operation whose plan is being computed.
+    pub fn new(operation: PendingOperation, resolution: Option<ConflictResolution>) -> Self {
+        Self {
+            operation,
+            resolution,
+            plan: None,
+            scroll: 0,
+        }
+    }
+
+    /// Whether a plan is worth showing before running it.
+    ///
+    /// Small, clean, same-filesystem pastes run straight away.
+    pub fn requires_confirmation(plan: &OperationPlan) -> bool {
+        plan.has_problems()
+            || plan.total_bytes >= PLAN_REVIEW_BYTES
+            || plan.steps.len() >= PLAN_REVIEW_STEPS
+            || plan.count(PlanAction::CrossDeviceMove) > 0
+    }
+
+    /// Scroll the step list up.
+    pub fn scroll_up(&mut self) {
+        self.scroll = self.scroll.saturating_sub(1);
+    }
+
+    /// Scroll the step list down.
+    pub fn scroll_down(&mut self) {
+        let len = self.plan.as_ref().map_or(0, |p| p.steps.len());
+        if self.scroll + 1 < len {
+            self.scroll += 1;
+        }
+    }
+}
+
+/// State for the sync preview modal.
+#[derive(Debug, Clone)]
+pub struct SyncReviewState {
+    /// Directory the sync copies from (the active tab).
+    pub source: PathBuf,
+    /// Directory the sync copies to (the other tab).
+    pub destination: PathBuf,
+    /// Options the diff is computed and applied with.
+    pub options: SyncOptions,
+    /// The computed diff, or `None` while comparing is still running.
+    pub diff: Option<SyncDiff>,
+    /// Scroll offset into the entry list.
+    pub scroll: usize,
+}
+
+impl SyncReviewState {
+    /// Create a review state for a diff that is being computed.
+    pub fn new(source: PathBuf, destination: PathBuf, options: SyncOptions) -> Self {
+        Self {
+            source,
+            destination,
+            options,
+            diff: None,
+            scroll: 0,
+        }
+    }
+
+    /// Number of entries the sync would act on.
+    pub fn pending(&self) -> usize {
+        self.diff
+            .as_ref()
+            .map_or(0, |d| d.pending(&self.options).count())
+    }
+
+    /// Scroll the entry list up.
+    pub fn scroll_up(&mut self) {
+        self.scroll = self.scroll.saturating_sub(1);
+    }
+
+    /// Scroll the entry list down.
+    pub fn scroll_down(&mut self) {
+        let len = self.diff.as_ref().map_or(0, |d| d.entries.len());
+        if self.scroll + 1 < len {
+            self.scroll += 1;
+        }
+    }
+}
+
+/// Word the user must type to confirm a shred.
+pub const SHRED_CONFIRM_WORD: &str = "shred";
+
+/// State for the shred confirmation modal.
+#[derive(Debug, Clone)]
+pub struct ShredConfirmState {
+    /// Items to shred with their sizes.
+    pub targets: Vec<(PathBuf, u64)>,
+    /// Random overwrite passes.
+    pub passes: u32,
+    /// Copy-on-write warnings for the target filesystems.
+    pub warnings: Vec<String>,
+    /// Typed confirmation.
+    pub input: InputState,
+}
+
+impl ShredConfirmState {
+    /// Total bytes that will be overwritten.
+    pub fn total_size(&self) -> u64 {
+        self.targets.iter().map(|(_, size)| size).sum()
+    }
+}
+
+/// Which items a chmod from the permissions modal applies to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ChmodScope {
+    /// Files and directories alike.
+    #[default]
+    All,
+    /// Only files (and other non-directories).
+    Files,
+    /// Only directories.
+    Dirs,
+}
+
+impl ChmodScope {
+    /// Cycle to the next scope.
+    pub fn next(self) -> Self {
+        match self {
+            Self::All => Self::Files,
+            Self::Files => Self::Dirs,
+            Self::Dirs => Self::All,
+        }
+    }
+
+    /// Label shown in the modal.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::All => "files and directories",
+            Self::Files => "files only",
+            Self::Dirs => "directories only",
+        }
+    }
+}
+
+/// Current permissions of one selected item.
+#[derive(Debug, Clone)]
+pub struct PermissionEntry {
+    /// Path of the item.
+    pub path: PathBuf,
+    /// Whether the item is a directory.
+    pub is_dir: bool,
+    /// Permission bits (including setuid, setgid and sticky).
+    pub mode: u32,
+    /// Owner name, or numeric uid if unknown.
+    pub owner: String,
+    /// Group name, or numeric gid if unknown.
+    pub group: String,
+}
+
+/// State for the permissions modal.
+#[derive(Debug, Clone)]
+pub struct PermissionsState {
+    /// The selection with its current permissions.
+    pub entries: Vec<PermissionEntry>,
+    /// Mode being typed (octal or symbolic).
+    pub input: InputState,
+    /// Which items the mode applies to.
+    pub scope: ChmodScope,
+    /// Apply to directory contents too.
+    pub recursive: bool,
+}
+
+impl PermissionsState {
+    /// Parse the typed mode into a chmod spec.
+    pub fn spec(&self) -> Result<ChmodSpec, String> {
+        let mode = ModeSpec::parse(self.input.buffer())?;
+        let (files, dirs) = match self.scope {
+            ChmodScope::All => (Some(mode.clone()), Some(mode)),
+            ChmodScope::Files => (Some(mode), None),
+            ChmodScope::Dirs => (None, Some(mode)),
+        };
+        Ok(ChmodSpec {
+            files,
+            dirs,
+            recursive: self.recursive,
+        })
+    }
+
+    /// Mode the first entry would end up with, if the typed mode is valid.
+    pub fn preview(&self) -> Option<u32> {
+        let entry = self.entries.first()?;
+        let spec = self.spec().ok()?;
+        Some(
+            spec.new_mode(entry.mode, entry.is_dir)
+                .unwrap_or(entry.mode & 0o7777),
+        )
+    }
+}
+
+/// Input field of the rename pattern modal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RenameField {
+    /// Regex to find (empty for a whole-name template).
+    #[default]
+    Find,
+    /// Replacement or template.
+    Replace,
+}
+
+/// State for the rename pattern modal.
+#[derive(Debug, Clone)]
+pub struct RenamePatternState {
+    /// Files to rename, in counter order.
+    pub paths: Vec<PathBuf>,
+    /// Regex input.
+    pub find: InputState,
+    /// Replacement/template input.
+    pub replace: InputState,
+    /// Field receiving keys.
+    pub focus: RenameField,
+    /// Case conversion applied to the new names.
+    pub case: CaseTransform,
+    /// Live preview, or why the rule is invalid.
+    pub preview: Result<Vec<RenamePreview>, String>,
+    /// Scroll offset of the preview list.
+    pub offset: usize,
+}
+
+impl RenamePatternState {
+    /// Create the state for `paths`, optionally prefilled.
+    pub fn new(paths: Vec<PathBuf>, find: &str, replace: &str) -> Self {
+        let mut state = Self {
+            paths,
+            find: InputState::with_initial(find),
+            replace: InputState::with_initial(replace),
+            focus: RenameField::default(),
+            case: CaseTransform::Keep,
+            preview: Ok(Vec::new()),
+            offset: 0,
+        };
+        state.refresh();
+        state
+    }
+
+    /// The rule described by the inputs.
+    pub fn rule(&self) -> RenameRule {
+        RenameRule::regex(self.find.buffer(), self.replace.buffer()).with_case(self.case)
+    }
+
+    /// Recompute the preview after an input change.
+    pub fn refresh(&mut self) {
+        self.preview = preview_renames(&self.paths, &self.rule());
+    }
+
+    /// The focused input.
+    pub fn input_mut(&mut self) -> &mut InputState {
+        match self.focus {
+            RenameField::Find => &mut self.find,
+            RenameField::Replace => &mut self.replace,
+        }
+    }
+
+    /// Number of collisions and invalid names in the preview.
+    pub fn problems(&self) -> usize {
+        self.preview
+            .as_ref()
+            .map_or(0, |p| p.iter().filter(|p| p.is_problem()).count())
+    }
+
+    /// `(path, new_name)` pairs of the renames that will run.
+    pub fn ready(&self) -> Vec<(PathBuf, String)> {
+        self.preview
+            .as_ref()
+            .map(|previews| {
+                previews
+                    .iter()
+                    .filter(|p| p.status == RenameStatus::Ready)
+                    .map(|p| (p.source.clone(), p.new_name.clone()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// State for the background jobs view.
+#[derive(Debug, Clone, Default)]
+pub struct JobsViewState {
+    /// Index of the selected job.
+    pub selected: usize,
+}
+
 /// State for the duplicates view with expandable groups.
 #[derive(Debug, Clone, Default)]
 pub struct DuplicatesViewState {
@@ -516,6 +861,374 @@ impl DuplicatesViewState {
     }
 }
 
+/// State for the trash view.
+#[derive(Debug, Clone, Default)]
+pub struct TrashViewState {
+    /// Items currently in the trash (newest first), `None` until loaded.
+    pub entries: Option<Vec<TrashEntry>>,
+    /// Index of the selected entry.
+    pub selected: usize,
+    /// Marked entries, keyed by their location inside the trash.
+    pub marked: HashSet<PathBuf>,
+    /// Error from the last attempt to read the trash.
+    pub error: Option<String>,
+}
+
+impl TrashViewState {
+    /// Replace the listing, dropping marks for entries that are gone.
+    pub fn set_entries(&mut self, entries: Vec<TrashEntry>) {
+        self.marked
+            .retain(|p| entries.iter().any(|e| &e.trashed_path == p));
+        self.selected = self.selected.min(entries.len().saturating_sub(1));
+        self.entries = Some(entries);
+        self.error = None;
+    }
+
+    /// Number of listed entries.
+    pub fn len(&self) -> usize {
+        self.entries.as_ref().map_or(0, Vec::len)
+    }
+
+    /// Whether there are no listed entries.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The entry under the cursor.
+    pub fn selected_entry(&self) -> Option<&TrashEntry> {
+        self.entries.as_ref()?.get(self.selected)
+    }
+
+    /// Toggle the mark on the selected entry.
+    pub fn toggle_mark(&mut self) {
+        if let Some(path) = self.selected_entry().map(|e| e.trashed_path.clone())
+            && !self.marked.remove(&path)
+        {
+            self.marked.insert(path);
+        }
+    }
+
+    /// Entries to act on: the marked ones, or the selected one if none are marked.
+    pub fn targets(&self) -> Vec<TrashEntry> {
+        let Some(entries) = &self.entries else {
+            return Vec::new();
+        };
+        if self.marked.is_empty() {
+            entries.get(self.selected).cloned().into_iter().collect()
+        } else {
+            entries
+                .iter()
+                .filter(|e| self.marked.contains(&e.trashed_path))
+                .cloned()
+                .collect()
+        }
+    }
+}
+
+/// Query filter applied to the Explorer tree.
+#[derive(Debug, Clone)]
+pub struct TreeFilter {
+    /// Query entries must satisfy to be shown.
+    pub query: Query,
+    /// The unfiltered tree, restored when the filter is cleared.
+    pub full_tree: FileTree,
+}
+
+/// State for the mounts view.
+#[derive(Debug, Clone, Default)]
+pub struct MountsViewState {
+    /// Mounted filesystems backed by real storage, `None` until loaded.
+    pub mounts: Option<Vec<Mount>>,
+    /// Index of the selected mount.
+    pub selected: usize,
+    /// Error from the last attempt to read the mount table.
+    pub error: Option<String>,
+}
+
+impl MountsViewState {
+    /// Replace the listing, keeping the selection in range.
+    pub fn set_mounts(&mut self, mounts: Vec<Mount>) {
+        self.selected = self.selected.min(mounts.len().saturating_sub(1));
+        self.mounts = Some(mounts);
+        self.error = None;
+    }
+
+    /// Number of listed mounts.
+    pub fn len(&self) -> usize {
+        self.mounts.as_ref().map_or(0, Vec::len)
+    }
+
+    /// Whether there are no listed mounts.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The mount under the cursor.
+    pub fn selected_mount(&self) -> Option<&Mount> {
+        self.mounts.as_ref()?.get(self.selected)
+    }
+}
+
+/// State for the Owners view.
+#[derive(Debug, Default)]
+pub struct OwnersViewState {
+    /// Index of the selected user or group.
+    pub selected: usize,
+    /// List groups instead of users.
+    pub show_groups: bool,
+}
+
+impl OwnersViewState {
+    /// The owners listed in the current mode.
+    pub fn entries<'a>(&self, report: &'a OwnershipReport) -> &'a [OwnerUsage] {
+        if self.show_groups {
+            &report.groups
+        } else {
+            &report.users
+        }
+    }
+
+    /// Switch between users and groups, resetting the selection.
+    pub fn toggle_groups(&mut self) {
+        self.show_groups = !self.show_groups;
+        self.selected = 0;
+    }
+}
+
+/// A row of the Types view: a category, or an extension within one.
+#[derive(Debug, Clone, Copy)]
+pub enum TypeRow<'a> {
+    Category(&'a CategoryStats),
+    Extension(&'a ExtensionStats),
+}
+
+/// State for the Types view.
+#[derive(Debug, Default)]
+pub struct TypesViewState {
+    /// Index of the selected row.
+    pub selected: usize,
+    /// Category drilled into; `None` lists the categories.
+    pub category: Option<FileCategory>,
+}
+
+impl TypesViewState {
+    /// Rows listed for `breakdown` in the current mode.
+    pub fn rows<'a>(&self, breakdown: &'a TypeBreakdown) -> Vec<TypeRow<'a>> {
+        match self.category {
+            None => breakdown.categories.iter().map(TypeRow::Category).collect(),
+            Some(category) => breakdown
+                .extensions_in(category)
+                .map(TypeRow::Extension)
+                .collect(),
+        }
+    }
+
+    /// Drill into `category`, listing its extensions.
+    pub fn enter(&mut self, category: FileCategory) {
+        self.category = Some(category);
+        self.selected = 0;
+    }
+
+    /// Return to the category list with the drilled category selected.
+    /// Returns false when already at the category list.
+    pub fn leave(&mut self, breakdown: &TypeBreakdown) -> bool {
+        let Some(category) = self.category.take() else {
+            return false;
+        };
+        self.selected = breakdown
+            .categories
+            .iter()
+            .position(|c| c.category == category)
+            .unwrap_or(0);
+        true
+    }
+}
+
+/// State for the Largest view.
+#[derive(Debug, Clone)]
+pub struct LargestViewState {
+    /// Index of the selected entry.
+    pub selected: usize,
+    /// Whether files, leaf directories or both are ranked.
+    pub kind: TopKind,
+    /// Filter words given to `:largest`, shown in the title.
+    pub filters: Vec<String>,
+    /// Ranked entries, largest first.
+    pub entries: Vec<TopEntry>,
+    /// Directory the entries were ranked under.
+    pub root: PathBuf,
+    finder: TopFinder,
+}
+
+impl Default for LargestViewState {
+    fn default() -> Self {
+        Self {
+            selected: 0,
+            kind: TopKind::Files,
+            filters: Vec::new(),
+            entries: Vec::new(),
+            root: PathBuf::new(),
+            finder: TopFinder::new(TOP_COUNT),
+        }
+    }
+}
+
+impl LargestViewState {
+    /// Replace the settings with the words of `:largest`. `files`, `dirs`
+    /// and `all` choose what is ranked, `ext:iso,img`, `older:6mo` and
+    /// `newer:30d` filter, and any other word is a path glob.
+    pub fn configure(&mut self, words: &[String]) -> Result<(), String> {
+        let mut kind = TopKind::Files;
+        let mut extensions = Vec::new();
+        let mut globs = Vec::new();
+        let mut finder = TopFinder::new(TOP_COUNT);
+        for word in words {
+            match word.as_str() {
+                "files" => kind = TopKind::Files,
+                "dirs" => kind = TopKind::Dirs,
+                "all" => kind = TopKind::Both,
+                _ => {
+                    if let Some(list) = word.strip_prefix("ext:") {
+                        extensions.extend(list.split(',').filter(|e| !e.is_empty()));
+                    } else if let Some(age) = word.strip_prefix("older:") {
+                        finder = finder.older_than(parse_duration(age)?);
+                    } else if let Some(age) = word.strip_prefix("newer:") {
+                        finder = finder.newer_than(parse_duration(age)?);
+                    } else {
+                        globs.push(word.clone());
+                    }
+                }
+            }
+        }
+        self.finder = finder.kind(kind).extensions(extensions).globs(&globs)?;
+        self.kind = kind;
+        self.filters = words.to_vec();
+        self.selected = 0;
+        Ok(())
+    }
+
+    /// Cycle between files, leaf directories and both.
+    pub fn cycle_kind(&mut self) {
+        self.kind = match self.kind {
+            TopKind::Files => TopKind::Dirs,
+            TopKind::Dirs => TopKind::Both,
+            TopKind::Both => TopKind::Files,
+        };
+        self.finder = self.finder.clone().kind(self.kind);
+        self.selected = 0;
+    }
+
+    /// Rank the entries below `node`, which lives at `path`.
+    pub fn reload(&mut self, node: &FileNode, path: &Path) {
+        self.entries = self
+            .finder
+            .clone()
+            .with_now(SystemTime::now())
+            .find_in(node, path);
+        self.root = path.to_path_buf();
+        self.selected = self.selected.min(self.entries.len().saturating_sub(1));
+    }
+
+    /// The selected entry.
+    pub fn selected_entry(&self) -> Option<&TopEntry> {
+        self.entries.get(self.selected)
+    }
+}
+
+/// State for the Timeline view.
+#[derive(Debug, Clone, Default)]
+pub struct TimelineViewState {
+    /// Index of the selected bin.
+    pub selected: usize,
+    /// Whether files are binned by week or month.
+    pub period: TimelinePeriod,
+    /// Files below `root` binned by mtime.
+    pub timeline: Timeline,
+    /// Directory the timeline was built for.
+    pub root: PathBuf,
+}
+
+impl TimelineViewState {
+    /// Switch between weekly and monthly bins.
+    pub fn toggle_period(&mut self) {
+        self.period = self.period.toggle();
+    }
+
+    /// Bin the files below `node`, which lives at `path`. The newest bin is
+    /// selected unless the same timeline is being refreshed.
+    pub fn reload(&mut self, node: &FileNode, path: &Path) {
+        let refresh = self.root == path && self.timeline.period == self.period;
+        self.timeline = Timeline::new(node, self.period);
+        self.root = path.to_path_buf();
+        let last = self.timeline.bins.len().saturating_sub(1);
+        self.selected = if refresh {
+            self.selected.min(last)
+        } else {
+            last
+        };
+    }
+
+    /// The selected bin.
+    pub fn selected_bin(&self) -> Option<&TimelineBin> {
+        self.timeline.bins.get(self.selected)
+    }
+}
+
+/// State for the Hygiene view.
+#[derive(Debug, Clone, Default)]
+pub struct HygieneViewState {
+    /// Index of the selected entry among the shown ones.
+    pub selected: usize,
+    /// Kind of issue shown, or every kind.
+    pub kind: Option<HygieneKind>,
+    /// Findings below `root` that the disk confirms; `None` before a full scan.
+    pub report: Option<HygieneReport>,
+    /// Directory the report covers.
+    pub root: PathBuf,
+}
+
+impl HygieneViewState {
+    /// Cycle through each kind of issue, then all of them.
+    pub fn cycle_kind(&mut self) {
+        self.kind = match self.kind {
+            None => Some(HygieneKind::ALL[0]),
+            Some(kind) => HygieneKind::ALL
+                .iter()
+                .position(|&k| k == kind)
+                .and_then(|i| HygieneKind::ALL.get(i + 1))
+                .copied(),
+        };
+        self.selected = 0;
+    }
+
+    /// Entries of the shown kind, in report order.
+    pub fn entries(&self) -> Vec<&HygieneEntry> {
+        self.report
+            .iter()
+            .flat_map(|r| &r.entries)
+            .filter(|e| self.kind.is_none_or(|kind| e.kind() == kind))
+            .collect()
+    }
+
+    /// The selected entry.
+    pub fn selected_entry(&self) -> Option<&HygieneEntry> {
+        self.entries().get(self.selected).copied()
+    }
+
+    /// Analyze the subtree `node`, which lives at `path`, keeping only
+    /// findings that still hold on disk. `apparent_size` says whether the
+    /// scan recorded file lengths rather than disk usage.
+    pub fn reload(&mut self, node: &FileNode, path: &Path, apparent_size: bool) {
+        let mut report = HygieneAnalyzer::new()
+            .apparent_size(apparent_size)
+            .analyze_in(node, path);
+        report.verify();
+        self.report = Some(report);
+        self.root = path.to_path_buf();
+        self.selected = self.selected.min(self.entries().len().saturating_sub(1));
+    }
+}
+
 /// A single tab representing an independent directory exploration context.
 #[derive(Debug, Clone)]
 pub struct Tab {
@@ -818,6 +1531,87 @@ impl BookmarkListState {
     }
 }
 
+/// An undo or redo that is still running.
+///
+/// Kept until the operation completes so the journal is only updated once
+/// the filesystem actually changed.
+#[derive(Debug, Clone)]
+pub enum PendingReplay {
+    /// An entry taken from the undo stack.
+    Undo(UndoEntry),
+    /// An entry taken from the redo stack.
+    Redo(UndoEntry),
+}
+
+/// A row in the undo history modal.
+#[derive(Debug, Clone)]
+pub struct HistoryRow {
+    /// Undo log entry ID.
+    pub id: u64,
+    /// Whether this is an undone operation waiting on the redo stack.
+    pub redo: bool,
+    /// When the operation was performed.
+    pub timestamp: SystemTime,
+    /// What the operation did.
+    pub description: String,
+    /// Whether the entry can be undone now, or why not.
+    pub status: Result<(), String>,
+}
+
+/// State for the undo history modal.
+///
+/// Rows are snapshotted (and verified against the filesystem) when the modal
+/// opens so rendering never touches the disk.
+#[derive(Debug, Clone, Default)]
+pub struct HistoryState {
+    /// Currently selected row index.
+    pub selected: usize,
+    /// Redo entries (furthest first) followed by undo entries, newest first.
+    pub rows: Vec<HistoryRow>,
+}
+
+impl HistoryState {
+    /// Build the history from the undo log's undo and redo stacks (both
+    /// oldest first).
+    ///
+    /// The selection starts on the most recent undo entry, with undone
+    /// operations listed above it like a timeline.
+    pub fn new<'a>(
+        undo: impl DoubleEndedIterator<Item = &'a UndoEntry>,
+        redo: impl Iterator<Item = &'a UndoEntry>,
+    ) -> Self {
+        let row = |entry: &UndoEntry, redo: bool| HistoryRow {
+            id: entry.id,
+            redo,
+            timestamp: entry.timestamp,
+            description: entry.description.clone(),
+            status: entry.verify(),
+        };
+        let mut rows: Vec<HistoryRow> = redo.map(|e| row(e, true)).collect();
+        let selected = rows.len();
+        rows.extend(undo.rev().map(|e| row(e, false)));
+        let selected = selected.min(rows.len().saturating_sub(1));
+        Self { selected, rows }
+    }
+
+    /// Move selection up.
+    pub fn move_up(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    /// Move selection down.
+    pub fn move_down(&mut self) {
+        if self.selected + 1 < self.rows.len() {
+            self.selected += 1;
+        }
+    }
+
+    /// Get the selected row.
+    pub fn selected_row(&self) -> Option<&HistoryRow> {
+        self.rows.get(self.selected)
+    }
+}
+
 /// A single rename operation in bulk rename.
 #[derive(Debug, Clone)]
 pub struct RenameEntry {
@@ -941,6 +1735,13 @@ pub struct UserSettings {
     /// Directory bookmarks.
     #[serde(default = "Bookmarks::with_defaults")]
     pub bookmarks: Bookmarks,
+    /// Preserve permissions, ownership, timestamps, xattrs and sparse files
+    /// when copying or moving across filesystems.
+    pub preserve_metadata: bool,
+    /// Verify copied files against their sources with BLAKE3.
+    pub verify_copies: bool,
+    /// How many background jobs (pastes) run at once.
+    pub job_concurrency: usize,
 }
 
 impl Default for UserSettings {
@@ -952,10 +1753,26 @@ impl Default for UserSettings {
             openers: FileOpeners::default(),
             editor: opensesame::EditorConfig::default(),
             bookmarks: Bookmarks::with_defaults(),
+            preserve_metadata: true,
+            verify_copies: false,
+            job_concurrency: 2,
         }
     }
 }
 
+impl UserSettings {
+    /// Copy options for pastes, derived from the file operation settings.
+    pub fn copy_options(&self) -> CopyOptions {
+        let mut options = if self.preserve_metadata {
+            CopyOptions::archive()
+        } else {
+            CopyOptions::default()
+        };
+        options.verify = self.verify_copies;
+        options
+    }
+}
+
 /// Configuration for file openers by extension.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
@@ -1023,21 +1840,54 @@ pub struct SettingsState {
     pub settings: UserSettings,
     /// Whether settings have been modified.
     pub dirty: bool,
+    /// Temporary copy of the scan profile being edited.
+    pub profile: Profile,
+    /// Whether the profile has been modified.
+    pub profile_dirty: bool,
 }
 
 impl SettingsState {
-    /// Create a new settings state with current settings.
-    pub fn new(settings: UserSettings) -> Self {
+    /// Index of the first scan-profile item.
+    pub const PROFILE_START: usize = 6;
+
+    /// Create a new settings state with current settings and scan profile.
+    pub fn new(settings: UserSettings, profile: Profile) -> Self {
         Self {
             selected: 0,
             settings,
             dirty: false,
+            profile,
+            profile_dirty: false,
         }
     }
 
     /// Number of settings items.
     pub fn item_count(&self) -> usize {
-        3 // scan_on_startup, show_hidden, default_layout
+        // scan_on_startup, show_hidden, default_layout, preserve_metadata, verify_copies,
+        // job_concurrency, then the profile's follow_symlinks, cross_filesystems,
+        // apparent_size, ignore_files, max_depth, threads, duplicate min_size, stale_threshold,
+        // age time basis
+        15
+    }
+
+    /// Display value of a scan-profile choice item.
+    pub fn profile_value(&self, index: usize) -> String {
+        let profile = &self.profile;
+        match index {
+            9 => profile.scan.ignore_files.to_string(),
+            10 => profile
+                .scan
+                .max_depth
+                .map_or_else(|| "unlimited".to_string(), |d| d.to_string()),
+            11 => match profile.scan.threads {
+                0 => "auto".to_string(),
+                n => n.to_string(),
+            },
+            12 => crate::ui::format_size(profile.duplicates.min_size),
+            13 => format_duration_spec(profile.age.stale_threshold),
+            14 => profile.age.time_basis.to_string(),
+            _ => String::new(),
+        }
     }
 
     /// Move selection up.
@@ -1074,15 +1924,170 @@ impl SettingsState {
                 };
                 self.dirty = true;
             }
+            3 => {
+                self.settings.preserve_metadata = !self.settings.preserve_metadata;
+                self.dirty = true;
+            }
+            4 => {
+                self.settings.verify_copies = !self.settings.verify_copies;
+                self.dirty = true;
+            }
+            5 => {
+                // Cycle 1, 2, 4, 8
+                self.settings.job_concurrency = match self.settings.job_concurrency {
+                    0 | 1 => 2,
+                    2 | 3 => 4,
+                    4..=7 => 8,
+                    _ => 1,
+                };
+                self.dirty = true;
+            }
+            index if index >= Self::PROFILE_START => self.toggle_profile(index),
             _ => {}
         }
     }
+
+    /// Toggle or cycle a scan-profile item.
+    fn toggle_profile(&mut self, index: usize) {
+        const DAY: u64 = 24 * 60 * 60;
+        let profile = &mut self.profile;
+        match index {
+            6 => profile.scan.follow_symlinks = !profile.scan.follow_symlinks,
+            7 => profile.scan.cross_filesystems = !profile.scan.cross_filesystems,
+            8 => profile.scan.apparent_size = !profile.scan.apparent_size,
+            9 => {
+                profile.scan.ignore_files = match profile.scan.ignore_files {
+                    IgnoreMode::Off => IgnoreMode::Exclude,
+                    IgnoreMode::Exclude => IgnoreMode::Report,
+                    IgnoreMode::Report => IgnoreMode::Off,
+                };
+            }
+            10 => {
+                // Cycle unlimited, 2, 4, 8, 16
+                profile.scan.max_depth = match profile.scan.max_depth {
+                    None => Some(2),
+                    Some(0..=3) => Some(4),
+                    Some(4..=7) => Some(8),
+                    Some(8..=15) => Some(16),
+                    Some(_) => None,
+                };
+            }
+            11 => {
+                // Cycle auto, 1, 2, 4, 8
+                profile.scan.threads = match profile.scan.threads {
+                    0 => 1,
+                    1 => 2,
+                    2 | 3 => 4,
+                    4..=7 => 8,
+                    _ => 0,
+                };
+            }
+            12 => {
+                // Cycle 1 KiB, 64 KiB, 1 MiB, 16 MiB
+                profile.duplicates.min_size = match profile.duplicates.min_size {
+                    0..=1023 => 1024,
+                    1024..=65535 => 64 * 1024,
+                    65536..=1048575 => 1024 * 1024,
+                    1048576..=16777215 => 16 * 1024 * 1024,
+                    _ => 1024,
+                };
+            }
+            13 => {
+                // Cycle 30 days, 90 days, 180 days, 1 year, 2 years
+                let days = profile.age.stale_threshold.as_secs() / DAY;
+                let next = match days {
+                    0..=29 => 30,
+                    30..=89 => 90,
+                    90..=179 => 180,
+                    180..=364 => 365,
+                    365..=729 => 730,
+                    _ => 30,
+                };
+                profile.age.stale_threshold = Duration::from_secs(next * DAY);
+            }
+            14 => profile.age.time_basis = profile.age.time_basis.next(),
+            _ => return,
+        }
+        self.profile_dirty = true;
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_largest_view_configure() {
+        let mut state = LargestViewState::default();
+        let words: Vec<String> = ["dirs", "ext:iso,img", "older:6mo", "backup/**"]
+            .iter()
+            .map(|w| w.to_string())
+            .collect();
+        assert!(state.configure(&words).is_ok());
+        assert_eq!(state.kind, TopKind::Dirs);
+        assert_eq!(state.filters.len(), 4);
+
+        state.cycle_kind();
+        assert_eq!(state.kind, TopKind::Both);
+
+        assert!(state.configure(&["older:soon".to_string()]).is_err());
+        assert!(state.configure(&["[".to_string()]).is_err());
+        // A failed configure keeps the previous settings.
+        assert_eq!(state.kind, TopKind::Both);
+    }
+
+    #[test]
+    fn test_timeline_view_reload() {
+        use gravityfile_core::{NodeId, Timestamps};
+
+        const WEEK: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+        let now = SystemTime::now();
+        let mut root =
+            FileNode::new_directory(NodeId::new(0), "root", Timestamps::with_modified(now));
+        root.children = vec![
+            FileNode::new_file(
+                NodeId::new(1),
+                "new",
+                1,
+                1,
+                Timestamps::with_modified(now),
+                false,
+            ),
+            FileNode::new_file(
+                NodeId::new(2),
+                "old",
+                1,
+                1,
+                Timestamps::with_modified(now - WEEK * 3),
+                false,
+            ),
+        ];
+
+        let mut state = TimelineViewState::default();
+        state.toggle_period();
+        assert_eq!(state.period, TimelinePeriod::Week);
+        state.reload(&root, Path::new("/root"));
+        assert_eq!(state.timeline.bins.len(), 4);
+        // The newest bin is selected first; a refresh keeps the selection.
+        assert_eq!(state.selected, 3);
+        state.selected = 1;
+        state.reload(&root, Path::new("/root"));
+        assert_eq!(state.selected, 1);
+        assert_eq!(state.selected_bin().unwrap().file_count, 0);
+    }
+
+    #[test]
+    fn test_hygiene_view_cycle_kind() {
+        let mut state = HygieneViewState::default();
+        for kind in HygieneKind::ALL {
+            state.cycle_kind();
+            assert_eq!(state.kind, Some(kind));
+        }
+        state.cycle_kind();
+        assert_eq!(state.kind, None);
+        assert!(state.entries().is_empty());
+    }
+
     #[test]
     fn test_visual_state_creation() {
         let state = VisualState::new(5);
@@ -1204,4 +2209,21 @@ mod tests {
         state.jump_to_bottom(10);
         assert_eq!(state.cursor, 9);
     }
+
+    #[test]
+    fn test_plan_review_threshold() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let tmp = dir.path();
+        std::fs::create_dir_all(tmp.join("src")).unwrap();
+        std::fs::create_dir_all(tmp.join("dst")).unwrap();
+        std::fs::write(tmp.join("src/a.txt"), b"small").unwrap();
+
+        let op = gravityfile_ops::FileOperation::copy(vec![tmp.join("src/a.txt")], tmp.join("dst"));
+        let plan = gravityfile_ops::plan_operation(&op, None);
+        assert!(!PlanReviewState::requires_confirmation(&plan));
+
+        std::fs::write(tmp.join("dst/a.txt"), b"old").unwrap();
+        let plan = gravityfile_ops::plan_operation(&op, None);
+        assert!(PlanReviewState::requires_confirmation(&plan));
+    }
 }
@@ -44,6 +44,8 @@ pub enum KeyAction {
     Cut,
     /// Paste from clipboard to current directory.
     Paste,
+    /// Paste clipboard items as symlinks.
+    PasteSymlink,
 
     // File operations
     /// Delete marked/current items (with confirmation).
@@ -58,6 +60,8 @@ pub enum KeyAction {
     Take,
     /// Undo last operation.
     Undo,
+    /// Redo the last undone operation.
+    Redo,
 
     // UI toggles
     ToggleDetails,
@@ -209,6 +213,7 @@ impl KeyAction {
             (KeyCode::Char('y'), KeyModifiers::NONE) => KeyAction::Yank,
             (KeyCode::Char('x'), KeyModifiers::NONE) => KeyAction::Cut,
             (KeyCode::Char('p'), KeyModifiers::NONE) => KeyAction::Paste,
+            (KeyCode::Char('L'), KeyModifiers::SHIFT) => KeyAction::PasteSymlink,
 
             // File operations
             (KeyCode::Char('d'), KeyModifiers::NONE) => KeyAction::Delete,
@@ -221,6 +226,7 @@ impl KeyAction {
 
             // Undo
             (KeyCode::Char('z'), KeyModifiers::CONTROL) => KeyAction::Undo,
+            (KeyCode::Char('r'), KeyModifiers::CONTROL) => KeyAction::Redo,
 
             // UI toggles
             (KeyCode::Char('i'), KeyModifiers::NONE) => KeyAction::ToggleDetails,
@@ -356,6 +362,10 @@ pub fn get_help_sections() -> Vec<HelpSection> {
                     keys: "p",
                     description: "Paste from clipboard",
                 },
+                KeyBinding {
+                    keys: "L",
+                    description: "Paste as symlink",
+                },
                 KeyBinding {
                     keys: "Esc",
                     description: "Clear clipboard/marks",
@@ -389,6 +399,10 @@ pub fn get_help_sections() -> Vec<HelpSection> {
                     keys: "Ctrl-z",
                     description: "Undo",
                 },
+                KeyBinding {
+                    keys: "Ctrl-r",
+                    description: "Redo",
+                },
             ],
         },
         HelpSection {
@@ -453,6 +467,162 @@ pub fn get_help_sections() -> Vec<HelpSection> {
                 },
             ],
         },
+        HelpSection {
+            title: "Trash View",
+            bindings: vec![
+                KeyBinding {
+                    keys: "r/Enter",
+                    description: "Restore to original path",
+                },
+                KeyBinding {
+                    keys: "d",
+                    description: "Delete permanently",
+                },
+                KeyBinding {
+                    keys: "R",
+                    description: "Reload trash listing",
+                },
+            ],
+        },
+        HelpSection {
+            title: "Jobs View",
+            bindings: vec![
+                KeyBinding {
+                    keys: "p",
+                    description: "Pause/resume selected job",
+                },
+                KeyBinding {
+                    keys: "d/x",
+                    description: "Cancel selected job",
+                },
+                KeyBinding {
+                    keys: "Esc/u",
+                    description: "Clear finished jobs",
+                },
+            ],
+        },
+        HelpSection {
+            title: "Mounts View",
+            bindings: vec![
+                KeyBinding {
+                    keys: "Enter",
+                    description: "Browse and scan the mount",
+                },
+                KeyBinding {
+                    keys: "R",
+                    description: "Reload mounts and free space",
+                },
+            ],
+        },
+        HelpSection {
+            title: "Age View",
+            bindings: vec![KeyBinding {
+                keys: "v",
+                description: "Cycle mtime, atime, btime or latest",
+            }],
+        },
+        HelpSection {
+            title: "Owners View",
+            bindings: vec![
+                KeyBinding {
+                    keys: "Enter",
+                    description: "Go to the owner's largest directory",
+                },
+                KeyBinding {
+                    keys: "v",
+                    description: "Switch between users and groups",
+                },
+            ],
+        },
+        HelpSection {
+            title: "Types View",
+            bindings: vec![
+                KeyBinding {
+                    keys: "Enter",
+                    description: "Show extensions of the category",
+                },
+                KeyBinding {
+                    keys: "Esc",
+                    description: "Back to categories",
+                },
+                KeyBinding {
+                    keys: "Bksp",
+                    description: "Go to the parent directory",
+                },
+            ],
+        },
+        HelpSection {
+            title: "Cleanup View",
+            bindings: vec![
+                KeyBinding {
+                    keys: "Space",
+                    description: "Mark candidate",
+                },
+                KeyBinding {
+                    keys: "a",
+                    description: "Mark all candidates",
+                },
+                KeyBinding {
+                    keys: "d",
+                    description: "Delete marked candidates",
+                },
+                KeyBinding {
+                    keys: "Enter",
+                    description: "Show candidate in the explorer",
+                },
+            ],
+        },
+        HelpSection {
+            title: "Largest View",
+            bindings: vec![
+                KeyBinding {
+                    keys: "v",
+                    description: "Cycle files, leaf dirs or both",
+                },
+                KeyBinding {
+                    keys: "Space",
+                    description: "Mark entry",
+                },
+                KeyBinding {
+                    keys: "Enter",
+                    description: "Show entry in the explorer",
+                },
+            ],
+        },
+        HelpSection {
+            title: "Timeline View",
+            bindings: vec![
+                KeyBinding {
+                    keys: "v",
+                    description: "Switch weekly or monthly bars",
+                },
+                KeyBinding {
+                    keys: "Enter",
+                    description: "Filter explorer to the bar's files",
+                },
+            ],
+        },
+        HelpSection {
+            title: "Hygiene View",
+            bindings: vec![
+                KeyBinding {
+                    keys: "v",
+                    description: "Cycle empty, zero-byte, broken, hard links",
+                },
+                KeyBinding {
+                    keys: "Space",
+                    description: "Mark entry",
+                },
+                KeyBinding {
+                    keys: "a",
+                    description: "Mark all deletable entries shown",
+                },
+                KeyBinding {
+                    keys: "Enter",
+                    description: "Show entry in the explorer",
+                },
+            ],
+        },
         HelpSection {
             title: "Bookmarks",
             bindings: vec![
@@ -547,10 +717,50 @@ pub fn get_command_help() -> Vec<(&'static str, &'static str)> {
         (":yank :y", "Copy to clipboard"),
         (":cut :x", "Cut to clipboard"),
         (":paste :p", "Paste from clipboard"),
+        (":symlink [-r]", "Paste as (relative) symlink"),
+        (":hardlink", "Paste as hardlink"),
         (":delete :rm", "Delete marked items"),
         (":rename <name>", "Rename current item"),
         (":rename-bulk :brn", "Bulk rename marked items"),
+        (
+            ":rnp [regex [repl]]",
+            "Rename by regex/template with preview",
+        ),
+        (":trash", "Browse the system trash"),
+        (":jobs", "Show background copy/move jobs"),
+        (":mounts", "Show filesystems and free space"),
+        (
+            ":age [mtime|atime|btime|latest]",
+            "Show file ages by the given time",
+        ),
+        (":owners", "Show usage per user and group"),
+        (":types", "Show usage per file type and extension"),
+        (":cleanup", "Show regenerable build artifacts and caches"),
+        (
+            ":largest [dirs|all] [ext:iso,img] [older:6mo] [GLOB]",
+            "Show the largest files or leaf dirs below here",
+        ),
+        (
+            ":timeline [week|month]",
+            "Show files below here by modification time",
+        ),
+        (
+            ":hygiene [empty|zero|broken|hardlinks]",
+            "Show empty dirs, zero-byte files and broken links",
+        ),
+        (":undo :u", "Undo last operation"),
+        (":redo", "Redo last undone operation"),
+        (":history", "Browse, undo and redo past operations"),
+        (":sync [two-way]", "Sync current dir into the next tab"),
+        (":shred [passes]", "Overwrite and delete marked items"),
+        (":chmod [-R] [mode]", "Show and change permissions"),
+        (":chown [-R] user[:group]", "Change owner (and group)"),
+        (":chgrp [-R] group", "Change group"),
         (":clear", "Clear all marks"),
+        (
+            ":filter [query]",
+            "Show only matching entries (no query clears)",
+        ),
         (":theme dark|light", "Set theme"),
         (":layout tree|miller", "Set layout"),
         (":sort <mode>", "Set sort (size/name/date/count)"),
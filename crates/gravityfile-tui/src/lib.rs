@@ -59,6 +59,10 @@ pub struct TuiConfig {
     pub cwd_file: Option<std::path::PathBuf>,
     /// Whether to print the last working directory to stdout on exit.
     pub print_cwd: bool,
+    /// Named `[profiles.<name>]` variant or profile file applied to scans.
+    pub profile: Option<String>,
+    /// Further scan roots combined with the launch path into one tree.
+    pub extra_roots: Vec<std::path::PathBuf>,
 }
 
 impl TuiConfig {
@@ -84,6 +88,18 @@ impl TuiConfig {
         self.print_cwd = print;
         self
     }
+
+    /// Select a scan profile by name or file path.
+    pub fn with_profile(mut self, profile: Option<String>) -> Self {
+        self.profile = profile;
+        self
+    }
+
+    /// Scan these roots together with the launch path as one tree.
+    pub fn with_extra_roots(mut self, roots: Vec<std::path::PathBuf>) -> Self {
+        self.extra_roots = roots;
+        self
+    }
 }
 
 /// Data collected at exit for shell integration.
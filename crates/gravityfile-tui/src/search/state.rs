@@ -231,7 +231,7 @@ impl SearchState {
         }
 
         // Sort by score descending
-        self.results.sort_by(|a, b| b.score.cmp(&a.score));
+        self.results.sort_by_key(|r| std::cmp::Reverse(r.score));
 
         // Limit results to prevent UI slowdown
         self.results.truncate(1000);
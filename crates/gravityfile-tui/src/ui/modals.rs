@@ -9,12 +9,19 @@ use ratatui::style::{Modifier, Style};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Clear, Paragraph, Widget};
 
-use gravityfile_ops::{Conflict, ConflictKind, OperationProgress, OperationType};
+use gravityfile_ops::{
+    Conflict, ConflictKind, OperationProgress, OperationType, PlanAction, RenameStatus, SyncChange,
+    SyncDirection, SyncMode, TrashEntry, format_mode,
+};
 
 use crate::app::input::InputState;
-use crate::app::state::{BookmarkListState, Bookmarks, DeletionProgress, SettingsState};
+use crate::app::state::{
+    BookmarkListState, Bookmarks, DeletionProgress, HistoryState, PermissionsState,
+    PlanReviewState, RenameField, RenamePatternState, SHRED_CONFIRM_WORD, SettingsState,
+    ShredConfirmState, SyncReviewState,
+};
 use crate::theme::Theme;
-use crate::ui::format_size;
+use crate::ui::{format_relative_time, format_size};
 
 /// Confirmation dialog for deletion.
 pub struct DeleteConfirmModal<'a> {
@@ -140,230 +147,124 @@ impl Widget for DeleteConfirmModal<'_> {
     }
 }
 
-/// Progress dialog for deletion operation.
-pub struct DeletionProgressModal<'a> {
+/// Confirmation dialog for permanently removing items from the trash.
+pub struct PurgeConfirmModal<'a> {
     theme: &'a Theme,
-    progress: Option<&'a DeletionProgress>,
+    entries: &'a [TrashEntry],
 }
 
-impl<'a> DeletionProgressModal<'a> {
-    /// Create a new deletion progress modal.
-    pub fn new(theme: &'a Theme, progress: Option<&'a DeletionProgress>) -> Self {
-        Self { theme, progress }
+impl<'a> PurgeConfirmModal<'a> {
+    /// Create a new purge confirmation modal.
+    pub fn new(theme: &'a Theme, entries: &'a [TrashEntry]) -> Self {
+        Self { theme, entries }
     }
 }
 
-impl Widget for DeletionProgressModal<'_> {
+impl Widget for PurgeConfirmModal<'_> {
     fn render(self, area: Rect, buf: &mut Buffer) {
-        // Calculate popup area
-        let popup_width = 50.min(area.width.saturating_sub(4));
-        let popup_height = 10.min(area.height.saturating_sub(4));
+        let popup_width = 60.min(area.width.saturating_sub(4));
+        let popup_height = (self.entries.len() as u16 + 9).min(area.height.saturating_sub(4));
 
         let popup_x = (area.width.saturating_sub(popup_width)) / 2 + area.x;
         let popup_y = (area.height.saturating_sub(popup_height)) / 2 + area.y;
 
         let popup_area = Rect::new(popup_x, popup_y, popup_width, popup_height);
 
-        // Clear the popup area
         Clear.render(popup_area, buf);
 
-        // Draw border
         let block = Block::default()
-            .title(" Deleting... ")
+            .title(" Empty From Trash ")
             .title_style(
                 Style::default()
-                    .fg(self.theme.warning)
+                    .fg(self.theme.error)
                     .add_modifier(Modifier::BOLD),
             )
             .borders(Borders::ALL)
-            .border_style(Style::default().fg(self.theme.warning));
+            .border_style(Style::default().fg(self.theme.error));
 
         let inner = block.inner(popup_area);
         block.render(popup_area, buf);
 
-        let mut lines = vec![];
-
-        if let Some(progress) = self.progress {
-            // Progress bar
-            let pct = progress.percentage();
-            let bar_width = (inner.width as usize).saturating_sub(10);
-            let filled = (pct as usize * bar_width) / 100;
-            let empty = bar_width.saturating_sub(filled);
+        let total_size: u64 = self.entries.iter().map(|e| e.size).sum();
 
-            lines.push(Line::raw(""));
-            lines.push(Line::from(vec![
-                Span::raw("  ["),
-                Span::styled(
-                    "\u{2588}".repeat(filled),
-                    Style::default().fg(self.theme.info),
-                ),
-                Span::styled(
-                    "\u{2591}".repeat(empty),
-                    Style::default().fg(self.theme.muted),
+        let mut lines = vec![
+            Line::styled(
+                format!(
+                    "Permanently delete {} items ({})?",
+                    self.entries.len(),
+                    format_size(total_size)
                 ),
-                Span::raw(format!("] {}%", pct)),
-            ]));
-
-            lines.push(Line::raw(""));
-            lines.push(Line::from(vec![
-                Span::styled("  Progress: ", self.theme.help_desc),
-                Span::raw(format!(
-                    "{}/{} items",
-                    progress.deleted + progress.failed,
-                    progress.total
-                )),
-            ]));
-
-            lines.push(Line::from(vec![
-                Span::styled("  Freed:    ", self.theme.help_desc),
-                Span::raw(format_size(progress.bytes_freed)),
-            ]));
-
-            if progress.failed > 0 {
-                lines.push(Line::from(vec![
-                    Span::styled("  Failed:   ", Style::default().fg(self.theme.error)),
-                    Span::styled(
-                        progress.failed.to_string(),
-                        Style::default().fg(self.theme.error),
-                    ),
-                ]));
-            }
-
-            // Current item being deleted
-            if let Some(current) = &progress.current {
-                let name = current
-                    .file_name()
-                    .map(|n| n.to_string_lossy().to_string())
-                    .unwrap_or_else(|| current.display().to_string());
-                let max_len = (inner.width as usize).saturating_sub(4);
-                let display_name = if name.len() > max_len {
-                    format!("...{}", &name[name.len().saturating_sub(max_len - 3)..])
-                } else {
-                    name
-                };
-                lines.push(Line::raw(""));
-                lines.push(Line::styled(
-                    format!("  {}", display_name),
-                    Style::default().fg(self.theme.muted),
-                ));
-            }
-        } else {
-            lines.push(Line::raw("  Preparing deletion..."));
-        }
-
-        Paragraph::new(lines).render(inner, buf);
-    }
-}
-
-/// Command palette input widget.
-pub struct CommandPalette<'a> {
-    theme: &'a Theme,
-    input: &'a str,
-    cursor: usize,
-}
+                Style::default()
+                    .fg(self.theme.error)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Line::styled(
+                "This cannot be undone.",
+                Style::default().fg(self.theme.warning),
+            ),
+            Line::raw(""),
+        ];
 
-impl<'a> CommandPalette<'a> {
-    /// Create a new command palette widget.
-    pub fn new(theme: &'a Theme, input: &'a str, cursor: usize) -> Self {
-        Self {
-            theme,
-            input,
-            cursor,
+        let max_items = (inner.height as usize).saturating_sub(6);
+        let max_path_len = (inner.width as usize).saturating_sub(4);
+        for entry in self.entries.iter().take(max_items) {
+            let full_path = entry.original_path.display().to_string();
+            let display_path = if full_path.len() > max_path_len {
+                format!(
+                    "...{}",
+                    &full_path[full_path.len().saturating_sub(max_path_len - 3)..]
+                )
+            } else {
+                full_path
+            };
+            let style = if entry.is_dir {
+                self.theme.directory
+            } else {
+                Style::default()
+            };
+            lines.push(Line::styled(format!("  {}", display_path), style));
         }
-    }
-}
-
-impl Widget for CommandPalette<'_> {
-    fn render(self, area: Rect, buf: &mut Buffer) {
-        // Clear the area first
-        Clear.render(area, buf);
-
-        // Build the command line display
-        let prompt = ":";
-
-        // Build the line with cursor indication
-        let mut spans = vec![Span::styled(
-            prompt,
-            Style::default()
-                .fg(self.theme.info)
-                .add_modifier(Modifier::BOLD),
-        )];
 
-        if self.input.is_empty() {
-            // Show placeholder
-            spans.push(Span::styled(
-                "type command (q, cd, help...)",
+        if self.entries.len() > max_items {
+            lines.push(Line::styled(
+                format!("  ... and {} more", self.entries.len() - max_items),
                 Style::default().fg(self.theme.muted),
             ));
-        } else {
-            // Show input with cursor
-            let cursor_pos = self.cursor.min(self.input.len());
-            let (before, after) = self.input.split_at(cursor_pos);
-            spans.push(Span::raw(before));
-
-            if !after.is_empty() {
-                // Show cursor under next char
-                let (cursor_char, rest) = after.split_at(1);
-                spans.push(Span::styled(
-                    cursor_char,
-                    Style::default().add_modifier(Modifier::REVERSED),
-                ));
-                spans.push(Span::raw(rest));
-            } else {
-                // Cursor at end - show block cursor
-                spans.push(Span::styled(
-                    " ",
-                    Style::default().add_modifier(Modifier::REVERSED),
-                ));
-            }
         }
 
-        let line = Line::from(spans);
-        Paragraph::new(line)
-            .style(self.theme.footer)
-            .render(area, buf);
+        lines.push(Line::raw(""));
+        lines.push(Line::from(vec![
+            Span::styled(" y/Enter ", self.theme.help_key),
+            Span::raw("Purge  "),
+            Span::styled(" n/Esc ", self.theme.help_key),
+            Span::raw("Cancel"),
+        ]));
+
+        Paragraph::new(lines).render(inner, buf);
     }
 }
 
-/// Progress dialog for copy/move operations.
-pub struct OperationProgressModal<'a> {
+/// Confirmation dialog for secure shredding. The user has to type
+/// [`SHRED_CONFIRM_WORD`] before anything is overwritten.
+pub struct ShredConfirmModal<'a> {
     theme: &'a Theme,
-    progress: &'a OperationProgress,
+    state: &'a ShredConfirmState,
 }
 
-impl<'a> OperationProgressModal<'a> {
-    /// Create a new operation progress modal.
-    pub fn new(theme: &'a Theme, progress: &'a OperationProgress) -> Self {
-        Self { theme, progress }
-    }
-
-    fn operation_title(&self) -> &'static str {
-        match self.progress.operation_type {
-            OperationType::Copy => " Copying... ",
-            OperationType::Move => " Moving... ",
-            OperationType::Delete => " Deleting... ",
-            OperationType::Rename => " Renaming... ",
-            OperationType::CreateFile => " Creating File... ",
-            OperationType::CreateDirectory => " Creating Directory... ",
-        }
-    }
-
-    fn operation_verb(&self) -> &'static str {
-        match self.progress.operation_type {
-            OperationType::Copy => "Copied",
-            OperationType::Move => "Moved",
-            OperationType::Delete => "Deleted",
-            OperationType::Rename => "Renamed",
-            OperationType::CreateFile => "Created",
-            OperationType::CreateDirectory => "Created",
-        }
+impl<'a> ShredConfirmModal<'a> {
+    /// Create a new shred confirmation modal.
+    pub fn new(theme: &'a Theme, state: &'a ShredConfirmState) -> Self {
+        Self { theme, state }
     }
 }
 
-impl Widget for OperationProgressModal<'_> {
+impl Widget for ShredConfirmModal<'_> {
     fn render(self, area: Rect, buf: &mut Buffer) {
-        let popup_width = 55.min(area.width.saturating_sub(4));
-        let popup_height = 12.min(area.height.saturating_sub(4));
+        let state = self.state;
+        let extra = state.warnings.len() + usize::from(state.input.error().is_some());
+        let popup_width = 64.min(area.width.saturating_sub(4));
+        let popup_height =
+            ((state.targets.len() + extra) as u16 + 12).min(area.height.saturating_sub(4));
 
         let popup_x = (area.width.saturating_sub(popup_width)) / 2 + area.x;
         let popup_y = (area.height.saturating_sub(popup_height)) / 2 + area.y;
@@ -373,142 +274,131 @@ impl Widget for OperationProgressModal<'_> {
         Clear.render(popup_area, buf);
 
         let block = Block::default()
-            .title(self.operation_title())
+            .title(" Secure Shred ")
             .title_style(
                 Style::default()
-                    .fg(self.theme.info)
+                    .fg(self.theme.error)
                     .add_modifier(Modifier::BOLD),
             )
             .borders(Borders::ALL)
-            .border_style(Style::default().fg(self.theme.info));
+            .border_style(Style::default().fg(self.theme.error));
 
         let inner = block.inner(popup_area);
         block.render(popup_area, buf);
 
-        let mut lines = vec![];
-
-        // Calculate percentage
-        let pct = if self.progress.bytes_total > 0 {
-            ((self.progress.bytes_processed as f64 / self.progress.bytes_total as f64) * 100.0)
-                as u8
-        } else if self.progress.files_total > 0 {
-            ((self.progress.files_completed as f64 / self.progress.files_total as f64) * 100.0)
-                as u8
-        } else {
-            0
-        };
-
-        // Progress bar
-        let bar_width = (inner.width as usize).saturating_sub(10);
-        let filled = (pct as usize * bar_width) / 100;
-        let empty = bar_width.saturating_sub(filled);
-
-        lines.push(Line::raw(""));
-        lines.push(Line::from(vec![
-            Span::raw("  ["),
-            Span::styled(
-                "\u{2588}".repeat(filled),
-                Style::default().fg(self.theme.info),
+        let mut lines = vec![
+            Line::styled(
+                format!(
+                    "Shred {} items ({}): {} random passes + zeros?",
+                    state.targets.len(),
+                    format_size(state.total_size()),
+                    state.passes
+                ),
+                Style::default()
+                    .fg(self.theme.error)
+                    .add_modifier(Modifier::BOLD),
             ),
-            Span::styled(
-                "\u{2591}".repeat(empty),
-                Style::default().fg(self.theme.muted),
+            Line::styled(
+                "Contents and names are destroyed. This cannot be undone.",
+                Style::default().fg(self.theme.warning),
             ),
-            Span::raw(format!("] {}%", pct)),
-        ]));
-
-        lines.push(Line::raw(""));
-
-        // Files progress
-        lines.push(Line::from(vec![
-            Span::styled("  Files:    ", self.theme.help_desc),
-            Span::raw(format!(
-                "{}/{} {}",
-                self.progress.files_completed,
-                self.progress.files_total,
-                self.operation_verb().to_lowercase()
-            )),
-        ]));
-
-        // Bytes progress
-        lines.push(Line::from(vec![
-            Span::styled("  Size:     ", self.theme.help_desc),
-            Span::raw(format!(
-                "{} / {}",
-                format_size(self.progress.bytes_processed),
-                format_size(self.progress.bytes_total)
-            )),
-        ]));
-
-        // Error count
-        if !self.progress.errors.is_empty() {
-            lines.push(Line::from(vec![
-                Span::styled("  Errors:   ", Style::default().fg(self.theme.error)),
-                Span::styled(
-                    self.progress.errors.len().to_string(),
-                    Style::default().fg(self.theme.error),
-                ),
-            ]));
+        ];
+        for warning in &state.warnings {
+            lines.push(Line::styled(
+                format!("\u{26A0} {}", warning),
+                Style::default()
+                    .fg(self.theme.warning)
+                    .add_modifier(Modifier::BOLD),
+            ));
         }
+        lines.push(Line::raw(""));
 
-        // Current file
-        if let Some(current) = &self.progress.current_file {
-            let name = current
-                .file_name()
-                .map(|n| n.to_string_lossy().to_string())
-                .unwrap_or_else(|| current.display().to_string());
-            let max_len = (inner.width as usize).saturating_sub(4);
-            let display_name = if name.len() > max_len {
-                format!("...{}", &name[name.len().saturating_sub(max_len - 3)..])
+        let max_items = (inner.height as usize).saturating_sub(lines.len() + 6 + extra);
+        let max_path_len = (inner.width as usize).saturating_sub(4);
+        for (path, _) in state.targets.iter().take(max_items) {
+            let full_path = path.display().to_string();
+            let display_path = if full_path.len() > max_path_len {
+                format!(
+                    "...{}",
+                    &full_path[full_path.len().saturating_sub(max_path_len - 3)..]
+                )
             } else {
-                name
+                full_path
             };
-            lines.push(Line::raw(""));
+            lines.push(Line::raw(format!("  {}", display_path)));
+        }
+        if state.targets.len() > max_items {
             lines.push(Line::styled(
-                format!("  {}", display_name),
+                format!("  ... and {} more", state.targets.len() - max_items),
                 Style::default().fg(self.theme.muted),
             ));
         }
 
+        lines.push(Line::raw(""));
+        lines.push(Line::styled(
+            format!(
+                "Type \"{}\" and press Enter to confirm:",
+                SHRED_CONFIRM_WORD
+            ),
+            self.theme.help_desc,
+        ));
+        lines.push(Line::from(vec![
+            Span::raw("  "),
+            Span::raw(state.input.buffer().to_string()),
+            Span::styled(" ", Style::default().add_modifier(Modifier::REVERSED)),
+        ]));
+        if let Some(error) = state.input.error() {
+            lines.push(Line::styled(
+                error.to_string(),
+                Style::default().fg(self.theme.error),
+            ));
+        }
+        lines.push(Line::raw(""));
+        lines.push(Line::from(vec![
+            Span::styled(" Enter ", self.theme.help_key),
+            Span::raw("Shred  "),
+            Span::styled(" Esc ", self.theme.help_key),
+            Span::raw("Cancel"),
+        ]));
+
         Paragraph::new(lines).render(inner, buf);
     }
 }
 
-/// Text input modal for rename/create operations.
-pub struct InputModal<'a> {
+/// Permissions editor showing the current `rwx` bits of the selection and a
+/// preview of the typed mode.
+pub struct PermissionsModal<'a> {
     theme: &'a Theme,
-    input: &'a InputState,
-    title: &'a str,
-    prompt: &'a str,
+    state: &'a PermissionsState,
 }
 
-impl<'a> InputModal<'a> {
-    /// Create a new input modal.
-    pub fn new(theme: &'a Theme, input: &'a InputState, title: &'a str, prompt: &'a str) -> Self {
-        Self {
-            theme,
-            input,
-            title,
-            prompt,
-        }
+impl<'a> PermissionsModal<'a> {
+    /// Create a new permissions modal.
+    pub fn new(theme: &'a Theme, state: &'a PermissionsState) -> Self {
+        Self { theme, state }
     }
 }
 
-impl Widget for InputModal<'_> {
+/// Maximum selected items listed in the permissions modal.
+const PERMISSIONS_MAX_ITEMS: usize = 6;
+
+impl Widget for PermissionsModal<'_> {
     fn render(self, area: Rect, buf: &mut Buffer) {
-        let popup_width = 50.min(area.width.saturating_sub(4));
-        let popup_height = if self.input.error().is_some() { 8 } else { 7 };
-        let popup_height = popup_height.min(area.height.saturating_sub(4));
+        let state = self.state;
+        let listed = state.entries.len().min(PERMISSIONS_MAX_ITEMS);
+        let more = usize::from(state.entries.len() > PERMISSIONS_MAX_ITEMS);
+        let error = usize::from(state.input.error().is_some());
+        let popup_width = 64.min(area.width.saturating_sub(4));
+        let popup_height = ((listed + more + error) as u16 + 17).min(area.height.saturating_sub(4));
 
         let popup_x = (area.width.saturating_sub(popup_width)) / 2 + area.x;
         let popup_y = (area.height.saturating_sub(popup_height)) / 2 + area.y;
-
         let popup_area = Rect::new(popup_x, popup_y, popup_width, popup_height);
 
         Clear.render(popup_area, buf);
 
         let block = Block::default()
-            .title(format!(" {} ", self.title))
+            .title(" Permissions ")
             .title_style(
                 Style::default()
                     .fg(self.theme.info)
@@ -520,87 +410,115 @@ impl Widget for InputModal<'_> {
         let inner = block.inner(popup_area);
         block.render(popup_area, buf);
 
-        let mut lines = vec![];
-
-        // Prompt
-        lines.push(Line::styled(self.prompt, self.theme.help_desc));
-        lines.push(Line::raw(""));
-
-        // Input field with cursor
-        let buffer = self.input.buffer();
-        let cursor = self.input.cursor();
-        let max_visible = (inner.width as usize).saturating_sub(4);
-
-        // Calculate visible portion
-        let (visible_start, cursor_in_view) = if cursor > max_visible - 1 {
-            (cursor - (max_visible - 1), max_visible - 1)
-        } else {
-            (0, cursor)
-        };
-
-        let visible_end = (visible_start + max_visible).min(buffer.len());
-        let visible_text = if buffer.is_empty() {
-            String::new()
-        } else {
-            buffer
-                .chars()
-                .skip(visible_start)
-                .take(visible_end - visible_start)
-                .collect()
-        };
-
-        let mut input_spans = vec![Span::raw("  ")];
-
-        if visible_text.is_empty() {
-            // Show cursor at empty position
-            input_spans.push(Span::styled(
-                " ",
-                Style::default().add_modifier(Modifier::REVERSED),
-            ));
-        } else {
-            // Text before cursor
-            let before: String = visible_text.chars().take(cursor_in_view).collect();
-            if !before.is_empty() {
-                input_spans.push(Span::raw(before));
-            }
+        let muted = Style::default().fg(self.theme.muted);
+        let mut lines = Vec::new();
 
-            // Cursor character
-            let cursor_char: String = visible_text.chars().skip(cursor_in_view).take(1).collect();
-            if cursor_char.is_empty() {
-                input_spans.push(Span::styled(
-                    " ",
-                    Style::default().add_modifier(Modifier::REVERSED),
-                ));
+        // Selection with its current mode and owner
+        let name_width = (inner.width as usize).saturating_sub(32);
+        for entry in state.entries.iter().take(PERMISSIONS_MAX_ITEMS) {
+            let kind = if entry.is_dir { 'd' } else { '-' };
+            let name = entry
+                .path
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_else(|| entry.path.display().to_string());
+            let name = if name.chars().count() > name_width && name_width > 3 {
+                let truncated: String = name.chars().take(name_width - 3).collect();
+                format!("{}...", truncated)
             } else {
-                input_spans.push(Span::styled(
-                    cursor_char,
-                    Style::default().add_modifier(Modifier::REVERSED),
-                ));
-            }
+                name
+            };
+            let owner = format!("{}:{}", entry.owner, entry.group);
+            lines.push(Line::from(vec![
+                Span::raw(format!(" {}{} ", kind, format_mode(entry.mode))),
+                Span::styled(format!("{:<18} ", owner), muted),
+                Span::styled(
+                    name,
+                    if entry.is_dir {
+                        self.theme.directory
+                    } else {
+                        Style::default()
+                    },
+                ),
+            ]));
+        }
+        if more == 1 {
+            lines.push(Line::styled(
+                format!(
+                    "  ... and {} more",
+                    state.entries.len() - PERMISSIONS_MAX_ITEMS
+                ),
+                muted,
+            ));
+        }
+        lines.push(Line::raw(""));
 
-            // Text after cursor
-            let after: String = visible_text.chars().skip(cursor_in_view + 1).collect();
-            if !after.is_empty() {
-                input_spans.push(Span::raw(after));
+        // rwx grid of the first item, highlighting bits the typed mode changes
+        let current = state.entries.first().map_or(0, |e| e.mode);
+        let new = state.preview().unwrap_or(current);
+        lines.push(Line::styled("           read  write  exec", muted));
+        for (label, shift) in [("User", 6), ("Group", 3), ("Other", 0)] {
+            let mut spans = vec![Span::raw(format!("  {:<8}", label))];
+            for bit in [4, 2, 1] {
+                let mask = bit << shift;
+                let on = new & mask != 0;
+                let style = if (current ^ new) & mask != 0 {
+                    Style::default()
+                        .fg(self.theme.warning)
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default()
+                };
+                spans.push(Span::styled(if on { " [x]  " } else { " [ ]  " }, style));
             }
+            lines.push(Line::from(spans));
         }
+        let mut summary = vec![Span::styled(
+            format!("  {:04o} {}", current, format_mode(current)),
+            muted,
+        )];
+        if new != current {
+            summary.push(Span::raw("  ->  "));
+            summary.push(Span::styled(
+                format!("{:04o} {}", new, format_mode(new)),
+                Style::default().fg(self.theme.warning),
+            ));
+        }
+        lines.push(Line::from(summary));
+        lines.push(Line::raw(""));
 
-        lines.push(Line::from(input_spans));
-
-        // Error message if any
-        if let Some(error) = self.input.error() {
-            lines.push(Line::raw(""));
+        // Mode input
+        lines.push(Line::styled(
+            "New mode (e.g. 755, u+x,go-w, a+rX):",
+            self.theme.help_desc,
+        ));
+        lines.push(Line::from(vec![
+            Span::raw("  "),
+            Span::raw(state.input.buffer().to_string()),
+            Span::styled(" ", Style::default().add_modifier(Modifier::REVERSED)),
+        ]));
+        if let Some(error) = state.input.error() {
             lines.push(Line::styled(
-                format!("  {}", error),
+                error.to_string(),
                 Style::default().fg(self.theme.error),
             ));
         }
-
-        // Help line
+        lines.push(Line::raw(""));
+        lines.push(Line::from(vec![
+            Span::styled(" Tab ", self.theme.help_key),
+            Span::raw(format!(" Apply to: {}", state.scope.label())),
+        ]));
+        lines.push(Line::from(vec![
+            Span::styled(" C-r ", self.theme.help_key),
+            Span::raw(format!(
+                " Recursive: {}",
+                if state.recursive { "yes" } else { "no" }
+            )),
+        ]));
         lines.push(Line::raw(""));
         lines.push(Line::from(vec![
             Span::styled(" Enter ", self.theme.help_key),
-            Span::raw("Confirm  "),
+            Span::raw("Apply  "),
             Span::styled(" Esc ", self.theme.help_key),
             Span::raw("Cancel"),
         ]));
@@ -609,226 +527,1325 @@ impl Widget for InputModal<'_> {
     }
 }
 
-/// Conflict resolution modal.
-pub struct ConflictModal<'a> {
+/// Rule-based rename with a live preview of every new name.
+pub struct RenamePatternModal<'a> {
     theme: &'a Theme,
-    conflict: &'a Conflict,
+    state: &'a RenamePatternState,
 }
 
-impl<'a> ConflictModal<'a> {
-    /// Create a new conflict modal.
-    pub fn new(theme: &'a Theme, conflict: &'a Conflict) -> Self {
-        Self { theme, conflict }
+impl<'a> RenamePatternModal<'a> {
+    /// Create a new rename pattern modal.
+    pub fn new(theme: &'a Theme, state: &'a RenamePatternState) -> Self {
+        Self { theme, state }
+    }
+
+    /// Spans of an input line, with a cursor when focused.
+    fn input_spans(&self, input: &'a InputState, focused: bool) -> Vec<Span<'a>> {
+        let buffer = input.buffer();
+        if !focused {
+            return vec![Span::raw(buffer)];
+        }
+        let cursor = input.cursor().min(buffer.len());
+        let (before, rest) = buffer.split_at(cursor);
+        let mut chars = rest.chars();
+        let at = chars.next().map_or(" ".to_string(), String::from);
+        vec![
+            Span::raw(before),
+            Span::styled(at, Style::default().add_modifier(Modifier::REVERSED)),
+            Span::raw(chars.as_str()),
+        ]
     }
 }
 
-impl Widget for ConflictModal<'_> {
+impl Widget for RenamePatternModal<'_> {
     fn render(self, area: Rect, buf: &mut Buffer) {
-        let popup_width = 60.min(area.width.saturating_sub(4));
-        let popup_height = 12.min(area.height.saturating_sub(4));
+        let state = self.state;
+        let popup_width = 90.min(area.width.saturating_sub(4));
+        let popup_height = (state.paths.len() as u16 + 11).clamp(14, area.height.saturating_sub(4));
 
         let popup_x = (area.width.saturating_sub(popup_width)) / 2 + area.x;
         let popup_y = (area.height.saturating_sub(popup_height)) / 2 + area.y;
-
         let popup_area = Rect::new(popup_x, popup_y, popup_width, popup_height);
 
         Clear.render(popup_area, buf);
 
-        let (title, title_color) = match self.conflict.kind {
-            ConflictKind::FileExists => (" File Exists ", self.theme.warning),
-            ConflictKind::DirectoryExists => (" Directory Exists ", self.theme.warning),
-            ConflictKind::SourceIsAncestor => (" Invalid Operation ", self.theme.error),
-            ConflictKind::PermissionDenied => (" Permission Denied ", self.theme.error),
-            ConflictKind::SameFile => (" Same File ", self.theme.info),
-        };
-
         let block = Block::default()
-            .title(title)
+            .title(format!(" Rename {} Items ", state.paths.len()))
             .title_style(
                 Style::default()
-                    .fg(title_color)
+                    .fg(self.theme.info)
                     .add_modifier(Modifier::BOLD),
             )
             .borders(Borders::ALL)
-            .border_style(Style::default().fg(title_color));
+            .border_style(self.theme.border);
 
         let inner = block.inner(popup_area);
         block.render(popup_area, buf);
 
-        let mut lines = vec![];
-
-        // Conflict description
-        let description = match self.conflict.kind {
-            ConflictKind::FileExists => "A file with this name already exists:",
-            ConflictKind::DirectoryExists => "A directory with this name already exists:",
-            ConflictKind::SourceIsAncestor => "Cannot copy/move a directory into itself:",
-            ConflictKind::PermissionDenied => "Permission denied for:",
-            ConflictKind::SameFile => "Source and destination are the same file:",
-        };
+        let muted = Style::default().fg(self.theme.muted);
+        let error_style = Style::default().fg(self.theme.error);
+        let mut lines = Vec::new();
+
+        for (label, field, input) in [
+            ("Find:    ", RenameField::Find, &state.find),
+            ("Replace: ", RenameField::Replace, &state.replace),
+        ] {
+            let focused = state.focus == field;
+            let label_style = if focused {
+                self.theme.help_key
+            } else {
+                self.theme.help_desc
+            };
+            let mut spans = vec![Span::styled(format!(" {}", label), label_style)];
+            spans.extend(self.input_spans(input, focused));
+            lines.push(Line::from(spans));
+        }
+        lines.push(Line::from(vec![
+            Span::styled(" Case:    ", self.theme.help_desc),
+            Span::raw(state.case.to_string()),
+            Span::styled(
+                "   tokens: {name} {stem} {ext} {parent} {size} {mtime:%Y-%m-%d} {n:3}",
+                muted,
+            ),
+        ]));
+        lines.push(Line::styled(
+            "─".repeat(inner.width as usize),
+            self.theme.border,
+        ));
 
-        lines.push(Line::styled(description, self.theme.help_desc));
-        lines.push(Line::raw(""));
+        // Preview rows
+        let rows = (inner.height as usize).saturating_sub(lines.len() + 3);
+        match &state.preview {
+            Ok(previews) => {
+                let old_width = (inner.width as usize).saturating_sub(6) / 2;
+                for preview in previews.iter().skip(state.offset).take(rows) {
+                    let old = preview
+                        .source
+                        .file_name()
+                        .map(|n| n.to_string_lossy().into_owned())
+                        .unwrap_or_default();
+                    let old = truncate_name(&old, old_width);
+                    let (new_style, note) = match &preview.status {
+                        RenameStatus::Ready => (Style::default().fg(self.theme.success), None),
+                        RenameStatus::Unchanged => (muted, None),
+                        RenameStatus::Invalid(e) | RenameStatus::Collision(e) => {
+                            (error_style, Some(e.as_str()))
+                        }
+                    };
+                    let mut spans = vec![
+                        Span::raw(format!(" {:<width$}", old, width = old_width)),
+                        Span::styled(" → ", muted),
+                        Span::styled(preview.new_name.clone(), new_style),
+                    ];
+                    if let Some(note) = note {
+                        spans.push(Span::styled(format!("  ({})", note), error_style));
+                    }
+                    lines.push(Line::from(spans));
+                }
+            }
+            Err(e) => lines.push(Line::styled(format!(" {}", e), error_style)),
+        }
 
-        // Source path
-        let source_name = self
-            .conflict
-            .source
-            .file_name()
-            .map(|n| n.to_string_lossy().to_string())
-            .unwrap_or_else(|| self.conflict.source.display().to_string());
-        let max_len = (inner.width as usize).saturating_sub(4);
-        let display_name = if source_name.len() > max_len {
-            format!(
-                "...{}",
-                &source_name[source_name.len().saturating_sub(max_len - 3)..]
-            )
+        // Summary, error and hints pinned to the bottom
+        let mut footer = Vec::new();
+        if let Some(error) = state.find.error().or(state.replace.error()) {
+            footer.push(Line::styled(format!(" {}", error), error_style));
         } else {
-            source_name
-        };
-        lines.push(Line::styled(
-            format!("  {}", display_name),
-            Style::default().fg(self.theme.info),
-        ));
+            let ready = state.ready().len();
+            let problems = state.problems();
+            let mut spans = vec![Span::raw(format!(" {} to rename", ready))];
+            if problems > 0 {
+                spans.push(Span::styled(
+                    format!(", {} problems", problems),
+                    error_style,
+                ));
+            }
+            footer.push(Line::from(spans));
+        }
+        footer.push(Line::from(vec![
+            Span::styled(" Enter ", self.theme.help_key),
+            Span::raw("Rename  "),
+            Span::styled(" Tab ", self.theme.help_key),
+            Span::raw("Field  "),
+            Span::styled(" C-t ", self.theme.help_key),
+            Span::raw("Case  "),
+            Span::styled(" ↑↓ ", self.theme.help_key),
+            Span::raw("Scroll  "),
+            Span::styled(" Esc ", self.theme.help_key),
+            Span::raw("Cancel"),
+        ]));
 
-        // Destination path
-        let dest = &self.conflict.destination;
-        let dest_name = dest
-            .file_name()
-            .map(|n| n.to_string_lossy().to_string())
-            .unwrap_or_else(|| dest.display().to_string());
-        let dest_display = if dest_name.len() > max_len {
-            format!(
-                "...{}",
-                &dest_name[dest_name.len().saturating_sub(max_len - 3)..]
+        let footer_height = footer.len() as u16;
+        let body = Rect::new(
+            inner.x,
+            inner.y,
+            inner.width,
+            inner.height.saturating_sub(footer_height),
+        );
+        Paragraph::new(lines).render(body, buf);
+        let footer_area = Rect::new(
+            inner.x,
+            inner.y + inner.height.saturating_sub(footer_height),
+            inner.width,
+            footer_height.min(inner.height),
+        );
+        Paragraph::new(footer).render(footer_area, buf);
+    }
+}
+
+/// Shorten `name` to `width` characters with a trailing ellipsis.
+fn truncate_name(name: &str, width: usize) -> String {
+    if name.chars().count() <= width || width < 4 {
+        name.to_string()
+    } else {
+        let kept: String = name.chars().take(width - 3).collect();
+        format!("{}...", kept)
+    }
+}
+
+/// Progress dialog for deletion operation.
+pub struct DeletionProgressModal<'a> {
+    theme: &'a Theme,
+    progress: Option<&'a DeletionProgress>,
+}
+
+impl<'a> DeletionProgressModal<'a> {
+    /// Create a new deletion progress modal.
+    pub fn new(theme: &'a Theme, progress: Option<&'a DeletionProgress>) -> Self {
+        Self { theme, progress }
+    }
+}
+
+impl Widget for DeletionProgressModal<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        // Calculate popup area
+        let popup_width = 50.min(area.width.saturating_sub(4));
+        let popup_height = 10.min(area.height.saturating_sub(4));
+
+        let popup_x = (area.width.saturating_sub(popup_width)) / 2 + area.x;
+        let popup_y = (area.height.saturating_sub(popup_height)) / 2 + area.y;
+
+        let popup_area = Rect::new(popup_x, popup_y, popup_width, popup_height);
+
+        // Clear the popup area
+        Clear.render(popup_area, buf);
+
+        // Draw border
+        let block = Block::default()
+            .title(" Deleting... ")
+            .title_style(
+                Style::default()
+                    .fg(self.theme.warning)
+                    .add_modifier(Modifier::BOLD),
             )
-        } else {
-            dest_name
-        };
-        lines.push(Line::styled(
-            format!("  → {}", dest_display),
-            Style::default().fg(self.theme.muted),
-        ));
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(self.theme.warning));
 
-        lines.push(Line::raw(""));
-        lines.push(Line::raw(""));
+        let inner = block.inner(popup_area);
+        block.render(popup_area, buf);
 
-        // Action options based on conflict type
-        match self.conflict.kind {
-            ConflictKind::FileExists | ConflictKind::DirectoryExists => {
-                lines.push(Line::from(vec![
-                    Span::styled(" s ", self.theme.help_key),
-                    Span::raw("Skip  "),
-                    Span::styled(" o ", self.theme.help_key),
-                    Span::raw("Overwrite  "),
-                    Span::styled(" r ", self.theme.help_key),
-                    Span::raw("Rename"),
-                ]));
-                lines.push(Line::from(vec![
-                    Span::styled(" S ", self.theme.help_key),
-                    Span::raw("Skip All  "),
-                    Span::styled(" O ", self.theme.help_key),
-                    Span::raw("Overwrite All  "),
-                    Span::styled(" Esc ", self.theme.help_key),
-                    Span::raw("Abort"),
-                ]));
-            }
-            ConflictKind::PermissionDenied => {
-                lines.push(Line::from(vec![
-                    Span::styled(" s ", self.theme.help_key),
-                    Span::raw("Skip  "),
-                    Span::styled(" S ", self.theme.help_key),
-                    Span::raw("Skip All  "),
-                    Span::styled(" Esc ", self.theme.help_key),
-                    Span::raw("Abort"),
-                ]));
-            }
-            ConflictKind::SourceIsAncestor => {
+        let mut lines = vec![];
+
+        if let Some(progress) = self.progress {
+            // Progress bar
+            let pct = progress.percentage();
+            let bar_width = (inner.width as usize).saturating_sub(10);
+            let filled = (pct as usize * bar_width) / 100;
+            let empty = bar_width.saturating_sub(filled);
+
+            lines.push(Line::raw(""));
+            lines.push(Line::from(vec![
+                Span::raw("  ["),
+                Span::styled(
+                    "\u{2588}".repeat(filled),
+                    Style::default().fg(self.theme.info),
+                ),
+                Span::styled(
+                    "\u{2591}".repeat(empty),
+                    Style::default().fg(self.theme.muted),
+                ),
+                Span::raw(format!("] {}%", pct)),
+            ]));
+
+            lines.push(Line::raw(""));
+            lines.push(Line::from(vec![
+                Span::styled("  Progress: ", self.theme.help_desc),
+                Span::raw(format!(
+                    "{}/{} items",
+                    progress.deleted + progress.failed,
+                    progress.total
+                )),
+            ]));
+
+            lines.push(Line::from(vec![
+                Span::styled("  Freed:    ", self.theme.help_desc),
+                Span::raw(format_size(progress.bytes_freed)),
+            ]));
+
+            if progress.failed > 0 {
                 lines.push(Line::from(vec![
-                    Span::styled(" Enter/Esc ", self.theme.help_key),
-                    Span::raw("Acknowledge"),
+                    Span::styled("  Failed:   ", Style::default().fg(self.theme.error)),
+                    Span::styled(
+                        progress.failed.to_string(),
+                        Style::default().fg(self.theme.error),
+                    ),
                 ]));
             }
-            ConflictKind::SameFile => {
-                // Can only skip (do nothing) or create renamed copy
+
+            // Current item being deleted
+            if let Some(current) = &progress.current {
+                let name = current
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_else(|| current.display().to_string());
+                let max_len = (inner.width as usize).saturating_sub(4);
+                let display_name = if name.len() > max_len {
+                    format!("...{}", &name[name.len().saturating_sub(max_len - 3)..])
+                } else {
+                    name
+                };
+                lines.push(Line::raw(""));
                 lines.push(Line::styled(
-                    "Cannot copy a file onto itself.",
+                    format!("  {}", display_name),
                     Style::default().fg(self.theme.muted),
                 ));
-                lines.push(Line::raw(""));
-                lines.push(Line::from(vec![
-                    Span::styled(" Enter/s ", self.theme.help_key),
-                    Span::raw("Skip  "),
-                    Span::styled(" r ", self.theme.help_key),
-                    Span::raw("Create Renamed Copy  "),
-                    Span::styled(" Esc ", self.theme.help_key),
-                    Span::raw("Cancel"),
-                ]));
             }
+        } else {
+            lines.push(Line::raw("  Preparing deletion..."));
         }
 
         Paragraph::new(lines).render(inner, buf);
     }
 }
 
-/// Settings modal for user preferences.
-pub struct SettingsModal<'a> {
+/// Command palette input widget.
+pub struct CommandPalette<'a> {
     theme: &'a Theme,
-    state: &'a SettingsState,
+    input: &'a str,
+    cursor: usize,
 }
 
-impl<'a> SettingsModal<'a> {
-    /// Create a new settings modal.
-    pub fn new(theme: &'a Theme, state: &'a SettingsState) -> Self {
-        Self { theme, state }
+impl<'a> CommandPalette<'a> {
+    /// Create a new command palette widget.
+    pub fn new(theme: &'a Theme, input: &'a str, cursor: usize) -> Self {
+        Self {
+            theme,
+            input,
+            cursor,
+        }
     }
+}
 
-    fn render_toggle(&self, label: &str, value: bool, selected: bool) -> Line<'static> {
-        let checkbox = if value { "[x]" } else { "[ ]" };
-        let style = if selected {
+impl Widget for CommandPalette<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        // Clear the area first
+        Clear.render(area, buf);
+
+        // Build the command line display
+        let prompt = ":";
+
+        // Build the line with cursor indication
+        let mut spans = vec![Span::styled(
+            prompt,
             Style::default()
                 .fg(self.theme.info)
-                .add_modifier(Modifier::BOLD)
+                .add_modifier(Modifier::BOLD),
+        )];
+
+        if self.input.is_empty() {
+            // Show placeholder
+            spans.push(Span::styled(
+                "type command (q, cd, help...)",
+                Style::default().fg(self.theme.muted),
+            ));
         } else {
-            Style::default()
-        };
+            // Show input with cursor
+            let cursor_pos = self.cursor.min(self.input.len());
+            let (before, after) = self.input.split_at(cursor_pos);
+            spans.push(Span::raw(before));
+
+            if !after.is_empty() {
+                // Show cursor under next char
+                let (cursor_char, rest) = after.split_at(1);
+                spans.push(Span::styled(
+                    cursor_char,
+                    Style::default().add_modifier(Modifier::REVERSED),
+                ));
+                spans.push(Span::raw(rest));
+            } else {
+                // Cursor at end - show block cursor
+                spans.push(Span::styled(
+                    " ",
+                    Style::default().add_modifier(Modifier::REVERSED),
+                ));
+            }
+        }
+
+        let line = Line::from(spans);
+        Paragraph::new(line)
+            .style(self.theme.footer)
+            .render(area, buf);
+    }
+}
+
+/// Progress dialog for copy/move operations.
+pub struct OperationProgressModal<'a> {
+    theme: &'a Theme,
+    progress: &'a OperationProgress,
+    cancellable: bool,
+}
+
+impl<'a> OperationProgressModal<'a> {
+    /// Create a new operation progress modal.
+    pub fn new(theme: &'a Theme, progress: &'a OperationProgress) -> Self {
+        Self {
+            theme,
+            progress,
+            cancellable: false,
+        }
+    }
+
+    /// Show the Esc-to-cancel hint.
+    pub fn cancellable(mut self, cancellable: bool) -> Self {
+        self.cancellable = cancellable;
+        self
+    }
+
+    fn operation_title(&self) -> &'static str {
+        match self.progress.operation_type {
+            OperationType::Copy => " Copying... ",
+            OperationType::Move => " Moving... ",
+            OperationType::Delete => " Deleting... ",
+            OperationType::Rename => " Renaming... ",
+            OperationType::CreateFile => " Creating File... ",
+            OperationType::CreateDirectory => " Creating Directory... ",
+            OperationType::Restore => " Restoring... ",
+            OperationType::Sync => " Syncing... ",
+            OperationType::Shred => " Shredding... ",
+            OperationType::Chmod => " Changing Permissions... ",
+            OperationType::Chown => " Changing Owner... ",
+            OperationType::Link => " Linking... ",
+        }
+    }
+
+    fn operation_verb(&self) -> &'static str {
+        match self.progress.operation_type {
+            OperationType::Copy => "Copied",
+            OperationType::Move => "Moved",
+            OperationType::Delete => "Deleted",
+            OperationType::Rename => "Renamed",
+            OperationType::CreateFile => "Created",
+            OperationType::CreateDirectory => "Created",
+            OperationType::Restore => "Restored",
+            OperationType::Sync => "Synced",
+            OperationType::Shred => "Shredded",
+            OperationType::Chmod => "Changed",
+            OperationType::Chown => "Changed",
+            OperationType::Link => "Linked",
+        }
+    }
+}
+
+impl Widget for OperationProgressModal<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let popup_width = 55.min(area.width.saturating_sub(4));
+        let popup_height = 14.min(area.height.saturating_sub(4));
+
+        let popup_x = (area.width.saturating_sub(popup_width)) / 2 + area.x;
+        let popup_y = (area.height.saturating_sub(popup_height)) / 2 + area.y;
+
+        let popup_area = Rect::new(popup_x, popup_y, popup_width, popup_height);
+
+        Clear.render(popup_area, buf);
+
+        let block = Block::default()
+            .title(self.operation_title())
+            .title_style(
+                Style::default()
+                    .fg(self.theme.info)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(self.theme.info));
+
+        let inner = block.inner(popup_area);
+        block.render(popup_area, buf);
+
+        let mut lines = vec![];
+
+        // Calculate percentage
+        let pct = if self.progress.bytes_total > 0 {
+            ((self.progress.bytes_processed as f64 / self.progress.bytes_total as f64) * 100.0)
+                as u8
+        } else if self.progress.files_total > 0 {
+            ((self.progress.files_completed as f64 / self.progress.files_total as f64) * 100.0)
+                as u8
+        } else {
+            0
+        };
+
+        // Progress bar
+        let bar_width = (inner.width as usize).saturating_sub(10);
+        let filled = (pct as usize * bar_width) / 100;
+        let empty = bar_width.saturating_sub(filled);
+
+        lines.push(Line::raw(""));
+        lines.push(Line::from(vec![
+            Span::raw("  ["),
+            Span::styled(
+                "\u{2588}".repeat(filled),
+                Style::default().fg(self.theme.info),
+            ),
+            Span::styled(
+                "\u{2591}".repeat(empty),
+                Style::default().fg(self.theme.muted),
+            ),
+            Span::raw(format!("] {}%", pct)),
+        ]));
+
+        lines.push(Line::raw(""));
+
+        // Files progress
+        lines.push(Line::from(vec![
+            Span::styled("  Files:    ", self.theme.help_desc),
+            Span::raw(format!(
+                "{}/{} {}",
+                self.progress.files_completed,
+                self.progress.files_total,
+                self.operation_verb().to_lowercase()
+            )),
+        ]));
+
+        // Bytes progress
+        lines.push(Line::from(vec![
+            Span::styled("  Size:     ", self.theme.help_desc),
+            Span::raw(format!(
+                "{} / {}",
+                format_size(self.progress.bytes_processed),
+                format_size(self.progress.bytes_total)
+            )),
+        ]));
+
+        // Error count
+        if !self.progress.errors.is_empty() {
+            lines.push(Line::from(vec![
+                Span::styled("  Errors:   ", Style::default().fg(self.theme.error)),
+                Span::styled(
+                    self.progress.errors.len().to_string(),
+                    Style::default().fg(self.theme.error),
+                ),
+            ]));
+        }
+
+        // Current file
+        if let Some(current) = &self.progress.current_file {
+            let name = current
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| current.display().to_string());
+            let max_len = (inner.width as usize).saturating_sub(4);
+            let display_name = if name.len() > max_len {
+                format!("...{}", &name[name.len().saturating_sub(max_len - 3)..])
+            } else {
+                name
+            };
+            lines.push(Line::raw(""));
+            lines.push(Line::styled(
+                format!("  {}", display_name),
+                Style::default().fg(self.theme.muted),
+            ));
+        }
+
+        if self.cancellable {
+            lines.push(Line::raw(""));
+            lines.push(Line::from(vec![
+                Span::styled(" Esc ", self.theme.help_key_style()),
+                Span::styled("Cancel", self.theme.help_desc_style()),
+            ]));
+        }
+
+        Paragraph::new(lines).render(inner, buf);
+    }
+}
+
+/// Text input modal for rename/create operations.
+pub struct InputModal<'a> {
+    theme: &'a Theme,
+    input: &'a InputState,
+    title: &'a str,
+    prompt: &'a str,
+}
+
+impl<'a> InputModal<'a> {
+    /// Create a new input modal.
+    pub fn new(theme: &'a Theme, input: &'a InputState, title: &'a str, prompt: &'a str) -> Self {
+        Self {
+            theme,
+            input,
+            title,
+            prompt,
+        }
+    }
+}
+
+impl Widget for InputModal<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let popup_width = 50.min(area.width.saturating_sub(4));
+        let popup_height = if self.input.error().is_some() { 8 } else { 7 };
+        let popup_height = popup_height.min(area.height.saturating_sub(4));
+
+        let popup_x = (area.width.saturating_sub(popup_width)) / 2 + area.x;
+        let popup_y = (area.height.saturating_sub(popup_height)) / 2 + area.y;
+
+        let popup_area = Rect::new(popup_x, popup_y, popup_width, popup_height);
+
+        Clear.render(popup_area, buf);
+
+        let block = Block::default()
+            .title(format!(" {} ", self.title))
+            .title_style(
+                Style::default()
+                    .fg(self.theme.info)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .borders(Borders::ALL)
+            .border_style(self.theme.border);
+
+        let inner = block.inner(popup_area);
+        block.render(popup_area, buf);
+
+        let mut lines = vec![];
+
+        // Prompt
+        lines.push(Line::styled(self.prompt, self.theme.help_desc));
+        lines.push(Line::raw(""));
+
+        // Input field with cursor
+        let buffer = self.input.buffer();
+        let cursor = self.input.cursor();
+        let max_visible = (inner.width as usize).saturating_sub(4);
+
+        // Calculate visible portion
+        let (visible_start, cursor_in_view) = if cursor > max_visible - 1 {
+            (cursor - (max_visible - 1), max_visible - 1)
+        } else {
+            (0, cursor)
+        };
+
+        let visible_end = (visible_start + max_visible).min(buffer.len());
+        let visible_text = if buffer.is_empty() {
+            String::new()
+        } else {
+            buffer
+                .chars()
+                .skip(visible_start)
+                .take(visible_end - visible_start)
+                .collect()
+        };
+
+        let mut input_spans = vec![Span::raw("  ")];
+
+        if visible_text.is_empty() {
+            // Show cursor at empty position
+            input_spans.push(Span::styled(
+                " ",
+                Style::default().add_modifier(Modifier::REVERSED),
+            ));
+        } else {
+            // Text before cursor
+            let before: String = visible_text.chars().take(cursor_in_view).collect();
+            if !before.is_empty() {
+                input_spans.push(Span::raw(before));
+            }
+
+            // Cursor character
+            let cursor_char: String = visible_text.chars().skip(cursor_in_view).take(1).collect();
+            if cursor_char.is_empty() {
+                input_spans.push(Span::styled(
+                    " ",
+                    Style::default().add_modifier(Modifier::REVERSED),
+                ));
+            } else {
+                input_spans.push(Span::styled(
+                    cursor_char,
+                    Style::default().add_modifier(Modifier::REVERSED),
+                ));
+            }
+
+            // Text after cursor
+            let after: String = visible_text.chars().skip(cursor_in_view + 1).collect();
+            if !after.is_empty() {
+                input_spans.push(Span::raw(after));
+            }
+        }
+
+        lines.push(Line::from(input_spans));
+
+        // Error message if any
+        if let Some(error) = self.input.error() {
+            lines.push(Line::raw(""));
+            lines.push(Line::styled(
+                format!("  {}", error),
+                Style::default().fg(self.theme.error),
+            ));
+        }
+
+        // Help line
+        lines.push(Line::raw(""));
+        lines.push(Line::from(vec![
+            Span::styled(" Enter ", self.theme.help_key),
+            Span::raw("Confirm  "),
+            Span::styled(" Esc ", self.theme.help_key),
+            Span::raw("Cancel"),
+        ]));
+
+        Paragraph::new(lines).render(inner, buf);
+    }
+}
+
+/// Conflict resolution modal.
+pub struct ConflictModal<'a> {
+    theme: &'a Theme,
+    conflict: &'a Conflict,
+}
+
+impl<'a> ConflictModal<'a> {
+    /// Create a new conflict modal.
+    pub fn new(theme: &'a Theme, conflict: &'a Conflict) -> Self {
+        Self { theme, conflict }
+    }
+}
+
+impl Widget for ConflictModal<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let popup_width = 60.min(area.width.saturating_sub(4));
+        let popup_height = 12.min(area.height.saturating_sub(4));
+
+        let popup_x = (area.width.saturating_sub(popup_width)) / 2 + area.x;
+        let popup_y = (area.height.saturating_sub(popup_height)) / 2 + area.y;
+
+        let popup_area = Rect::new(popup_x, popup_y, popup_width, popup_height);
+
+        Clear.render(popup_area, buf);
+
+        let (title, title_color) = match self.conflict.kind {
+            ConflictKind::FileExists => (" File Exists ", self.theme.warning),
+            ConflictKind::DirectoryExists => (" Directory Exists ", self.theme.warning),
+            ConflictKind::SourceIsAncestor => (" Invalid Operation ", self.theme.error),
+            ConflictKind::PermissionDenied => (" Permission Denied ", self.theme.error),
+            ConflictKind::SameFile => (" Same File ", self.theme.info),
+        };
+
+        let block = Block::default()
+            .title(title)
+            .title_style(
+                Style::default()
+                    .fg(title_color)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(title_color));
+
+        let inner = block.inner(popup_area);
+        block.render(popup_area, buf);
+
+        let mut lines = vec![];
+
+        // Conflict description
+        let description = match self.conflict.kind {
+            ConflictKind::FileExists => "A file with this name already exists:",
+            ConflictKind::DirectoryExists => "A directory with this name already exists:",
+            ConflictKind::SourceIsAncestor => "Cannot copy/move a directory into itself:",
+            ConflictKind::PermissionDenied => "Permission denied for:",
+            ConflictKind::SameFile => "Source and destination are the same file:",
+        };
+
+        lines.push(Line::styled(description, self.theme.help_desc));
+        lines.push(Line::raw(""));
+
+        // Source path
+        let source_name = self
+            .conflict
+            .source
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| self.conflict.source.display().to_string());
+        let max_len = (inner.width as usize).saturating_sub(4);
+        let display_name = if source_name.len() > max_len {
+            format!(
+                "...{}",
+                &source_name[source_name.len().saturating_sub(max_len - 3)..]
+            )
+        } else {
+            source_name
+        };
+        lines.push(Line::styled(
+            format!("  {}", display_name),
+            Style::default().fg(self.theme.info),
+        ));
+
+        // Destination path
+        let dest = &self.conflict.destination;
+        let dest_name = dest
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| dest.display().to_string());
+        let dest_display = if dest_name.len() > max_len {
+            format!(
+                "...{}",
+                &dest_name[dest_name.len().saturating_sub(max_len - 3)..]
+            )
+        } else {
+            dest_name
+        };
+        lines.push(Line::styled(
+            format!("  → {}", dest_display),
+            Style::default().fg(self.theme.muted),
+        ));
+
+        lines.push(Line::raw(""));
+        lines.push(Line::raw(""));
+
+        // Action options based on conflict type
+        match self.conflict.kind {
+            ConflictKind::FileExists | ConflictKind::DirectoryExists => {
+                lines.push(Line::from(vec![
+                    Span::styled(" s ", self.theme.help_key),
+                    Span::raw("Skip  "),
+                    Span::styled(" o ", self.theme.help_key),
+                    Span::raw("Overwrite  "),
+                    Span::styled(" r ", self.theme.help_key),
+                    Span::raw("Rename"),
+                ]));
+                lines.push(Line::from(vec![
+                    Span::styled(" S ", self.theme.help_key),
+                    Span::raw("Skip All  "),
+                    Span::styled(" O ", self.theme.help_key),
+                    Span::raw("Overwrite All  "),
+                    Span::styled(" Esc ", self.theme.help_key),
+                    Span::raw("Abort"),
+                ]));
+            }
+            ConflictKind::PermissionDenied => {
+                lines.push(Line::from(vec![
+                    Span::styled(" s ", self.theme.help_key),
+                    Span::raw("Skip  "),
+                    Span::styled(" S ", self.theme.help_key),
+                    Span::raw("Skip All  "),
+                    Span::styled(" Esc ", self.theme.help_key),
+                    Span::raw("Abort"),
+                ]));
+            }
+            ConflictKind::SourceIsAncestor => {
+                lines.push(Line::from(vec![
+                    Span::styled(" Enter/Esc ", self.theme.help_key),
+                    Span::raw("Acknowledge"),
+                ]));
+            }
+            ConflictKind::SameFile => {
+                // Can only skip (do nothing) or create renamed copy
+                lines.push(Line::styled(
+                    "Cannot copy a file onto itself.",
+                    Style::default().fg(self.theme.muted),
+                ));
+                lines.push(Line::raw(""));
+                lines.push(Line::from(vec![
+                    Span::styled(" Enter/s ", self.theme.help_key),
+                    Span::raw("Skip  "),
+                    Span::styled(" r ", self.theme.help_key),
+                    Span::raw("Create Renamed Copy  "),
+                    Span::styled(" Esc ", self.theme.help_key),
+                    Span::raw("Cancel"),
+                ]));
+            }
+        }
+
+        Paragraph::new(lines).render(inner, buf);
+    }
+}
+
+/// Settings modal for user preferences.
+pub struct SettingsModal<'a> {
+    theme: &'a Theme,
+    state: &'a SettingsState,
+}
+
+impl<'a> SettingsModal<'a> {
+    /// Create a new settings modal.
+    pub fn new(theme: &'a Theme, state: &'a SettingsState) -> Self {
+        Self { theme, state }
+    }
+
+    fn render_toggle(&self, label: &str, value: bool, selected: bool) -> Line<'static> {
+        let checkbox = if value { "[x]" } else { "[ ]" };
+        let style = if selected {
+            Style::default()
+                .fg(self.theme.info)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+        };
+
+        Line::from(vec![
+            Span::styled(if selected { " > " } else { "   " }, style),
+            Span::styled(checkbox.to_string(), style),
+            Span::styled(format!(" {}", label), style),
+        ])
+    }
+
+    fn render_choice(&self, label: &str, value: &str, selected: bool) -> Line<'static> {
+        let style = if selected {
+            Style::default()
+                .fg(self.theme.info)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+        };
+
+        Line::from(vec![
+            Span::styled(if selected { " > " } else { "   " }, style),
+            Span::styled(format!("{}: ", label), style),
+            Span::styled(
+                value.to_string(),
+                Style::default()
+                    .fg(self.theme.info)
+                    .add_modifier(if selected {
+                        Modifier::BOLD
+                    } else {
+                        Modifier::empty()
+                    }),
+            ),
+        ])
+    }
+}
+
+impl Widget for SettingsModal<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let popup_width = 50.min(area.width.saturating_sub(4));
+        let popup_height = 33.min(area.height.saturating_sub(4));
+
+        let popup_x = (area.width.saturating_sub(popup_width)) / 2 + area.x;
+        let popup_y = (area.height.saturating_sub(popup_height)) / 2 + area.y;
+
+        let popup_area = Rect::new(popup_x, popup_y, popup_width, popup_height);
+
+        Clear.render(popup_area, buf);
+
+        let title = if self.state.dirty {
+            " Settings * "
+        } else {
+            " Settings "
+        };
+
+        let block = Block::default()
+            .title(title)
+            .title_style(
+                Style::default()
+                    .fg(self.theme.info)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .borders(Borders::ALL)
+            .border_style(self.theme.border);
+
+        let inner = block.inner(popup_area);
+        block.render(popup_area, buf);
+
+        let mut lines = vec![Line::styled(
+            " Startup",
+            Style::default().add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+        )];
+        lines.push(Line::raw(""));
+
+        // Scan on startup
+        lines.push(self.render_toggle(
+            "Scan on startup",
+            self.state.settings.scan_on_startup,
+            self.state.selected == 0,
+        ));
+
+        lines.push(Line::raw(""));
+        lines.push(Line::styled(
+            " Display",
+            Style::default().add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+        ));
+        lines.push(Line::raw(""));
+
+        // Show hidden files
+        lines.push(self.render_toggle(
+            "Show hidden files",
+            self.state.settings.show_hidden,
+            self.state.selected == 1,
+        ));
+
+        // Default layout
+        lines.push(self.render_choice(
+            "Default layout",
+            &self.state.settings.default_layout,
+            self.state.selected == 2,
+        ));
+
+        lines.push(Line::raw(""));
+        lines.push(Line::styled(
+            " File operations",
+            Style::default().add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+        ));
+        lines.push(Line::raw(""));
+
+        lines.push(self.render_toggle(
+            "Preserve metadata",
+            self.state.settings.preserve_metadata,
+            self.state.selected == 3,
+        ));
+        lines.push(self.render_toggle(
+            "Verify copies (BLAKE3)",
+            self.state.settings.verify_copies,
+            self.state.selected == 4,
+        ));
+        lines.push(self.render_choice(
+            "Concurrent jobs",
+            &self.state.settings.job_concurrency.to_string(),
+            self.state.selected == 5,
+        ));
+
+        lines.push(Line::raw(""));
+        let profile_file = self
+            .state
+            .profile
+            .save_path()
+            .map(|p| match &self.state.profile.name {
+                Some(name) => format!("{} [profiles.{}]", p.display(), name),
+                None => p.display().to_string(),
+            })
+            .unwrap_or_default();
+        lines.push(Line::from(vec![
+            Span::styled(
+                " Scan profile",
+                Style::default().add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+            ),
+            Span::styled(
+                format!(
+                    " {}",
+                    truncate_name(&profile_file, inner.width as usize / 2)
+                ),
+                self.theme.help_desc_style(),
+            ),
+        ]));
+        lines.push(Line::raw(""));
+
+        let scan = &self.state.profile.scan;
+        let start = SettingsState::PROFILE_START;
+        for (offset, (label, value)) in [
+            ("Follow symlinks", scan.follow_symlinks),
+            ("Cross filesystems", scan.cross_filesystems),
+            ("Apparent size", scan.apparent_size),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            lines.push(self.render_toggle(label, value, self.state.selected == start + offset));
+        }
+        for (index, label) in [
+            (start + 3, "Ignore files"),
+            (start + 4, "Max depth"),
+            (start + 5, "Threads"),
+            (start + 6, "Duplicate min size"),
+            (start + 7, "Stale after"),
+            (start + 8, "Age by"),
+        ] {
+            lines.push(self.render_choice(
+                label,
+                &self.state.profile_value(index),
+                self.state.selected == index,
+            ));
+        }
+
+        lines.push(Line::raw(""));
+        lines.push(Line::raw(""));
+
+        // Help line
+        lines.push(Line::from(vec![
+            Span::styled(" j/k ", self.theme.help_key_style()),
+            Span::styled("Nav ", self.theme.help_desc_style()),
+            Span::styled(" Space/Enter ", self.theme.help_key_style()),
+            Span::styled("Toggle ", self.theme.help_desc_style()),
+            Span::styled(" s ", self.theme.help_key_style()),
+            Span::styled("Save ", self.theme.help_desc_style()),
+            Span::styled(" Esc ", self.theme.help_key_style()),
+            Span::styled("Close", self.theme.help_desc_style()),
+        ]));
+
+        Paragraph::new(lines).render(inner, buf);
+    }
+}
+
+/// Bookmark list modal for viewing and selecting bookmarks.
+pub struct BookmarkListModal<'a> {
+    theme: &'a Theme,
+    bookmarks: &'a Bookmarks,
+    state: &'a BookmarkListState,
+}
+
+impl<'a> BookmarkListModal<'a> {
+    /// Create a new bookmark list modal.
+    pub fn new(theme: &'a Theme, bookmarks: &'a Bookmarks, state: &'a BookmarkListState) -> Self {
+        Self {
+            theme,
+            bookmarks,
+            state,
+        }
+    }
+}
+
+impl Widget for BookmarkListModal<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let entries = self.bookmarks.sorted_entries();
+
+        // Calculate popup dimensions
+        let popup_width = 60.min(area.width.saturating_sub(4));
+        let popup_height = ((entries.len() + 6) as u16)
+            .min(area.height.saturating_sub(4))
+            .max(8);
+
+        let popup_x = (area.width.saturating_sub(popup_width)) / 2 + area.x;
+        let popup_y = (area.height.saturating_sub(popup_height)) / 2 + area.y;
+
+        let popup_area = Rect::new(popup_x, popup_y, popup_width, popup_height);
+
+        Clear.render(popup_area, buf);
+
+        let block = Block::default()
+            .title(" Bookmarks ")
+            .title_style(
+                Style::default()
+                    .fg(self.theme.info)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .borders(Borders::ALL)
+            .border_style(self.theme.border);
+
+        let inner = block.inner(popup_area);
+        block.render(popup_area, buf);
+
+        let mut lines = vec![];
+
+        if entries.is_empty() {
+            lines.push(Line::styled(
+                "  No bookmarks set",
+                Style::default().fg(self.theme.muted),
+            ));
+            lines.push(Line::raw(""));
+            lines.push(Line::styled(
+                "  Press m<key> to set a bookmark",
+                Style::default().fg(self.theme.muted),
+            ));
+        } else {
+            let max_visible = (inner.height as usize).saturating_sub(3);
+            let max_path_len = (inner.width as usize).saturating_sub(8);
+
+            for (i, (key, path)) in entries.iter().enumerate().take(max_visible) {
+                let is_selected = i == self.state.selected;
+
+                // Truncate path from the left if too long
+                let path_str = path.display().to_string();
+                let display_path = if path_str.len() > max_path_len {
+                    format!(
+                        "...{}",
+                        &path_str[path_str.len().saturating_sub(max_path_len - 3)..]
+                    )
+                } else {
+                    path_str
+                };
+
+                let style = if is_selected {
+                    Style::default()
+                        .fg(self.theme.info)
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default()
+                };
+
+                let prefix = if is_selected { " > " } else { "   " };
+
+                lines.push(Line::from(vec![
+                    Span::styled(prefix, style),
+                    Span::styled(
+                        format!("[{}]", key),
+                        Style::default()
+                            .fg(self.theme.info)
+                            .add_modifier(if is_selected {
+                                Modifier::BOLD
+                            } else {
+                                Modifier::empty()
+                            }),
+                    ),
+                    Span::styled(format!(" {}", display_path), style),
+                ]));
+            }
+
+            if entries.len() > max_visible {
+                lines.push(Line::styled(
+                    format!("  ... and {} more", entries.len() - max_visible),
+                    Style::default().fg(self.theme.muted),
+                ));
+            }
+        }
+
+        lines.push(Line::raw(""));
+        lines.push(Line::from(vec![
+            Span::styled(" j/k ", self.theme.help_key_style()),
+            Span::styled("Nav ", self.theme.help_desc_style()),
+            Span::styled(" Enter ", self.theme.help_key_style()),
+            Span::styled("Jump ", self.theme.help_desc_style()),
+            Span::styled(" d ", self.theme.help_key_style()),
+            Span::styled("Delete ", self.theme.help_desc_style()),
+            Span::styled(" Esc ", self.theme.help_key_style()),
+            Span::styled("Close", self.theme.help_desc_style()),
+        ]));
+
+        Paragraph::new(lines).render(inner, buf);
+    }
+}
+
+/// Undo history modal listing journal entries, newest first, with undone
+/// (redoable) entries above them.
+pub struct HistoryModal<'a> {
+    theme: &'a Theme,
+    state: &'a HistoryState,
+}
+
+impl<'a> HistoryModal<'a> {
+    /// Create a new history modal.
+    pub fn new(theme: &'a Theme, state: &'a HistoryState) -> Self {
+        Self { theme, state }
+    }
+}
+
+impl Widget for HistoryModal<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let rows = &self.state.rows;
+
+        let popup_width = 76.min(area.width.saturating_sub(4));
+        let popup_height = ((rows.len() + 7) as u16)
+            .min(area.height.saturating_sub(4))
+            .max(8);
+
+        let popup_x = (area.width.saturating_sub(popup_width)) / 2 + area.x;
+        let popup_y = (area.height.saturating_sub(popup_height)) / 2 + area.y;
+
+        let popup_area = Rect::new(popup_x, popup_y, popup_width, popup_height);
+
+        Clear.render(popup_area, buf);
+
+        let block = Block::default()
+            .title(" Undo History ")
+            .title_style(
+                Style::default()
+                    .fg(self.theme.info)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .borders(Borders::ALL)
+            .border_style(self.theme.border);
+
+        let inner = block.inner(popup_area);
+        block.render(popup_area, buf);
+
+        let mut lines = vec![];
+
+        if rows.is_empty() {
+            lines.push(Line::styled(
+                "  No operations recorded",
+                Style::default().fg(self.theme.muted),
+            ));
+        } else {
+            // Reserve space for the status line and key hints.
+            let max_visible = (inner.height as usize).saturating_sub(4).max(1);
+            let offset = self.state.selected.saturating_sub(max_visible - 1);
+
+            for (i, row) in rows.iter().enumerate().skip(offset).take(max_visible) {
+                let is_selected = i == self.state.selected;
+                let mut style = if is_selected {
+                    Style::default()
+                        .fg(self.theme.info)
+                        .add_modifier(Modifier::BOLD)
+                } else if row.status.is_err() {
+                    Style::default().fg(self.theme.muted)
+                } else {
+                    Style::default()
+                };
+                if row.redo {
+                    style = style.add_modifier(Modifier::ITALIC);
+                }
+
+                let prefix = if is_selected { " > " } else { "   " };
+                let marker = if row.status.is_ok() {
+                    "\u{2713}"
+                } else {
+                    "\u{2717}"
+                };
+
+                lines.push(Line::from(vec![
+                    Span::styled(prefix, style),
+                    Span::styled(
+                        format!("{} ", marker),
+                        Style::default().fg(if row.status.is_ok() {
+                            self.theme.success
+                        } else {
+                            self.theme.warning
+                        }),
+                    ),
+                    Span::styled(
+                        format!("{:>9} ", format_relative_time(row.timestamp)),
+                        Style::default().fg(self.theme.muted),
+                    ),
+                    Span::styled(if row.redo { "(undone) " } else { "" }, style),
+                    Span::styled(row.description.clone(), style),
+                ]));
+            }
+
+            lines.push(Line::raw(""));
+            if let Some(row) = self.state.selected_row()
+                && let Err(reason) = &row.status
+            {
+                let action = if row.redo { "redo" } else { "undo" };
+                lines.push(Line::styled(
+                    format!("  Cannot {}: {}", action, reason),
+                    Style::default().fg(self.theme.warning),
+                ));
+            } else {
+                lines.push(Line::raw(""));
+            }
+        }
+
+        lines.push(Line::from(vec![
+            Span::styled(" j/k ", self.theme.help_key_style()),
+            Span::styled("Nav ", self.theme.help_desc_style()),
+            Span::styled(" u/Enter ", self.theme.help_key_style()),
+            Span::styled("Undo/Redo ", self.theme.help_desc_style()),
+            Span::styled(" Esc ", self.theme.help_key_style()),
+            Span::styled("Close", self.theme.help_desc_style()),
+        ]));
 
-        Line::from(vec![
-            Span::styled(if selected { " > " } else { "   " }, style),
-            Span::styled(checkbox.to_string(), style),
-            Span::styled(format!(" {}", label), style),
-        ])
+        Paragraph::new(lines).render(inner, buf);
     }
+}
 
-    fn render_choice(&self, label: &str, value: &str, selected: bool) -> Line<'static> {
-        let style = if selected {
-            Style::default()
-                .fg(self.theme.info)
-                .add_modifier(Modifier::BOLD)
-        } else {
-            Style::default()
-        };
+/// Confirmation modal showing the dry-run plan of a paste.
+pub struct PlanModal<'a> {
+    theme: &'a Theme,
+    review: &'a PlanReviewState,
+}
 
-        Line::from(vec![
-            Span::styled(if selected { " > " } else { "   " }, style),
-            Span::styled(format!("{}: ", label), style),
-            Span::styled(
-                value.to_string(),
-                Style::default()
-                    .fg(self.theme.info)
-                    .add_modifier(if selected {
-                        Modifier::BOLD
-                    } else {
-                        Modifier::empty()
-                    }),
-            ),
-        ])
+impl<'a> PlanModal<'a> {
+    /// Create a new plan modal.
+    pub fn new(theme: &'a Theme, review: &'a PlanReviewState) -> Self {
+        Self { theme, review }
     }
 }
 
-impl Widget for SettingsModal<'_> {
+impl Widget for PlanModal<'_> {
     fn render(self, area: Rect, buf: &mut Buffer) {
-        let popup_width = 50.min(area.width.saturating_sub(4));
-        let popup_height = 14.min(area.height.saturating_sub(4));
+        let popup_width = 80.min(area.width.saturating_sub(4));
+        let popup_height = 24.min(area.height.saturating_sub(4));
 
         let popup_x = (area.width.saturating_sub(popup_width)) / 2 + area.x;
         let popup_y = (area.height.saturating_sub(popup_height)) / 2 + area.y;
@@ -837,14 +1854,8 @@ impl Widget for SettingsModal<'_> {
 
         Clear.render(popup_area, buf);
 
-        let title = if self.state.dirty {
-            " Settings * "
-        } else {
-            " Settings "
-        };
-
         let block = Block::default()
-            .title(title)
+            .title(" Operation Plan ")
             .title_style(
                 Style::default()
                     .fg(self.theme.info)
@@ -856,86 +1867,158 @@ impl Widget for SettingsModal<'_> {
         let inner = block.inner(popup_area);
         block.render(popup_area, buf);
 
-        let mut lines = vec![Line::styled(
-            " Startup",
-            Style::default().add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
-        )];
-        lines.push(Line::raw(""));
+        let Some(plan) = &self.review.plan else {
+            Paragraph::new(vec![
+                Line::styled("  Planning...", Style::default().fg(self.theme.muted)),
+                Line::raw(""),
+                Line::from(vec![
+                    Span::styled(" Esc ", self.theme.help_key_style()),
+                    Span::styled("Cancel", self.theme.help_desc_style()),
+                ]),
+            ])
+            .render(inner, buf);
+            return;
+        };
 
-        // Scan on startup
-        lines.push(self.render_toggle(
-            "Scan on startup",
-            self.state.settings.scan_on_startup,
-            self.state.selected == 0,
-        ));
+        let mut lines = vec![];
 
-        lines.push(Line::raw(""));
+        let summary = match plan.operation_type {
+            OperationType::Move => format!(
+                "Move: {} renames, {} cross-device moves ({})",
+                plan.count(PlanAction::Rename),
+                plan.count(PlanAction::CrossDeviceMove),
+                format_size(plan.total_bytes)
+            ),
+            _ => format!(
+                "Copy: {} files, {} directories, {} symlinks ({})",
+                plan.count(PlanAction::CreateFile) + plan.count(PlanAction::OverwriteFile),
+                plan.count(PlanAction::CreateDirectory),
+                plan.count(PlanAction::CreateSymlink),
+                format_size(plan.total_bytes)
+            ),
+        };
         lines.push(Line::styled(
-            " Display",
-            Style::default().add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+            summary,
+            Style::default().add_modifier(Modifier::BOLD),
         ));
-        lines.push(Line::raw(""));
 
-        // Show hidden files
-        lines.push(self.render_toggle(
-            "Show hidden files",
-            self.state.settings.show_hidden,
-            self.state.selected == 1,
-        ));
+        let replaced =
+            plan.count(PlanAction::OverwriteFile) + plan.count(PlanAction::RemoveExisting);
+        if replaced > 0 {
+            lines.push(Line::styled(
+                format!("{} existing items will be replaced", replaced),
+                Style::default().fg(self.theme.warning),
+            ));
+        }
 
-        // Default layout
-        lines.push(self.render_choice(
-            "Default layout",
-            &self.state.settings.default_layout,
-            self.state.selected == 2,
-        ));
+        if let Some(space) = &plan.space {
+            let style = if space.sufficient() {
+                Style::default().fg(self.theme.muted)
+            } else {
+                Style::default()
+                    .fg(self.theme.error)
+                    .add_modifier(Modifier::BOLD)
+            };
+            lines.push(Line::styled(
+                format!(
+                    "Destination free space: {} (needs {}){}",
+                    format_size(space.available),
+                    format_size(space.required),
+                    if space.sufficient() {
+                        ""
+                    } else {
+                        " - NOT ENOUGH SPACE"
+                    }
+                ),
+                style,
+            ));
+        }
 
-        lines.push(Line::raw(""));
+        for conflict in &plan.conflicts {
+            lines.push(Line::styled(
+                format!("! {}: {}", conflict.kind, conflict.destination.display()),
+                Style::default().fg(self.theme.warning),
+            ));
+        }
+        for error in &plan.errors {
+            lines.push(Line::styled(
+                format!("! {}", error),
+                Style::default().fg(self.theme.error),
+            ));
+        }
         lines.push(Line::raw(""));
 
-        // Help line
+        // Step list fills whatever is left above the key hints.
+        let max_steps = (inner.height as usize)
+            .saturating_sub(lines.len() + 2)
+            .max(1);
+        let max_path_len = (inner.width as usize).saturating_sub(30);
+        for step in plan.steps.iter().skip(self.review.scroll).take(max_steps) {
+            let path = step.path.display().to_string();
+            let path = if path.len() > max_path_len {
+                let start = path.len().saturating_sub(max_path_len.saturating_sub(3));
+                let start = path.ceil_char_boundary(start);
+                format!("...{}", &path[start..])
+            } else {
+                path
+            };
+            let action_style = match step.action {
+                PlanAction::OverwriteFile | PlanAction::RemoveExisting => {
+                    Style::default().fg(self.theme.warning)
+                }
+                PlanAction::CrossDeviceMove => Style::default().fg(self.theme.info),
+                _ => Style::default().fg(self.theme.success),
+            };
+            lines.push(Line::from(vec![
+                Span::styled(format!(" {:>17} ", step.action.to_string()), action_style),
+                Span::raw(path),
+                Span::styled(
+                    format!(" {}", format_size(step.bytes)),
+                    Style::default().fg(self.theme.muted),
+                ),
+            ]));
+        }
+        let shown = plan.steps.len().saturating_sub(self.review.scroll);
+        if shown > max_steps {
+            lines.push(Line::styled(
+                format!("  ... and {} more steps", shown - max_steps),
+                Style::default().fg(self.theme.muted),
+            ));
+        } else {
+            lines.push(Line::raw(""));
+        }
+
         lines.push(Line::from(vec![
+            Span::styled(" y/Enter ", self.theme.help_key_style()),
+            Span::styled("Run ", self.theme.help_desc_style()),
             Span::styled(" j/k ", self.theme.help_key_style()),
-            Span::styled("Nav ", self.theme.help_desc_style()),
-            Span::styled(" Space/Enter ", self.theme.help_key_style()),
-            Span::styled("Toggle ", self.theme.help_desc_style()),
-            Span::styled(" s ", self.theme.help_key_style()),
-            Span::styled("Save ", self.theme.help_desc_style()),
+            Span::styled("Scroll ", self.theme.help_desc_style()),
             Span::styled(" Esc ", self.theme.help_key_style()),
-            Span::styled("Close", self.theme.help_desc_style()),
+            Span::styled("Cancel", self.theme.help_desc_style()),
         ]));
 
         Paragraph::new(lines).render(inner, buf);
     }
 }
 
-/// Bookmark list modal for viewing and selecting bookmarks.
-pub struct BookmarkListModal<'a> {
+/// Sync preview modal listing what is new, changed and extra between the
+/// active tab's directory and the next tab's.
+pub struct SyncModal<'a> {
     theme: &'a Theme,
-    bookmarks: &'a Bookmarks,
-    state: &'a BookmarkListState,
+    review: &'a SyncReviewState,
 }
 
-impl<'a> BookmarkListModal<'a> {
-    /// Create a new bookmark list modal.
-    pub fn new(theme: &'a Theme, bookmarks: &'a Bookmarks, state: &'a BookmarkListState) -> Self {
-        Self {
-            theme,
-            bookmarks,
-            state,
-        }
+impl<'a> SyncModal<'a> {
+    /// Create a new sync modal.
+    pub fn new(theme: &'a Theme, review: &'a SyncReviewState) -> Self {
+        Self { theme, review }
     }
 }
 
-impl Widget for BookmarkListModal<'_> {
+impl Widget for SyncModal<'_> {
     fn render(self, area: Rect, buf: &mut Buffer) {
-        let entries = self.bookmarks.sorted_entries();
-
-        // Calculate popup dimensions
-        let popup_width = 60.min(area.width.saturating_sub(4));
-        let popup_height = ((entries.len() + 6) as u16)
-            .min(area.height.saturating_sub(4))
-            .max(8);
+        let popup_width = 80.min(area.width.saturating_sub(4));
+        let popup_height = 24.min(area.height.saturating_sub(4));
 
         let popup_x = (area.width.saturating_sub(popup_width)) / 2 + area.x;
         let popup_y = (area.height.saturating_sub(popup_height)) / 2 + area.y;
@@ -944,8 +2027,9 @@ impl Widget for BookmarkListModal<'_> {
 
         Clear.render(popup_area, buf);
 
+        let options = &self.review.options;
         let block = Block::default()
-            .title(" Bookmarks ")
+            .title(format!(" Sync ({}) ", options.mode))
             .title_style(
                 Style::default()
                     .fg(self.theme.info)
@@ -957,80 +2041,158 @@ impl Widget for BookmarkListModal<'_> {
         let inner = block.inner(popup_area);
         block.render(popup_area, buf);
 
-        let mut lines = vec![];
+        let arrow = match options.mode {
+            SyncMode::Mirror => "->",
+            SyncMode::TwoWay => "<->",
+        };
+        let mut lines = vec![
+            Line::from(vec![
+                Span::styled(
+                    self.review.source.display().to_string(),
+                    Style::default().add_modifier(Modifier::BOLD),
+                ),
+                Span::raw(format!(" {} ", arrow)),
+                Span::styled(
+                    self.review.destination.display().to_string(),
+                    Style::default().add_modifier(Modifier::BOLD),
+                ),
+            ]),
+            Line::styled(
+                format!(
+                    "Compare by {}; extras are {}",
+                    if options.compare_hash {
+                        "size and BLAKE3 hash"
+                    } else {
+                        "size and mtime"
+                    },
+                    match (options.mode, options.delete_extras) {
+                        (SyncMode::TwoWay, _) => "copied back",
+                        (SyncMode::Mirror, true) => "deleted",
+                        (SyncMode::Mirror, false) => "kept",
+                    }
+                ),
+                Style::default().fg(self.theme.muted),
+            ),
+        ];
 
-        if entries.is_empty() {
+        let Some(diff) = &self.review.diff else {
+            lines.push(Line::raw(""));
             lines.push(Line::styled(
-                "  No bookmarks set",
+                "  Comparing...",
                 Style::default().fg(self.theme.muted),
             ));
             lines.push(Line::raw(""));
+            lines.push(Line::from(vec![
+                Span::styled(" Esc ", self.theme.help_key_style()),
+                Span::styled("Cancel", self.theme.help_desc_style()),
+            ]));
+            Paragraph::new(lines).render(inner, buf);
+            return;
+        };
+
+        lines.push(Line::styled(
+            format!(
+                "{} new, {} changed, {} extra, {} unchanged ({} to copy)",
+                diff.count(SyncChange::New),
+                diff.count(SyncChange::Changed),
+                diff.count(SyncChange::Extra),
+                diff.unchanged,
+                format_size(diff.bytes_to_copy(options))
+            ),
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+        if diff.conflicts() > 0 {
             lines.push(Line::styled(
-                "  Press m<key> to set a bookmark",
-                Style::default().fg(self.theme.muted),
+                format!(
+                    "{} files changed on both sides will be skipped",
+                    diff.conflicts()
+                ),
+                Style::default().fg(self.theme.warning),
             ));
-        } else {
-            let max_visible = (inner.height as usize).saturating_sub(3);
-            let max_path_len = (inner.width as usize).saturating_sub(8);
-
-            for (i, (key, path)) in entries.iter().enumerate().take(max_visible) {
-                let is_selected = i == self.state.selected;
-
-                // Truncate path from the left if too long
-                let path_str = path.display().to_string();
-                let display_path = if path_str.len() > max_path_len {
-                    format!(
-                        "...{}",
-                        &path_str[path_str.len().saturating_sub(max_path_len - 3)..]
-                    )
-                } else {
-                    path_str
-                };
-
-                let style = if is_selected {
-                    Style::default()
-                        .fg(self.theme.info)
-                        .add_modifier(Modifier::BOLD)
-                } else {
-                    Style::default()
-                };
-
-                let prefix = if is_selected { " > " } else { "   " };
+        }
+        for error in &diff.errors {
+            lines.push(Line::styled(
+                format!("! {}", error),
+                Style::default().fg(self.theme.error),
+            ));
+        }
+        lines.push(Line::raw(""));
 
-                lines.push(Line::from(vec![
-                    Span::styled(prefix, style),
-                    Span::styled(
-                        format!("[{}]", key),
-                        Style::default()
-                            .fg(self.theme.info)
-                            .add_modifier(if is_selected {
-                                Modifier::BOLD
-                            } else {
-                                Modifier::empty()
-                            }),
-                    ),
-                    Span::styled(format!(" {}", display_path), style),
-                ]));
+        let max_entries = (inner.height as usize)
+            .saturating_sub(lines.len() + 2)
+            .max(1);
+        let max_path_len = (inner.width as usize).saturating_sub(16);
+        for entry in diff
+            .entries
+            .iter()
+            .skip(self.review.scroll)
+            .take(max_entries)
+        {
+            let mut path = entry.path.display().to_string();
+            if entry.is_dir {
+                path.push('/');
             }
-
-            if entries.len() > max_visible {
-                lines.push(Line::styled(
-                    format!("  ... and {} more", entries.len() - max_visible),
+            let path = if path.len() > max_path_len {
+                let start = path.len().saturating_sub(max_path_len.saturating_sub(3));
+                let start = path.ceil_char_boundary(start);
+                format!("...{}", &path[start..])
+            } else {
+                path
+            };
+            let (marker, style) = match (entry.change, entry.direction) {
+                (SyncChange::Extra, _) if options.delete_extras => {
+                    ("-", Style::default().fg(self.theme.error))
+                }
+                (SyncChange::Extra, _) => ("=", Style::default().fg(self.theme.muted)),
+                _ if entry.conflict => ("!", Style::default().fg(self.theme.warning)),
+                (SyncChange::New, SyncDirection::ToDestination) => {
+                    ("+", Style::default().fg(self.theme.success))
+                }
+                (SyncChange::New, SyncDirection::ToSource) => {
+                    ("<", Style::default().fg(self.theme.success))
+                }
+                (SyncChange::Changed, SyncDirection::ToDestination) => {
+                    ("~", Style::default().fg(self.theme.info))
+                }
+                (SyncChange::Changed, SyncDirection::ToSource) => {
+                    ("<~", Style::default().fg(self.theme.info))
+                }
+            };
+            lines.push(Line::from(vec![
+                Span::styled(format!(" {:>2} ", marker), style),
+                Span::raw(path),
+                Span::styled(
+                    format!(" {}", format_size(entry.bytes)),
                     Style::default().fg(self.theme.muted),
-                ));
-            }
+                ),
+            ]));
+        }
+        let shown = diff.entries.len().saturating_sub(self.review.scroll);
+        if diff.entries.is_empty() {
+            lines.push(Line::styled(
+                "  Already in sync",
+                Style::default().fg(self.theme.success),
+            ));
+        } else if shown > max_entries {
+            lines.push(Line::styled(
+                format!("  ... and {} more entries", shown - max_entries),
+                Style::default().fg(self.theme.muted),
+            ));
+        } else {
+            lines.push(Line::raw(""));
         }
 
-        lines.push(Line::raw(""));
         lines.push(Line::from(vec![
-            Span::styled(" j/k ", self.theme.help_key_style()),
-            Span::styled("Nav ", self.theme.help_desc_style()),
-            Span::styled(" Enter ", self.theme.help_key_style()),
-            Span::styled("Jump ", self.theme.help_desc_style()),
+            Span::styled(" y/Enter ", self.theme.help_key_style()),
+            Span::styled("Sync ", self.theme.help_desc_style()),
+            Span::styled(" m ", self.theme.help_key_style()),
+            Span::styled("Mode ", self.theme.help_desc_style()),
+            Span::styled(" h ", self.theme.help_key_style()),
+            Span::styled("Hash ", self.theme.help_desc_style()),
             Span::styled(" d ", self.theme.help_key_style()),
-            Span::styled("Delete ", self.theme.help_desc_style()),
+            Span::styled("Delete extras ", self.theme.help_desc_style()),
             Span::styled(" Esc ", self.theme.help_key_style()),
-            Span::styled("Close", self.theme.help_desc_style()),
+            Span::styled("Cancel", self.theme.help_desc_style()),
         ]));
 
         Paragraph::new(lines).render(inner, buf);
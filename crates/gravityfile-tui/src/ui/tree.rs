@@ -118,10 +118,18 @@ pub struct VisibleNode {
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum VisibleNodeKind {
-    Directory { expanded: bool },
-    File { executable: bool },
-    Symlink { broken: bool },
+    Directory {
+        expanded: bool,
+    },
+    File {
+        executable: bool,
+    },
+    Symlink {
+        broken: bool,
+    },
     Other,
+    /// Directory on another filesystem that was not scanned.
+    MountPoint,
 }
 
 /// Tree view widget.
@@ -216,6 +224,7 @@ impl<'a> TreeView<'a> {
             },
             NodeKind::Symlink { broken, .. } => VisibleNodeKind::Symlink { broken: *broken },
             NodeKind::Other => VisibleNodeKind::Other,
+            NodeKind::MountPoint { .. } => VisibleNodeKind::MountPoint,
         };
 
         items.push(VisibleItem {
@@ -334,6 +343,7 @@ impl StatefulWidget for TreeView<'_> {
                         "▶ "
                     }
                 }
+                VisibleNodeKind::MountPoint => "▷ ",
                 _ => "  ",
             };
 
@@ -347,6 +357,7 @@ impl StatefulWidget for TreeView<'_> {
                 }
                 VisibleNodeKind::Symlink { broken: false } => self.theme.symlink,
                 VisibleNodeKind::Other => self.theme.muted.into(),
+                VisibleNodeKind::MountPoint => self.theme.directory.add_modifier(Modifier::DIM),
             };
 
             // Apply clipboard styling (cut items are dimmed, copied items are italic)
@@ -419,6 +430,8 @@ impl StatefulWidget for TreeView<'_> {
                 && item.node.size == 0
             {
                 format!("{:>10}", "...")
+            } else if item.node.kind == VisibleNodeKind::MountPoint {
+                format!("{:>10}", "mount")
             } else {
                 format!("{:>10}", format_size(item.node.size))
             };
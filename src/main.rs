@@ -2,19 +2,41 @@
 //!
 //! Usage:
 //!   grav \[PATH\]              Launch interactive TUI
-//!   grav scan \[PATH\]         Quick scan summary
+//!   grav scan \[PATH...\]      Quick scan summary (several paths form one tree)
 //!   grav duplicates \[PATH\]   Find duplicate files
 //!   grav age \[PATH\]          Analyze file ages
 //!   grav export \[PATH\]       Export scan to JSON
+//!   grav owners \[PATH\]       Disk usage per user and group
+//!   grav types \[PATH\]        Disk usage per file type and extension
+//!   grav find QUERY \[PATH\] Find files matching a query expression
+//!   grav top \[PATH\]          Largest files or leaf directories anywhere
+//!   grav clean \[PATH\]        List (or delete) regenerable build artifacts and caches
+//!   grav policy check|apply POLICY \[PATH\]
+//!                          Evaluate or apply a retention policy
+//!   grav df                  Mounted filesystems and free space
+//!   grav rename FILES...     Rename files by regex or template
+//!   grav undo                Undo the last file operation
+//!   grav redo                Redo the last undone operation
 //!   grav --help              Show help
 
 use std::path::{Path, PathBuf};
 
 use clap::{Parser, Subcommand, ValueEnum};
-use color_eyre::eyre::{Context, Result};
-
-use gravityfile_analyze::{AgeAnalyzer, AgeConfig, DuplicateConfig, DuplicateFinder, format_age};
-use gravityfile_scan::{JwalkScanner, ScanConfig};
+use color_eyre::eyre::{Context, Result, bail, eyre};
+
+use gravityfile_analyze::{
+    AgeAnalyzer, AgeBucket, CleanupFinder, DuplicateFinder, FileCategory, FileTypeAnalyzer,
+    HygieneAnalyzer, HygieneKind, OwnershipAnalyzer, PolicyReport, Profile, Query, RetentionPolicy,
+    TimeBasis, TopEntry, TopFinder, TopKind, format_age, format_duration_spec, parse_duration,
+    parse_size,
+};
+use gravityfile_ops::{
+    AuditLog, AuditRecord, BatchRenameResult, CancellationToken, CaseTransform, ConflictResolution,
+    CopyOptions, FileOperation, JobManager, LinkKind, OperationComplete, OperationPlan,
+    OperationResult, OperationType, PlanAction, RenameRule, RenameStatus, UndoLog,
+    UndoableOperation, plan_operation, preview_renames, start_batch_rename,
+};
+use gravityfile_scan::{IgnoreMode, JwalkScanner, Mount, MountTable, ScanConfig};
 
 #[derive(Parser)]
 #[command(
@@ -26,9 +48,10 @@ use gravityfile_scan::{JwalkScanner, ScanConfig};
                   subcommands for quick operations."
 )]
 struct Cli {
-    /// Path to analyze (defaults to current directory)
+    /// Paths to analyze (defaults to current directory); several paths are
+    /// browsed as one combined tree
     #[arg(default_value = ".")]
-    path: PathBuf,
+    paths: Vec<PathBuf>,
 
     /// Start scanning immediately on startup (default: show quick listing only)
     #[arg(short = 'S', long)]
@@ -42,6 +65,10 @@ struct Cli {
     #[arg(long = "print-cwd")]
     print_cwd: bool,
 
+    /// Scan profile: a [profiles.NAME] variant from .gravityfile.toml, or a profile file
+    #[arg(long, global = true)]
+    profile: Option<String>,
+
     #[command(subcommand)]
     command: Option<Command>,
 }
@@ -50,9 +77,9 @@ struct Cli {
 enum Command {
     /// Quick scan and show summary
     Scan {
-        /// Path to scan
+        /// Paths to scan; several paths are combined into one tree
         #[arg(default_value = ".")]
-        path: PathBuf,
+        paths: Vec<PathBuf>,
 
         /// Maximum depth to display
         #[arg(short, long, default_value = "3")]
@@ -65,17 +92,21 @@ enum Command {
         /// Number of top entries to show per directory
         #[arg(short = 'n', long, default_value = "10")]
         top: usize,
+
+        /// Honour .gitignore/.ignore/.gravityignore files: off, exclude or report
+        #[arg(short = 'i', long = "ignore-files")]
+        ignore_files: Option<IgnoreMode>,
     },
 
     /// Find duplicate files
     Duplicates {
-        /// Path to scan
+        /// Paths to scan; several paths are combined into one tree
         #[arg(default_value = ".")]
-        path: PathBuf,
+        paths: Vec<PathBuf>,
 
-        /// Minimum file size to consider (e.g., "1KB", "1MB")
-        #[arg(short, long, default_value = "1KB")]
-        min_size: String,
+        /// Minimum file size to consider (e.g., "1KB", "1MB"; default from profile, 1KB)
+        #[arg(short, long)]
+        min_size: Option<String>,
 
         /// Maximum number of duplicate groups to show
         #[arg(short = 'n', long, default_value = "20")]
@@ -88,29 +119,446 @@ enum Command {
 
     /// Analyze file ages
     Age {
-        /// Path to scan
+        /// Paths to scan; several paths are combined into one tree
         #[arg(default_value = ".")]
-        path: PathBuf,
+        paths: Vec<PathBuf>,
+
+        /// Show stale directories older than this (e.g., "1y", "6m", "30d"; default from profile, 1y)
+        #[arg(short, long)]
+        stale: Option<String>,
+
+        /// Timestamp to measure ages from: mtime, atime, btime or latest (default from profile, mtime)
+        #[arg(long)]
+        by: Option<TimeBasis>,
+
+        /// Age buckets as comma-separated [NAME=]AGE (e.g., "Fresh=1w,1mo,1y"; default from profile)
+        #[arg(short, long)]
+        buckets: Option<String>,
+
+        /// Output format
+        #[arg(short, long, default_value = "text")]
+        format: OutputFormat,
+    },
+
+    /// Show disk usage per user and group
+    Owners {
+        /// Paths to scan; several paths are combined into one tree
+        #[arg(default_value = ".")]
+        paths: Vec<PathBuf>,
+
+        /// Number of users and groups to show
+        #[arg(short = 'n', long, default_value = "20")]
+        top: usize,
+
+        /// Output format
+        #[arg(short, long, default_value = "text")]
+        format: OutputFormat,
+    },
+
+    /// Show disk usage per file type and extension
+    Types {
+        /// Paths to scan; several paths are combined into one tree
+        #[arg(default_value = ".")]
+        paths: Vec<PathBuf>,
+
+        /// Only list extensions of this category (e.g. video, source, logs)
+        #[arg(short, long)]
+        category: Option<FileCategory>,
+
+        /// Number of extensions to show
+        #[arg(short = 'n', long, default_value = "20")]
+        top: usize,
+
+        /// Classify by extension only, without reading file contents
+        #[arg(long)]
+        no_sniff: bool,
+
+        /// Output format
+        #[arg(short, long, default_value = "text")]
+        format: OutputFormat,
+    },
+
+    /// Find files and directories matching a query
+    ///
+    /// Example: grav find "size > 1G and mtime < 6mo and ext in (iso, img)"
+    ///
+    /// Durations stand for that long ago, so `mtime < 6mo` finds files not
+    /// modified for six months and `mtime > 1w` files changed this week.
+    Find {
+        /// Query over name, path, ext, size, mtime, atime, created, kind,
+        /// git, owner and group
+        query: String,
+
+        /// Paths to scan; several paths are combined into one tree
+        #[arg(default_value = ".")]
+        paths: Vec<PathBuf>,
+
+        /// Output format
+        #[arg(short, long, default_value = "paths")]
+        format: FindFormat,
+    },
+
+    /// List the largest files or leaf directories anywhere under the paths
+    Top {
+        /// Paths to scan; several paths are combined into one tree
+        #[arg(default_value = ".")]
+        paths: Vec<PathBuf>,
+
+        /// Number of entries to show
+        #[arg(short = 'n', long, default_value = "50")]
+        count: usize,
+
+        /// Rank files (the default unless --dirs is given)
+        #[arg(long)]
+        files: bool,
+
+        /// Rank leaf directories, which have no subdirectories
+        #[arg(long)]
+        dirs: bool,
+
+        /// Only rank files with these extensions (e.g. iso,img)
+        #[arg(short, long, value_delimiter = ',')]
+        ext: Vec<String>,
+
+        /// Only rank entries not modified for this long (e.g. 6mo, 1y)
+        #[arg(long, value_parser = parse_duration)]
+        older_than: Option<std::time::Duration>,
+
+        /// Only rank entries modified within this long (e.g. 30d)
+        #[arg(long, value_parser = parse_duration)]
+        newer_than: Option<std::time::Duration>,
+
+        /// Only rank entries whose relative path matches this glob (repeatable)
+        #[arg(short, long)]
+        glob: Vec<String>,
+
+        /// Output format
+        #[arg(short, long, default_value = "text")]
+        format: TopFormat,
+    },
+
+    /// List regenerable build artifacts and caches, and delete them with --delete
+    Clean {
+        /// Paths to scan; several paths are combined into one tree
+        #[arg(default_value = ".")]
+        paths: Vec<PathBuf>,
+
+        /// Delete the candidates instead of only listing them
+        #[arg(long, visible_alias = "apply")]
+        delete: bool,
+
+        /// Only list the candidates (the default; kept for scripts)
+        #[arg(short = 'n', long, conflicts_with = "delete")]
+        dry_run: bool,
+
+        /// Only use these rules (e.g. cargo, node-modules)
+        #[arg(short, long)]
+        rule: Vec<String>,
+
+        /// Skip candidates smaller than this (e.g. 10M)
+        #[arg(long, value_parser = parse_size)]
+        min_size: Option<u64>,
+
+        /// Delete permanently instead of moving to the trash
+        #[arg(long)]
+        permanent: bool,
+
+        /// Output format
+        #[arg(short, long, default_value = "text")]
+        format: OutputFormat,
+    },
 
-        /// Show stale directories older than this (e.g., "1y", "6m", "30d")
-        #[arg(short, long, default_value = "1y")]
-        stale: String,
+    /// Find empty directories, zero-byte files, broken symlinks and stray hard links
+    Hygiene {
+        /// Paths to scan; several paths are combined into one tree
+        #[arg(default_value = ".")]
+        paths: Vec<PathBuf>,
+
+        /// Only report these kinds: empty, zero, broken, hardlinks (e.g. empty,zero)
+        #[arg(short, long, value_delimiter = ',')]
+        only: Vec<HygieneKind>,
+
+        /// Print the plan for deleting what was found instead of the report
+        #[arg(long, conflicts_with = "delete")]
+        plan: bool,
+
+        /// Delete empty directories, zero-byte files and broken symlinks
+        #[arg(long)]
+        delete: bool,
+
+        /// Delete permanently instead of moving to the trash
+        #[arg(long)]
+        permanent: bool,
 
         /// Output format
         #[arg(short, long, default_value = "text")]
         format: OutputFormat,
     },
 
+    /// Evaluate or apply a retention policy file
+    Policy {
+        #[command(subcommand)]
+        action: PolicyAction,
+
+        /// Output format
+        #[arg(short, long, default_value = "text", global = true)]
+        format: OutputFormat,
+    },
+
     /// Export scan results to JSON
     Export {
-        /// Path to scan
+        /// Paths to scan; several paths are combined into one tree
         #[arg(default_value = ".")]
-        path: PathBuf,
+        paths: Vec<PathBuf>,
 
         /// Output file (defaults to stdout)
         #[arg(short, long)]
         output: Option<PathBuf>,
     },
+
+    /// Show mounted filesystems with free space and inode usage
+    Df {
+        /// Include pseudo filesystems (proc, sysfs, cgroup, ...)
+        #[arg(short, long)]
+        all: bool,
+
+        /// Output format
+        #[arg(short, long, default_value = "text")]
+        format: OutputFormat,
+    },
+
+    /// Undo file operations recorded by the TUI
+    Undo {
+        /// List the journal instead of undoing anything
+        #[arg(short, long)]
+        list: bool,
+
+        /// Undo the entry with this ID instead of the most recent one
+        #[arg(long, conflicts_with = "list")]
+        id: Option<u64>,
+
+        /// Output format
+        #[arg(short, long, default_value = "text")]
+        format: OutputFormat,
+    },
+
+    /// Redo file operations reverted by undo
+    Redo {
+        /// Redo the entry with this ID instead of the most recently undone one
+        #[arg(long)]
+        id: Option<u64>,
+
+        /// Output format
+        #[arg(short, long, default_value = "text")]
+        format: OutputFormat,
+    },
+
+    /// Rename files by regex or template, with a preview
+    ///
+    /// Tokens: {name} {stem} {ext} {parent} {size} {mtime:%Y-%m-%d} {n:3}
+    Rename {
+        /// Files to rename; {n} counts them in this order
+        #[arg(required = true)]
+        paths: Vec<PathBuf>,
+
+        /// Regex to replace in each name (omit to use --to as a whole-name template)
+        #[arg(short = 'e', long)]
+        find: Option<String>,
+
+        /// Replacement with $1 / ${name} captures, or the name template
+        #[arg(short, long, default_value = "")]
+        to: String,
+
+        /// Case of the new names: keep, lower, upper or title
+        #[arg(short, long, default_value = "keep")]
+        case: CaseTransform,
+
+        /// First value of {n}
+        #[arg(long, default_value_t = 1)]
+        start: u64,
+
+        /// Increment of {n}
+        #[arg(long, default_value_t = 1)]
+        step: u64,
+
+        /// Only show the new names
+        #[arg(short = 'n', long)]
+        dry_run: bool,
+
+        /// Output format
+        #[arg(short, long, default_value = "text")]
+        format: OutputFormat,
+    },
+
+    /// Show what a file operation would do, without changing anything
+    Plan {
+        #[command(subcommand)]
+        operation: PlanOperation,
+
+        /// How destination conflicts would be resolved (default: report and skip)
+        #[arg(long, global = true)]
+        on_conflict: Option<ConflictChoice>,
+
+        /// Output format
+        #[arg(short, long, default_value = "text", global = true)]
+        format: OutputFormat,
+    },
+}
+
+#[derive(Subcommand)]
+enum PolicyAction {
+    /// List the files a policy would delete
+    Check {
+        /// Policy file (TOML)
+        policy: PathBuf,
+
+        /// Paths to scan; several paths are combined into one tree
+        #[arg(default_value = ".")]
+        paths: Vec<PathBuf>,
+    },
+
+    /// Delete the files a policy selects and record them in the audit log
+    Apply {
+        /// Policy file (TOML)
+        policy: PathBuf,
+
+        /// Paths to scan; several paths are combined into one tree
+        #[arg(default_value = ".")]
+        paths: Vec<PathBuf>,
+
+        /// Delete permanently instead of moving to the trash
+        #[arg(long)]
+        permanent: bool,
+
+        /// Audit log to append to (default: in the data directory)
+        #[arg(long)]
+        audit_log: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+enum PlanOperation {
+    /// Copy sources into a destination directory
+    Copy {
+        /// Files or directories to copy
+        #[arg(required = true)]
+        sources: Vec<PathBuf>,
+
+        /// Destination directory
+        destination: PathBuf,
+    },
+
+    /// Move sources into a destination directory
+    Move {
+        /// Files or directories to move
+        #[arg(required = true)]
+        sources: Vec<PathBuf>,
+
+        /// Destination directory
+        destination: PathBuf,
+    },
+
+    /// Create symlinks to sources in a destination directory
+    Link {
+        /// Files or directories to link to
+        #[arg(required = true)]
+        sources: Vec<PathBuf>,
+
+        /// Destination directory
+        destination: PathBuf,
+
+        /// Point symlinks at paths relative to the destination
+        #[arg(long, conflicts_with = "hard")]
+        relative: bool,
+
+        /// Create hardlinks instead of symlinks
+        #[arg(long)]
+        hard: bool,
+    },
+
+    /// Delete files or directories
+    Delete {
+        /// Files or directories to delete
+        #[arg(required = true)]
+        targets: Vec<PathBuf>,
+
+        /// Delete permanently instead of moving to the trash
+        #[arg(long)]
+        permanent: bool,
+    },
+
+    /// Overwrite files before deleting them
+    Shred {
+        /// Files or directories to shred
+        #[arg(required = true)]
+        targets: Vec<PathBuf>,
+
+        /// Number of random overwrite passes
+        #[arg(long, default_value_t = gravityfile_ops::DEFAULT_SHRED_PASSES)]
+        passes: u32,
+    },
+
+    /// Rename a file or directory
+    Rename {
+        /// Item to rename
+        source: PathBuf,
+
+        /// New file name
+        new_name: String,
+    },
+
+    /// Create an empty file
+    Touch {
+        /// Path of the new file
+        path: PathBuf,
+    },
+
+    /// Create a directory
+    Mkdir {
+        /// Path of the new directory
+        path: PathBuf,
+    },
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum ConflictChoice {
+    Skip,
+    Overwrite,
+    Rename,
+    Abort,
+}
+
+impl From<ConflictChoice> for ConflictResolution {
+    fn from(choice: ConflictChoice) -> Self {
+        match choice {
+            ConflictChoice::Skip => ConflictResolution::SkipAll,
+            ConflictChoice::Overwrite => ConflictResolution::OverwriteAll,
+            ConflictChoice::Rename => ConflictResolution::AutoRename,
+            ConflictChoice::Abort => ConflictResolution::Abort,
+        }
+    }
+}
+
+/// Output of `grav find`.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum FindFormat {
+    /// One path per line
+    Paths,
+    /// A JSON array
+    Json,
+    /// One JSON object per line
+    Ndjson,
+}
+
+/// Output of `grav top`.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum TopFormat {
+    /// A ranked table
+    Text,
+    /// A JSON array
+    Json,
+    /// Comma-separated values with a header row
+    Csv,
 }
 
 #[derive(Debug, Clone, Copy, ValueEnum, Default)]
@@ -124,41 +572,183 @@ fn main() -> Result<()> {
     color_eyre::install()?;
 
     let cli = Cli::parse();
+    let profile = cli.profile.as_deref();
 
     match cli.command {
         Some(Command::Scan {
-            path,
+            paths,
             depth,
             all,
             top,
+            ignore_files,
         }) => {
-            run_scan(&path, if all { None } else { Some(depth) }, top)?;
+            run_scan(
+                &paths,
+                if all { None } else { Some(depth) },
+                top,
+                ignore_files,
+                profile,
+            )?;
         }
         Some(Command::Duplicates {
-            path,
+            paths,
             min_size,
             top,
             format,
         }) => {
-            run_duplicates(&path, &min_size, top, format)?;
+            run_duplicates(&paths, min_size.as_deref(), top, format, profile)?;
         }
         Some(Command::Age {
-            path,
+            paths,
             stale,
+            by,
+            buckets,
+            format,
+        }) => {
+            run_age(
+                &paths,
+                stale.as_deref(),
+                by,
+                buckets.as_deref(),
+                format,
+                profile,
+            )?;
+        }
+        Some(Command::Owners { paths, top, format }) => {
+            run_owners(&paths, top, format, profile)?;
+        }
+        Some(Command::Types {
+            paths,
+            category,
+            top,
+            no_sniff,
+            format,
+        }) => {
+            run_types(&paths, category, top, !no_sniff, format, profile)?;
+        }
+        Some(Command::Find {
+            query,
+            paths,
+            format,
+        }) => {
+            run_find(&query, &paths, format, profile)?;
+        }
+        Some(Command::Top {
+            paths,
+            count,
+            files,
+            dirs,
+            ext,
+            older_than,
+            newer_than,
+            glob,
+            format,
+        }) => {
+            let kind = match (files, dirs) {
+                (true, true) => TopKind::Both,
+                (false, true) => TopKind::Dirs,
+                _ => TopKind::Files,
+            };
+            let mut finder = TopFinder::new(count)
+                .kind(kind)
+                .extensions(&ext)
+                .globs(&glob)
+                .map_err(|e| eyre!("Invalid glob: {}", e))?;
+            if let Some(age) = older_than {
+                finder = finder.older_than(age);
+            }
+            if let Some(age) = newer_than {
+                finder = finder.newer_than(age);
+            }
+            run_top(&paths, &finder, format, profile)?;
+        }
+        Some(Command::Clean {
+            paths,
+            delete,
+            dry_run: _,
+            rule,
+            min_size,
+            permanent,
             format,
         }) => {
-            run_age(&path, &stale, format)?;
+            run_clean(
+                &paths,
+                delete,
+                &rule,
+                min_size.unwrap_or(0),
+                !permanent,
+                format,
+                profile,
+            )?;
         }
-        Some(Command::Export { path, output }) => {
-            run_export(&path, output)?;
+        Some(Command::Hygiene {
+            paths,
+            only,
+            plan,
+            delete,
+            permanent,
+            format,
+        }) => {
+            run_hygiene(&paths, &only, plan, delete, !permanent, format, profile)?;
+        }
+        Some(Command::Policy { action, format }) => {
+            run_policy(action, format, profile)?;
+        }
+        Some(Command::Export { paths, output }) => {
+            run_export(&paths, output, profile)?;
+        }
+        Some(Command::Df { all, format }) => {
+            run_df(all, format)?;
+        }
+        Some(Command::Undo { list, id, format }) => {
+            run_undo(list, id, false, format)?;
+        }
+        Some(Command::Redo { id, format }) => {
+            run_undo(false, id, true, format)?;
+        }
+        Some(Command::Rename {
+            paths,
+            find,
+            to,
+            case,
+            start,
+            step,
+            dry_run,
+            format,
+        }) => {
+            let rule = RenameRule {
+                find: find.unwrap_or_default(),
+                replace: to,
+                case,
+                counter_start: start,
+                counter_step: step,
+            };
+            run_rename(paths, &rule, dry_run, format)?;
+        }
+        Some(Command::Plan {
+            operation,
+            on_conflict,
+            format,
+        }) => {
+            run_plan(operation, on_conflict.map(Into::into), format)?;
         }
         None => {
             // Launch TUI
-            let path = cli.path.canonicalize().context("Invalid path")?;
+            let mut roots = cli
+                .paths
+                .iter()
+                .map(|p| {
+                    p.canonicalize()
+                        .with_context(|| format!("Invalid path: {}", p.display()))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            let path = roots.remove(0);
             let config = gravityfile_tui::TuiConfig::new()
                 .with_scan_on_startup(cli.scan)
                 .with_cwd_file(cli.cwd_file)
-                .with_print_cwd(cli.print_cwd);
+                .with_print_cwd(cli.print_cwd)
+                .with_profile(cli.profile)
+                .with_extra_roots(roots);
             gravityfile_tui::run_with_config(path, config)?;
         }
     }
@@ -166,15 +756,45 @@ fn main() -> Result<()> {
     Ok(())
 }
 
-/// Run a quick scan and display summary.
-fn run_scan(path: &Path, max_depth: Option<u32>, top_n: usize) -> Result<()> {
-    let path = path.canonicalize().context("Invalid path")?;
+/// Resolve the scan roots and their scan config, using the profile found
+/// from the first root. Prints the "Scanning ..." line.
+fn load_scan_config(paths: &[PathBuf], profile: Option<&str>) -> Result<(Profile, ScanConfig)> {
+    let roots = paths
+        .iter()
+        .map(|p| {
+            p.canonicalize()
+                .with_context(|| format!("Invalid path: {}", p.display()))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let Some((first, rest)) = roots.split_first() else {
+        bail!("No path to scan");
+    };
 
-    eprintln!("Scanning {}...", path.display());
+    let profile = Profile::load(first, profile)?;
+    let mut config = profile.scan_config(first);
+    config.extra_roots = rest.to_vec();
+
+    let names: Vec<_> = roots.iter().map(|p| p.display().to_string()).collect();
+    eprintln!("Scanning {}...", names.join(", "));
+    Ok((profile, config))
+}
+
+/// Run a quick scan and display summary.
+fn run_scan(
+    paths: &[PathBuf],
+    max_depth: Option<u32>,
+    top_n: usize,
+    ignore_files: Option<IgnoreMode>,
+    profile: Option<&str>,
+) -> Result<()> {
+    let (_, mut config) = load_scan_config(paths, profile)?;
+    if let Some(ignore_files) = ignore_files {
+        config.ignore_files = ignore_files;
+    }
 
-    let config = ScanConfig::new(&path);
     let scanner = JwalkScanner::new();
     let tree = scanner.scan(&config).context("Scan failed")?;
+    let path = tree.root_path.clone();
 
     // Print summary
     println!();
@@ -184,10 +804,34 @@ fn run_scan(path: &Path, max_depth: Option<u32>, top_n: usize) -> Result<()> {
         path.display(),
         format_size(tree.stats.total_size)
     );
+    if tree.is_multi_root() {
+        println!(" {} roots combined", tree.roots.len());
+    }
     println!(
         " {} files, {} directories",
         tree.stats.total_files, tree.stats.total_dirs
     );
+    if config.ignore_files == IgnoreMode::Report {
+        let percent = |bytes: u64| {
+            if tree.stats.total_size == 0 {
+                0.0
+            } else {
+                bytes as f64 / tree.stats.total_size as f64 * 100.0
+            }
+        };
+        println!(
+            " Ignored: {} in {} files ({:.1}%)",
+            format_size(tree.stats.ignored_size),
+            tree.stats.ignored_files,
+            percent(tree.stats.ignored_size)
+        );
+        println!(
+            " Tracked: {} in {} files ({:.1}%)",
+            format_size(tree.stats.tracked_size()),
+            tree.stats.total_files - tree.stats.ignored_files,
+            percent(tree.stats.tracked_size())
+        );
+    }
     println!(" Scanned in {:.2}s", tree.scan_duration.as_secs_f64());
     println!("{}", "─".repeat(60));
     println!();
@@ -211,23 +855,28 @@ fn run_scan(path: &Path, max_depth: Option<u32>, top_n: usize) -> Result<()> {
 }
 
 /// Run duplicate detection.
-fn run_duplicates(path: &Path, min_size: &str, top_n: usize, format: OutputFormat) -> Result<()> {
-    let path = path.canonicalize().context("Invalid path")?;
-    let min_bytes = parse_size(min_size)?;
-
-    eprintln!("Scanning {}...", path.display());
+fn run_duplicates(
+    paths: &[PathBuf],
+    min_size: Option<&str>,
+    top_n: usize,
+    format: OutputFormat,
+    profile: Option<&str>,
+) -> Result<()> {
+    let (profile, config) = load_scan_config(paths, profile)?;
+
+    let mut dup_config = profile.duplicates.clone();
+    if let Some(min_size) = min_size {
+        dup_config.min_size = parse_size(min_size).map_err(|e| eyre!(e))?;
+    }
+    dup_config.max_groups = top_n;
 
-    let config = ScanConfig::new(&path);
     let scanner = JwalkScanner::new();
     let tree = scanner.scan(&config).context("Scan failed")?;
 
-    eprintln!("Finding duplicates (min size: {})...", min_size);
-
-    let dup_config = DuplicateConfig::builder()
-        .min_size(min_bytes)
-        .max_groups(top_n)
-        .build()
-        .unwrap();
+    eprintln!(
+        "Finding duplicates (min size: {})...",
+        format_size(dup_config.min_size)
+    );
 
     let finder = DuplicateFinder::with_config(dup_config);
     let report = finder.find_duplicates(&tree);
@@ -277,34 +926,65 @@ fn run_duplicates(path: &Path, min_size: &str, top_n: usize, format: OutputForma
 }
 
 /// Run age analysis.
-fn run_age(path: &Path, stale_threshold: &str, format: OutputFormat) -> Result<()> {
-    let path = path.canonicalize().context("Invalid path")?;
-    let stale_duration = parse_duration(stale_threshold)?;
-
-    eprintln!("Scanning {}...", path.display());
+fn run_age(
+    paths: &[PathBuf],
+    stale_threshold: Option<&str>,
+    time_basis: Option<TimeBasis>,
+    buckets: Option<&str>,
+    format: OutputFormat,
+    profile: Option<&str>,
+) -> Result<()> {
+    let (profile, config) = load_scan_config(paths, profile)?;
+
+    let mut age_config = profile.age.clone();
+    if let Some(stale) = stale_threshold {
+        age_config.stale_threshold = parse_duration(stale).map_err(|e| eyre!(e))?;
+    }
+    if let Some(time_basis) = time_basis {
+        age_config.time_basis = time_basis;
+    }
+    if let Some(buckets) = buckets {
+        age_config.buckets = AgeBucket::parse_list(buckets).map_err(|e| eyre!(e))?;
+    }
+    let stale_threshold = format_duration_spec(age_config.stale_threshold);
 
-    let config = ScanConfig::new(&path);
     let scanner = JwalkScanner::new();
     let tree = scanner.scan(&config).context("Scan failed")?;
 
     eprintln!("Analyzing file ages...");
 
-    let age_config = AgeConfig::builder()
-        .stale_threshold(stale_duration)
-        .build()
-        .unwrap();
-
     let analyzer = AgeAnalyzer::with_config(age_config);
-    let report = analyzer.analyze(&tree);
+    let mut report = analyzer.analyze(&tree);
+    if report.time_basis.uses_atime() {
+        report.atime_warning = MountTable::load_without_usage()
+            .ok()
+            .and_then(|table| table.atime_caveat(&tree));
+    }
 
     match format {
         OutputFormat::Text => {
             println!();
             println!("{}", "─".repeat(70));
-            println!(" Age Distribution Report");
+            println!(" Age Distribution Report (by {})", report.time_basis);
             println!("{}", "─".repeat(70));
             println!();
 
+            if let Some(warning) = &report.atime_warning {
+                println!(
+                    " Warning: {}; files may have been read more recently than shown.",
+                    warning
+                );
+            }
+            if report.fallback_files > 0 {
+                println!(
+                    " {} files have no {} and are aged by mtime.",
+                    report.fallback_files, report.time_basis
+                );
+            }
+            if report.atime_warning.is_some() || report.fallback_files > 0 {
+                println!();
+            }
+
             // Age buckets
             println!(" Age Distribution:");
             let max_size = report
@@ -328,7 +1008,14 @@ fn run_age(path: &Path, stale_threshold: &str, format: OutputFormat) -> Result<(
 
             // Stale directories
             if !report.stale_directories.is_empty() {
-                println!(" Stale Directories (no changes in {}):", stale_threshold);
+                println!(
+                    " Stale Directories (no {} in {}):",
+                    match report.time_basis {
+                        TimeBasis::Accessed => "access",
+                        _ => "changes",
+                    },
+                    stale_threshold
+                );
                 println!(" Total stale: {}", format_size(report.total_stale_size()));
                 println!();
 
@@ -353,30 +1040,1164 @@ fn run_age(path: &Path, stale_threshold: &str, format: OutputFormat) -> Result<(
     Ok(())
 }
 
-/// Export scan results to JSON.
-fn run_export(path: &Path, output: Option<PathBuf>) -> Result<()> {
-    let path = path.canonicalize().context("Invalid path")?;
-
-    eprintln!("Scanning {}...", path.display());
-
-    let config = ScanConfig::new(&path);
+/// Report disk usage per user and group.
+fn run_owners(
+    paths: &[PathBuf],
+    top_n: usize,
+    format: OutputFormat,
+    profile: Option<&str>,
+) -> Result<()> {
+    let (_, config) = load_scan_config(paths, profile)?;
     let scanner = JwalkScanner::new();
     let tree = scanner.scan(&config).context("Scan failed")?;
 
-    let json = serde_json::to_string_pretty(&tree)?;
+    eprintln!("Analyzing ownership...");
 
-    match output {
-        Some(output_path) => {
-            std::fs::write(&output_path, json)?;
-            eprintln!("Exported to {}", output_path.display());
-        }
-        None => {
-            println!("{}", json);
-        }
-    }
+    let mut report = OwnershipAnalyzer::new().analyze(&tree);
+    report.users.truncate(top_n);
+    report.groups.truncate(top_n);
 
-    Ok(())
-}
+    match format {
+        OutputFormat::Text => {
+            println!();
+            println!("{}", "─".repeat(70));
+            println!(
+                " Ownership Report - {} in {} files",
+                format_size(report.total_size),
+                report.total_files
+            );
+            println!("{}", "─".repeat(70));
+
+            for (title, owners) in [("Users", &report.users), ("Groups", &report.groups)] {
+                println!();
+                println!(" {}:", title);
+                for owner in owners {
+                    println!(
+                        "   {:<16} {:>10} {:>5.1}% {:>8} files  {}",
+                        owner.name,
+                        format_size(owner.total_size),
+                        report.share(owner) * 100.0,
+                        owner.file_count,
+                        make_bar(report.share(owner), 20)
+                    );
+                }
+            }
+
+            println!();
+            println!(" Largest directories per user:");
+            for owner in &report.users {
+                println!("   {}:", owner.name);
+                for dir in &owner.largest_directories {
+                    println!(
+                        "     {:>10} {:>6} files  {}",
+                        format_size(dir.size),
+                        dir.file_count,
+                        dir.path.display()
+                    );
+                }
+            }
+            if report.unknown_files > 0 {
+                println!();
+                println!(
+                    " {} files without ownership information",
+                    report.unknown_files
+                );
+            }
+            println!();
+        }
+        OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        }
+    }
+
+    Ok(())
+}
+
+/// Report disk usage per file category and extension.
+fn run_types(
+    paths: &[PathBuf],
+    category: Option<FileCategory>,
+    top_n: usize,
+    sniff: bool,
+    format: OutputFormat,
+    profile: Option<&str>,
+) -> Result<()> {
+    let (_, config) = load_scan_config(paths, profile)?;
+    let scanner = JwalkScanner::new();
+    let tree = scanner.scan(&config).context("Scan failed")?;
+
+    eprintln!("Classifying files...");
+
+    let report = FileTypeAnalyzer::new().sniff(sniff).analyze(&tree);
+    let mut breakdown = report.root;
+    if let Some(category) = category {
+        breakdown.categories.retain(|c| c.category == category);
+        breakdown.extensions.retain(|e| e.category == category);
+    }
+    breakdown.extensions.truncate(top_n);
+
+    match format {
+        OutputFormat::Text => {
+            println!();
+            println!("{}", "─".repeat(70));
+            println!(
+                " File Types - {} in {} files",
+                format_size(breakdown.total_size),
+                breakdown.file_count
+            );
+            println!("{}", "─".repeat(70));
+
+            println!();
+            println!(" Categories:");
+            for stats in &breakdown.categories {
+                let share = breakdown.share(stats.total_size);
+                println!(
+                    "   {:<12} {:>10} {:>5.1}% {:>8} files  {}",
+                    stats.category.label(),
+                    format_size(stats.total_size),
+                    share * 100.0,
+                    stats.file_count,
+                    make_bar(share, 20)
+                );
+            }
+
+            println!();
+            println!(" Extensions:");
+            for stats in &breakdown.extensions {
+                let share = breakdown.share(stats.total_size);
+                println!(
+                    "   {:<12} {:>10} {:>5.1}% {:>8} files  {}",
+                    stats.display_name(),
+                    format_size(stats.total_size),
+                    share * 100.0,
+                    stats.file_count,
+                    stats.category.label()
+                );
+            }
+            if report.sniffed_files > 0 {
+                println!();
+                println!(" {} files classified by content", report.sniffed_files);
+            }
+            println!();
+        }
+        OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&breakdown)?);
+        }
+    }
+
+    Ok(())
+}
+
+/// Print the nodes matching a query.
+fn run_find(
+    query: &str,
+    paths: &[PathBuf],
+    format: FindFormat,
+    profile: Option<&str>,
+) -> Result<()> {
+    use std::io::Write;
+
+    let query = Query::parse(query).map_err(|e| eyre!("Invalid query: {}", e))?;
+    let (_, config) = load_scan_config(paths, profile)?;
+    let scanner = JwalkScanner::new();
+    let tree = scanner.scan(&config).context("Scan failed")?;
+
+    let found = query.find(&tree);
+    let entry = |path: &Path, node: &gravityfile_core::FileNode| {
+        serde_json::json!({
+            "path": path,
+            "kind": node.kind.to_string(),
+            "size": node.size,
+            "modified": node
+                .timestamps
+                .modified
+                .duration_since(std::time::UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs()),
+            "uid": node.owner.map(|o| o.uid),
+            "gid": node.owner.map(|o| o.gid),
+            "git": node.git_status.map(|g| g.to_string()),
+        })
+    };
+
+    let mut out = std::io::BufWriter::new(std::io::stdout().lock());
+    let written = match format {
+        FindFormat::Paths => found
+            .iter()
+            .try_for_each(|(path, _)| writeln!(out, "{}", path.display())),
+        FindFormat::Ndjson => found
+            .iter()
+            .try_for_each(|(path, node)| writeln!(out, "{}", entry(path, node))),
+        FindFormat::Json => {
+            let entries: Vec<_> = found.iter().map(|(path, node)| entry(path, node)).collect();
+            writeln!(out, "{}", serde_json::to_string_pretty(&entries)?)
+        }
+    }
+    .and_then(|()| out.flush());
+
+    match written {
+        // The reader (e.g. `head`) went away; that is not an error.
+        Err(e) if e.kind() == std::io::ErrorKind::BrokenPipe => Ok(()),
+        other => Ok(other?),
+    }
+}
+
+/// List the largest entries selected by `finder`.
+fn run_top(
+    paths: &[PathBuf],
+    finder: &TopFinder,
+    format: TopFormat,
+    profile: Option<&str>,
+) -> Result<()> {
+    use std::io::Write;
+
+    let (_, config) = load_scan_config(paths, profile)?;
+    let scanner = JwalkScanner::new();
+    let tree = scanner.scan(&config).context("Scan failed")?;
+
+    let top = finder.find(&tree);
+    let modified = |entry: &TopEntry| {
+        entry
+            .modified
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs())
+    };
+
+    match format {
+        TopFormat::Text => {
+            let now = std::time::SystemTime::now();
+            println!();
+            println!("{}", "─".repeat(70));
+            println!(
+                " {} largest under {} ({} total)",
+                top.len(),
+                tree.root_path.display(),
+                format_size(tree.stats.total_size)
+            );
+            println!("{}", "─".repeat(70));
+            println!();
+            for (i, entry) in top.iter().enumerate() {
+                let age = now.duration_since(entry.modified).unwrap_or_default();
+                println!(
+                    " {:>4}. {:>10} {:>8}  {}{}",
+                    i + 1,
+                    format_size(entry.size),
+                    format_age(age),
+                    entry.path.display(),
+                    if entry.is_dir { "/" } else { "" }
+                );
+            }
+            println!();
+        }
+        TopFormat::Json => {
+            let entries: Vec<_> = top
+                .iter()
+                .map(|entry| {
+                    serde_json::json!({
+                        "path": entry.path,
+                        "kind": if entry.is_dir { "directory" } else { "file" },
+                        "size": entry.size,
+                        "files": entry.file_count,
+                        "modified": modified(entry),
+                    })
+                })
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&entries)?);
+        }
+        TopFormat::Csv => {
+            let mut out = std::io::BufWriter::new(std::io::stdout().lock());
+            let written = writeln!(out, "path,kind,size,files,modified")
+                .and_then(|()| {
+                    top.iter().try_for_each(|entry| {
+                        writeln!(
+                            out,
+                            "{},{},{},{},{}",
+                            csv_field(&entry.path.to_string_lossy()),
+                            if entry.is_dir { "directory" } else { "file" },
+                            entry.size,
+                            entry.file_count,
+                            modified(entry)
+                        )
+                    })
+                })
+                .and_then(|()| out.flush());
+            match written {
+                // The reader (e.g. `head`) went away; that is not an error.
+                Err(e) if e.kind() == std::io::ErrorKind::BrokenPipe => {}
+                other => other?,
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Quote a CSV field when it contains a separator, quote or line break.
+fn csv_field(value: &str) -> std::borrow::Cow<'_, str> {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\"")).into()
+    } else {
+        value.into()
+    }
+}
+
+/// List cleanup candidates and delete them if `delete` is set.
+fn run_clean(
+    paths: &[PathBuf],
+    delete: bool,
+    rules: &[String],
+    min_size: u64,
+    use_trash: bool,
+    format: OutputFormat,
+    profile: Option<&str>,
+) -> Result<()> {
+    let (profile, config) = load_scan_config(paths, profile)?;
+    let scanner = JwalkScanner::new();
+    let tree = scanner.scan(&config).context("Scan failed")?;
+
+    eprintln!("Looking for cleanup candidates...");
+
+    let mut report = CleanupFinder::with_config(&profile.cleanup).find(&tree);
+    report
+        .candidates
+        .retain(|c| c.size >= min_size && (rules.is_empty() || rules.contains(&c.rule)));
+    report.total_reclaimable = report.candidates.iter().map(|c| c.size).sum();
+
+    let complete = if !delete || report.candidates.is_empty() {
+        None
+    } else {
+        let targets = report.candidates.iter().map(|c| c.path.clone()).collect();
+        Some(run_delete_job(
+            targets,
+            use_trash,
+            format!("Cleaned {} directories", report.candidates.len()),
+        )?)
+    };
+
+    match format {
+        OutputFormat::Text => {
+            println!();
+            println!("{}", "─".repeat(70));
+            println!(
+                " Cleanup Candidates - {} reclaimable in {} directories",
+                format_size(report.total_reclaimable),
+                report.candidates.len()
+            );
+            println!("{}", "─".repeat(70));
+
+            println!();
+            for summary in report.by_rule() {
+                println!(
+                    "   {:<14} {:>10} {:>6} dirs  {}",
+                    summary.rule,
+                    format_size(summary.size),
+                    summary.count,
+                    summary.description
+                );
+            }
+
+            println!();
+            for candidate in &report.candidates {
+                println!(
+                    "   {:>10}  {:<14} {}",
+                    format_size(candidate.size),
+                    candidate.rule,
+                    candidate.path.display()
+                );
+            }
+            println!();
+
+            match &complete {
+                Some(complete) => {
+                    println!(
+                        " {} {} directories, {} freed ({} failed)",
+                        if use_trash { "Trashed" } else { "Deleted" },
+                        complete.succeeded,
+                        format_size(complete.bytes_processed),
+                        complete.failed
+                    );
+                    for error in &complete.errors {
+                        eprintln!("  {}", error);
+                    }
+                }
+                None if !report.candidates.is_empty() => {
+                    println!(" Nothing was deleted; rerun with --delete to remove these.");
+                }
+                None => {}
+            }
+        }
+        OutputFormat::Json => {
+            let output = serde_json::json!({
+                "candidates": report.candidates,
+                "total_reclaimable": report.total_reclaimable,
+                "delete": delete,
+                "succeeded": complete.as_ref().map_or(0, |c| c.succeeded),
+                "failed": complete.as_ref().map_or(0, |c| c.failed),
+                "errors": complete.as_ref().map(|c| &c.errors),
+            });
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        }
+    }
+
+    if complete.is_some_and(|c| c.failed > 0) {
+        bail!("Some directories could not be deleted");
+    }
+    Ok(())
+}
+
+/// Report hygiene issues, print a deletion plan for them or delete them.
+fn run_hygiene(
+    paths: &[PathBuf],
+    only: &[HygieneKind],
+    plan: bool,
+    delete: bool,
+    use_trash: bool,
+    format: OutputFormat,
+    profile: Option<&str>,
+) -> Result<()> {
+    let (_, config) = load_scan_config(paths, profile)?;
+    let scanner = JwalkScanner::new();
+    let tree = scanner.scan(&config).context("Scan failed")?;
+
+    eprintln!("Checking hygiene...");
+
+    let mut report = HygieneAnalyzer::new().analyze(&tree);
+    if !only.is_empty() {
+        report.retain_kinds(only);
+    }
+    // The scan may have skipped hidden or ignored entries; only keep what
+    // the disk confirms.
+    let changed = report.verify();
+    if changed > 0 {
+        eprintln!("Skipped {} entries the disk no longer confirms", changed);
+    }
+    let targets: Vec<PathBuf> = report.deletable().map(|e| e.path.clone()).collect();
+
+    if plan {
+        let plan = plan_operation(&FileOperation::delete(targets, use_trash), None);
+        return print_plan(&plan, format);
+    }
+
+    let complete = if delete && !targets.is_empty() {
+        let count = targets.len();
+        Some(run_delete_job(
+            targets,
+            use_trash,
+            format!("Removed {} hygiene entries", count),
+        )?)
+    } else {
+        None
+    };
+
+    match format {
+        OutputFormat::Text => {
+            println!();
+            println!("{}", "─".repeat(70));
+            println!(" Hygiene Report - {} entries", report.entries.len());
+            println!("{}", "─".repeat(70));
+
+            for kind in HygieneKind::ALL {
+                let entries: Vec<_> = report.entries.iter().filter(|e| e.kind() == kind).collect();
+                if entries.is_empty() {
+                    continue;
+                }
+                println!();
+                if kind == HygieneKind::DanglingHardlink {
+                    println!(
+                        " {} ({}, {} kept alive elsewhere):",
+                        kind.description(),
+                        entries.len(),
+                        format_size(report.pinned_bytes())
+                    );
+                } else {
+                    println!(" {} ({}):", kind.description(), entries.len());
+                }
+                for entry in entries {
+                    let detail = entry.issue.detail();
+                    if detail.is_empty() {
+                        println!("   {}", entry.path.display());
+                    } else {
+                        println!("   {}  {}", entry.path.display(), detail);
+                    }
+                }
+            }
+            println!();
+
+            match &complete {
+                Some(complete) => {
+                    println!(
+                        " {} {} entries ({} failed)",
+                        if use_trash { "Trashed" } else { "Deleted" },
+                        complete.succeeded,
+                        complete.failed
+                    );
+                    for error in &complete.errors {
+                        eprintln!("  {}", error);
+                    }
+                }
+                None if report.deletable().next().is_some() => {
+                    println!(" Use --plan to review or --delete to remove these entries.");
+                }
+                None => {}
+            }
+        }
+        OutputFormat::Json => {
+            let counts: serde_json::Map<String, serde_json::Value> = HygieneKind::ALL
+                .iter()
+                .map(|&kind| (kind.label().to_string(), report.count(kind).into()))
+                .collect();
+            let output = serde_json::json!({
+                "entries": report.entries,
+                "counts": counts,
+                "pinned_bytes": report.pinned_bytes(),
+                "skipped_changed": changed,
+                "succeeded": complete.as_ref().map_or(0, |c| c.succeeded),
+                "failed": complete.as_ref().map_or(0, |c| c.failed),
+                "errors": complete.as_ref().map(|c| &c.errors),
+            });
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        }
+    }
+
+    if complete.is_some_and(|c| c.failed > 0) {
+        bail!("Some entries could not be deleted");
+    }
+    Ok(())
+}
+
+/// Evaluate a retention policy and, for `apply`, delete what it selects.
+fn run_policy(action: PolicyAction, format: OutputFormat, profile: Option<&str>) -> Result<()> {
+    let (policy_path, paths, apply) = match &action {
+        PolicyAction::Check { policy, paths } => (policy, paths, None),
+        PolicyAction::Apply {
+            policy,
+            paths,
+            permanent,
+            audit_log,
+        } => (policy, paths, Some((!permanent, audit_log.clone()))),
+    };
+
+    let policy = RetentionPolicy::load(policy_path)?;
+    let (_, config) = load_scan_config(paths, profile)?;
+    let scanner = JwalkScanner::new();
+    let tree = scanner.scan(&config).context("Scan failed")?;
+
+    eprintln!("Evaluating {} rules...", policy.rules.len());
+    let mut report = policy.evaluate(&tree)?;
+    // Files rewritten or replaced since the scan no longer match what the
+    // rules judged; leave them alone.
+    let skipped = if apply.is_some() {
+        report.verify()
+    } else {
+        Vec::new()
+    };
+    if !skipped.is_empty() {
+        eprintln!(
+            "Skipped {} files that changed since the scan",
+            skipped.len()
+        );
+    }
+
+    let complete = match apply {
+        Some((use_trash, audit_log)) if !report.matches.is_empty() || !skipped.is_empty() => {
+            let targets: Vec<PathBuf> = report.matches.iter().map(|m| m.path.clone()).collect();
+            let complete = if targets.is_empty() {
+                OperationComplete {
+                    operation_type: OperationType::Delete,
+                    succeeded: 0,
+                    failed: 0,
+                    bytes_processed: 0,
+                    errors: Vec::new(),
+                }
+            } else {
+                run_delete_job(
+                    targets,
+                    use_trash,
+                    format!("Applied retention policy to {} files", report.matches.len()),
+                )?
+            };
+
+            let audit_path = audit_log
+                .or_else(gravityfile_ops::default_audit_path)
+                .ok_or_else(|| eyre!("No data directory for the audit log; pass --audit-log"))?;
+            let action = if use_trash { "trash" } else { "delete" };
+            let records: Vec<AuditRecord> = report
+                .matches
+                .iter()
+                .map(|m| {
+                    let record = AuditRecord::new(action, &m.path, m.size, &m.rule);
+                    match complete.errors.iter().find(|e| e.path == m.path) {
+                        Some(error) => record.with_error(&error.message),
+                        None => record,
+                    }
+                })
+                .chain(skipped.iter().map(|m| {
+                    AuditRecord::new(action, &m.path, m.size, &m.rule)
+                        .with_error("Skipped: changed since the scan")
+                }))
+                .collect();
+            AuditLog::new(&audit_path)
+                .append(&records)
+                .map_err(|e| eyre!(e))?;
+            Some((complete, use_trash, audit_path))
+        }
+        _ => None,
+    };
+
+    match format {
+        OutputFormat::Text => {
+            print_policy_report(&report);
+
+            match &complete {
+                Some((complete, use_trash, audit_path)) => {
+                    println!(
+                        " {} {} files, {} freed ({} failed)",
+                        if *use_trash { "Trashed" } else { "Deleted" },
+                        complete.succeeded,
+                        format_size(complete.bytes_processed),
+                        complete.failed
+                    );
+                    println!(" Audit log: {}", audit_path.display());
+                    for error in &complete.errors {
+                        eprintln!("  {}", error);
+                    }
+                }
+                None if matches!(action, PolicyAction::Check { .. })
+                    && !report.matches.is_empty() =>
+                {
+                    println!(" Check only: nothing was deleted.");
+                }
+                None => {}
+            }
+        }
+        OutputFormat::Json => {
+            let output = serde_json::json!({
+                "matches": report.matches,
+                "total_size": report.total_size,
+                "kept": report.kept,
+                "skipped": skipped,
+                "applied": complete.is_some(),
+                "succeeded": complete.as_ref().map_or(0, |(c, _, _)| c.succeeded),
+                "failed": complete.as_ref().map_or(0, |(c, _, _)| c.failed),
+                "errors": complete.as_ref().map(|(c, _, _)| &c.errors),
+                "audit_log": complete.as_ref().map(|(_, _, path)| path),
+            });
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        }
+    }
+
+    if complete.is_some_and(|(c, _, _)| c.failed > 0) {
+        bail!("Some files could not be deleted");
+    }
+    Ok(())
+}
+
+/// Print the files a retention policy selects, grouped by rule.
+fn print_policy_report(report: &PolicyReport) {
+    println!();
+    println!("{}", "─".repeat(70));
+    println!(
+        " Retention Policy - {} in {} files ({} kept by keep_newest)",
+        format_size(report.total_size),
+        report.matches.len(),
+        report.kept
+    );
+    println!("{}", "─".repeat(70));
+
+    println!();
+    for summary in report.by_rule() {
+        println!(
+            "   {:<20} {:>10} {:>8} files",
+            summary.rule,
+            format_size(summary.size),
+            summary.count
+        );
+    }
+
+    println!();
+    for m in &report.matches {
+        println!(
+            "   {:>10}  {:>10}  {:<20} {}",
+            format_size(m.size),
+            format_age(m.modified.elapsed().unwrap_or_default()),
+            m.rule,
+            m.path.display()
+        );
+    }
+    println!();
+}
+
+/// Delete `targets` through the job queue and record the result in the
+/// shared undo journal as `description`.
+fn run_delete_job(
+    targets: Vec<PathBuf>,
+    use_trash: bool,
+    description: String,
+) -> Result<OperationComplete> {
+    let runtime = tokio::runtime::Runtime::new()?;
+    let outcome = runtime.block_on(async move {
+        let (jobs, mut rx) = JobManager::new(1);
+        jobs.submit(
+            FileOperation::delete(targets, use_trash),
+            CopyOptions::default(),
+        );
+        rx.recv().await
+    });
+    let outcome = outcome.ok_or_else(|| eyre!("Deletion ended without a result"))?;
+
+    if let Some(undo) = outcome.undo
+        && let Some(journal) = gravityfile_ops::default_journal_path()
+    {
+        let mut log = UndoLog::open(&journal, 100).map_err(|e| eyre!(e))?;
+        log.record(undo, description);
+        log.save().map_err(|e| eyre!(e))?;
+    }
+    Ok(outcome.complete)
+}
+
+/// List mounted filesystems.
+fn run_df(all: bool, format: OutputFormat) -> Result<()> {
+    let table = MountTable::load().context("Failed to read the mount table")?;
+    let mounts: Vec<&Mount> = if all {
+        table.mounts.iter().collect()
+    } else {
+        table.real()
+    };
+
+    match format {
+        OutputFormat::Text => {
+            println!(
+                "{:<20} {:<8} {:>10} {:>10} {:>10} {:>5} {:>5}  Mounted on",
+                "Filesystem", "Type", "Size", "Used", "Avail", "Use%", "IUse%"
+            );
+            for mount in mounts {
+                let inode_use = if mount.inodes_total == 0 {
+                    "-".to_string()
+                } else {
+                    format!("{:.0}%", mount.inode_ratio() * 100.0)
+                };
+                println!(
+                    "{:<20} {:<8} {:>10} {:>10} {:>10} {:>4.0}% {:>5}  {}{}",
+                    mount.source,
+                    mount.fs_type,
+                    format_size(mount.total),
+                    format_size(mount.used),
+                    format_size(mount.available),
+                    mount.usage_ratio() * 100.0,
+                    inode_use,
+                    mount.mount_point.display(),
+                    if mount.is_read_only() { " (ro)" } else { "" }
+                );
+            }
+        }
+        OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&mounts)?);
+        }
+    }
+
+    Ok(())
+}
+
+/// Export scan results to JSON.
+fn run_export(paths: &[PathBuf], output: Option<PathBuf>, profile: Option<&str>) -> Result<()> {
+    let (_, config) = load_scan_config(paths, profile)?;
+
+    let scanner = JwalkScanner::new();
+    let tree = scanner.scan(&config).context("Scan failed")?;
+
+    let json = serde_json::to_string_pretty(&tree)?;
+
+    match output {
+        Some(output_path) => {
+            std::fs::write(&output_path, json)?;
+            eprintln!("Exported to {}", output_path.display());
+        }
+        None => {
+            println!("{}", json);
+        }
+    }
+
+    Ok(())
+}
+
+/// List, undo or redo entries in the persistent undo journal.
+fn run_undo(list: bool, id: Option<u64>, redo: bool, format: OutputFormat) -> Result<()> {
+    let journal = gravityfile_ops::default_journal_path()
+        .ok_or_else(|| eyre!("No data directory for the undo journal"))?;
+    let mut log = UndoLog::open(&journal, 100).map_err(|e| eyre!(e))?;
+
+    if list {
+        // Undone entries first (furthest first), then the undo stack newest first.
+        let rows: Vec<_> = log
+            .redo_iter()
+            .map(|e| (e, true))
+            .chain(log.iter().rev().map(|e| (e, false)))
+            .collect();
+        match format {
+            OutputFormat::Text => {
+                if rows.is_empty() {
+                    println!("No operations recorded.");
+                }
+                for (entry, undone) in rows {
+                    let status = match entry.verify() {
+                        Ok(()) => "ok".to_string(),
+                        Err(e) => e,
+                    };
+                    println!(
+                        "{:>5}  {:>14} ago  {}{}  [{}]",
+                        entry.id,
+                        format_age(entry.timestamp.elapsed().unwrap_or_default()),
+                        if undone { "(undone) " } else { "" },
+                        entry.description,
+                        status
+                    );
+                }
+            }
+            OutputFormat::Json => {
+                let entries: Vec<_> = rows
+                    .into_iter()
+                    .map(|(entry, undone)| {
+                        let verified = entry.verify();
+                        serde_json::json!({
+                            "id": entry.id,
+                            "stack": if undone { "redo" } else { "undo" },
+                            "timestamp": entry
+                                .timestamp
+                                .duration_since(std::time::UNIX_EPOCH)
+                                .map(|d| d.as_secs())
+                                .unwrap_or(0),
+                            "description": entry.description,
+                            "action": entry.operation.undo_description(),
+                            "available": verified.is_ok(),
+                            "reason": verified.err(),
+                        })
+                    })
+                    .collect();
+                println!("{}", serde_json::to_string_pretty(&entries)?);
+            }
+        }
+        return Ok(());
+    }
+
+    let action = if redo { "redo" } else { "undo" };
+    let latest = if redo { log.peek_redo() } else { log.peek() };
+    let Some(id) = id.or_else(|| latest.map(|e| e.id)) else {
+        println!("Nothing to {}.", action);
+        return Ok(());
+    };
+    let found = if redo { log.get_redo(id) } else { log.get(id) };
+    let Some(entry) = found else {
+        bail!("No {} entry with ID {}", action, id);
+    };
+    entry
+        .verify()
+        .map_err(|e| eyre!("Cannot {}: {}", action, e))?;
+    let entry = if redo {
+        log.take_redo(id)
+    } else {
+        log.take(id)
+    }
+    .ok_or_else(|| eyre!("Cannot {} entry {}", action, id))?;
+    let description = entry.description.clone();
+
+    let runtime = tokio::runtime::Runtime::new()?;
+    let replayed = entry.clone();
+    let complete = runtime.block_on(async move {
+        let mut rx = if redo {
+            gravityfile_ops::execute_redo(replayed)
+        } else {
+            gravityfile_ops::execute_undo(replayed)
+        };
+        let mut complete = None;
+        while let Some(result) = rx.recv().await {
+            if let OperationResult::Complete(c) = result {
+                complete = Some(c);
+            }
+        }
+        complete
+    });
+    let complete = complete.ok_or_else(|| eyre!("{} ended without a result", action))?;
+
+    // The entry is consumed even on partial failure, matching the TUI; only
+    // a clean run can be reversed again.
+    if complete.failed == 0 && complete.succeeded > 0 {
+        if redo {
+            log.record_redone(&entry);
+        } else {
+            log.record_undone(&entry);
+        }
+    }
+    log.save().map_err(|e| eyre!(e))?;
+
+    match format {
+        OutputFormat::Text => {
+            println!(
+                "{}: {} ({} succeeded, {} failed)",
+                if redo { "Redid" } else { "Undid" },
+                description,
+                complete.succeeded,
+                complete.failed
+            );
+            for error in &complete.errors {
+                eprintln!("  {}: {}", error.path.display(), error.message);
+            }
+        }
+        OutputFormat::Json => {
+            let errors: Vec<_> = complete
+                .errors
+                .iter()
+                .map(|e| serde_json::json!({ "path": e.path, "message": e.message }))
+                .collect();
+            let output = serde_json::json!({
+                "id": id,
+                "action": action,
+                "description": description,
+                "succeeded": complete.succeeded,
+                "failed": complete.failed,
+                "errors": errors,
+            });
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        }
+    }
+
+    if complete.failed > 0 {
+        let done = if redo { "redone" } else { "undone" };
+        bail!("{} items could not be {}", complete.failed, done);
+    }
+    Ok(())
+}
+
+/// Preview and run a rule-based rename.
+fn run_rename(
+    paths: Vec<PathBuf>,
+    rule: &RenameRule,
+    dry_run: bool,
+    format: OutputFormat,
+) -> Result<()> {
+    let paths = paths
+        .into_iter()
+        .map(|p| std::path::absolute(&p).with_context(|| format!("Invalid path: {}", p.display())))
+        .collect::<Result<Vec<_>>>()?;
+    let previews = preview_renames(&paths, rule).map_err(|e| eyre!(e))?;
+    let problems = previews.iter().filter(|p| p.is_problem()).count();
+    let renames: Vec<(PathBuf, String)> = previews
+        .iter()
+        .filter(|p| p.status == RenameStatus::Ready)
+        .map(|p| (p.source.clone(), p.new_name.clone()))
+        .collect();
+
+    if let OutputFormat::Text = format {
+        for preview in &previews {
+            let old = preview
+                .source
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy();
+            match &preview.status {
+                RenameStatus::Ready => println!(" {} -> {}", old, preview.new_name),
+                RenameStatus::Unchanged => println!(" {} (unchanged)", old),
+                RenameStatus::Invalid(e) | RenameStatus::Collision(e) => {
+                    println!(" {} -> {}  [{}]", old, preview.new_name, e)
+                }
+            }
+        }
+    }
+
+    let run = !dry_run && problems == 0 && !renames.is_empty();
+    let complete = if run {
+        let count = renames.len();
+        let runtime = tokio::runtime::Runtime::new()?;
+        let complete = runtime.block_on(async move {
+            let mut rx = start_batch_rename(renames, CancellationToken::new());
+            let mut complete = None;
+            while let Some(result) = rx.recv().await {
+                if let BatchRenameResult::Complete(c) = result {
+                    complete = Some(c);
+                }
+            }
+            complete
+        });
+        let complete = complete.ok_or_else(|| eyre!("Rename ended without a result"))?;
+
+        // Record in the shared journal so `grav undo` and the TUI can revert it.
+        if !complete.renamed.is_empty()
+            && let Some(journal) = gravityfile_ops::default_journal_path()
+        {
+            let mut log = UndoLog::open(&journal, 100).map_err(|e| eyre!(e))?;
+            log.record(
+                UndoableOperation::FilesMoved {
+                    moves: complete.renamed.clone(),
+                },
+                format!("Renamed {} items", count),
+            );
+            log.save().map_err(|e| eyre!(e))?;
+        }
+        Some(complete)
+    } else {
+        None
+    };
+
+    match format {
+        OutputFormat::Text => {
+            if let Some(complete) = &complete {
+                println!(
+                    "Renamed {} items ({} failed)",
+                    complete.inner.succeeded, complete.inner.failed
+                );
+                for error in &complete.inner.errors {
+                    eprintln!("  {}", error);
+                }
+            }
+        }
+        OutputFormat::Json => {
+            let output = serde_json::json!({
+                "renames": previews,
+                "applied": complete.is_some(),
+                "succeeded": complete.as_ref().map_or(0, |c| c.inner.succeeded),
+                "failed": complete.as_ref().map_or(0, |c| c.inner.failed),
+                "errors": complete.as_ref().map(|c| &c.inner.errors),
+            });
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        }
+    }
+
+    if problems > 0 {
+        bail!(
+            "{} names collide or are invalid; nothing was renamed",
+            problems
+        );
+    }
+    if complete.is_some_and(|c| c.inner.failed > 0) {
+        bail!("Some items could not be renamed");
+    }
+    Ok(())
+}
+
+/// Print the dry-run plan of a file operation.
+fn run_plan(
+    operation: PlanOperation,
+    resolution: Option<ConflictResolution>,
+    format: OutputFormat,
+) -> Result<()> {
+    let abs = |p: PathBuf| {
+        std::path::absolute(&p).with_context(|| format!("Invalid path: {}", p.display()))
+    };
+    let abs_all = |paths: Vec<PathBuf>| paths.into_iter().map(abs).collect::<Result<Vec<_>>>();
+
+    let operation = match operation {
+        PlanOperation::Copy {
+            sources,
+            destination,
+        } => FileOperation::copy(abs_all(sources)?, abs(destination)?),
+        PlanOperation::Move {
+            sources,
+            destination,
+        } => FileOperation::move_to(abs_all(sources)?, abs(destination)?),
+        PlanOperation::Link {
+            sources,
+            destination,
+            relative,
+            hard,
+        } => {
+            let kind = if hard {
+                LinkKind::Hardlink
+            } else if relative {
+                LinkKind::RelativeSymlink
+            } else {
+                LinkKind::Symlink
+            };
+            FileOperation::link(abs_all(sources)?, abs(destination)?, kind)
+        }
+        PlanOperation::Delete { targets, permanent } => {
+            FileOperation::delete(abs_all(targets)?, !permanent)
+        }
+        PlanOperation::Shred { targets, passes } => FileOperation::shred(abs_all(targets)?, passes),
+        PlanOperation::Rename { source, new_name } => FileOperation::rename(abs(source)?, new_name),
+        PlanOperation::Touch { path } => FileOperation::create_file(abs(path)?),
+        PlanOperation::Mkdir { path } => FileOperation::create_directory(abs(path)?),
+    };
+
+    let plan = plan_operation(&operation, resolution);
+    print_plan(&plan, format)
+}
+
+/// Print an operation plan as a step listing or JSON.
+fn print_plan(plan: &OperationPlan, format: OutputFormat) -> Result<()> {
+    match format {
+        OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(plan)?);
+        }
+        OutputFormat::Text => {
+            println!();
+            println!(
+                " {} plan: {} steps, {}",
+                plan.operation_type,
+                plan.steps.len(),
+                format_size(plan.total_bytes)
+            );
+            println!("{}", "─".repeat(60));
+
+            for step in &plan.steps {
+                let size = match step.action {
+                    PlanAction::CreateDirectory | PlanAction::CreateSymlink => String::new(),
+                    _ => format_size(step.bytes),
+                };
+                match &step.source {
+                    Some(source)
+                        if step.action != PlanAction::CreateFile
+                            && step.action != PlanAction::CreateDirectory
+                            && step.action != PlanAction::CreateSymlink =>
+                    {
+                        println!(
+                            " {:>17}  {} -> {}  {}",
+                            step.action.to_string(),
+                            source.display(),
+                            step.path.display(),
+                            size
+                        );
+                    }
+                    _ => println!(
+                        " {:>17}  {}  {}",
+                        step.action.to_string(),
+                        step.path.display(),
+                        size
+                    ),
+                }
+            }
+
+            if let Some(space) = &plan.space {
+                println!();
+                println!(
+                    " Free space on destination: {} (needs {}){}",
+                    format_size(space.available),
+                    format_size(space.required),
+                    if space.sufficient() {
+                        ""
+                    } else {
+                        " - NOT ENOUGH SPACE"
+                    }
+                );
+            }
+
+            if !plan.conflicts.is_empty() {
+                println!();
+                println!(" Conflicts ({}):", plan.conflicts.len());
+                for conflict in &plan.conflicts {
+                    println!("   {}: {}", conflict.kind, conflict.destination.display());
+                }
+            }
+
+            if !plan.errors.is_empty() {
+                println!();
+                println!(" Errors ({}):", plan.errors.len());
+                for error in &plan.errors {
+                    println!("   {}", error);
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
 
 /// Print a node and its children.
 fn print_node(
@@ -402,16 +2223,28 @@ fn print_node(
         node.name.to_string()
     };
 
-    let dir_marker = if node.is_dir() { "/" } else { "" };
+    let dir_marker = if node.is_dir() || node.kind.is_mount_point() {
+        "/"
+    } else {
+        ""
+    };
+    let ignored_marker = if let gravityfile_core::NodeKind::MountPoint { fs_type } = &node.kind {
+        format!(" (other mount: {})", fs_type)
+    } else if node.git_status == Some(gravityfile_core::GitStatus::Ignored) {
+        " (ignored)".to_string()
+    } else {
+        String::new()
+    };
 
     println!(
-        "{}{}{:<40} {:>10} {:>5.1}% {}",
+        "{}{}{:<40} {:>10} {:>5.1}% {}{}",
         indent,
         if node.is_dir() { "▼ " } else { "  " },
         truncate(&format!("{}{}", name, dir_marker), 40),
         format_size(node.size),
         ratio,
-        bar
+        bar,
+        ignored_marker
     );
 
     if node.is_dir() && depth < max_depth {
@@ -450,62 +2283,3 @@ fn truncate(s: &str, max_len: usize) -> String {
         format!("{}…", &s[..max_len - 1])
     }
 }
-
-/// Parse a size string (e.g., "1KB", "10MB", "1GB").
-fn parse_size(s: &str) -> Result<u64> {
-    let s = s.trim().to_uppercase();
-
-    let (num, multiplier) = if s.ends_with("GB") || s.ends_with("G") {
-        let num: f64 = s
-            .trim_end_matches(|c: char| !c.is_ascii_digit() && c != '.')
-            .parse()?;
-        (num, 1024 * 1024 * 1024)
-    } else if s.ends_with("MB") || s.ends_with("M") {
-        let num: f64 = s
-            .trim_end_matches(|c: char| !c.is_ascii_digit() && c != '.')
-            .parse()?;
-        (num, 1024 * 1024)
-    } else if s.ends_with("KB") || s.ends_with("K") {
-        let num: f64 = s
-            .trim_end_matches(|c: char| !c.is_ascii_digit() && c != '.')
-            .parse()?;
-        (num, 1024)
-    } else if s.ends_with('B') {
-        let num: f64 = s
-            .trim_end_matches(|c: char| !c.is_ascii_digit() && c != '.')
-            .parse()?;
-        (num, 1)
-    } else {
-        let num: f64 = s.parse()?;
-        (num, 1)
-    };
-
-    Ok((num * multiplier as f64) as u64)
-}
-
-/// Parse a duration string (e.g., "1y", "6m", "30d", "1w").
-fn parse_duration(s: &str) -> Result<std::time::Duration> {
-    let s = s.trim().to_lowercase();
-
-    let (num, multiplier) = if s.ends_with('y') {
-        let num: f64 = s.trim_end_matches('y').parse()?;
-        (num, 365.0 * 24.0 * 60.0 * 60.0)
-    } else if s.ends_with('m') {
-        let num: f64 = s.trim_end_matches('m').parse()?;
-        (num, 30.0 * 24.0 * 60.0 * 60.0)
-    } else if s.ends_with('w') {
-        let num: f64 = s.trim_end_matches('w').parse()?;
-        (num, 7.0 * 24.0 * 60.0 * 60.0)
-    } else if s.ends_with('d') {
-        let num: f64 = s.trim_end_matches('d').parse()?;
-        (num, 24.0 * 60.0 * 60.0)
-    } else if s.ends_with('h') {
-        let num: f64 = s.trim_end_matches('h').parse()?;
-        (num, 60.0 * 60.0)
-    } else {
-        let num: f64 = s.parse()?;
-        (num, 24.0 * 60.0 * 60.0) // Default to days
-    };
-
-    Ok(std::time::Duration::from_secs_f64(num * multiplier))
-}